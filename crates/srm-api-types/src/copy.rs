@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Request pro spuštění copy operace
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CopyBundleRequest {
+    pub target_tag: Option<String>,
+    pub timezone_offset_minutes: Option<i32>,
+    pub environment_id: Option<Uuid>,
+    pub source_registry_id: Option<Uuid>,
+    pub target_registry_id: Option<Uuid>,
+    /// Přepsání retry politiky a copy flagů jen pro tento job
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub copy_options: Option<CopyOptionsOverride>,
+    /// Povolí přepsat target tag, který už patří jinému jobu / v registry ukazuje na jiný digest
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overwrite: Option<bool>,
+}
+
+/// Přepsání retry politiky a skopeo copy flagů (registry / job); `None` = zdědit
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CopyOptionsOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_delay_seconds: Option<u64>,
+    /// `--all` (všechny platformy z manifest listu)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub all: Option<bool>,
+    /// `--preserve-digests`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preserve_digests: Option<bool>,
+    /// `--format` (`oci`, `v2s1`, `v2s2`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// Úklid release tagu po selhaném / zrušeném release jobu (`off`, `quarantine`, `delete`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub release_tag_cleanup: Option<String>,
+    /// Cross-repo mount vrstev z jiných repozitářů cílové registry před kopií
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob_reuse: Option<bool>,
+}
+
+impl CopyOptionsOverride {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Response s job ID
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CopyJobResponse {
+    pub job_id: Uuid,
+    pub message: String,
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Request pro vytvoření deploy jobu
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateDeployJobRequest {
+    pub release_id: Uuid,
+    pub environment_id: Uuid,
+    pub dry_run: Option<bool>,
+    pub release_image_url_mode: Option<String>,
+    /// Jen render + validace + report, bez git zápisů (na environmentu s `validate_only_required` vynuceno)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validate_only: Option<bool>,
+    /// Jednorázové přepsání extra env proměnných (jen klíče z allow-listu environmentu)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_overrides: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeployJobResponse {
+    pub job_id: Uuid,
+    pub message: String,
+}
@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Request pro batch status - copy i deploy job IDs v jednom seznamu
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobStatusBatchRequest {
+    pub job_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct JobStatusItem {
+    pub job_id: Uuid,
+    /// `copy` nebo `deploy`
+    pub kind: String,
+    pub status: String,
+    pub is_finished: bool,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_images: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub copied_images: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failed_images: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobStatusBatchResponse {
+    pub jobs: Vec<JobStatusItem>,
+    /// IDs, které neexistují nebo k nim uživatel nemá přístup
+    pub not_found: Vec<Uuid>,
+}
+
+/// Job čekající na spuštění (`GET /api/v1/queue`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct QueuedJob {
+    pub job_id: Uuid,
+    /// `copy` nebo `deploy`
+    pub kind: String,
+    /// `pending` (ještě nespuštěn přes `/start`), `queued` (čeká na volný worker), `starting` (převzatý workerem)
+    pub queue_state: String,
+    /// Pořadí ve frontě daného typu, 1 = další na řadě (jen `queued`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<i64>,
+    pub tenant_id: Uuid,
+    /// Bundle verze a cílový tag, resp. release a environment
+    pub description: String,
+    pub created_at: DateTime<Utc>,
+    pub queued_at: Option<DateTime<Utc>>,
+    pub claimed_at: Option<DateTime<Utc>>,
+    pub claimed_by: Option<String>,
+    /// Od zařazení do fronty, u `pending` od vytvoření
+    pub waiting_seconds: i64,
+}
+
+/// Počty jobů jednoho typu v tenantech, které volající vidí
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueueSummary {
+    pub kind: String,
+    pub pending: i64,
+    pub queued: i64,
+    pub starting: i64,
+    pub running: i64,
+}
+
+/// Běžící joby podle procesu (jen joby viditelných tenantů); `worker_id` = null pro joby spuštěné přímo v API procesu
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct WorkerLoad {
+    pub worker_id: Option<String>,
+    pub copy_running: i64,
+    pub deploy_running: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueOverview {
+    pub summary: Vec<QueueSummary>,
+    pub workers: Vec<WorkerLoad>,
+    pub jobs: Vec<QueuedJob>,
+}
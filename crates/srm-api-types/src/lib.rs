@@ -0,0 +1,10 @@
+//! Request/response typy HTTP API (`/api/v1`), sdílené serverem a `srm-client`.
+//!
+//! Feature `sqlx` přidává `FromRow` derive pro typy, které server čte přímo z DB.
+
+pub mod copy;
+pub mod deploy;
+pub mod jobs;
+pub mod logs;
+pub mod pipelines;
+pub mod releases;
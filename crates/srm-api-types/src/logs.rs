@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LogPollQuery {
+    /// Vrátí jen řádky se `seq > after_seq` (0 = od začátku)
+    pub after_seq: Option<i64>,
+    pub timeout_seconds: Option<u64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct PolledLogLine {
+    pub seq: i64,
+    pub line: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Progress snapshot (copy jobs only)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct JobProgress {
+    pub stage: Option<String>,
+    pub message: Option<String>,
+    pub bytes_copied: Option<i64>,
+    pub total_bytes: Option<i64>,
+    pub total_images: Option<i64>,
+    pub copied_images: Option<i64>,
+    pub failed_images: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogPollResponse {
+    pub lines: Vec<PolledLogLine>,
+    /// Hodnota pro další `?after_seq=`
+    pub next_seq: i64,
+    pub status: String,
+    pub finished: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub progress: Option<JobProgress>,
+}
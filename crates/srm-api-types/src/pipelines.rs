@@ -0,0 +1,45 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Request pro spuštění pipeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriggerPipelineRequest {
+    pub bundle_id: Uuid,
+    pub version: i32,
+    /// Environment chain; copy běží pro první prostředí, deploy postupně do všech
+    pub environment_ids: Vec<Uuid>,
+    pub release_id: String,
+    pub target_tag: Option<String>,
+    pub notes: Option<String>,
+    pub deploy: Option<bool>,
+    pub dry_run: Option<bool>,
+    /// Kanál vytvořeného release (výchozí `stable`); deploy vyžaduje, aby ho odebíraly všechna prostředí chainu
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+}
+
+/// Pipeline run - copy -> release -> deploy spuštěné jedním voláním
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct PipelineRun {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub bundle_id: Uuid,
+    pub bundle_version: i32,
+    pub environment_ids: Vec<Uuid>,
+    pub release_id: String,
+    pub target_tag: Option<String>,
+    pub deploy: bool,
+    pub dry_run: bool,
+    pub status: String,
+    pub stage: String,
+    pub copy_job_id: Option<Uuid>,
+    pub release_uuid: Option<Uuid>,
+    pub deploy_job_ids: Vec<Uuid>,
+    pub error_message: Option<String>,
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,43 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Request pro vytvoření nového release
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateReleaseRequest {
+    pub copy_job_id: Uuid,
+    pub release_id: String,
+    pub notes: Option<String>,
+    pub created_by: Option<String>,
+    pub source_ref_mode: Option<String>,
+    /// Kanál release: `rc`, `stable` (výchozí) nebo `hotfix`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+}
+
+/// Release - zamašličkovaný snapshot pro produkci
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct Release {
+    pub id: Uuid,
+    pub copy_job_id: Uuid,
+    pub release_id: String,
+    pub status: String,
+    pub source_ref_mode: String,
+    pub notes: Option<String>,
+    pub created_by: Option<String>,
+    pub is_auto: bool,
+    pub auto_reason: Option<String>,
+    pub extra_tags: Option<Vec<String>>,
+    /// Release, nad kterým vznikl tento patch release (selective copy)
+    #[serde(default)]
+    pub base_release_id: Option<Uuid>,
+    /// Kanál release (`rc`, `stable`, `hotfix`); nasadit ho jde jen do environmentů, které kanál odebírají
+    #[serde(default = "default_channel")]
+    pub channel: String,
+    pub created_at: DateTime<Utc>,
+}
+
+fn default_channel() -> String {
+    "stable".to_string()
+}
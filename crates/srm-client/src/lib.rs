@@ -0,0 +1,277 @@
+//! Tenký HTTP klient pro Simple Release Management API (`/api/v1`).
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), srm_client::Error> {
+//! use srm_client::{types::copy::CopyBundleRequest, Client};
+//!
+//! let client = Client::new("https://srm.example.com").with_token("...");
+//! let job = client
+//!     .copy_bundle_version(bundle_id(), 3, &CopyBundleRequest::default())
+//!     .await?;
+//! client.start_copy_job(job.job_id).await?;
+//! # Ok(())
+//! # }
+//! # fn bundle_id() -> uuid::Uuid { uuid::Uuid::nil() }
+//! ```
+
+use reqwest::StatusCode;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+pub use reqwest::Method;
+pub use srm_api_types as types;
+use types::{
+    copy::{CopyBundleRequest, CopyJobResponse},
+    deploy::{CreateDeployJobRequest, DeployJobResponse},
+    jobs::{JobStatusBatchRequest, JobStatusBatchResponse, QueueOverview},
+    logs::{LogPollQuery, LogPollResponse},
+    pipelines::{PipelineRun, TriggerPipelineRequest},
+    releases::{CreateReleaseRequest, Release},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Request to {path} failed: {source}")]
+    Http {
+        path: String,
+        #[source]
+        source: reqwest::Error,
+    },
+    #[error("{status} {path}: {message}")]
+    Api {
+        status: u16,
+        path: String,
+        message: String,
+    },
+    #[error("Unexpected response from {path}: {source}")]
+    Decode {
+        path: String,
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, Clone)]
+pub struct Client {
+    http: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+impl Client {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_http_client(reqwest::Client::new(), base_url)
+    }
+
+    /// Vlastní `reqwest::Client` (proxy, TLS, timeouty)
+    pub fn with_http_client(http: reqwest::Client, base_url: impl Into<String>) -> Self {
+        Client {
+            http,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            token: None,
+        }
+    }
+
+    /// Token posílaný jako `Authorization: Bearer <token>`
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// POST /api/v1/bundles/{id}/versions/{version}/copy
+    pub async fn copy_bundle_version(
+        &self,
+        bundle_id: Uuid,
+        version: i32,
+        request: &CopyBundleRequest,
+    ) -> Result<CopyJobResponse> {
+        let path = format!("/api/v1/bundles/{}/versions/{}/copy", bundle_id, version);
+        self.send_json(Method::POST, &path, Some(request)).await
+    }
+
+    /// POST /api/v1/copy/jobs/{id}/start
+    pub async fn start_copy_job(&self, job_id: Uuid) -> Result<()> {
+        let path = format!("/api/v1/copy/jobs/{}/start", job_id);
+        self.send(Method::POST, &path, None::<&()>).await.map(drop)
+    }
+
+    /// GET /api/v1/copy/jobs/{id} - detail jobu jako JSON
+    pub async fn get_copy_job(&self, job_id: Uuid) -> Result<Value> {
+        self.send(Method::GET, &format!("/api/v1/copy/jobs/{}", job_id), None::<&()>)
+            .await
+    }
+
+    /// GET /api/v1/copy/jobs/{id}/logs/poll
+    pub async fn poll_copy_job_logs(&self, job_id: Uuid, query: &LogPollQuery) -> Result<LogPollResponse> {
+        let path = format!("/api/v1/copy/jobs/{}/logs/poll{}", job_id, poll_query_string(query));
+        self.send_json(Method::GET, &path, None::<&()>).await
+    }
+
+    /// POST /api/v1/releases
+    pub async fn create_release(&self, request: &CreateReleaseRequest) -> Result<Release> {
+        self.send_json(Method::POST, "/api/v1/releases", Some(request)).await
+    }
+
+    /// GET /api/v1/releases/{id}
+    pub async fn get_release(&self, id: Uuid) -> Result<Release> {
+        self.send_json(Method::GET, &format!("/api/v1/releases/{}", id), None::<&()>)
+            .await
+    }
+
+    /// POST /api/v1/deploy/jobs
+    pub async fn create_deploy_job(&self, request: &CreateDeployJobRequest) -> Result<DeployJobResponse> {
+        self.send_json(Method::POST, "/api/v1/deploy/jobs", Some(request)).await
+    }
+
+    /// POST /api/v1/deploy/jobs/{id}/start
+    pub async fn start_deploy_job(&self, job_id: Uuid) -> Result<()> {
+        let path = format!("/api/v1/deploy/jobs/{}/start", job_id);
+        self.send(Method::POST, &path, None::<&()>).await.map(drop)
+    }
+
+    /// GET /api/v1/deploy/jobs/{id} - detail jobu jako JSON
+    pub async fn get_deploy_job(&self, job_id: Uuid) -> Result<Value> {
+        self.send(Method::GET, &format!("/api/v1/deploy/jobs/{}", job_id), None::<&()>)
+            .await
+    }
+
+    /// GET /api/v1/deploy/jobs/{id}/logs/history
+    pub async fn deploy_job_log_history(&self, job_id: Uuid) -> Result<Vec<String>> {
+        let path = format!("/api/v1/deploy/jobs/{}/logs/history", job_id);
+        self.send_json(Method::GET, &path, None::<&()>).await
+    }
+
+    /// GET /api/v1/deploy/jobs/{id}/logs/poll
+    pub async fn poll_deploy_job_logs(&self, job_id: Uuid, query: &LogPollQuery) -> Result<LogPollResponse> {
+        let path = format!("/api/v1/deploy/jobs/{}/logs/poll{}", job_id, poll_query_string(query));
+        self.send_json(Method::GET, &path, None::<&()>).await
+    }
+
+    /// POST /api/v1/jobs/status - stav více copy/deploy jobů
+    pub async fn job_status(&self, job_ids: &[Uuid]) -> Result<JobStatusBatchResponse> {
+        let request = JobStatusBatchRequest {
+            job_ids: job_ids.to_vec(),
+        };
+        self.send_json(Method::POST, "/api/v1/jobs/status", Some(&request)).await
+    }
+
+    /// GET /api/v1/queue - nespuštěné copy/deploy joby a vytížení workerů
+    pub async fn job_queue(&self) -> Result<QueueOverview> {
+        self.send_json(Method::GET, "/api/v1/queue", None::<&()>).await
+    }
+
+    /// POST /api/v1/pipelines/trigger
+    pub async fn trigger_pipeline(&self, request: &TriggerPipelineRequest) -> Result<PipelineRun> {
+        self.send_json(Method::POST, "/api/v1/pipelines/trigger", Some(request)).await
+    }
+
+    /// GET /api/v1/pipelines/{id}
+    pub async fn get_pipeline(&self, id: Uuid) -> Result<PipelineRun> {
+        self.send_json(Method::GET, &format!("/api/v1/pipelines/{}", id), None::<&()>)
+            .await
+    }
+
+    /// Obecný request pro endpointy bez typů v `srm-api-types`; prázdné tělo = `Value::Null`
+    pub async fn send<B: Serialize + ?Sized>(&self, method: Method, path: &str, body: Option<&B>) -> Result<Value> {
+        let mut request = self.http.request(method, format!("{}{}", self.base_url, path));
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+
+        let response = request.send().await.map_err(|source| Error::Http {
+            path: path.to_string(),
+            source,
+        })?;
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(Error::Api {
+                status: status.as_u16(),
+                path: path.to_string(),
+                message: error_message(status, &text),
+            });
+        }
+        if text.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+        serde_json::from_str(&text).map_err(|source| Error::Decode {
+            path: path.to_string(),
+            source,
+        })
+    }
+
+    async fn send_json<T: DeserializeOwned, B: Serialize + ?Sized>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<T> {
+        let value = self.send(method, path, body).await?;
+        serde_json::from_value(value).map_err(|source| Error::Decode {
+            path: path.to_string(),
+            source,
+        })
+    }
+}
+
+fn poll_query_string(query: &LogPollQuery) -> String {
+    let params: Vec<String> = [
+        query.after_seq.map(|v| format!("after_seq={}", v)),
+        query.timeout_seconds.map(|v| format!("timeout_seconds={}", v)),
+        query.limit.map(|v| format!("limit={}", v)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if params.is_empty() {
+        String::new()
+    } else {
+        format!("?{}", params.join("&"))
+    }
+}
+
+/// Vytáhne chybovou hlášku z v1 (`{error: "..."}`) i v2 (`{error: {message}}`) odpovědi
+pub fn error_message(status: StatusCode, body: &str) -> String {
+    let parsed: Option<Value> = serde_json::from_str(body).ok();
+    let message = parsed.as_ref().and_then(|value| match value.get("error") {
+        Some(Value::String(message)) => Some(message.clone()),
+        Some(Value::Object(error)) => error.get("message").and_then(Value::as_str).map(str::to_string),
+        _ => None,
+    });
+    message
+        .or_else(|| (!body.trim().is_empty()).then(|| body.trim().to_string()))
+        .unwrap_or_else(|| status.canonical_reason().unwrap_or("error").to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_message_handles_v1_and_v2_bodies() {
+        assert_eq!(error_message(StatusCode::BAD_REQUEST, r#"{"error":"boom"}"#), "boom");
+        assert_eq!(
+            error_message(StatusCode::NOT_FOUND, r#"{"error":{"code":"not_found","message":"gone"}}"#),
+            "gone"
+        );
+        assert_eq!(error_message(StatusCode::FORBIDDEN, "Insufficient role"), "Insufficient role");
+        assert_eq!(error_message(StatusCode::BAD_GATEWAY, ""), "Bad Gateway");
+    }
+
+    #[test]
+    fn poll_query_string_skips_unset_params() {
+        assert_eq!(poll_query_string(&LogPollQuery::default()), "");
+        let query = LogPollQuery {
+            after_seq: Some(10),
+            timeout_seconds: None,
+            limit: Some(50),
+        };
+        assert_eq!(poll_query_string(&query), "?after_seq=10&limit=50");
+    }
+}
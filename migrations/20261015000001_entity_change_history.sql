@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS entity_change_history (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    entity_type VARCHAR(50) NOT NULL,
+    entity_id UUID NOT NULL,
+    tenant_id UUID REFERENCES tenants(id) ON DELETE CASCADE,
+    changed_by VARCHAR(255),
+    changes JSONB NOT NULL DEFAULT '[]'::jsonb,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_entity_change_history_entity
+    ON entity_change_history (entity_type, entity_id, created_at DESC);
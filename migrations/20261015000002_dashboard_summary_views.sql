@@ -0,0 +1,107 @@
+-- Summary views for dashboard-style list/stats endpoints (refreshed by a background task)
+CREATE MATERIALIZED VIEW IF NOT EXISTS deploy_job_list_mv AS
+SELECT
+    dj.id,
+    dj.status,
+    dj.started_at,
+    dj.completed_at,
+    dj.error_message,
+    dj.commit_sha,
+    dj.tag_name,
+    e.name AS target_name,
+    e.slug AS env_name,
+    e.color AS env_color,
+    dj.environment_id,
+    r.id AS release_db_id,
+    r.release_id,
+    r.is_auto,
+    b.id AS bundle_id,
+    b.name AS bundle_name,
+    t.id AS tenant_id,
+    t.name AS tenant_name,
+    dj.dry_run
+FROM deploy_jobs dj
+JOIN environments e ON e.id = dj.environment_id
+JOIN releases r ON r.id = dj.release_id
+JOIN copy_jobs cj ON cj.id = r.copy_job_id
+JOIN bundle_versions bv ON bv.id = cj.bundle_version_id
+JOIN bundles b ON b.id = bv.bundle_id
+JOIN tenants t ON t.id = b.tenant_id;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_deploy_job_list_mv_id ON deploy_job_list_mv (id);
+CREATE INDEX IF NOT EXISTS idx_deploy_job_list_mv_started ON deploy_job_list_mv (tenant_id, started_at DESC);
+
+CREATE MATERIALIZED VIEW IF NOT EXISTS copy_job_list_mv AS
+SELECT
+    cj.id AS job_id,
+    bv.bundle_id,
+    b.name AS bundle_name,
+    b.tenant_id,
+    bv.version,
+    cj.target_tag,
+    cj.status,
+    cj.is_release_job,
+    cj.is_selective,
+    cj.base_copy_job_id,
+    cj.validate_only,
+    cj.source_registry_id,
+    cj.target_registry_id,
+    cj.environment_id,
+    cj.started_at,
+    cj.completed_at
+FROM copy_jobs cj
+JOIN bundle_versions bv ON bv.id = cj.bundle_version_id
+JOIN bundles b ON b.id = bv.bundle_id;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_copy_job_list_mv_id ON copy_job_list_mv (job_id);
+CREATE INDEX IF NOT EXISTS idx_copy_job_list_mv_started ON copy_job_list_mv (tenant_id, started_at DESC);
+
+CREATE MATERIALIZED VIEW IF NOT EXISTS dashboard_tenant_stats_mv AS
+SELECT
+    t.id AS tenant_id,
+    t.name AS tenant_name,
+    (SELECT COUNT(*) FROM bundles b WHERE b.tenant_id = t.id) AS bundles_total,
+    (SELECT COUNT(*) FROM bundles b WHERE b.tenant_id = t.id AND NOT b.is_archived) AS bundles_active,
+    (SELECT COUNT(*) FROM registries rg WHERE rg.tenant_id = t.id) AS registries_total,
+    (SELECT COUNT(*) FROM environments e WHERE e.tenant_id = t.id) AS environments_total,
+    (SELECT COUNT(*)
+     FROM releases r
+     JOIN copy_jobs cj ON cj.id = r.copy_job_id
+     JOIN bundle_versions bv ON bv.id = cj.bundle_version_id
+     JOIN bundles b ON b.id = bv.bundle_id
+     WHERE b.tenant_id = t.id) AS releases_total,
+    (SELECT COUNT(*)
+     FROM copy_jobs cj
+     JOIN bundle_versions bv ON bv.id = cj.bundle_version_id
+     JOIN bundles b ON b.id = bv.bundle_id
+     WHERE b.tenant_id = t.id) AS copy_jobs_total,
+    (SELECT COUNT(*)
+     FROM copy_jobs cj
+     JOIN bundle_versions bv ON bv.id = cj.bundle_version_id
+     JOIN bundles b ON b.id = bv.bundle_id
+     WHERE b.tenant_id = t.id AND cj.status = 'failed') AS copy_jobs_failed,
+    (SELECT COUNT(*)
+     FROM deploy_jobs dj
+     JOIN environments e ON e.id = dj.environment_id
+     WHERE e.tenant_id = t.id) AS deploy_jobs_total,
+    (SELECT COUNT(*)
+     FROM deploy_jobs dj
+     JOIN environments e ON e.id = dj.environment_id
+     WHERE e.tenant_id = t.id AND dj.status = 'failed') AS deploy_jobs_failed,
+    (SELECT MAX(dj.completed_at)
+     FROM deploy_jobs dj
+     JOIN environments e ON e.id = dj.environment_id
+     WHERE e.tenant_id = t.id AND dj.status = 'success') AS last_successful_deploy_at
+FROM tenants t;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_dashboard_tenant_stats_mv_tenant ON dashboard_tenant_stats_mv (tenant_id);
+
+CREATE TABLE IF NOT EXISTS dashboard_view_refreshes (
+    view_name VARCHAR(100) PRIMARY KEY,
+    refreshed_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    duration_ms BIGINT NOT NULL DEFAULT 0
+);
+
+INSERT INTO dashboard_view_refreshes (view_name)
+VALUES ('deploy_job_list_mv'), ('copy_job_list_mv'), ('dashboard_tenant_stats_mv')
+ON CONFLICT (view_name) DO NOTHING;
@@ -0,0 +1,25 @@
+-- Pipeline runs: copy -> release -> deploy orchestrated by POST /api/v1/pipelines/trigger
+CREATE TABLE IF NOT EXISTS pipeline_runs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    bundle_id UUID NOT NULL REFERENCES bundles(id) ON DELETE CASCADE,
+    bundle_version INTEGER NOT NULL,
+    environment_ids UUID[] NOT NULL,
+    release_id TEXT NOT NULL,
+    target_tag TEXT,
+    deploy BOOLEAN NOT NULL DEFAULT false,
+    dry_run BOOLEAN NOT NULL DEFAULT false,
+    status TEXT NOT NULL DEFAULT 'running',
+    stage TEXT NOT NULL DEFAULT 'copy',
+    copy_job_id UUID REFERENCES copy_jobs(id) ON DELETE SET NULL,
+    release_uuid UUID REFERENCES releases(id) ON DELETE SET NULL,
+    deploy_job_ids UUID[] NOT NULL DEFAULT '{}',
+    error_message TEXT,
+    created_by TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_pipeline_runs_tenant_created
+    ON pipeline_runs (tenant_id, created_at DESC);
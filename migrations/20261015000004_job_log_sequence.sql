@@ -0,0 +1,32 @@
+-- Sequence numbers for persisted log lines (long-poll fallback: ?after_seq=)
+ALTER TABLE copy_job_logs ADD COLUMN IF NOT EXISTS seq BIGINT;
+
+WITH ordered AS (
+    SELECT id, row_number() OVER (ORDER BY created_at, id) AS rn
+    FROM copy_job_logs
+)
+UPDATE copy_job_logs l SET seq = o.rn FROM ordered o WHERE l.id = o.id;
+
+CREATE SEQUENCE IF NOT EXISTS copy_job_logs_seq_seq OWNED BY copy_job_logs.seq;
+SELECT setval('copy_job_logs_seq_seq', COALESCE((SELECT MAX(seq) FROM copy_job_logs), 0) + 1, false);
+ALTER TABLE copy_job_logs
+    ALTER COLUMN seq SET DEFAULT nextval('copy_job_logs_seq_seq'),
+    ALTER COLUMN seq SET NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_copy_job_logs_job_seq ON copy_job_logs(copy_job_id, seq);
+
+ALTER TABLE deploy_job_logs ADD COLUMN IF NOT EXISTS seq BIGINT;
+
+WITH ordered AS (
+    SELECT id, row_number() OVER (ORDER BY created_at, id) AS rn
+    FROM deploy_job_logs
+)
+UPDATE deploy_job_logs l SET seq = o.rn FROM ordered o WHERE l.id = o.id;
+
+CREATE SEQUENCE IF NOT EXISTS deploy_job_logs_seq_seq OWNED BY deploy_job_logs.seq;
+SELECT setval('deploy_job_logs_seq_seq', COALESCE((SELECT MAX(seq) FROM deploy_job_logs), 0) + 1, false);
+ALTER TABLE deploy_job_logs
+    ALTER COLUMN seq SET DEFAULT nextval('deploy_job_logs_seq_seq'),
+    ALTER COLUMN seq SET NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_deploy_job_logs_job_seq ON deploy_job_logs(deploy_job_id, seq);
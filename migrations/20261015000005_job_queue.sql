@@ -0,0 +1,17 @@
+-- DB fronta jobů pro out-of-process workery (--role worker)
+ALTER TABLE copy_jobs
+    ADD COLUMN IF NOT EXISTS queued_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS claimed_by VARCHAR(255),
+    ADD COLUMN IF NOT EXISTS claimed_at TIMESTAMPTZ;
+
+ALTER TABLE deploy_jobs
+    ADD COLUMN IF NOT EXISTS queued_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS claimed_by VARCHAR(255),
+    ADD COLUMN IF NOT EXISTS claimed_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_copy_jobs_queue
+    ON copy_jobs(queued_at) WHERE status = 'pending' AND queued_at IS NOT NULL AND claimed_by IS NULL;
+CREATE INDEX IF NOT EXISTS idx_deploy_jobs_queue
+    ON deploy_jobs(queued_at) WHERE status = 'pending' AND queued_at IS NOT NULL AND claimed_by IS NULL;
+CREATE INDEX IF NOT EXISTS idx_copy_jobs_claimed_by ON copy_jobs(claimed_by) WHERE claimed_by IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_deploy_jobs_claimed_by ON deploy_jobs(claimed_by) WHERE claimed_by IS NOT NULL;
@@ -0,0 +1,4 @@
+-- Snapshot release manifestu uložený při vytvoření release (deploy a export ho čtou místo joinů)
+ALTER TABLE releases
+    ADD COLUMN IF NOT EXISTS manifest_snapshot JSONB,
+    ADD COLUMN IF NOT EXISTS manifest_snapshot_at TIMESTAMPTZ;
@@ -0,0 +1,4 @@
+-- Pointery do object storage (S3/MinIO) pro archivované logy a deploy diffy
+ALTER TABLE copy_jobs ADD COLUMN IF NOT EXISTS log_archive_key TEXT;
+ALTER TABLE deploy_jobs ADD COLUMN IF NOT EXISTS log_archive_key TEXT;
+ALTER TABLE deploy_job_diffs ADD COLUMN IF NOT EXISTS diff_patch_key TEXT;
@@ -0,0 +1,6 @@
+-- Deploy diffy: gzip uložení, limit velikosti a index souborů pro ?file=
+ALTER TABLE deploy_job_diffs
+    ADD COLUMN IF NOT EXISTS diff_patch_gz BYTEA,
+    ADD COLUMN IF NOT EXISTS diff_size_bytes BIGINT,
+    ADD COLUMN IF NOT EXISTS diff_truncated BOOLEAN NOT NULL DEFAULT false,
+    ADD COLUMN IF NOT EXISTS file_index JSONB;
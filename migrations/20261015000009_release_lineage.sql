@@ -0,0 +1,5 @@
+-- Patch release (selective copy nad release jobem) - odkaz na base release
+ALTER TABLE releases
+    ADD COLUMN IF NOT EXISTS base_release_id UUID REFERENCES releases(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_releases_base_release_id ON releases(base_release_id);
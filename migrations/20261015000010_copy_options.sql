@@ -0,0 +1,6 @@
+-- Retry politika a skopeo copy flagy: přepsání na registry a na copy jobu
+ALTER TABLE registries
+    ADD COLUMN IF NOT EXISTS copy_options JSONB;
+
+ALTER TABLE copy_jobs
+    ADD COLUMN IF NOT EXISTS copy_options JSONB;
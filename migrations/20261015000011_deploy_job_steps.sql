@@ -0,0 +1,15 @@
+-- Stav jednotlivých kroků deploy jobu (clone, render, encjson, kubeconform, diff, push)
+CREATE TABLE IF NOT EXISTS deploy_job_steps (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    deploy_job_id UUID NOT NULL REFERENCES deploy_jobs(id) ON DELETE CASCADE,
+    step TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    started_at TIMESTAMPTZ,
+    completed_at TIMESTAMPTZ,
+    duration_ms BIGINT,
+    error_line TEXT,
+    UNIQUE (deploy_job_id, step)
+);
+
+CREATE INDEX IF NOT EXISTS idx_deploy_job_steps_job_id ON deploy_job_steps(deploy_job_id);
@@ -0,0 +1,6 @@
+-- Vrstvení env proměnných: deploy target ← environment ← job
+-- Přepsání extra env proměnných pro konkrétní deploy job
+ALTER TABLE deploy_jobs ADD COLUMN IF NOT EXISTS env_overrides JSONB NOT NULL DEFAULT '{}'::jsonb;
+
+-- Snapshot výsledných proměnných v okamžiku spuštění jobu (GET /deploy/jobs/{id}/env)
+ALTER TABLE deploy_jobs ADD COLUMN IF NOT EXISTS resolved_env JSONB;
@@ -0,0 +1,5 @@
+-- Klíče extra env proměnných, které smí přepsat deploy job (přesný název nebo prefix `FEATURE_*`)
+ALTER TABLE environments ADD COLUMN IF NOT EXISTS job_env_override_allowlist TEXT[] NOT NULL DEFAULT '{}';
+
+-- Kdo job s přepsanými proměnnými vytvořil
+ALTER TABLE deploy_jobs ADD COLUMN IF NOT EXISTS env_overrides_by TEXT;
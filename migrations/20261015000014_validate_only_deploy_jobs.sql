@@ -0,0 +1,7 @@
+-- Validate-only deploy joby: render + validace + diff/images/report, nikdy žádný git zápis
+ALTER TABLE deploy_jobs
+    ADD COLUMN IF NOT EXISTS validate_only BOOLEAN NOT NULL DEFAULT false,
+    ADD COLUMN IF NOT EXISTS validation_report JSONB;
+
+-- Environment, na kterém jsou povolené jen validate-only joby (např. pro externí audit)
+ALTER TABLE environments ADD COLUMN IF NOT EXISTS validate_only_required BOOLEAN NOT NULL DEFAULT false;
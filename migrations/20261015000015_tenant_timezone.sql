@@ -0,0 +1,2 @@
+-- IANA timezone tenanta pro datum v auto tagu (NULL = offset z prohlížeče)
+ALTER TABLE tenants ADD COLUMN IF NOT EXISTS timezone TEXT;
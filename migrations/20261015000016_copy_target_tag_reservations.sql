@@ -0,0 +1,26 @@
+-- Unikátní target tag: (cílová registry, image, tag) patří jednomu copy jobu
+CREATE TABLE IF NOT EXISTS copy_job_target_tags (
+    target_registry_id UUID NOT NULL REFERENCES registries(id) ON DELETE CASCADE,
+    target_image TEXT NOT NULL,
+    target_tag TEXT NOT NULL,
+    copy_job_id UUID NOT NULL REFERENCES copy_jobs(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (target_registry_id, target_image, target_tag)
+);
+
+CREATE INDEX IF NOT EXISTS idx_copy_job_target_tags_job ON copy_job_target_tags(copy_job_id);
+
+-- Přepsání existujícího target tagu je explicitní volba jobu
+ALTER TABLE copy_jobs ADD COLUMN IF NOT EXISTS overwrite_target_tag BOOLEAN NOT NULL DEFAULT false;
+
+-- Existující tagy (vyhrává nejnovější job, který neselhal); release joby jen přetagovávají
+INSERT INTO copy_job_target_tags (target_registry_id, target_image, target_tag, copy_job_id, created_at)
+SELECT DISTINCT ON (cj.target_registry_id, cji.target_image, cji.target_tag)
+       cj.target_registry_id, cji.target_image, cji.target_tag, cj.id, cj.created_at
+FROM copy_job_images cji
+JOIN copy_jobs cj ON cj.id = cji.copy_job_id
+WHERE cj.target_registry_id IS NOT NULL
+  AND NOT cj.is_release_job
+  AND cj.status NOT IN ('failed', 'cancelled')
+ORDER BY cj.target_registry_id, cji.target_image, cji.target_tag, cj.created_at DESC
+ON CONFLICT DO NOTHING;
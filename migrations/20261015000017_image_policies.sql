@@ -0,0 +1,15 @@
+-- Allow/deny pravidla tenanta pro zdrojové images (regex na registry host / repository / slug environmentu)
+CREATE TABLE IF NOT EXISTS image_policies (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    effect TEXT NOT NULL CHECK (effect IN ('allow', 'deny')),
+    registry_pattern TEXT,
+    repository_pattern TEXT,
+    environment_pattern TEXT,
+    description TEXT,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_image_policies_tenant ON image_policies(tenant_id);
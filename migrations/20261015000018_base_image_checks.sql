@@ -0,0 +1,14 @@
+-- Poslední kontrola upstream digestu pro mapping na "plovoucím" tagu (latest, 1.27, ...)
+CREATE TABLE IF NOT EXISTS base_image_checks (
+    image_mapping_id UUID PRIMARY KEY REFERENCES image_mappings(id) ON DELETE CASCADE,
+    copy_job_image_id UUID REFERENCES copy_job_images(id) ON DELETE SET NULL,
+    source_ref TEXT NOT NULL,
+    copied_digest TEXT NOT NULL,
+    upstream_digest TEXT,
+    update_available BOOLEAN NOT NULL DEFAULT FALSE,
+    error TEXT,
+    last_copied_at TIMESTAMPTZ,
+    checked_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_base_image_checks_update ON base_image_checks(update_available) WHERE update_available;
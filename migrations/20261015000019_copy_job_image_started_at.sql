@@ -0,0 +1,7 @@
+-- Začátek kopie jednotlivé image (doba = copied_at - started_at) pro odhad ETA
+ALTER TABLE copy_job_images ADD COLUMN IF NOT EXISTS started_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_copy_job_images_source_history
+    ON copy_job_images(source_image, copied_at DESC) WHERE copy_status = 'success';
+CREATE INDEX IF NOT EXISTS idx_copy_job_images_recent_timed
+    ON copy_job_images(copied_at DESC) WHERE copy_status = 'success' AND started_at IS NOT NULL;
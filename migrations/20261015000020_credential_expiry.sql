@@ -0,0 +1,14 @@
+-- Volitelná expirace uložených credentials (robot tokeny, PAT, ...)
+ALTER TABLE registries ADD COLUMN IF NOT EXISTS credentials_expire_at TIMESTAMPTZ;
+ALTER TABLE environment_registry_credentials ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ;
+ALTER TABLE git_repositories ADD COLUMN IF NOT EXISTS credentials_expire_at TIMESTAMPTZ;
+
+-- Odeslané připomínky; každý práh (dny do expirace) se pro danou expiraci posílá jen jednou
+CREATE TABLE IF NOT EXISTS credential_expiry_reminders (
+    credential_kind TEXT NOT NULL,
+    credential_id UUID NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    threshold_days INTEGER NOT NULL,
+    sent_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (credential_kind, credential_id, expires_at, threshold_days)
+);
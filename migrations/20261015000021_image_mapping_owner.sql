@@ -0,0 +1,2 @@
+-- Vlastník aplikace (uživatel / tým) - cíl notifikací a skupina v release notes
+ALTER TABLE image_mappings ADD COLUMN IF NOT EXISTS owner VARCHAR(255);
@@ -0,0 +1,45 @@
+-- Cíle, kam se po vytvoření release doručí jeho manifest (commit do git repozitáře, S3 objekt, HTTP POST)
+CREATE TABLE IF NOT EXISTS manifest_destinations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('git', 's3', 'http')),
+    -- Jen releases tohoto bundle (NULL = všechny bundles tenanta)
+    bundle_id UUID REFERENCES bundles(id) ON DELETE CASCADE,
+    format TEXT NOT NULL DEFAULT 'yaml' CHECK (format IN ('yaml', 'json')),
+    -- Cesta v repozitáři / klíč objektu; placeholdery {tenant}, {bundle}, {release_id}, {release_uuid}, {ext}
+    path_template TEXT NOT NULL DEFAULT '{bundle}/{release_id}.{ext}',
+    git_repository_id UUID REFERENCES git_repositories(id) ON DELETE CASCADE,
+    git_branch TEXT,
+    -- HTTP: cílová URL; S3: endpoint (NULL = globální OBJECT_STORAGE_*)
+    url TEXT,
+    s3_bucket TEXT,
+    s3_region TEXT,
+    s3_access_key TEXT,
+    s3_path_style BOOLEAN NOT NULL DEFAULT FALSE,
+    -- HTTP bearer token / S3 secret key
+    secret_encrypted TEXT,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (tenant_id, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_manifest_destinations_tenant ON manifest_destinations(tenant_id);
+
+-- Stav doručení manifestu release do jednotlivých cílů
+CREATE TABLE IF NOT EXISTS release_manifest_deliveries (
+    release_id UUID NOT NULL REFERENCES releases(id) ON DELETE CASCADE,
+    destination_id UUID NOT NULL REFERENCES manifest_destinations(id) ON DELETE CASCADE,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'in_progress', 'success', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    -- Commit SHA / klíč objektu / HTTP status odpovědi
+    location TEXT,
+    error_message TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    delivered_at TIMESTAMPTZ,
+    PRIMARY KEY (release_id, destination_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_release_manifest_deliveries_status ON release_manifest_deliveries(status);
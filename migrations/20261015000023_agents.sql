@@ -0,0 +1,22 @@
+-- Agenti v izolovaných sítích (`--role agent`); copy joby prostředí s agentem spouští agent místo workeru
+CREATE TABLE IF NOT EXISTS agents (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    -- SHA-256 (hex) tokenu; samotný token se ukáže jen při vytvoření / rotaci
+    token_hash TEXT NOT NULL UNIQUE,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    -- Hlášeno agentem při heartbeatu
+    version TEXT,
+    hostname TEXT,
+    last_seen_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (tenant_id, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_agents_tenant ON agents(tenant_id);
+
+ALTER TABLE environments ADD COLUMN IF NOT EXISTS agent_id UUID REFERENCES agents(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_environments_agent ON environments(agent_id) WHERE agent_id IS NOT NULL;
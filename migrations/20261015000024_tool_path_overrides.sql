@@ -0,0 +1,9 @@
+-- Přepsání cest k binárkám nástrojů (např. starší skopeo pro legacy registry); NULL = zdědit
+-- Pořadí: environment -> tenant -> globální konfigurace (IMAGE_TOOL_PATH, ENCJSON_PATH, KUBE_BUILD_APP_PATH)
+ALTER TABLE tenants ADD COLUMN IF NOT EXISTS skopeo_path TEXT;
+ALTER TABLE tenants ADD COLUMN IF NOT EXISTS encjson_path TEXT;
+ALTER TABLE tenants ADD COLUMN IF NOT EXISTS kube_build_app_path TEXT;
+
+ALTER TABLE environments ADD COLUMN IF NOT EXISTS skopeo_path TEXT;
+ALTER TABLE environments ADD COLUMN IF NOT EXISTS encjson_path TEXT;
+ALTER TABLE environments ADD COLUMN IF NOT EXISTS kube_build_app_path TEXT;
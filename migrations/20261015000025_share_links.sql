@@ -0,0 +1,33 @@
+-- Dočasné podepsané odkazy na logy / diff jednoho jobu pro uživatele bez účtu (auditoři, dodavatelé)
+CREATE TABLE IF NOT EXISTS share_links (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    -- `copy` nebo `deploy`
+    job_kind TEXT NOT NULL,
+    job_id UUID NOT NULL,
+    -- `logs`, `diff`
+    scopes TEXT[] NOT NULL,
+    note TEXT,
+    created_by TEXT,
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_share_links_job ON share_links(job_kind, job_id);
+CREATE INDEX IF NOT EXISTS idx_share_links_tenant ON share_links(tenant_id);
+
+-- Audit přístupů přes odkaz, včetně odmítnutých (expirovaný / zrušený odkaz)
+CREATE TABLE IF NOT EXISTS share_link_access_log (
+    id BIGSERIAL PRIMARY KEY,
+    share_link_id UUID NOT NULL REFERENCES share_links(id) ON DELETE CASCADE,
+    -- `info`, `logs` nebo `diff`
+    resource TEXT NOT NULL,
+    -- `granted`, `expired`, `revoked` nebo `out_of_scope`
+    outcome TEXT NOT NULL,
+    remote_addr TEXT,
+    user_agent TEXT,
+    accessed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_share_link_access_log_link ON share_link_access_log(share_link_id, accessed_at DESC);
@@ -0,0 +1,121 @@
+-- Strukturované události jobů (start/konec jobu, kroky, image, schválení) pro timeline v UI a integrace
+CREATE TABLE IF NOT EXISTS job_events (
+    seq BIGSERIAL PRIMARY KEY,
+    copy_job_id UUID REFERENCES copy_jobs(id) ON DELETE CASCADE,
+    deploy_job_id UUID REFERENCES deploy_jobs(id) ON DELETE CASCADE,
+    -- `job.started`, `job.finished`, `step.started`, `step.finished`, `image.copied`, `image.failed`, `approval.granted`
+    event_type TEXT NOT NULL,
+    step TEXT,
+    data JSONB NOT NULL DEFAULT '{}'::jsonb,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK ((copy_job_id IS NULL) <> (deploy_job_id IS NULL))
+);
+
+CREATE INDEX IF NOT EXISTS idx_job_events_copy_job ON job_events(copy_job_id, seq) WHERE copy_job_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_job_events_deploy_job ON job_events(deploy_job_id, seq) WHERE deploy_job_id IS NOT NULL;
+
+-- Události ze změn stavu zapisují triggery, aby je generovaly všechny cesty
+-- (API, worker, agent, reaper) bez úprav jednotlivých míst v kódu.
+
+-- Start / konec copy a deploy jobu
+CREATE OR REPLACE FUNCTION job_events_on_job_status()
+RETURNS TRIGGER AS $$
+DECLARE
+    event TEXT;
+BEGIN
+    IF TG_OP = 'UPDATE' AND OLD.status IS NOT DISTINCT FROM NEW.status THEN
+        RETURN NEW;
+    END IF;
+
+    IF NEW.status = 'in_progress' THEN
+        event := 'job.started';
+    ELSIF NEW.status IN ('success', 'failed', 'cancelled') THEN
+        event := 'job.finished';
+    ELSE
+        RETURN NEW;
+    END IF;
+
+    IF TG_TABLE_NAME = 'copy_jobs' THEN
+        INSERT INTO job_events (copy_job_id, event_type, data)
+        VALUES (NEW.id, event, jsonb_build_object('status', NEW.status));
+    ELSE
+        INSERT INTO job_events (deploy_job_id, event_type, data)
+        VALUES (NEW.id, event, jsonb_build_object('status', NEW.status, 'error_message', NEW.error_message));
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS copy_jobs_job_events_trigger ON copy_jobs;
+CREATE TRIGGER copy_jobs_job_events_trigger
+    AFTER INSERT OR UPDATE OF status ON copy_jobs
+    FOR EACH ROW
+    EXECUTE FUNCTION job_events_on_job_status();
+
+DROP TRIGGER IF EXISTS deploy_jobs_job_events_trigger ON deploy_jobs;
+CREATE TRIGGER deploy_jobs_job_events_trigger
+    AFTER INSERT OR UPDATE OF status ON deploy_jobs
+    FOR EACH ROW
+    EXECUTE FUNCTION job_events_on_job_status();
+
+-- Kroky deploy jobu (deploy_job_steps)
+CREATE OR REPLACE FUNCTION job_events_on_deploy_step()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF OLD.status IS NOT DISTINCT FROM NEW.status THEN
+        RETURN NEW;
+    END IF;
+
+    IF NEW.status = 'in_progress' THEN
+        INSERT INTO job_events (deploy_job_id, event_type, step, data)
+        VALUES (NEW.deploy_job_id, 'step.started', NEW.step, jsonb_build_object('position', NEW.position));
+    ELSIF NEW.status IN ('success', 'warning', 'failed', 'skipped') THEN
+        INSERT INTO job_events (deploy_job_id, event_type, step, data)
+        VALUES (NEW.deploy_job_id, 'step.finished', NEW.step, jsonb_build_object(
+            'position', NEW.position,
+            'status', NEW.status,
+            'duration_ms', NEW.duration_ms,
+            'error_line', NEW.error_line
+        ));
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS deploy_job_steps_job_events_trigger ON deploy_job_steps;
+CREATE TRIGGER deploy_job_steps_job_events_trigger
+    AFTER UPDATE OF status ON deploy_job_steps
+    FOR EACH ROW
+    EXECUTE FUNCTION job_events_on_deploy_step();
+
+-- Jednotlivé image copy jobu
+CREATE OR REPLACE FUNCTION job_events_on_copy_image()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF OLD.copy_status IS NOT DISTINCT FROM NEW.copy_status
+        OR NEW.copy_status NOT IN ('success', 'failed') THEN
+        RETURN NEW;
+    END IF;
+
+    INSERT INTO job_events (copy_job_id, event_type, data)
+    VALUES (
+        NEW.copy_job_id,
+        CASE WHEN NEW.copy_status = 'success' THEN 'image.copied' ELSE 'image.failed' END,
+        jsonb_build_object(
+            'image_id', NEW.id,
+            'source', NEW.source_image || ':' || NEW.source_tag,
+            'target', NEW.target_image || ':' || NEW.target_tag,
+            'target_sha256', NEW.target_sha256,
+            'bytes_copied', NEW.bytes_copied,
+            'error_message', NEW.error_message
+        )
+    );
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS copy_job_images_job_events_trigger ON copy_job_images;
+CREATE TRIGGER copy_job_images_job_events_trigger
+    AFTER UPDATE OF copy_status ON copy_job_images
+    FOR EACH ROW
+    EXECUTE FUNCTION job_events_on_copy_image();
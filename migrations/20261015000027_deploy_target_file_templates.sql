@@ -0,0 +1,14 @@
+-- Šablony souborů generovaných při deployi (např. application.properties) z proměnných release.env
+CREATE TABLE IF NOT EXISTS deploy_target_file_templates (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    deploy_target_id UUID NOT NULL REFERENCES deploy_targets(id) ON DELETE CASCADE,
+    -- Relativní cesta v deploy výstupu
+    path TEXT NOT NULL,
+    -- Obsah šablony šifrovaný ENCRYPTION_SECRET (může obsahovat citlivé hodnoty)
+    content_encrypted TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (deploy_target_id, path)
+);
+
+CREATE INDEX IF NOT EXISTS idx_deploy_target_file_templates_target ON deploy_target_file_templates(deploy_target_id);
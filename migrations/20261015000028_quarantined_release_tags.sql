@@ -0,0 +1,13 @@
+-- Release tagy, které zůstaly v cílové registry po selhaném / zrušeném release jobu.
+-- Copy u nich nevěří shodě digestu a image zkopíruje znovu; úspěšný job záznam smaže.
+CREATE TABLE IF NOT EXISTS quarantined_release_tags (
+    target_registry_id UUID NOT NULL REFERENCES registries(id) ON DELETE CASCADE,
+    target_image TEXT NOT NULL,
+    target_tag TEXT NOT NULL,
+    digest TEXT NOT NULL,
+    copy_job_id UUID NOT NULL REFERENCES copy_jobs(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (target_registry_id, target_image, target_tag)
+);
+
+CREATE INDEX IF NOT EXISTS idx_quarantined_release_tags_job ON quarantined_release_tags(copy_job_id);
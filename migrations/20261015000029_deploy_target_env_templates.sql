@@ -0,0 +1,18 @@
+-- Šablona napojení environmentu na deploy target: cesty a větve s proměnnými ({env_slug}, {env_name}, {target_name}).
+-- Přidání nového environmentu do všech deploy targetů je pak jedna operace místo ruční úpravy každého targetu.
+CREATE TABLE IF NOT EXISTS deploy_target_env_templates (
+    deploy_target_id UUID PRIMARY KEY REFERENCES deploy_targets(id) ON DELETE CASCADE,
+    -- NULL = repozitář deploy targetu, jinak environmentu
+    env_repo_id UUID REFERENCES git_repositories(id),
+    env_repo_path TEXT,
+    env_repo_branch TEXT,
+    deploy_repo_id UUID REFERENCES git_repositories(id),
+    deploy_repo_path TEXT,
+    deploy_repo_branch TEXT,
+    allow_auto_release BOOLEAN NOT NULL DEFAULT FALSE,
+    append_env_suffix BOOLEAN NOT NULL DEFAULT FALSE,
+    release_manifest_mode VARCHAR(32) NOT NULL DEFAULT 'match_digest',
+    encjson_key_dir TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
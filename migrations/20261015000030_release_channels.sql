@@ -0,0 +1,14 @@
+-- Kanál release (rc / stable / hotfix) a odběr kanálů po environmentech.
+-- Stávající releases jsou stable, stávající environmenty odebírají všechny kanály (beze změny chování).
+ALTER TABLE releases
+    ADD COLUMN IF NOT EXISTS channel VARCHAR(16) NOT NULL DEFAULT 'stable'
+        CHECK (channel IN ('rc', 'stable', 'hotfix'));
+
+ALTER TABLE environments
+    ADD COLUMN IF NOT EXISTS release_channels TEXT[] NOT NULL DEFAULT '{rc,stable,hotfix}';
+
+-- Kanál release, který vznikne po doběhnutí release jobu (NULL = výchozí / z base release)
+ALTER TABLE copy_jobs
+    ADD COLUMN IF NOT EXISTS release_channel VARCHAR(16);
+
+CREATE INDEX IF NOT EXISTS idx_releases_channel ON releases(channel);
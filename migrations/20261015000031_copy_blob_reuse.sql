@@ -0,0 +1,4 @@
+-- Znovupoužití vrstev při kopii: bloby, které cílová registry už měla nebo je připojila z jiného repozitáře
+ALTER TABLE copy_job_images
+    ADD COLUMN IF NOT EXISTS bytes_reused BIGINT,
+    ADD COLUMN IF NOT EXISTS blobs_mounted INTEGER;
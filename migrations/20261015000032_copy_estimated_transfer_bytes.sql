@@ -0,0 +1,8 @@
+-- Přenesené bajty při znovupoužití vrstev jsou odhad (velikost vrstev, které cíl neměl), ne měření image toolu
+ALTER TABLE copy_job_images
+    ADD COLUMN IF NOT EXISTS estimated_transfer_bytes BIGINT;
+
+UPDATE copy_job_images
+SET estimated_transfer_bytes = bytes_copied,
+    bytes_copied = NULL
+WHERE bytes_reused IS NOT NULL;
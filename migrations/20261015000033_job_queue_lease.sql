@@ -0,0 +1,6 @@
+-- Lease převzatých jobů: worker ho obnovuje každým tickem, propadlý lease znamená spadlý worker
+ALTER TABLE copy_jobs ADD COLUMN IF NOT EXISTS heartbeat_at TIMESTAMPTZ;
+ALTER TABLE deploy_jobs ADD COLUMN IF NOT EXISTS heartbeat_at TIMESTAMPTZ;
+
+UPDATE copy_jobs SET heartbeat_at = claimed_at WHERE claimed_by IS NOT NULL AND heartbeat_at IS NULL;
+UPDATE deploy_jobs SET heartbeat_at = claimed_at WHERE claimed_by IS NOT NULL AND heartbeat_at IS NULL;
@@ -0,0 +1,4 @@
+-- Heartbeat driveru pipeline: běžící run bez heartbeatu patří spadlé instanci
+ALTER TABLE pipeline_runs ADD COLUMN IF NOT EXISTS heartbeat_at TIMESTAMPTZ NOT NULL DEFAULT now();
+
+UPDATE pipeline_runs SET heartbeat_at = updated_at;
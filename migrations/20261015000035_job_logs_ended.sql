@@ -0,0 +1,6 @@
+-- Značka konce logu: zapisuje se až po uložení posledního řádku, stream/poll končí teprve na ní
+ALTER TABLE copy_jobs ADD COLUMN IF NOT EXISTS logs_ended_at TIMESTAMPTZ;
+ALTER TABLE deploy_jobs ADD COLUMN IF NOT EXISTS logs_ended_at TIMESTAMPTZ;
+
+UPDATE copy_jobs SET logs_ended_at = completed_at WHERE status IN ('success', 'failed', 'cancelled');
+UPDATE deploy_jobs SET logs_ended_at = completed_at WHERE status IN ('success', 'failed', 'cancelled');
@@ -0,0 +1,15 @@
+-- Exporty release (release + manifest + release notes). S object storage drží DB jen pointer (object_key),
+-- bez něj je obsah v `content`, dokud ho archiver nepřesune
+CREATE TABLE IF NOT EXISTS release_exports (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    release_id UUID NOT NULL REFERENCES releases(id) ON DELETE CASCADE,
+    size_bytes BIGINT NOT NULL,
+    sha256 VARCHAR(64) NOT NULL,
+    content BYTEA,
+    object_key TEXT,
+    created_by VARCHAR(255),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT release_exports_stored CHECK (content IS NOT NULL OR object_key IS NOT NULL)
+);
+
+CREATE INDEX IF NOT EXISTS idx_release_exports_release ON release_exports(release_id, created_at DESC);
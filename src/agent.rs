@@ -0,0 +1,519 @@
+use anyhow::{bail, Context, Result};
+use reqwest::StatusCode;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::api::copy::is_missing_target_manifest_error;
+use crate::api::deploy::{self, DeployStepSink, DeployTools};
+use crate::services::agent_protocol::{
+    AgentCompleteRequest, AgentCopyImage, AgentCopyWork, AgentDeployCompleteRequest, AgentDeployStep,
+    AgentHeartbeat, AgentImageReport, AgentJobState, AgentLogBatch, DeployOutcome, DeployWork, IMAGE_FAILED,
+    IMAGE_IN_PROGRESS, IMAGE_SUCCESS, STEP_IN_PROGRESS, STEP_SKIPPED, STEP_SUCCESS, STEP_WARNING,
+};
+use crate::services::blob_reuse::{self, ReuseOptions};
+use crate::services::image_tool::{CopyStatus, SkopeoCredentials};
+use crate::services::ImageToolService;
+
+/// Jak často agent posílá nasbírané logy (a tím i zjišťuje zrušení jobu)
+const LOG_FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+/// Max. řádků v jednom log batchi (stejný limit jako na serveru)
+const LOG_BATCH_SIZE: usize = 1000;
+/// Pokusy o nahlášení dokončení jobu při výpadku spojení se serverem
+const COMPLETE_ATTEMPTS: u32 = 5;
+const COMPLETE_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Nastavení agenta (`--role agent`)
+#[derive(Debug, Clone)]
+pub struct AgentConfig {
+    /// URL centrálního SRM (včetně případného path prefixu)
+    pub server_url: String,
+    pub token: String,
+    pub poll_interval: Duration,
+}
+
+#[derive(Clone)]
+struct AgentClient {
+    http: reqwest::Client,
+    base_url: String,
+    token: String,
+}
+
+impl AgentClient {
+    async fn post<B: Serialize>(&self, path: &str, body: &B) -> Result<reqwest::Response> {
+        let response = self
+            .http
+            .post(format!("{}/api/v1/agent/{}", self.base_url, path))
+            .bearer_auth(&self.token)
+            .json(body)
+            .send()
+            .await
+            .with_context(|| format!("POST {} failed", path))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            bail!("POST {} returned {}: {}", path, status, body.trim());
+        }
+        Ok(response)
+    }
+}
+
+/// Smyčka agenta - heartbeat, převzetí copy / deploy jobu, lokální běh, hlášení výsledků
+pub async fn run(skopeo: ImageToolService, deploy_tools: DeployTools, config: AgentConfig) {
+    let client = AgentClient {
+        http: reqwest::Client::new(),
+        base_url: config.server_url.clone(),
+        token: config.token.clone(),
+    };
+    info!(server = %config.server_url, "Agent started");
+    loop {
+        match tick(&client, &skopeo, &deploy_tools).await {
+            // Po dokončeném jobu hned zkusit další
+            Ok(true) => continue,
+            Ok(false) => {}
+            Err(e) => warn!(error = %format!("{:#}", e), "Agent iteration failed"),
+        }
+        tokio::time::sleep(config.poll_interval).await;
+    }
+}
+
+/// `true` = agent zpracoval job
+async fn tick(client: &AgentClient, skopeo: &ImageToolService, deploy_tools: &DeployTools) -> Result<bool> {
+    client
+        .post(
+            "heartbeat",
+            &AgentHeartbeat {
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                hostname: std::env::var("HOSTNAME").ok(),
+            },
+        )
+        .await?;
+
+    let response = client.post("claim", &serde_json::json!({})).await?;
+    if response.status() != StatusCode::NO_CONTENT {
+        let work: AgentCopyWork = response.json().await.context("Invalid claim response")?;
+        run_copy_job(client, skopeo, work).await?;
+        return Ok(true);
+    }
+
+    let response = client.post("deploy-jobs/claim", &serde_json::json!({})).await?;
+    if response.status() == StatusCode::NO_CONTENT {
+        return Ok(false);
+    }
+    let work: DeployWork = response.json().await.context("Invalid deploy claim response")?;
+    run_deploy_job(client, deploy_tools, work).await?;
+    Ok(true)
+}
+
+async fn run_copy_job(client: &AgentClient, skopeo: &ImageToolService, work: AgentCopyWork) -> Result<()> {
+    let job_id = work.job_id;
+    info!(%job_id, images = work.images.len(), "Running copy job");
+
+    let (log_tx, log_rx) = broadcast::channel(512);
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let shipper = tokio::spawn(ship_logs(
+        client.clone(),
+        format!("copy-jobs/{}/logs", job_id),
+        job_id,
+        log_rx,
+        Some(cancelled.clone()),
+    ));
+
+    for img in &work.images {
+        if cancelled.load(Ordering::Relaxed) {
+            emit_log(&log_tx, "Cancel requested, stopping job".to_string());
+            break;
+        }
+        report_image(client, job_id, img.id, &AgentImageReport {
+            status: IMAGE_IN_PROGRESS.to_string(),
+            source_sha256: None,
+            target_sha256: None,
+            error_message: None,
+            bytes_copied: None,
+            estimated_transfer_bytes: None,
+            bytes_reused: None,
+            blobs_mounted: None,
+        })
+        .await;
+        let report = copy_image(skopeo, &work, img, &log_tx).await;
+        report_image(client, job_id, img.id, &report).await;
+    }
+
+    // Zavřením kanálu shipper odešle zbytek logů a skončí
+    drop(log_tx);
+    let _ = shipper.await;
+
+    let request = AgentCompleteRequest {
+        cancelled: cancelled.load(Ordering::Relaxed),
+    };
+    report_completion(client, &format!("copy-jobs/{}/complete", job_id), job_id, &request).await?;
+    info!(%job_id, cancelled = request.cancelled, "Copy job finished");
+    Ok(())
+}
+
+/// Kroky deploy pipeline běžící na agentovi - hlásí je serveru
+struct AgentDeploySteps<'a> {
+    client: &'a AgentClient,
+    job_id: Uuid,
+}
+
+impl AgentDeploySteps<'_> {
+    async fn report(&self, step: &str, status: &str, message: Option<&str>) {
+        let path = format!("deploy-jobs/{}/steps", self.job_id);
+        let body = AgentDeployStep {
+            step: step.to_string(),
+            status: status.to_string(),
+            message: message.map(str::to_string),
+        };
+        if let Err(e) = self.client.post(&path, &body).await {
+            warn!(job_id = %self.job_id, step, error = %format!("{:#}", e), "Failed to report deploy step");
+        }
+    }
+}
+
+impl DeployStepSink for AgentDeploySteps<'_> {
+    async fn start(&self, step: &str) {
+        self.report(step, STEP_IN_PROGRESS, None).await;
+    }
+
+    async fn success(&self, step: &str) {
+        self.report(step, STEP_SUCCESS, None).await;
+    }
+
+    async fn skip(&self, step: &str, reason: &str) {
+        self.report(step, STEP_SKIPPED, Some(reason)).await;
+    }
+
+    async fn warning(&self, step: &str, message: &str) {
+        self.report(step, STEP_WARNING, Some(message)).await;
+    }
+}
+
+/// Deploy pipeline nad vstupy od serveru; výsledky (i po chybě) uloží server
+async fn run_deploy_job(client: &AgentClient, tools: &DeployTools, work: DeployWork) -> Result<()> {
+    let job_id = work.job_id;
+    info!(%job_id, environment = %work.environment_slug, "Running deploy job");
+
+    let (log_tx, log_rx) = broadcast::channel(512);
+    let shipper = tokio::spawn(ship_logs(
+        client.clone(),
+        format!("deploy-jobs/{}/logs", job_id),
+        job_id,
+        log_rx,
+        None,
+    ));
+
+    let steps = AgentDeploySteps { client, job_id };
+    let mut outcome = DeployOutcome::default();
+    let result = deploy::execute_deploy_work(tools, work, &steps, &log_tx, &mut outcome).await;
+
+    drop(log_tx);
+    let _ = shipper.await;
+
+    let request = AgentDeployCompleteRequest {
+        outcome,
+        error: result.err().map(|e| format!("{:#}", e)),
+    };
+    report_completion(client, &format!("deploy-jobs/{}/complete", job_id), job_id, &request).await?;
+    info!(%job_id, failed = request.error.is_some(), "Deploy job finished");
+    Ok(())
+}
+
+/// Nahlásí dokončení jobu; výpadek spojení se serverem zkouší překlenout opakováním
+async fn report_completion<B: Serialize>(client: &AgentClient, path: &str, job_id: Uuid, request: &B) -> Result<()> {
+    let mut attempt = 1;
+    loop {
+        match client.post(path, request).await {
+            Ok(_) => return Ok(()),
+            Err(e) if attempt < COMPLETE_ATTEMPTS => {
+                warn!(%job_id, attempt, error = %format!("{:#}", e), "Failed to report job completion, retrying");
+                attempt += 1;
+                tokio::time::sleep(COMPLETE_RETRY_DELAY).await;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+async fn report_image(client: &AgentClient, job_id: Uuid, image_id: Uuid, report: &AgentImageReport) {
+    let path = format!("copy-jobs/{}/images/{}", job_id, image_id);
+    if let Err(e) = client.post(&path, report).await {
+        warn!(%job_id, %image_id, error = %format!("{:#}", e), "Failed to report image result");
+    }
+}
+
+/// Posílá logy po dávkách na `path`; u copy jobu odpověď serveru nastaví `cancelled`
+async fn ship_logs(
+    client: AgentClient,
+    path: String,
+    job_id: Uuid,
+    mut log_rx: broadcast::Receiver<String>,
+    cancelled: Option<Arc<AtomicBool>>,
+) {
+    let mut buffer: Vec<String> = Vec::new();
+    let mut ticker = tokio::time::interval(LOG_FLUSH_INTERVAL);
+    loop {
+        let closed = tokio::select! {
+            line = log_rx.recv() => match line {
+                Ok(line) => {
+                    buffer.push(line);
+                    if buffer.len() < LOG_BATCH_SIZE {
+                        continue;
+                    }
+                    false
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    buffer.push(format!("... {} log lines dropped by agent", skipped));
+                    continue;
+                }
+                Err(RecvError::Closed) => true,
+            },
+            _ = ticker.tick() => false,
+        };
+
+        let lines: Vec<String> = buffer.drain(..buffer.len().min(LOG_BATCH_SIZE)).collect();
+        match client.post(&path, &AgentLogBatch { lines }).await {
+            Ok(response) => {
+                if let Some(cancelled) = &cancelled
+                    && let Ok(state) = response.json::<AgentJobState>().await
+                    && state.cancelled
+                {
+                    cancelled.store(true, Ordering::Relaxed);
+                }
+            }
+            Err(e) => warn!(%job_id, error = %format!("{:#}", e), "Failed to send job logs"),
+        }
+        if closed && buffer.is_empty() {
+            return;
+        }
+    }
+}
+
+fn emit_log(log_tx: &broadcast::Sender<String>, line: String) {
+    let _ = log_tx.send(line);
+}
+
+fn image_report(
+    status: &str,
+    source_sha256: Option<String>,
+    target_sha256: Option<String>,
+    error_message: Option<String>,
+    bytes_copied: Option<i64>,
+) -> AgentImageReport {
+    AgentImageReport {
+        status: status.to_string(),
+        source_sha256,
+        target_sha256,
+        error_message,
+        bytes_copied,
+        estimated_transfer_bytes: None,
+        bytes_reused: None,
+        blobs_mounted: None,
+    }
+}
+
+/// Zkopíruje jeden obraz stejně jako copy job na serveru (skip při shodě digestu, retag existujícího manifestu)
+async fn copy_image(
+    skopeo: &ImageToolService,
+    work: &AgentCopyWork,
+    img: &AgentCopyImage,
+    log_tx: &broadcast::Sender<String>,
+) -> AgentImageReport {
+    let credentials = SkopeoCredentials {
+        source_username: img.source_username.clone(),
+        source_password: img.source_password.clone(),
+        target_username: img.target_username.clone(),
+        target_password: img.target_password.clone(),
+    };
+    let target_url = format!("{}:{}", img.target_repository, work.target_tag);
+    emit_log(log_tx, format!("Copying {} -> {}", img.source_url, target_url));
+
+    let source_sha = skopeo
+        .inspect_image(
+            &img.source_url,
+            credentials.source_username.as_deref(),
+            credentials.source_password.as_deref(),
+        )
+        .await
+        .ok()
+        .map(|info| info.digest);
+
+    if work.validate_only {
+        if source_sha.is_some() {
+            emit_log(log_tx, format!("VALIDATED {} (source digest ok)", img.source_url));
+            return image_report(IMAGE_SUCCESS, source_sha, None, None, Some(0));
+        }
+        emit_log(log_tx, format!("FAILED {} - Source inspect failed", img.source_url));
+        return image_report(IMAGE_FAILED, None, None, Some("Source inspect failed".to_string()), None);
+    }
+
+    if let Some(src_digest) = source_sha.as_deref() {
+        emit_log(log_tx, format!("Checking whether target tag already exists: {}", target_url));
+        match skopeo
+            .inspect_image(
+                &target_url,
+                credentials.target_username.as_deref(),
+                credentials.target_password.as_deref(),
+            )
+            .await
+        {
+            Ok(info) if info.digest == src_digest && !img.quarantined => {
+                emit_log(log_tx, format!("SKIP {} (digest match)", target_url));
+                let error = tag_extra(skopeo, work, img, &credentials, Some(src_digest), log_tx).await;
+                let status = if error.is_none() { IMAGE_SUCCESS } else { IMAGE_FAILED };
+                return image_report(status, source_sha.clone(), Some(info.digest), error, Some(0));
+            }
+            Ok(info) if !work.allow_overwrite => {
+                let message = format!(
+                    "Target tag already exists with a different digest ({}); set overwrite=true to replace it",
+                    info.digest
+                );
+                emit_log(log_tx, format!("FAILED {} - {}", target_url, message));
+                return image_report(IMAGE_FAILED, source_sha.clone(), Some(info.digest), Some(message), None);
+            }
+            Ok(info) if info.digest == src_digest => {
+                emit_log(log_tx, "Target tag is quarantined after a failed release job - copying again".to_string());
+            }
+            Ok(info) => {
+                emit_log(log_tx, format!("Target tag has a different digest ({}) - overwriting", info.digest));
+            }
+            Err(err) if is_missing_target_manifest_error(&err.to_string()) => {
+                emit_log(log_tx, format!("Target tag does not exist yet: {} - starting copy", target_url));
+            }
+            Err(err) => {
+                emit_log(
+                    log_tx,
+                    format!("WARN failed to inspect target tag {} ({}) - starting copy anyway", target_url, err),
+                );
+            }
+        }
+
+        if skopeo.supports_digest_retag() {
+            let target_digest_url = format!("{}@{}", img.target_repository, src_digest);
+            if let Ok(info) = skopeo
+                .inspect_image(
+                    &target_digest_url,
+                    credentials.target_username.as_deref(),
+                    credentials.target_password.as_deref(),
+                )
+                .await
+            {
+                emit_log(log_tx, format!("Tagging existing manifest: {} -> {}", target_digest_url, target_url));
+                match skopeo.tag_existing_manifest(&target_digest_url, &target_url, &credentials).await {
+                    Ok(()) => {
+                        emit_log(log_tx, format!("TAGGED {}", target_url));
+                        return image_report(IMAGE_SUCCESS, source_sha.clone(), Some(info.digest), None, Some(0));
+                    }
+                    Err(err) => {
+                        emit_log(log_tx, format!("WARN failed to tag existing manifest ({}) - starting full copy", err));
+                    }
+                }
+            }
+        }
+    }
+
+    let reuse = if work.copy_options.blob_reuse && work.copy_options.format.is_none() {
+        let options = ReuseOptions {
+            all: work.copy_options.all,
+            src_insecure: skopeo.src_insecure,
+            dst_insecure: skopeo.dst_insecure,
+        };
+        match blob_reuse::prepare_target(&img.source_url, &target_url, &credentials, &img.mount_from, options).await {
+            Ok(report) => {
+                emit_log(log_tx, format!("Layer reuse: {}", report.summary()));
+                Some(report)
+            }
+            Err(err) => {
+                emit_log(log_tx, format!("WARN layer reuse check failed ({}) - copying all layers", err));
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    match skopeo
+        .copy_image_with_retry(&img.source_url, &target_url, &credentials, &work.copy_options, Some(log_tx))
+        .await
+    {
+        Ok(progress) if progress.status == CopyStatus::Success => {
+            let target_sha = skopeo
+                .inspect_image(
+                    &target_url,
+                    credentials.target_username.as_deref(),
+                    credentials.target_password.as_deref(),
+                )
+                .await
+                .ok()
+                .map(|info| info.digest);
+            emit_log(log_tx, format!("SUCCESS {}", target_url));
+            let error = tag_extra(skopeo, work, img, &credentials, source_sha.as_deref(), log_tx).await;
+            let status = if error.is_none() { IMAGE_SUCCESS } else { IMAGE_FAILED };
+            let mut report = image_report(status, source_sha, target_sha, error, None);
+            if let Some(reuse) = reuse {
+                report.estimated_transfer_bytes = Some(reuse.estimated_transfer_bytes() as i64);
+                report.bytes_reused = Some(reuse.reused_bytes() as i64);
+                report.blobs_mounted = Some(reuse.mounted as i32);
+            }
+            report
+        }
+        Ok(progress) => {
+            emit_log(log_tx, format!("FAILED {} - {}", target_url, progress.message.trim()));
+            image_report(IMAGE_FAILED, source_sha, None, Some(progress.message.trim().to_string()), None)
+        }
+        Err(err) => {
+            emit_log(log_tx, format!("FAILED {} - {}", target_url, err));
+            image_report(IMAGE_FAILED, source_sha, None, Some(err.to_string()), None)
+        }
+    }
+}
+
+/// Doplní extra tagy (retag existujícího manifestu, jinak kopie); vrací chybu posledního neúspěšného tagu
+async fn tag_extra(
+    skopeo: &ImageToolService,
+    work: &AgentCopyWork,
+    img: &AgentCopyImage,
+    credentials: &SkopeoCredentials,
+    source_digest: Option<&str>,
+    log_tx: &broadcast::Sender<String>,
+) -> Option<String> {
+    let mut error = None;
+    for tag in work.extra_tags.iter().filter(|tag| **tag != work.target_tag) {
+        let extra_target_url = format!("{}:{}", img.target_repository, tag);
+        if let Some(digest) = source_digest
+            && skopeo.supports_digest_retag()
+        {
+            let target_digest_url = format!("{}@{}", img.target_repository, digest);
+            emit_log(log_tx, format!("Tagging existing manifest: {} -> {}", target_digest_url, extra_target_url));
+            match skopeo.tag_existing_manifest(&target_digest_url, &extra_target_url, credentials).await {
+                Ok(()) => {
+                    emit_log(log_tx, format!("TAGGED {}", extra_target_url));
+                    continue;
+                }
+                Err(err) => emit_log(
+                    log_tx,
+                    format!("WARN failed to tag existing manifest for extra tag {} ({}) - falling back to copy", tag, err),
+                ),
+            }
+        }
+        emit_log(log_tx, format!("Tagging {} -> {}", img.source_url, extra_target_url));
+        let message = match skopeo
+            .copy_image_with_retry(&img.source_url, &extra_target_url, credentials, &work.copy_options, Some(log_tx))
+            .await
+        {
+            Ok(progress) if progress.status == CopyStatus::Success => {
+                emit_log(log_tx, format!("TAGGED {}", extra_target_url));
+                continue;
+            }
+            Ok(progress) => progress.message.trim().to_string(),
+            Err(err) => err.to_string(),
+        };
+        emit_log(log_tx, format!("FAILED {} - {}", extra_target_url, message));
+        error = Some(format!("Extra tag {} failed: {}", tag, message));
+    }
+    error
+}
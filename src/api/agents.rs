@@ -0,0 +1,936 @@
+use axum::{
+    extract::{DefaultBodyLimit, FromRef, Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post, put},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tokio::sync::broadcast::{self, error::TryRecvError};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::api::copy::{self, CopyApiState, ErrorResponse};
+use crate::api::deploy::{self, DeployApiState};
+use crate::db::models::Agent;
+use crate::services::agent_protocol::{
+    self, AgentCompleteRequest, AgentCopyImage, AgentCopyWork, AgentDeployCompleteRequest, AgentDeployStep,
+    AgentHeartbeat, AgentImageReport, AgentJobState, AgentLogBatch, DeployWork, IMAGE_FAILED, IMAGE_IN_PROGRESS,
+    IMAGE_SUCCESS, STEP_IN_PROGRESS, STEP_SKIPPED, STEP_SUCCESS, STEP_WARNING,
+};
+use crate::services::blob_reuse;
+use crate::services::deploy_steps;
+use crate::services::events::{COPY_JOB_STARTED, DEPLOY_JOB_FINISHED, DEPLOY_JOB_STARTED};
+use crate::services::job_logs::JobKind;
+use crate::services::job_queue;
+use crate::services::lifecycle_notifications;
+
+/// Max. řádků v jednom log batchi od agenta
+const MAX_LOG_LINES_PER_BATCH: usize = 1000;
+/// Rezerva nad `DEPLOY_DIFF_MAX_BYTES` pro zbytek výsledku deploy jobu (inventory, obrazy)
+const DEPLOY_RESULT_OVERHEAD_BYTES: usize = 8 * 1024 * 1024;
+
+/// App state pro API agentů - handlery si berou copy nebo deploy state přes `FromRef`
+#[derive(Clone)]
+pub struct AgentApiState {
+    pub copy: CopyApiState,
+    pub deploy: DeployApiState,
+}
+
+impl FromRef<AgentApiState> for CopyApiState {
+    fn from_ref(state: &AgentApiState) -> Self {
+        state.copy.clone()
+    }
+}
+
+impl FromRef<AgentApiState> for DeployApiState {
+    fn from_ref(state: &AgentApiState) -> Self {
+        state.deploy.clone()
+    }
+}
+
+/// Request pro vytvoření agenta
+#[derive(Debug, Deserialize)]
+pub struct CreateAgentRequest {
+    pub tenant_id: Uuid,
+    pub name: String,
+    /// Prostředí, jejichž copy a deploy joby bude agent spouštět
+    #[serde(default)]
+    pub environment_ids: Vec<Uuid>,
+}
+
+/// Request pro update agenta
+#[derive(Debug, Deserialize)]
+pub struct UpdateAgentRequest {
+    pub name: String,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AgentEnvironmentsRequest {
+    pub environment_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct AgentEnvironment {
+    pub id: Uuid,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AgentResponse {
+    #[serde(flatten)]
+    pub agent: Agent,
+    pub environments: Vec<AgentEnvironment>,
+}
+
+/// Odpověď s tokenem - token se vrací jen při vytvoření a rotaci
+#[derive(Debug, Serialize)]
+pub struct AgentTokenResponse {
+    #[serde(flatten)]
+    pub agent: AgentResponse,
+    pub token: String,
+}
+
+/// Vytvoří router pro agenty.
+/// Správa agentů (`/agents`) je jen pro adminy; `/agent/*` volá agent se svým bearer tokenem
+/// a auth vrstva ho propouští bez hlaviček z proxy.
+pub fn router(state: AgentApiState) -> Router {
+    // Výsledek deploy jobu nese celý (už zkrácený) diff
+    let deploy_result_limit = state.deploy.diff_limits.max_bytes + DEPLOY_RESULT_OVERHEAD_BYTES;
+    Router::new()
+        .route("/tenants/{tenant_id}/agents", get(list_agents))
+        .route("/agents", post(create_agent))
+        .route("/agents/{id}", get(get_agent).put(update_agent).delete(delete_agent))
+        .route("/agents/{id}/rotate-token", post(rotate_agent_token))
+        .route("/agents/{id}/environments", put(set_agent_environments))
+        .route("/agent/heartbeat", post(agent_heartbeat))
+        .route("/agent/claim", post(agent_claim))
+        .route("/agent/copy-jobs/{job_id}/logs", post(agent_logs))
+        .route("/agent/copy-jobs/{job_id}/images/{image_id}", post(agent_image_report))
+        .route("/agent/copy-jobs/{job_id}/complete", post(agent_complete))
+        .route("/agent/deploy-jobs/claim", post(agent_deploy_claim))
+        .route("/agent/deploy-jobs/{job_id}/logs", post(agent_deploy_logs))
+        .route("/agent/deploy-jobs/{job_id}/steps", post(agent_deploy_step))
+        .route(
+            "/agent/deploy-jobs/{job_id}/complete",
+            post(agent_deploy_complete).layer(DefaultBodyLimit::max(deploy_result_limit)),
+        )
+        .with_state(state)
+}
+
+fn db_error(e: sqlx::Error) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: format!("Database error: {}", e),
+        }),
+    )
+}
+
+fn bad_request(error: String) -> (StatusCode, Json<ErrorResponse>) {
+    (StatusCode::BAD_REQUEST, Json(ErrorResponse { error }))
+}
+
+fn not_found(id: Uuid) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::NOT_FOUND,
+        Json(ErrorResponse {
+            error: format!("Agent with id {} not found", id),
+        }),
+    )
+}
+
+fn unauthorized() -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(ErrorResponse {
+            error: "Invalid or missing agent token".to_string(),
+        }),
+    )
+}
+
+fn conflict_or_db_error(name: &str) -> impl Fn(sqlx::Error) -> (StatusCode, Json<ErrorResponse>) + '_ {
+    move |e| match &e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: format!("Agent '{}' already exists", name),
+            }),
+        ),
+        _ => db_error(e),
+    }
+}
+
+fn validate_name(name: &str) -> Result<&str, (StatusCode, Json<ErrorResponse>)> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(bad_request("Agent name cannot be empty".to_string()));
+    }
+    Ok(name)
+}
+
+async fn load_agent(state: &CopyApiState, id: Uuid) -> Result<Agent, (StatusCode, Json<ErrorResponse>)> {
+    sqlx::query_as::<_, Agent>("SELECT * FROM agents WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| not_found(id))
+}
+
+async fn with_environments(
+    state: &CopyApiState,
+    agent: Agent,
+) -> Result<AgentResponse, (StatusCode, Json<ErrorResponse>)> {
+    let environments = sqlx::query_as::<_, AgentEnvironment>(
+        "SELECT id, name FROM environments WHERE agent_id = $1 ORDER BY name",
+    )
+    .bind(agent.id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(db_error)?;
+    Ok(AgentResponse { agent, environments })
+}
+
+/// Přiřadí agentovi právě tato prostředí (prostředí musí patřit tenantovi agenta)
+async fn assign_environments(
+    state: &CopyApiState,
+    agent: &Agent,
+    environment_ids: &[Uuid],
+) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    let known = sqlx::query_scalar::<_, Uuid>("SELECT id FROM environments WHERE tenant_id = $1 AND id = ANY($2)")
+        .bind(agent.tenant_id)
+        .bind(environment_ids)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(db_error)?;
+    if let Some(missing) = environment_ids.iter().find(|id| !known.contains(id)) {
+        return Err(bad_request(format!(
+            "Environment {} does not exist in the agent's tenant",
+            missing
+        )));
+    }
+
+    let mut tx = state.pool.begin().await.map_err(db_error)?;
+    sqlx::query("UPDATE environments SET agent_id = NULL WHERE agent_id = $1 AND NOT (id = ANY($2))")
+        .bind(agent.id)
+        .bind(environment_ids)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+    sqlx::query("UPDATE environments SET agent_id = $1 WHERE id = ANY($2)")
+        .bind(agent.id)
+        .bind(environment_ids)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+    tx.commit().await.map_err(db_error)?;
+    Ok(())
+}
+
+/// GET /api/v1/tenants/{tenant_id}/agents - Seznam agentů tenanta
+async fn list_agents(
+    State(state): State<CopyApiState>,
+    Path(tenant_id): Path<Uuid>,
+) -> Result<Json<Vec<AgentResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let agents = sqlx::query_as::<_, Agent>("SELECT * FROM agents WHERE tenant_id = $1 ORDER BY name")
+        .bind(tenant_id)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(db_error)?;
+
+    let mut result = Vec::with_capacity(agents.len());
+    for agent in agents {
+        result.push(with_environments(&state, agent).await?);
+    }
+    Ok(Json(result))
+}
+
+/// GET /api/v1/agents/{id} - Detail agenta
+async fn get_agent(
+    State(state): State<CopyApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<AgentResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let agent = load_agent(&state, id).await?;
+    Ok(Json(with_environments(&state, agent).await?))
+}
+
+/// POST /api/v1/agents - Registrace agenta; vrací token, který se už nedá znovu zobrazit
+async fn create_agent(
+    State(state): State<CopyApiState>,
+    Json(payload): Json<CreateAgentRequest>,
+) -> Result<(StatusCode, Json<AgentTokenResponse>), (StatusCode, Json<ErrorResponse>)> {
+    let name = validate_name(&payload.name)?;
+    let tenant_exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM tenants WHERE id = $1)")
+        .bind(payload.tenant_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(db_error)?;
+    if !tenant_exists {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Tenant with id {} not found", payload.tenant_id),
+            }),
+        ));
+    }
+
+    let token = agent_protocol::generate_token();
+    let agent = sqlx::query_as::<_, Agent>(
+        "INSERT INTO agents (tenant_id, name, token_hash) VALUES ($1, $2, $3) RETURNING *",
+    )
+    .bind(payload.tenant_id)
+    .bind(name)
+    .bind(agent_protocol::hash_token(&token))
+    .fetch_one(&state.pool)
+    .await
+    .map_err(conflict_or_db_error(name))?;
+
+    if !payload.environment_ids.is_empty()
+        && let Err(e) = assign_environments(&state, &agent, &payload.environment_ids).await
+    {
+        let _ = sqlx::query("DELETE FROM agents WHERE id = $1").bind(agent.id).execute(&state.pool).await;
+        return Err(e);
+    }
+
+    info!(agent = %agent.name, tenant_id = %agent.tenant_id, "Agent registered");
+    Ok((
+        StatusCode::CREATED,
+        Json(AgentTokenResponse {
+            agent: with_environments(&state, agent).await?,
+            token,
+        }),
+    ))
+}
+
+/// PUT /api/v1/agents/{id} - Přejmenování / (de)aktivace agenta
+async fn update_agent(
+    State(state): State<CopyApiState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateAgentRequest>,
+) -> Result<Json<AgentResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let name = validate_name(&payload.name)?;
+    let agent = sqlx::query_as::<_, Agent>(
+        "UPDATE agents SET name = $1, is_active = COALESCE($2, is_active), updated_at = NOW()
+         WHERE id = $3
+         RETURNING *",
+    )
+    .bind(name)
+    .bind(payload.is_active)
+    .bind(id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(conflict_or_db_error(name))?
+    .ok_or_else(|| not_found(id))?;
+
+    Ok(Json(with_environments(&state, agent).await?))
+}
+
+/// DELETE /api/v1/agents/{id} - Smazání agenta; jeho prostředí se vrací workerům
+async fn delete_agent(
+    State(state): State<CopyApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    let result = sqlx::query("DELETE FROM agents WHERE id = $1")
+        .bind(id)
+        .execute(&state.pool)
+        .await
+        .map_err(db_error)?;
+
+    if result.rows_affected() == 0 {
+        return Err(not_found(id));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/v1/agents/{id}/rotate-token - Nový token; starý přestává platit okamžitě
+async fn rotate_agent_token(
+    State(state): State<CopyApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<AgentTokenResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let token = agent_protocol::generate_token();
+    let agent = sqlx::query_as::<_, Agent>(
+        "UPDATE agents SET token_hash = $1, updated_at = NOW() WHERE id = $2 RETURNING *",
+    )
+    .bind(agent_protocol::hash_token(&token))
+    .bind(id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(db_error)?
+    .ok_or_else(|| not_found(id))?;
+
+    Ok(Json(AgentTokenResponse {
+        agent: with_environments(&state, agent).await?,
+        token,
+    }))
+}
+
+/// PUT /api/v1/agents/{id}/environments - Prostředí, jejichž copy a deploy joby spouští agent.
+/// Už zařazené joby (queued) převezme nový vlastník prostředí.
+async fn set_agent_environments(
+    State(state): State<CopyApiState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<AgentEnvironmentsRequest>,
+) -> Result<Json<AgentResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let agent = load_agent(&state, id).await?;
+    assign_environments(&state, &agent, &payload.environment_ids).await?;
+    Ok(Json(with_environments(&state, agent).await?))
+}
+
+/// Agent podle bearer tokenu; zároveň aktualizuje `last_seen_at`
+async fn authenticate(pool: &PgPool, headers: &HeaderMap) -> Result<Agent, (StatusCode, Json<ErrorResponse>)> {
+    let header = headers.get(header::AUTHORIZATION).and_then(|v| v.to_str().ok());
+    let token = agent_protocol::bearer_token(header).ok_or_else(unauthorized)?;
+    sqlx::query_as::<_, Agent>(
+        "UPDATE agents SET last_seen_at = NOW()
+         WHERE token_hash = $1 AND is_active
+         RETURNING *",
+    )
+    .bind(agent_protocol::hash_token(token))
+    .fetch_optional(pool)
+    .await
+    .map_err(db_error)?
+    .ok_or_else(unauthorized)
+}
+
+/// Stav copy / deploy jobu převzatého tímto agentem
+async fn owned_job_status(
+    pool: &PgPool,
+    kind: JobKind,
+    agent: &Agent,
+    job_id: Uuid,
+) -> Result<String, (StatusCode, Json<ErrorResponse>)> {
+    let (sql, label) = match kind {
+        JobKind::Copy => ("SELECT status FROM copy_jobs WHERE id = $1 AND claimed_by = $2", "Copy"),
+        JobKind::Deploy => ("SELECT status FROM deploy_jobs WHERE id = $1 AND claimed_by = $2", "Deploy"),
+    };
+    sqlx::query_scalar::<_, String>(sql)
+        .bind(job_id)
+        .bind(agent_protocol::claimed_by(agent.id))
+        .fetch_optional(pool)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: format!("{} job {} is not claimed by this agent", label, job_id),
+                }),
+            )
+        })
+}
+
+async fn persist_lines(state: &CopyApiState, job_id: Uuid, lines: &[String]) {
+    for line in lines {
+        copy::persist_copy_log_line(&state.pool, &state.log_fanout, job_id, line).await;
+    }
+}
+
+/// POST /api/v1/agent/heartbeat - Agent hlásí verzi a hostname
+async fn agent_heartbeat(
+    State(state): State<CopyApiState>,
+    headers: HeaderMap,
+    Json(payload): Json<AgentHeartbeat>,
+) -> Result<Json<AgentResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let agent = authenticate(&state.pool, &headers).await?;
+    let agent = sqlx::query_as::<_, Agent>(
+        "UPDATE agents SET version = $1, hostname = $2 WHERE id = $3 RETURNING *",
+    )
+    .bind(payload.version.trim())
+    .bind(payload.hostname.as_deref().map(str::trim).filter(|h| !h.is_empty()))
+    .bind(agent.id)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(db_error)?;
+    Ok(Json(with_environments(&state, agent).await?))
+}
+
+/// Úklid release tagů po agentovi: registry nemusí být ze serveru dostupná, tagy jdou jen do karantény
+async fn cleanup_release_tags(state: &CopyApiState, job_id: Uuid) {
+    let options = match copy::effective_copy_options(&state.pool, &state.skopeo.copy_defaults, job_id).await {
+        Ok(options) => options,
+        Err(e) => {
+            warn!(%job_id, error = %e, "Failed to load copy options for release tag cleanup");
+            return;
+        }
+    };
+    let lines = copy::cleanup_release_tags(&state.pool, job_id, options.release_tag_cleanup, None).await;
+    if !lines.is_empty() {
+        persist_lines(state, job_id, &lines).await;
+    }
+}
+
+/// Agent zpracovává joby postupně - běžící job při novém claim znamená restart agenta uprostřed jobu
+async fn fail_interrupted_jobs(state: &CopyApiState, agent: &Agent) -> Result<(), sqlx::Error> {
+    let interrupted = sqlx::query_scalar::<_, Uuid>(
+        "SELECT id FROM copy_jobs WHERE claimed_by = $1 AND status = 'in_progress'",
+    )
+    .bind(agent_protocol::claimed_by(agent.id))
+    .fetch_all(&state.pool)
+    .await?;
+
+    for job_id in interrupted {
+        warn!(%job_id, agent = %agent.name, "Agent restarted during copy job, marking it failed");
+        persist_lines(state, job_id, &["Agent restarted while the job was running".to_string()]).await;
+        sqlx::query(
+            "UPDATE copy_job_images
+             SET copy_status = 'failed', error_message = 'Interrupted (agent restarted)'
+             WHERE copy_job_id = $1 AND copy_status IN ('pending', 'in_progress')",
+        )
+        .bind(job_id)
+        .execute(&state.pool)
+        .await?;
+        cleanup_release_tags(state, job_id).await;
+        copy::finish_copy_job(&state.pool, &state.notifier, job_id, 1, false).await;
+        let _ = state.log_fanout.end_log(&state.pool, JobKind::Copy, job_id).await;
+    }
+    Ok(())
+}
+
+/// Deploy joby agenta přerušené jeho restartem (viz `fail_interrupted_jobs`)
+async fn fail_interrupted_deploy_jobs(state: &DeployApiState, agent: &Agent) -> Result<(), sqlx::Error> {
+    let interrupted = sqlx::query_scalar::<_, Uuid>(
+        "SELECT id FROM deploy_jobs WHERE claimed_by = $1 AND status = 'in_progress'",
+    )
+    .bind(agent_protocol::claimed_by(agent.id))
+    .fetch_all(&state.pool)
+    .await?;
+
+    for job_id in interrupted {
+        warn!(%job_id, agent = %agent.name, "Agent restarted during deploy job, marking it failed");
+        deploy::fail_interrupted_deploy_job(state, job_id, "Interrupted (agent restarted)").await;
+    }
+    Ok(())
+}
+
+async fn persist_deploy_lines(state: &DeployApiState, job_id: Uuid, lines: &[String]) {
+    for line in lines {
+        let _ = state.log_fanout.persist_line(&state.pool, JobKind::Deploy, job_id, line).await;
+    }
+}
+
+/// Uloží řádky, které serverová část deploy jobu zapsala do dočasného log kanálu
+async fn persist_buffered_deploy_log(state: &DeployApiState, job_id: Uuid, log_rx: &mut broadcast::Receiver<String>) {
+    loop {
+        match log_rx.try_recv() {
+            Ok(line) => persist_deploy_lines(state, job_id, &[line]).await,
+            Err(TryRecvError::Lagged(skipped)) => {
+                persist_deploy_lines(state, job_id, &[format!("... {} log lines dropped", skipped)]).await
+            }
+            Err(_) => break,
+        }
+    }
+}
+
+/// Označí převzatý deploy job jako běžící a sestaví pro agenta vstupy pipeline
+async fn prepare_agent_deploy_work(state: &DeployApiState, agent: &Agent, job_id: Uuid) -> anyhow::Result<DeployWork> {
+    sqlx::query("UPDATE deploy_jobs SET status = 'in_progress', started_at = NOW() WHERE id = $1")
+        .bind(job_id)
+        .execute(&state.pool)
+        .await?;
+    lifecycle_notifications::deploy_job(&state.pool, &state.notifier, &DEPLOY_JOB_STARTED, job_id).await;
+    deploy_steps::init(&state.pool, job_id).await;
+    deploy_steps::start(&state.pool, job_id, "prepare").await;
+    persist_deploy_lines(
+        state,
+        job_id,
+        &[format!("Starting deploy job {} on agent {}", job_id, agent.name)],
+    )
+    .await;
+
+    let (log_tx, mut log_rx) = broadcast::channel(1024);
+    let work = deploy::prepare_deploy_work(state, job_id, &log_tx).await;
+    persist_buffered_deploy_log(state, job_id, &mut log_rx).await;
+    work
+}
+
+/// Uzavře deploy job agenta stejně jako `launch_deploy_job` na workeru.
+/// `Ok` nese commit SHA a tag úspěšného deploye.
+async fn finish_agent_deploy_job(
+    state: &DeployApiState,
+    job_id: Uuid,
+    result: Result<(Option<String>, Option<String>), String>,
+) {
+    match result {
+        Ok((commit_sha, tag_name)) => {
+            let _ = sqlx::query(
+                "UPDATE deploy_jobs SET status = 'success', completed_at = NOW(), commit_sha = $1, tag_name = $2
+                 WHERE id = $3 AND status = 'in_progress'",
+            )
+            .bind(&commit_sha)
+            .bind(&tag_name)
+            .bind(job_id)
+            .execute(&state.pool)
+            .await;
+            persist_deploy_lines(state, job_id, &["Deploy job completed successfully".to_string()]).await;
+        }
+        Err(error) => {
+            persist_deploy_lines(state, job_id, &[format!("Deploy job failed: {}", error)]).await;
+            deploy_steps::fail(&state.pool, job_id, &anyhow::anyhow!(error.clone())).await;
+            let _ = sqlx::query(
+                "UPDATE deploy_jobs SET status = 'failed', completed_at = NOW(), error_message = $1
+                 WHERE id = $2 AND status = 'in_progress'",
+            )
+            .bind(&error)
+            .bind(job_id)
+            .execute(&state.pool)
+            .await;
+        }
+    }
+    lifecycle_notifications::deploy_job(&state.pool, &state.notifier, &DEPLOY_JOB_FINISHED, job_id).await;
+    let _ = state.log_fanout.end_log(&state.pool, JobKind::Deploy, job_id).await;
+}
+
+/// Připraví převzatý job pro agenta a označí ho jako běžící.
+/// Obrazy, u kterých nejde sestavit zdroj, se rovnou označí jako failed.
+async fn prepare_agent_work(
+    state: &CopyApiState,
+    agent: &Agent,
+    job_id: Uuid,
+) -> Result<AgentCopyWork, (StatusCode, Json<ErrorResponse>)> {
+    let prepared = copy::prepare_copy_job(state, job_id).await?;
+
+    let _ = sqlx::query(
+        "UPDATE copy_jobs
+         SET status = 'in_progress',
+             current_transfer_stage = NULL,
+             current_transfer_message = NULL,
+             current_bytes_copied = NULL,
+             current_total_bytes = NULL
+         WHERE id = $1",
+    )
+    .bind(job_id)
+    .execute(&state.pool)
+    .await;
+    lifecycle_notifications::copy_job(&state.pool, &state.notifier, &COPY_JOB_STARTED, job_id).await;
+    persist_lines(
+        state,
+        job_id,
+        &[
+            format!(
+                "Starting copy job {} ({} images) on agent {}",
+                job_id,
+                prepared.images.len(),
+                agent.name
+            ),
+            format!("Copy options: {}", prepared.copy_options.summary()),
+        ],
+    )
+    .await;
+
+    let mut images = Vec::with_capacity(prepared.images.len());
+    for img in &prepared.images {
+        let registry_id = img.source_registry_id.unwrap_or(prepared.source_registry_id);
+        let source = prepared
+            .source_registry_info
+            .get(&registry_id)
+            .ok_or_else(|| format!("Missing source registry {}", registry_id))
+            .and_then(|(base, username, password)| {
+                copy::build_source_url(base, img, &prepared.source_ref_mode)
+                    .map(|url| (url, username.clone(), password.clone()))
+            });
+        let mount_from = if prepared.copy_options.blob_reuse {
+            blob_reuse::mount_sources(&state.pool, prepared.target_registry_id, &img.target_image)
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .map(|image| format!("{}/{}", prepared.target_base_url, image))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        match source {
+            Ok((source_url, source_username, source_password)) => images.push(AgentCopyImage {
+                id: img.id,
+                source_url,
+                target_repository: format!("{}/{}", prepared.target_base_url, img.target_image),
+                source_username,
+                source_password,
+                target_username: prepared.target_username.clone(),
+                target_password: prepared.target_password.clone(),
+                quarantined: prepared.quarantined_images.contains(&img.target_image),
+                mount_from,
+            }),
+            Err(err) => {
+                persist_lines(state, job_id, &[format!("FAILED {} - {}", img.source_image, err)]).await;
+                let _ = sqlx::query(
+                    "UPDATE copy_job_images SET copy_status = 'failed', error_message = $1 WHERE id = $2",
+                )
+                .bind(err)
+                .bind(img.id)
+                .execute(&state.pool)
+                .await;
+            }
+        }
+    }
+
+    Ok(AgentCopyWork {
+        job_id,
+        target_tag: prepared.target_tag,
+        extra_tags: prepared.extra_tags,
+        validate_only: prepared.validate_only,
+        allow_overwrite: prepared.overwrite_target_tag || prepared.is_release_job,
+        copy_options: prepared.copy_options,
+        images,
+    })
+}
+
+/// POST /api/v1/agent/claim - Převezme další copy job prostředí agenta (204 = není práce)
+async fn agent_claim(
+    State(state): State<CopyApiState>,
+    State(deploy_state): State<DeployApiState>,
+    headers: HeaderMap,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let agent = authenticate(&state.pool, &headers).await?;
+    fail_interrupted_jobs(&state, &agent).await.map_err(db_error)?;
+    fail_interrupted_deploy_jobs(&deploy_state, &agent).await.map_err(db_error)?;
+
+    while let Some(job_id) = job_queue::claim_next_for_agent(&state.pool, JobKind::Copy, agent.id)
+        .await
+        .map_err(db_error)?
+    {
+        match prepare_agent_work(&state, &agent, job_id).await {
+            Ok(work) => {
+                info!(%job_id, agent = %agent.name, "Agent claimed copy job");
+                return Ok(Json(work).into_response());
+            }
+            Err((_, Json(e))) => {
+                warn!(%job_id, agent = %agent.name, error = %e.error, "Copy job failed to start");
+                job_queue::fail_unstarted(&state.pool, JobKind::Copy, job_id, &e.error)
+                    .await
+                    .map_err(db_error)?;
+            }
+        }
+    }
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// POST /api/v1/agent/copy-jobs/{job_id}/logs - Log řádky z agenta; odpověď říká, zda byl job zrušen
+async fn agent_logs(
+    State(state): State<CopyApiState>,
+    Path(job_id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(payload): Json<AgentLogBatch>,
+) -> Result<Json<AgentJobState>, (StatusCode, Json<ErrorResponse>)> {
+    let agent = authenticate(&state.pool, &headers).await?;
+    let status = owned_job_status(&state.pool, JobKind::Copy, &agent, job_id).await?;
+    if payload.lines.len() > MAX_LOG_LINES_PER_BATCH {
+        return Err(bad_request(format!(
+            "Too many log lines in one batch (max {})",
+            MAX_LOG_LINES_PER_BATCH
+        )));
+    }
+    persist_lines(&state, job_id, &payload.lines).await;
+    Ok(Json(AgentJobState {
+        cancelled: status == "cancelled",
+    }))
+}
+
+/// POST /api/v1/agent/copy-jobs/{job_id}/images/{image_id} - Výsledek kopírování jednoho obrazu
+async fn agent_image_report(
+    State(state): State<CopyApiState>,
+    Path((job_id, image_id)): Path<(Uuid, Uuid)>,
+    headers: HeaderMap,
+    Json(payload): Json<AgentImageReport>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    let agent = authenticate(&state.pool, &headers).await?;
+    let status = owned_job_status(&state.pool, JobKind::Copy, &agent, job_id).await?;
+    if ![IMAGE_IN_PROGRESS, IMAGE_SUCCESS, IMAGE_FAILED].contains(&payload.status.as_str()) {
+        return Err(bad_request(format!("Invalid image status '{}'", payload.status)));
+    }
+    // Zrušený / uzavřený job už stav obrazů nemění
+    if status != "in_progress" {
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
+    let result = sqlx::query(
+        "UPDATE copy_job_images
+         SET copy_status = $3,
+             source_sha256 = COALESCE($4, source_sha256),
+             target_sha256 = COALESCE($5, target_sha256),
+             error_message = $6,
+             bytes_copied = COALESCE($7, bytes_copied),
+             bytes_reused = COALESCE($8, bytes_reused),
+             blobs_mounted = COALESCE($9, blobs_mounted),
+             estimated_transfer_bytes = COALESCE($10, estimated_transfer_bytes),
+             started_at = CASE WHEN $3 = 'in_progress' THEN NOW() ELSE started_at END,
+             copied_at = CASE WHEN $3 = 'success' THEN NOW() ELSE copied_at END
+         WHERE id = $1 AND copy_job_id = $2",
+    )
+    .bind(image_id)
+    .bind(job_id)
+    .bind(&payload.status)
+    .bind(&payload.source_sha256)
+    .bind(&payload.target_sha256)
+    .bind(&payload.error_message)
+    .bind(payload.bytes_copied)
+    .bind(payload.bytes_reused)
+    .bind(payload.blobs_mounted)
+    .bind(payload.estimated_transfer_bytes)
+    .execute(&state.pool)
+    .await
+    .map_err(db_error)?;
+
+    if result.rows_affected() == 0 {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Image {} not found in copy job {}", image_id, job_id),
+            }),
+        ));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/v1/agent/copy-jobs/{job_id}/complete - Agent dokončil job; obrazy bez výsledku jsou failed
+async fn agent_complete(
+    State(state): State<CopyApiState>,
+    Path(job_id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(payload): Json<AgentCompleteRequest>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    let agent = authenticate(&state.pool, &headers).await?;
+    let status = owned_job_status(&state.pool, JobKind::Copy, &agent, job_id).await?;
+    if status == "success" || status == "failed" {
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
+    let cancelled = payload.cancelled || status == "cancelled";
+    if !cancelled {
+        sqlx::query(
+            "UPDATE copy_job_images
+             SET copy_status = 'failed', error_message = 'No result reported by agent'
+             WHERE copy_job_id = $1 AND copy_status IN ('pending', 'in_progress')",
+        )
+        .bind(job_id)
+        .execute(&state.pool)
+        .await
+        .map_err(db_error)?;
+    }
+    let failed = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM copy_job_images WHERE copy_job_id = $1 AND copy_status = 'failed'",
+    )
+    .bind(job_id)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(db_error)?;
+
+    if cancelled || failed > 0 {
+        cleanup_release_tags(&state, job_id).await;
+    }
+    copy::finish_copy_job(&state.pool, &state.notifier, job_id, failed as usize, cancelled).await;
+    persist_lines(&state, job_id, &["Copy job finished".to_string()]).await;
+    let _ = state.log_fanout.end_log(&state.pool, JobKind::Copy, job_id).await;
+    info!(%job_id, agent = %agent.name, failed, cancelled, "Agent finished copy job");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/v1/agent/deploy-jobs/claim - Převezme další deploy job prostředí agenta (204 = není práce)
+async fn agent_deploy_claim(
+    State(state): State<DeployApiState>,
+    State(copy_state): State<CopyApiState>,
+    headers: HeaderMap,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let agent = authenticate(&state.pool, &headers).await?;
+    fail_interrupted_jobs(&copy_state, &agent).await.map_err(db_error)?;
+    fail_interrupted_deploy_jobs(&state, &agent).await.map_err(db_error)?;
+
+    while let Some(job_id) = job_queue::claim_next_for_agent(&state.pool, JobKind::Deploy, agent.id)
+        .await
+        .map_err(db_error)?
+    {
+        match prepare_agent_deploy_work(&state, &agent, job_id).await {
+            Ok(work) => {
+                info!(%job_id, agent = %agent.name, "Agent claimed deploy job");
+                return Ok(Json(work).into_response());
+            }
+            Err(e) => {
+                warn!(%job_id, agent = %agent.name, error = %e, "Deploy job failed to start");
+                finish_agent_deploy_job(&state, job_id, Err(e.to_string())).await;
+            }
+        }
+    }
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// POST /api/v1/agent/deploy-jobs/{job_id}/logs - Log řádky deploy jobu z agenta
+async fn agent_deploy_logs(
+    State(state): State<DeployApiState>,
+    Path(job_id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(payload): Json<AgentLogBatch>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    let agent = authenticate(&state.pool, &headers).await?;
+    owned_job_status(&state.pool, JobKind::Deploy, &agent, job_id).await?;
+    if payload.lines.len() > MAX_LOG_LINES_PER_BATCH {
+        return Err(bad_request(format!(
+            "Too many log lines in one batch (max {})",
+            MAX_LOG_LINES_PER_BATCH
+        )));
+    }
+    persist_deploy_lines(&state, job_id, &payload.lines).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/v1/agent/deploy-jobs/{job_id}/steps - Změna stavu kroku deploy pipeline na agentovi
+async fn agent_deploy_step(
+    State(state): State<DeployApiState>,
+    Path(job_id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(payload): Json<AgentDeployStep>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    let agent = authenticate(&state.pool, &headers).await?;
+    let status = owned_job_status(&state.pool, JobKind::Deploy, &agent, job_id).await?;
+    if !deploy_steps::DEPLOY_STEPS.contains(&payload.step.as_str()) {
+        return Err(bad_request(format!("Unknown deploy step '{}'", payload.step)));
+    }
+    if ![STEP_IN_PROGRESS, STEP_SUCCESS, STEP_SKIPPED, STEP_WARNING].contains(&payload.status.as_str()) {
+        return Err(bad_request(format!("Invalid step status '{}'", payload.status)));
+    }
+    // Uzavřený job už kroky nemění
+    if status != "in_progress" {
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
+    let message = payload.message.as_deref().unwrap_or_default();
+    match payload.status.as_str() {
+        STEP_IN_PROGRESS => deploy_steps::start(&state.pool, job_id, &payload.step).await,
+        STEP_SUCCESS => deploy_steps::success(&state.pool, job_id, &payload.step).await,
+        STEP_SKIPPED => deploy_steps::skip(&state.pool, job_id, &payload.step, message).await,
+        _ => deploy_steps::warning(&state.pool, job_id, &payload.step, message).await,
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/v1/agent/deploy-jobs/{job_id}/complete - Agent dokončil deploy job; server uloží jeho výsledky
+async fn agent_deploy_complete(
+    State(state): State<DeployApiState>,
+    Path(job_id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(payload): Json<AgentDeployCompleteRequest>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    let agent = authenticate(&state.pool, &headers).await?;
+    let status = owned_job_status(&state.pool, JobKind::Deploy, &agent, job_id).await?;
+    if status != "in_progress" {
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
+    let commit_sha = payload.outcome.commit_sha.clone();
+    let tag_name = payload.outcome.tag_name.clone();
+    let (log_tx, mut log_rx) = broadcast::channel(1024);
+    let stored = deploy::store_deploy_outcome(&state, job_id, payload.outcome, &log_tx).await;
+    persist_buffered_deploy_log(&state, job_id, &mut log_rx).await;
+
+    let result = match (payload.error, stored) {
+        (Some(error), _) => Err(error),
+        (None, Err(e)) => Err(e.to_string()),
+        (None, Ok(())) => Ok((commit_sha, tag_name)),
+    };
+    let failed = result.is_err();
+    finish_agent_deploy_job(&state, job_id, result).await;
+    info!(%job_id, agent = %agent.name, failed, "Agent finished deploy job");
+    Ok(StatusCode::NO_CONTENT)
+}
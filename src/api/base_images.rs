@@ -0,0 +1,294 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::time::Duration;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::api::copy::{CopyApiState, ErrorResponse};
+use crate::auth::AuthContext;
+use crate::services::base_image_updates::{digest_changed, is_moving_tag};
+
+#[derive(Debug, Deserialize)]
+pub struct BaseImageUpdatesQuery {
+    pub tenant_id: Option<Uuid>,
+    pub bundle_id: Option<Uuid>,
+}
+
+/// Mapping, jehož upstream digest se od poslední kopie změnil
+#[derive(Debug, Serialize, FromRow)]
+pub struct BaseImageUpdate {
+    pub image_mapping_id: Uuid,
+    pub tenant_id: Uuid,
+    pub bundle_id: Uuid,
+    pub bundle_name: String,
+    pub bundle_version: i32,
+    pub app_name: String,
+    pub container_name: Option<String>,
+    pub source_ref: String,
+    pub copied_digest: String,
+    pub upstream_digest: Option<String>,
+    pub last_copied_at: Option<DateTime<Utc>>,
+    pub checked_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AffectedBundle {
+    pub bundle_id: Uuid,
+    pub bundle_name: String,
+    pub bundle_version: i32,
+    pub images: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BaseImageUpdatesResponse {
+    pub updates: Vec<BaseImageUpdate>,
+    pub bundles: Vec<AffectedBundle>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BaseImageCheckResponse {
+    pub checked: usize,
+    pub updates: usize,
+    pub errors: usize,
+}
+
+/// Kandidát na kontrolu: poslední úspěšná kopie mappingu z aktuální verze bundle
+#[derive(Debug, FromRow)]
+struct CheckCandidate {
+    image_mapping_id: Uuid,
+    copy_job_image_id: Uuid,
+    source_image: String,
+    source_tag: String,
+    source_registry_id: Uuid,
+    source_sha256: String,
+    environment_id: Option<Uuid>,
+    base_url: String,
+    last_copied_at: Option<DateTime<Utc>>,
+}
+
+/// Vytvoří router pro base image update endpoints
+pub fn router(state: CopyApiState) -> Router {
+    Router::new()
+        .route("/base-image-updates", get(list_base_image_updates))
+        .route("/base-image-updates/check", post(trigger_base_image_check))
+        .with_state(state)
+}
+
+/// Spustí periodickou kontrolu upstream digestů (interval 0 = vypnuto)
+pub fn spawn_checker(state: CopyApiState, interval: Duration) {
+    if interval.is_zero() {
+        info!("Base image update check disabled");
+        return;
+    }
+
+    info!("Base image update check every {}s", interval.as_secs());
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            match run_check(&state, Some(interval)).await {
+                Ok(result) if result.updates > 0 => {
+                    info!(checked = result.checked, updates = result.updates, "Base image updates detected");
+                }
+                Ok(_) => {}
+                Err(e) => warn!(error = %e, "Base image update check failed"),
+            }
+        }
+    });
+}
+
+/// Projde mappings na plovoucích tazích a porovná upstream digest s naposledy zkopírovaným.
+/// S `min_age` přeskočí mappings zkontrolované v posledním intervalu (víc procesů se stejným plánem).
+pub async fn run_check(state: &CopyApiState, min_age: Option<Duration>) -> Result<BaseImageCheckResponse, sqlx::Error> {
+    let min_age_seconds = min_age.map(|d| d.as_secs_f64()).unwrap_or(0.0);
+    let candidates = sqlx::query_as::<_, CheckCandidate>(
+        r#"
+        SELECT DISTINCT ON (im.id)
+            im.id AS image_mapping_id,
+            cji.id AS copy_job_image_id,
+            cji.source_image,
+            cji.source_tag,
+            cji.source_registry_id,
+            cji.source_sha256,
+            cj.environment_id,
+            r.base_url,
+            COALESCE(cji.copied_at, cj.completed_at) AS last_copied_at
+        FROM bundles b
+        JOIN bundle_versions bv ON bv.bundle_id = b.id AND bv.version = b.current_version
+        JOIN image_mappings im ON im.bundle_version_id = bv.id
+        JOIN copy_job_images cji ON cji.image_mapping_id = im.id
+        JOIN copy_jobs cj ON cj.id = cji.copy_job_id
+        JOIN registries r ON r.id = cji.source_registry_id
+        LEFT JOIN base_image_checks bic ON bic.image_mapping_id = im.id
+        WHERE NOT b.is_archived
+          AND NOT cj.is_release_job
+          AND cji.copy_status = 'success'
+          AND cji.source_sha256 IS NOT NULL
+          AND (bic.checked_at IS NULL OR bic.checked_at < NOW() - make_interval(secs => $1))
+        ORDER BY im.id, cji.created_at DESC
+        "#,
+    )
+    .bind(min_age_seconds)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let mut result = BaseImageCheckResponse {
+        checked: 0,
+        updates: 0,
+        errors: 0,
+    };
+    for candidate in candidates.into_iter().filter(|c| is_moving_tag(&c.source_tag)) {
+        let base_url = candidate
+            .base_url
+            .trim_start_matches("https://")
+            .trim_start_matches("http://")
+            .trim_end_matches('/');
+        let source_ref = format!("{}/{}:{}", base_url, candidate.source_image, candidate.source_tag);
+
+        let inspected = match state
+            .get_registry_credentials(candidate.source_registry_id, candidate.environment_id)
+            .await
+        {
+            Ok((username, password)) => state
+                .skopeo
+                .inspect_image(&source_ref, username.as_deref(), password.as_deref())
+                .await
+                .map(|info| info.digest),
+            Err(e) => Err(e),
+        };
+        let (upstream_digest, error) = match inspected {
+            Ok(digest) => (Some(digest), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        let update_available = upstream_digest
+            .as_deref()
+            .is_some_and(|digest| digest_changed(&candidate.source_sha256, digest));
+
+        sqlx::query(
+            r#"
+            INSERT INTO base_image_checks
+                (image_mapping_id, copy_job_image_id, source_ref, copied_digest, upstream_digest, update_available, error, last_copied_at, checked_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW())
+            ON CONFLICT (image_mapping_id) DO UPDATE SET
+                copy_job_image_id = EXCLUDED.copy_job_image_id,
+                source_ref = EXCLUDED.source_ref,
+                copied_digest = EXCLUDED.copied_digest,
+                upstream_digest = COALESCE(EXCLUDED.upstream_digest, base_image_checks.upstream_digest),
+                update_available = CASE WHEN EXCLUDED.upstream_digest IS NULL
+                    THEN base_image_checks.update_available AND base_image_checks.copied_digest = EXCLUDED.copied_digest
+                    ELSE EXCLUDED.update_available END,
+                error = EXCLUDED.error,
+                last_copied_at = EXCLUDED.last_copied_at,
+                checked_at = NOW()
+            "#,
+        )
+        .bind(candidate.image_mapping_id)
+        .bind(candidate.copy_job_image_id)
+        .bind(&source_ref)
+        .bind(&candidate.source_sha256)
+        .bind(&upstream_digest)
+        .bind(update_available)
+        .bind(&error)
+        .bind(candidate.last_copied_at)
+        .execute(&state.pool)
+        .await?;
+
+        result.checked += 1;
+        if update_available {
+            result.updates += 1;
+        }
+        if let Some(error) = error {
+            result.errors += 1;
+            warn!(source_ref = %source_ref, error = %error, "Base image digest check failed");
+        }
+    }
+    Ok(result)
+}
+
+/// GET /api/v1/base-image-updates - Mappings s novějším upstream digestem a dotčené bundles
+async fn list_base_image_updates(
+    Extension(auth): Extension<AuthContext>,
+    State(state): State<CopyApiState>,
+    Query(query): Query<BaseImageUpdatesQuery>,
+) -> Result<Json<BaseImageUpdatesResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let updates = sqlx::query_as::<_, BaseImageUpdate>(
+        r#"
+        SELECT
+            bic.image_mapping_id,
+            b.tenant_id,
+            b.id AS bundle_id,
+            b.name AS bundle_name,
+            bv.version AS bundle_version,
+            im.app_name,
+            im.container_name,
+            bic.source_ref,
+            bic.copied_digest,
+            bic.upstream_digest,
+            bic.last_copied_at,
+            bic.checked_at
+        FROM base_image_checks bic
+        JOIN image_mappings im ON im.id = bic.image_mapping_id
+        JOIN bundle_versions bv ON bv.id = im.bundle_version_id
+        JOIN bundles b ON b.id = bv.bundle_id
+        WHERE bic.update_available
+          AND bv.version = b.current_version
+          AND NOT b.is_archived
+          AND ($1::uuid IS NULL OR b.tenant_id = $1)
+          AND ($2::uuid IS NULL OR b.id = $2)
+        ORDER BY b.name, im.app_name, im.container_name
+        "#,
+    )
+    .bind(query.tenant_id)
+    .bind(query.bundle_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }),
+        )
+    })?;
+
+    let updates: Vec<BaseImageUpdate> = updates
+        .into_iter()
+        .filter(|update| auth.is_tenant_allowed(update.tenant_id))
+        .collect();
+    let mut bundles: Vec<AffectedBundle> = Vec::new();
+    for update in &updates {
+        match bundles.iter_mut().find(|b| b.bundle_id == update.bundle_id) {
+            Some(bundle) => bundle.images += 1,
+            None => bundles.push(AffectedBundle {
+                bundle_id: update.bundle_id,
+                bundle_name: update.bundle_name.clone(),
+                bundle_version: update.bundle_version,
+                images: 1,
+            }),
+        }
+    }
+
+    Ok(Json(BaseImageUpdatesResponse { updates, bundles }))
+}
+
+/// POST /api/v1/base-image-updates/check - Okamžitá kontrola všech mappings (bez ohledu na interval)
+async fn trigger_base_image_check(
+    State(state): State<CopyApiState>,
+) -> Result<Json<BaseImageCheckResponse>, (StatusCode, Json<ErrorResponse>)> {
+    run_check(&state, None).await.map(Json).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Base image update check failed: {}", e),
+            }),
+        )
+    })
+}
@@ -0,0 +1,646 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::post,
+    Extension, Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{Connection, PgConnection, PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::api::deploy::{self, EnvironmentRequest};
+use crate::auth::AuthContext;
+use crate::db::models::{Environment, ImageMapping};
+
+#[derive(Clone)]
+pub struct BulkApiState {
+    pub pool: PgPool,
+    pub encryption_secret: String,
+}
+
+/// Operace bulk položky
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BulkOp {
+    Create,
+    Update,
+    Upsert,
+    Delete,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BulkRequest<T> {
+    /// Jen validace a report, bez commitu
+    #[serde(default)]
+    pub dry_run: bool,
+    pub items: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EnvironmentBulkItem {
+    pub op: BulkOp,
+    /// ID existujícího environmentu (jinak se hledá podle slugu)
+    pub id: Option<Uuid>,
+    pub environment: Option<EnvironmentRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnvVarKind {
+    /// Mapování release env vars - release_env_var_mappings (source_key -> target_key)
+    Mapping,
+    /// Extra env vars - extra_env_vars (key -> value)
+    Extra,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EnvVarBulkItem {
+    pub op: BulkOp,
+    pub environment_id: Uuid,
+    pub kind: EnvVarKind,
+    pub key: String,
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImageMappingBulkRequest {
+    #[serde(default)]
+    pub dry_run: bool,
+    pub change_note: Option<String>,
+    pub items: Vec<ImageMappingBulkItem>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImageMappingBulkItem {
+    pub op: BulkOp,
+    pub source_image: Option<String>,
+    pub source_tag: Option<String>,
+    pub target_image: Option<String>,
+    pub app_name: String,
+    pub container_name: Option<String>,
+    /// Vlastník; u update bez hodnoty zůstává původní, `""` ho odebere
+    pub owner: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkItemResult {
+    pub index: usize,
+    pub op: BulkOp,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkResponse {
+    pub committed: bool,
+    pub dry_run: bool,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BulkItemResult>,
+    /// Nová verze bundle (jen image mappings, pokud se sada změnila)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bundle_version: Option<i32>,
+}
+
+/// Response s chybou
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+/// Vytvoří router pro bulk endpoints
+pub fn router(state: BulkApiState) -> Router {
+    Router::new()
+        .route("/tenants/{tenant_id}/environments/bulk", post(bulk_environments))
+        .route("/tenants/{tenant_id}/environments/env-vars/bulk", post(bulk_env_vars))
+        .route("/bundles/{bundle_id}/images/bulk", post(bulk_image_mappings))
+        .with_state(state)
+}
+
+fn db_error(e: sqlx::Error) -> ApiError {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: format!("Database error: {}", e),
+        }),
+    )
+}
+
+fn item_error(message: impl Into<String>) -> ApiError {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ErrorResponse {
+            error: message.into(),
+        }),
+    )
+}
+
+/// Spustí položku v savepointu; chyba položky vrátí savepoint a pokračuje se dál
+async fn finish_item(
+    savepoint: Transaction<'_, Postgres>,
+    index: usize,
+    op: BulkOp,
+    outcome: Result<Option<Uuid>, (StatusCode, Json<deploy::ErrorResponse>)>,
+) -> Result<BulkItemResult, ApiError> {
+    match outcome {
+        Ok(id) => {
+            savepoint.commit().await.map_err(db_error)?;
+            Ok(BulkItemResult { index, op, ok: true, id, error: None })
+        }
+        Err((_, Json(err))) => {
+            savepoint.rollback().await.map_err(db_error)?;
+            Ok(BulkItemResult { index, op, ok: false, id: None, error: Some(err.error) })
+        }
+    }
+}
+
+/// Commit celé dávky jen pokud vše prošlo a nejde o dry run
+async fn finish_batch(
+    tx: Transaction<'_, Postgres>,
+    dry_run: bool,
+    results: Vec<BulkItemResult>,
+    bundle_version: Option<i32>,
+) -> Result<(StatusCode, Json<BulkResponse>), ApiError> {
+    let failed = results.iter().filter(|r| !r.ok).count();
+    let committed = failed == 0 && !dry_run;
+    if committed {
+        tx.commit().await.map_err(db_error)?;
+    } else {
+        tx.rollback().await.map_err(db_error)?;
+    }
+    let status = if failed == 0 {
+        StatusCode::OK
+    } else {
+        StatusCode::UNPROCESSABLE_ENTITY
+    };
+    Ok((
+        status,
+        Json(BulkResponse {
+            committed,
+            dry_run,
+            succeeded: results.len() - failed,
+            failed,
+            results,
+            bundle_version: if committed { bundle_version } else { None },
+        }),
+    ))
+}
+
+/// POST /api/v1/tenants/{tenant_id}/environments/bulk - Bulk create/update/delete environmentů v jedné transakci
+async fn bulk_environments(
+    Extension(auth): Extension<AuthContext>,
+    State(state): State<BulkApiState>,
+    Path(tenant_id): Path<Uuid>,
+    Json(payload): Json<BulkRequest<EnvironmentBulkItem>>,
+) -> Result<(StatusCode, Json<BulkResponse>), ApiError> {
+    let mut tx = state.pool.begin().await.map_err(db_error)?;
+    let mut results = Vec::with_capacity(payload.items.len());
+    let mut updated: Vec<(Environment, Environment)> = Vec::new();
+
+    for (index, item) in payload.items.into_iter().enumerate() {
+        let op = item.op;
+        let mut savepoint = tx.begin().await.map_err(db_error)?;
+        let outcome =
+            apply_environment_item(&mut savepoint, &state.encryption_secret, tenant_id, item, &mut updated).await;
+        results.push(finish_item(savepoint, index, op, outcome).await?);
+    }
+
+    let response = finish_batch(tx, payload.dry_run, results, None).await?;
+    if response.1.committed {
+        for (before, after) in &updated {
+            deploy::record_environment_changes(&state.pool, &auth, before, after).await;
+        }
+    }
+    Ok(response)
+}
+
+async fn find_tenant_environment(
+    conn: &mut PgConnection,
+    tenant_id: Uuid,
+    id: Option<Uuid>,
+    slug: Option<&str>,
+) -> Result<Option<Environment>, (StatusCode, Json<deploy::ErrorResponse>)> {
+    let result = match (id, slug) {
+        (Some(id), _) => {
+            sqlx::query_as::<_, Environment>("SELECT * FROM environments WHERE id = $1 AND tenant_id = $2")
+                .bind(id)
+                .bind(tenant_id)
+                .fetch_optional(&mut *conn)
+                .await
+        }
+        (None, Some(slug)) => {
+            sqlx::query_as::<_, Environment>("SELECT * FROM environments WHERE slug = $1 AND tenant_id = $2")
+                .bind(slug)
+                .bind(tenant_id)
+                .fetch_optional(&mut *conn)
+                .await
+        }
+        (None, None) => return Ok(None),
+    };
+    result.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(deploy::ErrorResponse {
+                error: format!("Database error: {}", e),
+            }),
+        )
+    })
+}
+
+fn env_item_error(message: impl Into<String>) -> (StatusCode, Json<deploy::ErrorResponse>) {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(deploy::ErrorResponse {
+            error: message.into(),
+        }),
+    )
+}
+
+async fn apply_environment_item(
+    conn: &mut PgConnection,
+    encryption_secret: &str,
+    tenant_id: Uuid,
+    item: EnvironmentBulkItem,
+    updated: &mut Vec<(Environment, Environment)>,
+) -> Result<Option<Uuid>, (StatusCode, Json<deploy::ErrorResponse>)> {
+    let slug = item.environment.as_ref().map(|env| {
+        env.slug
+            .as_deref()
+            .map(deploy::slugify_env_name)
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| deploy::slugify_env_name(env.name.trim()))
+    });
+    let existing = find_tenant_environment(conn, tenant_id, item.id, slug.as_deref()).await?;
+
+    match item.op {
+        BulkOp::Create => {
+            let payload = item.environment.ok_or_else(|| env_item_error("environment is required for create"))?;
+            if existing.is_some() {
+                return Err(env_item_error(format!(
+                    "Environment '{}' already exists",
+                    slug.unwrap_or_default()
+                )));
+            }
+            let env = deploy::insert_environment(conn, encryption_secret, tenant_id, payload).await?;
+            Ok(Some(env.id))
+        }
+        BulkOp::Update | BulkOp::Upsert => {
+            let payload = item.environment.ok_or_else(|| env_item_error("environment is required"))?;
+            match existing {
+                Some(current) => {
+                    let (before, after) =
+                        deploy::update_environment_record(conn, encryption_secret, current.id, payload).await?;
+                    let id = after.id;
+                    updated.push((before, after));
+                    Ok(Some(id))
+                }
+                None if item.op == BulkOp::Upsert => {
+                    let env = deploy::insert_environment(conn, encryption_secret, tenant_id, payload).await?;
+                    Ok(Some(env.id))
+                }
+                None => Err(env_item_error("Environment not found for tenant")),
+            }
+        }
+        BulkOp::Delete => {
+            let current = existing.ok_or_else(|| env_item_error("Environment not found for tenant"))?;
+            deploy::delete_environment_record(conn, current.id).await?;
+            Ok(Some(current.id))
+        }
+    }
+}
+
+/// POST /api/v1/tenants/{tenant_id}/environments/env-vars/bulk - Bulk úprava env var mappings
+async fn bulk_env_vars(
+    State(state): State<BulkApiState>,
+    Path(tenant_id): Path<Uuid>,
+    Json(payload): Json<BulkRequest<EnvVarBulkItem>>,
+) -> Result<(StatusCode, Json<BulkResponse>), ApiError> {
+    let mut tx = state.pool.begin().await.map_err(db_error)?;
+    let mut results = Vec::with_capacity(payload.items.len());
+
+    for (index, item) in payload.items.into_iter().enumerate() {
+        let op = item.op;
+        let mut savepoint = tx.begin().await.map_err(db_error)?;
+        let outcome = apply_env_var_item(&mut savepoint, tenant_id, item).await;
+        results.push(finish_item(savepoint, index, op, outcome).await?);
+    }
+
+    finish_batch(tx, payload.dry_run, results, None).await
+}
+
+async fn apply_env_var_item(
+    conn: &mut PgConnection,
+    tenant_id: Uuid,
+    item: EnvVarBulkItem,
+) -> Result<Option<Uuid>, (StatusCode, Json<deploy::ErrorResponse>)> {
+    let key = item.key.trim();
+    if key.is_empty() {
+        return Err(env_item_error("key cannot be empty"));
+    }
+    let column = match item.kind {
+        EnvVarKind::Mapping => "release_env_var_mappings",
+        EnvVarKind::Extra => "extra_env_vars",
+    };
+
+    let current = sqlx::query_scalar::<_, serde_json::Value>(&format!(
+        "SELECT {} FROM environments WHERE id = $1 AND tenant_id = $2 FOR UPDATE",
+        column
+    ))
+    .bind(item.environment_id)
+    .bind(tenant_id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| env_item_error(format!("Database error: {}", e)))?
+    .ok_or_else(|| env_item_error("Environment not found for tenant"))?;
+
+    let mut map = current.as_object().cloned().unwrap_or_default();
+    let exists = map.contains_key(key);
+    match item.op {
+        BulkOp::Create if exists => return Err(env_item_error(format!("Key '{}' already exists", key))),
+        BulkOp::Update | BulkOp::Delete if !exists => {
+            return Err(env_item_error(format!("Key '{}' not found", key)));
+        }
+        _ => {}
+    }
+
+    if item.op == BulkOp::Delete {
+        map.remove(key);
+    } else {
+        let value = item.value.as_deref().map(str::trim).unwrap_or("");
+        if matches!(item.kind, EnvVarKind::Mapping) && value.is_empty() {
+            return Err(env_item_error("value (target key) cannot be empty for mapping"));
+        }
+        map.insert(key.to_string(), serde_json::Value::String(value.to_string()));
+    }
+
+    sqlx::query(&format!("UPDATE environments SET {} = $1 WHERE id = $2", column))
+        .bind(serde_json::Value::Object(map))
+        .bind(item.environment_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| env_item_error(format!("Database error: {}", e)))?;
+
+    Ok(Some(item.environment_id))
+}
+
+/// Klíč image mappingu v rámci verze (app + container)
+fn mapping_key(app_name: &str, container_name: Option<&str>) -> (String, String) {
+    (
+        app_name.trim().to_string(),
+        container_name.map(str::trim).unwrap_or("").to_string(),
+    )
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct MappingDraft {
+    source_image: String,
+    source_tag: String,
+    target_image: String,
+    app_name: String,
+    container_name: Option<String>,
+    owner: Option<String>,
+}
+
+impl From<&ImageMapping> for MappingDraft {
+    fn from(mapping: &ImageMapping) -> Self {
+        MappingDraft {
+            source_image: mapping.source_image.clone(),
+            source_tag: mapping.source_tag.clone(),
+            target_image: mapping.target_image.clone(),
+            app_name: mapping.app_name.clone(),
+            container_name: mapping.container_name.clone(),
+            owner: mapping.owner.clone(),
+        }
+    }
+}
+
+fn draft_from_item(item: &ImageMappingBulkItem, current: Option<&MappingDraft>) -> Result<MappingDraft, String> {
+    let pick = |value: &Option<String>, fallback: Option<&String>| {
+        value
+            .as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+            .or_else(|| fallback.cloned())
+    };
+    let source_image = pick(&item.source_image, current.map(|c| &c.source_image))
+        .ok_or_else(|| "Source image cannot be empty".to_string())?;
+    let target_image = pick(&item.target_image, current.map(|c| &c.target_image))
+        .ok_or_else(|| "Target image cannot be empty".to_string())?;
+    let source_tag = pick(&item.source_tag, current.map(|c| &c.source_tag)).unwrap_or_else(|| "latest".to_string());
+    if item.app_name.trim().is_empty() {
+        return Err("App name cannot be empty".to_string());
+    }
+    let owner = match item.owner.as_deref().map(str::trim) {
+        Some("") => None,
+        Some(owner) => Some(owner.to_string()),
+        None => current.and_then(|c| c.owner.clone()),
+    };
+    Ok(MappingDraft {
+        source_image,
+        source_tag,
+        target_image,
+        app_name: item.app_name.trim().to_string(),
+        container_name: item
+            .container_name
+            .as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string),
+        owner,
+    })
+}
+
+/// Aplikuje bulk položky na aktuální sadu mappings; vrací výsledek každé položky
+fn apply_mapping_items(
+    drafts: &mut Vec<MappingDraft>,
+    items: &[ImageMappingBulkItem],
+) -> Vec<BulkItemResult> {
+    let mut results = Vec::with_capacity(items.len());
+    for (index, item) in items.iter().enumerate() {
+        let key = mapping_key(&item.app_name, item.container_name.as_deref());
+        let position = drafts
+            .iter()
+            .position(|d| mapping_key(&d.app_name, d.container_name.as_deref()) == key);
+
+        let outcome: Result<(), String> = match (item.op, position) {
+            (BulkOp::Create, Some(_)) => Err(format!("Mapping for {}/{} already exists", key.0, key.1)),
+            (BulkOp::Update, None) | (BulkOp::Delete, None) => {
+                Err(format!("Mapping for {}/{} not found", key.0, key.1))
+            }
+            (BulkOp::Delete, Some(pos)) => {
+                drafts.remove(pos);
+                Ok(())
+            }
+            (_, Some(pos)) => draft_from_item(item, Some(&drafts[pos])).map(|draft| drafts[pos] = draft),
+            (_, None) => draft_from_item(item, None).map(|draft| drafts.push(draft)),
+        };
+
+        results.push(BulkItemResult {
+            index,
+            op: item.op,
+            ok: outcome.is_ok(),
+            id: None,
+            error: outcome.err(),
+        });
+    }
+    results
+}
+
+/// POST /api/v1/bundles/{bundle_id}/images/bulk - Bulk změna image mappings (vytvoří novou verzi bundle)
+async fn bulk_image_mappings(
+    Extension(auth): Extension<AuthContext>,
+    State(state): State<BulkApiState>,
+    Path(bundle_id): Path<Uuid>,
+    Json(payload): Json<ImageMappingBulkRequest>,
+) -> Result<(StatusCode, Json<BulkResponse>), ApiError> {
+    let mut tx = state.pool.begin().await.map_err(db_error)?;
+
+    let (current_version, is_archived): (i32, bool) =
+        sqlx::query_as("SELECT current_version, is_archived FROM bundles WHERE id = $1 FOR UPDATE")
+            .bind(bundle_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(db_error)?
+            .ok_or_else(|| {
+                (
+                    StatusCode::NOT_FOUND,
+                    Json(ErrorResponse {
+                        error: format!("Bundle with id {} not found", bundle_id),
+                    }),
+                )
+            })?;
+    if is_archived {
+        return Err(item_error("Bundle is archived. Restore it before changing image mappings."));
+    }
+
+    // Image mappings jsou immutable - změny vždy vytvoří novou verzi
+    let current = sqlx::query_as::<_, ImageMapping>(
+        r#"
+        SELECT im.*
+        FROM image_mappings im
+        JOIN bundle_versions bv ON bv.id = im.bundle_version_id
+        WHERE bv.bundle_id = $1 AND bv.version = $2
+        ORDER BY im.created_at
+        "#,
+    )
+    .bind(bundle_id)
+    .bind(current_version)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(db_error)?;
+
+    let original: Vec<MappingDraft> = current.iter().map(MappingDraft::from).collect();
+    let mut drafts = original.clone();
+    let results = apply_mapping_items(&mut drafts, &payload.items);
+    let all_ok = results.iter().all(|r| r.ok);
+
+    let mut new_version = None;
+    if all_ok && drafts != original {
+        let version = current_version + 1;
+        let version_id = sqlx::query_scalar::<_, Uuid>(
+            "INSERT INTO bundle_versions (bundle_id, version, change_note, created_by)
+             VALUES ($1, $2, $3, $4)
+             RETURNING id",
+        )
+        .bind(bundle_id)
+        .bind(version)
+        .bind(payload.change_note.clone().unwrap_or_else(|| "Bulk image mapping update".to_string()))
+        .bind(&auth.username)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+        for draft in &drafts {
+            sqlx::query(
+                "INSERT INTO image_mappings
+                 (bundle_version_id, source_image, source_tag, target_image, app_name, container_name, owner)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            )
+            .bind(version_id)
+            .bind(&draft.source_image)
+            .bind(&draft.source_tag)
+            .bind(&draft.target_image)
+            .bind(&draft.app_name)
+            .bind(&draft.container_name)
+            .bind(&draft.owner)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        }
+
+        sqlx::query("UPDATE bundle_versions SET is_archived = TRUE WHERE bundle_id = $1 AND version < $2")
+            .bind(bundle_id)
+            .bind(version)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        sqlx::query("UPDATE bundles SET current_version = $1 WHERE id = $2")
+            .bind(version)
+            .bind(bundle_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        new_version = Some(version);
+    }
+
+    finish_batch(tx, payload.dry_run, results, new_version).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(op: BulkOp, app: &str, source: Option<&str>) -> ImageMappingBulkItem {
+        ImageMappingBulkItem {
+            op,
+            source_image: source.map(str::to_string),
+            source_tag: None,
+            target_image: source.map(|s| format!("target/{}", s)),
+            app_name: app.to_string(),
+            container_name: None,
+            owner: None,
+        }
+    }
+
+    #[test]
+    fn mapping_items_are_idempotent_and_report_errors() {
+        let mut drafts = Vec::new();
+        let results = apply_mapping_items(&mut drafts, &[item(BulkOp::Upsert, "api", Some("src/api"))]);
+        assert!(results[0].ok);
+        let snapshot = drafts.clone();
+
+        let results = apply_mapping_items(&mut drafts, &[item(BulkOp::Upsert, "api", Some("src/api"))]);
+        assert!(results[0].ok);
+        assert_eq!(drafts, snapshot);
+
+        let results = apply_mapping_items(
+            &mut drafts,
+            &[item(BulkOp::Create, "api", Some("src/api")), item(BulkOp::Delete, "web", None)],
+        );
+        assert!(!results[0].ok);
+        assert!(!results[1].ok);
+    }
+
+    #[test]
+    fn mapping_owner_is_kept_unless_overridden() {
+        let mut drafts = Vec::new();
+        let mut owned = item(BulkOp::Create, "api", Some("src/api"));
+        owned.owner = Some(" team-payments ".to_string());
+        apply_mapping_items(&mut drafts, &[owned]);
+        assert_eq!(drafts[0].owner.as_deref(), Some("team-payments"));
+
+        apply_mapping_items(&mut drafts, &[item(BulkOp::Update, "api", Some("src/api-v2"))]);
+        assert_eq!(drafts[0].owner.as_deref(), Some("team-payments"));
+
+        let mut cleared = item(BulkOp::Update, "api", None);
+        cleared.owner = Some(String::new());
+        apply_mapping_items(&mut drafts, &[cleared]);
+        assert_eq!(drafts[0].owner, None);
+    }
+}
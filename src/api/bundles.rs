@@ -1,16 +1,19 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
-    routing::{get, put},
+    routing::{get, post, put},
     Extension, Json, Router,
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::auth::AuthContext;
 use crate::db::models::{Bundle, BundleVersion, ImageMapping};
+use crate::services::change_history;
+use crate::services::csv_import::{self, CsvImportQuery, CsvImportResult, CsvRowError};
+use crate::services::image_policy::{self, PolicyImage, PolicyViolation};
 
 /// Request pro vytvoření nového bundle
 #[derive(Debug, Deserialize)]
@@ -100,6 +103,8 @@ pub struct CreateImageMappingRequest {
     pub target_image: String,
     pub app_name: String,
     pub container_name: Option<String>,
+    #[serde(default)]
+    pub owner: Option<String>,
 }
 
 /// Response s chybou
@@ -119,6 +124,42 @@ pub struct ArchiveBundleVersionRequest {
     pub is_archived: bool,
 }
 
+/// Počítadlo auto tagů bundle pro environment a den
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct BundleTagCounterRow {
+    pub bundle_id: Uuid,
+    pub environment_id: Uuid,
+    pub environment_name: String,
+    pub environment_slug: String,
+    pub target_registry_id: Option<Uuid>,
+    pub target_registry_name: Option<String>,
+    pub date: NaiveDate,
+    pub counter: i32,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BundleTagCountersQuery {
+    pub environment_id: Option<Uuid>,
+    pub date: Option<NaiveDate>,
+}
+
+/// Request pro nastavení počítadla (`counter: 0` = reset, další auto tag dostane 1)
+#[derive(Debug, Deserialize)]
+pub struct SetBundleTagCounterRequest {
+    pub environment_id: Uuid,
+    pub date: NaiveDate,
+    pub counter: i32,
+}
+
+/// Request pro posunutí počítadla (např. když tag vznikl mimo SRM)
+#[derive(Debug, Deserialize)]
+pub struct BumpBundleTagCounterRequest {
+    pub environment_id: Uuid,
+    pub date: NaiveDate,
+    pub by: Option<i32>,
+}
+
 /// Response s bundle včetně počtu image mappings
 #[derive(Debug, Serialize, sqlx::FromRow)]
 pub struct BundleWithStats {
@@ -152,9 +193,12 @@ pub fn router(pool: PgPool) -> Router {
         .route("/bundles/{bundle_id}/copy-jobs", get(list_bundle_copy_jobs))
         .route("/bundles/{bundle_id}/releases", get(list_bundle_releases))
         .route("/bundles/{bundle_id}/deployments", get(list_bundle_deployments))
+        .route("/bundles/{bundle_id}/tag-counters", get(list_bundle_tag_counters).put(set_bundle_tag_counter))
+        .route("/bundles/{bundle_id}/tag-counters/bump", post(bump_bundle_tag_counter))
 
         // Image mappings
         .route("/bundles/{bundle_id}/versions/{version}/images", get(list_image_mappings).post(create_image_mapping))
+        .route("/bundles/{bundle_id}/versions/{version}/images/import", post(import_image_mappings_csv))
         .route("/bundles/{bundle_id}/versions/{version}/images/{mapping_id}", get(get_image_mapping).delete(delete_image_mapping))
 
         .with_state(pool)
@@ -442,6 +486,7 @@ async fn create_bundle(
 
 /// PUT /api/v1/bundles/{id} - Update bundle
 async fn update_bundle(
+    Extension(auth): Extension<AuthContext>,
     State(pool): State<PgPool>,
     Path(id): Path<Uuid>,
     Json(payload): Json<UpdateBundleRequest>,
@@ -456,6 +501,7 @@ async fn update_bundle(
     }
 
     let auto_tag_enabled = payload.auto_tag_enabled.unwrap_or(false);
+    let before = load_bundle_snapshot(&pool, id).await?;
 
     let bundle = sqlx::query_as::<_, Bundle>(
         "UPDATE bundles
@@ -491,7 +537,10 @@ async fn update_bundle(
     })?;
 
     match bundle {
-        Some(bundle) => Ok(Json(bundle)),
+        Some(bundle) => {
+            record_bundle_changes(&pool, &auth, before.as_ref(), &bundle).await;
+            Ok(Json(bundle))
+        }
         None => Err((
             StatusCode::NOT_FOUND,
             Json(ErrorResponse {
@@ -503,10 +552,12 @@ async fn update_bundle(
 
 /// PUT /api/v1/bundles/{id}/archive - Archive/restore bundle
 async fn set_bundle_archive(
+    Extension(auth): Extension<AuthContext>,
     State(pool): State<PgPool>,
     Path(id): Path<Uuid>,
     Json(payload): Json<ArchiveBundleRequest>,
 ) -> Result<Json<Bundle>, (StatusCode, Json<ErrorResponse>)> {
+    let before = load_bundle_snapshot(&pool, id).await?;
     let bundle = sqlx::query_as::<_, Bundle>(
         "UPDATE bundles
          SET is_archived = $1
@@ -527,7 +578,10 @@ async fn set_bundle_archive(
     })?;
 
     match bundle {
-        Some(bundle) => Ok(Json(bundle)),
+        Some(bundle) => {
+            record_bundle_changes(&pool, &auth, before.as_ref(), &bundle).await;
+            Ok(Json(bundle))
+        }
         None => Err((
             StatusCode::NOT_FOUND,
             Json(ErrorResponse {
@@ -537,6 +591,46 @@ async fn set_bundle_archive(
     }
 }
 
+async fn load_bundle_snapshot(
+    pool: &PgPool,
+    id: Uuid,
+) -> Result<Option<Bundle>, (StatusCode, Json<ErrorResponse>)> {
+    sqlx::query_as::<_, Bundle>(
+        "SELECT id, tenant_id, source_registry_id, name, description, auto_tag_enabled, current_version, is_archived, created_at
+         FROM bundles WHERE id = $1",
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }),
+        )
+    })
+}
+
+async fn record_bundle_changes(pool: &PgPool, auth: &AuthContext, before: Option<&Bundle>, after: &Bundle) {
+    let Some(before) = before else {
+        return;
+    };
+    let changes = change_history::diff_snapshots(before, after);
+    if let Err(e) = change_history::record_changes(
+        pool,
+        "bundle",
+        after.id,
+        Some(after.tenant_id),
+        Some(&auth.username),
+        &changes,
+    )
+    .await
+    {
+        tracing::warn!(bundle_id = %after.id, error = %e, "Failed to record bundle change history");
+    }
+}
+
 /// DELETE /api/v1/bundles/{id} - Smazání bundle
 async fn delete_bundle(
     State(pool): State<PgPool>,
@@ -1008,6 +1102,16 @@ async fn create_image_mapping(
         )
     })?;
 
+    let violations = bundle_policy_violations(&pool, bundle_id, &[payload.source_image.as_str()]).await?;
+    if !violations.is_empty() {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse {
+                error: image_policy::violations_message(&violations),
+            }),
+        ));
+    }
+
     // Vytvořit image mapping
     let source_tag = if payload.source_tag.trim().is_empty() {
         "latest".to_string()
@@ -1020,11 +1124,17 @@ async fn create_image_mapping(
         .map(str::trim)
         .filter(|v| !v.is_empty())
         .map(str::to_string);
+    let owner = payload
+        .owner
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string);
 
     let mapping = sqlx::query_as::<_, ImageMapping>(
         "INSERT INTO image_mappings
-         (bundle_version_id, source_image, source_tag, target_image, app_name, container_name)
-         VALUES ($1, $2, $3, $4, $5, $6)
+         (bundle_version_id, source_image, source_tag, target_image, app_name, container_name, owner)
+         VALUES ($1, $2, $3, $4, $5, $6, $7)
          RETURNING *"
     )
     .bind(bundle_version_id)
@@ -1033,6 +1143,7 @@ async fn create_image_mapping(
     .bind(&payload.target_image)
     .bind(&payload.app_name)
     .bind(&container_name)
+    .bind(&owner)
     .fetch_one(&pool)
     .await
     .map_err(|e| {
@@ -1047,6 +1158,233 @@ async fn create_image_mapping(
     Ok((StatusCode::CREATED, Json(mapping)))
 }
 
+/// POST /api/v1/bundles/{bundle_id}/versions/{version}/images/import - Import image mappings z CSV
+///
+/// Sloupce: source_image, source_tag, target_image, app_name, container_name, owner.
+/// Při jakékoliv chybě se neimportuje nic; `?validate_only=true` jen validuje.
+async fn import_image_mappings_csv(
+    State(pool): State<PgPool>,
+    Path((bundle_id, version)): Path<(Uuid, i32)>,
+    Query(query): Query<CsvImportQuery>,
+    body: String,
+) -> Result<(StatusCode, Json<CsvImportResult>), (StatusCode, Json<ErrorResponse>)> {
+    let validate_only = query.validate_only.unwrap_or(false);
+
+    let bundle_version_id: Uuid = sqlx::query_scalar(
+        "SELECT id FROM bundle_versions WHERE bundle_id = $1 AND version = $2"
+    )
+    .bind(bundle_id)
+    .bind(version)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }),
+        )
+    })?
+    .ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Bundle version {} not found for bundle {}", version, bundle_id),
+            }),
+        )
+    })?;
+
+    let records = match csv_import::parse_records(&body, &["source_image", "target_image", "app_name"]) {
+        Ok(records) => records,
+        Err(errors) => {
+            return Ok((
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(CsvImportResult {
+                    validate_only,
+                    total_rows: 0,
+                    imported: 0,
+                    errors,
+                }),
+            ))
+        }
+    };
+
+    let existing = sqlx::query_as::<_, (String, Option<String>)>(
+        "SELECT app_name, container_name FROM image_mappings WHERE bundle_version_id = $1",
+    )
+    .bind(bundle_version_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }),
+        )
+    })?;
+    let mut seen: std::collections::HashSet<(String, String)> = existing
+        .into_iter()
+        .map(|(app, container)| (app, container.unwrap_or_default()))
+        .collect();
+
+    let mut errors = Vec::new();
+    let mut mappings = Vec::new();
+    let mut mapping_rows = Vec::new();
+    for record in &records {
+        let mut row_ok = true;
+        for column in ["source_image", "target_image", "app_name"] {
+            if record.get(column).is_none() {
+                errors.push(CsvRowError::new(record.row, Some(column), "Value cannot be empty"));
+                row_ok = false;
+            }
+        }
+        if !row_ok {
+            continue;
+        }
+        let app_name = record.get("app_name").unwrap_or_default().to_string();
+        let container_name = record.get("container_name").map(str::to_string);
+        let key = (app_name.clone(), container_name.clone().unwrap_or_default());
+        if !seen.insert(key) {
+            errors.push(CsvRowError::new(
+                record.row,
+                Some("app_name"),
+                format!(
+                    "Mapping for {}/{} already exists in this version",
+                    app_name,
+                    container_name.as_deref().unwrap_or("-")
+                ),
+            ));
+            continue;
+        }
+        mappings.push(CreateImageMappingRequest {
+            source_image: record.get("source_image").unwrap_or_default().to_string(),
+            source_tag: record.get("source_tag").unwrap_or("latest").to_string(),
+            target_image: record.get("target_image").unwrap_or_default().to_string(),
+            app_name,
+            container_name,
+            owner: record.get("owner").map(str::to_string),
+        });
+        mapping_rows.push(record.row);
+    }
+
+    let source_images: Vec<&str> = mappings.iter().map(|m| m.source_image.as_str()).collect();
+    let violations = bundle_policy_violations(&pool, bundle_id, &source_images).await?;
+    for violation in &violations {
+        errors.push(CsvRowError::new(
+            mapping_rows[violation.index],
+            Some("source_image"),
+            format!("Blocked by image policy: {}", violation.reason),
+        ));
+    }
+
+    let total_rows = records.len();
+    if !errors.is_empty() || validate_only {
+        let status = if errors.is_empty() {
+            StatusCode::OK
+        } else {
+            StatusCode::UNPROCESSABLE_ENTITY
+        };
+        return Ok((
+            status,
+            Json(CsvImportResult {
+                validate_only,
+                total_rows,
+                imported: 0,
+                errors,
+            }),
+        ));
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }),
+        )
+    })?;
+    for mapping in &mappings {
+        sqlx::query(
+            "INSERT INTO image_mappings
+             (bundle_version_id, source_image, source_tag, target_image, app_name, container_name, owner)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)"
+        )
+        .bind(bundle_version_id)
+        .bind(&mapping.source_image)
+        .bind(&mapping.source_tag)
+        .bind(&mapping.target_image)
+        .bind(&mapping.app_name)
+        .bind(&mapping.container_name)
+        .bind(&mapping.owner)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Database error: {}", e),
+                }),
+            )
+        })?;
+    }
+    tx.commit().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to commit transaction: {}", e),
+            }),
+        )
+    })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(CsvImportResult {
+            validate_only,
+            total_rows,
+            imported: mappings.len(),
+            errors,
+        }),
+    ))
+}
+
+/// Porušení image policies platných pro všechny environmenty tenanta (bez `environment_pattern`).
+/// Zdrojem je source registry bundle; pravidla pro konkrétní environment se ověřují až při copy jobu.
+async fn bundle_policy_violations(
+    pool: &PgPool,
+    bundle_id: Uuid,
+    source_images: &[&str],
+) -> Result<Vec<PolicyViolation>, (StatusCode, Json<ErrorResponse>)> {
+    let policy_error = |e: sqlx::Error| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to evaluate image policies: {}", e),
+            }),
+        )
+    };
+    let bundle = sqlx::query_as::<_, (Uuid, String)>(
+        "SELECT b.tenant_id, r.base_url
+         FROM bundles b
+         JOIN registries r ON r.id = b.source_registry_id
+         WHERE b.id = $1",
+    )
+    .bind(bundle_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(policy_error)?;
+    let Some((tenant_id, base_url)) = bundle else {
+        return Ok(Vec::new());
+    };
+    let images: Vec<PolicyImage> = source_images
+        .iter()
+        .map(|image| PolicyImage::new(&base_url, image))
+        .collect();
+    image_policy::check_images(pool, tenant_id, None, &images)
+        .await
+        .map_err(policy_error)
+}
+
 /// DELETE /api/v1/bundles/{bundle_id}/versions/{version}/images/{mapping_id} - Smazání image mapping
 async fn delete_image_mapping(
     State(pool): State<PgPool>,
@@ -1061,3 +1399,351 @@ async fn delete_image_mapping(
         }),
     ))
 }
+
+const TAG_COUNTER_SELECT: &str = r#"
+    SELECT btc.bundle_id, btc.environment_id, e.name AS environment_name, e.slug AS environment_slug,
+           e.target_registry_id, r.name AS target_registry_name, btc.date, btc.counter, btc.updated_at
+    FROM bundle_tag_counters btc
+    JOIN environments e ON e.id = btc.environment_id
+    LEFT JOIN registries r ON r.id = e.target_registry_id
+"#;
+
+/// GET /api/v1/bundles/{bundle_id}/tag-counters - Počítadla auto tagů (`?environment_id=`, `?date=`)
+async fn list_bundle_tag_counters(
+    Extension(auth): Extension<AuthContext>,
+    State(pool): State<PgPool>,
+    Path(bundle_id): Path<Uuid>,
+    Query(query): Query<BundleTagCountersQuery>,
+) -> Result<Json<Vec<BundleTagCounterRow>>, (StatusCode, Json<ErrorResponse>)> {
+    load_tag_counter_bundle(&pool, &auth, bundle_id).await?;
+
+    let rows = sqlx::query_as::<_, BundleTagCounterRow>(&format!(
+        "{TAG_COUNTER_SELECT}
+         WHERE btc.bundle_id = $1
+           AND ($2::uuid IS NULL OR btc.environment_id = $2)
+           AND ($3::date IS NULL OR btc.date = $3)
+         ORDER BY btc.date DESC, e.name"
+    ))
+    .bind(bundle_id)
+    .bind(query.environment_id)
+    .bind(query.date)
+    .fetch_all(&pool)
+    .await
+    .map_err(tag_counter_db_error)?;
+
+    Ok(Json(rows))
+}
+
+/// PUT /api/v1/bundles/{bundle_id}/tag-counters - Nastaví/resetuje počítadlo
+async fn set_bundle_tag_counter(
+    Extension(auth): Extension<AuthContext>,
+    State(pool): State<PgPool>,
+    Path(bundle_id): Path<Uuid>,
+    Json(payload): Json<SetBundleTagCounterRequest>,
+) -> Result<Json<BundleTagCounterRow>, (StatusCode, Json<ErrorResponse>)> {
+    if payload.counter < 0 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Counter must not be negative".to_string(),
+            }),
+        ));
+    }
+    let bundle = load_tag_counter_bundle(&pool, &auth, bundle_id).await?;
+    update_tag_counter(&pool, &auth, &bundle, payload.environment_id, payload.date, |_| payload.counter).await
+}
+
+/// POST /api/v1/bundles/{bundle_id}/tag-counters/bump - Posune počítadlo o `by` (výchozí 1)
+async fn bump_bundle_tag_counter(
+    Extension(auth): Extension<AuthContext>,
+    State(pool): State<PgPool>,
+    Path(bundle_id): Path<Uuid>,
+    Json(payload): Json<BumpBundleTagCounterRequest>,
+) -> Result<Json<BundleTagCounterRow>, (StatusCode, Json<ErrorResponse>)> {
+    let by = payload.by.unwrap_or(1);
+    if by < 1 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Bump must be at least 1".to_string(),
+            }),
+        ));
+    }
+    let bundle = load_tag_counter_bundle(&pool, &auth, bundle_id).await?;
+    update_tag_counter(&pool, &auth, &bundle, payload.environment_id, payload.date, |current| {
+        current.saturating_add(by)
+    })
+    .await
+}
+
+async fn load_tag_counter_bundle(
+    pool: &PgPool,
+    auth: &AuthContext,
+    bundle_id: Uuid,
+) -> Result<Bundle, (StatusCode, Json<ErrorResponse>)> {
+    let bundle = load_bundle_snapshot(pool, bundle_id).await?.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Bundle with id {} not found", bundle_id),
+            }),
+        )
+    })?;
+    if !auth.is_tenant_allowed(bundle.tenant_id) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse {
+                error: "Tenant access denied".to_string(),
+            }),
+        ));
+    }
+    Ok(bundle)
+}
+
+/// Změna počítadla pod zámkem řádku (souběžný copy job ho jinak může posunout mezi čtením a zápisem)
+/// a se záznamem do historie bundle
+async fn update_tag_counter(
+    pool: &PgPool,
+    auth: &AuthContext,
+    bundle: &Bundle,
+    environment_id: Uuid,
+    date: NaiveDate,
+    next: impl FnOnce(i32) -> i32,
+) -> Result<Json<BundleTagCounterRow>, (StatusCode, Json<ErrorResponse>)> {
+    let env_slug = sqlx::query_scalar::<_, String>("SELECT slug FROM environments WHERE id = $1 AND tenant_id = $2")
+        .bind(environment_id)
+        .bind(bundle.tenant_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(tag_counter_db_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: "Environment does not belong to this tenant".to_string(),
+                }),
+            )
+        })?;
+
+    let mut tx = pool.begin().await.map_err(tag_counter_db_error)?;
+    // Prázdný řádek zamkne konflikt na PK i pro počítadlo, které zatím neexistuje
+    sqlx::query(
+        "INSERT INTO bundle_tag_counters (bundle_id, environment_id, date, counter)
+         VALUES ($1, $2, $3, 0)
+         ON CONFLICT (bundle_id, environment_id, date) DO NOTHING",
+    )
+    .bind(bundle.id)
+    .bind(environment_id)
+    .bind(date)
+    .execute(&mut *tx)
+    .await
+    .map_err(tag_counter_db_error)?;
+    let before: i32 = sqlx::query_scalar(
+        "SELECT counter FROM bundle_tag_counters
+         WHERE bundle_id = $1 AND environment_id = $2 AND date = $3
+         FOR UPDATE",
+    )
+    .bind(bundle.id)
+    .bind(environment_id)
+    .bind(date)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(tag_counter_db_error)?;
+    let after = next(before);
+    sqlx::query(
+        "UPDATE bundle_tag_counters SET counter = $4, updated_at = now()
+         WHERE bundle_id = $1 AND environment_id = $2 AND date = $3",
+    )
+    .bind(bundle.id)
+    .bind(environment_id)
+    .bind(date)
+    .bind(after)
+    .execute(&mut *tx)
+    .await
+    .map_err(tag_counter_db_error)?;
+    // Odpověď se čte ještě pod zámkem, po commitu by mohla vidět hodnotu souběžné změny
+    let row = sqlx::query_as::<_, BundleTagCounterRow>(&format!(
+        "{TAG_COUNTER_SELECT}
+         WHERE btc.bundle_id = $1 AND btc.environment_id = $2 AND btc.date = $3"
+    ))
+    .bind(bundle.id)
+    .bind(environment_id)
+    .bind(date)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(tag_counter_db_error)?;
+    tx.commit().await.map_err(tag_counter_db_error)?;
+
+    let change = change_history::FieldChange {
+        field: format!("tag_counter.{}.{}", env_slug, date),
+        before: before.into(),
+        after: after.into(),
+    };
+    if before != after
+        && let Err(e) = change_history::record_changes(
+            pool,
+            "bundle",
+            bundle.id,
+            Some(bundle.tenant_id),
+            Some(&auth.username),
+            &[change],
+        )
+        .await
+    {
+        tracing::warn!(bundle_id = %bundle.id, error = %e, "Failed to record tag counter change history");
+    }
+
+    Ok(Json(row))
+}
+
+fn tag_counter_db_error(e: sqlx::Error) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: format!("Database error: {}", e),
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::Role;
+
+    struct TagCounterFixture {
+        pool: PgPool,
+        tenant_id: Uuid,
+        environment_id: Uuid,
+        bundle: Bundle,
+    }
+
+    impl TagCounterFixture {
+        async fn new() -> Self {
+            let pool = PgPool::connect(&std::env::var("DATABASE_URL").unwrap()).await.unwrap();
+            let slug = format!("tag-counter-test-{}", Uuid::new_v4());
+            let tenant_id: Uuid = sqlx::query_scalar("INSERT INTO tenants (name, slug) VALUES ($1, $1) RETURNING id")
+                .bind(&slug)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            let registry_id: Uuid = sqlx::query_scalar(
+                "INSERT INTO registries (tenant_id, name, registry_type, base_url, role, auth_type)
+                 VALUES ($1, 'source', 'generic', 'https://harbor.example.com', 'both', 'none')
+                 RETURNING id",
+            )
+            .bind(tenant_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            let environment_id: Uuid = sqlx::query_scalar(
+                "INSERT INTO environments (tenant_id, name, slug) VALUES ($1, 'prod', 'prod') RETURNING id",
+            )
+            .bind(tenant_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            let bundle = sqlx::query_as::<_, Bundle>(
+                "INSERT INTO bundles (tenant_id, source_registry_id, name) VALUES ($1, $2, 'app') RETURNING *",
+            )
+            .bind(tenant_id)
+            .bind(registry_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            TagCounterFixture {
+                pool,
+                tenant_id,
+                environment_id,
+                bundle,
+            }
+        }
+
+        async fn update(&self, next: impl FnOnce(i32) -> i32) -> i32 {
+            let date = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+            let Json(row) = update_tag_counter(&self.pool, &auth(), &self.bundle, self.environment_id, date, next)
+                .await
+                .unwrap();
+            row.counter
+        }
+
+        async fn history(&self) -> Vec<(Option<String>, serde_json::Value)> {
+            sqlx::query_as(
+                "SELECT changed_by, changes FROM entity_change_history
+                 WHERE entity_type = 'bundle' AND entity_id = $1
+                 ORDER BY created_at",
+            )
+            .bind(self.bundle.id)
+            .fetch_all(&self.pool)
+            .await
+            .unwrap()
+        }
+
+        async fn cleanup(self) {
+            sqlx::query("DELETE FROM entity_change_history WHERE entity_id = $1")
+                .bind(self.bundle.id)
+                .execute(&self.pool)
+                .await
+                .unwrap();
+            sqlx::query("DELETE FROM tenants WHERE id = $1")
+                .bind(self.tenant_id)
+                .execute(&self.pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    fn auth() -> AuthContext {
+        AuthContext {
+            username: "tester".to_string(),
+            email: None,
+            groups: Vec::new(),
+            roles: vec![Role::Admin],
+            tenant_slugs: Vec::new(),
+            tenant_ids: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    #[ignore] // Vyžaduje Postgres s migracemi v DATABASE_URL
+    async fn concurrent_bumps_never_get_the_same_counter() {
+        let fixture = std::sync::Arc::new(TagCounterFixture::new().await);
+        let tasks: Vec<_> = (0..8)
+            .map(|_| {
+                let fixture = fixture.clone();
+                tokio::spawn(async move { fixture.update(|current| current + 1).await })
+            })
+            .collect();
+        let mut counters = Vec::new();
+        for task in tasks {
+            counters.push(task.await.unwrap());
+        }
+        let history = fixture.history().await;
+        std::sync::Arc::into_inner(fixture).unwrap().cleanup().await;
+
+        counters.sort();
+        assert_eq!(counters, (1..=8).collect::<Vec<i32>>());
+        assert_eq!(history.len(), 8);
+    }
+
+    #[tokio::test]
+    #[ignore] // Vyžaduje Postgres s migracemi v DATABASE_URL
+    async fn reset_restarts_counter_and_is_recorded_in_history() {
+        let fixture = TagCounterFixture::new().await;
+        fixture.update(|current| current + 2).await;
+        let reset = fixture.update(|_| 0).await;
+        let unchanged = fixture.update(|_| 0).await;
+        let next = fixture.update(|current| current + 1).await;
+        let history = fixture.history().await;
+        fixture.cleanup().await;
+
+        assert_eq!((reset, unchanged, next), (0, 0, 1));
+        // Reset na stejnou hodnotu se do historie nezapisuje
+        assert_eq!(history.len(), 3);
+        let (changed_by, changes) = &history[1];
+        assert_eq!(changed_by.as_deref(), Some("tester"));
+        assert_eq!(
+            changes,
+            &serde_json::json!([{"field": "tag_counter.prod.2026-10-16", "before": 2, "after": 0}])
+        );
+    }
+}
@@ -2,15 +2,18 @@
 
 use axum::{
     extract::{Path, State, Query},
-    http::StatusCode,
-    response::sse::{Event, KeepAlive, Sse},
+    http::{HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
     routing::{get, post},
     Extension, Json, Router,
 };
 use futures::stream::{self, BoxStream, Stream, StreamExt};
 use chrono::{Datelike, Duration, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool};
 use std::collections::{HashMap, HashSet};
 use std::convert::Infallible;
 use std::sync::Arc;
@@ -20,7 +23,23 @@ use uuid::Uuid;
 use crate::auth::AuthContext;
 use crate::crypto;
 use crate::db::models::{Bundle, CopyJobImage, Environment, ImageMapping, Registry, Release};
-use crate::services::image_tool::SkopeoCredentials;
+use crate::services::copy_eta;
+use crate::services::blob_reuse::{self, ReuseOptions};
+use crate::services::dashboard_views;
+use crate::services::events::{COPY_JOB_FINISHED, COPY_JOB_STARTED};
+use crate::services::image_policy::{self, PolicyImage};
+use crate::services::job_logs::{self, JobKind, LogPollQuery, LogPollResponse, LogStreamItem, LogStreamQuery};
+use crate::services::job_queue::{self, JobDispatch};
+use crate::services::lifecycle_notifications;
+use crate::services::log_fanout::LogFanout;
+use crate::services::notifications::Notifier;
+use crate::services::object_storage::ObjectStorage;
+use crate::services::owner_notifications;
+use crate::services::release_channels;
+use crate::services::release_manifest;
+use crate::services::release_tag_cleanup::{self, CleanupAction, ReleaseTagCleanup};
+use crate::services::tool_paths;
+use crate::services::image_tool::{self, CopyOptionsOverride, SkopeoCredentials};
 use crate::services::ImageToolService;
 
 const PROGRESS_MARKER_PREFIX: &str = "__PROGRESS__";
@@ -45,15 +64,7 @@ struct ProgressMarkerEvent {
     status: Option<String>,
 }
 
-/// Request pro spuštění copy operace
-#[derive(Debug, Deserialize)]
-pub struct CopyBundleRequest {
-    pub target_tag: Option<String>,
-    pub timezone_offset_minutes: Option<i32>,
-    pub environment_id: Option<Uuid>,
-    pub source_registry_id: Option<Uuid>,
-    pub target_registry_id: Option<Uuid>,
-}
+pub use srm_api_types::copy::{CopyBundleRequest, CopyJobResponse};
 
 #[derive(Debug, Deserialize)]
 pub struct PrecheckRequest {
@@ -67,12 +78,6 @@ pub struct ErrorResponse {
     pub error: String,
 }
 
-/// Response s job ID
-#[derive(Debug, Serialize)]
-pub struct CopyJobResponse {
-    pub job_id: Uuid,
-    pub message: String,
-}
 
 #[derive(Debug, Deserialize)]
 pub struct NextTagQuery {
@@ -122,6 +127,29 @@ pub struct PrecheckFailure {
     pub error: String,
 }
 
+#[derive(Debug, Default, Deserialize)]
+pub struct PrecheckQuery {
+    /// SSE: event `image` pro každý image, na konci `done` s PrecheckResult
+    #[serde(default)]
+    pub stream: bool,
+}
+
+/// Výsledek jednoho image v průběžném (SSE) precheck
+#[derive(Debug, Serialize)]
+pub struct PrecheckImageResult {
+    pub source_image: String,
+    pub source_tag: String,
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+/// Image k ověření; Err = chyba zjištěná už před inspect (např. chybějící digest)
+struct PrecheckTarget {
+    source_image: String,
+    source_tag: String,
+    source_url: Result<String, String>,
+}
+
 /// Status copy jobu
 #[derive(Debug, Clone, Serialize)]
 pub struct CopyJobStatus {
@@ -148,6 +176,11 @@ pub struct CopyJobStatus {
     pub started_at: chrono::DateTime<chrono::Utc>,
     pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
     pub duration_seconds: i64,
+    /// Odhad zbývajícího času z historie kopií (None = není z čeho odhadovat / job skončil)
+    pub eta_seconds: Option<i64>,
+    pub estimated_completion_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Procento hotového času jobu (0-100)
+    pub percent_complete: Option<f64>,
 }
 
 /// Shrnutý záznam copy jobu
@@ -170,6 +203,12 @@ pub struct CopyJobSummary {
     pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct JobListQuery {
+    /// Bypass summary view and read live data
+    pub fresh: Option<bool>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ReleaseCopyRequest {
     pub source_copy_job_id: Uuid,
@@ -177,12 +216,18 @@ pub struct ReleaseCopyRequest {
     pub environment_id: Option<Uuid>,
     pub release_id: String,
     pub notes: Option<String>,
+    /// Kanál release, který vznikne po doběhnutí jobu (výchozí `stable`)
+    #[serde(default)]
+    pub channel: Option<String>,
     pub source_ref_mode: Option<String>,
     pub source_tag_override: Option<String>,
     pub validate_only: Option<bool>,
     pub extra_tags: Option<Vec<String>>,
     pub rename_rules: Vec<RenameRule>,
     pub overrides: Vec<ImageOverride>,
+    /// Přepsání retry politiky a copy flagů jen pro tento job
+    #[serde(default)]
+    pub copy_options: Option<CopyOptionsOverride>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -199,6 +244,34 @@ pub struct SelectiveCopyRequest {
     pub selected_image_ids: Vec<Uuid>,
     pub target_tag: Option<String>,
     pub timezone_offset_minutes: Option<i32>,
+    /// Povolí přepsat target tag, který už patří jinému jobu
+    pub overwrite: Option<bool>,
+    /// Jen pro release base job - ID nového (patch) release
+    pub release_id: Option<String>,
+    pub notes: Option<String>,
+    /// Jen pro release base job - úspěšný copy job, ze kterého se berou vybrané images
+    pub source_copy_job_id: Option<Uuid>,
+    /// Jen pro release base job - kanál patch release (výchozí kanál base release)
+    pub channel: Option<String>,
+}
+
+/// Release job, nad kterým se staví patch release
+struct SelectiveReleaseBase {
+    job_id: Uuid,
+    bundle_id: Uuid,
+    source_registry_id: Uuid,
+    target_registry_id: Uuid,
+    environment_id: Uuid,
+}
+
+/// Image patch release jobu (odkud a kam se kopíruje)
+struct PatchReleaseImage {
+    image_mapping_id: Uuid,
+    source_image: String,
+    source_tag: String,
+    source_registry_id: Uuid,
+    target_image: String,
+    source_sha256: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -221,11 +294,16 @@ pub struct CopyApiState {
     pub encryption_secret: String,
     pub job_logs: Arc<RwLock<std::collections::HashMap<Uuid, broadcast::Sender<String>>>>,
     pub cancel_flags: Arc<RwLock<HashSet<Uuid>>>,
+    pub dispatch: JobDispatch,
+    pub log_fanout: LogFanout,
+    pub precheck_concurrency: usize,
+    pub object_storage: ObjectStorage,
+    pub notifier: Notifier,
 }
 
 impl CopyApiState {
     /// Získá dešifrované credentials pro registry
-    async fn get_registry_credentials(
+    pub(crate) async fn get_registry_credentials(
         &self,
         registry_id: Uuid,
         environment_id: Option<Uuid>,
@@ -401,10 +479,39 @@ pub fn router(state: CopyApiState) -> Router {
         .route("/copy/jobs/{job_id}/stream", get(copy_job_stream_sse))
         .route("/copy/jobs/{job_id}/logs", get(copy_job_logs_sse))
         .route("/copy/jobs/{job_id}/logs/history", get(copy_job_logs_history))
+        .route("/copy/jobs/{job_id}/logs/poll", get(copy_job_logs_poll))
+        .route("/copy/jobs/{job_id}/logs/stream", get(copy_job_logs_stream_sse))
         .route("/copy/jobs/{job_id}/progress", get(copy_job_progress_sse))
         .with_state(state)
 }
 
+/// Datum auto tagu v timezone tenanta bundle; bez nastavené timezone se použije offset z prohlížeče
+async fn auto_tag_date(
+    pool: &PgPool,
+    bundle_id: Uuid,
+    offset_minutes: Option<i32>,
+) -> Result<NaiveDate, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_date = sqlx::query_scalar::<_, NaiveDate>(
+        "SELECT (now() AT TIME ZONE t.timezone)::date
+         FROM bundles b
+         JOIN tenants t ON t.id = b.tenant_id
+         WHERE b.id = $1 AND t.timezone IS NOT NULL",
+    )
+    .bind(bundle_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to resolve auto tag date: {}", e),
+            }),
+        )
+    })?;
+    Ok(tenant_date.unwrap_or_else(|| local_date_from_offset(offset_minutes)))
+}
+
+/// `offset_minutes` má znaménko jako JS `Date.getTimezoneOffset()` (UTC - lokální čas, Praha v létě = -120)
 fn local_date_from_offset(offset_minutes: Option<i32>) -> NaiveDate {
     let offset = offset_minutes.unwrap_or(0) as i64;
     let local = Utc::now() - Duration::minutes(offset);
@@ -492,7 +599,7 @@ async fn resolve_registry_project_path(
     .map(|v| v.flatten())
 }
 
-fn build_source_url(base: &str, img: &CopyJobImage, mode: &str) -> Result<String, String> {
+pub(crate) fn build_source_url(base: &str, img: &CopyJobImage, mode: &str) -> Result<String, String> {
     if mode == "digest" {
         if let Some(digest) = img.source_sha256.as_deref() {
             if !digest.trim().is_empty() {
@@ -543,7 +650,140 @@ fn parse_progress_marker(line: &str) -> Option<ProgressMarkerEvent> {
         .and_then(|json| serde_json::from_str::<ProgressMarkerEvent>(json).ok())
 }
 
-fn is_missing_target_manifest_error(err: &str) -> bool {
+/// Zarezervuje (registry, image, tag) pro nový job. Tag jiného jobu se převezme jen s `overwrite`,
+/// nebo když ten job selhal / byl zrušen. Souběžná rezervace čeká na commit první a pak dostane konflikt.
+async fn reserve_target_tags(
+    conn: &mut PgConnection,
+    job_id: Uuid,
+    target_registry_id: Uuid,
+    target_images: &[String],
+    target_tag: &str,
+    overwrite: bool,
+) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    let db_error = |e: sqlx::Error| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to reserve target tag: {}", e),
+            }),
+        )
+    };
+    let reserved: Vec<String> = sqlx::query_scalar(
+        "INSERT INTO copy_job_target_tags (target_registry_id, target_image, target_tag, copy_job_id)
+         SELECT DISTINCT $1, image, $3, $4 FROM UNNEST($2::text[]) AS image
+         ON CONFLICT (target_registry_id, target_image, target_tag) DO UPDATE
+         SET copy_job_id = EXCLUDED.copy_job_id, created_at = NOW()
+         WHERE $5 OR EXISTS (
+             SELECT 1 FROM copy_jobs cj
+             WHERE cj.id = copy_job_target_tags.copy_job_id AND cj.status IN ('failed', 'cancelled')
+         )
+         RETURNING target_image",
+    )
+    .bind(target_registry_id)
+    .bind(target_images)
+    .bind(target_tag)
+    .bind(job_id)
+    .bind(overwrite)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(db_error)?;
+
+    let mut conflicts: Vec<&str> = target_images
+        .iter()
+        .map(String::as_str)
+        .filter(|image| !reserved.iter().any(|r| r == image))
+        .collect();
+    if conflicts.is_empty() {
+        return Ok(());
+    }
+    conflicts.sort_unstable();
+    conflicts.dedup();
+
+    let owner: Option<Uuid> = sqlx::query_scalar(
+        "SELECT copy_job_id FROM copy_job_target_tags
+         WHERE target_registry_id = $1 AND target_image = $2 AND target_tag = $3",
+    )
+    .bind(target_registry_id)
+    .bind(conflicts[0])
+    .bind(target_tag)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(db_error)?;
+    Err((
+        StatusCode::CONFLICT,
+        Json(ErrorResponse {
+            error: format!(
+                "Target tag {} is already used{} for {}; set overwrite=true to replace it",
+                target_tag,
+                owner.map(|id| format!(" by copy job {}", id)).unwrap_or_default(),
+                conflicts.join(", ")
+            ),
+        }),
+    ))
+}
+
+/// Porušení image policies tenanta -> 403 (při kopírování do environmentu)
+async fn enforce_image_policies(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    environment_slug: &str,
+    images: &[PolicyImage],
+) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    let violations = image_policy::check_images(pool, tenant_id, Some(environment_slug), images)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Failed to evaluate image policies: {}", e),
+                }),
+            )
+        })?;
+    if violations.is_empty() {
+        return Ok(());
+    }
+    Err((
+        StatusCode::FORBIDDEN,
+        Json(ErrorResponse {
+            error: image_policy::violations_message(&violations),
+        }),
+    ))
+}
+
+/// Image policies release kopií - dvojice (registry, ze které se kopíruje; cesta image)
+async fn enforce_release_image_policies(
+    pool: &PgPool,
+    environment: &Environment,
+    images: &[(Uuid, &str)],
+) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    let registry_ids: Vec<Uuid> = images.iter().map(|(registry_id, _)| *registry_id).collect();
+    let base_urls: HashMap<Uuid, String> =
+        sqlx::query_as::<_, (Uuid, String)>("SELECT id, base_url FROM registries WHERE id = ANY($1)")
+            .bind(&registry_ids)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse {
+                        error: format!("Database error: {}", e),
+                    }),
+                )
+            })?
+            .into_iter()
+            .collect();
+    let policy_images = release_policy_images(images, &base_urls);
+    enforce_image_policies(pool, environment.tenant_id, &environment.slug, &policy_images).await
+}
+
+fn release_policy_images(images: &[(Uuid, &str)], base_urls: &HashMap<Uuid, String>) -> Vec<PolicyImage> {
+    images
+        .iter()
+        .filter_map(|(registry_id, path)| base_urls.get(registry_id).map(|url| PolicyImage::new(url, path)))
+        .collect()
+}
+
+pub(crate) fn is_missing_target_manifest_error(err: &str) -> bool {
     let err = err.to_ascii_lowercase();
     err.contains("manifest_unknown")
         || err.contains("manifest unknown")
@@ -651,6 +891,29 @@ async fn fetch_copy_job_status_from_db(pool: &PgPool, job_id: Uuid) -> Result<Op
         .num_seconds()
         .max(0);
 
+    let (eta_seconds, estimated_completion_at, percent_complete) = if job_logs::is_finished_status(&row.status) {
+        (None, None, Some(100.0))
+    } else {
+        let remaining = estimate_copy_job_remaining(
+            pool,
+            job_id,
+            row.current_bytes_copied.map(|v| v as u64),
+            row.current_total_bytes.map(|v| v as u64),
+        )
+        .await?;
+        // Pending job ještě neběží - uplynulý čas se nepočítá
+        let elapsed = if row.status == "pending" { 0 } else { duration_seconds };
+        match remaining {
+            Some(remaining) => (
+                Some(remaining.round() as i64),
+                (row.status != "pending")
+                    .then(|| Utc::now() + Duration::seconds(remaining.round() as i64)),
+                Some(copy_eta::percent_complete(elapsed as f64, remaining)),
+            ),
+            None => (None, None, None),
+        }
+    };
+
     Ok(Some(CopyJobStatus {
         job_id,
         bundle_id: row.bundle_id,
@@ -675,11 +938,99 @@ async fn fetch_copy_job_status_from_db(pool: &PgPool, job_id: Uuid) -> Result<Op
         started_at: row.started_at,
         completed_at: row.completed_at,
         duration_seconds,
+        eta_seconds,
+        estimated_completion_at,
+        percent_complete,
     }))
 }
 
+/// Zbývající čas copy jobu podle historie kopií stejných source images a nedávné propustnosti
+async fn estimate_copy_job_remaining(
+    pool: &PgPool,
+    job_id: Uuid,
+    current_bytes_copied: Option<u64>,
+    current_total_bytes: Option<u64>,
+) -> Result<Option<f64>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, (String, Option<f64>, Option<f64>, Option<f64>)>(
+        r#"
+        SELECT i.copy_status,
+               EXTRACT(EPOCH FROM (NOW() - i.started_at))::float8 AS elapsed_seconds,
+               h.avg_seconds,
+               h.avg_bytes
+        FROM copy_job_images i
+        LEFT JOIN LATERAL (
+            SELECT AVG(EXTRACT(EPOCH FROM (p.copied_at - p.started_at)))::float8 AS avg_seconds,
+                   AVG(NULLIF(p.bytes_copied, 0))::float8 AS avg_bytes
+            FROM (
+                SELECT copied_at, started_at, bytes_copied
+                FROM copy_job_images
+                WHERE source_image = i.source_image
+                  AND copy_status = 'success'
+                  AND copy_job_id <> i.copy_job_id
+                ORDER BY copied_at DESC NULLS LAST
+                LIMIT 10
+            ) p
+        ) h ON TRUE
+        WHERE i.copy_job_id = $1 AND i.copy_status IN ('pending', 'in_progress')
+        "#,
+    )
+    .bind(job_id)
+    .fetch_all(pool)
+    .await?;
+    if rows.is_empty() {
+        return Ok(Some(0.0));
+    }
+
+    let (bytes_per_second, seconds_per_image) = sqlx::query_as::<_, (Option<f64>, Option<f64>)>(
+        r#"
+        SELECT (SUM(bytes_copied) FILTER (WHERE bytes_copied > 0)
+                / NULLIF(SUM(EXTRACT(EPOCH FROM (copied_at - started_at))) FILTER (WHERE bytes_copied > 0), 0))::float8,
+               AVG(EXTRACT(EPOCH FROM (copied_at - started_at)))::float8
+        FROM (
+            SELECT copied_at, started_at, bytes_copied
+            FROM copy_job_images
+            WHERE copy_status = 'success' AND started_at IS NOT NULL AND copied_at IS NOT NULL
+            ORDER BY copied_at DESC
+            LIMIT 200
+        ) recent
+        "#,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let images: Vec<copy_eta::RemainingImage> = rows
+        .into_iter()
+        .map(|(status, elapsed_seconds, history_seconds, history_bytes)| copy_eta::RemainingImage {
+            history_seconds,
+            history_bytes,
+            in_progress: (status == "in_progress").then(|| copy_eta::InProgressImage {
+                elapsed_seconds: elapsed_seconds.unwrap_or(0.0).max(0.0),
+                bytes_copied: current_bytes_copied,
+                total_bytes: current_total_bytes,
+            }),
+        })
+        .collect();
+    let baseline = copy_eta::EtaBaseline {
+        bytes_per_second,
+        seconds_per_image,
+    };
+    Ok(copy_eta::estimate_remaining_seconds(&images, &baseline))
+}
+
+/// Validace a převod job copy options pro uložení
+fn job_copy_options(
+    copy_options: Option<&CopyOptionsOverride>,
+) -> Result<Option<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    let Some(copy_options) = copy_options.filter(|o| !o.is_empty()) else {
+        return Ok(None);
+    };
+    image_tool::validate_copy_options(copy_options)
+        .map_err(|error| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })))?;
+    Ok(serde_json::to_value(copy_options).ok())
+}
+
 /// POST /api/v1/bundles/{bundle_id}/versions/{version}/copy - Spustí copy operaci
-async fn copy_bundle_version(
+pub(crate) async fn copy_bundle_version(
     State(state): State<CopyApiState>,
     Path((bundle_id, version)): Path<(Uuid, i32)>,
     Json(payload): Json<CopyBundleRequest>,
@@ -817,7 +1168,7 @@ async fn copy_bundle_version(
             )
         })?;
 
-    let _source_registry: (String,) = sqlx::query_as(
+    let source_registry: (String,) = sqlx::query_as(
         "SELECT base_url FROM registries WHERE id = $1",
     )
     .bind(source_registry_id)
@@ -847,8 +1198,18 @@ async fn copy_bundle_version(
         )
     })?;
 
+    let policy_images: Vec<PolicyImage> = mappings
+        .iter()
+        .map(|mapping| {
+            let source_path =
+                apply_registry_project_path(&mapping.source_image, environment.source_project_path.as_deref());
+            PolicyImage::new(&source_registry.0, &source_path)
+        })
+        .collect();
+    enforce_image_policies(&state.pool, bundle.tenant_id, &environment.slug, &policy_images).await?;
+
     let target_tag = if bundle.auto_tag_enabled {
-        let date = local_date_from_offset(payload.timezone_offset_minutes);
+        let date = auto_tag_date(&state.pool, bundle.id, payload.timezone_offset_minutes).await?;
         let counter: i32 = sqlx::query_scalar(
             r#"
             INSERT INTO bundle_tag_counters (bundle_id, environment_id, date, counter)
@@ -885,11 +1246,29 @@ async fn copy_bundle_version(
         tag
     };
 
-    // Vytvořit job
+    let copy_options = job_copy_options(payload.copy_options.as_ref())?;
+    let overwrite = payload.overwrite.unwrap_or(false);
+
+    let source_project_path = environment.source_project_path.clone();
+    let target_project_path = environment.target_project_path.clone();
+    let target_paths: Vec<String> = mappings
+        .iter()
+        .map(|mapping| apply_registry_project_path(&mapping.target_image, target_project_path.as_deref()))
+        .collect();
+
+    // Vytvořit job (spolu s rezervací target tagu, aby souběžný job nedostal stejný tag)
     let job_id = Uuid::new_v4();
+    let mut tx = state.pool.begin().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }),
+        )
+    })?;
     sqlx::query(
-        "INSERT INTO copy_jobs (id, bundle_version_id, target_tag, status, source_registry_id, target_registry_id, environment_id)
-         VALUES ($1, $2, $3, 'pending', $4, $5, $6)"
+        "INSERT INTO copy_jobs (id, bundle_version_id, target_tag, status, source_registry_id, target_registry_id, environment_id, copy_options, overwrite_target_tag)
+         VALUES ($1, $2, $3, 'pending', $4, $5, $6, $7, $8)"
     )
     .bind(job_id)
     .bind(bundle_version_id)
@@ -897,7 +1276,9 @@ async fn copy_bundle_version(
     .bind(source_registry_id)
     .bind(target_registry_id)
     .bind(environment_id)
-    .execute(&state.pool)
+    .bind(&copy_options)
+    .bind(overwrite)
+    .execute(&mut *tx)
     .await
     .map_err(|e| {
         (
@@ -907,16 +1288,12 @@ async fn copy_bundle_version(
             }),
         )
     })?;
+    reserve_target_tags(&mut tx, job_id, target_registry_id, &target_paths, &target_tag, overwrite).await?;
 
-    let source_project_path = environment.source_project_path.clone();
-    let target_project_path = environment.target_project_path.clone();
-
-    // Vytvořit snapshot image mappings pro tento job
-    for mapping in &mappings {
+    // Vytvořit snapshot image mappings pro tento job (ve stejné transakci - job bez obrazů nesmí zůstat)
+    for (mapping, target_path) in mappings.iter().zip(&target_paths) {
         let source_path =
             apply_registry_project_path(&mapping.source_image, source_project_path.as_deref());
-        let target_path =
-            apply_registry_project_path(&mapping.target_image, target_project_path.as_deref());
         let _copy_job_image_id: Uuid = sqlx::query_scalar(
             "INSERT INTO copy_job_images
              (copy_job_id, image_mapping_id, source_image, source_tag, target_image, target_tag, copy_status)
@@ -927,9 +1304,9 @@ async fn copy_bundle_version(
         .bind(mapping.id)
         .bind(&source_path)
         .bind(&mapping.source_tag)
-        .bind(&target_path)
+        .bind(target_path)
         .bind(&target_tag)
-        .fetch_one(&state.pool)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| {
             (
@@ -940,6 +1317,14 @@ async fn copy_bundle_version(
             )
         })?;
     }
+    tx.commit().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to create copy job: {}", e),
+            }),
+        )
+    })?;
 
     Ok((
         StatusCode::ACCEPTED,
@@ -996,7 +1381,7 @@ async fn get_next_copy_tag(
         )
     })?;
 
-    let date = local_date_from_offset(query.tz_offset_minutes);
+    let date = auto_tag_date(&state.pool, bundle.id, query.tz_offset_minutes).await?;
     let current: Option<i32> = sqlx::query_scalar(
         "SELECT counter FROM bundle_tag_counters WHERE bundle_id = $1 AND environment_id = $2 AND date = $3",
     )
@@ -1024,9 +1409,10 @@ async fn get_next_copy_tag(
 async fn precheck_copy_images(
     State(state): State<CopyApiState>,
     Path((bundle_id, version)): Path<(Uuid, i32)>,
+    Query(query): Query<PrecheckQuery>,
     Json(payload): Json<PrecheckRequest>,
-) -> Result<Json<PrecheckResult>, (StatusCode, Json<ErrorResponse>)> {
-    let _bundle = sqlx::query_as::<_, Bundle>("SELECT * FROM bundles WHERE id = $1")
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let bundle = sqlx::query_as::<_, Bundle>("SELECT * FROM bundles WHERE id = $1")
         .bind(bundle_id)
         .fetch_optional(&state.pool)
         .await
@@ -1070,11 +1456,7 @@ async fn precheck_copy_images(
     })?;
 
     if mappings.is_empty() {
-        return Ok(Json(PrecheckResult {
-            total: 0,
-            ok: 0,
-            failed: vec![],
-        }));
+        return Ok(run_precheck(&state, Vec::new(), None, None, None, query.stream).await);
     }
 
     let environment_id = payload.environment_id.ok_or_else(|| {
@@ -1161,31 +1543,145 @@ async fn precheck_copy_images(
 
     let source_project_path = environment.source_project_path.clone();
 
-    let total = mappings.len();
-    let mut failed = Vec::new();
+    let policy_images: Vec<PolicyImage> = mappings
+        .iter()
+        .map(|mapping| {
+            let source_path =
+                apply_registry_project_path(&mapping.source_image, source_project_path.as_deref());
+            PolicyImage::new(&source_registry.base_url, &source_path)
+        })
+        .collect();
+    let violations = image_policy::check_images(&state.pool, bundle.tenant_id, Some(&environment.slug), &policy_images)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Failed to evaluate image policies: {}", e),
+                }),
+            )
+        })?;
 
-    for mapping in mappings {
-        let source_path =
-            apply_registry_project_path(&mapping.source_image, source_project_path.as_deref());
-        let source_url = format!(
-            "{}/{}:{}",
-            source_base_url, source_path, mapping.source_tag
-        );
-        let result = state
-            .skopeo
-            .inspect_image(&source_url, source_username.as_deref(), source_password.as_deref())
-            .await;
-        if let Err(err) = result {
-            failed.push(PrecheckFailure {
+    let targets = mappings
+        .into_iter()
+        .enumerate()
+        .map(|(index, mapping)| {
+            let source_path =
+                apply_registry_project_path(&mapping.source_image, source_project_path.as_deref());
+            // Image zakázaná policy se neinspectuje, rovnou je failed
+            let violation = violations.iter().find(|v| v.index == index);
+            let source_url = match violation {
+                Some(violation) => Err(format!("Blocked by image policy: {}", violation.reason)),
+                None => Ok(format!("{}/{}:{}", source_base_url, source_path, mapping.source_tag)),
+            };
+            PrecheckTarget {
                 source_image: source_path,
                 source_tag: mapping.source_tag,
-                error: err.to_string(),
-            });
-        }
+                source_url,
+            }
+        })
+        .collect();
+
+    let tool_path = environment_tool_path(&state, environment.id).await?;
+    Ok(run_precheck(&state, targets, source_username, source_password, tool_path, query.stream).await)
+}
+
+/// Přepsaná binárka image toolu pro environment (environment / tenant)
+async fn environment_tool_path(
+    state: &CopyApiState,
+    environment_id: Uuid,
+) -> Result<Option<String>, (StatusCode, Json<ErrorResponse>)> {
+    tool_paths::for_environment(&state.pool, environment_id)
+        .await
+        .map(|overrides| overrides.skopeo_path)
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Database error: {}", e),
+                }),
+            )
+        })
+}
+
+/// Inspect precheck targetů paralelně (max `precheck_concurrency` současně).
+/// Bez `stream` vrací PrecheckResult jako JSON, se `stream` průběžně SSE.
+async fn run_precheck(
+    state: &CopyApiState,
+    targets: Vec<PrecheckTarget>,
+    username: Option<String>,
+    password: Option<String>,
+    tool_path: Option<String>,
+    stream: bool,
+) -> Response {
+    let total = targets.len();
+    let skopeo = match tool_path {
+        Some(path) => state.skopeo.clone().with_tool_path(path),
+        None => state.skopeo.clone(),
+    };
+    let results = futures::stream::iter(targets)
+        .map(move |target| {
+            let skopeo = skopeo.clone();
+            let username = username.clone();
+            let password = password.clone();
+            async move {
+                let error = match target.source_url {
+                    Ok(source_url) => skopeo
+                        .inspect_image(&source_url, username.as_deref(), password.as_deref())
+                        .await
+                        .err()
+                        .map(|err| err.to_string()),
+                    Err(err) => Some(err),
+                };
+                PrecheckImageResult {
+                    source_image: target.source_image,
+                    source_tag: target.source_tag,
+                    ok: error.is_none(),
+                    error,
+                }
+            }
+        })
+        .buffered(state.precheck_concurrency.max(1));
+
+    if stream {
+        let events = async_stream::stream! {
+            let mut results = Box::pin(results);
+            let mut failed = Vec::new();
+            while let Some(result) = results.next().await {
+                yield Ok::<_, Infallible>(
+                    Event::default()
+                        .event("image")
+                        .data(serde_json::to_string(&result).unwrap_or_default()),
+                );
+                if let Some(error) = result.error {
+                    failed.push(PrecheckFailure {
+                        source_image: result.source_image,
+                        source_tag: result.source_tag,
+                        error,
+                    });
+                }
+            }
+            let ok = total - failed.len();
+            let summary = PrecheckResult { total, ok, failed };
+            yield Ok(Event::default()
+                .event("done")
+                .data(serde_json::to_string(&summary).unwrap_or_default()));
+        };
+        return Sse::new(events).keep_alive(KeepAlive::default()).into_response();
     }
 
+    let failed: Vec<PrecheckFailure> = results
+        .filter_map(|result| async move {
+            result.error.map(|error| PrecheckFailure {
+                source_image: result.source_image,
+                source_tag: result.source_tag,
+                error,
+            })
+        })
+        .collect()
+        .await;
     let ok = total - failed.len();
-    Ok(Json(PrecheckResult { total, ok, failed }))
+    Json(PrecheckResult { total, ok, failed }).into_response()
 }
 
 /// GET /api/v1/copy/jobs/{job_id}/images - seznam image výsledků pro job
@@ -1215,8 +1711,9 @@ async fn get_copy_job_images(
 async fn precheck_release_copy_images(
     Extension(auth): Extension<AuthContext>,
     State(state): State<CopyApiState>,
+    Query(query): Query<PrecheckQuery>,
     Json(payload): Json<ReleaseCopyRequest>,
-) -> Result<Json<PrecheckResult>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
     let source_ref_mode = payload
         .source_ref_mode
         .unwrap_or_else(|| "tag".to_string())
@@ -1353,11 +1850,7 @@ async fn precheck_release_copy_images(
     })?;
 
     if images.is_empty() {
-        return Ok(Json(PrecheckResult {
-            total: 0,
-            ok: 0,
-            failed: vec![],
-        }));
+        return Ok(run_precheck(&state, Vec::new(), None, None, None, query.stream).await);
     }
 
     let (source_username, source_password) = state
@@ -1378,52 +1871,45 @@ async fn precheck_release_copy_images(
         .trim_start_matches("http://")
         .to_string();
 
-    let total = images.len();
-    let mut failed = Vec::new();
-
-    for img in images {
-        if source_ref_mode == "digest" && img.target_sha256.as_deref().unwrap_or("").is_empty() {
-            failed.push(PrecheckFailure {
-                source_image: img.target_image.clone(),
-                source_tag: img.target_tag.clone(),
-                error: "Missing digest".to_string(),
-            });
-            continue;
-        }
+    let targets = images
+        .into_iter()
+        .map(|img| {
+            if source_ref_mode == "digest" && img.target_sha256.as_deref().unwrap_or("").is_empty() {
+                return PrecheckTarget {
+                    source_image: img.target_image,
+                    source_tag: img.target_tag,
+                    source_url: Err("Missing digest".to_string()),
+                };
+            }
 
-        let (source_url, effective_tag) = if source_ref_mode == "digest" {
-            (
-                format!(
-                    "{}/{}@{}",
-                    source_base_url,
-                    img.target_image,
-                    img.target_sha256.as_deref().unwrap_or("")
-                ),
-                img.target_tag.clone(),
-            )
-        } else {
-            let tag = source_tag_override
-                .as_deref()
-                .unwrap_or(&img.target_tag)
-                .to_string();
-            (format!("{}/{}:{}", source_base_url, img.target_image, tag), tag)
-        };
+            let (source_url, effective_tag) = if source_ref_mode == "digest" {
+                (
+                    format!(
+                        "{}/{}@{}",
+                        source_base_url,
+                        img.target_image,
+                        img.target_sha256.as_deref().unwrap_or("")
+                    ),
+                    img.target_tag.clone(),
+                )
+            } else {
+                let tag = source_tag_override
+                    .as_deref()
+                    .unwrap_or(&img.target_tag)
+                    .to_string();
+                (format!("{}/{}:{}", source_base_url, img.target_image, tag), tag)
+            };
 
-        let result = state
-            .skopeo
-            .inspect_image(&source_url, source_username.as_deref(), source_password.as_deref())
-            .await;
-        if let Err(err) = result {
-            failed.push(PrecheckFailure {
-                source_image: img.target_image.clone(),
+            PrecheckTarget {
+                source_image: img.target_image,
                 source_tag: effective_tag,
-                error: err.to_string(),
-            });
-        }
-    }
+                source_url: Ok(source_url),
+            }
+        })
+        .collect();
 
-    let ok = total - failed.len();
-    Ok(Json(PrecheckResult { total, ok, failed }))
+    let tool_path = environment_tool_path(&state, environment_id).await?;
+    Ok(run_precheck(&state, targets, source_username, source_password, tool_path, query.stream).await)
 }
 
 /// POST /api/v1/copy/jobs/release - Spustí release copy job ze zdrojového jobu
@@ -1450,6 +1936,9 @@ async fn start_release_copy_job(
         "digest" => "digest".to_string(),
         _ => "tag".to_string(),
     };
+    let channel = release_channels::parse_optional(payload.channel.as_deref())
+        .map_err(|error| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })))?
+        .unwrap_or(release_channels::DEFAULT_CHANNEL);
 
     let payload = ReleaseCopyRequest {
         release_id: release_id.clone(),
@@ -1705,6 +2194,12 @@ async fn start_release_copy_job(
         ));
     }
 
+    let policy_images: Vec<(Uuid, &str)> = source_images
+        .iter()
+        .map(|img| (source_registry_id, img.target_image.as_str()))
+        .collect();
+    enforce_release_image_policies(&state.pool, &environment, &policy_images).await?;
+
     // Připravit override map
     let mut overrides = std::collections::HashMap::new();
     for ov in payload.overrides {
@@ -1713,14 +2208,16 @@ async fn start_release_copy_job(
         }
     }
 
+    let copy_options = job_copy_options(payload.copy_options.as_ref())?;
+
     // Vytvořit nový job
     let job_id = Uuid::new_v4();
     let validate_only = payload.validate_only.unwrap_or(false);
 
     sqlx::query(
         "INSERT INTO copy_jobs
-         (id, bundle_version_id, target_tag, status, source_registry_id, target_registry_id, source_ref_mode, is_release_job, release_id, release_notes, validate_only, environment_id, extra_tags, base_copy_job_id)
-         VALUES ($1, $2, $3, 'pending', $4, $5, $6, TRUE, $7, $8, $9, $10, $11, $12)"
+         (id, bundle_version_id, target_tag, status, source_registry_id, target_registry_id, source_ref_mode, is_release_job, release_id, release_notes, validate_only, environment_id, extra_tags, base_copy_job_id, copy_options, release_channel)
+         VALUES ($1, $2, $3, 'pending', $4, $5, $6, TRUE, $7, $8, $9, $10, $11, $12, $13, $14)"
     )
     .bind(job_id)
     .bind(bundle_version_id)
@@ -1734,6 +2231,8 @@ async fn start_release_copy_job(
     .bind(Some(environment_id))
     .bind(&extra_tags)
     .bind(payload.source_copy_job_id)
+    .bind(&copy_options)
+    .bind(channel)
     .execute(&state.pool)
     .await
     .map_err(|e| {
@@ -1973,6 +2472,12 @@ async fn start_copy_from_release_job(
         ));
     }
 
+    let policy_images: Vec<(Uuid, &str)> = source_images
+        .iter()
+        .map(|img| (source_registry_id, img.target_image.as_str()))
+        .collect();
+    enforce_release_image_policies(&state.pool, &environment, &policy_images).await?;
+
     let mut extra_tags = payload
         .extra_tags
         .unwrap_or_else(|| release.extra_tags.clone().unwrap_or_default())
@@ -2141,15 +2646,6 @@ async fn start_selective_copy_job(
         ));
     }
 
-    if is_release_job {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: "Selective copy is only allowed for normal copy jobs".to_string(),
-            }),
-        ));
-    }
-
     let (Some(source_registry_id), Some(target_registry_id), Some(environment_id)) = (source_registry_id, target_registry_id, environment_id) else {
         return Err((
             StatusCode::BAD_REQUEST,
@@ -2159,43 +2655,16 @@ async fn start_selective_copy_job(
         ));
     };
 
-    let target_tag = if auto_tag_enabled {
-        let date = local_date_from_offset(payload.timezone_offset_minutes);
-        let counter: i32 = sqlx::query_scalar(
-            r#"
-            INSERT INTO bundle_tag_counters (bundle_id, environment_id, date, counter)
-            VALUES ($1, $2, $3, 1)
-            ON CONFLICT (bundle_id, environment_id, date)
-            DO UPDATE SET counter = bundle_tag_counters.counter + 1, updated_at = now()
-            RETURNING counter
-            "#,
-        )
-        .bind(bundle_id)
-        .bind(environment_id)
-        .bind(date)
-        .fetch_one(&state.pool)
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: format!("Failed to generate target tag: {}", e),
-                }),
-            )
-        })?;
-        format_tag(date, counter)
-    } else {
-        let tag = payload.target_tag.clone().unwrap_or_default().trim().to_string();
-        if tag.is_empty() {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: "Target tag is required".to_string(),
-                }),
-            ));
-        }
-        tag
-    };
+    if is_release_job {
+        let base = SelectiveReleaseBase {
+            job_id: base_job_id,
+            bundle_id,
+            source_registry_id,
+            target_registry_id,
+            environment_id,
+        };
+        return start_selective_release_copy_job(&state, base, payload).await;
+    }
 
     let base_images = sqlx::query_as::<_, CopyJobImage>(
         "SELECT * FROM copy_job_images WHERE copy_job_id = $1 ORDER BY created_at"
@@ -2221,20 +2690,6 @@ async fn start_selective_copy_job(
         ));
     }
 
-    let selected: std::collections::HashSet<Uuid> = payload.selected_image_ids.into_iter().collect();
-    let invalid_selected = selected
-        .iter()
-        .filter(|id| !base_images.iter().any(|img| &img.id == *id))
-        .count();
-    if invalid_selected > 0 {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: "Some selected images do not belong to the base copy job".to_string(),
-            }),
-        ));
-    }
-
     let environment = sqlx::query_as::<_, Environment>(
         "SELECT * FROM environments WHERE id = $1",
     )
@@ -2258,6 +2713,76 @@ async fn start_selective_copy_job(
         )
     })?;
 
+    let source_base_url: String = sqlx::query_scalar("SELECT base_url FROM registries WHERE id = $1")
+        .bind(source_registry_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Failed to get source registry: {}", e),
+                }),
+            )
+        })?;
+    let policy_images: Vec<PolicyImage> = base_images
+        .iter()
+        .map(|img| PolicyImage::new(&source_base_url, &img.source_image))
+        .collect();
+    enforce_image_policies(&state.pool, tenant_id, &environment.slug, &policy_images).await?;
+
+    let target_tag = if auto_tag_enabled {
+        let date = auto_tag_date(&state.pool, bundle_id, payload.timezone_offset_minutes).await?;
+        let counter: i32 = sqlx::query_scalar(
+            r#"
+            INSERT INTO bundle_tag_counters (bundle_id, environment_id, date, counter)
+            VALUES ($1, $2, $3, 1)
+            ON CONFLICT (bundle_id, environment_id, date)
+            DO UPDATE SET counter = bundle_tag_counters.counter + 1, updated_at = now()
+            RETURNING counter
+            "#,
+        )
+        .bind(bundle_id)
+        .bind(environment_id)
+        .bind(date)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Failed to generate target tag: {}", e),
+                }),
+            )
+        })?;
+        format_tag(date, counter)
+    } else {
+        let tag = payload.target_tag.clone().unwrap_or_default().trim().to_string();
+        if tag.is_empty() {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: "Target tag is required".to_string(),
+                }),
+            ));
+        }
+        tag
+    };
+
+    let selected: std::collections::HashSet<Uuid> = payload.selected_image_ids.into_iter().collect();
+    let invalid_selected = selected
+        .iter()
+        .filter(|id| !base_images.iter().any(|img| &img.id == *id))
+        .count();
+    if invalid_selected > 0 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Some selected images do not belong to the base copy job".to_string(),
+            }),
+        ));
+    }
+
     let source_mappings = sqlx::query_as::<_, ImageMapping>(
         "SELECT * FROM image_mappings WHERE bundle_version_id = $1 ORDER BY created_at",
     )
@@ -2278,11 +2803,23 @@ async fn start_selective_copy_job(
         .map(|mapping| (mapping.id, mapping))
         .collect();
 
+    let overwrite = payload.overwrite.unwrap_or(false);
+    let target_images: Vec<String> = base_images.iter().map(|img| img.target_image.clone()).collect();
+
     let job_id = Uuid::new_v4();
+    let create_error = |e: sqlx::Error| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to create selective copy job: {}", e),
+            }),
+        )
+    };
+    let mut tx = state.pool.begin().await.map_err(create_error)?;
     sqlx::query(
         "INSERT INTO copy_jobs
-         (id, bundle_version_id, target_tag, status, source_registry_id, target_registry_id, is_selective, base_copy_job_id, environment_id)
-         VALUES ($1, $2, $3, 'pending', $4, $5, TRUE, $6, $7)"
+         (id, bundle_version_id, target_tag, status, source_registry_id, target_registry_id, is_selective, base_copy_job_id, environment_id, overwrite_target_tag)
+         VALUES ($1, $2, $3, 'pending', $4, $5, TRUE, $6, $7, $8)"
     )
     .bind(job_id)
     .bind(bundle_version_id)
@@ -2291,16 +2828,11 @@ async fn start_selective_copy_job(
     .bind(target_registry_id)
     .bind(base_job_id)
     .bind(environment_id)
-    .execute(&state.pool)
+    .bind(overwrite)
+    .execute(&mut *tx)
     .await
-    .map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: format!("Failed to create selective copy job: {}", e),
-            }),
-        )
-    })?;
+    .map_err(create_error)?;
+    reserve_target_tags(&mut tx, job_id, target_registry_id, &target_images, &target_tag, overwrite).await?;
 
     for img in base_images {
         let is_selected = selected.contains(&img.id);
@@ -2341,7 +2873,7 @@ async fn start_selective_copy_job(
         .bind(source_registry_override)
         .bind(&img.target_image)
         .bind(&target_tag)
-        .fetch_one(&state.pool)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| {
             (
@@ -2352,6 +2884,7 @@ async fn start_selective_copy_job(
             )
         })?;
     }
+    tx.commit().await.map_err(create_error)?;
 
     Ok((
         StatusCode::ACCEPTED,
@@ -2362,18 +2895,268 @@ async fn start_selective_copy_job(
     ))
 }
 
+/// Patch release - vybrané images z jiného copy jobu, ostatní se přetagují z base release
+async fn start_selective_release_copy_job(
+    state: &CopyApiState,
+    base: SelectiveReleaseBase,
+    payload: SelectiveCopyRequest,
+) -> Result<(StatusCode, Json<CopyJobResponse>), (StatusCode, Json<ErrorResponse>)> {
+    let db_error = |e: sqlx::Error| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }),
+        )
+    };
+    let bad_request = |error: String| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error }));
+
+    let release_id = payload.release_id.as_deref().unwrap_or("").trim().to_string();
+    if release_id.is_empty() {
+        return Err(bad_request("Release ID is required for a patched release".to_string()));
+    }
+    let channel = release_channels::parse_optional(payload.channel.as_deref()).map_err(bad_request)?;
+    let Some(source_copy_job_id) = payload.source_copy_job_id else {
+        return Err(bad_request(
+            "Source copy job is required for a patched release".to_string(),
+        ));
+    };
+
+    let base_release = sqlx::query_as::<_, (Uuid, String)>(
+        "SELECT id, release_id FROM releases WHERE copy_job_id = $1 ORDER BY created_at DESC LIMIT 1",
+    )
+    .bind(base.job_id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(db_error)?;
+    let Some((_, base_release_id)) = base_release else {
+        return Err(bad_request("Base release job has no release".to_string()));
+    };
+
+    let release_exists = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM releases WHERE release_id = $1)"
+    )
+    .bind(&release_id)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(db_error)?;
+    if release_exists {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: format!("Release with ID '{}' already exists", release_id),
+            }),
+        ));
+    }
+
+    // Zdroj vybraných images - běžný copy job téhož bundlu do registry, ze které čte base release
+    let source_job = sqlx::query_as::<_, (String, bool, Option<Uuid>, Uuid)>(
+        "SELECT cj.status, cj.is_release_job, cj.target_registry_id, bv.bundle_id
+         FROM copy_jobs cj
+         JOIN bundle_versions bv ON bv.id = cj.bundle_version_id
+         WHERE cj.id = $1"
+    )
+    .bind(source_copy_job_id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(db_error)?;
+    let Some((source_status, source_is_release, source_target_registry_id, source_bundle_id)) = source_job else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Copy job with id {} not found", source_copy_job_id),
+            }),
+        ));
+    };
+    if source_status != "success" || source_is_release {
+        return Err(bad_request(
+            "Source copy job must be a successful normal copy job".to_string(),
+        ));
+    }
+    if source_bundle_id != base.bundle_id {
+        return Err(bad_request(
+            "Source copy job belongs to a different bundle".to_string(),
+        ));
+    }
+    if source_target_registry_id != Some(base.source_registry_id) {
+        return Err(bad_request(
+            "Source copy job must target the registry the base release was copied from".to_string(),
+        ));
+    }
+
+    let source_ref_mode: String = sqlx::query_scalar("SELECT source_ref_mode FROM copy_jobs WHERE id = $1")
+        .bind(base.job_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(db_error)?;
+    let digest_mode = source_ref_mode == "digest";
+
+    let base_images = sqlx::query_as::<_, CopyJobImage>(
+        "SELECT * FROM copy_job_images WHERE copy_job_id = $1 ORDER BY created_at"
+    )
+    .bind(base.job_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(db_error)?;
+    let patch_images = sqlx::query_as::<_, CopyJobImage>(
+        "SELECT * FROM copy_job_images WHERE copy_job_id = $1 ORDER BY created_at"
+    )
+    .bind(source_copy_job_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(db_error)?;
+
+    let selected: std::collections::HashSet<Uuid> = payload.selected_image_ids.into_iter().collect();
+    if selected.iter().any(|id| !base_images.iter().any(|img| &img.id == id)) {
+        return Err(bad_request(
+            "Some selected images do not belong to the base copy job".to_string(),
+        ));
+    }
+
+    // Base release job má jako source image cestu ve zdrojové registry; mapping id se mezi verzemi mění
+    let mut images: Vec<PatchReleaseImage> = Vec::with_capacity(base_images.len());
+    let mut missing = Vec::new();
+    for img in &base_images {
+        if selected.contains(&img.id) {
+            let patch = patch_images
+                .iter()
+                .find(|p| p.image_mapping_id == img.image_mapping_id)
+                .or_else(|| patch_images.iter().find(|p| p.target_image == img.source_image));
+            let Some(patch) = patch.filter(|p| !digest_mode || p.target_sha256.is_some()) else {
+                missing.push(img.source_image.clone());
+                continue;
+            };
+            images.push(PatchReleaseImage {
+                image_mapping_id: img.image_mapping_id,
+                source_image: patch.target_image.clone(),
+                source_tag: patch.target_tag.clone(),
+                source_registry_id: base.source_registry_id,
+                target_image: img.target_image.clone(),
+                source_sha256: patch.target_sha256.clone().filter(|_| digest_mode),
+            });
+        } else {
+            images.push(PatchReleaseImage {
+                image_mapping_id: img.image_mapping_id,
+                source_image: img.target_image.clone(),
+                source_tag: img.target_tag.clone(),
+                source_registry_id: base.target_registry_id,
+                target_image: img.target_image.clone(),
+                source_sha256: img.target_sha256.clone().filter(|_| digest_mode),
+            });
+        }
+    }
+    if !missing.is_empty() {
+        return Err(bad_request(format!(
+            "Source copy job has no usable image for: {}",
+            missing.join(", ")
+        )));
+    }
+    if digest_mode && images.iter().any(|img| img.source_sha256.is_none()) {
+        return Err(bad_request(
+            "Base release is missing digests; cannot patch it in digest mode".to_string(),
+        ));
+    }
+
+    let environment = sqlx::query_as::<_, Environment>("SELECT * FROM environments WHERE id = $1")
+        .bind(base.environment_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(db_error)?;
+    let policy_images: Vec<(Uuid, &str)> = images
+        .iter()
+        .map(|img| (img.source_registry_id, img.source_image.as_str()))
+        .collect();
+    enforce_release_image_policies(&state.pool, &environment, &policy_images).await?;
+
+    let job_id = Uuid::new_v4();
+    let notes = payload
+        .notes
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or_else(|| format!("Patch of release {}", base_release_id));
+    sqlx::query(
+        "INSERT INTO copy_jobs
+         (id, bundle_version_id, target_tag, status, source_registry_id, target_registry_id, source_ref_mode, is_release_job, is_selective, release_id, release_notes, environment_id, extra_tags, base_copy_job_id, release_channel)
+         SELECT $1, bundle_version_id, $2, 'pending', $3, $4, $5, TRUE, TRUE, $2, $6, $7, '{}', $8, $9
+         FROM copy_jobs WHERE id = $8"
+    )
+    .bind(job_id)
+    .bind(&release_id)
+    .bind(base.source_registry_id)
+    .bind(base.target_registry_id)
+    .bind(&source_ref_mode)
+    .bind(&notes)
+    .bind(base.environment_id)
+    .bind(base.job_id)
+    .bind(channel)
+    .execute(&state.pool)
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to create patched release copy job: {}", e),
+            }),
+        )
+    })?;
+
+    for img in &images {
+        let _: Uuid = sqlx::query_scalar(
+            "INSERT INTO copy_job_images
+             (copy_job_id, image_mapping_id, source_image, source_tag, source_registry_id, target_image, target_tag, source_sha256)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+             RETURNING id"
+        )
+        .bind(job_id)
+        .bind(img.image_mapping_id)
+        .bind(&img.source_image)
+        .bind(&img.source_tag)
+        .bind(img.source_registry_id)
+        .bind(&img.target_image)
+        .bind(&release_id)
+        .bind(&img.source_sha256)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Failed to snapshot patched release images: {}", e),
+                }),
+            )
+        })?;
+    }
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(CopyJobResponse {
+            job_id,
+            message: format!(
+                "Patched release copy job created ({} of {} images from source, release {})",
+                selected.len(),
+                images.len(),
+                release_id
+            ),
+        }),
+    ))
+}
+
 /// GET /api/v1/copy/jobs - seznam copy jobů
 async fn list_copy_jobs(
     Extension(auth): Extension<AuthContext>,
     State(state): State<CopyApiState>,
-) -> Result<Json<Vec<CopyJobSummary>>, (StatusCode, Json<ErrorResponse>)> {
-    let jobs = if auth.is_admin() {
-        sqlx::query_as::<_, CopyJobSummary>(
-            r#"
+    Query(query): Query<JobListQuery>,
+) -> Result<(axum::http::HeaderMap, Json<Vec<CopyJobSummary>>), (StatusCode, Json<ErrorResponse>)> {
+    let refreshed_at = if query.fresh.unwrap_or(false) {
+        None
+    } else {
+        dashboard_views::usable_summary(&state.pool, "copy_job_list_mv").await
+    };
+    let live = r#"
             SELECT
                 cj.id AS job_id,
                 bv.bundle_id,
                 b.name AS bundle_name,
+                b.tenant_id,
                 bv.version,
                 cj.target_tag,
                 cj.status,
@@ -2389,39 +3172,25 @@ async fn list_copy_jobs(
             FROM copy_jobs cj
             JOIN bundle_versions bv ON bv.id = cj.bundle_version_id
             JOIN bundles b ON b.id = bv.bundle_id
-            ORDER BY cj.started_at DESC
-            LIMIT 100
-            "#,
-        )
+        "#;
+    let source = if refreshed_at.is_some() {
+        dashboard_views::job_list_source("copy_job_list_mv", "job_id", live)
+    } else {
+        format!("({}) jobs", live)
+    };
+
+    let jobs = if auth.is_admin() {
+        sqlx::query_as::<_, CopyJobSummary>(&format!(
+            "SELECT * FROM {} ORDER BY started_at DESC LIMIT 100",
+            source
+        ))
         .fetch_all(&state.pool)
         .await
     } else {
-        sqlx::query_as::<_, CopyJobSummary>(
-            r#"
-            SELECT
-                cj.id AS job_id,
-                bv.bundle_id,
-                b.name AS bundle_name,
-                bv.version,
-                cj.target_tag,
-                cj.status,
-                cj.is_release_job,
-                cj.is_selective,
-                cj.base_copy_job_id,
-                cj.validate_only,
-                cj.source_registry_id,
-                cj.target_registry_id,
-                cj.environment_id,
-                cj.started_at,
-                cj.completed_at
-            FROM copy_jobs cj
-            JOIN bundle_versions bv ON bv.id = cj.bundle_version_id
-            JOIN bundles b ON b.id = bv.bundle_id
-            WHERE b.tenant_id = ANY($1)
-            ORDER BY cj.started_at DESC
-            LIMIT 100
-            "#,
-        )
+        sqlx::query_as::<_, CopyJobSummary>(&format!(
+            "SELECT * FROM {} WHERE tenant_id = ANY($1) ORDER BY started_at DESC LIMIT 100",
+            source
+        ))
         .bind(&auth.tenant_ids)
         .fetch_all(&state.pool)
         .await
@@ -2435,7 +3204,7 @@ async fn list_copy_jobs(
         )
     })?;
 
-    Ok(Json(jobs))
+    Ok((dashboard_views::freshness_headers(refreshed_at), Json(jobs)))
 }
 
 /// GET /api/v1/copy/jobs/compare?job_a=...&job_b=... - porovnání digestů mezi dvěma copy joby
@@ -2629,13 +3398,101 @@ async fn compare_copy_jobs(
     Ok(Json(results))
 }
 
-/// POST /api/v1/copy/jobs/{job_id}/start - Spustí pending copy job
-async fn start_copy_job(
+/// POST /api/v1/copy/jobs/{job_id}/start - Spustí pending copy job (v roli `web` ho zařadí do fronty)
+pub(crate) async fn start_copy_job(
     State(state): State<CopyApiState>,
     Path(job_id): Path<Uuid>,
 ) -> Result<(StatusCode, Json<CopyJobResponse>), (StatusCode, Json<ErrorResponse>)> {
-    let job = sqlx::query_as::<_, (String, Option<Uuid>, Option<Uuid>, String, String, bool, Option<String>, Option<String>, bool, Option<Uuid>, Option<Vec<String>>, Option<Uuid>)>(
-        "SELECT status, source_registry_id, target_registry_id, target_tag, source_ref_mode, is_release_job, release_id, release_notes, validate_only, environment_id, extra_tags, base_copy_job_id
+    // Prostředí s agentem: job vždy jen do fronty, převezme ho agent
+    let has_agent = job_queue::job_agent(&state.pool, JobKind::Copy, job_id)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Database error: {}", e),
+                }),
+            )
+        })?
+        .is_some();
+    if state.dispatch == JobDispatch::Queue || has_agent {
+        return enqueue_copy_job(&state, job_id).await;
+    }
+    launch_copy_job(state, job_id).await
+}
+
+async fn enqueue_copy_job(
+    state: &CopyApiState,
+    job_id: Uuid,
+) -> Result<(StatusCode, Json<CopyJobResponse>), (StatusCode, Json<ErrorResponse>)> {
+    let db_error = |e: sqlx::Error| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }),
+        )
+    };
+    let Some((status, queued)) = job_queue::queue_state(&state.pool, JobKind::Copy, job_id)
+        .await
+        .map_err(db_error)?
+    else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Copy job with id {} not found", job_id),
+            }),
+        ));
+    };
+    if status != "pending" || queued || !job_queue::enqueue(&state.pool, JobKind::Copy, job_id).await.map_err(db_error)? {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Copy job is not pending".to_string(),
+            }),
+        ));
+    }
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(CopyJobResponse {
+            job_id,
+            message: "Copy job queued".to_string(),
+        }),
+    ))
+}
+
+/// Copy job načtený a ověřený z DB, s dešifrovanými credentials - připravený ke spuštění
+/// v tomto procesu nebo na agentovi
+pub(crate) struct PreparedCopyJob {
+    pub images: Vec<CopyJobImage>,
+    pub source_registry_id: Uuid,
+    /// Registry id -> (host, username, password)
+    pub source_registry_info: HashMap<Uuid, (String, Option<String>, Option<String>)>,
+    pub target_registry_id: Uuid,
+    pub target_base_url: String,
+    pub target_username: Option<String>,
+    pub target_password: Option<String>,
+    pub target_tag: String,
+    pub extra_tags: Vec<String>,
+    pub source_ref_mode: String,
+    pub copy_options: image_tool::CopyOptions,
+    pub validate_only: bool,
+    pub overwrite_target_tag: bool,
+    pub is_release_job: bool,
+    /// Cílové image, jejichž release tag je v karanténě (shodě digestu se nevěří)
+    pub quarantined_images: HashSet<String>,
+    /// Přepsaná binárka image toolu (environment / tenant)
+    pub skopeo_path: Option<String>,
+}
+
+/// Validuje pending copy job a načte vše potřebné ke spuštění
+pub(crate) async fn prepare_copy_job(
+    state: &CopyApiState,
+    job_id: Uuid,
+) -> Result<PreparedCopyJob, (StatusCode, Json<ErrorResponse>)> {
+    let job = sqlx::query_as::<_, (String, Option<Uuid>, Option<Uuid>, String, String, bool, bool, Option<Uuid>, Option<Vec<String>>, Option<Uuid>)>(
+        "SELECT status, source_registry_id, target_registry_id, target_tag, source_ref_mode, is_release_job, validate_only, environment_id, extra_tags, base_copy_job_id
          FROM copy_jobs WHERE id = $1"
     )
     .bind(job_id)
@@ -2650,7 +3507,7 @@ async fn start_copy_job(
         )
     })?;
 
-    let Some((status, source_registry_id, target_registry_id, target_tag, source_ref_mode, is_release_job, release_id, release_notes, validate_only, environment_id, extra_tags, base_copy_job_id)) = job else {
+    let Some((status, source_registry_id, target_registry_id, target_tag, source_ref_mode, is_release_job, validate_only, environment_id, extra_tags, base_copy_job_id)) = job else {
         return Err((
             StatusCode::NOT_FOUND,
             Json(ErrorResponse {
@@ -2668,6 +3525,19 @@ async fn start_copy_job(
         ));
     }
 
+    let overwrite_target_tag: bool = sqlx::query_scalar("SELECT overwrite_target_tag FROM copy_jobs WHERE id = $1")
+        .bind(job_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Database error: {}", e),
+                }),
+            )
+        })?;
+
     let (Some(source_registry_id), Some(target_registry_id)) = (source_registry_id, target_registry_id) else {
         return Err((
             StatusCode::BAD_REQUEST,
@@ -2679,8 +3549,12 @@ async fn start_copy_job(
 
     let source_env_id = if is_release_job {
         if let Some(base_id) = base_copy_job_id {
+            // Patch release má jako base release job - zdrojové prostředí je o úroveň níž
             sqlx::query_scalar::<_, Option<Uuid>>(
-                "SELECT environment_id FROM copy_jobs WHERE id = $1",
+                "SELECT COALESCE(CASE WHEN b.is_release_job THEN src.environment_id END, b.environment_id)
+                 FROM copy_jobs b
+                 LEFT JOIN copy_jobs src ON src.id = b.base_copy_job_id
+                 WHERE b.id = $1",
             )
             .bind(base_id)
             .fetch_optional(&state.pool)
@@ -2741,53 +3615,9 @@ async fn start_copy_job(
         }
     }
 
-    let (log_tx, _log_rx) = broadcast::channel(512);
-    state.job_logs.write().await.insert(job_id, log_tx.clone());
-
-    // Persist logs to DB
-    let pool_for_log = state.pool.clone();
-    let mut log_rx = log_tx.subscribe();
-    tokio::spawn(async move {
-        while let Ok(line) = log_rx.recv().await {
-            if let Some(progress) = parse_progress_marker(&line) {
-                let stage = progress.phase.or(progress.stage);
-                let message = progress.message.or(progress.r#ref);
-                let bytes_copied = progress.current.map(|v| v as i64);
-                let total_bytes = progress.total.map(|v| v as i64);
-                let _ = sqlx::query(
-                    "UPDATE copy_jobs
-                     SET current_transfer_stage = $2,
-                         current_transfer_message = $3,
-                         current_bytes_copied = $4,
-                         current_total_bytes = $5
-                     WHERE id = $1",
-                )
-                .bind(job_id)
-                .bind(stage)
-                .bind(message)
-                .bind(bytes_copied)
-                .bind(total_bytes)
-                .execute(&pool_for_log)
-                .await;
-                continue;
-            }
-            let _ = sqlx::query(
-                "INSERT INTO copy_job_logs (copy_job_id, line) VALUES ($1, $2)",
-            )
-            .bind(job_id)
-            .bind(line)
-            .execute(&pool_for_log)
-            .await;
-        }
-    });
-
-    let pool_clone = state.pool.clone();
-    let skopeo_clone = state.skopeo.clone();
-    let log_state_clone = state.job_logs.clone();
-
     let extra_tags = extra_tags.unwrap_or_default();
 
-    let mut source_registry_ids = std::collections::HashSet::new();
+    let mut source_registry_ids = HashSet::new();
     source_registry_ids.insert(source_registry_id);
     for img in &images {
         if let Some(id) = img.source_registry_id {
@@ -2795,7 +3625,7 @@ async fn start_copy_job(
         }
     }
 
-    let mut source_registry_info: std::collections::HashMap<Uuid, (String, Option<String>, Option<String>)> = std::collections::HashMap::new();
+    let mut source_registry_info: HashMap<Uuid, (String, Option<String>, Option<String>)> = HashMap::new();
     for registry_id in source_registry_ids {
         let registry: (String,) = sqlx::query_as(
             "SELECT base_url FROM registries WHERE id = $1",
@@ -2811,8 +3641,14 @@ async fn start_copy_job(
                 }),
             )
         })?;
+        // Čtení z cílové registry (přetagování) používá credentials cílového prostředí
+        let credentials_env_id = if registry_id == target_registry_id && registry_id != source_registry_id {
+            environment_id
+        } else {
+            source_env_id
+        };
         let (username, password) = state
-            .get_registry_credentials(registry_id, source_env_id)
+            .get_registry_credentials(registry_id, credentials_env_id)
             .await
             .map_err(|e| {
             (
@@ -2858,15 +3694,346 @@ async fn start_copy_job(
     })?;
 
     let target_base_url = target_registry.0.trim_start_matches("https://").trim_start_matches("http://").to_string();
-    let release_id = release_id.clone();
-    let release_notes = release_notes.clone();
-    let source_ref_mode = source_ref_mode.clone();
+
+    let copy_options = effective_copy_options(&state.pool, &state.skopeo.copy_defaults, job_id)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Database error: {}", e),
+                }),
+            )
+        })?;
+
+    let quarantined_images = if is_release_job {
+        release_tag_cleanup::quarantined_images(&state.pool, target_registry_id, &target_tag)
+            .await
+            .map_err(|e| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse {
+                        error: format!("Database error: {}", e),
+                    }),
+                )
+            })?
+    } else {
+        HashSet::new()
+    };
+
+    let skopeo_path = tool_paths::for_copy_job(&state.pool, environment_id, target_registry_id)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Database error: {}", e),
+                }),
+            )
+        })?
+        .skopeo_path;
+
+    Ok(PreparedCopyJob {
+        images,
+        source_registry_id,
+        source_registry_info,
+        target_registry_id,
+        target_base_url,
+        target_username,
+        target_password,
+        target_tag,
+        extra_tags,
+        source_ref_mode,
+        copy_options,
+        validate_only,
+        overwrite_target_tag,
+        is_release_job,
+        quarantined_images,
+        skopeo_path,
+    })
+}
+
+/// Retry politika a copy flagy jobu: globální default -> cílová registry -> job
+pub(crate) async fn effective_copy_options(
+    pool: &PgPool,
+    defaults: &image_tool::CopyOptions,
+    job_id: Uuid,
+) -> Result<image_tool::CopyOptions, sqlx::Error> {
+    let (registry_copy_options, job_copy_options) = sqlx::query_as::<_, (Option<serde_json::Value>, Option<serde_json::Value>)>(
+        "SELECT r.copy_options, cj.copy_options FROM copy_jobs cj
+         LEFT JOIN registries r ON r.id = cj.target_registry_id
+         WHERE cj.id = $1",
+    )
+    .bind(job_id)
+    .fetch_optional(pool)
+    .await?
+    .unwrap_or_default();
+    let parse_override = |value: Option<serde_json::Value>| {
+        value.and_then(|value| serde_json::from_value::<CopyOptionsOverride>(value).ok())
+    };
+    Ok(defaults
+        .clone()
+        .with_override(parse_override(registry_copy_options).as_ref())
+        .with_override(parse_override(job_copy_options).as_ref()))
+}
+
+/// Cílová registry pro mazání release tagů (jen když job běžel v tomto procesu)
+pub(crate) struct ReleaseTagRegistry<'a> {
+    pub skopeo: &'a ImageToolService,
+    pub credentials: &'a SkopeoCredentials,
+    pub target_base_url: &'a str,
+}
+
+/// Kompenzace selhaného / zrušeného release jobu: release tagy, které job stihl zapsat,
+/// smaže z registry nebo zaeviduje v karanténě. Vrací řádky do logu jobu.
+pub(crate) async fn cleanup_release_tags(
+    pool: &PgPool,
+    job_id: Uuid,
+    mode: ReleaseTagCleanup,
+    registry: Option<ReleaseTagRegistry<'_>>,
+) -> Vec<String> {
+    if mode == ReleaseTagCleanup::Off {
+        return Vec::new();
+    }
+    let tags = match release_tag_cleanup::pushed_tags(pool, job_id).await {
+        Ok(tags) => tags,
+        Err(e) => return vec![format!("WARN release tag cleanup skipped: {}", e)],
+    };
+    if tags.is_empty() {
+        return Vec::new();
+    }
+
+    let mut lines = vec![format!(
+        "Release tag cleanup ({}): {} tag(s) pushed before the job ended",
+        mode.as_str(),
+        tags.len()
+    )];
+    let can_delete = registry.as_ref().is_some_and(|registry| registry.skopeo.supports_delete());
+    if mode == ReleaseTagCleanup::Delete && !can_delete {
+        lines.push(match &registry {
+            Some(_) => "Image tool cannot delete images - quarantining instead".to_string(),
+            None => "Job did not run in this process (agent) - quarantining instead of deleting".to_string(),
+        });
+    }
+    let mut deleted = Vec::new();
+    let mut quarantined = Vec::new();
+    for tag in &tags {
+        let reference = format!("{}:{}", tag.target_image, tag.target_tag);
+        let Some(action) = release_tag_cleanup::action_for(mode, tag, can_delete) else {
+            continue;
+        };
+        if action == CleanupAction::Delete
+            && let Some(registry) = &registry
+        {
+            let url = format!("{}/{}", registry.target_base_url, reference);
+            match registry.skopeo.delete_image(&url, registry.credentials).await {
+                Ok(()) => {
+                    lines.push(format!("DELETED {}", url));
+                    deleted.push(reference);
+                    continue;
+                }
+                Err(err) => lines.push(format!("WARN failed to delete {} ({}) - quarantining", url, err)),
+            }
+        } else if mode == ReleaseTagCleanup::Delete && tag.digest_shared {
+            lines.push(format!(
+                "Digest {} of {} is used by another job - quarantining instead of deleting",
+                tag.digest, reference
+            ));
+        }
+        match release_tag_cleanup::quarantine(pool, job_id, tag).await {
+            Ok(()) => {
+                lines.push(format!("QUARANTINED {} ({})", reference, tag.digest));
+                quarantined.push(reference);
+            }
+            Err(e) => lines.push(format!("WARN failed to quarantine {}: {}", reference, e)),
+        }
+    }
+
+    if let Err(e) = release_tag_cleanup::record_event(pool, job_id, mode, &deleted, &quarantined).await {
+        tracing::warn!("Failed to record release tag cleanup of copy job {}: {}", job_id, e);
+    }
+    lines
+}
+
+/// Uloží řádek logu copy jobu; progress markery jen aktualizují stav přenosu
+pub(crate) async fn persist_copy_log_line(pool: &PgPool, fanout: &LogFanout, job_id: Uuid, line: &str) {
+    if let Some(progress) = parse_progress_marker(line) {
+        let _ = fanout.publish_line(pool, JobKind::Copy, job_id, line).await;
+        let stage = progress.phase.or(progress.stage);
+        let message = progress.message.or(progress.r#ref);
+        let bytes_copied = progress.current.map(|v| v as i64);
+        let total_bytes = progress.total.map(|v| v as i64);
+        let _ = sqlx::query(
+            "UPDATE copy_jobs
+             SET current_transfer_stage = $2,
+                 current_transfer_message = $3,
+                 current_bytes_copied = $4,
+                 current_total_bytes = $5
+             WHERE id = $1",
+        )
+        .bind(job_id)
+        .bind(stage)
+        .bind(message)
+        .bind(bytes_copied)
+        .bind(total_bytes)
+        .execute(pool)
+        .await;
+        return;
+    }
+    let _ = fanout.persist_line(pool, JobKind::Copy, job_id, line).await;
+}
+
+/// Copy job přerušený pádem workeru - nedokončené images a celý job jsou failed
+pub(crate) async fn fail_interrupted_copy_job(state: &CopyApiState, job_id: Uuid, reason: &str) {
+    persist_copy_log_line(&state.pool, &state.log_fanout, job_id, reason).await;
+    let _ = sqlx::query(
+        "UPDATE copy_job_images
+         SET copy_status = 'failed', error_message = $2
+         WHERE copy_job_id = $1 AND copy_status IN ('pending', 'in_progress')",
+    )
+    .bind(job_id)
+    .bind(reason)
+    .execute(&state.pool)
+    .await;
+    finish_copy_job(&state.pool, &state.notifier, job_id, 1, false).await;
+    let _ = state.log_fanout.end_log(&state.pool, JobKind::Copy, job_id).await;
+}
+
+/// Uzavře doběhnutý copy job: stav, notifikace vlastníků a u úspěšného release jobu založení release
+pub(crate) async fn finish_copy_job(pool: &PgPool, notifier: &Notifier, job_id: Uuid, failed: usize, cancelled: bool) {
+    if cancelled {
+        let _ = sqlx::query(
+            "UPDATE copy_jobs SET status = 'cancelled', completed_at = NOW() WHERE id = $1",
+        )
+        .bind(job_id)
+        .execute(pool)
+        .await;
+
+        let _ = sqlx::query(
+            "UPDATE copy_job_images
+             SET copy_status = 'cancelled', error_message = 'Cancelled'
+             WHERE copy_job_id = $1 AND copy_status IN ('pending', 'in_progress')",
+        )
+        .bind(job_id)
+        .execute(pool)
+        .await;
+    } else {
+        let _ = sqlx::query(
+            "UPDATE copy_jobs
+             SET status = $1, completed_at = NOW()
+             WHERE id = $2"
+        )
+        .bind(if failed == 0 { "success" } else { "failed" })
+        .bind(job_id)
+        .execute(pool)
+        .await;
+
+        if failed > 0
+            && let Err(e) = owner_notifications::notify_failed_images(pool, notifier, job_id).await
+        {
+            tracing::warn!("Failed to notify owners of copy job {}: {}", job_id, e);
+        }
+    }
+    lifecycle_notifications::copy_job(pool, notifier, &COPY_JOB_FINISHED, job_id).await;
+
+    if cancelled || failed > 0 {
+        return;
+    }
+    let release = sqlx::query_as::<_, (bool, Option<String>, Option<String>, String, Option<Vec<String>>)>(
+        "SELECT is_release_job, release_id, release_notes, source_ref_mode, extra_tags FROM copy_jobs WHERE id = $1",
+    )
+    .bind(job_id)
+    .fetch_optional(pool)
+    .await;
+    if let Ok(Some((true, Some(release_id), release_notes, source_ref_mode, extra_tags))) = release {
+        // Patch release (selective nad release jobem) si pamatuje base release a bez
+        // explicitního kanálu zůstává v jeho kanálu
+        let created = sqlx::query_scalar::<_, Uuid>(
+            "WITH base AS (
+                 SELECT r.id, r.channel FROM copy_jobs cj
+                 JOIN releases r ON r.copy_job_id = cj.base_copy_job_id
+                 WHERE cj.id = $1 AND cj.is_selective
+                 ORDER BY r.created_at DESC
+                 LIMIT 1
+             )
+             INSERT INTO releases (copy_job_id, release_id, status, source_ref_mode, notes, is_auto, extra_tags, base_release_id, channel)
+             SELECT $1, $2, 'draft', $3, $4, false, $5, (SELECT id FROM base),
+                    COALESCE(cj.release_channel, (SELECT channel FROM base), 'stable')
+             FROM copy_jobs cj WHERE cj.id = $1
+             RETURNING id"
+        )
+        .bind(job_id)
+        .bind(&release_id)
+        .bind(&source_ref_mode)
+        .bind(&release_notes)
+        .bind(extra_tags.unwrap_or_default())
+        .fetch_one(pool)
+        .await;
+        if let Ok(release_db_id) = created {
+            release_manifest::store_manifest_snapshot(pool, release_db_id).await;
+            lifecycle_notifications::release_created(pool, notifier, release_db_id).await;
+        }
+        if let Err(e) = release_tag_cleanup::release_quarantine(pool, job_id).await {
+            tracing::warn!("Failed to release quarantined tags of copy job {}: {}", job_id, e);
+        }
+    }
+}
+
+/// Validuje a spustí copy job v aktuálním procesu (API v roli `all`, worker)
+pub(crate) async fn launch_copy_job(
+    state: CopyApiState,
+    job_id: Uuid,
+) -> Result<(StatusCode, Json<CopyJobResponse>), (StatusCode, Json<ErrorResponse>)> {
+    let PreparedCopyJob {
+        images,
+        source_registry_id,
+        source_registry_info,
+        target_registry_id,
+        target_base_url,
+        target_username,
+        target_password,
+        target_tag,
+        extra_tags,
+        source_ref_mode,
+        copy_options,
+        validate_only,
+        overwrite_target_tag,
+        is_release_job,
+        quarantined_images,
+        skopeo_path,
+    } = prepare_copy_job(&state, job_id).await?;
+
+    let (log_tx, _log_rx) = broadcast::channel(512);
+    state.job_logs.write().await.insert(job_id, log_tx.clone());
+
+    // Persist logs to DB (+ fan-out do ostatních instancí)
+    let pool_for_log = state.pool.clone();
+    let fanout = state.log_fanout.clone();
+    let mut log_rx = log_tx.subscribe();
+    tokio::spawn(async move {
+        while let Ok(line) = log_rx.recv().await {
+            persist_copy_log_line(&pool_for_log, &fanout, job_id, &line).await;
+        }
+        let _ = fanout.end_log(&pool_for_log, JobKind::Copy, job_id).await;
+    });
+
+    let pool_clone = state.pool.clone();
+    let skopeo_clone = match skopeo_path {
+        Some(path) => state.skopeo.clone().with_tool_path(path),
+        None => state.skopeo.clone(),
+    };
+    let log_state_clone = state.job_logs.clone();
     let cancel_flags = state.cancel_flags.clone();
+    let notifier = state.notifier.clone();
 
     tokio::spawn(async move {
         let mut failed = 0;
         let mut cancelled = false;
         emit_log(&log_tx, format!("Starting copy job {} ({} images)", job_id, images.len()));
+        emit_log(&log_tx, format!("Copy options: {}", copy_options.summary()));
+        if skopeo_clone.image_tool_path != state.skopeo.image_tool_path {
+            emit_log(&log_tx, format!("Image tool override: {}", skopeo_clone.image_tool_path));
+        }
 
         if cancel_flags.read().await.contains(&job_id) {
             cancelled = true;
@@ -2885,6 +4052,7 @@ async fn start_copy_job(
             .bind(job_id)
             .execute(&pool_clone)
             .await;
+        lifecycle_notifications::copy_job(&pool_clone, &notifier, &COPY_JOB_STARTED, job_id).await;
 
         for img in images {
             if cancel_flags.read().await.contains(&job_id) {
@@ -2937,7 +4105,7 @@ async fn start_copy_job(
 
             emit_log(&log_tx, format!("Copying {} -> {}", source_url, target_url));
 
-            let _ = sqlx::query("UPDATE copy_job_images SET copy_status = 'in_progress' WHERE id = $1")
+            let _ = sqlx::query("UPDATE copy_job_images SET copy_status = 'in_progress', started_at = NOW() WHERE id = $1")
                 .bind(img.id)
                 .execute(&pool_clone)
                 .await;
@@ -2983,6 +4151,7 @@ async fn start_copy_job(
                 continue;
             }
 
+            let quarantined = quarantined_images.contains(&img.target_image);
             if let Some(ref src_digest) = source_sha {
                 emit_log(&log_tx, format!("Checking whether target tag already exists: {}", target_url));
                 match skopeo_clone.inspect_image(
@@ -2991,7 +4160,7 @@ async fn start_copy_job(
                     credentials.target_password.as_deref(),
                 ).await {
                     Ok(info) => {
-                        if info.digest == *src_digest {
+                        if info.digest == *src_digest && !quarantined {
                             let _ = sqlx::query(
                                 "UPDATE copy_job_images
                                  SET copy_status = 'success',
@@ -3042,8 +4211,7 @@ async fn start_copy_job(
                                             &source_url,
                                             &extra_target_url,
                                             &credentials,
-                                            3,
-                                            10,
+                                            &copy_options,
                                             Some(&log_tx),
                                         )
                                         .await
@@ -3082,6 +4250,31 @@ async fn start_copy_job(
                             }
                             continue;
                         }
+                        if !overwrite_target_tag && !is_release_job {
+                            let message = format!(
+                                "Target tag already exists with a different digest ({}); set overwrite=true to replace it",
+                                info.digest
+                            );
+                            let _ = sqlx::query(
+                                "UPDATE copy_job_images
+                                 SET copy_status = 'failed', source_sha256 = $1, target_sha256 = $2, error_message = $3
+                                 WHERE id = $4"
+                            )
+                            .bind(&source_sha)
+                            .bind(&info.digest)
+                            .bind(&message)
+                            .bind(img.id)
+                            .execute(&pool_clone)
+                            .await;
+                            failed += 1;
+                            emit_log(&log_tx, format!("FAILED {} - {}", target_url, message));
+                            continue;
+                        }
+                        if info.digest == *src_digest {
+                            emit_log(&log_tx, "Target tag is quarantined after a failed release job - copying again".to_string());
+                        } else {
+                            emit_log(&log_tx, format!("Target tag has a different digest ({}) - overwriting", info.digest));
+                        }
                     }
                     Err(err) => {
                         if is_missing_target_manifest_error(&err.to_string()) {
@@ -3166,13 +4359,39 @@ async fn start_copy_job(
                 }
             }
 
+            // Vrstvy z předchozích release v cílové registry se nepřenáší znovu
+            let reuse = if copy_options.blob_reuse && copy_options.format.is_none() {
+                let mount_from: Vec<String> = blob_reuse::mount_sources(&pool_clone, target_registry_id, &img.target_image)
+                    .await
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|image| format!("{}/{}", target_base_url, image))
+                    .collect();
+                let options = ReuseOptions {
+                    all: copy_options.all,
+                    src_insecure: skopeo_clone.src_insecure,
+                    dst_insecure: skopeo_clone.dst_insecure,
+                };
+                match blob_reuse::prepare_target(&source_url, &target_url, &credentials, &mount_from, options).await {
+                    Ok(report) => {
+                        emit_log(&log_tx, format!("Layer reuse: {}", report.summary()));
+                        Some(report)
+                    }
+                    Err(err) => {
+                        emit_log(&log_tx, format!("WARN layer reuse check failed ({}) - copying all layers", err));
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+
             match skopeo_clone
                 .copy_image_with_retry(
                     &source_url,
                     &target_url,
                     &credentials,
-                    3,
-                    10,
+                    &copy_options,
                     Some(&log_tx),
                 )
                 .await
@@ -3192,11 +4411,17 @@ async fn start_copy_job(
                          SET copy_status = 'success',
                              source_sha256 = $1,
                              target_sha256 = $2,
-                             copied_at = NOW()
-                         WHERE id = $3"
+                             copied_at = NOW(),
+                             estimated_transfer_bytes = $3,
+                             bytes_reused = $4,
+                             blobs_mounted = $5
+                         WHERE id = $6"
                     )
                     .bind(&source_sha)
                     .bind(&target_sha)
+                    .bind(reuse.as_ref().map(|r| r.estimated_transfer_bytes() as i64))
+                    .bind(reuse.as_ref().map(|r| r.reused_bytes() as i64))
+                    .bind(reuse.as_ref().map(|r| r.mounted as i32))
                     .bind(img.id)
                     .execute(&pool_clone)
                     .await;
@@ -3238,8 +4463,7 @@ async fn start_copy_job(
                                     &source_url,
                                     &extra_target_url,
                                     &credentials,
-                                    3,
-                                    10,
+                                    &copy_options,
                                     Some(&log_tx),
                                 )
                                 .await
@@ -3310,50 +4534,25 @@ async fn start_copy_job(
             }
         }
 
-        if cancelled {
-            let _ = sqlx::query(
-                "UPDATE copy_jobs SET status = 'cancelled', completed_at = NOW() WHERE id = $1",
-            )
-            .bind(job_id)
-            .execute(&pool_clone)
-            .await;
-
-            let _ = sqlx::query(
-                "UPDATE copy_job_images
-                 SET copy_status = 'cancelled', error_message = 'Cancelled'
-                 WHERE copy_job_id = $1 AND copy_status IN ('pending', 'in_progress')",
-            )
-            .bind(job_id)
-            .execute(&pool_clone)
-            .await;
-        } else {
-            let _ = sqlx::query(
-                "UPDATE copy_jobs
-                 SET status = $1, completed_at = NOW()
-                 WHERE id = $2"
-            )
-            .bind(if failed == 0 { "success" } else { "failed" })
-            .bind(job_id)
-            .execute(&pool_clone)
-            .await;
-        }
-
-        if !cancelled && failed == 0 && is_release_job {
-            if let Some(release_id) = release_id {
-                let _ = sqlx::query(
-                    "INSERT INTO releases (copy_job_id, release_id, status, source_ref_mode, notes, is_auto, extra_tags)
-                     VALUES ($1, $2, 'draft', $3, $4, false, $5)"
-                )
-                .bind(job_id)
-                .bind(&release_id)
-                .bind(&source_ref_mode)
-                .bind(&release_notes)
-                .bind(&extra_tags)
-                .execute(&pool_clone)
-                .await;
+        if is_release_job && !validate_only && (cancelled || failed > 0) {
+            let credentials = SkopeoCredentials {
+                source_username: None,
+                source_password: None,
+                target_username: target_username.clone(),
+                target_password: target_password.clone(),
+            };
+            let registry = ReleaseTagRegistry {
+                skopeo: &skopeo_clone,
+                credentials: &credentials,
+                target_base_url: &target_base_url,
+            };
+            for line in cleanup_release_tags(&pool_clone, job_id, copy_options.release_tag_cleanup, Some(registry)).await {
+                emit_log(&log_tx, line);
             }
         }
 
+        finish_copy_job(&pool_clone, &notifier, job_id, failed, cancelled).await;
+
         emit_log(&log_tx, "Copy job finished".to_string());
         log_state_clone.write().await.remove(&job_id);
         cancel_flags.write().await.remove(&job_id);
@@ -3426,6 +4625,11 @@ async fn cancel_copy_job(
     if let Some(sender) = state.job_logs.read().await.get(&job_id) {
         let _ = sender.send("Cancel requested".to_string());
     }
+    // Běžící job ohlásí konec sám ve finish_copy_job, nespuštěný už nikdo nepřevezme
+    if status == "pending" {
+        let _ = state.log_fanout.end_log(&state.pool, JobKind::Copy, job_id).await;
+        lifecycle_notifications::copy_job(&state.pool, &state.notifier, &COPY_JOB_FINISHED, job_id).await;
+    }
 
     Ok((
         StatusCode::ACCEPTED,
@@ -3511,9 +4715,11 @@ async fn copy_job_stream_sse(
                     let logs = state.job_logs.read().await;
                     logs.get(&job_id).cloned()
                 };
-                if let Some(sender) = sender {
-                    rx = Some(sender.subscribe());
-                }
+                rx = match sender {
+                    Some(sender) => Some(sender.subscribe()),
+                    // Job běží na jiné instanci
+                    None => state.log_fanout.subscribe(&state.pool, JobKind::Copy, job_id).await,
+                };
             }
 
             tokio::select! {
@@ -3625,9 +4831,12 @@ async fn copy_job_logs_sse(
         let logs = state.job_logs.read().await;
         logs.get(&job_id).cloned()
     };
+    let receiver = match sender {
+        Some(sender) => Some(sender.subscribe()),
+        None => state.log_fanout.subscribe(&state.pool, JobKind::Copy, job_id).await,
+    };
 
-    let stream: BoxStream<'static, Result<Event, Infallible>> = if let Some(sender) = sender {
-        let rx = sender.subscribe();
+    let stream: BoxStream<'static, Result<Event, Infallible>> = if let Some(rx) = receiver {
         stream::unfold(rx, |mut rx| async move {
             match rx.recv().await {
                 Ok(line) => {
@@ -3652,25 +4861,156 @@ async fn copy_job_logs_sse(
     Sse::new(stream).keep_alive(KeepAlive::default())
 }
 
-/// GET /api/v1/copy/jobs/{job_id}/logs/history - celé uložené logy
-async fn copy_job_logs_history(
+/// GET /api/v1/copy/jobs/{job_id}/logs/stream?after_seq= - historie od offsetu + živé logy v jednom SSE streamu
+async fn copy_job_logs_stream_sse(
     State(state): State<CopyApiState>,
     Path(job_id): Path<Uuid>,
-) -> Result<Json<Vec<String>>, (StatusCode, Json<ErrorResponse>)> {
-    let lines = sqlx::query_scalar::<_, String>(
-        "SELECT line FROM copy_job_logs WHERE copy_job_id = $1 ORDER BY created_at",
-    )
-    .bind(job_id)
-    .fetch_all(&state.pool)
-    .await
-    .map_err(|e| {
+    Query(query): Query<LogStreamQuery>,
+    headers: HeaderMap,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let last_event_id = headers.get("last-event-id").and_then(|value| value.to_str().ok());
+    let after_seq = job_logs::stream_start_seq(last_event_id, &query);
+
+    // Živý kanál slouží jen jako wakeup, řádky se vždy čtou z DB podle seq
+    let sender = {
+        let logs = state.job_logs.read().await;
+        logs.get(&job_id).cloned()
+    };
+    let wakeup = match sender {
+        Some(sender) => Some(sender.subscribe()),
+        None => state.log_fanout.subscribe(&state.pool, JobKind::Copy, job_id).await,
+    };
+
+    let items = job_logs::stream_logs(state.pool.clone(), state.object_storage.clone(), JobKind::Copy, job_id, after_seq, wakeup);
+    // try_stream po chybě končí, takže error event je vždy poslední
+    let stream = items.map(|item| {
+        let event = match item {
+            Ok(LogStreamItem::Line(line)) => Event::default().id(line.seq.to_string()).event("log").data(line.line),
+            Ok(LogStreamItem::End(status)) => Event::default().event("log-end").data(status),
+            Ok(LogStreamItem::NotFound) => Event::default().event("log-end").data("Copy job not found"),
+            Err(e) => Event::default().event("error").data(format!("Database error: {}", e)),
+        };
+        Ok(event)
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/// GET /api/v1/copy/jobs/{job_id}/logs/poll?after_seq= - long-poll fallback pro logy a progress
+async fn copy_job_logs_poll(
+    State(state): State<CopyApiState>,
+    Path(job_id): Path<Uuid>,
+    Query(query): Query<LogPollQuery>,
+) -> Result<Json<LogPollResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let response = job_logs::poll_logs(&state.pool, &state.object_storage, JobKind::Copy, job_id, &query)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Database error: {}", e),
+                }),
+            )
+        })?;
+
+    response.map(Json).ok_or_else(|| {
         (
-            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::NOT_FOUND,
             Json(ErrorResponse {
-                error: format!("Database error: {}", e),
+                error: "Copy job not found".to_string(),
             }),
         )
-    })?;
+    })
+}
 
-    Ok(Json(lines))
+/// GET /api/v1/copy/jobs/{job_id}/logs/history - celé uložené logy
+async fn copy_job_logs_history(
+    State(state): State<CopyApiState>,
+    Path(job_id): Path<Uuid>,
+) -> Result<Json<Vec<String>>, (StatusCode, Json<ErrorResponse>)> {
+    let lines = job_logs::load_all_lines(&state.pool, &state.object_storage, JobKind::Copy, job_id)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Failed to load copy job logs: {}", e),
+                }),
+            )
+        })?;
+
+    Ok(Json(lines.into_iter().map(|line| line.line).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn release_policy_images_use_source_registry_host() {
+        let registry_id = Uuid::new_v4();
+        let base_urls = HashMap::from([(registry_id, "https://harbor.example.com/".to_string())]);
+        let images = [(registry_id, "/prod/app/"), (Uuid::new_v4(), "unknown/app")];
+        assert_eq!(
+            release_policy_images(&images, &base_urls),
+            vec![PolicyImage::new("harbor.example.com", "prod/app")]
+        );
+    }
+
+    #[tokio::test]
+    #[ignore] // Vyžaduje Postgres s migracemi v DATABASE_URL
+    async fn from_release_copy_into_denied_environment_is_rejected() {
+        let pool = PgPool::connect(&std::env::var("DATABASE_URL").unwrap()).await.unwrap();
+        let slug = format!("policy-test-{}", Uuid::new_v4());
+        let tenant_id: Uuid = sqlx::query_scalar("INSERT INTO tenants (name, slug) VALUES ($1, $1) RETURNING id")
+            .bind(&slug)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let registry_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO registries (tenant_id, name, registry_type, base_url, role, auth_type)
+             VALUES ($1, 'release', 'generic', 'https://harbor.example.com', 'both', 'none')
+             RETURNING id",
+        )
+        .bind(tenant_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO image_policies (tenant_id, name, effect, repository_pattern, environment_pattern)
+             VALUES ($1, 'no-debug-in-prod', 'deny', '.*/debug', 'prod')",
+        )
+        .bind(tenant_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let mut environments = Vec::new();
+        for env_slug in ["prod", "dev"] {
+            let environment = sqlx::query_as::<_, Environment>(
+                "INSERT INTO environments (tenant_id, name, slug) VALUES ($1, $2, $2) RETURNING *",
+            )
+            .bind(tenant_id)
+            .bind(env_slug)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            environments.push(environment);
+        }
+
+        // Manifest release: image se kopírují z target registry zdrojového jobu
+        let images = [(registry_id, "team/app"), (registry_id, "team/debug")];
+        let denied = enforce_release_image_policies(&pool, &environments[0], &images).await;
+        let allowed = enforce_release_image_policies(&pool, &environments[1], &images).await;
+
+        sqlx::query("DELETE FROM tenants WHERE id = $1")
+            .bind(tenant_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let (status, Json(body)) = denied.unwrap_err();
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert!(body.error.contains("harbor.example.com/team/debug"), "{}", body.error);
+        assert!(allowed.is_ok());
+    }
 }
@@ -0,0 +1,80 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    routing::get,
+    Extension, Json, Router,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::auth::AuthContext;
+use crate::services::credential_expiry::{self, ExpiringCredential, ExpiryState};
+
+#[derive(Clone)]
+pub struct CredentialApiState {
+    pub pool: PgPool,
+    pub warn_days: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExpiringCredentialsQuery {
+    pub tenant_id: Option<Uuid>,
+    /// Okno v dnech (výchozí `CREDENTIAL_EXPIRY_WARN_DAYS`)
+    pub days: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExpiringCredentialView {
+    #[serde(flatten)]
+    pub credential: ExpiringCredential,
+    pub days_left: i64,
+    pub expiry: ExpiryState,
+}
+
+/// Response s chybou
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+/// Vytvoří router pro přehled expirujících credentials
+pub fn router(state: CredentialApiState) -> Router {
+    Router::new()
+        .route("/credentials/expiring", get(list_expiring_credentials))
+        .with_state(state)
+}
+
+/// GET /api/v1/credentials/expiring - Registry, environment a git credentials expirující v okně
+async fn list_expiring_credentials(
+    Extension(auth): Extension<AuthContext>,
+    State(state): State<CredentialApiState>,
+    Query(query): Query<ExpiringCredentialsQuery>,
+) -> Result<Json<Vec<ExpiringCredentialView>>, (StatusCode, Json<ErrorResponse>)> {
+    let days = query.days.unwrap_or(state.warn_days).clamp(0, 3650);
+    let credentials = credential_expiry::list_expiring(&state.pool, days, query.tenant_id)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Database error: {}", e),
+                }),
+            )
+        })?;
+
+    let now = Utc::now();
+    let views = credentials
+        .into_iter()
+        .filter(|credential| auth.is_tenant_allowed(credential.tenant_id))
+        .map(|credential| ExpiringCredentialView {
+            days_left: credential.days_left(now),
+            expiry: credential_expiry::expiry_state_at(Some(credential.expires_at), now, days)
+                .unwrap_or(ExpiryState::Expired),
+            credential,
+        })
+        .collect();
+
+    Ok(Json(views))
+}
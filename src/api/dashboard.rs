@@ -0,0 +1,136 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::auth::AuthContext;
+use crate::services::dashboard_views;
+
+/// Souhrnné statistiky tenanta ze summary view
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct TenantStats {
+    pub tenant_id: Uuid,
+    pub tenant_name: String,
+    pub bundles_total: i64,
+    pub bundles_active: i64,
+    pub registries_total: i64,
+    pub environments_total: i64,
+    pub releases_total: i64,
+    pub copy_jobs_total: i64,
+    pub copy_jobs_failed: i64,
+    pub deploy_jobs_total: i64,
+    pub deploy_jobs_failed: i64,
+    pub last_successful_deploy_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DashboardStatsResponse {
+    pub refreshed_at: Option<DateTime<Utc>>,
+    pub age_seconds: Option<i64>,
+    pub tenants: Vec<TenantStats>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ViewRefreshStatus {
+    pub view_name: String,
+    pub refreshed_at: DateTime<Utc>,
+    pub duration_ms: i64,
+}
+
+/// Response s chybou
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+/// Vytvoří router pro dashboard endpoints
+pub fn router(pool: PgPool) -> Router {
+    Router::new()
+        .route("/dashboard/stats", get(get_dashboard_stats))
+        .route("/dashboard/refresh", post(refresh_dashboard_views))
+        .with_state(pool)
+}
+
+/// GET /api/v1/dashboard/stats - Souhrnné statistiky (summary view + čas refreshe)
+async fn get_dashboard_stats(
+    Extension(auth): Extension<AuthContext>,
+    State(pool): State<PgPool>,
+) -> Result<Json<DashboardStatsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let refreshed_at = dashboard_views::view_refreshed_at(&pool, "dashboard_tenant_stats_mv")
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Database error: {}", e),
+                }),
+            )
+        })?;
+
+    let tenants = if auth.is_admin() {
+        sqlx::query_as::<_, TenantStats>(
+            "SELECT * FROM dashboard_tenant_stats_mv ORDER BY tenant_name",
+        )
+        .fetch_all(&pool)
+        .await
+    } else {
+        sqlx::query_as::<_, TenantStats>(
+            "SELECT * FROM dashboard_tenant_stats_mv WHERE tenant_id = ANY($1) ORDER BY tenant_name",
+        )
+        .bind(&auth.tenant_ids)
+        .fetch_all(&pool)
+        .await
+    }
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }),
+        )
+    })?;
+
+    Ok(Json(DashboardStatsResponse {
+        refreshed_at,
+        age_seconds: refreshed_at.map(|ts| (Utc::now() - ts).num_seconds()),
+        tenants,
+    }))
+}
+
+/// POST /api/v1/dashboard/refresh - Okamžitý refresh summary views (admin)
+async fn refresh_dashboard_views(
+    State(pool): State<PgPool>,
+) -> Result<Json<Vec<ViewRefreshStatus>>, (StatusCode, Json<ErrorResponse>)> {
+    for view in dashboard_views::SUMMARY_VIEWS {
+        dashboard_views::refresh_view(&pool, view).await.map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Failed to refresh {}: {}", view, e),
+                }),
+            )
+        })?;
+    }
+
+    let rows = sqlx::query_as::<_, ViewRefreshStatus>(
+        "SELECT view_name, refreshed_at, duration_ms FROM dashboard_view_refreshes ORDER BY view_name",
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }),
+        )
+    })?;
+
+    Ok(Json(rows))
+}
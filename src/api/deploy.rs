@@ -1,9 +1,9 @@
 #![allow(dead_code)]
 
 use axum::{
-    extract::{Path, State},
-    http::{header, StatusCode},
-    response::{IntoResponse, Sse},
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response, Sse},
     routing::{get, post},
     Extension, Json, Router,
 };
@@ -11,15 +11,14 @@ use anyhow::Context;
 use futures::stream;
 use serde::{Deserialize, Serialize};
 use serde_yaml_ng::Value as YamlValue;
-use sqlx::PgPool;
+use sqlx::{Connection, PgConnection, PgPool};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     io::ErrorKind,
     path::{Path as FsPath, PathBuf},
     sync::Arc,
     time::Duration,
 };
-use tempfile::TempDir;
 use tokio::{
     io::{AsyncBufReadExt, BufReader},
     process::Command,
@@ -29,13 +28,36 @@ use uuid::Uuid;
 use walkdir::WalkDir;
 
 use crate::{
+    api::fieldsets::FieldsetQuery,
     auth::AuthContext,
     crypto,
     db::models::{
-        DeployJob, DeployJobDiff, DeployJobLog, DeployTarget, DeployTargetEncjsonKey, DeployTargetEnv,
-        DeployTargetEnvVar, DeployTargetExtraEnvVar, Environment, GitRepository, Release,
+        DeployJob, DeployJobDiff, DeployJobStep, DeployTarget, DeployTargetEncjsonKey, DeployTargetEnv,
+        DeployTargetEnvVar, DeployTargetExtraEnvVar, DeployTargetFileTemplate, Environment, GitRepository, Release,
     },
-    services::release_manifest::{build_release_manifest, ReleaseManifest},
+    services::change_history::{self, FieldChange},
+    services::config_preview::{self, ConfigPreview},
+    services::dashboard_views,
+    services::agent_protocol::{DeployDiff, DeployEnvVar, DeployJobImageRow, DeployOutcome, DeployWork, DeployWorkFile, DeployWorkRepo},
+    services::deploy_steps,
+    services::events::{DEPLOY_JOB_FINISHED, DEPLOY_JOB_STARTED},
+    services::job_events,
+    services::env_layers::{self, EnvLayer, EnvLayerValues, ResolvedEnv},
+    services::file_templates,
+    services::key_material,
+    services::lifecycle_notifications,
+    services::reaper,
+    services::deploy_diff::{self, DiffFileEntry, DiffLimits},
+    services::job_logs::{self, JobKind, LogPollQuery, LogPollResponse, LogStreamItem, LogStreamQuery},
+    services::job_queue::{self, JobDispatch},
+    services::log_fanout::LogFanout,
+    services::notifications::Notifier,
+    services::object_storage::ObjectStorage,
+    services::release_channels,
+    services::release_manifest::{self, load_release_manifest, store_manifest_snapshot, ReleaseManifest},
+    services::sandbox::{SandboxTool, ToolProgram, ToolSandbox},
+    services::tool_paths,
+    services::validation_report::{self, CheckStatus, ValidationReport},
 };
 
 #[derive(Debug, Deserialize)]
@@ -47,7 +69,7 @@ struct KubeBuildInventory {
 }
 
 async fn ensure_environment(
-    pool: &PgPool,
+    executor: impl sqlx::PgExecutor<'_>,
     tenant_id: Uuid,
     env_name: &str,
 ) -> Result<Environment, (StatusCode, Json<ErrorResponse>)> {
@@ -74,7 +96,7 @@ async fn ensure_environment(
     .bind(tenant_id)
     .bind(name)
     .bind(&slug)
-    .fetch_one(pool)
+    .fetch_one(executor)
     .await
     .map_err(|e| {
         (
@@ -88,7 +110,7 @@ async fn ensure_environment(
     Ok(env)
 }
 
-fn slugify_env_name(name: &str) -> String {
+pub(crate) fn slugify_env_name(name: &str) -> String {
     let mut out = String::new();
     let mut last_dash = false;
     for ch in name.trim().to_lowercase().chars() {
@@ -108,6 +130,39 @@ fn slugify_env_name(name: &str) -> String {
     out
 }
 
+/// Ořízne a deduplikuje allow-list; neplatná položka je chyba requestu
+fn normalize_override_allowlist(
+    entries: Option<Vec<String>>,
+) -> Result<Option<Vec<String>>, (StatusCode, Json<ErrorResponse>)> {
+    let Some(entries) = entries else {
+        return Ok(None);
+    };
+    let mut normalized: Vec<String> = Vec::new();
+    for entry in entries.iter().map(|e| e.trim()).filter(|e| !e.is_empty()) {
+        if !env_layers::is_valid_allowlist_entry(entry) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Invalid job env override allow-list entry: {}", entry),
+                }),
+            ));
+        }
+        if !normalized.iter().any(|e| e == entry) {
+            normalized.push(entry.to_string());
+        }
+    }
+    Ok(Some(normalized))
+}
+
+fn normalize_release_channels(
+    channels: Option<Vec<String>>,
+) -> Result<Option<Vec<String>>, (StatusCode, Json<ErrorResponse>)> {
+    channels
+        .map(|channels| release_channels::normalize_subscriptions(&channels))
+        .transpose()
+        .map_err(|error| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })))
+}
+
 fn sanitize_path(value: Option<String>) -> Option<String> {
     value
         .map(|v| v.trim().trim_matches('/').to_string())
@@ -142,64 +197,26 @@ fn extra_env_vars_to_json(vars: Option<Vec<DeployTargetExtraEnvVarInput>>) -> se
     serde_json::Value::Object(map)
 }
 
-fn env_vars_from_json(value: &serde_json::Value) -> Vec<DeployTargetEnvVarInput> {
-    let mut items = Vec::new();
-    if let serde_json::Value::Object(map) = value {
-        for (source_key, target_val) in map {
-            let target_key = target_val.as_str().unwrap_or("").to_string();
-            if !source_key.trim().is_empty() && !target_key.trim().is_empty() {
-                items.push(DeployTargetEnvVarInput {
-                    source_key: source_key.to_string(),
-                    target_key,
-                });
-            }
-        }
-    }
-    items
-}
-
-fn extra_env_vars_from_json(value: &serde_json::Value) -> Vec<DeployTargetExtraEnvVarInput> {
-    let mut items = Vec::new();
-    if let serde_json::Value::Object(map) = value {
-        for (key, val) in map {
-            let value = val.as_str().unwrap_or("").to_string();
-            if !key.trim().is_empty() {
-                items.push(DeployTargetExtraEnvVarInput {
-                    key: key.to_string(),
-                    value,
-                });
-            }
-        }
-    }
-    items
-}
-
-async fn upsert_deploy_target_env(
-    pool: &PgPool,
+pub(crate) async fn upsert_deploy_target_env(
+    executor: impl sqlx::PgExecutor<'_>,
     deploy_target_id: Uuid,
     environment: &Environment,
-    payload_env_repo_id: Uuid,
-    payload_env_repo_path: Option<String>,
-    payload_env_repo_branch: Option<String>,
-    payload_deploy_repo_id: Uuid,
-    payload_deploy_repo_path: Option<String>,
-    payload_deploy_repo_branch: Option<String>,
-    allow_auto_release: bool,
-    append_env_suffix: bool,
-    is_active: bool,
-    release_manifest_mode: Option<String>,
-    encjson_key_dir: Option<String>,
+    entry: &DeployTargetEnvInput,
 ) -> Result<DeployTargetEnv, (StatusCode, Json<ErrorResponse>)> {
-    let env_repo_branch = payload_env_repo_branch
+    let env_repo_branch = entry
+        .env_repo_branch
         .clone()
         .filter(|v| !v.trim().is_empty());
-    let deploy_repo_branch = payload_deploy_repo_branch
+    let deploy_repo_branch = entry
+        .deploy_repo_branch
         .clone()
         .filter(|v| !v.trim().is_empty());
-    let env_repo_path = payload_env_repo_path
+    let env_repo_path = entry
+        .env_repo_path
         .clone()
         .filter(|v| !v.trim().is_empty());
-    let deploy_repo_path = payload_deploy_repo_path
+    let deploy_repo_path = entry
+        .deploy_repo_path
         .clone()
         .filter(|v| !v.trim().is_empty());
     let env_repo_path = if env_repo_branch.is_some() {
@@ -212,7 +229,10 @@ async fn upsert_deploy_target_env(
     } else {
         Some(deploy_repo_path.unwrap_or_else(|| format!("deploy/{}", environment.slug)))
     };
-    let manifest_mode = release_manifest_mode.unwrap_or_else(|| "match_digest".to_string());
+    let manifest_mode = entry
+        .release_manifest_mode
+        .clone()
+        .unwrap_or_else(|| "match_digest".to_string());
 
     let row = sqlx::query_as::<_, DeployTargetEnv>(
         r#"
@@ -239,18 +259,18 @@ async fn upsert_deploy_target_env(
     )
     .bind(deploy_target_id)
     .bind(environment.id)
-    .bind(payload_env_repo_id)
+    .bind(entry.env_repo_id)
     .bind(env_repo_path)
     .bind(env_repo_branch)
-    .bind(payload_deploy_repo_id)
+    .bind(entry.deploy_repo_id)
     .bind(deploy_repo_path)
     .bind(deploy_repo_branch)
-    .bind(allow_auto_release)
-    .bind(append_env_suffix)
-    .bind(is_active)
+    .bind(entry.allow_auto_release.unwrap_or(false))
+    .bind(entry.append_env_suffix.unwrap_or(false))
+    .bind(entry.is_active.unwrap_or(true))
     .bind(manifest_mode)
-    .bind(encjson_key_dir)
-    .fetch_one(pool)
+    .bind(&entry.encjson_key_dir)
+    .fetch_one(executor)
     .await
     .map_err(|e| {
         (
@@ -275,6 +295,52 @@ pub struct DeployApiState {
     pub encjson_key_dir: Option<String>,
     pub kubeconform_path: String,
     pub job_logs: Arc<RwLock<HashMap<Uuid, broadcast::Sender<String>>>>,
+    pub dispatch: JobDispatch,
+    pub log_fanout: LogFanout,
+    pub sandbox: ToolSandbox,
+    pub object_storage: ObjectStorage,
+    pub diff_limits: DiffLimits,
+    pub notifier: Notifier,
+}
+
+impl DeployApiState {
+    pub fn tools(&self) -> DeployTools {
+        DeployTools {
+            kube_build_app_path: self.kube_build_app_path.clone(),
+            apply_env_path: self.apply_env_path.clone(),
+            encjson_path: self.encjson_path.clone(),
+            encjson_legacy_path: self.encjson_legacy_path.clone(),
+            encjson_key_dir: self.encjson_key_dir.clone(),
+            kubeconform_path: self.kubeconform_path.clone(),
+            sandbox: self.sandbox.clone(),
+        }
+    }
+}
+
+/// Nástroje deploy pipeline - na serveru z `DeployApiState`, na agentovi z jeho konfigurace
+#[derive(Clone)]
+pub struct DeployTools {
+    pub kube_build_app_path: String,
+    pub apply_env_path: String,
+    pub encjson_path: String,
+    pub encjson_legacy_path: String,
+    pub encjson_key_dir: Option<String>,
+    pub kubeconform_path: String,
+    pub sandbox: ToolSandbox,
+}
+
+impl DeployTools {
+    /// Binárky encjson / kube_build_app přepsané na tenantovi nebo environmentu
+    fn with_overrides(&self, encjson_path: Option<&str>, kube_build_app_path: Option<&str>) -> Self {
+        let mut tools = self.clone();
+        if let Some(path) = encjson_path {
+            tools.encjson_path = path.to_string();
+        }
+        if let Some(path) = kube_build_app_path {
+            tools.kube_build_app_path = path.to_string();
+        }
+        tools
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -323,13 +389,7 @@ pub struct UpdateDeployTargetRequest {
     pub extra_env_vars: Option<Vec<DeployTargetExtraEnvVarInput>>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct CreateDeployJobRequest {
-    pub release_id: Uuid,
-    pub environment_id: Uuid,
-    pub dry_run: Option<bool>,
-    pub release_image_url_mode: Option<String>,
-}
+pub use srm_api_types::deploy::{CreateDeployJobRequest, DeployJobResponse};
 
 #[derive(Debug, Deserialize)]
 pub struct AutoDeployFromCopyJobRequest {
@@ -337,6 +397,8 @@ pub struct AutoDeployFromCopyJobRequest {
     pub environment_id: Uuid,
     pub dry_run: Option<bool>,
     pub release_image_url_mode: Option<String>,
+    /// Kanál nově založeného auto release (výchozí `rc`)
+    pub channel: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -370,6 +432,12 @@ pub struct EnvironmentRequest {
     pub extra_env_vars: Option<Vec<DeployTargetExtraEnvVarInput>>,
     pub argocd_poll_interval_seconds: Option<i32>,
     pub kubernetes_poll_interval_seconds: Option<i32>,
+    /// Klíče extra env proměnných, které smí přepsat deploy job (`KEY` nebo `PREFIX_*`)
+    pub job_env_override_allowlist: Option<Vec<String>>,
+    /// Environment přijímá jen validate-only deploy joby
+    pub validate_only_required: Option<bool>,
+    /// Odebírané kanály release (`rc`, `stable`, `hotfix`); výchozí všechny
+    pub release_channels: Option<Vec<String>>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -447,7 +515,7 @@ pub struct DeployTargetEnvSummary {
 }
 
 async fn get_deploy_target_summary(
-    pool: &PgPool,
+    conn: &mut PgConnection,
     target_id: Uuid,
 ) -> Result<DeployTargetSummary, (StatusCode, Json<ErrorResponse>)> {
     let base = sqlx::query_as::<_, (Uuid, Uuid, String, bool, bool, chrono::DateTime<chrono::Utc>)>(
@@ -464,7 +532,7 @@ async fn get_deploy_target_summary(
         "#,
     )
     .bind(target_id)
-    .fetch_optional(pool)
+    .fetch_optional(&mut *conn)
     .await
     .map_err(|e| {
         (
@@ -511,7 +579,7 @@ async fn get_deploy_target_summary(
         "#
     )
     .bind(id)
-    .fetch_all(pool)
+    .fetch_all(&mut *conn)
     .await
     .map_err(|e| {
         (
@@ -533,17 +601,116 @@ async fn get_deploy_target_summary(
     })
 }
 
+/// Porovnatelný stav deploy targetu pro preview a change history
+#[derive(Debug, Serialize)]
+struct DeployTargetSnapshot {
+    #[serde(flatten)]
+    target: DeployTarget,
+    envs: Vec<DeployTargetEnvSummary>,
+    env_vars: Vec<serde_json::Value>,
+    extra_env_vars: Vec<serde_json::Value>,
+    encjson_public_keys: Vec<String>,
+}
+
+async fn load_deploy_target_snapshot(
+    conn: &mut PgConnection,
+    id: Uuid,
+) -> Result<DeployTargetSnapshot, (StatusCode, Json<ErrorResponse>)> {
+    let target = sqlx::query_as::<_, DeployTarget>("SELECT * FROM deploy_targets WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: format!("Deploy target with id {} not found", id),
+                }),
+            )
+        })?;
+    let envs = get_deploy_target_summary(&mut *conn, id).await?.envs;
+    let env_vars = sqlx::query_scalar::<_, serde_json::Value>(
+        "SELECT json_build_object('source_key', source_key, 'target_key', target_key)
+         FROM deploy_target_env_vars WHERE deploy_target_id = $1
+         ORDER BY source_key, target_key",
+    )
+    .bind(id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(db_error)?;
+    let extra_env_vars = sqlx::query_scalar::<_, serde_json::Value>(
+        "SELECT json_build_object('key', key, 'value', value)
+         FROM deploy_target_extra_env_vars WHERE deploy_target_id = $1
+         ORDER BY key",
+    )
+    .bind(id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(db_error)?;
+    let encjson_public_keys = sqlx::query_scalar::<_, String>(
+        "SELECT public_key FROM deploy_target_encjson_keys WHERE deploy_target_id = $1 ORDER BY public_key",
+    )
+    .bind(id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(db_error)?;
+
+    Ok(DeployTargetSnapshot {
+        target,
+        envs,
+        env_vars,
+        extra_env_vars,
+        encjson_public_keys,
+    })
+}
+
+/// `None` = target před změnou neexistoval, resp. po ní už neexistuje
+fn deploy_target_changes(before: Option<&DeployTargetSnapshot>, after: Option<&DeployTargetSnapshot>) -> Vec<FieldChange> {
+    let to_value = |snapshot: Option<&DeployTargetSnapshot>| {
+        snapshot
+            .and_then(|s| serde_json::to_value(s).ok())
+            .unwrap_or(serde_json::Value::Null)
+    };
+    let mut changes = change_history::diff_snapshots(&to_value(before), &to_value(after));
+    changes.extend(change_history::secret_change(
+        "encjson_private_key",
+        before.and_then(|s| s.target.encjson_private_key_encrypted.as_deref()),
+        after.and_then(|s| s.target.encjson_private_key_encrypted.as_deref()),
+    ));
+    changes
+}
+
+async fn record_deploy_target_changes(
+    pool: &PgPool,
+    auth: &AuthContext,
+    before: Option<&DeployTargetSnapshot>,
+    after: Option<&DeployTargetSnapshot>,
+) {
+    let Some(target) = after.or(before).map(|s| &s.target) else {
+        return;
+    };
+    let changes = deploy_target_changes(before, after);
+    if let Err(e) = change_history::record_changes(
+        pool,
+        "deploy_target",
+        target.id,
+        Some(target.tenant_id),
+        Some(&auth.username),
+        &changes,
+    )
+    .await
+    {
+        tracing::warn!(deploy_target_id = %target.id, error = %e, "Failed to record deploy target change history");
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct EncjsonKeySummary {
     pub public_key: String,
     pub has_private: bool,
 }
 
-#[derive(Debug, Serialize)]
-pub struct DeployJobResponse {
-    pub job_id: Uuid,
-    pub message: String,
-}
 
 #[derive(Debug, Serialize, sqlx::FromRow)]
 pub struct DeployJobSummary {
@@ -564,6 +731,9 @@ pub struct DeployJobSummary {
     pub bundle_id: Option<Uuid>,
     pub dry_run: bool,
     pub release_image_url_mode: String,
+    pub env_overrides: serde_json::Value,
+    pub env_overrides_by: Option<String>,
+    pub validate_only: bool,
 }
 
 #[derive(Debug, Serialize, sqlx::FromRow)]
@@ -589,11 +759,10 @@ pub struct DeployJobListRow {
     pub dry_run: bool,
 }
 
-#[derive(Debug, Serialize, sqlx::FromRow)]
-pub struct DeployJobImageRow {
-    pub file_path: String,
-    pub container_name: String,
-    pub image: String,
+#[derive(Debug, Deserialize)]
+pub struct DeployJobListQuery {
+    /// Bypass summary view and read live data
+    pub fresh: Option<bool>,
 }
 
 fn normalize_release_image_url_mode(mode: Option<String>) -> String {
@@ -636,19 +805,86 @@ pub struct ErrorResponse {
     pub error: String,
 }
 
+fn db_error(e: sqlx::Error) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: format!("Database error: {}", e),
+        }),
+    )
+}
+
+/// `?preview=true` – vrátí změny a dotčené deploye bez uložení
+#[derive(Debug, Default, Deserialize)]
+pub struct PreviewQuery {
+    #[serde(default)]
+    pub preview: bool,
+}
+
+/// Volitelné porovnání s image mappings verze bundlu
+#[derive(Debug, Deserialize)]
+pub struct ExpectedImagesQuery {
+    pub bundle_id: Option<Uuid>,
+    pub version: Option<i32>,
+}
+
+/// Dvojice app/container, kterou strict režim vyžaduje v release manifestu
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct ExpectedImage {
+    pub app_name: String,
+    pub container_name: Option<String>,
+}
+
+/// Rozdíl mezi očekávanými dvojicemi a mappings verze bundlu
+#[derive(Debug, Serialize)]
+pub struct ExpectedImagesReconciliation {
+    pub bundle_id: Uuid,
+    pub version: i32,
+    /// Očekávané prostředím, ale bez mappingu - strict deploy by selhal
+    pub missing: Vec<ExpectedImage>,
+    /// Mappings, které env repo nezná
+    pub extra: Vec<ExpectedImage>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExpectedImagesResponse {
+    pub environment_id: Uuid,
+    pub env_repo_id: Uuid,
+    pub env_repo_branch: String,
+    pub env_repo_path: String,
+    pub commit_sha: String,
+    pub release_manifest_mode: String,
+    /// `false` = režim environmentu dvojice nevynucuje (jen informativní)
+    pub strict: bool,
+    pub expected: Vec<ExpectedImage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bundle: Option<ExpectedImagesReconciliation>,
+}
+
 pub fn router(state: DeployApiState) -> Router {
     Router::new()
         .route("/tenants/{tenant_id}/environments", get(list_environments).post(create_environment))
         .route("/environments/{id}", get(get_environment).put(update_environment).delete(delete_environment))
+        .route("/environments/{id}/expected-images", get(get_environment_expected_images))
+        .route("/tenants/{tenant_id}/deploy-targets", get(list_deploy_targets).post(create_deploy_target))
+        .route("/deploy-targets/{id}", get(get_deploy_target).put(update_deploy_target).delete(delete_deploy_target))
+        .route("/deploy-targets/{id}/archive", post(archive_deploy_target))
+        .route("/deploy-targets/{id}/unarchive", post(unarchive_deploy_target))
+        .route("/releases/{id}/deploy-targets", get(list_release_deploy_targets))
         .route("/releases/{id}/deploy-jobs", get(list_release_deploy_jobs))
         .route("/deploy/jobs", get(list_deploy_jobs).post(create_deploy_job))
         .route("/deploy/jobs/from-copy", post(auto_deploy_from_copy_job))
         .route("/deploy/jobs/{id}", get(get_deploy_job))
         .route("/deploy/jobs/{id}/inventory", get(get_deploy_job_inventory))
-        .route("/deploy/jobs/{id}/start", post(start_deploy_job))
+        .route("/deploy/jobs/{id}/start", post(approve_and_start_deploy_job))
         .route("/deploy/jobs/{id}/logs", get(deploy_job_logs_sse))
         .route("/deploy/jobs/{id}/logs/history", get(deploy_job_logs_history))
+        .route("/deploy/jobs/{id}/logs/poll", get(deploy_job_logs_poll))
+        .route("/deploy/jobs/{id}/logs/stream", get(deploy_job_logs_stream_sse))
         .route("/deploy/jobs/{id}/diff", get(deploy_job_diff))
+        .route("/deploy/jobs/{id}/steps", get(deploy_job_steps))
+        .route("/deploy/jobs/{id}/env", get(deploy_job_env))
+        .route("/deploy/jobs/{id}/report", get(deploy_job_report))
         .route("/deploy/jobs/{id}/images", get(deploy_job_images))
         .with_state(state)
 }
@@ -750,7 +986,8 @@ async fn list_deploy_targets(
 async fn list_environments(
     State(state): State<DeployApiState>,
     Path(tenant_id): Path<Uuid>,
-) -> Result<Json<Vec<Environment>>, (StatusCode, Json<ErrorResponse>)> {
+    Query(fieldset): Query<FieldsetQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
     let envs = sqlx::query_as::<_, Environment>(
         "SELECT * FROM environments WHERE tenant_id = $1 ORDER BY name",
     )
@@ -766,7 +1003,8 @@ async fn list_environments(
         )
     })?;
 
-    Ok(Json(envs))
+    let value = serde_json::to_value(envs).unwrap_or_default();
+    Ok(Json(fieldset.apply(value, &[])))
 }
 
 async fn create_environment(
@@ -774,6 +1012,24 @@ async fn create_environment(
     Path(tenant_id): Path<Uuid>,
     Json(payload): Json<EnvironmentRequest>,
 ) -> Result<(StatusCode, Json<Environment>), (StatusCode, Json<ErrorResponse>)> {
+    let mut conn = state.pool.acquire().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }),
+        )
+    })?;
+    let env = insert_environment(&mut conn, &state.encryption_secret, tenant_id, payload).await?;
+    Ok((StatusCode::CREATED, Json(env)))
+}
+
+pub(crate) async fn insert_environment(
+    conn: &mut PgConnection,
+    encryption_secret: &str,
+    tenant_id: Uuid,
+    payload: EnvironmentRequest,
+) -> Result<Environment, (StatusCode, Json<ErrorResponse>)> {
     let name = payload.name.trim();
     if name.is_empty() {
         return Err((
@@ -789,6 +1045,8 @@ async fn create_environment(
         .map(slugify_env_name)
         .filter(|s| !s.is_empty())
         .unwrap_or_else(|| slugify_env_name(name));
+    let job_env_override_allowlist = normalize_override_allowlist(payload.job_env_override_allowlist.clone())?;
+    let subscribed_channels = normalize_release_channels(payload.release_channels.clone())?;
 
     let env_repo_path = sanitize_path(payload.env_repo_path);
     let deploy_repo_path = sanitize_path(payload.deploy_repo_path);
@@ -814,7 +1072,7 @@ async fn create_environment(
     let source_password_encrypted = payload.source_password.as_deref()
         .map(str::trim)
         .filter(|v| !v.is_empty())
-        .map(|v| crypto::encrypt(v, &state.encryption_secret))
+        .map(|v| crypto::encrypt(v, encryption_secret))
         .transpose()
         .map_err(|e| {
             (
@@ -827,7 +1085,7 @@ async fn create_environment(
     let source_token_encrypted = payload.source_token.as_deref()
         .map(str::trim)
         .filter(|v| !v.is_empty())
-        .map(|v| crypto::encrypt(v, &state.encryption_secret))
+        .map(|v| crypto::encrypt(v, encryption_secret))
         .transpose()
         .map_err(|e| {
             (
@@ -840,7 +1098,7 @@ async fn create_environment(
     let target_password_encrypted = payload.target_password.as_deref()
         .map(str::trim)
         .filter(|v| !v.is_empty())
-        .map(|v| crypto::encrypt(v, &state.encryption_secret))
+        .map(|v| crypto::encrypt(v, encryption_secret))
         .transpose()
         .map_err(|e| {
             (
@@ -853,7 +1111,7 @@ async fn create_environment(
     let target_token_encrypted = payload.target_token.as_deref()
         .map(str::trim)
         .filter(|v| !v.is_empty())
-        .map(|v| crypto::encrypt(v, &state.encryption_secret))
+        .map(|v| crypto::encrypt(v, encryption_secret))
         .transpose()
         .map_err(|e| {
             (
@@ -875,7 +1133,8 @@ async fn create_environment(
             env_repo_id, env_repo_path, env_repo_branch,
             deploy_repo_id, deploy_repo_path, deploy_repo_branch,
             allow_auto_release, append_env_suffix, release_manifest_mode, encjson_key_dir,
-            release_env_var_mappings, extra_env_vars, argocd_poll_interval_seconds, kubernetes_poll_interval_seconds
+            release_env_var_mappings, extra_env_vars, argocd_poll_interval_seconds, kubernetes_poll_interval_seconds,
+            job_env_override_allowlist, validate_only_required, release_channels
         )
         VALUES (
             $1, $2, $3, $4,
@@ -886,7 +1145,8 @@ async fn create_environment(
             $17, $18, $19,
             $20, $21, $22,
             $23, $24, $25, $26,
-            $27, $28, $29, $30
+            $27, $28, $29, $30,
+            $31, $32, $33
         )
         RETURNING *
         "#
@@ -921,7 +1181,10 @@ async fn create_environment(
     .bind(extra_env_vars_to_json(payload.extra_env_vars.clone()))
     .bind(payload.argocd_poll_interval_seconds.unwrap_or(0))
     .bind(payload.kubernetes_poll_interval_seconds.unwrap_or(0))
-    .fetch_one(&state.pool)
+    .bind(job_env_override_allowlist.unwrap_or_default())
+    .bind(payload.validate_only_required.unwrap_or(false))
+    .bind(subscribed_channels.unwrap_or_else(|| release_channels::CHANNELS.map(String::from).to_vec()))
+    .fetch_one(&mut *conn)
     .await
     .map_err(|e| {
         let msg = format!("Database error: {}", e);
@@ -938,13 +1201,27 @@ async fn create_environment(
         )
     })?;
 
-    Ok((StatusCode::CREATED, Json(env)))
+    Ok(env)
 }
 
+/// Volitelné sub-resources pro `GET /environments/{id}?expand=`
+const ENVIRONMENT_EXPANSIONS: &[&str] = &[
+    "registry_paths",
+    "registry_credentials",
+    "registry_access",
+    "argocd_apps",
+    "kubernetes_namespaces",
+];
+
 async fn get_environment(
     State(state): State<DeployApiState>,
     Path(id): Path<Uuid>,
-) -> Result<Json<Environment>, (StatusCode, Json<ErrorResponse>)> {
+    Query(fieldset): Query<FieldsetQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    let expansions = fieldset
+        .expansions(ENVIRONMENT_EXPANSIONS)
+        .map_err(|error| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })))?;
+
     let env = sqlx::query_as::<_, Environment>(
         "SELECT * FROM environments WHERE id = $1",
     )
@@ -960,22 +1237,100 @@ async fn get_environment(
         )
     })?;
 
-    match env {
-        Some(env) => Ok(Json(env)),
-        None => Err((
+    let Some(env) = env else {
+        return Err((
             StatusCode::NOT_FOUND,
             Json(ErrorResponse {
                 error: format!("Environment with id {} not found", id),
             }),
-        )),
+        ));
+    };
+
+    let mut value = serde_json::to_value(env).unwrap_or_default();
+    for expansion in &expansions {
+        // Credentials bez tajných hodnot, jen příznaky
+        let sql = match expansion.as_str() {
+            "registry_paths" => {
+                "SELECT registry_id, project_path_override, created_at
+                 FROM environment_registry_paths WHERE environment_id = $1"
+            }
+            "registry_credentials" => {
+                "SELECT registry_id, auth_type, username,
+                        password_encrypted IS NOT NULL AS has_password,
+                        token_encrypted IS NOT NULL AS has_token, created_at
+                 FROM environment_registry_credentials WHERE environment_id = $1"
+            }
+            "registry_access" => {
+                "SELECT registry_id, is_enabled, created_at
+                 FROM environment_registry_access WHERE environment_id = $1"
+            }
+            "argocd_apps" => {
+                "SELECT id, argocd_instance_id, application_name, is_active, last_sync_status,
+                        last_health_status, last_revision, last_checked_at
+                 FROM environment_argocd_apps WHERE environment_id = $1"
+            }
+            _ => {
+                "SELECT id, kubernetes_instance_id, namespace, is_active
+                 FROM environment_kubernetes_namespaces WHERE environment_id = $1"
+            }
+        };
+        let rows = sqlx::query_scalar::<_, serde_json::Value>(&format!(
+            "SELECT COALESCE(json_agg(t), '[]'::json) FROM ({}) t",
+            sql
+        ))
+        .bind(id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Database error: {}", e),
+                }),
+            )
+        })?;
+        value[expansion.as_str()] = rows;
     }
+
+    Ok(Json(fieldset.apply(value, &expansions)))
 }
 
 async fn update_environment(
+    Extension(auth): Extension<AuthContext>,
     State(state): State<DeployApiState>,
     Path(id): Path<Uuid>,
+    Query(query): Query<PreviewQuery>,
     Json(payload): Json<EnvironmentRequest>,
-) -> Result<Json<Environment>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    if query.preview {
+        let mut tx = state.pool.begin().await.map_err(db_error)?;
+        let (current, env) = update_environment_record(&mut tx, &state.encryption_secret, id, payload).await?;
+        let affected = config_preview::affected_deploys(&mut tx, env.id).await.map_err(db_error)?;
+        tx.rollback().await.map_err(db_error)?;
+        let changes = environment_changes(&current, &env);
+        return Ok(Json(ConfigPreview::new(changes, affected, env)).into_response());
+    }
+
+    let mut conn = state.pool.acquire().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }),
+        )
+    })?;
+    let (current, env) = update_environment_record(&mut conn, &state.encryption_secret, id, payload).await?;
+    record_environment_changes(&state.pool, &auth, &current, &env).await;
+    Ok(Json(env).into_response())
+}
+
+/// Returns the environment before and after the update.
+pub(crate) async fn update_environment_record(
+    conn: &mut PgConnection,
+    encryption_secret: &str,
+    id: Uuid,
+    payload: EnvironmentRequest,
+) -> Result<(Environment, Environment), (StatusCode, Json<ErrorResponse>)> {
     let name = payload.name.trim();
     if name.is_empty() {
         return Err((
@@ -991,12 +1346,14 @@ async fn update_environment(
         .map(slugify_env_name)
         .filter(|s| !s.is_empty())
         .unwrap_or_else(|| slugify_env_name(name));
+    let job_env_override_allowlist = normalize_override_allowlist(payload.job_env_override_allowlist.clone())?;
+    let subscribed_channels = normalize_release_channels(payload.release_channels.clone())?;
 
     let current = sqlx::query_as::<_, Environment>(
         "SELECT * FROM environments WHERE id = $1",
     )
     .bind(id)
-    .fetch_optional(&state.pool)
+    .fetch_optional(&mut *conn)
     .await
     .map_err(|e| {
         (
@@ -1038,7 +1395,7 @@ async fn update_environment(
     let source_auth_type = payload.source_auth_type.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string);
     let target_auth_type = payload.target_auth_type.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string);
     let source_password_encrypted = match payload.source_password.as_deref().map(str::trim) {
-        Some(v) if !v.is_empty() => Some(crypto::encrypt(v, &state.encryption_secret).map_err(|e| {
+        Some(v) if !v.is_empty() => Some(crypto::encrypt(v, encryption_secret).map_err(|e| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ErrorResponse {
@@ -1049,7 +1406,7 @@ async fn update_environment(
         _ => current.source_password_encrypted.clone(),
     };
     let source_token_encrypted = match payload.source_token.as_deref().map(str::trim) {
-        Some(v) if !v.is_empty() => Some(crypto::encrypt(v, &state.encryption_secret).map_err(|e| {
+        Some(v) if !v.is_empty() => Some(crypto::encrypt(v, encryption_secret).map_err(|e| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ErrorResponse {
@@ -1060,7 +1417,7 @@ async fn update_environment(
         _ => current.source_token_encrypted.clone(),
     };
     let target_password_encrypted = match payload.target_password.as_deref().map(str::trim) {
-        Some(v) if !v.is_empty() => Some(crypto::encrypt(v, &state.encryption_secret).map_err(|e| {
+        Some(v) if !v.is_empty() => Some(crypto::encrypt(v, encryption_secret).map_err(|e| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ErrorResponse {
@@ -1071,7 +1428,7 @@ async fn update_environment(
         _ => current.target_password_encrypted.clone(),
     };
     let target_token_encrypted = match payload.target_token.as_deref().map(str::trim) {
-        Some(v) if !v.is_empty() => Some(crypto::encrypt(v, &state.encryption_secret).map_err(|e| {
+        Some(v) if !v.is_empty() => Some(crypto::encrypt(v, encryption_secret).map_err(|e| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ErrorResponse {
@@ -1113,8 +1470,11 @@ async fn update_environment(
             release_env_var_mappings = $26,
             extra_env_vars = $27,
             argocd_poll_interval_seconds = $28,
-            kubernetes_poll_interval_seconds = $29
-        WHERE id = $30
+            kubernetes_poll_interval_seconds = $29,
+            job_env_override_allowlist = $30,
+            validate_only_required = $31,
+            release_channels = $32
+        WHERE id = $33
         RETURNING *
         "#
     )
@@ -1147,8 +1507,11 @@ async fn update_environment(
     .bind(if payload.extra_env_vars.is_some() { extra_env_vars_to_json(payload.extra_env_vars.clone()) } else { current.extra_env_vars.clone() })
     .bind(payload.argocd_poll_interval_seconds.unwrap_or(current.argocd_poll_interval_seconds))
     .bind(payload.kubernetes_poll_interval_seconds.unwrap_or(current.kubernetes_poll_interval_seconds))
+    .bind(job_env_override_allowlist.unwrap_or_else(|| current.job_env_override_allowlist.clone()))
+    .bind(payload.validate_only_required.unwrap_or(current.validate_only_required))
+    .bind(subscribed_channels.unwrap_or_else(|| current.release_channels.clone()))
     .bind(id)
-    .fetch_optional(&state.pool)
+    .fetch_optional(&mut *conn)
     .await
     .map_err(|e| {
         (
@@ -1160,7 +1523,7 @@ async fn update_environment(
     })?;
 
     match env {
-        Some(env) => Ok(Json(env)),
+        Some(env) => Ok((current, env)),
         None => Err((
             StatusCode::NOT_FOUND,
             Json(ErrorResponse {
@@ -1170,15 +1533,61 @@ async fn update_environment(
     }
 }
 
+fn environment_changes(before: &Environment, after: &Environment) -> Vec<FieldChange> {
+    let mut changes = change_history::diff_snapshots(before, after);
+    let secrets = [
+        ("source_password", &before.source_password_encrypted, &after.source_password_encrypted),
+        ("source_token", &before.source_token_encrypted, &after.source_token_encrypted),
+        ("target_password", &before.target_password_encrypted, &after.target_password_encrypted),
+        ("target_token", &before.target_token_encrypted, &after.target_token_encrypted),
+    ];
+    for (field, old, new) in secrets {
+        changes.extend(change_history::secret_change(field, old.as_deref(), new.as_deref()));
+    }
+    changes
+}
+
+pub(crate) async fn record_environment_changes(pool: &PgPool, auth: &AuthContext, before: &Environment, after: &Environment) {
+    let changes = environment_changes(before, after);
+    if let Err(e) = change_history::record_changes(
+        pool,
+        "environment",
+        after.id,
+        Some(after.tenant_id),
+        Some(&auth.username),
+        &changes,
+    )
+    .await
+    {
+        tracing::warn!(environment_id = %after.id, error = %e, "Failed to record environment change history");
+    }
+}
+
 async fn delete_environment(
     State(state): State<DeployApiState>,
     Path(id): Path<Uuid>,
 ) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    let mut conn = state.pool.acquire().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }),
+        )
+    })?;
+    delete_environment_record(&mut conn, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub(crate) async fn delete_environment_record(
+    conn: &mut PgConnection,
+    id: Uuid,
+) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
     let in_use = sqlx::query_scalar::<_, bool>(
         "SELECT EXISTS(SELECT 1 FROM deploy_jobs WHERE environment_id = $1)",
     )
     .bind(id)
-    .fetch_one(&state.pool)
+    .fetch_one(&mut *conn)
     .await
     .map_err(|e| {
         (
@@ -1202,7 +1611,7 @@ async fn delete_environment(
         "SELECT EXISTS(SELECT 1 FROM copy_jobs WHERE environment_id = $1)",
     )
     .bind(id)
-    .fetch_one(&state.pool)
+    .fetch_one(&mut *conn)
     .await
     .map_err(|e| {
         (
@@ -1224,7 +1633,7 @@ async fn delete_environment(
 
     let result = sqlx::query("DELETE FROM environments WHERE id = $1")
         .bind(id)
-        .execute(&state.pool)
+        .execute(&mut *conn)
         .await
         .map_err(|e| {
             (
@@ -1244,13 +1653,21 @@ async fn delete_environment(
         ));
     }
 
-    Ok(StatusCode::NO_CONTENT)
+    Ok(())
 }
 
+/// Volitelné sub-resources pro `GET /deploy-targets/{id}?expand=`
+const DEPLOY_TARGET_EXPANSIONS: &[&str] = &["deploy_jobs", "environments"];
+
 async fn get_deploy_target(
     State(state): State<DeployApiState>,
     Path(id): Path<Uuid>,
-) -> Result<Json<DeployTargetWithKeys>, (StatusCode, Json<ErrorResponse>)> {
+    Query(fieldset): Query<FieldsetQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    let expansions = fieldset
+        .expansions(DEPLOY_TARGET_EXPANSIONS)
+        .map_err(|error| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })))?;
+
     let target = sqlx::query_as::<_, (Uuid, Uuid, String, bool, bool, chrono::DateTime<chrono::Utc>)>(
         r#"
         SELECT
@@ -1379,12 +1796,47 @@ async fn get_deploy_target(
                 )
             })?;
 
-            Ok(Json(DeployTargetWithKeys {
+            let mut value = serde_json::to_value(DeployTargetWithKeys {
                 target: summary,
                 encjson_keys: summaries,
                 env_vars,
                 extra_env_vars,
-            }))
+            })
+            .unwrap_or_default();
+            for expansion in &expansions {
+                let sql = match expansion.as_str() {
+                    "deploy_jobs" => {
+                        "SELECT COALESCE(json_agg(t ORDER BY t.created_at DESC), '[]'::json) FROM (
+                            SELECT id, release_id, environment_id, status, dry_run, validate_only, started_at,
+                                   completed_at, error_message, commit_sha, tag_name, created_at
+                            FROM deploy_jobs WHERE deploy_target_id = $1
+                            ORDER BY created_at DESC LIMIT 50
+                         ) t"
+                    }
+                    _ => {
+                        "SELECT COALESCE(json_agg(t ORDER BY t.slug), '[]'::json) FROM (
+                            SELECT e.id, e.name, e.slug, e.color, e.target_registry_id, e.release_channels
+                            FROM environments e
+                            JOIN deploy_target_envs dte ON dte.environment_id = e.id
+                            WHERE dte.deploy_target_id = $1
+                         ) t"
+                    }
+                };
+                value[expansion.as_str()] = sqlx::query_scalar::<_, serde_json::Value>(sql)
+                    .bind(id)
+                    .fetch_one(&state.pool)
+                    .await
+                    .map_err(|e| {
+                        (
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            Json(ErrorResponse {
+                                error: format!("Database error: {}", e),
+                            }),
+                        )
+                    })?;
+            }
+
+            Ok(Json(fieldset.apply(value, &expansions)))
         }
         None => Err((
             StatusCode::NOT_FOUND,
@@ -1396,6 +1848,7 @@ async fn get_deploy_target(
 }
 
 async fn create_deploy_target(
+    Extension(auth): Extension<AuthContext>,
     State(state): State<DeployApiState>,
     Path(tenant_id): Path<Uuid>,
     Json(payload): Json<CreateDeployTargetRequest>,
@@ -1420,8 +1873,14 @@ async fn create_deploy_target(
         ));
     }
 
+    if let Some(keys) = &payload.encjson_keys {
+        check_encjson_keys(keys)?;
+    }
     let encjson_private_key_encrypted = match payload.encjson_private_key {
-        Some(key) if !key.trim().is_empty() => Some(
+        Some(key) if !key.trim().is_empty() => Some({
+            let key = key_material::validate_encjson_private_key(&key).map_err(|e| {
+                (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: e }))
+            })?;
             crypto::encrypt(&key, &state.encryption_secret).map_err(|e| {
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
@@ -1429,8 +1888,8 @@ async fn create_deploy_target(
                         error: format!("Failed to encrypt encjson private key: {}", e),
                     }),
                 )
-            })?,
-        ),
+            })?
+        }),
         _ => None,
     };
 
@@ -1736,27 +2195,13 @@ async fn create_deploy_target(
     })?;
 
     for (environment, entry) in &env_entries {
-        let _ = upsert_deploy_target_env(
-            &state.pool,
-            target.id,
-            environment,
-            entry.env_repo_id,
-            entry.env_repo_path.clone(),
-            entry.env_repo_branch.clone(),
-            entry.deploy_repo_id,
-            entry.deploy_repo_path.clone(),
-            entry.deploy_repo_branch.clone(),
-            entry.allow_auto_release.unwrap_or(false),
-            entry.append_env_suffix.unwrap_or(false),
-            entry.is_active.unwrap_or(true),
-            entry.release_manifest_mode.clone(),
-            entry.encjson_key_dir.clone(),
-        )
-        .await?;
+        let _ = upsert_deploy_target_env(&state.pool, target.id, environment, entry).await?;
     }
 
+    let mut conn = state.pool.acquire().await.map_err(db_error)?;
+
     if let Some(keys) = payload.encjson_keys {
-        store_encjson_keys(&state, target.id, keys).await?;
+        store_encjson_keys(&mut conn, &state.encryption_secret, target.id, keys).await?;
     } else if let Some(source_id) = payload.copy_from_target_id {
         let same_tenant = sqlx::query_scalar::<_, bool>(
             "SELECT EXISTS(SELECT 1 FROM deploy_targets WHERE id = $1 AND tenant_id = $2)",
@@ -1806,7 +2251,7 @@ async fn create_deploy_target(
     }
 
     if let Some(env_vars) = payload.env_vars {
-        store_deploy_target_env_vars(&state, target.id, env_vars).await?;
+        store_deploy_target_env_vars(&mut conn, target.id, env_vars).await?;
     } else if let Some(source_id) = payload.copy_from_target_id {
         sqlx::query(
             r#"
@@ -1831,7 +2276,7 @@ async fn create_deploy_target(
     }
 
     if let Some(extra_env_vars) = payload.extra_env_vars {
-        store_deploy_target_extra_env_vars(&state, target.id, extra_env_vars).await?;
+        store_deploy_target_extra_env_vars(&mut conn, target.id, extra_env_vars).await?;
     } else if let Some(source_id) = payload.copy_from_target_id {
         sqlx::query(
             r#"
@@ -1855,15 +2300,19 @@ async fn create_deploy_target(
         })?;
     }
 
-    let summary = get_deploy_target_summary(&state.pool, target.id).await?;
+    let after = load_deploy_target_snapshot(&mut conn, target.id).await?;
+    record_deploy_target_changes(&state.pool, &auth, None, Some(&after)).await;
+    let summary = get_deploy_target_summary(&mut conn, target.id).await?;
     Ok((StatusCode::CREATED, Json(summary)))
 }
 
 async fn update_deploy_target(
+    Extension(auth): Extension<AuthContext>,
     State(state): State<DeployApiState>,
     Path(id): Path<Uuid>,
+    Query(query): Query<PreviewQuery>,
     Json(payload): Json<UpdateDeployTargetRequest>,
-) -> Result<Json<DeployTargetSummary>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
     let use_envs = payload.envs.as_ref().map(|v| !v.is_empty()).unwrap_or(false);
     if payload.name.trim().is_empty() {
         return Err((
@@ -1897,8 +2346,17 @@ async fn update_deploy_target(
         )
     })?;
 
+    let mut tx = state.pool.begin().await.map_err(db_error)?;
+    let before = load_deploy_target_snapshot(&mut tx, id).await?;
+
+    if let Some(keys) = &payload.encjson_keys {
+        check_encjson_keys(keys)?;
+    }
     let encjson_private_key_encrypted = match payload.encjson_private_key {
-        Some(key) if !key.trim().is_empty() => Some(
+        Some(key) if !key.trim().is_empty() => Some({
+            let key = key_material::validate_encjson_private_key(&key).map_err(|e| {
+                (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: e }))
+            })?;
             crypto::encrypt(&key, &state.encryption_secret).map_err(|e| {
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
@@ -1906,8 +2364,8 @@ async fn update_deploy_target(
                         error: format!("Failed to encrypt encjson private key: {}", e),
                     }),
                 )
-            })?,
-        ),
+            })?
+        }),
         _ => None,
     };
 
@@ -2159,7 +2617,7 @@ async fn update_deploy_target(
             ));
         }
 
-        let environment = ensure_environment(&state.pool, target_tenant, &payload_env_name).await?;
+        let environment = ensure_environment(&mut *tx, target_tenant, &payload_env_name).await?;
         let env_input = DeployTargetEnvInput {
             environment_id: environment.id,
             env_repo_id,
@@ -2213,7 +2671,7 @@ async fn update_deploy_target(
     .bind(base_is_active)
     .bind(payload.is_archived)
     .bind(id)
-    .fetch_optional(&state.pool)
+    .fetch_optional(&mut *tx)
     .await
     .map_err(|e| {
         (
@@ -2224,59 +2682,57 @@ async fn update_deploy_target(
         )
     })?;
 
-    match target {
-        Some(target) => {
-            for (environment, entry) in &env_entries {
-                let _ = upsert_deploy_target_env(
-                    &state.pool,
-                    target.id,
-                    environment,
-                    entry.env_repo_id,
-                    entry.env_repo_path.clone(),
-                    entry.env_repo_branch.clone(),
-                    entry.deploy_repo_id,
-                    entry.deploy_repo_path.clone(),
-                    entry.deploy_repo_branch.clone(),
-                    entry.allow_auto_release.unwrap_or(false),
-                    entry.append_env_suffix.unwrap_or(false),
-                    entry.is_active.unwrap_or(true),
-                    entry.release_manifest_mode.clone(),
-                    entry.encjson_key_dir.clone(),
-                )
-                .await?;
-            }
-
-            if let Some(keys) = payload.encjson_keys {
-                store_encjson_keys(&state, target.id, keys).await?;
-            }
-            if let Some(env_vars) = payload.env_vars {
-                store_deploy_target_env_vars(&state, target.id, env_vars).await?;
-            }
-            if let Some(extra_env_vars) = payload.extra_env_vars {
-                store_deploy_target_extra_env_vars(&state, target.id, extra_env_vars).await?;
-            }
-            let summary = get_deploy_target_summary(&state.pool, target.id).await?;
-            Ok(Json(summary))
-        }
-        None => Err((
+    let Some(target) = target else {
+        return Err((
             StatusCode::NOT_FOUND,
             Json(ErrorResponse {
                 error: format!("Deploy target with id {} not found", id),
             }),
-        )),
+        ));
+    };
+
+    for (environment, entry) in &env_entries {
+        let _ = upsert_deploy_target_env(&mut *tx, target.id, environment, entry).await?;
     }
-}
 
-#[derive(Debug, Serialize)]
+    if let Some(keys) = payload.encjson_keys {
+        store_encjson_keys(&mut tx, &state.encryption_secret, target.id, keys).await?;
+    }
+    if let Some(env_vars) = payload.env_vars {
+        store_deploy_target_env_vars(&mut tx, target.id, env_vars).await?;
+    }
+    if let Some(extra_env_vars) = payload.extra_env_vars {
+        store_deploy_target_extra_env_vars(&mut tx, target.id, extra_env_vars).await?;
+    }
+    let summary = get_deploy_target_summary(&mut tx, target.id).await?;
+    let after = load_deploy_target_snapshot(&mut tx, target.id).await?;
+
+    if query.preview {
+        let affected = config_preview::affected_target_deploys(&mut tx, target.id).await.map_err(db_error)?;
+        tx.rollback().await.map_err(db_error)?;
+        let changes = deploy_target_changes(Some(&before), Some(&after));
+        return Ok(Json(ConfigPreview::new(changes, affected, summary)).into_response());
+    }
+
+    tx.commit().await.map_err(db_error)?;
+    record_deploy_target_changes(&state.pool, &auth, Some(&before), Some(&after)).await;
+    Ok(Json(summary).into_response())
+}
+
+#[derive(Debug, Serialize)]
 struct DeployTargetDeleteResponse {
     archived: bool,
     message: String,
 }
 
 async fn delete_deploy_target(
+    Extension(auth): Extension<AuthContext>,
     State(state): State<DeployApiState>,
     Path(id): Path<Uuid>,
 ) -> Result<(StatusCode, Json<DeployTargetDeleteResponse>), (StatusCode, Json<ErrorResponse>)> {
+    let mut conn = state.pool.acquire().await.map_err(db_error)?;
+    let before = load_deploy_target_snapshot(&mut conn, id).await?;
+
     let has_jobs = sqlx::query_scalar::<_, bool>(
         "SELECT EXISTS(SELECT 1 FROM deploy_jobs WHERE deploy_target_id = $1)",
     )
@@ -2315,6 +2771,8 @@ async fn delete_deploy_target(
             ));
         }
 
+        let after = load_deploy_target_snapshot(&mut conn, id).await?;
+        record_deploy_target_changes(&state.pool, &auth, Some(&before), Some(&after)).await;
         return Ok((
             StatusCode::OK,
             Json(DeployTargetDeleteResponse {
@@ -2346,6 +2804,7 @@ async fn delete_deploy_target(
         ));
     }
 
+    record_deploy_target_changes(&state.pool, &auth, Some(&before), None).await;
     Ok((
         StatusCode::OK,
         Json(DeployTargetDeleteResponse {
@@ -2357,9 +2816,13 @@ async fn delete_deploy_target(
 
 async fn set_deploy_target_archived(
     state: &DeployApiState,
+    auth: &AuthContext,
     id: Uuid,
     archived: bool,
 ) -> Result<(StatusCode, Json<DeployTargetDeleteResponse>), (StatusCode, Json<ErrorResponse>)> {
+    let mut conn = state.pool.acquire().await.map_err(db_error)?;
+    let before = load_deploy_target_snapshot(&mut conn, id).await?;
+
     let result = sqlx::query("UPDATE deploy_targets SET is_archived = $1 WHERE id = $2")
         .bind(archived)
         .bind(id)
@@ -2383,6 +2846,8 @@ async fn set_deploy_target_archived(
         ));
     }
 
+    let after = load_deploy_target_snapshot(&mut conn, id).await?;
+    record_deploy_target_changes(&state.pool, auth, Some(&before), Some(&after)).await;
     Ok((
         StatusCode::OK,
         Json(DeployTargetDeleteResponse {
@@ -2397,17 +2862,19 @@ async fn set_deploy_target_archived(
 }
 
 async fn archive_deploy_target(
+    Extension(auth): Extension<AuthContext>,
     State(state): State<DeployApiState>,
     Path(id): Path<Uuid>,
 ) -> Result<(StatusCode, Json<DeployTargetDeleteResponse>), (StatusCode, Json<ErrorResponse>)> {
-    set_deploy_target_archived(&state, id, true).await
+    set_deploy_target_archived(&state, &auth, id, true).await
 }
 
 async fn unarchive_deploy_target(
+    Extension(auth): Extension<AuthContext>,
     State(state): State<DeployApiState>,
     Path(id): Path<Uuid>,
 ) -> Result<(StatusCode, Json<DeployTargetDeleteResponse>), (StatusCode, Json<ErrorResponse>)> {
-    set_deploy_target_archived(&state, id, false).await
+    set_deploy_target_archived(&state, &auth, id, false).await
 }
 
 async fn list_release_deploy_targets(
@@ -2472,7 +2939,8 @@ async fn list_release_deploy_jobs(
         SELECT dj.id, dj.release_id, dj.environment_id, dj.status, dj.started_at, dj.completed_at,
                dj.error_message, dj.commit_sha, dj.tag_name,
                e.name as target_name, e.slug AS env_name, e.color AS env_color,
-               r.is_auto, r.copy_job_id, b.id as bundle_id, dj.dry_run, dj.release_image_url_mode
+               r.is_auto, r.copy_job_id, b.id as bundle_id, dj.dry_run, dj.release_image_url_mode,
+               dj.env_overrides, dj.env_overrides_by, dj.validate_only
         FROM deploy_jobs dj
         JOIN environments e ON e.id = dj.environment_id
         JOIN releases r ON r.id = dj.release_id
@@ -2501,10 +2969,14 @@ async fn list_release_deploy_jobs(
 async fn list_deploy_jobs(
     Extension(auth): Extension<AuthContext>,
     State(state): State<DeployApiState>,
-) -> Result<Json<Vec<DeployJobListRow>>, (StatusCode, Json<ErrorResponse>)> {
-    let jobs = if auth.is_admin() {
-        sqlx::query_as::<_, DeployJobListRow>(
-            r#"
+    Query(query): Query<DeployJobListQuery>,
+) -> Result<(axum::http::HeaderMap, Json<Vec<DeployJobListRow>>), (StatusCode, Json<ErrorResponse>)> {
+    let refreshed_at = if query.fresh.unwrap_or(false) {
+        None
+    } else {
+        dashboard_views::usable_summary(&state.pool, "deploy_job_list_mv").await
+    };
+    let live = r#"
             SELECT
                 dj.id,
                 dj.status,
@@ -2532,47 +3004,25 @@ async fn list_deploy_jobs(
             JOIN bundle_versions bv ON bv.id = cj.bundle_version_id
             JOIN bundles b ON b.id = bv.bundle_id
             JOIN tenants t ON t.id = b.tenant_id
-            ORDER BY dj.started_at DESC
-            LIMIT 200
-            "#,
-        )
+        "#;
+    let source = if refreshed_at.is_some() {
+        dashboard_views::job_list_source("deploy_job_list_mv", "id", live)
+    } else {
+        format!("({}) jobs", live)
+    };
+
+    let jobs = if auth.is_admin() {
+        sqlx::query_as::<_, DeployJobListRow>(&format!(
+            "SELECT * FROM {} ORDER BY started_at DESC LIMIT 200",
+            source
+        ))
         .fetch_all(&state.pool)
         .await
     } else {
-        sqlx::query_as::<_, DeployJobListRow>(
-            r#"
-            SELECT
-                dj.id,
-                dj.status,
-                dj.started_at,
-                dj.completed_at,
-                dj.error_message,
-                dj.commit_sha,
-                dj.tag_name,
-                e.name as target_name,
-                e.slug AS env_name,
-                e.color AS env_color,
-                dj.environment_id,
-                r.id as release_db_id,
-                r.release_id,
-                r.is_auto,
-                b.id as bundle_id,
-                b.name as bundle_name,
-                t.id as tenant_id,
-                t.name as tenant_name,
-                dj.dry_run
-            FROM deploy_jobs dj
-            JOIN environments e ON e.id = dj.environment_id
-            JOIN releases r ON r.id = dj.release_id
-            JOIN copy_jobs cj ON cj.id = r.copy_job_id
-            JOIN bundle_versions bv ON bv.id = cj.bundle_version_id
-            JOIN bundles b ON b.id = bv.bundle_id
-            JOIN tenants t ON t.id = b.tenant_id
-            WHERE t.id = ANY($1)
-            ORDER BY dj.started_at DESC
-            LIMIT 200
-            "#,
-        )
+        sqlx::query_as::<_, DeployJobListRow>(&format!(
+            "SELECT * FROM {} WHERE tenant_id = ANY($1) ORDER BY started_at DESC LIMIT 200",
+            source
+        ))
         .bind(&auth.tenant_ids)
         .fetch_all(&state.pool)
         .await
@@ -2586,7 +3036,7 @@ async fn list_deploy_jobs(
         )
     })?;
 
-    Ok(Json(jobs))
+    Ok((dashboard_views::freshness_headers(refreshed_at), Json(jobs)))
 }
 
 async fn get_deploy_job(
@@ -2598,7 +3048,8 @@ async fn get_deploy_job(
         SELECT dj.id, dj.release_id, dj.environment_id, dj.status, dj.started_at, dj.completed_at,
                dj.error_message, dj.commit_sha, dj.tag_name,
                e.name as target_name, e.slug AS env_name, e.color AS env_color,
-               r.is_auto, r.copy_job_id, b.id as bundle_id, dj.dry_run, dj.release_image_url_mode
+               r.is_auto, r.copy_job_id, b.id as bundle_id, dj.dry_run, dj.release_image_url_mode,
+               dj.env_overrides, dj.env_overrides_by, dj.validate_only
         FROM deploy_jobs dj
         JOIN environments e ON e.id = dj.environment_id
         JOIN releases r ON r.id = dj.release_id
@@ -2667,7 +3118,7 @@ async fn get_deploy_job_inventory(
     }
 }
 
-async fn create_deploy_job(
+pub(crate) async fn create_deploy_job(
     Extension(auth): Extension<AuthContext>,
     State(state): State<DeployApiState>,
     Json(payload): Json<CreateDeployJobRequest>,
@@ -2767,12 +3218,33 @@ async fn create_deploy_job(
         ));
     }
 
+    let env_overrides = payload.env_overrides.unwrap_or_default();
+    if let Err(error) =
+        env_layers::validate_job_overrides(&env_overrides, &environment.job_env_override_allowlist)
+    {
+        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse { error })));
+    }
+
+    if environment.validate_only_required && payload.validate_only == Some(false) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Environment allows only validate-only deploy jobs".to_string(),
+            }),
+        ));
+    }
+
     let job_id = create_deploy_job_record(
         &state,
         payload.release_id,
-        environment.id,
-        payload.dry_run.unwrap_or(true),
-        normalize_release_image_url_mode(payload.release_image_url_mode),
+        &environment,
+        DeployJobOptions {
+            dry_run: payload.dry_run.unwrap_or(true),
+            release_image_url_mode: normalize_release_image_url_mode(payload.release_image_url_mode),
+            validate_only: payload.validate_only.unwrap_or(false),
+        },
+        &env_overrides,
+        Some(&auth.username),
     )
     .await?;
 
@@ -2821,6 +3293,9 @@ async fn auto_deploy_from_copy_job(
             }),
         ));
     }
+    let channel = release_channels::parse_optional(payload.channel.as_deref())
+        .map_err(|error| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })))?
+        .unwrap_or(release_channels::AUTO_RELEASE_CHANNEL);
 
     let job_row = sqlx::query_as::<_, (String, String, Uuid)>(
         r#"
@@ -2915,9 +3390,9 @@ async fn auto_deploy_from_copy_job(
         if let Some(release) = existing_by_tag {
             release
         } else {
-            sqlx::query_as::<_, Release>(
-                "INSERT INTO releases (copy_job_id, release_id, status, source_ref_mode, notes, created_by, is_auto, auto_reason)
-                 VALUES ($1, $2, 'draft', 'tag', $3, $4, true, $5)
+            let release = sqlx::query_as::<_, Release>(
+                "INSERT INTO releases (copy_job_id, release_id, status, source_ref_mode, notes, created_by, is_auto, auto_reason, channel)
+                 VALUES ($1, $2, 'draft', 'tag', $3, $4, true, $5, $6)
                  RETURNING *",
             )
             .bind(payload.copy_job_id)
@@ -2925,6 +3400,7 @@ async fn auto_deploy_from_copy_job(
             .bind("Auto release from copy job")
             .bind("system")
             .bind("copy_job_deploy")
+            .bind(channel)
             .fetch_one(&state.pool)
             .await
             .map_err(|e| {
@@ -2934,7 +3410,10 @@ async fn auto_deploy_from_copy_job(
                         error: format!("Failed to create auto release: {}", e),
                     }),
                 )
-            })?
+            })?;
+            store_manifest_snapshot(&state.pool, release.id).await;
+            lifecycle_notifications::release_created(&state.pool, &state.notifier, release.id).await;
+            release
         }
     };
 
@@ -2942,9 +3421,14 @@ async fn auto_deploy_from_copy_job(
     let job_id = create_deploy_job_record(
         &state,
         release.id,
-        environment.id,
-        dry_run,
-        normalize_release_image_url_mode(payload.release_image_url_mode),
+        &environment,
+        DeployJobOptions {
+            dry_run,
+            release_image_url_mode: normalize_release_image_url_mode(payload.release_image_url_mode),
+            validate_only: false,
+        },
+        &BTreeMap::new(),
+        None,
     )
     .await?;
 
@@ -2957,23 +3441,66 @@ async fn auto_deploy_from_copy_job(
     ))
 }
 
+struct DeployJobOptions {
+    dry_run: bool,
+    release_image_url_mode: String,
+    validate_only: bool,
+}
+
 async fn create_deploy_job_record(
     state: &DeployApiState,
     release_id: Uuid,
-    environment_id: Uuid,
-    dry_run: bool,
-    release_image_url_mode: String,
+    environment: &Environment,
+    options: DeployJobOptions,
+    env_overrides: &BTreeMap<String, String>,
+    requested_by: Option<&str>,
 ) -> Result<Uuid, (StatusCode, Json<ErrorResponse>)> {
+    // Odběr kanálů platí pro ruční, pipeline i automatické deploye
+    let channel = sqlx::query_scalar::<_, String>("SELECT channel FROM releases WHERE id = $1")
+        .bind(release_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Database error: {}", e),
+                }),
+            )
+        })?;
+    if let Some(channel) = channel
+        && !release_channels::is_subscribed(&environment.release_channels, &channel)
+    {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: format!(
+                    "Environment {} does not subscribe to release channel '{}' (subscribed: {})",
+                    environment.slug,
+                    channel,
+                    environment.release_channels.join(", ")
+                ),
+            }),
+        ));
+    }
+
     let job_id = Uuid::new_v4();
+    // Politika environmentu má přednost i před automatickými deployi
+    let validate_only = options.validate_only || environment.validate_only_required;
+    // Autor se ukládá jen k jobům, které něco přepisují (audit)
+    let env_overrides_by = requested_by.filter(|_| !env_overrides.is_empty());
     sqlx::query(
-        "INSERT INTO deploy_jobs (id, release_id, environment_id, status, dry_run, release_image_url_mode)
-         VALUES ($1, $2, $3, 'pending', $4, $5)",
+        "INSERT INTO deploy_jobs (id, release_id, environment_id, status, dry_run, release_image_url_mode, env_overrides, env_overrides_by, validate_only)
+         VALUES ($1, $2, $3, 'pending', $4, $5, $6, $7, $8)",
     )
     .bind(job_id)
     .bind(release_id)
-    .bind(environment_id)
-    .bind(dry_run)
-    .bind(release_image_url_mode)
+    .bind(environment.id)
+    .bind(options.dry_run)
+    .bind(options.release_image_url_mode)
+    .bind(serde_json::json!(env_overrides))
+    .bind(env_overrides_by)
+    .bind(validate_only)
     .execute(&state.pool)
     .await
     .map_err(|e| {
@@ -2985,7 +3512,22 @@ async fn create_deploy_job_record(
         )
     })?;
 
-    ensure_deploy_job_log_channel(state, job_id).await;
+    // V roli `web` job poběží na workeru a v prostředí s agentem na agentovi, lokální kanál by zůstal prázdný
+    if state.dispatch == JobDispatch::InProcess
+        && job_queue::environment_agent(&state.pool, environment.id)
+            .await
+            .map_err(|e| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse {
+                        error: format!("Database error: {}", e),
+                    }),
+                )
+            })?
+            .is_none()
+    {
+        ensure_deploy_job_log_channel(state, job_id).await;
+    }
 
     Ok(job_id)
 }
@@ -3001,23 +3543,74 @@ async fn ensure_deploy_job_log_channel(state: &DeployApiState, job_id: Uuid) ->
     let log_persist_state = state.clone();
     let mut log_rx = log_tx.subscribe();
     tokio::spawn(async move {
+        let pool = &log_persist_state.pool;
         while let Ok(line) = log_rx.recv().await {
-            let _ = sqlx::query(
-                "INSERT INTO deploy_job_logs (deploy_job_id, log_line) VALUES ($1, $2)",
-            )
-            .bind(job_id)
-            .bind(line)
-            .execute(&log_persist_state.pool)
-            .await;
+            let _ = log_persist_state
+                .log_fanout
+                .persist_line(pool, JobKind::Deploy, job_id, &line)
+                .await;
         }
+        let _ = log_persist_state.log_fanout.end_log(pool, JobKind::Deploy, job_id).await;
     });
 
     log_tx
 }
 
-async fn start_deploy_job(
+/// POST /api/v1/deploy/jobs/{id}/start - Ruční spuštění uživatelem se v timeline jobu eviduje jako schválení
+async fn approve_and_start_deploy_job(
+    Extension(auth): Extension<AuthContext>,
+    State(state): State<DeployApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<(StatusCode, Json<DeployJobResponse>), (StatusCode, Json<ErrorResponse>)> {
+    job_events::record_approval(&state.pool, id, &auth.username).await;
+    start_deploy_job(State(state), Path(id)).await
+}
+
+/// Spustí pending deploy job (v roli `web` a v prostředí s agentem ho zařadí do fronty)
+pub(crate) async fn start_deploy_job(
     State(state): State<DeployApiState>,
     Path(id): Path<Uuid>,
+) -> Result<(StatusCode, Json<DeployJobResponse>), (StatusCode, Json<ErrorResponse>)> {
+    let db_error = |e: sqlx::Error| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }),
+        )
+    };
+    // Prostředí s agentem: job vždy jen do fronty, převezme ho agent
+    let has_agent = job_queue::job_agent(&state.pool, JobKind::Deploy, id)
+        .await
+        .map_err(db_error)?
+        .is_some();
+    if state.dispatch == JobDispatch::Queue || has_agent {
+        let queued = job_queue::enqueue(&state.pool, JobKind::Deploy, id)
+            .await
+            .map_err(db_error)?;
+        if !queued {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: "Deploy job is not pending".to_string(),
+                }),
+            ));
+        }
+        return Ok((
+            StatusCode::ACCEPTED,
+            Json(DeployJobResponse {
+                job_id: id,
+                message: "Deploy job queued".to_string(),
+            }),
+        ));
+    }
+    launch_deploy_job(state, id).await
+}
+
+/// Spustí deploy job v aktuálním procesu (API v roli `all`, worker)
+pub(crate) async fn launch_deploy_job(
+    state: DeployApiState,
+    id: Uuid,
 ) -> Result<(StatusCode, Json<DeployJobResponse>), (StatusCode, Json<ErrorResponse>)> {
     let updated = sqlx::query(
         "UPDATE deploy_jobs SET status = 'in_progress', started_at = NOW() WHERE id = $1 AND status = 'pending'",
@@ -3043,11 +3636,14 @@ async fn start_deploy_job(
         ));
     }
 
+    lifecycle_notifications::deploy_job(&state.pool, &state.notifier, &DEPLOY_JOB_STARTED, id).await;
+
     let log_tx = ensure_deploy_job_log_channel(&state, id).await;
     let state_clone = state.clone();
     tokio::spawn(async move {
         if let Err(e) = run_deploy_job(state_clone.clone(), id, log_tx.clone()).await {
             let _ = log_tx.send(format!("Deploy job failed: {}", e));
+            deploy_steps::fail(&state_clone.pool, id, &e).await;
             let _ = sqlx::query(
                 "UPDATE deploy_jobs SET status = 'failed', completed_at = NOW(), error_message = $1 WHERE id = $2",
             )
@@ -3056,6 +3652,9 @@ async fn start_deploy_job(
             .execute(&state_clone.pool)
             .await;
         }
+        lifecycle_notifications::deploy_job(&state_clone.pool, &state_clone.notifier, &DEPLOY_JOB_FINISHED, id).await;
+        // Uzavře log stream (persist task pak ohlásí konec i ostatním instancím)
+        state_clone.job_logs.write().await.remove(&id);
     });
 
     Ok((
@@ -3067,8 +3666,51 @@ async fn start_deploy_job(
     ))
 }
 
+/// Deploy job přerušený pádem workeru
+pub(crate) async fn fail_interrupted_deploy_job(state: &DeployApiState, job_id: Uuid, reason: &str) {
+    let _ = state.log_fanout.persist_line(&state.pool, JobKind::Deploy, job_id, reason).await;
+    deploy_steps::fail(&state.pool, job_id, &anyhow::anyhow!(reason.to_string())).await;
+    let _ = sqlx::query(
+        "UPDATE deploy_jobs SET status = 'failed', completed_at = NOW(), error_message = $1
+         WHERE id = $2 AND status = 'in_progress'",
+    )
+    .bind(reason)
+    .bind(job_id)
+    .execute(&state.pool)
+    .await;
+    lifecycle_notifications::deploy_job(&state.pool, &state.notifier, &DEPLOY_JOB_FINISHED, job_id).await;
+    let _ = state.log_fanout.end_log(&state.pool, JobKind::Deploy, job_id).await;
+}
+
+/// Počet, formát a shoda párů encjson klíčů ještě před zápisem deploy targetu
+fn check_encjson_keys(keys: &[EncjsonKeyInput]) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    let bad_request = |error: String| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error }));
+    if keys.len() > key_material::MAX_ENCJSON_KEYS {
+        return Err(bad_request(format!(
+            "Too many encjson keys ({}), at most {} per deploy target",
+            keys.len(),
+            key_material::MAX_ENCJSON_KEYS
+        )));
+    }
+    let mut seen = HashSet::new();
+    for key in keys {
+        if key.public_key.trim().is_empty() {
+            return Err(bad_request("Encjson public key cannot be empty".to_string()));
+        }
+        let public = key_material::validate_encjson_public_key(&key.public_key).map_err(bad_request)?;
+        if !seen.insert(public.to_ascii_lowercase()) {
+            return Err(bad_request(format!("Duplicate encjson public key {}", public)));
+        }
+        if let Some(private) = key.private_key.as_deref().filter(|v| !v.trim().is_empty()) {
+            key_material::validate_encjson_keypair(&public, private).map_err(bad_request)?;
+        }
+    }
+    Ok(())
+}
+
 async fn store_encjson_keys(
-    state: &DeployApiState,
+    conn: &mut PgConnection,
+    encryption_secret: &str,
     deploy_target_id: Uuid,
     keys: Vec<EncjsonKeyInput>,
 ) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
@@ -3077,7 +3719,7 @@ async fn store_encjson_keys(
         "SELECT * FROM deploy_target_encjson_keys WHERE deploy_target_id = $1",
     )
     .bind(deploy_target_id)
-    .fetch_all(&state.pool)
+    .fetch_all(&mut *conn)
     .await
     .map_err(|e| {
         (
@@ -3092,19 +3734,14 @@ async fn store_encjson_keys(
         existing.insert(row.public_key, row.private_key_encrypted);
     }
 
+    check_encjson_keys(&keys)?;
     let mut resolved = Vec::new();
     for key in keys {
         let public = key.public_key.trim().to_string();
-        if public.is_empty() {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: "Encjson public key cannot be empty".to_string(),
-                }),
-            ));
-        }
         let encrypted = if let Some(private) = key.private_key.as_deref().filter(|v| !v.trim().is_empty()) {
-            crypto::encrypt(private, &state.encryption_secret).map_err(|e| {
+            let private = key_material::validate_encjson_keypair(&public, private)
+                .map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: e })))?;
+            crypto::encrypt(&private, encryption_secret).map_err(|e| {
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     Json(ErrorResponse {
@@ -3126,7 +3763,7 @@ async fn store_encjson_keys(
         resolved.push((public, encrypted));
     }
 
-    let mut tx = state.pool.begin().await.map_err(|e| {
+    let mut tx = conn.begin().await.map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse {
@@ -3180,7 +3817,7 @@ async fn store_encjson_keys(
 }
 
 async fn store_deploy_target_env_vars(
-    state: &DeployApiState,
+    conn: &mut PgConnection,
     deploy_target_id: Uuid,
     vars: Vec<DeployTargetEnvVarInput>,
 ) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
@@ -3199,7 +3836,7 @@ async fn store_deploy_target_env_vars(
         cleaned.push((source_key, target_key));
     }
 
-    let mut tx = state.pool.begin().await.map_err(|e| {
+    let mut tx = conn.begin().await.map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse {
@@ -3253,7 +3890,7 @@ async fn store_deploy_target_env_vars(
 }
 
 async fn store_deploy_target_extra_env_vars(
-    state: &DeployApiState,
+    conn: &mut PgConnection,
     deploy_target_id: Uuid,
     vars: Vec<DeployTargetExtraEnvVarInput>,
 ) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
@@ -3272,7 +3909,7 @@ async fn store_deploy_target_extra_env_vars(
         cleaned.push((key, value));
     }
 
-    let mut tx = state.pool.begin().await.map_err(|e| {
+    let mut tx = conn.begin().await.map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse {
@@ -3325,6 +3962,39 @@ async fn store_deploy_target_extra_env_vars(
     Ok(())
 }
 
+/// Evidence kroků deploy pipeline - worker je zapisuje do DB, agent je posílá na server.
+/// Chybu běžícího kroku zapíše až `deploy_steps::fail` při uzavření jobu.
+pub(crate) trait DeployStepSink {
+    async fn start(&self, step: &str);
+    async fn success(&self, step: &str);
+    async fn skip(&self, step: &str, reason: &str);
+    async fn warning(&self, step: &str, message: &str);
+}
+
+/// Kroky deploy jobu běžícího v tomto procesu
+struct DbDeploySteps<'a> {
+    pool: &'a PgPool,
+    job_id: Uuid,
+}
+
+impl DeployStepSink for DbDeploySteps<'_> {
+    async fn start(&self, step: &str) {
+        deploy_steps::start(self.pool, self.job_id, step).await;
+    }
+
+    async fn success(&self, step: &str) {
+        deploy_steps::success(self.pool, self.job_id, step).await;
+    }
+
+    async fn skip(&self, step: &str, reason: &str) {
+        deploy_steps::skip(self.pool, self.job_id, step, reason).await;
+    }
+
+    async fn warning(&self, step: &str, message: &str) {
+        deploy_steps::warning(self.pool, self.job_id, step, message).await;
+    }
+}
+
 async fn run_deploy_job(state: DeployApiState, job_id: Uuid, log_tx: broadcast::Sender<String>) -> anyhow::Result<()> {
     let _ = log_tx.send(format!("Starting deploy job {}", job_id));
 
@@ -3333,6 +4003,42 @@ async fn run_deploy_job(state: DeployApiState, job_id: Uuid, log_tx: broadcast::
         .execute(&state.pool)
         .await?;
 
+    // Chybu běžícího kroku zapíše `deploy_steps::fail` v launch_deploy_job
+    deploy_steps::init(&state.pool, job_id).await;
+    deploy_steps::start(&state.pool, job_id, "prepare").await;
+
+    let work = prepare_deploy_work(&state, job_id, &log_tx).await?;
+    let steps = DbDeploySteps {
+        pool: &state.pool,
+        job_id,
+    };
+    let mut outcome = DeployOutcome::default();
+    let result = execute_deploy_work(&state.tools(), work, &steps, &log_tx, &mut outcome).await;
+    let commit_sha = outcome.commit_sha.clone();
+    let tag_name = outcome.tag_name.clone();
+    let stored = store_deploy_outcome(&state, job_id, outcome, &log_tx).await;
+    result?;
+    stored?;
+
+    sqlx::query(
+        "UPDATE deploy_jobs SET status = 'success', completed_at = NOW(), commit_sha = $1, tag_name = $2 WHERE id = $3",
+    )
+    .bind(&commit_sha)
+    .bind(&tag_name)
+    .bind(job_id)
+    .execute(&state.pool)
+    .await?;
+
+    let _ = log_tx.send("Deploy job completed successfully".to_string());
+    Ok(())
+}
+
+/// Načte z DB vše, co deploy pipeline potřebuje; credentials a šablony souborů dešifruje
+pub(crate) async fn prepare_deploy_work(
+    state: &DeployApiState,
+    job_id: Uuid,
+    log_tx: &broadcast::Sender<String>,
+) -> anyhow::Result<DeployWork> {
     let job = sqlx::query_as::<_, DeployJob>("SELECT * FROM deploy_jobs WHERE id = $1")
         .bind(job_id)
         .fetch_one(&state.pool)
@@ -3345,18 +4051,42 @@ async fn run_deploy_job(state: DeployApiState, job_id: Uuid, log_tx: broadcast::
     .fetch_one(&state.pool)
     .await?;
 
+    let overrides = tool_paths::for_environment(&state.pool, environment.id).await?;
+
     let release = sqlx::query_as::<_, Release>("SELECT * FROM releases WHERE id = $1")
         .bind(job.release_id)
         .fetch_one(&state.pool)
         .await?;
 
-    let env_var_rows = env_vars_from_json(&environment.release_env_var_mappings);
-    let extra_env_rows = extra_env_vars_from_json(&environment.extra_env_vars);
-    let mapped_vars = build_release_env_var_map(&env_var_rows, &release, &log_tx);
-
-    let temp_dir = TempDir::new()?;
-    let env_repo_path = temp_dir.path().join("environments");
-    let deploy_repo_path = temp_dir.path().join("deploy");
+    let job_env = build_job_env(&state.pool, &job, &environment, &release).await?;
+    log_job_env(&job_env, log_tx);
+    if let Some(requested_by) = job.env_overrides_by.as_deref() {
+        let _ = log_tx.send(format!("Job env overrides requested by {}", requested_by));
+    }
+    sqlx::query("UPDATE deploy_jobs SET resolved_env = $1 WHERE id = $2")
+        .bind(serde_json::to_value(&job_env)?)
+        .bind(job_id)
+        .execute(&state.pool)
+        .await?;
+    let env_var_rows: Vec<DeployTargetEnvVarInput> = job_env
+        .resolved
+        .release_env_var_mappings
+        .iter()
+        .map(|m| DeployTargetEnvVarInput {
+            source_key: m.source_key.clone(),
+            target_key: m.target_key.clone(),
+        })
+        .collect();
+    let release_env_vars = build_release_env_var_map(&env_var_rows, &release, log_tx);
+    let extra_env_vars = job_env
+        .resolved
+        .extra_env_vars
+        .iter()
+        .map(|v| DeployEnvVar {
+            key: v.key.clone(),
+            value: v.value.clone(),
+        })
+        .collect();
 
     let env_repo_id = environment
         .env_repo_id
@@ -3374,9 +4104,6 @@ async fn run_deploy_job(state: DeployApiState, job_id: Uuid, log_tx: broadcast::
         .fetch_one(&state.pool)
         .await?;
 
-    let git_env_env = build_git_env_for_repo(&state, &env_repo, temp_dir.path())?;
-    let git_env_deploy = build_git_env_for_repo(&state, &deploy_repo, temp_dir.path())?;
-
     let env_branch = environment
         .env_repo_branch
         .as_deref()
@@ -3388,29 +4115,9 @@ async fn run_deploy_job(state: DeployApiState, job_id: Uuid, log_tx: broadcast::
         .filter(|v| !v.trim().is_empty())
         .unwrap_or(&deploy_repo.default_branch);
 
-    run_git_clone(&env_repo.repo_url, env_branch, &env_repo_path, &git_env_env, &log_tx).await?;
-    run_git_clone(&deploy_repo.repo_url, deploy_branch, &deploy_repo_path, &git_env_deploy, &log_tx).await?;
-
-    let mut release_manifest = build_release_manifest(&state.pool, release.id).await?;
-    let env_repo_subdir = environment
-        .env_repo_path
-        .as_deref()
-        .unwrap_or(&environment.slug);
-    let env_repo_subdir = env_repo_subdir.trim().trim_start_matches('/').to_string();
-    apply_release_manifest_mode(
-        environment
-            .release_manifest_mode
-            .as_deref()
-            .unwrap_or("strict"),
-        &mut release_manifest,
-        &env_repo_path,
-        &environment.slug,
-        Some(env_repo_subdir.as_str()),
-    )
-    .await?;
-
+    let mut release_manifest = load_release_manifest(&state.pool, release.id).await?;
     if job.release_image_url_mode == "environment_registry" {
-        retarget_release_manifest_to_environment(&state.pool, &mut release_manifest, &environment, &log_tx)
+        retarget_release_manifest_to_environment(&state.pool, &mut release_manifest, &environment, log_tx)
             .await?;
     } else {
         let _ = log_tx.send(
@@ -3418,57 +4125,186 @@ async fn run_deploy_job(state: DeployApiState, job_id: Uuid, log_tx: broadcast::
         );
     }
 
-    let manifest_path = temp_dir.path().join("release-manifest.yml");
-    let yaml = serde_yaml_ng::to_string(&release_manifest)?;
-    tokio::fs::write(&manifest_path, yaml)
-        .await
-        .with_context(|| format!("Failed to write release manifest to {}", manifest_path.display()))?;
-
+    let env_repo_subdir = environment
+        .env_repo_path
+        .as_deref()
+        .unwrap_or(&environment.slug);
     let deploy_rel_path = environment
         .deploy_repo_path
         .as_deref()
         .unwrap_or("")
         .trim()
         .trim_start_matches('/');
-    let deploy_path = if deploy_rel_path.is_empty() {
-        deploy_repo_path.clone()
+    let tag_name = if environment.append_env_suffix {
+        format!("{}-{}", release.release_id, environment.slug)
     } else {
-        deploy_repo_path.join(deploy_rel_path)
+        release.release_id.clone()
     };
-    if !deploy_path.exists() {
-        tokio::fs::create_dir_all(&deploy_path)
-            .await
-            .with_context(|| format!("Failed to create deploy path {}", deploy_path.display()))?;
+
+    Ok(DeployWork {
+        job_id,
+        environment_slug: environment.slug.clone(),
+        release_id: release.release_id.clone(),
+        tag_name,
+        dry_run: job.dry_run,
+        // Politika environmentu platí i pro joby založené před jejím zapnutím
+        validate_only: job.validate_only || environment.validate_only_required,
+        env_repo: deploy_work_repo(state, &env_repo, env_branch)?,
+        deploy_repo: deploy_work_repo(state, &deploy_repo, deploy_branch)?,
+        env_repo_path: env_repo_subdir.trim().trim_start_matches('/').to_string(),
+        deploy_repo_path: deploy_rel_path.to_string(),
+        release_manifest_mode: environment
+            .release_manifest_mode
+            .clone()
+            .unwrap_or_else(|| "strict".to_string()),
+        release_manifest,
+        release_env_vars,
+        extra_env_vars,
+        encjson_key_dir: environment.encjson_key_dir.clone(),
+        file_templates: load_file_templates(state, &job).await?,
+        encjson_path: overrides.encjson_path,
+        kube_build_app_path: overrides.kube_build_app_path,
+        diff_max_bytes: state.diff_limits.max_bytes,
+    })
+}
+
+/// Deploy pipeline bez přístupu k DB (clone, render, encjson, kubeconform, diff, push) - běží na workeru
+/// i na agentovi. Výsledky plní do `outcome` průběžně, takže zůstanou i po chybě.
+pub(crate) async fn execute_deploy_work(
+    tools: &DeployTools,
+    mut work: DeployWork,
+    steps: &impl DeployStepSink,
+    log_tx: &broadcast::Sender<String>,
+    outcome: &mut DeployOutcome,
+) -> anyhow::Result<()> {
+    if let Some(path) = &work.encjson_path {
+        let _ = log_tx.send(format!("encjson override: {}", path));
+    }
+    if let Some(path) = &work.kube_build_app_path {
+        let _ = log_tx.send(format!("kube_build_app override: {}", path));
     }
+    let tools = tools.with_overrides(work.encjson_path.as_deref(), work.kube_build_app_path.as_deref());
 
-    clean_deploy_output(&deploy_path).await?;
+    let job_id = work.job_id;
+    let validate_only = work.validate_only;
+    let mut report = ValidationReport::default();
+    if validate_only {
+        let _ = log_tx.send("Validate-only job: render + validation only, git writes are disabled".to_string());
+    }
+
+    // Prefix s job id - reaper pozná adresáře běžících jobů
+    let temp_dir = tempfile::Builder::new()
+        .prefix(&format!("{}{}-", reaper::DEPLOY_TEMP_PREFIX, job_id))
+        .tempdir()?;
+    let env_repo_path = temp_dir.path().join("environments");
+    let deploy_repo_path = temp_dir.path().join("deploy");
+
+    let git_env_env = git_env_for_repo(&work.env_repo, temp_dir.path())?;
+    let git_env_deploy = git_env_for_repo(&work.deploy_repo, temp_dir.path())?;
+
+    hand_over_workspace(&tools.sandbox, temp_dir.path(), log_tx)?;
+    steps.success("prepare").await;
+
+    steps.start("clone").await;
+    run_git_clone(&tools.sandbox, &work.env_repo.repo_url, &work.env_repo.branch, &env_repo_path, &git_env_env, log_tx).await?;
+    run_git_clone(&tools.sandbox, &work.deploy_repo.repo_url, &work.deploy_repo.branch, &deploy_repo_path, &git_env_deploy, log_tx).await?;
+    if validate_only {
+        disable_git_push(&tools.sandbox, &env_repo_path, log_tx).await?;
+        disable_git_push(&tools.sandbox, &deploy_repo_path, log_tx).await?;
+    }
+    steps.success("clone").await;
+
+    steps.start("render").await;
+
+    let env_repo_subdir = work.env_repo_path.as_str();
+    let release_manifest = &mut work.release_manifest;
+    if validate_only {
+        let manifest_mode = validation_report::strict_manifest_mode(&work.release_manifest_mode);
+        let _ = log_tx.send(format!("Release manifest mode forced to {}", manifest_mode));
+        match apply_release_manifest_mode(
+            &manifest_mode,
+            release_manifest,
+            &env_repo_path,
+            &work.environment_slug,
+            Some(env_repo_subdir),
+        )
+        .await
+        {
+            Ok(()) => report.record("release_manifest", CheckStatus::Passed, None),
+            Err(err) => {
+                let _ = log_tx.send(format!("Release manifest check failed: {:#}", err));
+                report.record("release_manifest", CheckStatus::Failed, Some(format!("{:#}", err)));
+            }
+        }
+    } else {
+        apply_release_manifest_mode(
+            &work.release_manifest_mode,
+            release_manifest,
+            &env_repo_path,
+            &work.environment_slug,
+            Some(env_repo_subdir),
+        )
+        .await?;
+    }
+
+    let schema_errors = release_manifest::validate_manifest(release_manifest);
+    if validate_only {
+        if schema_errors.is_empty() {
+            report.record("manifest_schema", CheckStatus::Passed, None);
+        } else {
+            report.record("manifest_schema", CheckStatus::Failed, Some(schema_errors.join("; ")));
+        }
+    } else if !schema_errors.is_empty() {
+        anyhow::bail!("Release manifest does not match schema: {}", schema_errors.join("; "));
+    }
+
+    let manifest_path = temp_dir.path().join("release-manifest.yml");
+    let yaml = serde_yaml_ng::to_string(release_manifest)?;
+    tokio::fs::write(&manifest_path, yaml)
+        .await
+        .with_context(|| format!("Failed to write release manifest to {}", manifest_path.display()))?;
+
+    let deploy_rel_path = work.deploy_repo_path.as_str();
+    let deploy_path = if deploy_rel_path.is_empty() {
+        deploy_repo_path.clone()
+    } else {
+        deploy_repo_path.join(deploy_rel_path)
+    };
+    if !deploy_path.exists() {
+        tokio::fs::create_dir_all(&deploy_path)
+            .await
+            .with_context(|| format!("Failed to create deploy path {}", deploy_path.display()))?;
+    }
+
+    clean_deploy_output(&deploy_path).await?;
 
     let kube_build_env = build_kube_build_env(
-        &environment.slug,
-        &release,
+        &work.environment_slug,
+        &work.release_id,
         &manifest_path,
         &env_repo_path,
-        &mapped_vars,
-        &extra_env_rows,
+        &work.release_env_vars,
+        &work.extra_env_vars,
     )?;
+    hand_over_workspace(&tools.sandbox, temp_dir.path(), log_tx)?;
     let _ = log_tx.send("== kube_build_app generate ==".to_string());
     run_command_logged(
-        &state.kube_build_app_path,
-        &["-e", &environment.slug, "-t", deploy_path.to_string_lossy().as_ref(), "-r", manifest_path.to_string_lossy().as_ref()],
+        tools.sandbox.program(SandboxTool::KubeBuildApp, &tools.kube_build_app_path),
+        &["-e", &work.environment_slug, "-t", deploy_path.to_string_lossy().as_ref(), "-r", manifest_path.to_string_lossy().as_ref()],
         Some(&env_repo_path),
         &kube_build_env,
-        &log_tx,
+        log_tx,
         "kube_build_app",
     )
     .await?;
 
     let _ = log_tx.send("== kube_build_app summary (-s) ==".to_string());
     run_command_logged(
-        &state.kube_build_app_path,
-        &["-e", &environment.slug, "-s"],
+        tools.sandbox.program(SandboxTool::KubeBuildApp, &tools.kube_build_app_path),
+        &["-e", &work.environment_slug, "-s"],
         Some(&env_repo_path),
         &kube_build_env,
-        &log_tx,
+        log_tx,
         "kube_build_app -s",
     )
     .await?;
@@ -3476,8 +4312,8 @@ async fn run_deploy_job(state: DeployApiState, job_id: Uuid, log_tx: broadcast::
     let _ = log_tx.send("== kube_build_app inventory (-i) ==".to_string());
     let _ = log_tx.send("Collecting inventory...".to_string());
     match run_command_capture(
-        &state.kube_build_app_path,
-        &["-e", &environment.slug, "-r", manifest_path.to_string_lossy().as_ref(), "-i"],
+        tools.sandbox.program(SandboxTool::KubeBuildApp, &tools.kube_build_app_path),
+        &["-e", &work.environment_slug, "-r", manifest_path.to_string_lossy().as_ref(), "-i"],
         Some(&env_repo_path),
         &kube_build_env,
         "kube_build_app -i",
@@ -3496,55 +4332,77 @@ async fn run_deploy_job(state: DeployApiState, job_id: Uuid, log_tx: broadcast::
             } else {
                 let _ = log_tx.send("Inventory parse failed: output was not valid JSON".to_string());
             }
-            let generated_profiles_json = if generated_profiles.is_empty() {
+            outcome.generated_profiles = if generated_profiles.is_empty() {
                 None
             } else {
                 Some(serde_json::json!(generated_profiles))
             };
-            sqlx::query(
-                "UPDATE deploy_jobs SET kube_build_inventory = $1, generated_profiles = $2 WHERE id = $3",
-            )
-            .bind(parsed_inventory)
-            .bind(generated_profiles_json)
-            .bind(job_id)
-            .execute(&state.pool)
-            .await?;
+            outcome.inventory = parsed_inventory;
         }
         Err(err) => {
             let _ = log_tx.send(format!("kube_build_app -i failed (ignored): {}", err));
         }
     }
 
+    steps.success("render").await;
+
+    steps.start("encjson").await;
     let env_file_path = temp_dir.path().join("release.env");
     build_env_file(
-        &state,
-        &environment,
-        environment.encjson_key_dir.as_deref(),
+        &tools,
+        work.encjson_key_dir.as_deref(),
         &env_repo_path,
-        env_repo_subdir.as_str(),
+        env_repo_subdir,
         &env_file_path,
-        &release,
-        &env_var_rows,
-        &extra_env_rows,
-        &log_tx,
+        &work.release_id,
+        &work.release_env_vars,
+        &work.extra_env_vars,
+        log_tx,
     )
     .await?;
 
-    apply_env_to_outputs(&state, &deploy_path, &env_file_path, &log_tx).await?;
+    hand_over_workspace(&tools.sandbox, temp_dir.path(), log_tx)?;
+    apply_env_to_outputs(&tools, &deploy_path, &env_file_path, log_tx).await?;
+    write_file_templates(&tools, &work.file_templates, &deploy_path, temp_dir.path(), &env_file_path, log_tx).await?;
+    steps.success("encjson").await;
 
-    if let Err(err) = collect_and_store_deploy_images(&state.pool, job_id, &deploy_path, &log_tx).await {
-        let _ = log_tx.send(format!("Failed to collect deploy images (ignored): {}", err));
+    match collect_deploy_images(&deploy_path, log_tx).await {
+        Ok(rows) => {
+            if rows.is_empty() {
+                let _ = log_tx.send("No deploy images detected (deployments folder empty)".to_string());
+            }
+            if validate_only {
+                report.images = rows.len();
+                let images: Vec<String> = rows.iter().map(|row| row.image.clone()).collect();
+                let release_repos: Vec<String> = release_manifest.images.iter().map(|img| img.image.clone()).collect();
+                let foreign = validation_report::foreign_images(&images, &release_repos);
+                if foreign.is_empty() {
+                    report.record("images", CheckStatus::Passed, None);
+                } else {
+                    let _ = log_tx.send(format!("Images not from release manifest: {}", foreign.join(", ")));
+                    report.record("images", CheckStatus::Warning, Some(foreign.join(", ")));
+                }
+            }
+            outcome.images = Some(rows);
+        }
+        Err(err) => {
+            let _ = log_tx.send(format!("Failed to collect deploy images (ignored): {}", err));
+            report.record("images", CheckStatus::Skipped, Some(format!("{:#}", err)));
+        }
     }
 
-    let kubeconform_path = state.kubeconform_path.trim();
+    let kubeconform_path = tools.kubeconform_path.trim();
+    steps.start("kubeconform").await;
     if kubeconform_path.is_empty() {
         let _ = log_tx.send("kubeconform skipped (KUBECONFORM_PATH not set)".to_string());
+        steps.skip("kubeconform", "KUBECONFORM_PATH not set").await;
+        report.record("kubeconform", CheckStatus::Skipped, Some("KUBECONFORM_PATH not set".to_string()));
     } else if let Err(err) = run_command_logged(
-        kubeconform_path,
+        tools.sandbox.program(SandboxTool::Kubeconform, kubeconform_path),
         &["-strict", "-ignore-missing-schemas", "-summary", "-output", "json", "."],
         Some(&deploy_path),
         &HashMap::new(),
-        &log_tx,
+        log_tx,
         "kubeconform",
     )
     .await
@@ -3557,122 +4415,196 @@ async fn run_deploy_job(state: DeployApiState, job_id: Uuid, log_tx: broadcast::
 
         if not_found {
             let _ = log_tx.send("kubeconform not found, skipping validation".to_string());
+            steps.skip("kubeconform", "kubeconform not found").await;
+            report.record("kubeconform", CheckStatus::Skipped, Some("kubeconform not found".to_string()));
+        } else if validate_only {
+            let _ = log_tx.send("kubeconform reported errors".to_string());
+            steps.warning("kubeconform", &format!("{:#}", err)).await;
+            report.record("kubeconform", CheckStatus::Failed, deploy_steps::first_error_line(&format!("{:#}", err)));
         } else {
             let _ = log_tx.send("kubeconform reported errors (ignored)".to_string());
+            steps.warning("kubeconform", &format!("{:#}", err)).await;
         }
+    } else {
+        steps.success("kubeconform").await;
+        report.record("kubeconform", CheckStatus::Passed, None);
     }
 
-    let diff_info = collect_deploy_diff(&deploy_repo_path, deploy_rel_path, &log_tx).await?;
-    let tag_name = if environment.append_env_suffix {
-        format!("{}-{}", release.release_id, environment.slug)
-    } else {
-        release.release_id.clone()
-    };
+    steps.start("diff").await;
+    let diff_info = collect_deploy_diff(&tools.sandbox, &deploy_repo_path, deploy_rel_path, log_tx).await?;
 
     if let Some(diff) = diff_info {
-        let _ = sqlx::query(
-            "INSERT INTO deploy_job_diffs (deploy_job_id, files_changed, diff_patch) VALUES ($1, $2, $3)",
-        )
-        .bind(job_id)
-        .bind(diff.files_changed)
-        .bind(diff.diff_patch)
-        .execute(&state.pool)
-        .await;
-
-        if job.dry_run {
+        report.files_changed = diff.files_changed.lines().filter(|line| !line.trim().is_empty()).count();
+        let diff = cap_deploy_diff(diff, work.diff_max_bytes, log_tx);
+        report.diff_truncated = diff.truncated;
+        outcome.diff = Some(diff);
+        steps.success("diff").await;
+
+        if validate_only {
+            let _ = log_tx.send("Validate only: skipping git add/commit/push/tag".to_string());
+            steps.skip("push", "validate only").await;
+        } else if work.dry_run {
             let _ = log_tx.send("Dry run enabled: skipping git add/commit/push/tag".to_string());
+            steps.skip("push", "dry run").await;
         } else {
+            steps.start("push").await;
             run_git_commit_and_push(
+                &tools.sandbox,
                 &deploy_repo_path,
                 deploy_rel_path,
-                &tag_name,
-                &deploy_repo.repo_url,
+                &work.tag_name,
+                &work.deploy_repo.repo_url,
                 &git_env_deploy,
-                &log_tx,
+                log_tx,
             )
             .await?;
+            steps.success("push").await;
         }
     } else {
         let _ = log_tx.send("No deploy changes detected; skipping git commit/push/tag".to_string());
+        steps.success("diff").await;
+        steps.skip("push", "no deploy changes").await;
     }
 
-    let commit_sha = if job.dry_run {
+    if validate_only {
+        let passed = report.passed;
+        let failed_checks = report.failed_checks().join(", ");
+        outcome.validation_report = Some(report);
+        if !passed {
+            anyhow::bail!("Validation failed: {}", failed_checks);
+        }
+        let _ = log_tx.send("Validation passed".to_string());
+    }
+
+    outcome.commit_sha = if work.dry_run || validate_only {
         None
     } else {
-        get_git_head_sha(&deploy_repo_path, &git_env_deploy).await.ok()
+        get_git_head_sha(&tools.sandbox, &deploy_repo_path, &git_env_deploy).await.ok()
     };
+    outcome.tag_name = Some(work.tag_name);
+    Ok(())
+}
 
-    sqlx::query(
-        "UPDATE deploy_jobs SET status = 'success', completed_at = NOW(), commit_sha = $1, tag_name = $2 WHERE id = $3",
-    )
-    .bind(&commit_sha)
-    .bind(&tag_name)
-    .bind(job_id)
-    .execute(&state.pool)
-    .await?;
-
-    let _ = log_tx.send("Deploy job completed successfully".to_string());
+/// Uloží výsledky deploy pipeline (inventory, obrazy, diff, validační report) - i po chybě jobu
+pub(crate) async fn store_deploy_outcome(
+    state: &DeployApiState,
+    job_id: Uuid,
+    outcome: DeployOutcome,
+    log_tx: &broadcast::Sender<String>,
+) -> anyhow::Result<()> {
+    if outcome.inventory.is_some() || outcome.generated_profiles.is_some() {
+        sqlx::query(
+            "UPDATE deploy_jobs SET kube_build_inventory = $1, generated_profiles = $2 WHERE id = $3",
+        )
+        .bind(outcome.inventory)
+        .bind(outcome.generated_profiles)
+        .bind(job_id)
+        .execute(&state.pool)
+        .await?;
+    }
+    if let Some(images) = outcome.images
+        && let Err(err) = store_deploy_images(&state.pool, job_id, &images).await
+    {
+        let _ = log_tx.send(format!("Failed to store deploy images (ignored): {}", err));
+    }
+    if let Some(diff) = outcome.diff {
+        store_deploy_diff(state, job_id, diff, log_tx).await;
+    }
+    if let Some(report) = outcome.validation_report {
+        sqlx::query("UPDATE deploy_jobs SET validation_report = $1 WHERE id = $2")
+            .bind(serde_json::to_value(&report)?)
+            .bind(job_id)
+            .execute(&state.pool)
+            .await?;
+    }
     Ok(())
 }
 
-fn build_git_env_for_repo(
+/// Se samostatným sandbox UID musí nástroje vidět soubory, které zapsal server (SSH klíče, manifest, env)
+pub(crate) fn hand_over_workspace(sandbox: &ToolSandbox, path: &FsPath, log_tx: &broadcast::Sender<String>) -> anyhow::Result<()> {
+    sandbox.hand_over(path).map_err(|err| {
+        let message = format!("Failed to hand over workspace {} to sandbox user: {}", path.display(), err);
+        let _ = log_tx.send(message.clone());
+        anyhow::Error::new(err).context(message)
+    })
+}
+
+pub(crate) fn build_git_env_for_repo(
     state: &DeployApiState,
     repo: &GitRepository,
     temp_root: &FsPath,
 ) -> anyhow::Result<HashMap<String, String>> {
-    let mut env = HashMap::new();
-    env.insert("GIT_TERMINAL_PROMPT".to_string(), "0".to_string());
+    git_env_for_repo(&deploy_work_repo(state, repo, &repo.default_branch)?, temp_root)
+}
 
+/// Repozitář s dešifrovanými credentials podle typu autentizace
+fn deploy_work_repo(state: &DeployApiState, repo: &GitRepository, branch: &str) -> anyhow::Result<DeployWorkRepo> {
+    let mut work_repo = DeployWorkRepo {
+        id: repo.id,
+        repo_url: repo.repo_url.clone(),
+        branch: branch.to_string(),
+        ssh_private_key: None,
+        username: None,
+        token: None,
+    };
     match repo.git_auth_type.as_str() {
         "ssh" => {
             if let Some(enc_key) = &repo.git_ssh_key_encrypted {
-                let mut key = crypto::decrypt(enc_key, &state.encryption_secret)?;
-                // Normalize key formatting in case it was stored with escaped newlines.
-                if key.contains("\\n") {
-                    key = key.replace("\\n", "\n");
-                }
-                if key.contains("\r\n") {
-                    key = key.replace("\r\n", "\n");
-                }
-                let key = if key.ends_with('\n') { key } else { format!("{key}\n") };
-                let key_path = temp_root.join(format!("git_ssh_key_{}", repo.id));
-                std::fs::write(&key_path, key.as_bytes())
-                    .with_context(|| format!("Failed to write git ssh key {}", key_path.display()))?;
-                #[cfg(unix)]
-                {
-                    use std::os::unix::fs::PermissionsExt;
-                    let mut perms = std::fs::metadata(&key_path)?.permissions();
-                    perms.set_mode(0o600);
-                    std::fs::set_permissions(&key_path, perms)?;
-                }
-                let ssh_cmd = format!(
-                    "ssh -i {} -o StrictHostKeyChecking=no -o UserKnownHostsFile=/dev/null",
-                    key_path.display()
-                );
-                env.insert("GIT_SSH_COMMAND".to_string(), ssh_cmd);
+                // Klíče uložené před validací mohou mít escapované konce řádků
+                work_repo.ssh_private_key =
+                    Some(key_material::normalize_ssh_private_key(&crypto::decrypt(enc_key, &state.encryption_secret)?));
             }
         }
         "token" => {
             if let (Some(enc_token), Some(username)) = (&repo.git_token_encrypted, &repo.git_username) {
-                let token = crypto::decrypt(enc_token, &state.encryption_secret)?;
-                env.insert("SRM_GIT_TOKEN".to_string(), token);
-                env.insert("SRM_GIT_USERNAME".to_string(), username.clone());
+                work_repo.token = Some(crypto::decrypt(enc_token, &state.encryption_secret)?);
+                work_repo.username = Some(username.clone());
             }
         }
         _ => {}
     }
+    Ok(work_repo)
+}
+
+/// Git env pro clone/push; SSH klíč se zapíše do `temp_root`
+fn git_env_for_repo(repo: &DeployWorkRepo, temp_root: &FsPath) -> anyhow::Result<HashMap<String, String>> {
+    let mut env = HashMap::new();
+    env.insert("GIT_TERMINAL_PROMPT".to_string(), "0".to_string());
+
+    if let Some(key) = &repo.ssh_private_key {
+        let key_path = temp_root.join(format!("git_ssh_key_{}", repo.id));
+        std::fs::write(&key_path, key.as_bytes())
+            .with_context(|| format!("Failed to write git ssh key {}", key_path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mut perms = std::fs::metadata(&key_path)?.permissions();
+            perms.set_mode(0o600);
+            std::fs::set_permissions(&key_path, perms)?;
+        }
+        let ssh_cmd = format!(
+            "ssh -i {} -o StrictHostKeyChecking=no -o UserKnownHostsFile=/dev/null",
+            key_path.display()
+        );
+        env.insert("GIT_SSH_COMMAND".to_string(), ssh_cmd);
+    }
+    if let (Some(token), Some(username)) = (&repo.token, &repo.username) {
+        env.insert("SRM_GIT_TOKEN".to_string(), token.clone());
+        env.insert("SRM_GIT_USERNAME".to_string(), username.clone());
+    }
 
     Ok(env)
 }
 
-fn inject_http_auth(repo_url: &str, username: &str, token: &str) -> anyhow::Result<String> {
+pub(crate) fn inject_http_auth(repo_url: &str, username: &str, token: &str) -> anyhow::Result<String> {
     let mut url = url::Url::parse(repo_url)?;
     url.set_username(username).ok();
     url.set_password(Some(token)).ok();
     Ok(url.to_string())
 }
 
-async fn run_git_clone(
+pub(crate) async fn run_git_clone(
+    sandbox: &ToolSandbox,
     repo_url: &str,
     branch: &str,
     path: &FsPath,
@@ -3689,7 +4621,7 @@ async fn run_git_clone(
     }
 
     run_command_logged(
-        "git",
+        sandbox.program(SandboxTool::Git, "git"),
         &["clone", "--branch", branch, &url, path.to_string_lossy().as_ref()],
         None,
         git_env,
@@ -3701,18 +4633,18 @@ async fn run_git_clone(
 
 fn build_kube_build_env(
     env_name: &str,
-    release: &Release,
+    release_id: &str,
     manifest_path: &FsPath,
     env_repo_path: &FsPath,
     mapped_vars: &HashMap<String, String>,
-    extra_env_vars: &[DeployTargetExtraEnvVarInput],
+    extra_env_vars: &[DeployEnvVar],
 ) -> anyhow::Result<HashMap<String, String>> {
     let mut env = HashMap::new();
     env.insert(
         "ENVIRONMENTS_DIR".to_string(),
         env_repo_path.to_string_lossy().to_string(),
     );
-    env.insert("SIMPLE_RELEASE_ID".to_string(), release.release_id.clone());
+    env.insert("SIMPLE_RELEASE_ID".to_string(), release_id.to_string());
     env.insert(
         "SRM_RELEASE_MANIFEST".to_string(),
         manifest_path.to_string_lossy().to_string(),
@@ -3732,15 +4664,14 @@ fn build_kube_build_env(
 }
 
 async fn build_env_file(
-    state: &DeployApiState,
-    environment: &Environment,
+    tools: &DeployTools,
     encjson_key_dir: Option<&str>,
     env_repo_path: &FsPath,
     env_subdir: &str,
     env_file_path: &FsPath,
-    release: &Release,
-    env_vars: &[DeployTargetEnvVarInput],
-    extra_env_vars: &[DeployTargetExtraEnvVarInput],
+    release_id: &str,
+    release_env_vars: &HashMap<String, String>,
+    extra_env_vars: &[DeployEnvVar],
     log_tx: &broadcast::Sender<String>,
 ) -> anyhow::Result<()> {
     let env_dir = env_repo_path.join(env_subdir);
@@ -3751,22 +4682,22 @@ async fn build_env_file(
 
     let effective_key_dir = encjson_key_dir
         .filter(|v| !v.trim().is_empty())
-        .or_else(|| state.encjson_key_dir.as_deref().filter(|v| !v.trim().is_empty()));
+        .or_else(|| tools.encjson_key_dir.as_deref().filter(|v| !v.trim().is_empty()));
     let key_dir_override = effective_key_dir.map(PathBuf::from);
     let key_dir_override = key_dir_override.as_ref().map(|p| p.as_path());
 
     if secured.exists() {
-        let output = run_encjson_dotenv(state, environment, &secured, log_tx, key_dir_override).await?;
+        let output = run_encjson_dotenv(tools, &secured, log_tx, key_dir_override).await?;
         combined.push_str(&output);
     }
 
     if unsecured.exists() {
-        let output = run_encjson_dotenv(state, environment, &unsecured, log_tx, key_dir_override).await?;
+        let output = run_encjson_dotenv(tools, &unsecured, log_tx, key_dir_override).await?;
         combined.push_str(&output);
     }
 
-    combined.push_str(&format!("SIMPLE_RELEASE_ID={}\n", release.release_id));
-    for (key, value) in build_release_env_var_map(env_vars, release, log_tx) {
+    combined.push_str(&format!("SIMPLE_RELEASE_ID={}\n", release_id));
+    for (key, value) in release_env_vars {
         combined.push_str(&format!("{}={}\n", key, value));
     }
     for item in extra_env_vars {
@@ -3783,6 +4714,73 @@ async fn build_env_file(
     Ok(())
 }
 
+/// Šablony souborů deploy targetu jobu s dešifrovaným obsahem
+async fn load_file_templates(state: &DeployApiState, job: &DeployJob) -> anyhow::Result<Vec<DeployWorkFile>> {
+    let Some(target_id) = env_defaults_target_id(&state.pool, job).await? else {
+        return Ok(Vec::new());
+    };
+    let templates = sqlx::query_as::<_, DeployTargetFileTemplate>(
+        "SELECT * FROM deploy_target_file_templates WHERE deploy_target_id = $1 ORDER BY path",
+    )
+    .bind(target_id)
+    .fetch_all(&state.pool)
+    .await?;
+    templates
+        .into_iter()
+        .map(|template| {
+            let content = crypto::decrypt(&template.content_encrypted, &state.encryption_secret)
+                .with_context(|| format!("Failed to decrypt file template {}", template.path))?;
+            Ok(DeployWorkFile {
+                path: template.path,
+                content,
+            })
+        })
+        .collect()
+}
+
+/// Vygeneruje soubory ze šablon deploy targetu do deploy výstupu.
+/// Proměnné jsou stejné jako v `release.env`; nedefinovaná proměnná deploy zastaví.
+async fn write_file_templates(
+    tools: &DeployTools,
+    templates: &[DeployWorkFile],
+    deploy_path: &FsPath,
+    workspace: &FsPath,
+    env_file_path: &FsPath,
+    log_tx: &broadcast::Sender<String>,
+) -> anyhow::Result<()> {
+    if templates.is_empty() {
+        return Ok(());
+    }
+
+    let _ = log_tx.send(format!("== file templates ({}) ==", templates.len()));
+    let env_contents = tokio::fs::read_to_string(env_file_path)
+        .await
+        .with_context(|| format!("Failed to read env file {}", env_file_path.display()))?;
+    let vars = file_templates::parse_env_file(&env_contents);
+
+    for template in templates {
+        // Cesta se validuje i při uložení; tady kvůli ručním úpravám v DB
+        let rel_path = file_templates::validate_path(&template.path).map_err(anyhow::Error::msg)?;
+        let rendered = file_templates::render(&template.content, &vars).map_err(|missing| {
+            anyhow::anyhow!("File template {} uses undefined variables: {}", rel_path, missing.join(", "))
+        })?;
+
+        let output_path = deploy_path.join(&rel_path);
+        if let Some(parent) = output_path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+        }
+        tokio::fs::write(&output_path, rendered.as_bytes())
+            .await
+            .with_context(|| format!("Failed to write file template {}", output_path.display()))?;
+        // Obsah se nevypisuje - může obsahovat secrety
+        let _ = log_tx.send(format!("Rendered file template {} ({} bytes)", rel_path, rendered.len()));
+    }
+
+    hand_over_workspace(&tools.sandbox, workspace, log_tx)
+}
+
 async fn load_deploy_target_env_vars(pool: &PgPool, deploy_target_id: Uuid) -> anyhow::Result<Vec<DeployTargetEnvVar>> {
     let rows = sqlx::query_as::<_, DeployTargetEnvVar>(
         "SELECT * FROM deploy_target_env_vars WHERE deploy_target_id = $1 ORDER BY target_key",
@@ -3806,6 +4804,99 @@ async fn load_deploy_target_extra_env_vars(
     Ok(rows)
 }
 
+/// Deploy target, ze kterého environment přebírá výchozí proměnné:
+/// target jobu, jinak poslední aktivní nearchivovaný target navázaný na environment
+async fn env_defaults_target_id(pool: &PgPool, job: &DeployJob) -> anyhow::Result<Option<Uuid>> {
+    if job.deploy_target_id.is_some() {
+        return Ok(job.deploy_target_id);
+    }
+    let id = sqlx::query_scalar::<_, Uuid>(
+        "SELECT dte.deploy_target_id
+         FROM deploy_target_envs dte
+         JOIN deploy_targets dt ON dt.id = dte.deploy_target_id
+         WHERE dte.environment_id = $1 AND dte.is_active AND NOT dt.is_archived
+         ORDER BY dte.created_at DESC
+         LIMIT 1",
+    )
+    .bind(job.environment_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(id)
+}
+
+/// Proměnné jobu po složení vrstev target ← environment ← job
+async fn build_job_env(
+    pool: &PgPool,
+    job: &DeployJob,
+    environment: &Environment,
+    release: &Release,
+) -> anyhow::Result<DeployJobEnv> {
+    let deploy_target_id = env_defaults_target_id(pool, job).await?;
+    let mut layers = Vec::new();
+    if let Some(target_id) = deploy_target_id {
+        let mappings = load_deploy_target_env_vars(pool, target_id).await?;
+        let extra = load_deploy_target_extra_env_vars(pool, target_id).await?;
+        layers.push((
+            EnvLayer::Target,
+            EnvLayerValues {
+                mappings: mappings.into_iter().map(|v| (v.source_key, v.target_key)).collect(),
+                extra_env_vars: extra.into_iter().map(|v| (v.key, v.value)).collect(),
+            },
+        ));
+    }
+    layers.push((
+        EnvLayer::Environment,
+        EnvLayerValues::from_json(&environment.release_env_var_mappings, &environment.extra_env_vars),
+    ));
+    layers.push((
+        EnvLayer::Job,
+        EnvLayerValues::from_json(&serde_json::Value::Null, &job.env_overrides),
+    ));
+    let resolved = env_layers::resolve(&layers);
+
+    // Pořadí jako v release.env - pozdější hodnota vyhrává
+    let mut effective = BTreeMap::new();
+    effective.insert("SIMPLE_RELEASE_ID".to_string(), release.release_id.clone());
+    for mapping in &resolved.release_env_var_mappings {
+        if let Some(value) = release_env_var_value(&mapping.source_key, release) {
+            effective.insert(mapping.target_key.clone(), value);
+        }
+    }
+    for var in &resolved.extra_env_vars {
+        effective.insert(var.key.clone(), var.value.clone());
+    }
+
+    Ok(DeployJobEnv {
+        deploy_target_id,
+        resolved,
+        effective,
+        snapshot: false,
+    })
+}
+
+fn log_job_env(job_env: &DeployJobEnv, log_tx: &broadcast::Sender<String>) {
+    if let Some(target_id) = job_env.deploy_target_id {
+        let _ = log_tx.send(format!("Env var defaults from deploy target {}", target_id));
+    }
+    // Environment bez přepsání je běžný případ - logují se jen hodnoty z jiných vrstev
+    for var in job_env
+        .resolved
+        .extra_env_vars
+        .iter()
+        .filter(|v| v.layer != EnvLayer::Environment || !v.overrides.is_empty())
+    {
+        let _ = log_tx.send(format!("Env var {} set by {} layer", var.key, var.layer.as_str()));
+    }
+}
+
+/// Hodnota podporovaného `source_key` mapování
+fn release_env_var_value(source_key: &str, release: &Release) -> Option<String> {
+    match source_key {
+        "SIMPLE_RELEASE_ID" => Some(release.release_id.clone()),
+        _ => None,
+    }
+}
+
 fn build_release_env_var_map(
     env_vars: &[DeployTargetEnvVarInput],
     release: &Release,
@@ -3818,11 +4909,7 @@ fn build_release_env_var_map(
         if source_key.is_empty() || target_key.is_empty() {
             continue;
         }
-        let value = match source_key {
-            "SIMPLE_RELEASE_ID" => Some(release.release_id.clone()),
-            _ => None,
-        };
-        if let Some(val) = value {
+        if let Some(val) = release_env_var_value(source_key, release) {
             mapped.insert(target_key.to_string(), val);
         } else {
             let _ = log_tx.send(format!(
@@ -3835,8 +4922,7 @@ fn build_release_env_var_map(
 }
 
 async fn run_encjson_dotenv(
-    state: &DeployApiState,
-    environment: &Environment,
+    tools: &DeployTools,
     file_path: &FsPath,
     log_tx: &broadcast::Sender<String>,
     keydir_override: Option<&FsPath>,
@@ -3852,14 +4938,14 @@ async fn run_encjson_dotenv(
                 "encjson legacy detected in {}, using legacy pipeline",
                 file_path.display()
             ));
-            run_encjson_legacy_pipeline(state, environment, file_path, keydir_override).await
+            run_encjson_legacy_pipeline(tools, file_path, keydir_override).await
         }
         EncJsonApi::Modern => {
             let _ = log_tx.send(format!(
                 "encjson modern detected in {}, using encjson-rs",
                 file_path.display()
             ));
-            run_encjson_modern(state, environment, file_path, keydir_override).await
+            run_encjson_modern(tools, file_path, keydir_override).await
         }
     }
 }
@@ -3895,12 +4981,12 @@ fn format_command_failure(status: std::process::ExitStatus, stderr: &[u8]) -> St
 }
 
 async fn run_encjson_modern(
-    state: &DeployApiState,
-    environment: &Environment,
+    tools: &DeployTools,
     file_path: &FsPath,
     keydir_override: Option<&FsPath>,
 ) -> anyhow::Result<String> {
-    let mut cmd = Command::new(&state.encjson_path);
+    let program = tools.sandbox.program(SandboxTool::Encjson, &tools.encjson_path);
+    let mut cmd = program.command(None);
     cmd.arg("decrypt")
         .arg("-f")
         .arg(file_path)
@@ -3909,11 +4995,9 @@ async fn run_encjson_modern(
 
     if let Some(keydir) = keydir_override {
         cmd.arg("-k").arg(keydir);
-    } else if let Some(keydir) = &environment.encjson_key_dir {
-        cmd.arg("-k").arg(keydir);
     }
 
-    let output = cmd.output().await?;
+    let output = cmd.output().await.map_err(|err| spawn_failure(program, err))?;
     if !output.status.success() {
         anyhow::bail!(
             "encjson-rs failed for {} ({})",
@@ -3926,12 +5010,12 @@ async fn run_encjson_modern(
 }
 
 async fn run_encjson_legacy_pipeline(
-    state: &DeployApiState,
-    environment: &Environment,
+    tools: &DeployTools,
     file_path: &FsPath,
     keydir_override: Option<&FsPath>,
 ) -> anyhow::Result<String> {
-    let mut legacy_cmd = Command::new(&state.encjson_legacy_path);
+    let legacy_program = tools.sandbox.program(SandboxTool::Encjson, &tools.encjson_legacy_path);
+    let mut legacy_cmd = legacy_program.command(None);
     legacy_cmd
         .arg("decrypt")
         .arg("-f")
@@ -3941,17 +5025,16 @@ async fn run_encjson_legacy_pipeline(
 
     if let Some(keydir) = keydir_override {
         legacy_cmd.arg("-k").arg(keydir);
-    } else if let Some(keydir) = &environment.encjson_key_dir {
-        legacy_cmd.arg("-k").arg(keydir);
     }
 
-    let mut legacy_child = legacy_cmd.spawn()?;
+    let mut legacy_child = legacy_cmd.spawn().map_err(|err| spawn_failure(legacy_program, err))?;
     let mut legacy_stdout = legacy_child
         .stdout
         .take()
         .context("Failed to capture legacy encjson stdout")?;
 
-    let mut modern_cmd = Command::new(&state.encjson_path);
+    let modern_program = tools.sandbox.program(SandboxTool::Encjson, &tools.encjson_path);
+    let mut modern_cmd = modern_program.command(None);
     modern_cmd
         .arg("decrypt")
         .arg("-o")
@@ -3961,7 +5044,7 @@ async fn run_encjson_legacy_pipeline(
         .stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::piped());
 
-    let mut modern_child = modern_cmd.spawn()?;
+    let mut modern_child = modern_cmd.spawn().map_err(|err| spawn_failure(modern_program, err))?;
     let mut modern_stdin = modern_child
         .stdin
         .take()
@@ -4027,7 +5110,7 @@ async fn build_encjson_keydir(
 }
 
 async fn apply_env_to_outputs(
-    state: &DeployApiState,
+    tools: &DeployTools,
     deploy_path: &FsPath,
     env_file_path: &FsPath,
     log_tx: &broadcast::Sender<String>,
@@ -4036,18 +5119,18 @@ async fn apply_env_to_outputs(
     let services_external = deploy_path.join("services").join("external");
 
     let _ = log_tx.send("Applying env to deployments".to_string());
-    apply_env_to_dir(state, &deployments, env_file_path, log_tx).await?;
+    apply_env_to_dir(tools, &deployments, env_file_path, log_tx).await?;
 
     if services_external.exists() {
         let _ = log_tx.send("Applying env to services/external".to_string());
-        apply_env_to_dir(state, &services_external, env_file_path, log_tx).await?;
+        apply_env_to_dir(tools, &services_external, env_file_path, log_tx).await?;
     }
 
     Ok(())
 }
 
 async fn apply_env_to_dir(
-    state: &DeployApiState,
+    tools: &DeployTools,
     dir: &FsPath,
     env_file_path: &FsPath,
     log_tx: &broadcast::Sender<String>,
@@ -4061,7 +5144,7 @@ async fn apply_env_to_dir(
             let path = entry.path();
             if path.extension().and_then(|v| v.to_str()) == Some("yml") {
                 run_command_logged(
-                    &state.apply_env_path,
+                    tools.sandbox.program(SandboxTool::ApplyEnv, &tools.apply_env_path),
                     &["-E", env_file_path.to_string_lossy().as_ref(), "-f", path.to_string_lossy().as_ref(), "-w"],
                     None,
                     &HashMap::new(),
@@ -4077,6 +5160,7 @@ async fn apply_env_to_dir(
 }
 
 async fn run_git_commit_and_push(
+    sandbox: &ToolSandbox,
     repo_path: &FsPath,
     deploy_path: &str,
     release_id: &str,
@@ -4085,16 +5169,17 @@ async fn run_git_commit_and_push(
     log_tx: &broadcast::Sender<String>,
 ) -> anyhow::Result<()> {
     let _ = log_tx.send("Preparing git commit".to_string());
+    let git = sandbox.program(SandboxTool::Git, "git");
 
-    run_command_logged("git", &["config", "user.name", "simple-release-management"], Some(repo_path), git_env, log_tx, "git config").await?;
-    run_command_logged("git", &["config", "user.email", "release-management@local"], Some(repo_path), git_env, log_tx, "git config").await?;
+    run_command_logged(git, &["config", "user.name", "simple-release-management"], Some(repo_path), git_env, log_tx, "git config").await?;
+    run_command_logged(git, &["config", "user.email", "release-management@local"], Some(repo_path), git_env, log_tx, "git config").await?;
 
     let add_path = if deploy_path.trim().is_empty() { "." } else { deploy_path };
-    run_command_logged("git", &["add", add_path], Some(repo_path), git_env, log_tx, "git add").await?;
+    run_command_logged(git, &["add", add_path], Some(repo_path), git_env, log_tx, "git add").await?;
 
     let commit_msg = format!("release {}", release_id);
     run_command_logged(
-        "git",
+        git,
         &["commit", "--allow-empty", "-m", &commit_msg],
         Some(repo_path),
         git_env,
@@ -4104,7 +5189,7 @@ async fn run_git_commit_and_push(
     .await?;
 
     run_command_logged(
-        "git",
+        git,
         &["tag", "-f", "-a", release_id, "-m", &commit_msg],
         Some(repo_path),
         git_env,
@@ -4116,7 +5201,7 @@ async fn run_git_commit_and_push(
     if let (Some(token), Some(username)) = (git_env.get("SRM_GIT_TOKEN"), git_env.get("SRM_GIT_USERNAME")) {
         let authed = inject_http_auth(repo_url, username, token)?;
         run_command_logged(
-            "git",
+            git,
             &["remote", "set-url", "origin", &authed],
             Some(repo_path),
             git_env,
@@ -4126,17 +5211,21 @@ async fn run_git_commit_and_push(
         .await?;
     }
 
-    run_command_logged("git", &["push"], Some(repo_path), git_env, log_tx, "git push").await?;
-    run_command_logged("git", &["push", "--force", "--tags"], Some(repo_path), git_env, log_tx, "git push --tags").await?;
+    run_command_logged(git, &["push"], Some(repo_path), git_env, log_tx, "git push").await?;
+    run_command_logged(git, &["push", "--force", "--tags"], Some(repo_path), git_env, log_tx, "git push --tags").await?;
 
     Ok(())
 }
 
-async fn get_git_head_sha(repo_path: &FsPath, git_env: &HashMap<String, String>) -> anyhow::Result<String> {
-    let output = Command::new("git")
+pub(crate) async fn get_git_head_sha(
+    sandbox: &ToolSandbox,
+    repo_path: &FsPath,
+    git_env: &HashMap<String, String>,
+) -> anyhow::Result<String> {
+    let output = sandbox
+        .command(SandboxTool::Git, "git", Some(repo_path))
         .arg("rev-parse")
         .arg("HEAD")
-        .current_dir(repo_path)
         .envs(git_env)
         .output()
         .await?;
@@ -4164,6 +5253,156 @@ async fn clean_deploy_output(deploy_path: &FsPath) -> anyhow::Result<()> {
     Ok(())
 }
 
+fn expected_image(app_name: String, container_name: String) -> ExpectedImage {
+    ExpectedImage {
+        app_name,
+        container_name: Some(container_name).filter(|c| !c.is_empty()),
+    }
+}
+
+/// GET /api/v1/environments/{id}/expected-images - Dvojice app/container, které strict režim
+/// vyžaduje v release manifestu (z aktuálního env repa). S `?bundle_id=&version=` vrací i rozdíl
+/// proti mappings dané verze, aby šly opravit před releasem.
+async fn get_environment_expected_images(
+    State(state): State<DeployApiState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ExpectedImagesQuery>,
+) -> Result<Json<ExpectedImagesResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let bad_request = |error: String| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error }));
+    let internal = |error: anyhow::Error| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("{:#}", error),
+            }),
+        )
+    };
+
+    let environment = sqlx::query_as::<_, Environment>("SELECT * FROM environments WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: format!("Environment with id {} not found", id),
+                }),
+            )
+        })?;
+
+    let mappings = match (query.bundle_id, query.version) {
+        (Some(bundle_id), Some(version)) => {
+            let rows = sqlx::query_as::<_, (String, Option<String>)>(
+                "SELECT im.app_name, im.container_name
+                 FROM image_mappings im
+                 JOIN bundle_versions bv ON bv.id = im.bundle_version_id
+                 JOIN bundles b ON b.id = bv.bundle_id
+                 WHERE b.id = $1 AND b.tenant_id = $2 AND bv.version = $3",
+            )
+            .bind(bundle_id)
+            .bind(environment.tenant_id)
+            .bind(version)
+            .fetch_all(&state.pool)
+            .await
+            .map_err(db_error)?;
+            Some((bundle_id, version, rows))
+        }
+        (None, None) => None,
+        _ => return Err(bad_request("bundle_id and version must be used together".to_string())),
+    };
+
+    let env_repo_id = environment
+        .env_repo_id
+        .ok_or_else(|| bad_request("Environment has no env repository".to_string()))?;
+    let env_repo = sqlx::query_as::<_, GitRepository>("SELECT * FROM git_repositories WHERE id = $1")
+        .bind(env_repo_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(db_error)?;
+    let branch = environment
+        .env_repo_branch
+        .as_deref()
+        .filter(|v| !v.trim().is_empty())
+        .unwrap_or(&env_repo.default_branch)
+        .to_string();
+    let env_subdir = environment
+        .env_repo_path
+        .as_deref()
+        .unwrap_or(&environment.slug)
+        .trim()
+        .trim_start_matches('/')
+        .to_string();
+    let manifest_mode = environment
+        .release_manifest_mode
+        .as_deref()
+        .unwrap_or("strict")
+        .trim()
+        .to_lowercase();
+
+    let temp_dir = tempfile::Builder::new()
+        .prefix("srm-expected-images-")
+        .tempdir()
+        .map_err(|e| internal(e.into()))?;
+    let repo_path = temp_dir.path().join("environments");
+    let git_env = build_git_env_for_repo(&state, &env_repo, temp_dir.path()).map_err(internal)?;
+    // Výstup gitu nikdo nečte, chyba nese první řádek stderr
+    let (log_tx, _) = broadcast::channel::<String>(16);
+    hand_over_workspace(&state.sandbox, temp_dir.path(), &log_tx).map_err(internal)?;
+    run_git_clone(&state.sandbox, &env_repo.repo_url, &branch, &repo_path, &git_env, &log_tx)
+        .await
+        .map_err(internal)?;
+    let commit_sha = get_git_head_sha(&state.sandbox, &repo_path, &git_env)
+        .await
+        .map_err(internal)?;
+
+    let pairs = load_env_app_container_pairs(&repo_path, &environment.slug, Some(env_subdir.as_str()))
+        .await
+        .map_err(|e| bad_request(format!("Failed to read apps from env repository: {:#}", e)))?;
+
+    let bundle = mappings.map(|(bundle_id, version, rows)| {
+        let actual: HashSet<(String, String)> = rows
+            .into_iter()
+            .map(|(app, container)| (app, container.unwrap_or_default()))
+            .collect();
+        let mut missing: Vec<ExpectedImage> = pairs
+            .difference(&actual)
+            .map(|(app, container)| expected_image(app.clone(), container.clone()))
+            .collect();
+        let mut extra: Vec<ExpectedImage> = actual
+            .difference(&pairs)
+            .map(|(app, container)| expected_image(app.clone(), container.clone()))
+            .collect();
+        missing.sort();
+        extra.sort();
+        ExpectedImagesReconciliation {
+            bundle_id,
+            version,
+            missing,
+            extra,
+        }
+    });
+
+    let mut expected: Vec<ExpectedImage> = pairs
+        .into_iter()
+        .map(|(app, container)| expected_image(app, container))
+        .collect();
+    expected.sort();
+
+    Ok(Json(ExpectedImagesResponse {
+        environment_id: environment.id,
+        env_repo_id,
+        env_repo_branch: branch,
+        env_repo_path: env_subdir,
+        commit_sha,
+        strict: manifest_mode.starts_with("strict"),
+        release_manifest_mode: manifest_mode,
+        expected,
+        bundle,
+    }))
+}
+
 async fn apply_release_manifest_mode(
     mode: &str,
     manifest: &mut ReleaseManifest,
@@ -4434,24 +5673,21 @@ fn apply_placeholder(content: &str, kind: &str, key: &str, value: &str) -> Strin
     result
 }
 
-async fn run_command_logged(
-    program: &str,
+pub(crate) async fn run_command_logged(
+    program: ToolProgram<'_>,
     args: &[&str],
     cwd: Option<&FsPath>,
     envs: &HashMap<String, String>,
     log_tx: &broadcast::Sender<String>,
     label: &str,
 ) -> anyhow::Result<()> {
-    let mut cmd = Command::new(program);
+    let mut cmd = program.command(cwd);
     cmd.args(args);
-    if let Some(dir) = cwd {
-        cmd.current_dir(dir);
-    }
     cmd.envs(envs);
     cmd.stdout(std::process::Stdio::piped());
     cmd.stderr(std::process::Stdio::piped());
 
-    let mut child = cmd.spawn()?;
+    let mut child = spawn_logged(program, &mut cmd, log_tx)?;
     let stdout = child.stdout.take().unwrap();
     let stderr = child.stderr.take().unwrap();
 
@@ -4465,40 +5701,69 @@ async fn run_command_logged(
         }
     });
 
+    // První řádek stderr jde do chyby (deploy_job_steps.error_line)
     let log_tx_clone = log_tx.clone();
     let stderr_task = tokio::spawn(async move {
+        let mut first_line: Option<String> = None;
         while let Ok(Some(line)) = stderr_reader.next_line().await {
+            if first_line.is_none() && !line.trim().is_empty() {
+                first_line = Some(line.trim().to_string());
+            }
             let _ = log_tx_clone.send(line);
         }
+        first_line
     });
 
     let status = child.wait().await?;
     stdout_task.await.ok();
-    stderr_task.await.ok();
+    let first_stderr = stderr_task.await.ok().flatten();
 
     if !status.success() {
+        if let Some(note) = program.exit_note(&status) {
+            let _ = log_tx.send(note);
+        }
         let _ = log_tx.send(format!("{} failed with exit code {:?}", label, status.code()));
-        anyhow::bail!("{} failed", label);
+        match first_stderr {
+            Some(line) => anyhow::bail!("{} failed: {}", label, line),
+            None => anyhow::bail!("{} failed", label),
+        }
     }
 
     Ok(())
 }
 
+fn spawn_failure(program: ToolProgram<'_>, err: std::io::Error) -> anyhow::Error {
+    let message = program.spawn_error(&err);
+    anyhow::Error::new(err).context(message)
+}
+
+/// Spuštění nástroje; selhání (i sandboxu samotného) jde do logu jobu
+fn spawn_logged(
+    program: ToolProgram<'_>,
+    cmd: &mut Command,
+    log_tx: &broadcast::Sender<String>,
+) -> anyhow::Result<tokio::process::Child> {
+    cmd.spawn().map_err(|err| {
+        let _ = log_tx.send(program.spawn_error(&err));
+        spawn_failure(program, err)
+    })
+}
+
 async fn run_command_capture_logged(
-    program: &str,
+    program: ToolProgram<'_>,
     args: &[&str],
     cwd: Option<&FsPath>,
     envs: &HashMap<String, String>,
     log_tx: &broadcast::Sender<String>,
     label: &str,
 ) -> anyhow::Result<String> {
-    let mut cmd = Command::new(program);
+    let mut cmd = program.command(cwd);
     cmd.args(args);
-    if let Some(dir) = cwd {
-        cmd.current_dir(dir);
-    }
     cmd.envs(envs);
-    cmd.output().await.map(|output| {
+    cmd.stdout(std::process::Stdio::piped());
+    cmd.stderr(std::process::Stdio::piped());
+    let child = spawn_logged(program, &mut cmd, log_tx)?;
+    child.wait_with_output().await.map(|output| {
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
 
@@ -4512,6 +5777,9 @@ async fn run_command_capture_logged(
         if output.status.success() {
             Ok(stdout)
         } else {
+            if let Some(note) = program.exit_note(&output.status) {
+                let _ = log_tx.send(note);
+            }
             let _ = log_tx.send(format!("{} failed with exit code {:?}", label, output.status.code()));
             Err(anyhow::anyhow!(
                 "{} failed: {}",
@@ -4523,25 +5791,24 @@ async fn run_command_capture_logged(
 }
 
 async fn run_command_capture(
-    program: &str,
+    program: ToolProgram<'_>,
     args: &[&str],
     cwd: Option<&FsPath>,
     envs: &HashMap<String, String>,
     label: &str,
 ) -> anyhow::Result<String> {
-    let mut cmd = Command::new(program);
+    let mut cmd = program.command(cwd);
     cmd.args(args);
-    if let Some(dir) = cwd {
-        cmd.current_dir(dir);
-    }
     cmd.envs(envs);
 
-    let output = cmd.output().await?;
+    let output = cmd.output().await.map_err(|err| spawn_failure(program, err))?;
     let stdout = String::from_utf8_lossy(&output.stdout).to_string();
     let stderr = String::from_utf8_lossy(&output.stderr).to_string();
 
     if output.status.success() {
         Ok(stdout)
+    } else if let Some(note) = program.exit_note(&output.status) {
+        Err(anyhow::anyhow!("{} failed: {}", label, note))
     } else {
         Err(anyhow::anyhow!("{} failed: {}", label, stderr.trim()))
     }
@@ -4653,31 +5920,101 @@ struct DeployDiffSnapshot {
     diff_patch: String,
 }
 
+/// Zkrátí diff na limit (`DEPLOY_DIFF_MAX_BYTES`)
+fn cap_deploy_diff(diff: DeployDiffSnapshot, max_bytes: usize, log_tx: &broadcast::Sender<String>) -> DeployDiff {
+    let size_bytes = diff.diff_patch.len();
+    let (diff_patch, truncated) = deploy_diff::cap_patch(diff.diff_patch, max_bytes);
+    if truncated {
+        let _ = log_tx.send(format!(
+            "Diff is {} bytes, stored only the first {} bytes (DEPLOY_DIFF_MAX_BYTES)",
+            size_bytes,
+            diff_patch.len()
+        ));
+    }
+    DeployDiff {
+        files_changed: diff.files_changed,
+        diff_patch,
+        size_bytes: size_bytes as i64,
+        truncated,
+    }
+}
+
+/// Uloží diff jako gzip s indexem souborů
+async fn store_deploy_diff(
+    state: &DeployApiState,
+    job_id: Uuid,
+    diff: DeployDiff,
+    log_tx: &broadcast::Sender<String>,
+) {
+    let file_index = serde_json::to_value(deploy_diff::index_patch(&diff.diff_patch)).unwrap_or_default();
+    let patch_gz = match deploy_diff::compress(&diff.diff_patch) {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            let _ = log_tx.send(format!("Failed to compress diff: {}", err));
+            return;
+        }
+    };
+
+    if let Err(err) = sqlx::query(
+        "INSERT INTO deploy_job_diffs (deploy_job_id, files_changed, diff_patch, diff_patch_gz, diff_size_bytes, diff_truncated, file_index)
+         VALUES ($1, $2, '', $3, $4, $5, $6)",
+    )
+    .bind(job_id)
+    .bind(diff.files_changed)
+    .bind(patch_gz)
+    .bind(diff.size_bytes)
+    .bind(diff.truncated)
+    .bind(file_index)
+    .execute(&state.pool)
+    .await
+    {
+        let _ = log_tx.send(format!("Failed to store diff: {}", err));
+    }
+}
+
+/// Validate-only job: push URL míří na neexistující remote, takže žádný git zápis neprojde ani omylem
+async fn disable_git_push(
+    sandbox: &ToolSandbox,
+    repo_path: &FsPath,
+    log_tx: &broadcast::Sender<String>,
+) -> anyhow::Result<()> {
+    run_command_logged(
+        sandbox.program(SandboxTool::Git, "git"),
+        &["remote", "set-url", "--push", "origin", "srm-validate-only-no-push"],
+        Some(repo_path),
+        &HashMap::new(),
+        log_tx,
+        "git remote set-url --push",
+    )
+    .await
+}
+
 async fn collect_deploy_diff(
+    sandbox: &ToolSandbox,
     repo_path: &FsPath,
     deploy_path: &str,
     log_tx: &broadcast::Sender<String>,
 ) -> anyhow::Result<Option<DeployDiffSnapshot>> {
     let add_path = if deploy_path.trim().is_empty() { "." } else { deploy_path };
 
-    let intent_out = Command::new("git")
+    let intent_out = sandbox
+        .command(SandboxTool::Git, "git", Some(repo_path))
         .arg("add")
         .arg("-N")
         .arg("--")
         .arg(add_path)
-        .current_dir(repo_path)
         .output()
         .await?;
     if !intent_out.status.success() {
         let _ = log_tx.send("git add -N failed (continuing)".to_string());
     }
 
-    let status_out = Command::new("git")
+    let status_out = sandbox
+        .command(SandboxTool::Git, "git", Some(repo_path))
         .arg("status")
         .arg("--porcelain")
         .arg("--")
         .arg(add_path)
-        .current_dir(repo_path)
         .output()
         .await?;
     if !status_out.status.success() {
@@ -4690,12 +6027,12 @@ async fn collect_deploy_diff(
         return Ok(None);
     }
 
-    let diff_out = Command::new("git")
+    let diff_out = sandbox
+        .command(SandboxTool::Git, "git", Some(repo_path))
         .arg("diff")
         .arg("--unified=3")
         .arg("--")
         .arg(add_path)
-        .current_dir(repo_path)
         .output()
         .await?;
     if !diff_out.status.success() {
@@ -4707,33 +6044,21 @@ async fn collect_deploy_diff(
     Ok(Some(DeployDiffSnapshot { files_changed, diff_patch }))
 }
 
-async fn collect_and_store_deploy_images(
-    pool: &PgPool,
-    job_id: Uuid,
-    deploy_path: &FsPath,
-    log_tx: &broadcast::Sender<String>,
-) -> anyhow::Result<()> {
-    let rows = collect_deploy_images(deploy_path, log_tx).await?;
+async fn store_deploy_images(pool: &PgPool, job_id: Uuid, rows: &[DeployJobImageRow]) -> Result<(), sqlx::Error> {
     let mut tx = pool.begin().await?;
     sqlx::query("DELETE FROM deploy_job_images WHERE deploy_job_id = $1")
         .bind(job_id)
         .execute(&mut *tx)
         .await?;
 
-    if rows.is_empty() {
-        let _ = log_tx.send("No deploy images detected (deployments folder empty)".to_string());
-        tx.commit().await?;
-        return Ok(());
-    }
-
     for row in rows {
         sqlx::query(
             "INSERT INTO deploy_job_images (deploy_job_id, file_path, container_name, image) VALUES ($1, $2, $3, $4)",
         )
         .bind(job_id)
-        .bind(row.file_path)
-        .bind(row.container_name)
-        .bind(row.image)
+        .bind(&row.file_path)
+        .bind(&row.container_name)
+        .bind(&row.image)
         .execute(&mut *tx)
         .await?;
     }
@@ -4856,10 +6181,15 @@ async fn deploy_job_logs_sse(
     State(state): State<DeployApiState>,
     Path(job_id): Path<Uuid>,
 ) -> impl IntoResponse {
-    let receiver = {
+    let local = {
         let logs = state.job_logs.read().await;
         logs.get(&job_id).map(|sender| sender.subscribe())
     };
+    let receiver = match local {
+        Some(rx) => Some(rx),
+        // Job běží na jiné instanci
+        None => state.log_fanout.subscribe(&state.pool, JobKind::Deploy, job_id).await,
+    };
 
     let Some(rx) = receiver else {
         return (
@@ -4882,49 +6212,315 @@ async fn deploy_job_logs_sse(
         .into_response()
 }
 
+/// GET /api/v1/deploy/jobs/{id}/logs/stream?after_seq= - historie od offsetu + živé logy v jednom SSE streamu
+async fn deploy_job_logs_stream_sse(
+    State(state): State<DeployApiState>,
+    Path(job_id): Path<Uuid>,
+    Query(query): Query<LogStreamQuery>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let last_event_id = headers.get("last-event-id").and_then(|value| value.to_str().ok());
+    let after_seq = job_logs::stream_start_seq(last_event_id, &query);
+
+    // Živý kanál slouží jen jako wakeup, řádky se vždy čtou z DB podle seq
+    let local = {
+        let logs = state.job_logs.read().await;
+        logs.get(&job_id).map(|sender| sender.subscribe())
+    };
+    let wakeup = match local {
+        Some(rx) => Some(rx),
+        None => state.log_fanout.subscribe(&state.pool, JobKind::Deploy, job_id).await,
+    };
+
+    let items = job_logs::stream_logs(state.pool.clone(), state.object_storage.clone(), JobKind::Deploy, job_id, after_seq, wakeup);
+    let stream = futures::StreamExt::map(items, |item| {
+        use axum::response::sse::Event;
+        let event = match item {
+            Ok(LogStreamItem::Line(line)) => Event::default().id(line.seq.to_string()).event("log").data(line.line),
+            Ok(LogStreamItem::End(status)) => Event::default().event("log-end").data(status),
+            Ok(LogStreamItem::NotFound) => Event::default().event("log-end").data("Deploy job not found"),
+            Err(e) => Event::default().event("error").data(format!("Failed to load deploy job logs: {}", e)),
+        };
+        Ok::<_, std::convert::Infallible>(event)
+    });
+
+    Sse::new(stream)
+        .keep_alive(axum::response::sse::KeepAlive::new().interval(Duration::from_secs(10)))
+        .into_response()
+}
+
+/// GET /api/v1/deploy/jobs/{id}/logs/poll?after_seq= - long-poll fallback pro logy
+async fn deploy_job_logs_poll(
+    State(state): State<DeployApiState>,
+    Path(job_id): Path<Uuid>,
+    Query(query): Query<LogPollQuery>,
+) -> Result<Json<LogPollResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let response = job_logs::poll_logs(&state.pool, &state.object_storage, JobKind::Deploy, job_id, &query)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Failed to load deploy job logs: {}", e),
+                }),
+            )
+        })?;
+
+    response.map(Json).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Deploy job not found".to_string(),
+            }),
+        )
+    })
+}
+
 async fn deploy_job_logs_history(
     State(state): State<DeployApiState>,
     Path(job_id): Path<Uuid>,
 ) -> Result<Json<Vec<String>>, (StatusCode, Json<ErrorResponse>)> {
-    let rows = sqlx::query_as::<_, DeployJobLog>(
-        "SELECT * FROM deploy_job_logs WHERE deploy_job_id = $1 ORDER BY created_at",
+    let lines = job_logs::load_all_lines(&state.pool, &state.object_storage, JobKind::Deploy, job_id)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Failed to load deploy job logs: {}", e),
+                }),
+            )
+        })?;
+
+    Ok(Json(lines.into_iter().map(|line| line.line).collect()))
+}
+
+/// Výsledné env proměnné deploy jobu (bez hodnot z encjson souborů)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeployJobEnv {
+    /// Deploy target, ze kterého pochází vrstva `target`
+    pub deploy_target_id: Option<Uuid>,
+    #[serde(flatten)]
+    pub resolved: ResolvedEnv,
+    /// Proměnné, které job přidá do release.env / prostředí kube_build_app
+    pub effective: BTreeMap<String, String>,
+    /// true = uloženo při spuštění jobu, false = spočteno z aktuální konfigurace
+    #[serde(default)]
+    pub snapshot: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeployJobDiffQuery {
+    /// Vrátí jen diff jednoho souboru (cesta z `files`)
+    pub file: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeployJobDiffResponse {
+    #[serde(flatten)]
+    pub diff: DeployJobDiff,
+    pub files: Vec<DiffFileEntry>,
+    /// Patch je větší než DEPLOY_DIFF_INLINE_MAX_BYTES - UI načítá soubory přes `?file=`
+    pub patch_omitted: bool,
+}
+
+async fn deploy_job_diff(
+    State(state): State<DeployApiState>,
+    Path(job_id): Path<Uuid>,
+    Query(query): Query<DeployJobDiffQuery>,
+) -> Result<Json<Option<DeployJobDiffResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    Ok(Json(load_deploy_job_diff(&state, job_id, query.file.as_deref()).await?))
+}
+
+/// Poslední diff deploy jobu (i pro sdílené odkazy); `file` = jen jeden soubor z indexu
+pub(crate) async fn load_deploy_job_diff(
+    state: &DeployApiState,
+    job_id: Uuid,
+    file: Option<&str>,
+) -> Result<Option<DeployJobDiffResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let row = sqlx::query_as::<_, DeployJobDiff>(
+        "SELECT * FROM deploy_job_diffs WHERE deploy_job_id = $1 ORDER BY created_at DESC LIMIT 1",
     )
     .bind(job_id)
-    .fetch_all(&state.pool)
+    .fetch_optional(&state.pool)
     .await
     .map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse {
-                error: format!("Failed to load deploy job logs: {}", e),
+                error: format!("Failed to load deploy job diff: {}", e),
             }),
         )
     })?;
 
-    let lines = rows.into_iter().map(|row| row.log_line).collect();
-    Ok(Json(lines))
+    let Some(mut diff) = row else {
+        return Ok(None);
+    };
+
+    // Patch může být v DB jako text / gzip, nebo archivovaný v object storage
+    let patch = deploy_diff::load_patch(&state.object_storage, &diff)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::BAD_GATEWAY,
+                Json(ErrorResponse {
+                    error: format!("Failed to load deploy job diff: {}", e),
+                }),
+            )
+        })?;
+    // Starší diffy index nemají
+    let files = diff
+        .file_index
+        .take()
+        .and_then(|value| serde_json::from_value::<Vec<DiffFileEntry>>(value).ok())
+        .unwrap_or_else(|| deploy_diff::index_patch(&patch));
+
+    let mut patch_omitted = false;
+    diff.diff_patch = if let Some(file) = file {
+        let entry = files
+            .iter()
+            .find(|entry| entry.path == file)
+            .filter(|entry| entry.offset + entry.length <= patch.len())
+            .ok_or_else(|| {
+                (
+                    StatusCode::NOT_FOUND,
+                    Json(ErrorResponse {
+                        error: format!("File '{}' not found in deploy job diff", file),
+                    }),
+                )
+            })?;
+        patch[entry.offset..entry.offset + entry.length].to_string()
+    } else if patch.len() > state.diff_limits.inline_max_bytes {
+        patch_omitted = true;
+        String::new()
+    } else {
+        patch
+    };
+    diff.diff_patch_gz = None;
+
+    Ok(Some(DeployJobDiffResponse {
+        diff,
+        files,
+        patch_omitted,
+    }))
 }
 
-async fn deploy_job_diff(
+/// GET /api/v1/deploy/jobs/{id}/report - report validate-only jobu (`null`, dokud job nedoběhl)
+async fn deploy_job_report(
     State(state): State<DeployApiState>,
     Path(job_id): Path<Uuid>,
-) -> Result<Json<Option<DeployJobDiff>>, (StatusCode, Json<ErrorResponse>)> {
-    let row = sqlx::query_as::<_, DeployJobDiff>(
-        "SELECT * FROM deploy_job_diffs WHERE deploy_job_id = $1 ORDER BY created_at DESC LIMIT 1",
+) -> Result<Json<Option<ValidationReport>>, (StatusCode, Json<ErrorResponse>)> {
+    let db_error = |e: String| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to load validation report: {}", e),
+            }),
+        )
+    };
+    let (validate_only, report) = sqlx::query_as::<_, (bool, Option<serde_json::Value>)>(
+        "SELECT validate_only, validation_report FROM deploy_jobs WHERE id = $1",
     )
     .bind(job_id)
     .fetch_optional(&state.pool)
     .await
+    .map_err(|e| db_error(e.to_string()))?
+    .ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Deploy job not found".to_string(),
+            }),
+        )
+    })?;
+
+    if !validate_only {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Deploy job is not validate-only".to_string(),
+            }),
+        ));
+    }
+
+    let report = report
+        .map(serde_json::from_value::<ValidationReport>)
+        .transpose()
+        .map_err(|e| db_error(e.to_string()))?;
+    Ok(Json(report))
+}
+
+/// GET /api/v1/deploy/jobs/{id}/env - výsledné proměnné jobu po složení vrstev target ← environment ← job
+async fn deploy_job_env(
+    State(state): State<DeployApiState>,
+    Path(job_id): Path<Uuid>,
+) -> Result<Json<DeployJobEnv>, (StatusCode, Json<ErrorResponse>)> {
+    let db_error = |e: String| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to resolve deploy job env: {}", e),
+            }),
+        )
+    };
+    let job = sqlx::query_as::<_, DeployJob>("SELECT * FROM deploy_jobs WHERE id = $1")
+        .bind(job_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| db_error(e.to_string()))?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: "Deploy job not found".to_string(),
+                }),
+            )
+        })?;
+
+    if let Some(snapshot) = job.resolved_env.clone() {
+        let mut job_env: DeployJobEnv = serde_json::from_value(snapshot).map_err(|e| db_error(e.to_string()))?;
+        job_env.snapshot = true;
+        return Ok(Json(job_env));
+    }
+
+    // Job ještě neběžel - spočítá se z aktuální konfigurace
+    let environment = sqlx::query_as::<_, Environment>("SELECT * FROM environments WHERE id = $1")
+        .bind(job.environment_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| db_error(e.to_string()))?;
+    let release = sqlx::query_as::<_, Release>("SELECT * FROM releases WHERE id = $1")
+        .bind(job.release_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| db_error(e.to_string()))?;
+    let job_env = build_job_env(&state.pool, &job, &environment, &release)
+        .await
+        .map_err(|e| db_error(e.to_string()))?;
+    Ok(Json(job_env))
+}
+
+/// GET /api/v1/deploy/jobs/{id}/steps - stav, délka a první řádek chyby jednotlivých kroků
+async fn deploy_job_steps(
+    State(state): State<DeployApiState>,
+    Path(job_id): Path<Uuid>,
+) -> Result<Json<Vec<DeployJobStep>>, (StatusCode, Json<ErrorResponse>)> {
+    let rows = sqlx::query_as::<_, DeployJobStep>(
+        "SELECT step, position, status, started_at, completed_at, duration_ms, error_line
+         FROM deploy_job_steps WHERE deploy_job_id = $1 ORDER BY position",
+    )
+    .bind(job_id)
+    .fetch_all(&state.pool)
+    .await
     .map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse {
-                error: format!("Failed to load deploy job diff: {}", e),
+                error: format!("Failed to load deploy job steps: {}", e),
             }),
         )
     })?;
 
-    Ok(Json(row))
+    Ok(Json(rows))
 }
 
 async fn deploy_job_images(
@@ -0,0 +1,422 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::api::deploy::{self, DeployApiState, ErrorResponse};
+use crate::db::models::{DeployTarget, DeployTargetEnvTemplate, Environment};
+use crate::services::env_templates::{self, TemplateVars};
+
+/// Request pro uložení šablony napojení environmentu
+#[derive(Debug, Deserialize)]
+pub struct PutEnvTemplateRequest {
+    pub env_repo_id: Option<Uuid>,
+    pub env_repo_path: Option<String>,
+    pub env_repo_branch: Option<String>,
+    pub deploy_repo_id: Option<Uuid>,
+    pub deploy_repo_path: Option<String>,
+    pub deploy_repo_branch: Option<String>,
+    pub allow_auto_release: Option<bool>,
+    pub append_env_suffix: Option<bool>,
+    pub release_manifest_mode: Option<String>,
+    pub encjson_key_dir: Option<String>,
+}
+
+/// Request pro napojení environmentu na deploy targety podle jejich šablon
+#[derive(Debug, Deserialize)]
+pub struct ApplyEnvTemplatesRequest {
+    pub environment_id: Uuid,
+    /// Jen vybrané deploy targety (výchozí: všechny nearchivované targety tenanta)
+    pub deploy_target_ids: Option<Vec<Uuid>>,
+    /// Přepsat i existující napojení (výchozí false = přeskočit)
+    pub overwrite: Option<bool>,
+    /// Jen náhled, nic se nezapíše
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApplyAction {
+    Created,
+    Updated,
+    Skipped,
+}
+
+/// Výsledek pro jeden deploy target (u `created` / `updated` s výslednými hodnotami)
+#[derive(Debug, Serialize)]
+pub struct ApplyEnvTemplateResult {
+    pub deploy_target_id: Uuid,
+    pub deploy_target_name: String,
+    pub action: ApplyAction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env: Option<RenderedEnv>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderedEnv {
+    pub env_repo_id: Uuid,
+    pub env_repo_path: Option<String>,
+    pub env_repo_branch: Option<String>,
+    pub deploy_repo_id: Uuid,
+    pub deploy_repo_path: Option<String>,
+    pub deploy_repo_branch: Option<String>,
+    pub allow_auto_release: bool,
+    pub append_env_suffix: bool,
+    pub release_manifest_mode: String,
+    pub encjson_key_dir: Option<String>,
+    pub is_active: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApplyEnvTemplatesResponse {
+    pub environment_id: Uuid,
+    pub dry_run: bool,
+    pub created: usize,
+    pub updated: usize,
+    pub skipped: usize,
+    pub results: Vec<ApplyEnvTemplateResult>,
+}
+
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+/// Vytvoří router pro šablony napojení environmentů na deploy targety.
+/// Zápis jde přes `/deploy-targets`, který auth vrstva povoluje jen adminům.
+pub fn router(state: DeployApiState) -> Router {
+    Router::new()
+        .route(
+            "/deploy-targets/{id}/env-template",
+            get(get_env_template).put(put_env_template).delete(delete_env_template),
+        )
+        .route("/deploy-targets/env-templates/apply", post(apply_env_templates))
+        .with_state(state)
+}
+
+fn error(status: StatusCode, message: impl Into<String>) -> ApiError {
+    (status, Json(ErrorResponse { error: message.into() }))
+}
+
+fn db_error(e: sqlx::Error) -> ApiError {
+    error(StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e))
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// Cesta a větev se vylučují (stejně jako u `deploy_target_envs`)
+fn check_path_or_branch(repo: &str, path: Option<&str>, branch: Option<&str>) -> Result<(), String> {
+    if path.is_some() && branch.is_some() {
+        return Err(format!("Set either {repo}_repo_path or {repo}_repo_branch, not both"));
+    }
+    for pattern in [path, branch].into_iter().flatten() {
+        env_templates::validate(pattern)?;
+    }
+    Ok(())
+}
+
+async fn ensure_deploy_target(state: &DeployApiState, id: Uuid) -> Result<(), ApiError> {
+    let exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM deploy_targets WHERE id = $1)")
+        .bind(id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(db_error)?;
+    if !exists {
+        return Err(error(StatusCode::NOT_FOUND, format!("Deploy target with id {} not found", id)));
+    }
+    Ok(())
+}
+
+/// GET /api/v1/deploy-targets/{id}/env-template - Šablona napojení environmentu
+async fn get_env_template(
+    State(state): State<DeployApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<DeployTargetEnvTemplate>, ApiError> {
+    sqlx::query_as::<_, DeployTargetEnvTemplate>("SELECT * FROM deploy_target_env_templates WHERE deploy_target_id = $1")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(db_error)?
+        .map(Json)
+        .ok_or_else(|| error(StatusCode::NOT_FOUND, format!("Deploy target {} has no env template", id)))
+}
+
+/// PUT /api/v1/deploy-targets/{id}/env-template - Založí nebo přepíše šablonu
+async fn put_env_template(
+    State(state): State<DeployApiState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<PutEnvTemplateRequest>,
+) -> Result<Json<DeployTargetEnvTemplate>, ApiError> {
+    let env_repo_path = non_empty(payload.env_repo_path);
+    let env_repo_branch = non_empty(payload.env_repo_branch);
+    let deploy_repo_path = non_empty(payload.deploy_repo_path);
+    let deploy_repo_branch = non_empty(payload.deploy_repo_branch);
+    let encjson_key_dir = non_empty(payload.encjson_key_dir);
+    check_path_or_branch("env", env_repo_path.as_deref(), env_repo_branch.as_deref())
+        .and_then(|_| check_path_or_branch("deploy", deploy_repo_path.as_deref(), deploy_repo_branch.as_deref()))
+        .and_then(|_| encjson_key_dir.as_deref().map_or(Ok(()), env_templates::validate))
+        .map_err(|e| error(StatusCode::BAD_REQUEST, e))?;
+    ensure_deploy_target(&state, id).await?;
+
+    let template = sqlx::query_as::<_, DeployTargetEnvTemplate>(
+        "INSERT INTO deploy_target_env_templates
+            (deploy_target_id, env_repo_id, env_repo_path, env_repo_branch, deploy_repo_id, deploy_repo_path,
+             deploy_repo_branch, allow_auto_release, append_env_suffix, release_manifest_mode, encjson_key_dir)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+         ON CONFLICT (deploy_target_id) DO UPDATE SET
+            env_repo_id = EXCLUDED.env_repo_id,
+            env_repo_path = EXCLUDED.env_repo_path,
+            env_repo_branch = EXCLUDED.env_repo_branch,
+            deploy_repo_id = EXCLUDED.deploy_repo_id,
+            deploy_repo_path = EXCLUDED.deploy_repo_path,
+            deploy_repo_branch = EXCLUDED.deploy_repo_branch,
+            allow_auto_release = EXCLUDED.allow_auto_release,
+            append_env_suffix = EXCLUDED.append_env_suffix,
+            release_manifest_mode = EXCLUDED.release_manifest_mode,
+            encjson_key_dir = EXCLUDED.encjson_key_dir,
+            updated_at = NOW()
+         RETURNING *",
+    )
+    .bind(id)
+    .bind(payload.env_repo_id)
+    .bind(&env_repo_path)
+    .bind(&env_repo_branch)
+    .bind(payload.deploy_repo_id)
+    .bind(&deploy_repo_path)
+    .bind(&deploy_repo_branch)
+    .bind(payload.allow_auto_release.unwrap_or(false))
+    .bind(payload.append_env_suffix.unwrap_or(false))
+    .bind(non_empty(payload.release_manifest_mode).unwrap_or_else(|| "match_digest".to_string()))
+    .bind(&encjson_key_dir)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(db_error)?;
+    info!("Env template stored for deploy target {}", id);
+
+    Ok(Json(template))
+}
+
+/// DELETE /api/v1/deploy-targets/{id}/env-template - Smaže šablonu (existující napojení zůstanou)
+async fn delete_env_template(
+    State(state): State<DeployApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let result = sqlx::query("DELETE FROM deploy_target_env_templates WHERE deploy_target_id = $1")
+        .bind(id)
+        .execute(&state.pool)
+        .await
+        .map_err(db_error)?;
+    if result.rows_affected() == 0 {
+        return Err(error(StatusCode::NOT_FOUND, format!("Deploy target {} has no env template", id)));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Výsledné napojení podle šablony; repozitář bere ze šablony, pak z deploy targetu, pak z environmentu.
+/// Bez cesty i větve platí stejné výchozí cesty jako při ručním napojení (`{env_slug}`, `deploy/{env_slug}`).
+fn render_env(
+    template: &DeployTargetEnvTemplate,
+    target: &DeployTarget,
+    environment: &Environment,
+    is_active: bool,
+) -> Result<RenderedEnv, String> {
+    let vars = TemplateVars {
+        env_slug: environment.slug.clone(),
+        env_name: environment.name.clone(),
+        target_name: target.name.clone(),
+    };
+    let env_repo_id = template
+        .env_repo_id
+        .or(target.env_repo_id)
+        .or(environment.env_repo_id)
+        .ok_or("No env repo in the template, deploy target or environment")?;
+    let deploy_repo_id = template
+        .deploy_repo_id
+        .or(target.deploy_repo_id)
+        .or(environment.deploy_repo_id)
+        .ok_or("No deploy repo in the template, deploy target or environment")?;
+
+    let env_repo_branch = env_templates::render_optional(template.env_repo_branch.as_deref(), &vars)?;
+    let env_repo_path = match env_repo_branch {
+        Some(_) => None,
+        None => Some(
+            env_templates::render_optional(template.env_repo_path.as_deref(), &vars)?
+                .unwrap_or_else(|| environment.slug.clone()),
+        ),
+    };
+    let deploy_repo_branch = env_templates::render_optional(template.deploy_repo_branch.as_deref(), &vars)?;
+    let deploy_repo_path = match deploy_repo_branch {
+        Some(_) => None,
+        None => Some(
+            env_templates::render_optional(template.deploy_repo_path.as_deref(), &vars)?
+                .unwrap_or_else(|| format!("deploy/{}", environment.slug)),
+        ),
+    };
+
+    Ok(RenderedEnv {
+        env_repo_id,
+        env_repo_path,
+        env_repo_branch,
+        deploy_repo_id,
+        deploy_repo_path,
+        deploy_repo_branch,
+        allow_auto_release: template.allow_auto_release,
+        append_env_suffix: template.append_env_suffix,
+        release_manifest_mode: template.release_manifest_mode.clone(),
+        encjson_key_dir: env_templates::render_optional(template.encjson_key_dir.as_deref(), &vars)?,
+        is_active,
+    })
+}
+
+/// POST /api/v1/deploy-targets/env-templates/apply - Napojí environment na deploy targety podle jejich šablon (v jedné transakci)
+async fn apply_env_templates(
+    State(state): State<DeployApiState>,
+    Json(payload): Json<ApplyEnvTemplatesRequest>,
+) -> Result<Json<ApplyEnvTemplatesResponse>, ApiError> {
+    let dry_run = payload.dry_run.unwrap_or(false);
+    let overwrite = payload.overwrite.unwrap_or(false);
+
+    let environment = sqlx::query_as::<_, Environment>("SELECT * FROM environments WHERE id = $1")
+        .bind(payload.environment_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            error(
+                StatusCode::NOT_FOUND,
+                format!("Environment with id {} not found", payload.environment_id),
+            )
+        })?;
+
+    let targets = sqlx::query_as::<_, DeployTarget>(
+        "SELECT * FROM deploy_targets
+         WHERE tenant_id = $1 AND NOT is_archived AND ($2::uuid[] IS NULL OR id = ANY($2))
+         ORDER BY name",
+    )
+    .bind(environment.tenant_id)
+    .bind(&payload.deploy_target_ids)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(db_error)?;
+    if let Some(ids) = &payload.deploy_target_ids {
+        let missing: Vec<String> = ids
+            .iter()
+            .filter(|id| !targets.iter().any(|target| target.id == **id))
+            .map(Uuid::to_string)
+            .collect();
+        if !missing.is_empty() {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "Deploy targets not found in the environment's tenant or archived: {}",
+                    missing.join(", ")
+                ),
+            ));
+        }
+    }
+
+    let templates: HashMap<Uuid, DeployTargetEnvTemplate> = sqlx::query_as::<_, DeployTargetEnvTemplate>(
+        "SELECT t.* FROM deploy_target_env_templates t
+         JOIN deploy_targets dt ON dt.id = t.deploy_target_id
+         WHERE dt.tenant_id = $1",
+    )
+    .bind(environment.tenant_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(db_error)?
+    .into_iter()
+    .map(|template| (template.deploy_target_id, template))
+    .collect();
+    // Existující napojení: deploy target -> is_active
+    let existing: HashMap<Uuid, bool> = sqlx::query_as::<_, (Uuid, bool)>(
+        "SELECT deploy_target_id, is_active FROM deploy_target_envs WHERE environment_id = $1",
+    )
+    .bind(environment.id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(db_error)?
+    .into_iter()
+    .collect();
+
+    let mut results = Vec::new();
+    for target in &targets {
+        let skipped = |reason: String| ApplyEnvTemplateResult {
+            deploy_target_id: target.id,
+            deploy_target_name: target.name.clone(),
+            action: ApplyAction::Skipped,
+            reason: Some(reason),
+            env: None,
+        };
+        let Some(template) = templates.get(&target.id) else {
+            results.push(skipped("Deploy target has no env template".to_string()));
+            continue;
+        };
+        let action = match existing.get(&target.id) {
+            Some(_) if !overwrite => {
+                results.push(skipped("Environment is already linked (use overwrite)".to_string()));
+                continue;
+            }
+            Some(_) => ApplyAction::Updated,
+            None => ApplyAction::Created,
+        };
+        match render_env(template, target, &environment, existing.get(&target.id).copied().unwrap_or(true)) {
+            Ok(env) => results.push(ApplyEnvTemplateResult {
+                deploy_target_id: target.id,
+                deploy_target_name: target.name.clone(),
+                action,
+                reason: None,
+                env: Some(env),
+            }),
+            Err(e) => results.push(skipped(e)),
+        }
+    }
+
+    if !dry_run {
+        let mut tx = state.pool.begin().await.map_err(db_error)?;
+        for result in &results {
+            let Some(env) = &result.env else {
+                continue;
+            };
+            let entry = deploy::DeployTargetEnvInput {
+                environment_id: environment.id,
+                env_repo_id: env.env_repo_id,
+                env_repo_path: env.env_repo_path.clone(),
+                env_repo_branch: env.env_repo_branch.clone(),
+                deploy_repo_id: env.deploy_repo_id,
+                deploy_repo_path: env.deploy_repo_path.clone(),
+                deploy_repo_branch: env.deploy_repo_branch.clone(),
+                allow_auto_release: Some(env.allow_auto_release),
+                append_env_suffix: Some(env.append_env_suffix),
+                release_manifest_mode: Some(env.release_manifest_mode.clone()),
+                is_active: Some(env.is_active),
+                encjson_key_dir: env.encjson_key_dir.clone(),
+            };
+            deploy::upsert_deploy_target_env(&mut *tx, result.deploy_target_id, &environment, &entry).await?;
+        }
+        tx.commit().await.map_err(db_error)?;
+    }
+
+    let count = |pick: fn(&ApplyAction) -> bool| results.iter().filter(|r| pick(&r.action)).count();
+    let response = ApplyEnvTemplatesResponse {
+        environment_id: environment.id,
+        dry_run,
+        created: count(|a| matches!(a, ApplyAction::Created)),
+        updated: count(|a| matches!(a, ApplyAction::Updated)),
+        skipped: count(|a| matches!(a, ApplyAction::Skipped)),
+        results,
+    };
+    if !dry_run {
+        info!(
+            "Env templates applied for environment {}: {} created, {} updated, {} skipped",
+            environment.slug, response.created, response.updated, response.skipped
+        );
+    }
+    Ok(Json(response))
+}
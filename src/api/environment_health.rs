@@ -0,0 +1,87 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::services::environment_health::{self, EnvironmentHealth};
+
+#[derive(Clone)]
+pub struct EnvironmentHealthApiState {
+    pub pool: PgPool,
+    pub warn_days: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EnvironmentHealthQuery {
+    /// Okno expirace credentials v dnech (výchozí `CREDENTIAL_EXPIRY_WARN_DAYS`)
+    pub days: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EnvironmentHealthResponse {
+    pub tenant_id: Uuid,
+    pub generated_at: DateTime<Utc>,
+    pub warn_days: i64,
+    pub environments: Vec<EnvironmentHealth>,
+}
+
+/// Response s chybou
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+/// Vytvoří router pro semaforový přehled prostředí
+pub fn router(state: EnvironmentHealthApiState) -> Router {
+    Router::new()
+        .route("/tenants/{tenant_id}/environments/health", get(get_environment_health))
+        .with_state(state)
+}
+
+/// GET /api/v1/tenants/{tenant_id}/environments/health - Poslední deploy, ArgoCD health, drift a expirace credentials po prostředích
+async fn get_environment_health(
+    State(state): State<EnvironmentHealthApiState>,
+    Path(tenant_id): Path<Uuid>,
+    Query(query): Query<EnvironmentHealthQuery>,
+) -> Result<Json<EnvironmentHealthResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let db_error = |e: sqlx::Error| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }),
+        )
+    };
+
+    let exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM tenants WHERE id = $1)")
+        .bind(tenant_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(db_error)?;
+    if !exists {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Tenant with id {} not found", tenant_id),
+            }),
+        ));
+    }
+
+    let warn_days = query.days.unwrap_or(state.warn_days).clamp(0, 3650);
+    let environments = environment_health::tenant_overview(&state.pool, tenant_id, warn_days)
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(EnvironmentHealthResponse {
+        tenant_id,
+        generated_at: Utc::now(),
+        warn_days,
+        environments,
+    }))
+}
@@ -0,0 +1,70 @@
+use axum::{extract::Path, http::StatusCode, routing::get, Json, Router};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::services::events::{self, COMPATIBILITY_POLICY, EVENT_CATALOG};
+
+#[derive(Debug, Serialize)]
+pub struct EventCatalogEntry {
+    #[serde(rename = "type")]
+    pub event_type: &'static str,
+    pub version: u32,
+    pub description: &'static str,
+    pub schema_url: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EventCatalogResponse {
+    pub compatibility_policy: &'static str,
+    pub events: Vec<EventCatalogEntry>,
+}
+
+/// Response s chybou
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+/// Vytvoří router pro katalog eventů
+pub fn router() -> Router {
+    Router::new()
+        .route("/events/catalog", get(get_catalog))
+        .route("/events/schemas/{event_type}/{version}", get(get_schema))
+}
+
+/// GET /api/v1/events/catalog - Seznam odchozích eventů (typ, verze, odkaz na schema)
+async fn get_catalog() -> Json<EventCatalogResponse> {
+    Json(EventCatalogResponse {
+        compatibility_policy: COMPATIBILITY_POLICY,
+        events: EVENT_CATALOG
+            .iter()
+            .map(|spec| EventCatalogEntry {
+                event_type: spec.event_type,
+                version: spec.version,
+                description: spec.description,
+                schema_url: format!("/api/v1/events/schemas/{}/v{}", spec.event_type, spec.version),
+            })
+            .collect(),
+    })
+}
+
+/// GET /api/v1/events/schemas/{event_type}/{version} - JSON Schema konkrétní verze eventu (`v1` nebo `1`)
+async fn get_schema(
+    Path((event_type, version)): Path<(String, String)>,
+) -> Result<Json<Value>, (StatusCode, Json<ErrorResponse>)> {
+    version
+        .strip_prefix('v')
+        .unwrap_or(&version)
+        .parse::<u32>()
+        .ok()
+        .and_then(|version| events::find_spec(&event_type, version))
+        .map(|spec| Json(events::json_schema(spec)))
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: format!("Event schema {} {} not found", event_type, version),
+                }),
+            )
+        })
+}
@@ -0,0 +1,95 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+/// `?fields=a,b` (sparse fieldset) a `?expand=x,y` (volitelné sub-resources)
+#[derive(Debug, Default, Deserialize)]
+pub struct FieldsetQuery {
+    pub fields: Option<String>,
+    pub expand: Option<String>,
+}
+
+fn split_list(raw: Option<&str>) -> Vec<&str> {
+    raw.unwrap_or("")
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+impl FieldsetQuery {
+    /// Requested expansions; unknown names are an error so typos don't silently return less data.
+    pub fn expansions(&self, allowed: &[&str]) -> Result<Vec<String>, String> {
+        let requested = split_list(self.expand.as_deref());
+        if let Some(unknown) = requested.iter().find(|name| !allowed.contains(name)) {
+            return Err(format!(
+                "Unknown expand '{}', allowed: {}",
+                unknown,
+                allowed.join(", ")
+            ));
+        }
+        Ok(requested.into_iter().map(str::to_string).collect())
+    }
+
+    /// Ponechá jen požadované top-level klíče (+ `id` a rozbalené sub-resources).
+    /// Bez `?fields=` vrací objekt beze změny.
+    pub fn apply(&self, value: Value, expansions: &[String]) -> Value {
+        let fields = split_list(self.fields.as_deref());
+        if fields.is_empty() {
+            return value;
+        }
+        match value {
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .filter(|(key, _)| {
+                        key == "id"
+                            || fields.contains(&key.as_str())
+                            || expansions.iter().any(|e| e == key)
+                    })
+                    .collect(),
+            ),
+            Value::Array(items) => {
+                Value::Array(items.into_iter().map(|item| self.apply(item, expansions)).collect())
+            }
+            other => other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn query(fields: Option<&str>, expand: Option<&str>) -> FieldsetQuery {
+        FieldsetQuery {
+            fields: fields.map(str::to_string),
+            expand: expand.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn fields_keep_id_and_expansions() {
+        let value = json!({ "id": 1, "name": "dev", "extra_env_vars": {"A": "1"}, "argocd_apps": [] });
+        let q = query(Some("name"), Some("argocd_apps"));
+        let expansions = q.expansions(&["argocd_apps"]).unwrap();
+        assert_eq!(q.apply(value, &expansions), json!({ "id": 1, "name": "dev", "argocd_apps": [] }));
+
+        let list = json!([{ "id": 1, "name": "a", "slug": "a" }]);
+        assert_eq!(query(Some("slug"), None).apply(list, &[]), json!([{ "id": 1, "slug": "a" }]));
+    }
+
+    #[test]
+    fn no_fields_returns_value_unchanged() {
+        let value = json!({ "id": 1, "name": "dev" });
+        assert_eq!(query(None, None).apply(value.clone(), &[]), value);
+    }
+
+    #[test]
+    fn unknown_expand_is_rejected() {
+        assert!(query(None, Some("manifest, bogus")).expansions(&["manifest"]).is_err());
+        assert_eq!(
+            query(None, Some("manifest, ")).expansions(&["manifest"]).unwrap(),
+            vec!["manifest".to_string()]
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{delete, get},
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::api::deploy::DeployApiState;
+use crate::crypto;
+use crate::db::models::DeployTargetFileTemplate;
+use crate::services::file_templates::{self, MAX_TEMPLATE_BYTES};
+
+/// Request pro uložení šablony (podle cesty: založí nebo přepíše)
+#[derive(Debug, Deserialize)]
+pub struct PutFileTemplateRequest {
+    pub path: String,
+    pub content: String,
+}
+
+/// Šablona bez obsahu - obsah může nést secrety, API ho nevrací
+#[derive(Debug, Serialize)]
+pub struct FileTemplateSummary {
+    pub id: Uuid,
+    pub deploy_target_id: Uuid,
+    pub path: String,
+    /// Proměnné `${NAME}`, které šablona používá
+    pub placeholders: Vec<String>,
+    pub size_bytes: usize,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Response s chybou
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+/// Vytvoří router pro šablony souborů deploy targetu.
+/// Zápis jde přes `/deploy-targets`, který auth vrstva povoluje jen adminům.
+pub fn router(state: DeployApiState) -> Router {
+    Router::new()
+        .route(
+            "/deploy-targets/{id}/file-templates",
+            get(list_file_templates).put(put_file_template),
+        )
+        .route("/deploy-targets/{id}/file-templates/{template_id}", delete(delete_file_template))
+        .with_state(state)
+}
+
+fn error(status: StatusCode, message: impl Into<String>) -> ApiError {
+    (status, Json(ErrorResponse { error: message.into() }))
+}
+
+fn db_error(e: sqlx::Error) -> ApiError {
+    error(StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e))
+}
+
+fn summarize(state: &DeployApiState, template: DeployTargetFileTemplate) -> Result<FileTemplateSummary, ApiError> {
+    let content = crypto::decrypt(&template.content_encrypted, &state.encryption_secret).map_err(|e| {
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to decrypt file template {}: {}", template.path, e),
+        )
+    })?;
+    Ok(FileTemplateSummary {
+        id: template.id,
+        deploy_target_id: template.deploy_target_id,
+        path: template.path,
+        placeholders: file_templates::placeholders(&content),
+        size_bytes: content.len(),
+        created_at: template.created_at,
+        updated_at: template.updated_at,
+    })
+}
+
+async fn ensure_deploy_target(state: &DeployApiState, id: Uuid) -> Result<(), ApiError> {
+    let exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM deploy_targets WHERE id = $1)")
+        .bind(id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(db_error)?;
+    if !exists {
+        return Err(error(StatusCode::NOT_FOUND, format!("Deploy target with id {} not found", id)));
+    }
+    Ok(())
+}
+
+/// GET /api/v1/deploy-targets/{id}/file-templates - Šablony souborů deploy targetu (bez obsahu)
+async fn list_file_templates(
+    State(state): State<DeployApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<FileTemplateSummary>>, ApiError> {
+    ensure_deploy_target(&state, id).await?;
+    let templates = sqlx::query_as::<_, DeployTargetFileTemplate>(
+        "SELECT * FROM deploy_target_file_templates WHERE deploy_target_id = $1 ORDER BY path",
+    )
+    .bind(id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(db_error)?;
+
+    let summaries = templates
+        .into_iter()
+        .map(|template| summarize(&state, template))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Json(summaries))
+}
+
+/// PUT /api/v1/deploy-targets/{id}/file-templates - Založí nebo přepíše šablonu na dané cestě
+async fn put_file_template(
+    State(state): State<DeployApiState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<PutFileTemplateRequest>,
+) -> Result<Json<FileTemplateSummary>, ApiError> {
+    let path = file_templates::validate_path(&payload.path).map_err(|e| error(StatusCode::BAD_REQUEST, e))?;
+    if payload.content.len() > MAX_TEMPLATE_BYTES {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            format!("File template exceeds {} bytes", MAX_TEMPLATE_BYTES),
+        ));
+    }
+    ensure_deploy_target(&state, id).await?;
+
+    let content_encrypted = crypto::encrypt(&payload.content, &state.encryption_secret)
+        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, format!("Encryption error: {}", e)))?;
+    let template = sqlx::query_as::<_, DeployTargetFileTemplate>(
+        "INSERT INTO deploy_target_file_templates (deploy_target_id, path, content_encrypted)
+         VALUES ($1, $2, $3)
+         ON CONFLICT (deploy_target_id, path)
+         DO UPDATE SET content_encrypted = EXCLUDED.content_encrypted, updated_at = NOW()
+         RETURNING *",
+    )
+    .bind(id)
+    .bind(&path)
+    .bind(&content_encrypted)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(db_error)?;
+    info!("File template {} stored for deploy target {}", path, id);
+
+    Ok(Json(summarize(&state, template)?))
+}
+
+/// DELETE /api/v1/deploy-targets/{id}/file-templates/{template_id} - Smaže šablonu
+async fn delete_file_template(
+    State(state): State<DeployApiState>,
+    Path((id, template_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, ApiError> {
+    let result = sqlx::query("DELETE FROM deploy_target_file_templates WHERE id = $1 AND deploy_target_id = $2")
+        .bind(template_id)
+        .bind(id)
+        .execute(&state.pool)
+        .await
+        .map_err(db_error)?;
+    if result.rows_affected() == 0 {
+        return Err(error(
+            StatusCode::NOT_FOUND,
+            format!("File template with id {} not found", template_id),
+        ));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
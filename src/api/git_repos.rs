@@ -4,11 +4,14 @@ use axum::{
     routing::get,
     Extension, Json, Router,
 };
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::auth::AuthContext;
+use crate::services::credential_expiry;
+use crate::services::key_material;
 use crate::{
     crypto,
     db::models::GitRepository,
@@ -18,6 +21,8 @@ use crate::{
 pub struct GitRepoApiState {
     pub pool: PgPool,
     pub encryption_secret: String,
+    /// Okno "expiring soon" pro credentials (dny)
+    pub credential_expiry_warn_days: i64,
 }
 
 #[derive(Debug, Serialize)]
@@ -34,6 +39,8 @@ pub struct CreateGitRepoRequest {
     pub git_username: Option<String>,
     pub git_token: Option<String>,
     pub git_ssh_key: Option<String>,
+    /// Kdy přestane platit token / SSH klíč; null = bez expirace
+    pub credentials_expire_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -45,6 +52,14 @@ pub struct UpdateGitRepoRequest {
     pub git_username: Option<String>,
     pub git_token: Option<String>,
     pub git_ssh_key: Option<String>,
+    /// Kdy přestane platit token / SSH klíč; null = bez expirace
+    pub credentials_expire_at: Option<DateTime<Utc>>,
+}
+
+/// Doplní stav expirace credentials do odpovědi
+fn with_credentials_expiry(mut repo: GitRepository, warn_days: i64) -> GitRepository {
+    repo.credentials_expiry = credential_expiry::expiry_state(repo.credentials_expire_at, warn_days);
+    repo
 }
 
 pub fn router(state: GitRepoApiState) -> Router {
@@ -82,7 +97,12 @@ async fn list_git_repos(
         )
     })?;
 
-    Ok(Json(repos))
+    Ok(Json(
+        repos
+            .into_iter()
+            .map(|repo| with_credentials_expiry(repo, state.credential_expiry_warn_days))
+            .collect(),
+    ))
 }
 
 async fn list_tenant_git_repos(
@@ -104,7 +124,12 @@ async fn list_tenant_git_repos(
         )
     })?;
 
-    Ok(Json(repos))
+    Ok(Json(
+        repos
+            .into_iter()
+            .map(|repo| with_credentials_expiry(repo, state.credential_expiry_warn_days))
+            .collect(),
+    ))
 }
 
 async fn get_git_repo(
@@ -125,7 +150,7 @@ async fn get_git_repo(
         })?;
 
     match repo {
-        Some(repo) => Ok(Json(repo)),
+        Some(repo) => Ok(Json(with_credentials_expiry(repo, state.credential_expiry_warn_days))),
         None => Err((
             StatusCode::NOT_FOUND,
             Json(ErrorResponse {
@@ -191,7 +216,9 @@ async fn create_git_repo(
     };
 
     let git_ssh_key_encrypted = match payload.git_ssh_key {
-        Some(key) if !key.trim().is_empty() => Some(
+        Some(key) if !key.trim().is_empty() => Some({
+            let key = key_material::validate_ssh_private_key(&key)
+                .map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: e })))?;
             crypto::encrypt(&key, &state.encryption_secret).map_err(|e| {
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
@@ -199,16 +226,16 @@ async fn create_git_repo(
                         error: format!("Failed to encrypt git ssh key: {}", e),
                     }),
                 )
-            })?,
-        ),
+            })?
+        }),
         _ => None,
     };
 
     let repo = sqlx::query_as::<_, GitRepository>(
         r#"
         INSERT INTO git_repositories
-            (tenant_id, name, repo_url, default_branch, git_auth_type, git_username, git_token_encrypted, git_ssh_key_encrypted)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            (tenant_id, name, repo_url, default_branch, git_auth_type, git_username, git_token_encrypted, git_ssh_key_encrypted, credentials_expire_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
         RETURNING *
         "#
     )
@@ -220,6 +247,7 @@ async fn create_git_repo(
     .bind(payload.git_username)
     .bind(git_token_encrypted)
     .bind(git_ssh_key_encrypted)
+    .bind(payload.credentials_expire_at)
     .fetch_one(&state.pool)
     .await
     .map_err(|e| {
@@ -231,7 +259,10 @@ async fn create_git_repo(
         )
     })?;
 
-    Ok((StatusCode::CREATED, Json(repo)))
+    Ok((
+        StatusCode::CREATED,
+        Json(with_credentials_expiry(repo, state.credential_expiry_warn_days)),
+    ))
 }
 
 async fn update_git_repo(
@@ -272,7 +303,9 @@ async fn update_git_repo(
     };
 
     let git_ssh_key_encrypted = match payload.git_ssh_key {
-        Some(key) if !key.trim().is_empty() => Some(
+        Some(key) if !key.trim().is_empty() => Some({
+            let key = key_material::validate_ssh_private_key(&key)
+                .map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: e })))?;
             crypto::encrypt(&key, &state.encryption_secret).map_err(|e| {
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
@@ -280,8 +313,8 @@ async fn update_git_repo(
                         error: format!("Failed to encrypt git ssh key: {}", e),
                     }),
                 )
-            })?,
-        ),
+            })?
+        }),
         _ => None,
     };
 
@@ -294,7 +327,8 @@ async fn update_git_repo(
             git_auth_type = $4,
             git_username = $5,
             git_token_encrypted = COALESCE($6, git_token_encrypted),
-            git_ssh_key_encrypted = COALESCE($7, git_ssh_key_encrypted)
+            git_ssh_key_encrypted = COALESCE($7, git_ssh_key_encrypted),
+            credentials_expire_at = $9
         WHERE id = $8
         RETURNING *
         "#
@@ -307,6 +341,7 @@ async fn update_git_repo(
     .bind(git_token_encrypted)
     .bind(git_ssh_key_encrypted)
     .bind(id)
+    .bind(payload.credentials_expire_at)
     .fetch_one(&state.pool)
     .await
     .map_err(|e| {
@@ -318,7 +353,7 @@ async fn update_git_repo(
         )
     })?;
 
-    Ok(Json(repo))
+    Ok(Json(with_credentials_expiry(repo, state.credential_expiry_warn_days)))
 }
 
 async fn delete_git_repo(
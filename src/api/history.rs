@@ -0,0 +1,98 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::db::models::EntityChange;
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    pub limit: Option<i64>,
+}
+
+/// Response s chybou
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+/// Vytvoří router pro change history endpoints
+pub fn router(pool: PgPool) -> Router {
+    Router::new()
+        .route("/environments/{id}/history", get(environment_history))
+        .route("/registries/{id}/history", get(registry_history))
+        .route("/bundles/{id}/history", get(bundle_history))
+        .route("/deploy-targets/{id}/history", get(deploy_target_history))
+        .with_state(pool)
+}
+
+/// GET /api/v1/environments/{id}/history - Historie změn environmentu
+async fn environment_history(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<Vec<EntityChange>>, (StatusCode, Json<ErrorResponse>)> {
+    list_entity_history(&pool, "environment", id, query.limit).await
+}
+
+/// GET /api/v1/registries/{id}/history - Historie změn registry
+async fn registry_history(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<Vec<EntityChange>>, (StatusCode, Json<ErrorResponse>)> {
+    list_entity_history(&pool, "registry", id, query.limit).await
+}
+
+/// GET /api/v1/bundles/{id}/history - Historie změn bundle
+async fn bundle_history(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<Vec<EntityChange>>, (StatusCode, Json<ErrorResponse>)> {
+    list_entity_history(&pool, "bundle", id, query.limit).await
+}
+
+/// GET /api/v1/deploy-targets/{id}/history - Historie změn deploy targetu
+async fn deploy_target_history(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<Vec<EntityChange>>, (StatusCode, Json<ErrorResponse>)> {
+    list_entity_history(&pool, "deploy_target", id, query.limit).await
+}
+
+async fn list_entity_history(
+    pool: &PgPool,
+    entity_type: &str,
+    entity_id: Uuid,
+    limit: Option<i64>,
+) -> Result<Json<Vec<EntityChange>>, (StatusCode, Json<ErrorResponse>)> {
+    let limit = limit.unwrap_or(100).clamp(1, 1000);
+    let rows = sqlx::query_as::<_, EntityChange>(
+        "SELECT * FROM entity_change_history
+         WHERE entity_type = $1 AND entity_id = $2
+         ORDER BY created_at DESC
+         LIMIT $3",
+    )
+    .bind(entity_type)
+    .bind(entity_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }),
+        )
+    })?;
+
+    Ok(Json(rows))
+}
@@ -0,0 +1,245 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::db::models::ImagePolicy;
+use crate::services::image_policy::{self, EFFECT_ALLOW, EFFECT_DENY};
+
+/// Request pro vytvoření image policy
+#[derive(Debug, Deserialize)]
+pub struct CreateImagePolicyRequest {
+    pub tenant_id: Uuid,
+    #[serde(flatten)]
+    pub policy: ImagePolicyRequest,
+}
+
+/// Request pro update image policy
+#[derive(Debug, Deserialize)]
+pub struct ImagePolicyRequest {
+    pub name: String,
+    pub effect: String,
+    pub registry_pattern: Option<String>,
+    pub repository_pattern: Option<String>,
+    pub environment_pattern: Option<String>,
+    pub description: Option<String>,
+    pub is_active: Option<bool>,
+}
+
+/// Response s chybou
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+/// Vytvoří router pro image policy endpoints.
+/// Zápis jde přes `/image-policies`, který auth vrstva povoluje jen adminům.
+pub fn router(pool: PgPool) -> Router {
+    Router::new()
+        .route("/tenants/{tenant_id}/image-policies", get(list_image_policies))
+        .route("/image-policies", post(create_image_policy))
+        .route(
+            "/image-policies/{id}",
+            get(get_image_policy).put(update_image_policy).delete(delete_image_policy),
+        )
+        .with_state(pool)
+}
+
+fn db_error(e: sqlx::Error) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: format!("Database error: {}", e),
+        }),
+    )
+}
+
+fn bad_request(error: String) -> (StatusCode, Json<ErrorResponse>) {
+    (StatusCode::BAD_REQUEST, Json(ErrorResponse { error }))
+}
+
+fn normalize_pattern(pattern: Option<&str>) -> Option<String> {
+    pattern.map(str::trim).filter(|p| !p.is_empty()).map(str::to_string)
+}
+
+/// Validace requestu; vrací normalizované patterny (registry, repository, environment)
+async fn validate_request(
+    pool: &PgPool,
+    payload: &ImagePolicyRequest,
+) -> Result<[Option<String>; 3], (StatusCode, Json<ErrorResponse>)> {
+    if payload.name.trim().is_empty() {
+        return Err(bad_request("Policy name cannot be empty".to_string()));
+    }
+    if payload.effect != EFFECT_ALLOW && payload.effect != EFFECT_DENY {
+        return Err(bad_request(format!(
+            "Invalid effect '{}', expected '{}' or '{}'",
+            payload.effect, EFFECT_ALLOW, EFFECT_DENY
+        )));
+    }
+    let patterns = [
+        normalize_pattern(payload.registry_pattern.as_deref()),
+        normalize_pattern(payload.repository_pattern.as_deref()),
+        normalize_pattern(payload.environment_pattern.as_deref()),
+    ];
+    if patterns[0].is_none() && patterns[1].is_none() {
+        return Err(bad_request(
+            "Policy needs a registry_pattern or repository_pattern".to_string(),
+        ));
+    }
+    for (field, pattern) in ["registry_pattern", "repository_pattern", "environment_pattern"]
+        .iter()
+        .zip(&patterns)
+    {
+        if let Some(pattern) = pattern
+            && let Err(err) = image_policy::validate_pattern(pool, pattern).await
+        {
+            return Err(bad_request(format!("Invalid {}: {}", field, err)));
+        }
+    }
+    Ok(patterns)
+}
+
+/// GET /api/v1/tenants/{tenant_id}/image-policies - Seznam image policies tenanta
+async fn list_image_policies(
+    State(pool): State<PgPool>,
+    Path(tenant_id): Path<Uuid>,
+) -> Result<Json<Vec<ImagePolicy>>, (StatusCode, Json<ErrorResponse>)> {
+    let policies = sqlx::query_as::<_, ImagePolicy>(
+        "SELECT * FROM image_policies WHERE tenant_id = $1 ORDER BY created_at",
+    )
+    .bind(tenant_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(policies))
+}
+
+/// GET /api/v1/image-policies/{id} - Detail image policy
+async fn get_image_policy(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ImagePolicy>, (StatusCode, Json<ErrorResponse>)> {
+    sqlx::query_as::<_, ImagePolicy>("SELECT * FROM image_policies WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(db_error)?
+        .map(Json)
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: format!("Image policy with id {} not found", id),
+                }),
+            )
+        })
+}
+
+/// POST /api/v1/image-policies - Vytvoření image policy
+async fn create_image_policy(
+    State(pool): State<PgPool>,
+    Json(payload): Json<CreateImagePolicyRequest>,
+) -> Result<(StatusCode, Json<ImagePolicy>), (StatusCode, Json<ErrorResponse>)> {
+    let tenant_exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM tenants WHERE id = $1)")
+        .bind(payload.tenant_id)
+        .fetch_one(&pool)
+        .await
+        .map_err(db_error)?;
+    if !tenant_exists {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Tenant with id {} not found", payload.tenant_id),
+            }),
+        ));
+    }
+
+    let policy = &payload.policy;
+    let [registry_pattern, repository_pattern, environment_pattern] = validate_request(&pool, policy).await?;
+
+    let created = sqlx::query_as::<_, ImagePolicy>(
+        "INSERT INTO image_policies
+         (tenant_id, name, effect, registry_pattern, repository_pattern, environment_pattern, description, is_active)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+         RETURNING *",
+    )
+    .bind(payload.tenant_id)
+    .bind(policy.name.trim())
+    .bind(&policy.effect)
+    .bind(&registry_pattern)
+    .bind(&repository_pattern)
+    .bind(&environment_pattern)
+    .bind(&policy.description)
+    .bind(policy.is_active.unwrap_or(true))
+    .fetch_one(&pool)
+    .await
+    .map_err(db_error)?;
+
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// PUT /api/v1/image-policies/{id} - Update image policy
+async fn update_image_policy(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ImagePolicyRequest>,
+) -> Result<Json<ImagePolicy>, (StatusCode, Json<ErrorResponse>)> {
+    let [registry_pattern, repository_pattern, environment_pattern] = validate_request(&pool, &payload).await?;
+
+    sqlx::query_as::<_, ImagePolicy>(
+        "UPDATE image_policies
+         SET name = $1, effect = $2, registry_pattern = $3, repository_pattern = $4,
+             environment_pattern = $5, description = $6, is_active = COALESCE($7, is_active)
+         WHERE id = $8
+         RETURNING *",
+    )
+    .bind(payload.name.trim())
+    .bind(&payload.effect)
+    .bind(&registry_pattern)
+    .bind(&repository_pattern)
+    .bind(&environment_pattern)
+    .bind(&payload.description)
+    .bind(payload.is_active)
+    .bind(id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(db_error)?
+    .map(Json)
+    .ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Image policy with id {} not found", id),
+            }),
+        )
+    })
+}
+
+/// DELETE /api/v1/image-policies/{id} - Smazání image policy
+async fn delete_image_policy(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    let result = sqlx::query("DELETE FROM image_policies WHERE id = $1")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(db_error)?;
+
+    if result.rows_affected() == 0 {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Image policy with id {} not found", id),
+            }),
+        ));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
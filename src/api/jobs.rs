@@ -0,0 +1,403 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::sse::{Event, KeepAlive, Sse},
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::convert::Infallible;
+use uuid::Uuid;
+
+use crate::auth::AuthContext;
+use crate::services::job_events::{self, JobEvent, JobEventStreamItem, JobEventsQuery};
+use crate::services::job_logs::{self, JobKind, LogStreamQuery};
+
+/// Maximální počet jobů v jednom batch dotazu
+const MAX_BATCH_JOB_IDS: usize = 200;
+
+pub use srm_api_types::jobs::{
+    JobStatusBatchRequest, JobStatusBatchResponse, JobStatusItem, QueueOverview, QueueSummary, QueuedJob, WorkerLoad,
+};
+
+/// Řádek batch dotazu - tenant slouží jen pro filtrování přístupu
+#[derive(Debug, sqlx::FromRow)]
+struct JobStatusRow {
+    #[sqlx(flatten)]
+    item: JobStatusItem,
+    tenant_id: Uuid,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QueueQuery {
+    /// `copy` nebo `deploy`
+    pub kind: Option<String>,
+    pub tenant_id: Option<Uuid>,
+}
+
+/// Response s chybou
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+/// Vytvoří router pro společné job endpoints
+pub fn router(pool: PgPool) -> Router {
+    Router::new()
+        .route("/jobs/status", post(batch_job_status))
+        .route("/queue", get(job_queue))
+        .route("/copy/jobs/{job_id}/events", get(copy_job_events))
+        .route("/copy/jobs/{job_id}/events/stream", get(copy_job_events_stream))
+        .route("/deploy/jobs/{id}/events", get(deploy_job_events))
+        .route("/deploy/jobs/{id}/events/stream", get(deploy_job_events_stream))
+        .with_state(pool)
+}
+
+/// POST /api/v1/jobs/status - Stav více copy/deploy jobů v jedné odpovědi
+async fn batch_job_status(
+    Extension(auth): Extension<AuthContext>,
+    State(pool): State<PgPool>,
+    Json(payload): Json<JobStatusBatchRequest>,
+) -> Result<Json<JobStatusBatchResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let mut job_ids = payload.job_ids;
+    job_ids.sort();
+    job_ids.dedup();
+
+    if job_ids.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "job_ids cannot be empty".to_string(),
+            }),
+        ));
+    }
+    if job_ids.len() > MAX_BATCH_JOB_IDS {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: format!("At most {} job_ids are allowed", MAX_BATCH_JOB_IDS),
+            }),
+        ));
+    }
+
+    let rows = sqlx::query_as::<_, JobStatusRow>(
+        r#"
+        SELECT
+            cj.id AS job_id,
+            'copy' AS kind,
+            cj.status,
+            cj.status IN ('success', 'failed', 'cancelled') AS is_finished,
+            cj.started_at,
+            cj.completed_at,
+            NULL::text AS error_message,
+            (SELECT COUNT(*) FROM copy_job_images cji WHERE cji.copy_job_id = cj.id) AS total_images,
+            (SELECT COUNT(*) FROM copy_job_images cji
+             WHERE cji.copy_job_id = cj.id AND cji.copy_status = 'success') AS copied_images,
+            (SELECT COUNT(*) FROM copy_job_images cji
+             WHERE cji.copy_job_id = cj.id AND cji.copy_status = 'failed') AS failed_images,
+            b.tenant_id
+        FROM copy_jobs cj
+        JOIN bundle_versions bv ON bv.id = cj.bundle_version_id
+        JOIN bundles b ON b.id = bv.bundle_id
+        WHERE cj.id = ANY($1)
+        UNION ALL
+        SELECT
+            dj.id AS job_id,
+            'deploy' AS kind,
+            dj.status,
+            dj.status IN ('success', 'failed', 'cancelled') AS is_finished,
+            dj.started_at,
+            dj.completed_at,
+            dj.error_message,
+            NULL::bigint AS total_images,
+            NULL::bigint AS copied_images,
+            NULL::bigint AS failed_images,
+            e.tenant_id
+        FROM deploy_jobs dj
+        JOIN environments e ON e.id = dj.environment_id
+        WHERE dj.id = ANY($1)
+        "#,
+    )
+    .bind(&job_ids)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }),
+        )
+    })?;
+
+    let jobs: Vec<JobStatusItem> = rows
+        .into_iter()
+        .filter(|row| auth.is_tenant_allowed(row.tenant_id))
+        .map(|row| row.item)
+        .collect();
+    let not_found = job_ids
+        .into_iter()
+        .filter(|id| !jobs.iter().any(|job| job.job_id == *id))
+        .collect();
+
+    Ok(Json(JobStatusBatchResponse { jobs, not_found }))
+}
+
+/// GET /api/v1/queue - Nespuštěné copy/deploy joby s pořadím ve frontě a vytížení workerů
+async fn job_queue(
+    Extension(auth): Extension<AuthContext>,
+    State(pool): State<PgPool>,
+    Query(query): Query<QueueQuery>,
+) -> Result<Json<QueueOverview>, (StatusCode, Json<ErrorResponse>)> {
+    if let Some(kind) = query.kind.as_deref()
+        && kind != "copy"
+        && kind != "deploy"
+    {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "kind must be copy or deploy".to_string(),
+            }),
+        ));
+    }
+
+    // Pořadí se počítá přes všechny tenanty - i cizí joby zabírají místo ve frontě
+    let waiting = sqlx::query_as::<_, QueuedJob>(
+        r#"
+        WITH waiting AS (
+            SELECT
+                cj.id AS job_id,
+                'copy' AS kind,
+                b.tenant_id,
+                b.name || ' v' || bv.version || ' -> ' || cj.target_tag AS description,
+                cj.created_at,
+                cj.queued_at,
+                cj.claimed_at,
+                cj.claimed_by
+            FROM copy_jobs cj
+            JOIN bundle_versions bv ON bv.id = cj.bundle_version_id
+            JOIN bundles b ON b.id = bv.bundle_id
+            WHERE cj.status = 'pending'
+            UNION ALL
+            SELECT
+                dj.id,
+                'deploy',
+                e.tenant_id,
+                r.release_id || ' -> ' || e.slug,
+                dj.created_at,
+                dj.queued_at,
+                dj.claimed_at,
+                dj.claimed_by
+            FROM deploy_jobs dj
+            JOIN environments e ON e.id = dj.environment_id
+            JOIN releases r ON r.id = dj.release_id
+            WHERE dj.status = 'pending'
+        )
+        SELECT
+            job_id,
+            kind,
+            CASE
+                WHEN claimed_by IS NOT NULL THEN 'starting'
+                WHEN queued_at IS NOT NULL THEN 'queued'
+                ELSE 'pending'
+            END AS queue_state,
+            CASE WHEN claimed_by IS NULL AND queued_at IS NOT NULL THEN
+                ROW_NUMBER() OVER (PARTITION BY kind, claimed_by IS NULL AND queued_at IS NOT NULL ORDER BY queued_at)
+            END AS position,
+            tenant_id,
+            description,
+            created_at,
+            queued_at,
+            claimed_at,
+            claimed_by,
+            EXTRACT(EPOCH FROM NOW() - COALESCE(queued_at, created_at))::bigint AS waiting_seconds
+        FROM waiting
+        ORDER BY kind, queued_at NULLS LAST, created_at
+        "#,
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }),
+        )
+    })?;
+
+    // Souhrn a workery jen z jobů tenantů, které volající vidí (admin = všechny)
+    let allowed_tenants = (!auth.is_admin()).then(|| auth.tenant_ids.clone());
+    let workers = sqlx::query_as::<_, WorkerLoad>(
+        r#"
+        SELECT
+            worker_id,
+            COUNT(*) FILTER (WHERE kind = 'copy') AS copy_running,
+            COUNT(*) FILTER (WHERE kind = 'deploy') AS deploy_running
+        FROM (
+            SELECT 'copy' AS kind, cj.claimed_by AS worker_id, b.tenant_id
+            FROM copy_jobs cj
+            JOIN bundle_versions bv ON bv.id = cj.bundle_version_id
+            JOIN bundles b ON b.id = bv.bundle_id
+            WHERE cj.status = 'in_progress'
+            UNION ALL
+            SELECT 'deploy', dj.claimed_by, e.tenant_id
+            FROM deploy_jobs dj
+            JOIN environments e ON e.id = dj.environment_id
+            WHERE dj.status = 'in_progress'
+        ) running
+        WHERE ($1::uuid[] IS NULL OR tenant_id = ANY($1))
+          AND ($2::uuid IS NULL OR tenant_id = $2)
+        GROUP BY worker_id
+        ORDER BY worker_id NULLS FIRST
+        "#,
+    )
+    .bind(&allowed_tenants)
+    .bind(query.tenant_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }),
+        )
+    })?;
+
+    let waiting: Vec<QueuedJob> = waiting
+        .into_iter()
+        .filter(|job| query.tenant_id.is_none_or(|id| id == job.tenant_id))
+        .filter(|job| auth.is_tenant_allowed(job.tenant_id))
+        .collect();
+
+    let summary = ["copy", "deploy"]
+        .into_iter()
+        .filter(|kind| query.kind.as_deref().is_none_or(|k| k == *kind))
+        .map(|kind| {
+            let count = |state: &str| {
+                waiting
+                    .iter()
+                    .filter(|job| job.kind == kind && job.queue_state == state)
+                    .count() as i64
+            };
+            QueueSummary {
+                kind: kind.to_string(),
+                pending: count("pending"),
+                queued: count("queued"),
+                starting: count("starting"),
+                running: workers
+                    .iter()
+                    .map(|w| if kind == "copy" { w.copy_running } else { w.deploy_running })
+                    .sum(),
+            }
+        })
+        .collect();
+
+    let jobs = waiting
+        .into_iter()
+        .filter(|job| query.kind.as_deref().is_none_or(|k| k == job.kind))
+        .collect();
+
+    Ok(Json(QueueOverview { summary, workers, jobs }))
+}
+
+/// Události jobu; 404, pokud job neexistuje
+async fn list_job_events(
+    pool: &PgPool,
+    kind: JobKind,
+    job_id: Uuid,
+    query: &JobEventsQuery,
+) -> Result<Json<Vec<JobEvent>>, (StatusCode, Json<ErrorResponse>)> {
+    let db_error = |e: sqlx::Error| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }),
+        )
+    };
+    if job_events::job_status(pool, kind, job_id).await.map_err(db_error)?.is_none() {
+        let what = if kind == JobKind::Copy { "Copy job" } else { "Deploy job" };
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("{} not found", what),
+            }),
+        ));
+    }
+    let events = job_events::list(pool, kind, job_id, query).await.map_err(db_error)?;
+    Ok(Json(events))
+}
+
+/// SSE stream událostí: `job-event` (id = seq, data = JSON události), na konci `events-end` se stavem jobu
+fn job_events_sse(
+    pool: PgPool,
+    kind: JobKind,
+    job_id: Uuid,
+    query: JobEventsQuery,
+    headers: HeaderMap,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let last_event_id = headers.get("last-event-id").and_then(|value| value.to_str().ok());
+    let after_seq = job_logs::stream_start_seq(last_event_id, &LogStreamQuery { after_seq: query.after_seq });
+    let query = JobEventsQuery {
+        after_seq: Some(after_seq),
+        ..query
+    };
+
+    let stream = job_events::stream_events(pool, kind, job_id, query).map(|item| {
+        let event = match item {
+            Ok(JobEventStreamItem::Event(event)) => Event::default()
+                .id(event.seq.to_string())
+                .event("job-event")
+                .json_data(&event)
+                .unwrap_or_else(|e| Event::default().event("error").data(e.to_string())),
+            Ok(JobEventStreamItem::End(status)) => Event::default().event("events-end").data(status),
+            Ok(JobEventStreamItem::NotFound) => Event::default().event("events-end").data("Job not found"),
+            Err(e) => Event::default().event("error").data(format!("Database error: {}", e)),
+        };
+        Ok(event)
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/// GET /api/v1/copy/jobs/{job_id}/events?after_seq=&event_type= - Strukturované události copy jobu
+async fn copy_job_events(
+    State(pool): State<PgPool>,
+    Path(job_id): Path<Uuid>,
+    Query(query): Query<JobEventsQuery>,
+) -> Result<Json<Vec<JobEvent>>, (StatusCode, Json<ErrorResponse>)> {
+    list_job_events(&pool, JobKind::Copy, job_id, &query).await
+}
+
+/// GET /api/v1/copy/jobs/{job_id}/events/stream - Události copy jobu jako SSE (historie + nové)
+async fn copy_job_events_stream(
+    State(pool): State<PgPool>,
+    Path(job_id): Path<Uuid>,
+    Query(query): Query<JobEventsQuery>,
+    headers: HeaderMap,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    job_events_sse(pool, JobKind::Copy, job_id, query, headers)
+}
+
+/// GET /api/v1/deploy/jobs/{id}/events?after_seq=&event_type= - Strukturované události deploy jobu
+async fn deploy_job_events(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<JobEventsQuery>,
+) -> Result<Json<Vec<JobEvent>>, (StatusCode, Json<ErrorResponse>)> {
+    list_job_events(&pool, JobKind::Deploy, id, &query).await
+}
+
+/// GET /api/v1/deploy/jobs/{id}/events/stream - Události deploy jobu jako SSE (historie + nové)
+async fn deploy_job_events_stream(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<JobEventsQuery>,
+    headers: HeaderMap,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    job_events_sse(pool, JobKind::Deploy, id, query, headers)
+}
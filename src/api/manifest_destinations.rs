@@ -0,0 +1,771 @@
+use anyhow::Context;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::api::deploy::{self, DeployApiState};
+use crate::crypto;
+use crate::db::models::{GitRepository, ManifestDestination};
+use crate::services::manifest_delivery::{
+    self, PathVars, DEFAULT_PATH_TEMPLATE, FORMAT_JSON, FORMAT_YAML, KIND_GIT, KIND_HTTP, KIND_S3,
+};
+use crate::services::object_storage::{ObjectStorage, ObjectStorageConfig};
+use crate::services::release_manifest::load_release_manifest;
+use crate::services::sandbox::SandboxTool;
+
+/// Počet doručení zpracovaných v jednom průchodu
+const DELIVERY_BATCH_SIZE: i64 = 20;
+/// Doručení `in_progress` starší než tohle se považuje za přerušené (pád procesu) a zkusí se znovu
+const STALE_DELIVERY_MINUTES: i32 = 15;
+
+/// Request pro vytvoření cíle doručení manifestů
+#[derive(Debug, Deserialize)]
+pub struct CreateManifestDestinationRequest {
+    pub tenant_id: Uuid,
+    #[serde(flatten)]
+    pub destination: ManifestDestinationRequest,
+}
+
+/// Request pro update cíle doručení manifestů
+#[derive(Debug, Deserialize)]
+pub struct ManifestDestinationRequest {
+    pub name: String,
+    pub kind: String,
+    pub bundle_id: Option<Uuid>,
+    pub format: Option<String>,
+    pub path_template: Option<String>,
+    pub git_repository_id: Option<Uuid>,
+    pub git_branch: Option<String>,
+    pub url: Option<String>,
+    pub s3_bucket: Option<String>,
+    pub s3_region: Option<String>,
+    pub s3_access_key: Option<String>,
+    pub s3_path_style: Option<bool>,
+    /// HTTP bearer token / S3 secret key; při update bez hodnoty zůstává uložený
+    pub secret: Option<String>,
+    pub is_active: Option<bool>,
+}
+
+/// Stav doručení manifestu release do jednoho cíle
+#[derive(Debug, Serialize, FromRow)]
+pub struct ManifestDelivery {
+    pub release_id: Uuid,
+    pub destination_id: Uuid,
+    pub destination_name: String,
+    pub kind: String,
+    /// `pending`, `in_progress`, `success` nebo `failed`
+    pub status: String,
+    pub attempts: i32,
+    /// Commit SHA / klíč objektu / HTTP status odpovědi
+    pub location: Option<String>,
+    pub error_message: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeliveryRunResult {
+    pub enqueued: u64,
+    pub delivered: usize,
+    pub failed: usize,
+}
+
+/// Response s chybou
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+#[derive(Debug, FromRow)]
+struct ClaimedDelivery {
+    release_id: Uuid,
+    destination_id: Uuid,
+}
+
+#[derive(Debug, FromRow)]
+struct DeliveryReleaseRow {
+    release_id: String,
+    tenant_slug: String,
+    bundle_name: String,
+}
+
+/// Vytvoří router pro cíle doručení release manifestů.
+/// Zápis cílů jde přes `/manifest-destinations`, který auth vrstva povoluje jen adminům.
+pub fn router(state: DeployApiState) -> Router {
+    Router::new()
+        .route("/tenants/{tenant_id}/manifest-destinations", get(list_manifest_destinations))
+        .route("/manifest-destinations", post(create_manifest_destination))
+        .route(
+            "/manifest-destinations/{id}",
+            get(get_manifest_destination)
+                .put(update_manifest_destination)
+                .delete(delete_manifest_destination),
+        )
+        .route("/releases/{id}/deliveries", get(list_release_deliveries))
+        .route("/releases/{id}/deliveries/{destination_id}", post(queue_release_delivery))
+        .with_state(state)
+}
+
+fn db_error(e: sqlx::Error) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: format!("Database error: {}", e),
+        }),
+    )
+}
+
+fn bad_request(error: String) -> (StatusCode, Json<ErrorResponse>) {
+    (StatusCode::BAD_REQUEST, Json(ErrorResponse { error }))
+}
+
+fn not_found(id: Uuid) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::NOT_FOUND,
+        Json(ErrorResponse {
+            error: format!("Manifest destination with id {} not found", id),
+        }),
+    )
+}
+
+fn normalize(value: Option<&str>) -> Option<String> {
+    value.map(str::trim).filter(|v| !v.is_empty()).map(str::to_string)
+}
+
+/// Normalizovaný request připravený k uložení
+struct ValidatedDestination {
+    format: String,
+    path_template: String,
+    git_branch: Option<String>,
+    url: Option<String>,
+    s3_bucket: Option<String>,
+    s3_region: Option<String>,
+    s3_access_key: Option<String>,
+    secret_encrypted: Option<String>,
+}
+
+/// Validace requestu podle druhu cíle. `has_stored_secret` = update cíle, který už secret má.
+async fn validate_request(
+    state: &DeployApiState,
+    tenant_id: Uuid,
+    payload: &ManifestDestinationRequest,
+    has_stored_secret: bool,
+) -> Result<ValidatedDestination, (StatusCode, Json<ErrorResponse>)> {
+    if payload.name.trim().is_empty() {
+        return Err(bad_request("Destination name cannot be empty".to_string()));
+    }
+    let format = payload.format.as_deref().map(str::trim).unwrap_or(FORMAT_YAML).to_string();
+    if format != FORMAT_YAML && format != FORMAT_JSON {
+        return Err(bad_request(format!(
+            "Invalid format '{}', expected '{}' or '{}'",
+            format, FORMAT_YAML, FORMAT_JSON
+        )));
+    }
+    let path_template = normalize(payload.path_template.as_deref()).unwrap_or_else(|| DEFAULT_PATH_TEMPLATE.to_string());
+    manifest_delivery::validate_path_template(&path_template).map_err(bad_request)?;
+
+    if let Some(bundle_id) = payload.bundle_id {
+        let exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM bundles WHERE id = $1 AND tenant_id = $2)")
+            .bind(bundle_id)
+            .bind(tenant_id)
+            .fetch_one(&state.pool)
+            .await
+            .map_err(db_error)?;
+        if !exists {
+            return Err(bad_request(format!("Bundle {} does not belong to the tenant", bundle_id)));
+        }
+    }
+
+    let url = normalize(payload.url.as_deref());
+    let secret = normalize(payload.secret.as_deref());
+    let has_secret = secret.is_some() || has_stored_secret;
+    let mut validated = ValidatedDestination {
+        format,
+        path_template,
+        git_branch: None,
+        url: None,
+        s3_bucket: None,
+        s3_region: None,
+        s3_access_key: None,
+        secret_encrypted: None,
+    };
+
+    match payload.kind.as_str() {
+        KIND_GIT => {
+            let Some(repo_id) = payload.git_repository_id else {
+                return Err(bad_request("Git destination needs git_repository_id".to_string()));
+            };
+            let exists = sqlx::query_scalar::<_, bool>(
+                "SELECT EXISTS(SELECT 1 FROM git_repositories WHERE id = $1 AND tenant_id = $2)",
+            )
+            .bind(repo_id)
+            .bind(tenant_id)
+            .fetch_one(&state.pool)
+            .await
+            .map_err(db_error)?;
+            if !exists {
+                return Err(bad_request(format!("Git repository {} does not belong to the tenant", repo_id)));
+            }
+            validated.git_branch = normalize(payload.git_branch.as_deref());
+            return Ok(validated);
+        }
+        KIND_S3 => {
+            let bucket = normalize(payload.s3_bucket.as_deref());
+            let access_key = normalize(payload.s3_access_key.as_deref());
+            if url.is_some() {
+                if bucket.is_none() || access_key.is_none() || !has_secret {
+                    return Err(bad_request(
+                        "S3 destination with an endpoint url needs s3_bucket, s3_access_key and secret".to_string(),
+                    ));
+                }
+            } else if !state.object_storage.is_enabled() {
+                return Err(bad_request(
+                    "S3 destination without an endpoint url needs the global object storage (OBJECT_STORAGE_*)".to_string(),
+                ));
+            }
+            validated.url = url;
+            validated.s3_bucket = bucket;
+            validated.s3_region = normalize(payload.s3_region.as_deref());
+            validated.s3_access_key = access_key;
+        }
+        KIND_HTTP => {
+            let Some(url) = url else {
+                return Err(bad_request("HTTP destination needs url".to_string()));
+            };
+            match url::Url::parse(&url) {
+                Ok(parsed) if parsed.scheme() == "http" || parsed.scheme() == "https" => {}
+                _ => return Err(bad_request(format!("Invalid HTTP url '{}'", url))),
+            }
+            validated.url = Some(url);
+        }
+        other => {
+            return Err(bad_request(format!(
+                "Invalid kind '{}', expected '{}', '{}' or '{}'",
+                other, KIND_GIT, KIND_S3, KIND_HTTP
+            )));
+        }
+    }
+
+    if let Some(secret) = secret {
+        validated.secret_encrypted = Some(crypto::encrypt(&secret, &state.encryption_secret).map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Failed to encrypt destination secret: {}", e),
+                }),
+            )
+        })?);
+    }
+    Ok(validated)
+}
+
+/// GET /api/v1/tenants/{tenant_id}/manifest-destinations - Seznam cílů doručení tenanta
+async fn list_manifest_destinations(
+    State(state): State<DeployApiState>,
+    Path(tenant_id): Path<Uuid>,
+) -> Result<Json<Vec<ManifestDestination>>, (StatusCode, Json<ErrorResponse>)> {
+    let destinations = sqlx::query_as::<_, ManifestDestination>(
+        "SELECT * FROM manifest_destinations WHERE tenant_id = $1 ORDER BY name",
+    )
+    .bind(tenant_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(destinations))
+}
+
+/// GET /api/v1/manifest-destinations/{id} - Detail cíle doručení
+async fn get_manifest_destination(
+    State(state): State<DeployApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ManifestDestination>, (StatusCode, Json<ErrorResponse>)> {
+    sqlx::query_as::<_, ManifestDestination>("SELECT * FROM manifest_destinations WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(db_error)?
+        .map(Json)
+        .ok_or_else(|| not_found(id))
+}
+
+/// POST /api/v1/manifest-destinations - Vytvoření cíle doručení.
+/// Doručují se releases vytvořené od tohoto okamžiku; starší jde doručit přes `/releases/{id}/deliveries/{destination_id}`.
+async fn create_manifest_destination(
+    State(state): State<DeployApiState>,
+    Json(payload): Json<CreateManifestDestinationRequest>,
+) -> Result<(StatusCode, Json<ManifestDestination>), (StatusCode, Json<ErrorResponse>)> {
+    let tenant_exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM tenants WHERE id = $1)")
+        .bind(payload.tenant_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(db_error)?;
+    if !tenant_exists {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Tenant with id {} not found", payload.tenant_id),
+            }),
+        ));
+    }
+
+    let destination = &payload.destination;
+    let validated = validate_request(&state, payload.tenant_id, destination, false).await?;
+
+    let created = sqlx::query_as::<_, ManifestDestination>(
+        "INSERT INTO manifest_destinations
+         (tenant_id, name, kind, bundle_id, format, path_template, git_repository_id, git_branch,
+          url, s3_bucket, s3_region, s3_access_key, s3_path_style, secret_encrypted, is_active)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+         RETURNING *",
+    )
+    .bind(payload.tenant_id)
+    .bind(destination.name.trim())
+    .bind(&destination.kind)
+    .bind(destination.bundle_id)
+    .bind(&validated.format)
+    .bind(&validated.path_template)
+    .bind(destination.git_repository_id.filter(|_| destination.kind == KIND_GIT))
+    .bind(&validated.git_branch)
+    .bind(&validated.url)
+    .bind(&validated.s3_bucket)
+    .bind(&validated.s3_region)
+    .bind(&validated.s3_access_key)
+    .bind(destination.s3_path_style.unwrap_or(false))
+    .bind(&validated.secret_encrypted)
+    .bind(destination.is_active.unwrap_or(true))
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|e| match &e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: format!("Manifest destination '{}' already exists", destination.name.trim()),
+            }),
+        ),
+        _ => db_error(e),
+    })?;
+
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// PUT /api/v1/manifest-destinations/{id} - Update cíle doručení
+async fn update_manifest_destination(
+    State(state): State<DeployApiState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ManifestDestinationRequest>,
+) -> Result<Json<ManifestDestination>, (StatusCode, Json<ErrorResponse>)> {
+    let existing = sqlx::query_as::<_, ManifestDestination>("SELECT * FROM manifest_destinations WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| not_found(id))?;
+
+    let has_stored_secret = existing.secret_encrypted.is_some() && existing.kind == payload.kind;
+    let validated = validate_request(&state, existing.tenant_id, &payload, has_stored_secret).await?;
+
+    sqlx::query_as::<_, ManifestDestination>(
+        "UPDATE manifest_destinations
+         SET name = $1, kind = $2, bundle_id = $3, format = $4, path_template = $5,
+             git_repository_id = $6, git_branch = $7, url = $8, s3_bucket = $9, s3_region = $10,
+             s3_access_key = $11, s3_path_style = COALESCE($12, s3_path_style),
+             secret_encrypted = CASE WHEN kind = $2 THEN COALESCE($13, secret_encrypted) ELSE $13 END,
+             is_active = COALESCE($14, is_active), updated_at = NOW()
+         WHERE id = $15
+         RETURNING *",
+    )
+    .bind(payload.name.trim())
+    .bind(&payload.kind)
+    .bind(payload.bundle_id)
+    .bind(&validated.format)
+    .bind(&validated.path_template)
+    .bind(payload.git_repository_id.filter(|_| payload.kind == KIND_GIT))
+    .bind(&validated.git_branch)
+    .bind(&validated.url)
+    .bind(&validated.s3_bucket)
+    .bind(&validated.s3_region)
+    .bind(&validated.s3_access_key)
+    .bind(payload.s3_path_style)
+    .bind(&validated.secret_encrypted)
+    .bind(payload.is_active)
+    .bind(id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(db_error)?
+    .map(Json)
+    .ok_or_else(|| not_found(id))
+}
+
+/// DELETE /api/v1/manifest-destinations/{id} - Smazání cíle doručení (včetně historie doručení)
+async fn delete_manifest_destination(
+    State(state): State<DeployApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    let result = sqlx::query("DELETE FROM manifest_destinations WHERE id = $1")
+        .bind(id)
+        .execute(&state.pool)
+        .await
+        .map_err(db_error)?;
+
+    if result.rows_affected() == 0 {
+        return Err(not_found(id));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+const DELIVERY_SELECT: &str = r#"
+    SELECT d.release_id, d.destination_id, md.name AS destination_name, md.kind,
+           d.status, d.attempts, d.location, d.error_message,
+           d.created_at, d.updated_at, d.delivered_at
+    FROM release_manifest_deliveries d
+    JOIN manifest_destinations md ON md.id = d.destination_id
+"#;
+
+/// GET /api/v1/releases/{id}/deliveries - Stav doručení manifestu release do jednotlivých cílů
+async fn list_release_deliveries(
+    State(state): State<DeployApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<ManifestDelivery>>, (StatusCode, Json<ErrorResponse>)> {
+    let deliveries = sqlx::query_as::<_, ManifestDelivery>(&format!(
+        "{} WHERE d.release_id = $1 ORDER BY md.name",
+        DELIVERY_SELECT
+    ))
+    .bind(id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(deliveries))
+}
+
+/// POST /api/v1/releases/{id}/deliveries/{destination_id} - Zařadí (znovu) doručení manifestu do cíle.
+/// Funguje i pro releases starší než cíl; počítadlo pokusů se vynuluje.
+async fn queue_release_delivery(
+    State(state): State<DeployApiState>,
+    Path((id, destination_id)): Path<(Uuid, Uuid)>,
+) -> Result<(StatusCode, Json<ManifestDelivery>), (StatusCode, Json<ErrorResponse>)> {
+    let destination_active = sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT md.is_active
+        FROM manifest_destinations md
+        JOIN releases r ON r.id = $1
+        JOIN copy_jobs cj ON cj.id = r.copy_job_id
+        JOIN bundle_versions bv ON bv.id = cj.bundle_version_id
+        JOIN bundles b ON b.id = bv.bundle_id
+        WHERE md.id = $2 AND md.tenant_id = b.tenant_id
+        "#,
+    )
+    .bind(id)
+    .bind(destination_id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(db_error)?
+    .ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Release {} or its tenant's manifest destination {} not found", id, destination_id),
+            }),
+        )
+    })?;
+    if !destination_active {
+        return Err(bad_request(format!("Manifest destination {} is not active", destination_id)));
+    }
+
+    sqlx::query(
+        "INSERT INTO release_manifest_deliveries (release_id, destination_id)
+         VALUES ($1, $2)
+         ON CONFLICT (release_id, destination_id) DO UPDATE
+         SET status = 'pending', attempts = 0, error_message = NULL, updated_at = NOW()",
+    )
+    .bind(id)
+    .bind(destination_id)
+    .execute(&state.pool)
+    .await
+    .map_err(db_error)?;
+
+    let delivery = sqlx::query_as::<_, ManifestDelivery>(&format!(
+        "{} WHERE d.release_id = $1 AND d.destination_id = $2",
+        DELIVERY_SELECT
+    ))
+    .bind(id)
+    .bind(destination_id)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(db_error)?;
+
+    Ok((StatusCode::ACCEPTED, Json(delivery)))
+}
+
+/// Spustí periodické doručování release manifestů (interval 0 = vypnuto)
+pub fn spawn_delivery_task(state: DeployApiState, interval: Duration, max_attempts: u32) {
+    if interval.is_zero() {
+        info!("Release manifest delivery disabled");
+        return;
+    }
+
+    info!(
+        "Release manifest delivery every {}s (max {} attempts)",
+        interval.as_secs(),
+        max_attempts
+    );
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            match run_deliveries(&state, max_attempts).await {
+                Ok(result) if result.delivered > 0 || result.failed > 0 => {
+                    info!(
+                        delivered = result.delivered,
+                        failed = result.failed,
+                        "Release manifest deliveries processed"
+                    );
+                }
+                Ok(_) => {}
+                Err(e) => warn!(error = %e, "Release manifest delivery run failed"),
+            }
+        }
+    });
+}
+
+/// Založí doručení pro nové releases a zpracuje čekající. Selhaná se opakují s exponenciálním
+/// odstupem (1, 2, 4… minut, max. hodina) do `max_attempts` pokusů. Claim přes `SKIP LOCKED`,
+/// takže více procesů nedoručí stejný manifest dvakrát.
+pub async fn run_deliveries(state: &DeployApiState, max_attempts: u32) -> Result<DeliveryRunResult, sqlx::Error> {
+    let enqueued = sqlx::query(
+        r#"
+        INSERT INTO release_manifest_deliveries (release_id, destination_id)
+        SELECT r.id, md.id
+        FROM manifest_destinations md
+        JOIN bundles b ON b.tenant_id = md.tenant_id AND (md.bundle_id IS NULL OR md.bundle_id = b.id)
+        JOIN bundle_versions bv ON bv.bundle_id = b.id
+        JOIN copy_jobs cj ON cj.bundle_version_id = bv.id
+        JOIN releases r ON r.copy_job_id = cj.id
+        WHERE md.is_active AND r.created_at >= md.created_at
+        ON CONFLICT DO NOTHING
+        "#,
+    )
+    .execute(&state.pool)
+    .await?
+    .rows_affected();
+
+    let claimed = sqlx::query_as::<_, ClaimedDelivery>(
+        r#"
+        UPDATE release_manifest_deliveries d
+        SET status = 'in_progress', attempts = d.attempts + 1, updated_at = NOW()
+        FROM (
+            SELECT rd.release_id, rd.destination_id
+            FROM release_manifest_deliveries rd
+            JOIN manifest_destinations md ON md.id = rd.destination_id AND md.is_active
+            WHERE rd.status = 'pending'
+               OR (rd.status = 'failed' AND rd.attempts < $1
+                   AND rd.updated_at < NOW() - make_interval(secs => LEAST(60 * power(2, rd.attempts - 1), 3600)))
+               OR (rd.status = 'in_progress' AND rd.updated_at < NOW() - make_interval(mins => $2))
+            ORDER BY rd.created_at
+            LIMIT $3
+            FOR UPDATE OF rd SKIP LOCKED
+        ) c
+        WHERE d.release_id = c.release_id AND d.destination_id = c.destination_id
+        RETURNING d.release_id, d.destination_id
+        "#,
+    )
+    .bind(max_attempts as i32)
+    .bind(STALE_DELIVERY_MINUTES)
+    .bind(DELIVERY_BATCH_SIZE)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let mut result = DeliveryRunResult {
+        enqueued,
+        delivered: 0,
+        failed: 0,
+    };
+    for claim in claimed {
+        match deliver(state, claim.release_id, claim.destination_id).await {
+            Ok(location) => {
+                sqlx::query(
+                    "UPDATE release_manifest_deliveries
+                     SET status = 'success', location = $3, error_message = NULL,
+                         updated_at = NOW(), delivered_at = NOW()
+                     WHERE release_id = $1 AND destination_id = $2",
+                )
+                .bind(claim.release_id)
+                .bind(claim.destination_id)
+                .bind(&location)
+                .execute(&state.pool)
+                .await?;
+                result.delivered += 1;
+            }
+            Err(e) => {
+                warn!(
+                    release_id = %claim.release_id,
+                    destination_id = %claim.destination_id,
+                    error = %e,
+                    "Release manifest delivery failed"
+                );
+                sqlx::query(
+                    "UPDATE release_manifest_deliveries
+                     SET status = 'failed', error_message = $3, updated_at = NOW()
+                     WHERE release_id = $1 AND destination_id = $2",
+                )
+                .bind(claim.release_id)
+                .bind(claim.destination_id)
+                .bind(format!("{:#}", e))
+                .execute(&state.pool)
+                .await?;
+                result.failed += 1;
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Doručí manifest release do cíle; vrací umístění (commit SHA / klíč objektu / HTTP status)
+async fn deliver(state: &DeployApiState, release_uuid: Uuid, destination_id: Uuid) -> anyhow::Result<String> {
+    let destination = sqlx::query_as::<_, ManifestDestination>("SELECT * FROM manifest_destinations WHERE id = $1")
+        .bind(destination_id)
+        .fetch_one(&state.pool)
+        .await?;
+    let release = sqlx::query_as::<_, DeliveryReleaseRow>(
+        r#"
+        SELECT r.release_id, t.slug AS tenant_slug, b.name AS bundle_name
+        FROM releases r
+        JOIN copy_jobs cj ON cj.id = r.copy_job_id
+        JOIN bundle_versions bv ON bv.id = cj.bundle_version_id
+        JOIN bundles b ON b.id = bv.bundle_id
+        JOIN tenants t ON t.id = b.tenant_id
+        WHERE r.id = $1
+        "#,
+    )
+    .bind(release_uuid)
+    .fetch_one(&state.pool)
+    .await?;
+
+    let manifest = load_release_manifest(&state.pool, release_uuid).await?;
+    let (body, content_type) = manifest_delivery::serialize_manifest(&manifest, &destination.format)?;
+    let release_uuid = release_uuid.to_string();
+    let path = manifest_delivery::render_path(
+        &destination.path_template,
+        &PathVars {
+            tenant: &release.tenant_slug,
+            bundle: &release.bundle_name,
+            release_id: &release.release_id,
+            release_uuid: &release_uuid,
+            format: &destination.format,
+        },
+    );
+    let secret = destination
+        .secret_encrypted
+        .as_deref()
+        .map(|encrypted| crypto::decrypt(encrypted, &state.encryption_secret))
+        .transpose()
+        .context("Failed to decrypt destination secret")?;
+
+    match destination.kind.as_str() {
+        KIND_GIT => deliver_git(state, &destination, &path, &body, &release.release_id).await,
+        KIND_S3 => {
+            let storage = match destination.url.as_deref() {
+                Some(endpoint) => ObjectStorage::new(Some(ObjectStorageConfig {
+                    endpoint: endpoint.to_string(),
+                    bucket: destination.s3_bucket.clone().unwrap_or_default(),
+                    region: destination.s3_region.clone().unwrap_or_else(|| "us-east-1".to_string()),
+                    access_key: destination.s3_access_key.clone().unwrap_or_default(),
+                    secret_key: secret.unwrap_or_default(),
+                    path_style: destination.s3_path_style,
+                    ..Default::default()
+                })),
+                None => state.object_storage.clone(),
+            };
+            storage.put(&path, body, content_type).await?;
+            Ok(path)
+        }
+        KIND_HTTP => {
+            let url = destination.url.as_deref().context("HTTP destination has no url")?;
+            let client = reqwest::Client::builder().timeout(Duration::from_secs(30)).build()?;
+            let mut request = client
+                .post(url)
+                .header(reqwest::header::CONTENT_TYPE, content_type)
+                .header("X-SRM-Release-Id", &release.release_id)
+                .header("X-SRM-Manifest-Path", &path)
+                .body(body);
+            if let Some(token) = secret {
+                request = request.bearer_auth(token);
+            }
+            let response = request.send().await.with_context(|| format!("POST {} failed", url))?;
+            let status = response.status();
+            if !status.is_success() {
+                anyhow::bail!("POST {} returned {}", url, status);
+            }
+            Ok(format!("HTTP {}", status.as_u16()))
+        }
+        other => anyhow::bail!("Unknown destination kind '{}'", other),
+    }
+}
+
+/// Commit manifestu do git repozitáře; vrací SHA commitu
+async fn deliver_git(
+    state: &DeployApiState,
+    destination: &ManifestDestination,
+    path: &str,
+    body: &[u8],
+    release_id: &str,
+) -> anyhow::Result<String> {
+    let repo_id = destination.git_repository_id.context("Git destination has no repository")?;
+    let repo = sqlx::query_as::<_, GitRepository>("SELECT * FROM git_repositories WHERE id = $1")
+        .bind(repo_id)
+        .fetch_one(&state.pool)
+        .await?;
+    let branch = destination
+        .git_branch
+        .as_deref()
+        .filter(|v| !v.trim().is_empty())
+        .unwrap_or(&repo.default_branch);
+
+    let temp_dir = tempfile::Builder::new().prefix("srm-manifest-").tempdir()?;
+    let repo_path = temp_dir.path().join("repo");
+    let git_env: HashMap<String, String> = deploy::build_git_env_for_repo(state, &repo, temp_dir.path())?;
+    // Výstup gitu nikdo nečte, chyba nese první řádek stderr
+    let (log_tx, _) = broadcast::channel::<String>(16);
+
+    deploy::hand_over_workspace(&state.sandbox, temp_dir.path(), &log_tx)?;
+    deploy::run_git_clone(&state.sandbox, &repo.repo_url, branch, &repo_path, &git_env, &log_tx).await?;
+
+    let file_path = repo_path.join(path);
+    if let Some(parent) = file_path.parent() {
+        std::fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    std::fs::write(&file_path, body).with_context(|| format!("Failed to write {}", file_path.display()))?;
+    deploy::hand_over_workspace(&state.sandbox, temp_dir.path(), &log_tx)?;
+
+    let git = state.sandbox.program(SandboxTool::Git, "git");
+    let cwd = Some(repo_path.as_path());
+    deploy::run_command_logged(git, &["config", "user.name", "simple-release-management"], cwd, &git_env, &log_tx, "git config").await?;
+    deploy::run_command_logged(git, &["config", "user.email", "release-management@local"], cwd, &git_env, &log_tx, "git config").await?;
+    deploy::run_command_logged(git, &["add", path], cwd, &git_env, &log_tx, "git add").await?;
+    let message = format!("manifest {}", release_id);
+    deploy::run_command_logged(git, &["commit", "--allow-empty", "-m", &message], cwd, &git_env, &log_tx, "git commit").await?;
+    if let (Some(token), Some(username)) = (git_env.get("SRM_GIT_TOKEN"), git_env.get("SRM_GIT_USERNAME")) {
+        let authed = deploy::inject_http_auth(&repo.repo_url, username, token)?;
+        deploy::run_command_logged(git, &["remote", "set-url", "origin", &authed], cwd, &git_env, &log_tx, "git remote set-url")
+            .await?;
+    }
+    deploy::run_command_logged(git, &["push", "origin", branch], cwd, &git_env, &log_tx, "git push").await?;
+
+    deploy::get_git_head_sha(&state.sandbox, &repo_path, &git_env).await
+}
+
@@ -1,13 +1,33 @@
+pub mod agents;
 pub mod bundles;
 pub mod auth;
+pub mod base_images;
+pub mod bulk;
 pub mod copy;
+pub mod credentials;
+pub mod dashboard;
 pub mod deploy;
+pub mod env_templates;
+pub mod environment_health;
+pub mod error;
+pub mod events;
+pub mod fieldsets;
+pub mod file_templates;
 pub mod git_repos;
+pub mod history;
+pub mod image_policies;
+pub mod jobs;
 pub mod argocd;
 pub mod kubernetes;
+pub mod manifest_destinations;
+pub mod pipelines;
+pub mod pull_secrets;
 pub mod registries;
 pub mod releases;
+pub mod share_links;
 pub mod tenants;
+pub mod tool_paths;
+pub mod v2;
 
 use axum::{routing::get, Json, Router};
 use serde::Serialize;
@@ -16,16 +36,27 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+use crate::services::notifications::Notifier;
+use crate::services::object_storage::ObjectStorage;
+
 /// Vytvoří router s všemi API endpointy
 pub fn create_api_router(
     pool: PgPool,
     encryption_secret: String,
     image_tool: String,
     image_tool_path: String,
+    credential_expiry_warn_days: i64,
+    notifier: Notifier,
+    object_storage: ObjectStorage,
 ) -> Router {
     let registry_state = registries::RegistryApiState {
         pool: pool.clone(),
         encryption_secret,
+        credential_expiry_warn_days,
+    };
+    let bulk_state = bulk::BulkApiState {
+        pool: pool.clone(),
+        encryption_secret: registry_state.encryption_secret.clone(),
     };
     let version_response = VersionResponse {
         version: env!("CARGO_PKG_VERSION"),
@@ -36,6 +67,7 @@ pub fn create_api_router(
     let git_repo_state = git_repos::GitRepoApiState {
         pool: pool.clone(),
         encryption_secret: registry_state.encryption_secret.clone(),
+        credential_expiry_warn_days,
     };
     let argocd_state = argocd::ArgocdApiState {
         pool: pool.clone(),
@@ -76,10 +108,28 @@ pub fn create_api_router(
         .nest("/tenants", tenants::router(pool.clone()))
         .merge(registries::router(registry_state))
         .merge(git_repos::router(git_repo_state))
+        .merge(credentials::router(credentials::CredentialApiState {
+            pool: pool.clone(),
+            warn_days: credential_expiry_warn_days,
+        }))
+        .merge(environment_health::router(environment_health::EnvironmentHealthApiState {
+            pool: pool.clone(),
+            warn_days: credential_expiry_warn_days,
+        }))
         .merge(argocd::router(argocd_state))
         .merge(kubernetes::router(kubernetes_state))
         .merge(bundles::router(pool.clone()))
-        .merge(releases::router(pool.clone()))
+        .merge(releases::router(releases::ReleaseApiState {
+            pool: pool.clone(),
+            notifier,
+            object_storage,
+        }))
+        .merge(history::router(pool.clone()))
+        .merge(image_policies::router(pool.clone()))
+        .merge(dashboard::router(pool.clone()))
+        .merge(jobs::router(pool.clone()))
+        .merge(events::router())
+        .merge(bulk::router(bulk_state))
         .route(
             "/version",
             get({
@@ -88,7 +138,9 @@ pub fn create_api_router(
             }),
         );
 
-    Router::new().nest("/api/v1", api_v1)
+    Router::new()
+        .nest("/api/v1", api_v1)
+        .nest("/api/v2", v2::router(pool))
 }
 
 #[derive(Clone, Serialize)]
@@ -0,0 +1,384 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use serde::Serialize;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::api::{copy, deploy, releases};
+use crate::auth::{AuthContext, Role};
+use crate::db::models::PipelineRun;
+use crate::services::{lifecycle_notifications, release_channels};
+
+/// Interval, ve kterém driver kontroluje stav copy/deploy jobů a obnovuje heartbeat
+const PIPELINE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Běžící run bez heartbeatu déle než tato doba patří spadlé instanci
+const PIPELINE_ORPHAN_AFTER: Duration = Duration::from_secs(30);
+
+/// App state pro pipeline API - orchestruje copy + release + deploy
+#[derive(Clone)]
+pub struct PipelineApiState {
+    pub copy: copy::CopyApiState,
+    pub deploy: deploy::DeployApiState,
+    /// Nejdelší čekání na dokončení jednoho copy/deploy jobu (`PIPELINE_STEP_TIMEOUT_SECONDS`)
+    pub step_timeout: Duration,
+}
+
+pub use srm_api_types::pipelines::TriggerPipelineRequest;
+
+/// Response s chybou
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+/// Vytvoří router pro pipeline endpoints
+pub fn router(state: PipelineApiState) -> Router {
+    Router::new()
+        .route("/pipelines/trigger", post(trigger_pipeline))
+        .route("/pipelines/{id}", get(get_pipeline))
+        .with_state(state)
+}
+
+fn error(status: StatusCode, message: impl Into<String>) -> (StatusCode, Json<ErrorResponse>) {
+    (status, Json(ErrorResponse { error: message.into() }))
+}
+
+fn db_error(e: sqlx::Error) -> (StatusCode, Json<ErrorResponse>) {
+    error(StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e))
+}
+
+/// POST /api/v1/pipelines/trigger - Spustí copy -> release -> (deploy) a vrátí pipeline handle
+async fn trigger_pipeline(
+    Extension(auth): Extension<AuthContext>,
+    State(state): State<PipelineApiState>,
+    Json(payload): Json<TriggerPipelineRequest>,
+) -> Result<(StatusCode, Json<PipelineRun>), (StatusCode, Json<ErrorResponse>)> {
+    let release_id = payload.release_id.trim().to_string();
+    if release_id.is_empty() {
+        return Err(error(StatusCode::BAD_REQUEST, "release_id cannot be empty"));
+    }
+    if payload.environment_ids.is_empty() {
+        return Err(error(StatusCode::BAD_REQUEST, "environment_ids cannot be empty"));
+    }
+    let deploy = payload.deploy.unwrap_or(false);
+    let channel = release_channels::parse_optional(payload.channel.as_deref())
+        .map_err(|e| error(StatusCode::BAD_REQUEST, e))?
+        .unwrap_or(release_channels::DEFAULT_CHANNEL);
+    if deploy && !auth.roles.iter().any(|r| matches!(r, Role::Admin | Role::DeployManager)) {
+        return Err(error(StatusCode::FORBIDDEN, "Deploy requires the deploy manager role"));
+    }
+
+    let pool = &state.copy.pool;
+    let tenant_id = sqlx::query_scalar::<_, Uuid>("SELECT tenant_id FROM bundles WHERE id = $1")
+        .bind(payload.bundle_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| error(StatusCode::NOT_FOUND, "Bundle not found"))?;
+    if !auth.is_tenant_allowed(tenant_id) {
+        return Err(error(StatusCode::FORBIDDEN, "Tenant access denied"));
+    }
+
+    let env_count = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM environments WHERE id = ANY($1) AND tenant_id = $2",
+    )
+    .bind(&payload.environment_ids)
+    .bind(tenant_id)
+    .fetch_one(pool)
+    .await
+    .map_err(db_error)?;
+    let mut unique_envs = payload.environment_ids.clone();
+    unique_envs.sort();
+    unique_envs.dedup();
+    if env_count as usize != unique_envs.len() || unique_envs.len() != payload.environment_ids.len() {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "environment_ids must be distinct environments of the bundle's tenant",
+        ));
+    }
+    // Chain by jinak spadl až po copy + release na prvním prostředí, které kanál neodebírá
+    if deploy {
+        let unsubscribed = sqlx::query_scalar::<_, String>(
+            "SELECT slug FROM environments WHERE id = ANY($1) AND NOT ($2 = ANY(release_channels)) ORDER BY slug",
+        )
+        .bind(&payload.environment_ids)
+        .bind(channel)
+        .fetch_all(pool)
+        .await
+        .map_err(db_error)?;
+        if !unsubscribed.is_empty() {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "Environments {} do not subscribe to release channel '{}'",
+                    unsubscribed.join(", "),
+                    channel
+                ),
+            ));
+        }
+    }
+
+    let run = sqlx::query_as::<_, PipelineRun>(
+        "INSERT INTO pipeline_runs
+            (tenant_id, bundle_id, bundle_version, environment_ids, release_id, target_tag, deploy, dry_run, created_by)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+         RETURNING *",
+    )
+    .bind(tenant_id)
+    .bind(payload.bundle_id)
+    .bind(payload.version)
+    .bind(&payload.environment_ids)
+    .bind(&release_id)
+    .bind(&payload.target_tag)
+    .bind(deploy)
+    .bind(payload.dry_run.unwrap_or(false))
+    .bind(&auth.username)
+    .fetch_one(pool)
+    .await
+    .map_err(db_error)?;
+
+    let driver_run = run.clone();
+    tokio::spawn(async move {
+        let run_id = driver_run.id;
+        let pool = state.copy.pool.clone();
+        let notifier = state.copy.notifier.clone();
+        let heartbeat = tokio::spawn(heartbeat_loop(pool.clone(), run_id));
+        let result = drive_pipeline(state, auth, driver_run, payload.notes, channel).await;
+        heartbeat.abort();
+        if let Err(message) = result {
+            tracing::warn!(pipeline_id = %run_id, error = %message, "Pipeline failed");
+            let _ = sqlx::query(
+                "UPDATE pipeline_runs
+                 SET status = 'failed', error_message = $2, updated_at = now(), completed_at = now()
+                 WHERE id = $1",
+            )
+            .bind(run_id)
+            .bind(&message)
+            .execute(&pool)
+            .await;
+        }
+        lifecycle_notifications::pipeline_finished(&pool, &notifier, run_id).await;
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(run)))
+}
+
+/// GET /api/v1/pipelines/{id} - Stav pipeline (agregovaný status + ID jobů)
+async fn get_pipeline(
+    State(state): State<PipelineApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<PipelineRun>, (StatusCode, Json<ErrorResponse>)> {
+    sqlx::query_as::<_, PipelineRun>("SELECT * FROM pipeline_runs WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.copy.pool)
+        .await
+        .map_err(db_error)?
+        .map(Json)
+        .ok_or_else(|| error(StatusCode::NOT_FOUND, format!("Pipeline {} not found", id)))
+}
+
+/// Provede jednotlivé kroky pipeline; chyba = zpráva pro `error_message`
+async fn drive_pipeline(
+    state: PipelineApiState,
+    auth: AuthContext,
+    run: PipelineRun,
+    notes: Option<String>,
+    channel: &str,
+) -> Result<(), String> {
+    let pool = state.copy.pool.clone();
+
+    // 1) Copy job
+    let (_, Json(created)) = copy::copy_bundle_version(
+        State(state.copy.clone()),
+        Path((run.bundle_id, run.bundle_version)),
+        Json(copy::CopyBundleRequest {
+            target_tag: run.target_tag.clone(),
+            timezone_offset_minutes: None,
+            environment_id: run.environment_ids.first().copied(),
+            source_registry_id: None,
+            target_registry_id: None,
+            copy_options: None,
+            overwrite: None,
+        }),
+    )
+    .await
+    .map_err(|(_, Json(e))| format!("Copy job creation failed: {}", e.error))?;
+    let copy_job_id = created.job_id;
+    record_step(
+        &pool,
+        "UPDATE pipeline_runs SET copy_job_id = $2, updated_at = now() WHERE id = $1",
+        run.id,
+        copy_job_id,
+    )
+    .await?;
+
+    let _ = copy::start_copy_job(State(state.copy.clone()), Path(copy_job_id))
+        .await
+        .map_err(|(_, Json(e))| format!("Copy job start failed: {}", e.error))?;
+    let copy_status = wait_for_status(&pool, "copy_jobs", copy_job_id, state.step_timeout).await?;
+    if copy_status != "success" {
+        return Err(format!("Copy job {} finished with status {}", copy_job_id, copy_status));
+    }
+
+    // 2) Release
+    let (_, Json(release)) = releases::create_release_global(
+        Extension(auth.clone()),
+        State(pool.clone()),
+        State(state.copy.notifier.clone()),
+        Json(releases::CreateReleaseRequest {
+            copy_job_id,
+            release_id: run.release_id.clone(),
+            notes,
+            created_by: Some(auth.username.clone()),
+            source_ref_mode: None,
+            channel: Some(channel.to_string()),
+        }),
+    )
+    .await
+    .map_err(|(_, Json(e))| format!("Release creation failed: {}", e.error))?;
+    record_step(
+        &pool,
+        "UPDATE pipeline_runs SET stage = 'release', release_uuid = $2, updated_at = now() WHERE id = $1",
+        run.id,
+        release.id,
+    )
+    .await?;
+
+    // 3) Deploy - postupně přes environment chain, další prostředí až po úspěchu předchozího
+    if run.deploy {
+        for environment_id in &run.environment_ids {
+            let (_, Json(job)) = deploy::create_deploy_job(
+                Extension(auth.clone()),
+                State(state.deploy.clone()),
+                Json(deploy::CreateDeployJobRequest {
+                    release_id: release.id,
+                    environment_id: *environment_id,
+                    dry_run: Some(run.dry_run),
+                    release_image_url_mode: None,
+                    validate_only: None,
+                    env_overrides: None,
+                }),
+            )
+            .await
+            .map_err(|(_, Json(e))| format!("Deploy job creation failed: {}", e.error))?;
+            record_step(
+                &pool,
+                "UPDATE pipeline_runs
+                 SET stage = 'deploy', deploy_job_ids = array_append(deploy_job_ids, $2), updated_at = now()
+                 WHERE id = $1",
+                run.id,
+                job.job_id,
+            )
+            .await?;
+
+            let _ = deploy::start_deploy_job(State(state.deploy.clone()), Path(job.job_id))
+                .await
+                .map_err(|(_, Json(e))| format!("Deploy job start failed: {}", e.error))?;
+            let deploy_status = wait_for_status(&pool, "deploy_jobs", job.job_id, state.step_timeout).await?;
+            if deploy_status != "success" {
+                return Err(format!("Deploy job {} finished with status {}", job.job_id, deploy_status));
+            }
+        }
+    }
+
+    sqlx::query(
+        "UPDATE pipeline_runs
+         SET status = 'success', stage = 'done', updated_at = now(), completed_at = now()
+         WHERE id = $1",
+    )
+    .bind(run.id)
+    .execute(&pool)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+    Ok(())
+}
+
+/// Uloží ID objektu vytvořeného v kroku pipeline
+async fn record_step(pool: &sqlx::PgPool, sql: &str, run_id: Uuid, value: Uuid) -> Result<(), String> {
+    sqlx::query(sql)
+        .bind(run_id)
+        .bind(value)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+    Ok(())
+}
+
+/// Čeká na terminální stav copy/deploy jobu, nejdéle `timeout`
+async fn wait_for_status(pool: &sqlx::PgPool, table: &str, job_id: Uuid, timeout: Duration) -> Result<String, String> {
+    // `table` je vždy konstanta (copy_jobs / deploy_jobs)
+    let sql = format!("SELECT status FROM {} WHERE id = $1", table);
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        let status = sqlx::query_scalar::<_, String>(&sql)
+            .bind(job_id)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        if matches!(status.as_str(), "success" | "failed" | "cancelled") {
+            return Ok(status);
+        }
+        if tokio::time::Instant::now() >= deadline {
+            let kind = if table == "copy_jobs" { "Copy job" } else { "Deploy job" };
+            return Err(format!(
+                "{} {} did not finish within {} s (status {})",
+                kind,
+                job_id,
+                timeout.as_secs(),
+                status
+            ));
+        }
+        tokio::time::sleep(PIPELINE_POLL_INTERVAL).await;
+    }
+}
+
+/// Heartbeat běžícího driveru; přeruší se po doběhnutí pipeline
+async fn heartbeat_loop(pool: sqlx::PgPool, run_id: Uuid) {
+    let mut interval = tokio::time::interval(PIPELINE_POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        let _ = sqlx::query("UPDATE pipeline_runs SET heartbeat_at = now() WHERE id = $1 AND status = 'running'")
+            .bind(run_id)
+            .execute(&pool)
+            .await;
+    }
+}
+
+/// Běžící runy, jejichž driver zmizel s restartem nebo pádem instance, označí jako failed
+pub async fn fail_orphaned_runs(state: &PipelineApiState) -> Result<usize, sqlx::Error> {
+    let pool = &state.copy.pool;
+    let orphaned = sqlx::query_scalar::<_, Uuid>(
+        "UPDATE pipeline_runs
+         SET status = 'failed',
+             error_message = 'Pipeline interrupted (server stopped while the pipeline was running)',
+             updated_at = now(), completed_at = now()
+         WHERE status = 'running' AND heartbeat_at < now() - make_interval(secs => $1)
+         RETURNING id",
+    )
+    .bind(PIPELINE_ORPHAN_AFTER.as_secs_f64())
+    .fetch_all(pool)
+    .await?;
+    for run_id in &orphaned {
+        tracing::warn!(pipeline_id = %run_id, "Pipeline driver lost, run marked as failed");
+        lifecycle_notifications::pipeline_finished(pool, &state.copy.notifier, *run_id).await;
+    }
+    Ok(orphaned.len())
+}
+
+/// Hned po startu a pak periodicky uklízí runy po spadlých instancích
+pub fn spawn_orphan_sweeper(state: PipelineApiState) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PIPELINE_ORPHAN_AFTER);
+        loop {
+            interval.tick().await;
+            if let Err(e) = fail_orphaned_runs(&state).await {
+                tracing::warn!("Failed to sweep orphaned pipelines: {}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,165 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::api::copy::{CopyApiState, ErrorResponse};
+use crate::auth::AuthContext;
+use crate::services::pull_secret::{self, ExternalSecretRef, PullSecretFormat};
+
+#[derive(Debug, Deserialize)]
+pub struct PullSecretQuery {
+    /// `secret` (výchozí) nebo `external-secret`
+    pub format: Option<String>,
+    /// Název Secretu (výchozí `<env slug>-pull-secret`)
+    pub name: Option<String>,
+    pub namespace: Option<String>,
+    /// ExternalSecret: název SecretStore / ClusterSecretStore
+    pub secret_store: Option<String>,
+    pub secret_store_kind: Option<String>,
+    /// ExternalSecret: klíč v secret store s obsahem `.dockerconfigjson`
+    pub remote_key: Option<String>,
+    pub remote_property: Option<String>,
+    pub refresh_interval: Option<String>,
+}
+
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+/// Vytvoří router pro generování image pull secretů
+pub fn router(state: CopyApiState) -> Router {
+    Router::new()
+        .route("/environments/{id}/pull-secret", get(get_pull_secret))
+        .with_state(state)
+}
+
+fn error(status: StatusCode, message: impl Into<String>) -> ApiError {
+    (status, Json(ErrorResponse { error: message.into() }))
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// GET /api/v1/environments/{id}/pull-secret - Kubernetes manifest pull secretu pro cílovou registry environmentu (YAML)
+async fn get_pull_secret(
+    Extension(auth): Extension<AuthContext>,
+    State(state): State<CopyApiState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<PullSecretQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let format = match non_empty(query.format) {
+        None => PullSecretFormat::Secret,
+        Some(value) => PullSecretFormat::parse(&value).ok_or_else(|| {
+            error(
+                StatusCode::BAD_REQUEST,
+                format!("Unknown format '{}' (allowed: {})", value, pull_secret::FORMATS.join(", ")),
+            )
+        })?,
+    };
+    // Secret nese credentials v čitelné podobě, ExternalSecret jen odkaz do secret store
+    if format == PullSecretFormat::Secret && !auth.is_admin() {
+        return Err(error(
+            StatusCode::FORBIDDEN,
+            "Only admins can generate a Secret with credentials, use format=external-secret",
+        ));
+    }
+
+    let environment = sqlx::query_as::<_, (String, Option<Uuid>)>(
+        "SELECT slug, target_registry_id FROM environments WHERE id = $1",
+    )
+    .bind(id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e)))?;
+    let Some((slug, target_registry_id)) = environment else {
+        return Err(error(StatusCode::NOT_FOUND, format!("Environment with id {} not found", id)));
+    };
+    let target_registry_id = target_registry_id
+        .ok_or_else(|| error(StatusCode::BAD_REQUEST, "Environment has no target registry"))?;
+
+    let name = non_empty(query.name).unwrap_or_else(|| format!("{}-pull-secret", slug));
+    let namespace = non_empty(query.namespace);
+    pull_secret::validate_name("name", &name)
+        .and_then(|_| namespace.as_deref().map_or(Ok(()), |ns| pull_secret::validate_name("namespace", ns)))
+        .map_err(|e| error(StatusCode::BAD_REQUEST, e))?;
+
+    let manifest = match format {
+        PullSecretFormat::Secret => {
+            let base_url = sqlx::query_scalar::<_, String>("SELECT base_url FROM registries WHERE id = $1")
+                .bind(target_registry_id)
+                .fetch_one(&state.pool)
+                .await
+                .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e)))?;
+            let (username, password) = state
+                .get_registry_credentials(target_registry_id, Some(id))
+                .await
+                .map_err(|e| {
+                    error(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        format!("Failed to load registry credentials: {}", e),
+                    )
+                })?;
+            let (Some(username), Some(password)) = (username, password) else {
+                return Err(error(
+                    StatusCode::BAD_REQUEST,
+                    "Target registry credentials need a username and a password or token",
+                ));
+            };
+            info!(
+                "Pull secret {} generated for environment {} by {}",
+                name, slug, auth.username
+            );
+            let config = pull_secret::docker_config_json(&base_url, &username, &password);
+            pull_secret::secret_manifest(&name, namespace.as_deref(), &config)
+        }
+        PullSecretFormat::ExternalSecret => {
+            let store_kind = non_empty(query.secret_store_kind).unwrap_or_else(|| "SecretStore".to_string());
+            if !pull_secret::STORE_KINDS.contains(&store_kind.as_str()) {
+                return Err(error(
+                    StatusCode::BAD_REQUEST,
+                    format!(
+                        "Unknown secret_store_kind '{}' (allowed: {})",
+                        store_kind,
+                        pull_secret::STORE_KINDS.join(", ")
+                    ),
+                ));
+            }
+            let (Some(store_name), Some(remote_key)) = (non_empty(query.secret_store), non_empty(query.remote_key))
+            else {
+                return Err(error(
+                    StatusCode::BAD_REQUEST,
+                    "format=external-secret requires secret_store and remote_key",
+                ));
+            };
+            let reference = ExternalSecretRef {
+                store_name,
+                store_kind,
+                remote_key,
+                remote_property: non_empty(query.remote_property),
+                refresh_interval: non_empty(query.refresh_interval).unwrap_or_else(|| "1h".to_string()),
+            };
+            pull_secret::external_secret_manifest(&name, namespace.as_deref(), &reference)
+        }
+    };
+
+    let yaml = serde_yaml_ng::to_string(&manifest).map_err(|e| {
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to serialize manifest: {}", e),
+        )
+    })?;
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "text/yaml; charset=utf-8"),
+            (header::CACHE_CONTROL, "no-store"),
+        ],
+        yaml,
+    ))
+}
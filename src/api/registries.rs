@@ -1,11 +1,12 @@
 #![allow(dead_code)]
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
-    routing::get,
+    routing::{get, post},
     Extension, Json, Router,
 };
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use uuid::Uuid;
@@ -13,12 +14,18 @@ use uuid::Uuid;
 use crate::auth::AuthContext;
 use crate::crypto;
 use crate::db::models::Registry;
-use crate::services::image_tool::SkopeoCredentials;
+use crate::services::change_history;
+use crate::services::credential_expiry::{self, ExpiryState};
+use crate::services::csv_import::{self, CsvImportQuery, CsvImportResult, CsvRowError};
+use crate::services::docker_config::{self, SkippedDockerAuth};
+use crate::services::image_tool::{self, CopyOptionsOverride, SkopeoCredentials};
 
 #[derive(Clone)]
 pub struct RegistryApiState {
     pub pool: PgPool,
     pub encryption_secret: String,
+    /// Okno "expiring soon" pro credentials (dny)
+    pub credential_expiry_warn_days: i64,
 }
 
 impl RegistryApiState {
@@ -161,9 +168,25 @@ pub struct CreateRegistryRequest {
     pub role: String,
     pub description: Option<String>,
     pub is_active: Option<bool>,
+    /// Kdy přestanou platit credentials; null = bez expirace
+    pub credentials_expire_at: Option<DateTime<Utc>>,
     pub environment_paths: Option<Vec<EnvironmentRegistryPathInput>>,
     pub environment_credentials: Option<Vec<EnvironmentRegistryCredentialInput>>,
     pub environment_access: Option<Vec<EnvironmentRegistryAccessInput>>,
+    /// Retry politika a copy flagy pro kopírování do této registry
+    pub copy_options: Option<CopyOptionsOverride>,
+}
+
+/// Validace a převod copy options pro uložení; prázdné přepsání = NULL
+fn copy_options_value(
+    copy_options: &CopyOptionsOverride,
+) -> Result<Option<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    image_tool::validate_copy_options(copy_options)
+        .map_err(|error| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })))?;
+    if copy_options.is_empty() {
+        return Ok(None);
+    }
+    Ok(serde_json::to_value(copy_options).ok())
 }
 
 /// Request pro update registry
@@ -181,9 +204,13 @@ pub struct UpdateRegistryRequest {
     pub role: String,
     pub description: Option<String>,
     pub is_active: Option<bool>,
+    /// Kdy přestanou platit credentials; null = bez expirace
+    pub credentials_expire_at: Option<DateTime<Utc>>,
     pub environment_paths: Option<Vec<EnvironmentRegistryPathInput>>,
     pub environment_credentials: Option<Vec<EnvironmentRegistryCredentialInput>>,
     pub environment_access: Option<Vec<EnvironmentRegistryAccessInput>>,
+    /// Retry politika a copy flagy pro kopírování do této registry
+    pub copy_options: Option<CopyOptionsOverride>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -200,6 +227,7 @@ pub struct EnvironmentRegistryCredentialInput {
     pub username: Option<String>,
     pub password: Option<String>,
     pub token: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -226,6 +254,10 @@ pub struct EnvironmentRegistryCredentialView {
     pub username: Option<String>,
     pub has_password: bool,
     pub has_token: bool,
+    pub expires_at: Option<DateTime<Utc>>,
+    #[sqlx(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiry: Option<ExpiryState>,
 }
 
 #[derive(Debug, Serialize, sqlx::FromRow)]
@@ -236,12 +268,39 @@ pub struct EnvironmentRegistryAccessView {
     pub is_enabled: bool,
 }
 
+/// Credentials naimportované z docker config.json pro jednu registry
+#[derive(Debug, Serialize)]
+pub struct DockerConfigImportedCredential {
+    pub registry_id: Uuid,
+    pub registry_name: String,
+    pub host: String,
+    pub auth_type: String,
+}
+
+/// Výsledek importu docker config.json
+#[derive(Debug, Serialize)]
+pub struct DockerConfigImportResult {
+    pub validate_only: bool,
+    pub imported: Vec<DockerConfigImportedCredential>,
+    /// Hosty z config.json bez registry v tenantu
+    pub unmatched_hosts: Vec<String>,
+    /// Záznamy bez použitelných credentials (credsStore, credHelpers, identitytoken)
+    pub skipped: Vec<SkippedDockerAuth>,
+    pub errors: Vec<String>,
+}
+
 /// Response s chybou
 #[derive(Debug, Serialize)]
 pub struct ErrorResponse {
     pub error: String,
 }
 
+/// Doplní stav expirace credentials do odpovědi
+fn with_credentials_expiry(mut registry: Registry, warn_days: i64) -> Registry {
+    registry.credentials_expiry = credential_expiry::expiry_state(registry.credentials_expire_at, warn_days);
+    registry
+}
+
 /// Vytvoří router pro registries endpoints
 pub fn router(state: RegistryApiState) -> Router {
     Router::new()
@@ -259,10 +318,18 @@ pub fn router(state: RegistryApiState) -> Router {
             "/registries/{id}/environment-credentials",
             get(get_registry_environment_credentials),
         )
+        .route(
+            "/registries/{id}/environment-credentials/import",
+            post(import_environment_credentials_csv),
+        )
         .route(
             "/registries/{id}/environment-access",
             get(get_registry_environment_access),
         )
+        .route(
+            "/environments/{id}/registry-credentials/import-docker-config",
+            post(import_docker_config_credentials),
+        )
         .with_state(state)
 }
 
@@ -419,13 +486,14 @@ async fn upsert_environment_credentials(
         sqlx::query(
             r#"
             INSERT INTO environment_registry_credentials
-                (environment_id, registry_id, auth_type, username, password_encrypted, token_encrypted)
-            VALUES ($1, $2, $3, $4, $5, $6)
+                (environment_id, registry_id, auth_type, username, password_encrypted, token_encrypted, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
             ON CONFLICT (environment_id, registry_id)
             DO UPDATE SET auth_type = EXCLUDED.auth_type,
                           username = EXCLUDED.username,
                           password_encrypted = EXCLUDED.password_encrypted,
-                          token_encrypted = EXCLUDED.token_encrypted
+                          token_encrypted = EXCLUDED.token_encrypted,
+                          expires_at = EXCLUDED.expires_at
             "#,
         )
         .bind(entry.environment_id)
@@ -434,6 +502,7 @@ async fn upsert_environment_credentials(
         .bind(username)
         .bind(password_encrypted)
         .bind(token_encrypted)
+        .bind(entry.expires_at)
         .execute(pool)
         .await
         .map_err(|e| {
@@ -449,6 +518,313 @@ async fn upsert_environment_credentials(
     Ok(())
 }
 
+/// POST /api/v1/registries/{id}/environment-credentials/import - Import environment credentials z CSV
+///
+/// Sloupce: environment (slug nebo id), auth_type, username, password, token, expires_at (volitelně).
+/// Při jakékoliv chybě se neimportuje nic; `?validate_only=true` jen validuje.
+async fn import_environment_credentials_csv(
+    State(state): State<RegistryApiState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<CsvImportQuery>,
+    body: String,
+) -> Result<(StatusCode, Json<CsvImportResult>), (StatusCode, Json<ErrorResponse>)> {
+    let validate_only = query.validate_only.unwrap_or(false);
+
+    let tenant_id = sqlx::query_scalar::<_, Uuid>("SELECT tenant_id FROM registries WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Database error: {}", e),
+                }),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: format!("Registry with id {} not found", id),
+                }),
+            )
+        })?;
+
+    let records = match csv_import::parse_records(&body, &["environment", "auth_type"]) {
+        Ok(records) => records,
+        Err(errors) => {
+            return Ok((
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(CsvImportResult {
+                    validate_only,
+                    total_rows: 0,
+                    imported: 0,
+                    errors,
+                }),
+            ))
+        }
+    };
+
+    let environments = sqlx::query_as::<_, (Uuid, String)>(
+        "SELECT id, slug FROM environments WHERE tenant_id = $1",
+    )
+    .bind(tenant_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }),
+        )
+    })?;
+    let existing = sqlx::query_as::<_, (Uuid, Option<String>, bool, bool)>(
+        "SELECT environment_id, username, password_encrypted IS NOT NULL, token_encrypted IS NOT NULL
+         FROM environment_registry_credentials WHERE registry_id = $1",
+    )
+    .bind(id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }),
+        )
+    })?;
+
+    let valid_auth_types = ["none", "basic", "token", "bearer"];
+    let mut errors = Vec::new();
+    let mut entries = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    for record in &records {
+        let env_ref = record.get("environment").unwrap_or_default();
+        let environment_id = environments
+            .iter()
+            .find(|(env_id, slug)| slug == env_ref || env_id.to_string() == env_ref)
+            .map(|(env_id, _)| *env_id);
+        let Some(environment_id) = environment_id else {
+            errors.push(CsvRowError::new(
+                record.row,
+                Some("environment"),
+                format!("Environment '{}' not found in tenant", env_ref),
+            ));
+            continue;
+        };
+        if !seen.insert(environment_id) {
+            errors.push(CsvRowError::new(record.row, Some("environment"), "Duplicate environment"));
+            continue;
+        }
+
+        let auth_type = record.get("auth_type").unwrap_or_default().to_lowercase();
+        if !valid_auth_types.contains(&auth_type.as_str()) {
+            errors.push(CsvRowError::new(
+                record.row,
+                Some("auth_type"),
+                format!("Invalid auth_type. Must be one of: {}", valid_auth_types.join(", ")),
+            ));
+            continue;
+        }
+
+        let stored = existing.iter().find(|row| row.0 == environment_id);
+        let has_username = record.get("username").is_some() || stored.and_then(|row| row.1.as_ref()).is_some();
+        let has_password = record.get("password").is_some() || stored.map(|row| row.2).unwrap_or(false);
+        let has_token = record.get("token").is_some() || stored.map(|row| row.3).unwrap_or(false);
+        let missing = match auth_type.as_str() {
+            "basic" if !has_username => Some("username"),
+            "basic" if !has_password => Some("password"),
+            "token" if !has_username => Some("username"),
+            "token" | "bearer" if !has_token => Some("token"),
+            _ => None,
+        };
+        if let Some(column) = missing {
+            errors.push(CsvRowError::new(
+                record.row,
+                Some(column),
+                format!("Auth type '{}' requires {}", auth_type, column),
+            ));
+            continue;
+        }
+
+        let expires_at = match record.get("expires_at") {
+            Some(value) => match credential_expiry::parse_expiry(value) {
+                Some(expires_at) => Some(expires_at),
+                None => {
+                    errors.push(CsvRowError::new(
+                        record.row,
+                        Some("expires_at"),
+                        format!("Invalid expires_at '{}', expected YYYY-MM-DD or RFC 3339", value),
+                    ));
+                    continue;
+                }
+            },
+            None => None,
+        };
+
+        entries.push(EnvironmentRegistryCredentialInput {
+            environment_id,
+            auth_type,
+            username: record.get("username").map(str::to_string),
+            password: record.get("password").map(str::to_string),
+            token: record.get("token").map(str::to_string),
+            expires_at,
+        });
+    }
+
+    let total_rows = records.len();
+    if !errors.is_empty() || validate_only {
+        let status = if errors.is_empty() {
+            StatusCode::OK
+        } else {
+            StatusCode::UNPROCESSABLE_ENTITY
+        };
+        return Ok((
+            status,
+            Json(CsvImportResult {
+                validate_only,
+                total_rows,
+                imported: 0,
+                errors,
+            }),
+        ));
+    }
+
+    upsert_environment_credentials(&state.pool, tenant_id, id, &entries, &state.encryption_secret).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(CsvImportResult {
+            validate_only,
+            total_rows,
+            imported: entries.len(),
+            errors,
+        }),
+    ))
+}
+
+/// POST /api/v1/environments/{id}/registry-credentials/import-docker-config - Import z docker config.json
+///
+/// Body je obsah `~/.docker/config.json`. Každý záznam z `auths` se přiřadí registries tenanta
+/// se stejným hostem a uloží (šifrovaně) jako credentials prostředí. Hosty bez registry se jen vypíšou.
+/// Při jakékoliv chybě se neimportuje nic; `?validate_only=true` jen validuje.
+async fn import_docker_config_credentials(
+    State(state): State<RegistryApiState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<CsvImportQuery>,
+    body: String,
+) -> Result<(StatusCode, Json<DockerConfigImportResult>), (StatusCode, Json<ErrorResponse>)> {
+    let validate_only = query.validate_only.unwrap_or(false);
+
+    let tenant_id = sqlx::query_scalar::<_, Uuid>("SELECT tenant_id FROM environments WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Database error: {}", e),
+                }),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: format!("Environment with id {} not found", id),
+                }),
+            )
+        })?;
+
+    let mut result = DockerConfigImportResult {
+        validate_only,
+        imported: Vec::new(),
+        unmatched_hosts: Vec::new(),
+        skipped: Vec::new(),
+        errors: Vec::new(),
+    };
+    let parsed = match docker_config::parse(&body) {
+        Ok(parsed) => parsed,
+        Err(error) => {
+            result.errors.push(error);
+            return Ok((StatusCode::UNPROCESSABLE_ENTITY, Json(result)));
+        }
+    };
+    result.skipped = parsed.skipped;
+    result.errors = parsed.errors;
+
+    let registries = sqlx::query_as::<_, (Uuid, String, String)>(
+        "SELECT id, name, base_url FROM registries WHERE tenant_id = $1 ORDER BY name",
+    )
+    .bind(tenant_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }),
+        )
+    })?;
+
+    // Jeden host může mít víc registries (různé project paths) - credentials dostanou všechny
+    let mut entries = Vec::new();
+    for credential in &parsed.credentials {
+        let matching: Vec<&(Uuid, String, String)> = registries
+            .iter()
+            .filter(|(_, _, base_url)| docker_config::normalize_host(base_url) == credential.host)
+            .collect();
+        if matching.is_empty() {
+            result.unmatched_hosts.push(credential.host.clone());
+            continue;
+        }
+        for (registry_id, registry_name, _) in matching {
+            result.imported.push(DockerConfigImportedCredential {
+                registry_id: *registry_id,
+                registry_name: registry_name.clone(),
+                host: credential.host.clone(),
+                auth_type: credential.auth_type.clone(),
+            });
+            entries.push((
+                *registry_id,
+                EnvironmentRegistryCredentialInput {
+                    environment_id: id,
+                    auth_type: credential.auth_type.clone(),
+                    username: credential.username.clone(),
+                    password: credential.password.clone(),
+                    token: credential.token.clone(),
+                    expires_at: None,
+                },
+            ));
+        }
+    }
+
+    if !result.errors.is_empty() {
+        result.imported.clear();
+        return Ok((StatusCode::UNPROCESSABLE_ENTITY, Json(result)));
+    }
+    if validate_only {
+        return Ok((StatusCode::OK, Json(result)));
+    }
+
+    for (registry_id, entry) in entries {
+        upsert_environment_credentials(
+            &state.pool,
+            tenant_id,
+            registry_id,
+            std::slice::from_ref(&entry),
+            &state.encryption_secret,
+        )
+        .await?;
+    }
+
+    Ok((StatusCode::OK, Json(result)))
+}
+
 async fn upsert_environment_access(
     pool: &PgPool,
     tenant_id: Uuid,
@@ -659,7 +1035,12 @@ async fn list_all_registries(
         )
     })?;
 
-    Ok(Json(registries))
+    Ok(Json(
+        registries
+            .into_iter()
+            .map(|registry| with_credentials_expiry(registry, state.credential_expiry_warn_days))
+            .collect(),
+    ))
 }
 
 /// GET /api/v1/tenants/{tenant_id}/registries - Seznam registries pro tenanta
@@ -682,7 +1063,12 @@ async fn list_registries(
         )
     })?;
 
-    Ok(Json(registries))
+    Ok(Json(
+        registries
+            .into_iter()
+            .map(|registry| with_credentials_expiry(registry, state.credential_expiry_warn_days))
+            .collect(),
+    ))
 }
 
 /// GET /api/v1/registries/{id} - Detail registry
@@ -704,7 +1090,7 @@ async fn get_registry(
         })?;
 
     match registry {
-        Some(registry) => Ok(Json(registry)),
+        Some(registry) => Ok(Json(with_credentials_expiry(registry, state.credential_expiry_warn_days))),
         None => Err((
             StatusCode::NOT_FOUND,
             Json(ErrorResponse {
@@ -872,10 +1258,15 @@ async fn create_registry(
         .filter(|path| !path.is_empty())
         .map(|path| path.trim_matches('/').to_string());
 
+    let copy_options = match payload.copy_options.as_ref() {
+        Some(copy_options) => copy_options_value(copy_options)?,
+        None => None,
+    };
+
     // Vytvoření registry
     let registry = sqlx::query_as::<_, Registry>(
-        "INSERT INTO registries (tenant_id, name, registry_type, base_url, default_project_path, auth_type, username, password_encrypted, token_encrypted, role, description, is_active)
-         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        "INSERT INTO registries (tenant_id, name, registry_type, base_url, default_project_path, auth_type, username, password_encrypted, token_encrypted, role, description, is_active, copy_options, credentials_expire_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
          RETURNING *",
     )
     .bind(tenant_id)
@@ -890,6 +1281,8 @@ async fn create_registry(
     .bind(&payload.role)
     .bind(&payload.description)
     .bind(payload.is_active.unwrap_or(true))
+    .bind(&copy_options)
+    .bind(payload.credentials_expire_at)
     .fetch_one(&state.pool)
     .await
     .map_err(|e| {
@@ -933,11 +1326,15 @@ async fn create_registry(
         upsert_environment_access(&state.pool, tenant_id, registry.id, access).await?;
     }
 
-    Ok((StatusCode::CREATED, Json(registry)))
+    Ok((
+        StatusCode::CREATED,
+        Json(with_credentials_expiry(registry, state.credential_expiry_warn_days)),
+    ))
 }
 
 /// PUT /api/v1/registries/{id} - Update registry
 async fn update_registry(
+    Extension(auth): Extension<AuthContext>,
     State(state): State<RegistryApiState>,
     Path(id): Path<Uuid>,
     Json(payload): Json<UpdateRegistryRequest>,
@@ -1103,11 +1500,18 @@ async fn update_registry(
         .filter(|path| !path.is_empty())
         .map(|path| path.trim_matches('/').to_string());
 
+    // Bez copy_options v requestu zůstává uložené nastavení
+    let copy_options = match payload.copy_options.as_ref() {
+        Some(copy_options) => copy_options_value(copy_options)?,
+        None => existing.copy_options.clone(),
+    };
+
     // Update registry
     let registry = sqlx::query_as::<_, Registry>(
         "UPDATE registries
          SET tenant_id = $1, name = $2, registry_type = $3, base_url = $4, default_project_path = $5, auth_type = $6, username = $7,
-             password_encrypted = $8, token_encrypted = $9, role = $10, description = $11, is_active = $12
+             password_encrypted = $8, token_encrypted = $9, role = $10, description = $11, is_active = $12, copy_options = $14,
+             credentials_expire_at = $15
          WHERE id = $13
          RETURNING *",
     )
@@ -1124,6 +1528,8 @@ async fn update_registry(
     .bind(&payload.description)
     .bind(payload.is_active.unwrap_or(true))
     .bind(id)
+    .bind(&copy_options)
+    .bind(payload.credentials_expire_at)
     .fetch_optional(&state.pool)
     .await
     .map_err(|e| {
@@ -1168,7 +1574,8 @@ async fn update_registry(
             if let Some(access) = payload.environment_access.as_ref() {
                 upsert_environment_access(&state.pool, registry.tenant_id, registry.id, access).await?;
             }
-            Ok(Json(registry))
+            record_registry_changes(&state.pool, &auth, &existing, &registry).await;
+            Ok(Json(with_credentials_expiry(registry, state.credential_expiry_warn_days)))
         }
         None => Err((
             StatusCode::NOT_FOUND,
@@ -1179,6 +1586,32 @@ async fn update_registry(
     }
 }
 
+async fn record_registry_changes(pool: &PgPool, auth: &AuthContext, before: &Registry, after: &Registry) {
+    let mut changes = change_history::diff_snapshots(before, after);
+    changes.extend(change_history::secret_change(
+        "password",
+        before.password_encrypted.as_deref(),
+        after.password_encrypted.as_deref(),
+    ));
+    changes.extend(change_history::secret_change(
+        "token",
+        before.token_encrypted.as_deref(),
+        after.token_encrypted.as_deref(),
+    ));
+    if let Err(e) = change_history::record_changes(
+        pool,
+        "registry",
+        after.id,
+        Some(after.tenant_id),
+        Some(&auth.username),
+        &changes,
+    )
+    .await
+    {
+        tracing::warn!(registry_id = %after.id, error = %e, "Failed to record registry change history");
+    }
+}
+
 /// DELETE /api/v1/registries/{id} - Smazání registry
 async fn delete_registry(
     State(state): State<RegistryApiState>,
@@ -1260,7 +1693,8 @@ async fn get_registry_environment_credentials(
             COALESCE(erc.auth_type, '') AS auth_type,
             erc.username,
             (erc.password_encrypted IS NOT NULL) AS has_password,
-            (erc.token_encrypted IS NOT NULL) AS has_token
+            (erc.token_encrypted IS NOT NULL) AS has_token,
+            erc.expires_at
         FROM environments e
         LEFT JOIN environment_registry_credentials erc
           ON erc.environment_id = e.id AND erc.registry_id = $1
@@ -1280,6 +1714,14 @@ async fn get_registry_environment_credentials(
         )
     })?;
 
+    let rows = rows
+        .into_iter()
+        .map(|mut row| {
+            row.expiry = credential_expiry::expiry_state(row.expires_at, state.credential_expiry_warn_days);
+            row
+        })
+        .collect();
+
     Ok(Json(rows))
 }
 
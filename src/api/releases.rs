@@ -1,8 +1,8 @@
 use axum::{
-    extract::{Path, State, Query},
+    extract::{FromRef, Path, State, Query},
     http::{header, StatusCode},
     response::IntoResponse,
-    routing::get,
+    routing::{get, post},
     Extension, Json, Router,
 };
 use serde::{Deserialize, Serialize};
@@ -10,23 +10,28 @@ use sqlx::PgPool;
 use std::collections::HashMap;
 use uuid::Uuid;
 
-use crate::{auth::AuthContext, db::models::Release, services::release_manifest::build_release_manifest};
+use crate::{api::fieldsets::FieldsetQuery, auth::AuthContext, db::models::Release, services::release_manifest::{self, load_release_manifest, refresh_release_manifest, store_manifest_snapshot, RELEASE_MANIFEST_SCHEMA_VERSION}};
+use crate::services::object_storage::ObjectStorage;
+use crate::services::release_exports::{self, ExportContent, ReleaseExport};
+use crate::services::{lifecycle_notifications, notifications::Notifier, release_channels, release_notes};
 
-/// Request pro vytvoření nového release
-#[derive(Debug, Deserialize)]
-pub struct CreateReleaseRequest {
-    pub copy_job_id: Uuid,
-    pub release_id: String,
-    pub notes: Option<String>,
-    pub created_by: Option<String>,
-    pub source_ref_mode: Option<String>,
-}
+pub use srm_api_types::releases::CreateReleaseRequest;
 
 /// Request pro update release
 #[derive(Debug, Deserialize)]
 pub struct UpdateReleaseRequest {
     pub status: String,
     pub notes: Option<String>,
+    /// Přesun do jiného kanálu (např. povýšení `rc` -> `stable`); None = beze změny
+    pub channel: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReleaseListQuery {
+    /// Jen releases z daného kanálu
+    pub channel: Option<String>,
+    /// Jen releases z kanálů, které environment odebírá
+    pub environment_id: Option<Uuid>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -35,6 +40,14 @@ pub struct CompareReleasesQuery {
     pub release_b: Uuid,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ReleaseNotesQuery {
+    /// Release pro porovnání (výchozí base release nebo předchozí release bundle)
+    pub against: Option<Uuid>,
+    /// `json` (výchozí) nebo `markdown`
+    pub format: Option<String>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct CompareReleaseRow {
     pub app_name: String,
@@ -52,6 +65,7 @@ pub struct ReleaseSummary {
     pub status: String,
     pub source_ref_mode: String,
     pub is_auto: bool,
+    pub channel: String,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub tenant_id: Uuid,
     pub tenant_name: String,
@@ -67,28 +81,80 @@ pub struct ReleaseSummary {
     pub environment_color: Option<String>,
 }
 
+/// Výsledek `POST /release-manifest/validate`
+#[derive(Debug, Serialize)]
+pub struct ManifestValidationResponse {
+    pub valid: bool,
+    /// Verze uvedená ve vstupu (`0` = bez verze)
+    pub schema_version: u32,
+    pub current_schema_version: u32,
+    /// Vstup byl ve starší verzi a validoval se po převodu na aktuální
+    pub migrated: bool,
+    pub errors: Vec<String>,
+}
+
 /// Response s chybou
 #[derive(Debug, Serialize)]
 pub struct ErrorResponse {
     pub error: String,
 }
 
+/// App state pro releases API - handlery si berou pool, notifier nebo úložiště přes `FromRef`
+#[derive(Clone)]
+pub struct ReleaseApiState {
+    pub pool: PgPool,
+    pub notifier: Notifier,
+    pub object_storage: ObjectStorage,
+}
+
+impl FromRef<ReleaseApiState> for PgPool {
+    fn from_ref(state: &ReleaseApiState) -> Self {
+        state.pool.clone()
+    }
+}
+
+impl FromRef<ReleaseApiState> for Notifier {
+    fn from_ref(state: &ReleaseApiState) -> Self {
+        state.notifier.clone()
+    }
+}
+
+impl FromRef<ReleaseApiState> for ObjectStorage {
+    fn from_ref(state: &ReleaseApiState) -> Self {
+        state.object_storage.clone()
+    }
+}
+
 /// Vytvoří router pro releases endpoints
-pub fn router(pool: PgPool) -> Router {
+pub fn router(state: ReleaseApiState) -> Router {
     Router::new()
         .route("/releases", get(list_all_releases).post(create_release_global))
         .route("/tenants/{tenant_id}/releases", get(list_releases).post(create_release))
         .route("/releases/compare", get(compare_releases))
         .route("/releases/{id}", get(get_release).put(update_release))
         .route("/releases/{id}/manifest", get(get_release_manifest))
-        .with_state(pool)
+        .route("/releases/{id}/notes", get(get_release_notes))
+        .route("/releases/{id}/manifest/refresh", post(refresh_release_manifest_snapshot))
+        .route("/releases/{id}/exports", get(list_release_exports).post(create_release_export))
+        .route("/releases/{id}/exports/{export_id}/download", get(download_release_export))
+        .route("/release-manifest/schema", get(get_release_manifest_schema))
+        .route("/release-manifest/validate", post(validate_release_manifest))
+        .with_state(state)
+}
+
+/// Kanál z `?channel=` ověřený proti známým kanálům
+fn list_channel(query: &ReleaseListQuery) -> Result<Option<&'static str>, (StatusCode, Json<ErrorResponse>)> {
+    release_channels::parse_optional(query.channel.as_deref())
+        .map_err(|error| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })))
 }
 
-/// GET /api/v1/releases - Seznam všech releases
+/// GET /api/v1/releases?channel=&environment_id= - Seznam všech releases
 async fn list_all_releases(
     Extension(auth): Extension<AuthContext>,
     State(pool): State<PgPool>,
+    Query(query): Query<ReleaseListQuery>,
 ) -> Result<Json<Vec<ReleaseSummary>>, (StatusCode, Json<ErrorResponse>)> {
+    let channel = list_channel(&query)?;
     let releases = if auth.is_admin() {
         sqlx::query_as::<_, ReleaseSummary>(
             r#"
@@ -99,6 +165,7 @@ async fn list_all_releases(
                 r.status,
                 r.source_ref_mode,
                 r.is_auto,
+                r.channel,
                 r.created_at,
                 t.id AS tenant_id,
                 t.name AS tenant_name,
@@ -119,10 +186,14 @@ async fn list_all_releases(
             JOIN tenants t ON t.id = b.tenant_id
             LEFT JOIN environments e ON e.id = cj.environment_id
             LEFT JOIN deploy_jobs dj ON dj.release_id = r.id
+            WHERE ($1::text IS NULL OR r.channel = $1)
+              AND ($2::uuid IS NULL OR EXISTS (SELECT 1 FROM environments se WHERE se.id = $2 AND r.channel = ANY(se.release_channels)))
             GROUP BY r.id, t.id, b.id, e.id
             ORDER BY r.created_at DESC
             "#,
         )
+        .bind(channel)
+        .bind(query.environment_id)
         .fetch_all(&pool)
         .await
     } else {
@@ -135,6 +206,7 @@ async fn list_all_releases(
                 r.status,
                 r.source_ref_mode,
                 r.is_auto,
+                r.channel,
                 r.created_at,
                 t.id AS tenant_id,
                 t.name AS tenant_name,
@@ -156,11 +228,15 @@ async fn list_all_releases(
             LEFT JOIN environments e ON e.id = cj.environment_id
             LEFT JOIN deploy_jobs dj ON dj.release_id = r.id
             WHERE t.id = ANY($1)
+              AND ($2::text IS NULL OR r.channel = $2)
+              AND ($3::uuid IS NULL OR EXISTS (SELECT 1 FROM environments se WHERE se.id = $3 AND r.channel = ANY(se.release_channels)))
             GROUP BY r.id, t.id, b.id, e.id
             ORDER BY r.created_at DESC
             "#,
         )
         .bind(&auth.tenant_ids)
+        .bind(channel)
+        .bind(query.environment_id)
         .fetch_all(&pool)
         .await
     }
@@ -176,11 +252,13 @@ async fn list_all_releases(
     Ok(Json(releases))
 }
 
-/// GET /api/v1/tenants/{tenant_id}/releases - Seznam releases pro tenanta
+/// GET /api/v1/tenants/{tenant_id}/releases?channel=&environment_id= - Seznam releases pro tenanta
 async fn list_releases(
     State(pool): State<PgPool>,
     Path(tenant_id): Path<Uuid>,
+    Query(query): Query<ReleaseListQuery>,
 ) -> Result<Json<Vec<ReleaseSummary>>, (StatusCode, Json<ErrorResponse>)> {
+    let channel = list_channel(&query)?;
     let releases = sqlx::query_as::<_, ReleaseSummary>(
         r#"
         SELECT
@@ -190,6 +268,7 @@ async fn list_releases(
             r.status,
             r.source_ref_mode,
             r.is_auto,
+            r.channel,
             r.created_at,
             t.id AS tenant_id,
             t.name AS tenant_name,
@@ -211,11 +290,15 @@ async fn list_releases(
         LEFT JOIN environments e ON e.id = cj.environment_id
         LEFT JOIN deploy_jobs dj ON dj.release_id = r.id
         WHERE b.tenant_id = $1
+          AND ($2::text IS NULL OR r.channel = $2)
+          AND ($3::uuid IS NULL OR EXISTS (SELECT 1 FROM environments se WHERE se.id = $3 AND r.channel = ANY(se.release_channels)))
         GROUP BY r.id, t.id, b.id, e.id
         ORDER BY r.created_at DESC
         "#
     )
     .bind(tenant_id)
+    .bind(channel)
+    .bind(query.environment_id)
     .fetch_all(&pool)
     .await
     .map_err(|e| {
@@ -312,7 +395,7 @@ async fn compare_releases(
         }
     }
 
-    let manifest_a = build_release_manifest(&pool, params.release_a).await.map_err(|e| {
+    let manifest_a = load_release_manifest(&pool, params.release_a).await.map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse {
@@ -320,7 +403,7 @@ async fn compare_releases(
             }),
         )
     })?;
-    let manifest_b = build_release_manifest(&pool, params.release_b).await.map_err(|e| {
+    let manifest_b = load_release_manifest(&pool, params.release_b).await.map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse {
@@ -387,11 +470,19 @@ async fn compare_releases(
     Ok(Json(results))
 }
 
+/// Volitelné sub-resources pro `GET /releases/{id}?expand=`
+const RELEASE_EXPANSIONS: &[&str] = &["copy_job", "images", "deploy_jobs", "manifest"];
+
 /// GET /api/v1/releases/{id} - Detail release
 async fn get_release(
     State(pool): State<PgPool>,
     Path(id): Path<Uuid>,
-) -> Result<Json<Release>, (StatusCode, Json<ErrorResponse>)> {
+    Query(fieldset): Query<FieldsetQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    let expansions = fieldset
+        .expansions(RELEASE_EXPANSIONS)
+        .map_err(|error| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })))?;
+
     let release = sqlx::query_as::<_, Release>("SELECT * FROM releases WHERE id = $1")
         .bind(id)
         .fetch_optional(&pool)
@@ -405,20 +496,79 @@ async fn get_release(
             )
         })?;
 
-    match release {
-        Some(release) => Ok(Json(release)),
-        None => Err((
+    let Some(release) = release else {
+        return Err((
             StatusCode::NOT_FOUND,
             Json(ErrorResponse {
                 error: format!("Release with id {} not found", id),
             }),
-        )),
+        ));
+    };
+
+    let db_error = |e: sqlx::Error| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }),
+        )
+    };
+
+    let copy_job_id = release.copy_job_id;
+    let mut value = serde_json::to_value(release).unwrap_or_default();
+    for expansion in &expansions {
+        value[expansion.as_str()] = match expansion.as_str() {
+            "manifest" => {
+                let manifest = load_release_manifest(&pool, id).await.map_err(|e| {
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ErrorResponse {
+                            error: format!("Failed to build manifest: {}", e),
+                        }),
+                    )
+                })?;
+                serde_json::to_value(manifest).unwrap_or_default()
+            }
+            "copy_job" => sqlx::query_scalar::<_, serde_json::Value>(
+                "SELECT row_to_json(cj) FROM copy_jobs cj WHERE cj.id = $1",
+            )
+            .bind(copy_job_id)
+            .fetch_optional(&pool)
+            .await
+            .map_err(db_error)?
+            .unwrap_or_default(),
+            "images" => sqlx::query_scalar::<_, serde_json::Value>(
+                "SELECT COALESCE(json_agg(t ORDER BY t.target_image), '[]'::json) FROM (
+                    SELECT id, image_mapping_id, source_image, source_tag, target_image, target_tag,
+                           source_sha256, target_sha256, copy_status
+                    FROM copy_job_images WHERE copy_job_id = $1
+                 ) t",
+            )
+            .bind(copy_job_id)
+            .fetch_one(&pool)
+            .await
+            .map_err(db_error)?,
+            _ => sqlx::query_scalar::<_, serde_json::Value>(
+                "SELECT COALESCE(json_agg(t ORDER BY t.created_at DESC), '[]'::json) FROM (
+                    SELECT id, environment_id, status, dry_run, started_at, completed_at,
+                           error_message, commit_sha, tag_name, created_at
+                    FROM deploy_jobs WHERE release_id = $1
+                 ) t",
+            )
+            .bind(id)
+            .fetch_one(&pool)
+            .await
+            .map_err(db_error)?,
+        };
     }
+
+    Ok(Json(fieldset.apply(value, &expansions)))
 }
 
 /// POST /api/v1/tenants/{tenant_id}/releases - Vytvoření nového release
 async fn create_release(
     State(pool): State<PgPool>,
+    State(notifier): State<Notifier>,
     Path(tenant_id): Path<Uuid>,
     Json(payload): Json<CreateReleaseRequest>,
 ) -> Result<(StatusCode, Json<Release>), (StatusCode, Json<ErrorResponse>)> {
@@ -501,11 +651,14 @@ async fn create_release(
         "digest" => "digest".to_string(),
         _ => "tag".to_string(),
     };
+    let channel = release_channels::parse_optional(payload.channel.as_deref())
+        .map_err(|error| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })))?
+        .unwrap_or(release_channels::DEFAULT_CHANNEL);
 
     // Vytvoření release
     let release = sqlx::query_as::<_, Release>(
-        "INSERT INTO releases (copy_job_id, release_id, status, source_ref_mode, notes, created_by, is_auto)
-         VALUES ($1, $2, 'draft', $3, $4, $5, false)
+        "INSERT INTO releases (copy_job_id, release_id, status, source_ref_mode, notes, created_by, is_auto, channel)
+         VALUES ($1, $2, 'draft', $3, $4, $5, false, $6)
          RETURNING *",
     )
     .bind(payload.copy_job_id)
@@ -513,6 +666,7 @@ async fn create_release(
     .bind(&source_ref_mode)
     .bind(&payload.notes)
     .bind(&payload.created_by)
+    .bind(channel)
     .fetch_one(&pool)
     .await
     .map_err(|e| {
@@ -535,13 +689,17 @@ async fn create_release(
         )
     })?;
 
+    store_manifest_snapshot(&pool, release.id).await;
+    lifecycle_notifications::release_created(&pool, &notifier, release.id).await;
+
     Ok((StatusCode::CREATED, Json(release)))
 }
 
 /// POST /api/v1/releases - Vytvoření nového release bez tenanta (tenant se odvodí z copy jobu)
-async fn create_release_global(
+pub(crate) async fn create_release_global(
     Extension(auth): Extension<AuthContext>,
     State(pool): State<PgPool>,
+    State(notifier): State<Notifier>,
     Json(payload): Json<CreateReleaseRequest>,
 ) -> Result<(StatusCode, Json<Release>), (StatusCode, Json<ErrorResponse>)> {
     let release_id = payload.release_id.trim().to_string();
@@ -642,10 +800,13 @@ async fn create_release_global(
         "digest" => "digest".to_string(),
         _ => "tag".to_string(),
     };
+    let channel = release_channels::parse_optional(payload.channel.as_deref())
+        .map_err(|error| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })))?
+        .unwrap_or(release_channels::DEFAULT_CHANNEL);
 
     let release = sqlx::query_as::<_, Release>(
-        "INSERT INTO releases (copy_job_id, release_id, status, source_ref_mode, notes, created_by, is_auto)
-         VALUES ($1, $2, 'draft', $3, $4, $5, false)
+        "INSERT INTO releases (copy_job_id, release_id, status, source_ref_mode, notes, created_by, is_auto, channel)
+         VALUES ($1, $2, 'draft', $3, $4, $5, false, $6)
          RETURNING *",
     )
     .bind(payload.copy_job_id)
@@ -653,6 +814,7 @@ async fn create_release_global(
     .bind(&source_ref_mode)
     .bind(&payload.notes)
     .bind(&payload.created_by)
+    .bind(channel)
     .fetch_one(&pool)
     .await
     .map_err(|e| {
@@ -675,6 +837,9 @@ async fn create_release_global(
         )
     })?;
 
+    store_manifest_snapshot(&pool, release.id).await;
+    lifecycle_notifications::release_created(&pool, &notifier, release.id).await;
+
     Ok((StatusCode::CREATED, Json(release)))
 }
 
@@ -695,14 +860,18 @@ async fn update_release(
         ));
     }
 
+    let channel = release_channels::parse_optional(payload.channel.as_deref())
+        .map_err(|error| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })))?;
+
     let release = sqlx::query_as::<_, Release>(
         "UPDATE releases
-         SET status = $1, notes = $2
-         WHERE id = $3
+         SET status = $1, notes = $2, channel = COALESCE($3, channel)
+         WHERE id = $4
          RETURNING *",
     )
     .bind(&payload.status)
     .bind(&payload.notes)
+    .bind(channel)
     .bind(id)
     .fetch_optional(&pool)
     .await
@@ -726,12 +895,50 @@ async fn update_release(
     }
 }
 
+/// POST /api/v1/releases/{id}/manifest/refresh - Znovu sestaví snapshot manifestu z copy jobu
+async fn refresh_release_manifest_snapshot(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM releases WHERE id = $1)")
+        .bind(id)
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Database error: {}", e),
+                }),
+            )
+        })?;
+    if !exists {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Release with id {} not found", id),
+            }),
+        ));
+    }
+
+    let manifest = refresh_release_manifest(&pool, id).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to build manifest: {}", e),
+            }),
+        )
+    })?;
+
+    Ok(Json(manifest))
+}
+
 /// GET /api/v1/releases/{id}/manifest - Release manifest (YAML) pro deployment
 async fn get_release_manifest(
     State(pool): State<PgPool>,
     Path(id): Path<Uuid>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
-    let manifest = build_release_manifest(&pool, id).await.map_err(|e| {
+    let manifest = load_release_manifest(&pool, id).await.map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse {
@@ -755,3 +962,206 @@ async fn get_release_manifest(
         yaml,
     ))
 }
+
+/// POST /api/v1/releases/{id}/exports - Export release (release + manifest + release notes) ke stažení
+async fn create_release_export(
+    Extension(auth): Extension<AuthContext>,
+    State(pool): State<PgPool>,
+    State(storage): State<ObjectStorage>,
+    Path(id): Path<Uuid>,
+) -> Result<(StatusCode, Json<ReleaseExport>), (StatusCode, Json<ErrorResponse>)> {
+    let export = release_exports::create_export(&pool, &storage, id, Some(&auth.username))
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Failed to export release: {}", e),
+                }),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: format!("Release with id {} not found", id),
+                }),
+            )
+        })?;
+    Ok((StatusCode::CREATED, Json(export)))
+}
+
+/// GET /api/v1/releases/{id}/exports - Exporty release od nejnovějšího
+async fn list_release_exports(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<ReleaseExport>>, (StatusCode, Json<ErrorResponse>)> {
+    release_exports::list_exports(&pool, id).await.map(Json).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }),
+        )
+    })
+}
+
+/// GET /api/v1/releases/{id}/exports/{export_id}/download - Obsah exportu; z object storage se streamuje
+async fn download_release_export(
+    State(pool): State<PgPool>,
+    State(storage): State<ObjectStorage>,
+    Path((id, export_id)): Path<(Uuid, Uuid)>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    let (export, content) = release_exports::load_export(&pool, id, export_id)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Database error: {}", e),
+                }),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: format!("Export {} of release {} not found", export_id, id),
+                }),
+            )
+        })?;
+
+    let body = match content {
+        ExportContent::Inline(bytes) => axum::body::Body::from(bytes),
+        ExportContent::Stored(key) => {
+            let response = storage.open(&key).await.map_err(|e| {
+                (
+                    StatusCode::BAD_GATEWAY,
+                    Json(ErrorResponse {
+                        error: format!("Failed to load release export: {}", e),
+                    }),
+                )
+            })?;
+            axum::body::Body::from_stream(futures::stream::try_unfold(response, |mut response| async move {
+                Ok::<_, reqwest::Error>(response.chunk().await?.map(|chunk| (chunk, response)))
+            }))
+        }
+    };
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/json".to_string()),
+            (header::CONTENT_LENGTH, export.size_bytes.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"release-export-{}.json\"", export.id),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// GET /api/v1/releases/{id}/notes - Změny image seskupené podle vlastníka aplikace
+async fn get_release_notes(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ReleaseNotesQuery>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    let markdown = match query.format.as_deref() {
+        None | Some("json") => false,
+        Some("markdown") => true,
+        Some(other) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Unsupported format '{}' (expected json or markdown)", other),
+                }),
+            ))
+        }
+    };
+
+    if let Some(against) = query.against {
+        let exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM releases WHERE id = $1)")
+            .bind(against)
+            .fetch_one(&pool)
+            .await
+            .map_err(|e| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse {
+                        error: format!("Database error: {}", e),
+                    }),
+                )
+            })?;
+        if !exists {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: format!("Release with id {} not found", against),
+                }),
+            ));
+        }
+    }
+
+    let notes = release_notes::build_release_notes(&pool, id, query.against)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Failed to build release notes: {}", e),
+                }),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: format!("Release with id {} not found", id),
+                }),
+            )
+        })?;
+
+    if markdown {
+        return Ok((
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "text/markdown; charset=utf-8")],
+            release_notes::render_markdown(&notes),
+        )
+            .into_response());
+    }
+    Ok(Json(notes).into_response())
+}
+
+/// GET /api/v1/release-manifest/schema - JSON Schema aktuální verze release manifestu
+async fn get_release_manifest_schema() -> Json<serde_json::Value> {
+    Json(release_manifest::manifest_json_schema())
+}
+
+/// POST /api/v1/release-manifest/validate - Ověří manifest (YAML nebo JSON) proti aktuálnímu schématu
+async fn validate_release_manifest(body: String) -> Result<Json<ManifestValidationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let value = serde_yaml_ng::from_str::<serde_json::Value>(&body).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: format!("Invalid manifest YAML: {}", e),
+            }),
+        )
+    })?;
+    let schema_version = release_manifest::schema_version_of(&value).unwrap_or(0);
+
+    let errors = match release_manifest::migrate_manifest(value) {
+        Ok(manifest) => release_manifest::validate_manifest(&manifest),
+        Err(e) => vec![e.to_string()],
+    };
+
+    Ok(Json(ManifestValidationResponse {
+        valid: errors.is_empty(),
+        schema_version,
+        current_schema_version: RELEASE_MANIFEST_SCHEMA_VERSION,
+        migrated: schema_version < RELEASE_MANIFEST_SCHEMA_VERSION,
+        errors,
+    }))
+}
@@ -0,0 +1,431 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::api::deploy::{self, DeployApiState, DeployJobDiffQuery, DeployJobDiffResponse};
+use crate::auth::AuthContext;
+use crate::db::models::ShareLink;
+use crate::services::job_logs::{self, JobKind};
+use crate::services::share_links::{
+    self, DEFAULT_EXPIRES_IN_HOURS, MAX_EXPIRES_IN_HOURS, OUTCOME_EXPIRED, OUTCOME_GRANTED, OUTCOME_OUT_OF_SCOPE,
+    OUTCOME_REVOKED, SCOPE_DIFF, SCOPE_LOGS,
+};
+
+/// Request pro vytvoření sdíleného odkazu
+#[derive(Debug, Deserialize)]
+pub struct CreateShareLinkRequest {
+    /// `copy` nebo `deploy`
+    pub job_kind: String,
+    pub job_id: Uuid,
+    /// `logs` (výchozí), `diff` (jen deploy job)
+    pub scopes: Option<Vec<String>>,
+    /// Platnost v hodinách (výchozí 24, max 720)
+    pub expires_in_hours: Option<i64>,
+    pub note: Option<String>,
+}
+
+/// Vytvořený odkaz; token ani URL se už znovu nezobrazí
+#[derive(Debug, Serialize)]
+pub struct ShareLinkCreated {
+    #[serde(flatten)]
+    pub link: ShareLink,
+    pub token: String,
+    /// Relativní URL podle scopes (`logs`, `diff`) + `info`
+    pub urls: ShareLinkUrls,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ShareLinkUrls {
+    pub info: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logs: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShareLinkListQuery {
+    pub job_kind: Option<String>,
+    pub job_id: Option<Uuid>,
+}
+
+/// Odkaz s počtem a časem posledního povoleného přístupu
+#[derive(Debug, Serialize, FromRow)]
+pub struct ShareLinkSummary {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub link: ShareLink,
+    pub access_count: i64,
+    pub last_accessed_at: Option<DateTime<Utc>>,
+}
+
+/// Záznam auditu přístupů
+#[derive(Debug, Serialize, FromRow)]
+pub struct ShareLinkAccess {
+    pub id: i64,
+    pub resource: String,
+    pub outcome: String,
+    pub remote_addr: Option<String>,
+    pub user_agent: Option<String>,
+    pub accessed_at: DateTime<Utc>,
+}
+
+/// Co vidí příjemce odkazu
+#[derive(Debug, Serialize)]
+pub struct SharedJobInfo {
+    pub job_kind: String,
+    pub job_id: Uuid,
+    pub status: Option<String>,
+    pub scopes: Vec<String>,
+    pub note: Option<String>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Response s chybou
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+/// Vytvoří router pro sdílené odkazy.
+/// Správa (`/share-links`) je jen pro adminy; `/shared/{token}` je veřejné a ověřuje podpis tokenu.
+pub fn router(state: DeployApiState) -> Router {
+    Router::new()
+        .route("/tenants/{tenant_id}/share-links", get(list_share_links))
+        .route("/share-links", post(create_share_link))
+        .route("/share-links/{id}", get(get_share_link).delete(revoke_share_link))
+        .route("/share-links/{id}/access-log", get(get_share_link_access_log))
+        .route("/shared/{token}", get(shared_info))
+        .route("/shared/{token}/logs", get(shared_logs))
+        .route("/shared/{token}/diff", get(shared_diff))
+        .with_state(state)
+}
+
+fn db_error(e: sqlx::Error) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: format!("Database error: {}", e),
+        }),
+    )
+}
+
+fn error(status: StatusCode, error: impl Into<String>) -> (StatusCode, Json<ErrorResponse>) {
+    (status, Json(ErrorResponse { error: error.into() }))
+}
+
+fn shared_url(token: &str, resource: Option<&str>) -> String {
+    match resource {
+        Some(resource) => format!("/api/v1/shared/{}/{}", token, resource),
+        None => format!("/api/v1/shared/{}", token),
+    }
+}
+
+/// Tenant jobu (copy přes bundle, deploy přes environment)
+async fn job_tenant(
+    state: &DeployApiState,
+    job_kind: &str,
+    job_id: Uuid,
+) -> Result<Option<Uuid>, (StatusCode, Json<ErrorResponse>)> {
+    let query = match job_kind {
+        "copy" => {
+            "SELECT b.tenant_id FROM copy_jobs cj
+             JOIN bundle_versions bv ON bv.id = cj.bundle_version_id
+             JOIN bundles b ON b.id = bv.bundle_id
+             WHERE cj.id = $1"
+        }
+        "deploy" => {
+            "SELECT e.tenant_id FROM deploy_jobs dj
+             JOIN environments e ON e.id = dj.environment_id
+             WHERE dj.id = $1"
+        }
+        other => {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                format!("Invalid job_kind '{}', expected 'copy' or 'deploy'", other),
+            ))
+        }
+    };
+    sqlx::query_scalar::<_, Uuid>(query)
+        .bind(job_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(db_error)
+}
+
+/// POST /api/v1/share-links - Podepsaný odkaz na logy / diff jednoho jobu
+async fn create_share_link(
+    Extension(auth): Extension<AuthContext>,
+    State(state): State<DeployApiState>,
+    Json(payload): Json<CreateShareLinkRequest>,
+) -> Result<(StatusCode, Json<ShareLinkCreated>), (StatusCode, Json<ErrorResponse>)> {
+    let job_kind = payload.job_kind.trim().to_lowercase();
+    let tenant_id = job_tenant(&state, &job_kind, payload.job_id)
+        .await?
+        .ok_or_else(|| {
+            error(
+                StatusCode::NOT_FOUND,
+                format!("{} job with id {} not found", job_kind, payload.job_id),
+            )
+        })?;
+    if !auth.is_tenant_allowed(tenant_id) {
+        return Err(error(StatusCode::FORBIDDEN, "Tenant access denied"));
+    }
+
+    let scopes = share_links::validate_scopes(&job_kind, payload.scopes.as_deref())
+        .map_err(|e| error(StatusCode::BAD_REQUEST, e))?;
+    let hours = payload.expires_in_hours.unwrap_or(DEFAULT_EXPIRES_IN_HOURS);
+    if !(1..=MAX_EXPIRES_IN_HOURS).contains(&hours) {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            format!("expires_in_hours must be between 1 and {}", MAX_EXPIRES_IN_HOURS),
+        ));
+    }
+    // Celé sekundy - stejná hodnota je podepsaná v tokenu
+    let expires_at = Utc
+        .timestamp_opt((Utc::now() + Duration::hours(hours)).timestamp(), 0)
+        .single()
+        .unwrap_or_else(Utc::now);
+    let note = payload.note.as_deref().map(str::trim).filter(|v| !v.is_empty());
+
+    let link = sqlx::query_as::<_, ShareLink>(
+        "INSERT INTO share_links (tenant_id, job_kind, job_id, scopes, note, created_by, expires_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7)
+         RETURNING *",
+    )
+    .bind(tenant_id)
+    .bind(&job_kind)
+    .bind(payload.job_id)
+    .bind(&scopes)
+    .bind(note)
+    .bind(&auth.username)
+    .bind(expires_at)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(db_error)?;
+    info!(
+        "Share link {} for {} job {} ({}) created by {}, expires {}",
+        link.id,
+        job_kind,
+        link.job_id,
+        scopes.join(","),
+        auth.username,
+        expires_at
+    );
+
+    let token = share_links::sign(&state.encryption_secret, link.id, link.expires_at);
+    let has_scope = |scope: &str| scopes.iter().any(|s| s == scope);
+    let urls = ShareLinkUrls {
+        info: shared_url(&token, None),
+        logs: has_scope(SCOPE_LOGS).then(|| shared_url(&token, Some(SCOPE_LOGS))),
+        diff: has_scope(SCOPE_DIFF).then(|| shared_url(&token, Some(SCOPE_DIFF))),
+    };
+    Ok((StatusCode::CREATED, Json(ShareLinkCreated { link, token, urls })))
+}
+
+const SUMMARY_SELECT: &str = "SELECT sl.*,
+        (SELECT COUNT(*) FROM share_link_access_log a WHERE a.share_link_id = sl.id AND a.outcome = 'granted') AS access_count,
+        (SELECT MAX(a.accessed_at) FROM share_link_access_log a WHERE a.share_link_id = sl.id AND a.outcome = 'granted') AS last_accessed_at
+     FROM share_links sl";
+
+/// GET /api/v1/tenants/{tenant_id}/share-links - Odkazy tenanta (volitelně jednoho jobu)
+async fn list_share_links(
+    State(state): State<DeployApiState>,
+    Path(tenant_id): Path<Uuid>,
+    Query(query): Query<ShareLinkListQuery>,
+) -> Result<Json<Vec<ShareLinkSummary>>, (StatusCode, Json<ErrorResponse>)> {
+    let links = sqlx::query_as::<_, ShareLinkSummary>(&format!(
+        "{} WHERE sl.tenant_id = $1
+           AND ($2::text IS NULL OR sl.job_kind = $2)
+           AND ($3::uuid IS NULL OR sl.job_id = $3)
+         ORDER BY sl.created_at DESC",
+        SUMMARY_SELECT
+    ))
+    .bind(tenant_id)
+    .bind(query.job_kind.as_deref().map(str::trim).map(str::to_lowercase))
+    .bind(query.job_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(db_error)?;
+    Ok(Json(links))
+}
+
+async fn get_share_link(
+    State(state): State<DeployApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ShareLinkSummary>, (StatusCode, Json<ErrorResponse>)> {
+    let link = sqlx::query_as::<_, ShareLinkSummary>(&format!("{} WHERE sl.id = $1", SUMMARY_SELECT))
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| error(StatusCode::NOT_FOUND, format!("Share link with id {} not found", id)))?;
+    Ok(Json(link))
+}
+
+/// DELETE /api/v1/share-links/{id} - Zruší odkaz; záznam i audit přístupů zůstávají
+async fn revoke_share_link(
+    Extension(auth): Extension<AuthContext>,
+    State(state): State<DeployApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    let result = sqlx::query("UPDATE share_links SET revoked_at = COALESCE(revoked_at, NOW()) WHERE id = $1")
+        .bind(id)
+        .execute(&state.pool)
+        .await
+        .map_err(db_error)?;
+    if result.rows_affected() == 0 {
+        return Err(error(StatusCode::NOT_FOUND, format!("Share link with id {} not found", id)));
+    }
+    info!("Share link {} revoked by {}", id, auth.username);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /api/v1/share-links/{id}/access-log - Audit přístupů přes odkaz (nejnovější první)
+async fn get_share_link_access_log(
+    State(state): State<DeployApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<ShareLinkAccess>>, (StatusCode, Json<ErrorResponse>)> {
+    let entries = sqlx::query_as::<_, ShareLinkAccess>(
+        "SELECT id, resource, outcome, remote_addr, user_agent, accessed_at
+         FROM share_link_access_log WHERE share_link_id = $1
+         ORDER BY accessed_at DESC, id DESC
+         LIMIT 1000",
+    )
+    .bind(id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(db_error)?;
+    Ok(Json(entries))
+}
+
+fn header_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim().chars().take(500).collect::<String>())
+        .filter(|v| !v.is_empty())
+}
+
+/// Ověří token a zapíše přístup do auditu. Neplatný podpis se nezapisuje (id nelze věřit).
+async fn authorize_shared(
+    state: &DeployApiState,
+    headers: &HeaderMap,
+    token: &str,
+    resource: &str,
+) -> Result<ShareLink, (StatusCode, Json<ErrorResponse>)> {
+    let not_found = || error(StatusCode::NOT_FOUND, "Share link not found");
+    let (id, signed_expires_at) = share_links::verify(&state.encryption_secret, token).ok_or_else(not_found)?;
+    let link = sqlx::query_as::<_, ShareLink>("SELECT * FROM share_links WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(db_error)?
+        .filter(|link| link.expires_at.timestamp() == signed_expires_at.timestamp())
+        .ok_or_else(not_found)?;
+
+    let outcome = if link.revoked_at.is_some() {
+        OUTCOME_REVOKED
+    } else if link.expires_at <= Utc::now() {
+        OUTCOME_EXPIRED
+    } else if resource != "info" && !link.scopes.iter().any(|s| s == resource) {
+        OUTCOME_OUT_OF_SCOPE
+    } else {
+        OUTCOME_GRANTED
+    };
+
+    // Proxy před SRM posílá adresu klienta v X-Forwarded-For (první položka)
+    let remote_addr = header_value(headers, "x-forwarded-for")
+        .and_then(|v| v.split(',').next().map(|v| v.trim().to_string()))
+        .or_else(|| header_value(headers, "x-real-ip"));
+    sqlx::query(
+        "INSERT INTO share_link_access_log (share_link_id, resource, outcome, remote_addr, user_agent)
+         VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(link.id)
+    .bind(resource)
+    .bind(outcome)
+    .bind(remote_addr)
+    .bind(header_value(headers, "user-agent"))
+    .execute(&state.pool)
+    .await
+    .map_err(db_error)?;
+
+    match outcome {
+        OUTCOME_REVOKED => Err(error(StatusCode::GONE, "Share link has been revoked")),
+        OUTCOME_EXPIRED => Err(error(StatusCode::GONE, "Share link has expired")),
+        OUTCOME_OUT_OF_SCOPE => Err(error(
+            StatusCode::FORBIDDEN,
+            format!("Share link does not include '{}'", resource),
+        )),
+        _ => Ok(link),
+    }
+}
+
+/// GET /api/v1/shared/{token} - Základní informace o sdíleném jobu
+async fn shared_info(
+    State(state): State<DeployApiState>,
+    Path(token): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<SharedJobInfo>, (StatusCode, Json<ErrorResponse>)> {
+    let link = authorize_shared(&state, &headers, &token, "info").await?;
+    let query = if link.job_kind == "copy" {
+        "SELECT status FROM copy_jobs WHERE id = $1"
+    } else {
+        "SELECT status FROM deploy_jobs WHERE id = $1"
+    };
+    let status = sqlx::query_scalar::<_, String>(query)
+        .bind(link.job_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(db_error)?;
+    Ok(Json(SharedJobInfo {
+        job_kind: link.job_kind,
+        job_id: link.job_id,
+        status,
+        scopes: link.scopes,
+        note: link.note,
+        expires_at: link.expires_at,
+    }))
+}
+
+/// GET /api/v1/shared/{token}/logs - Celá historie logů jobu
+async fn shared_logs(
+    State(state): State<DeployApiState>,
+    Path(token): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<String>>, (StatusCode, Json<ErrorResponse>)> {
+    let link = authorize_shared(&state, &headers, &token, SCOPE_LOGS).await?;
+    let kind = if link.job_kind == "copy" { JobKind::Copy } else { JobKind::Deploy };
+    let lines = job_logs::load_all_lines(&state.pool, &state.object_storage, kind, link.job_id)
+        .await
+        .map_err(|e| {
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to load job logs: {}", e),
+            )
+        })?;
+    Ok(Json(lines.into_iter().map(|line| line.line).collect()))
+}
+
+/// GET /api/v1/shared/{token}/diff - Diff deploy jobu (`?file=` jako u `/deploy/jobs/{id}/diff`)
+async fn shared_diff(
+    State(state): State<DeployApiState>,
+    Path(token): Path<String>,
+    Query(query): Query<DeployJobDiffQuery>,
+    headers: HeaderMap,
+) -> Result<Json<Option<DeployJobDiffResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let link = authorize_shared(&state, &headers, &token, SCOPE_DIFF).await?;
+    let diff = deploy::load_deploy_job_diff(&state, link.job_id, query.file.as_deref())
+        .await
+        .map_err(|(status, Json(e))| error(status, e.error))?;
+    Ok(Json(diff))
+}
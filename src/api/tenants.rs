@@ -17,6 +17,7 @@ pub struct CreateTenantRequest {
     pub name: String,
     pub slug: String,
     pub description: Option<String>,
+    pub timezone: Option<String>,
 }
 
 /// Request pro update tenanta
@@ -24,6 +25,7 @@ pub struct CreateTenantRequest {
 pub struct UpdateTenantRequest {
     pub name: String,
     pub description: Option<String>,
+    pub timezone: Option<String>,
 }
 
 /// Response s chybou
@@ -113,13 +115,16 @@ async fn create_tenant(
         ));
     }
 
+    let timezone = validate_timezone(&pool, payload.timezone.as_deref()).await?;
+
     // Vytvoření tenanta
     let tenant = sqlx::query_as::<_, Tenant>(
-        "INSERT INTO tenants (name, slug, description) VALUES ($1, $2, $3) RETURNING *",
+        "INSERT INTO tenants (name, slug, description, timezone) VALUES ($1, $2, $3, $4) RETURNING *",
     )
     .bind(&payload.name)
     .bind(&payload.slug)
     .bind(&payload.description)
+    .bind(timezone)
     .fetch_one(&pool)
     .await
     .map_err(|e| {
@@ -162,12 +167,15 @@ async fn update_tenant(
         ));
     }
 
+    let timezone = validate_timezone(&pool, payload.timezone.as_deref()).await?;
+
     // Update tenanta
     let tenant = sqlx::query_as::<_, Tenant>(
-        "UPDATE tenants SET name = $1, description = $2 WHERE id = $3 RETURNING *",
+        "UPDATE tenants SET name = $1, description = $2, timezone = $3 WHERE id = $4 RETURNING *",
     )
     .bind(&payload.name)
     .bind(&payload.description)
+    .bind(timezone)
     .bind(id)
     .fetch_optional(&pool)
     .await
@@ -232,3 +240,34 @@ async fn delete_tenant(
 
     Ok(StatusCode::NO_CONTENT)
 }
+
+/// Timezone musí znát Postgres (`pg_timezone_names`), ten z ní počítá datum auto tagů
+async fn validate_timezone(
+    pool: &PgPool,
+    timezone: Option<&str>,
+) -> Result<Option<String>, (StatusCode, Json<ErrorResponse>)> {
+    let Some(timezone) = timezone.map(str::trim).filter(|tz| !tz.is_empty()) else {
+        return Ok(None);
+    };
+    let known = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM pg_timezone_names WHERE name = $1)")
+        .bind(timezone)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Database error: {}", e),
+                }),
+            )
+        })?;
+    if !known {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: format!("Unknown timezone '{}'", timezone),
+            }),
+        ));
+    }
+    Ok(Some(timezone.to_string()))
+}
@@ -0,0 +1,184 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use serde::Serialize;
+use sqlx::PgPool;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::services::tool_paths::{self, EffectiveToolPaths, ToolPathDefaults, ToolPathOverrides};
+
+/// App state pro přepsání cest k nástrojům
+#[derive(Clone)]
+pub struct ToolPathsApiState {
+    pub pool: PgPool,
+    /// Globální cesty z konfigurace (IMAGE_TOOL_PATH, ENCJSON_PATH, KUBE_BUILD_APP_PATH)
+    pub defaults: ToolPathDefaults,
+}
+
+/// Přepsání tenanta a výsledné cesty
+#[derive(Debug, Serialize)]
+pub struct TenantToolPathsResponse {
+    pub tenant_id: Uuid,
+    pub overrides: ToolPathOverrides,
+    pub effective: EffectiveToolPaths,
+}
+
+/// Přepsání environmentu, zděděná přepsání tenanta a výsledné cesty
+#[derive(Debug, Serialize)]
+pub struct EnvironmentToolPathsResponse {
+    pub environment_id: Uuid,
+    pub tenant_id: Uuid,
+    pub overrides: ToolPathOverrides,
+    pub tenant_overrides: ToolPathOverrides,
+    pub effective: EffectiveToolPaths,
+}
+
+/// Response s chybou
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+/// Vytvoří router pro přepsání cest k nástrojům.
+/// Zápis (`PUT .../tool-paths`) auth vrstva povoluje jen adminům.
+pub fn router(state: ToolPathsApiState) -> Router {
+    Router::new()
+        .route(
+            "/tenants/{tenant_id}/tool-paths",
+            get(get_tenant_tool_paths).put(update_tenant_tool_paths),
+        )
+        .route(
+            "/environments/{id}/tool-paths",
+            get(get_environment_tool_paths).put(update_environment_tool_paths),
+        )
+        .with_state(state)
+}
+
+fn db_error(e: sqlx::Error) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: format!("Database error: {}", e),
+        }),
+    )
+}
+
+fn not_found(what: &str, id: Uuid) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::NOT_FOUND,
+        Json(ErrorResponse {
+            error: format!("{} with id {} not found", what, id),
+        }),
+    )
+}
+
+async fn tenant_response(
+    state: &ToolPathsApiState,
+    tenant_id: Uuid,
+) -> Result<TenantToolPathsResponse, (StatusCode, Json<ErrorResponse>)> {
+    let overrides = tool_paths::tenant_overrides(&state.pool, tenant_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| not_found("Tenant", tenant_id))?;
+    let effective = tool_paths::effective(None, &overrides, &state.defaults);
+    Ok(TenantToolPathsResponse {
+        tenant_id,
+        overrides,
+        effective,
+    })
+}
+
+async fn environment_response(
+    state: &ToolPathsApiState,
+    environment_id: Uuid,
+) -> Result<EnvironmentToolPathsResponse, (StatusCode, Json<ErrorResponse>)> {
+    let (tenant_id, overrides) = tool_paths::environment_overrides(&state.pool, environment_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| not_found("Environment", environment_id))?;
+    let tenant_overrides = tool_paths::for_tenant(&state.pool, tenant_id).await.map_err(db_error)?;
+    let effective = tool_paths::effective(Some(&overrides), &tenant_overrides, &state.defaults);
+    Ok(EnvironmentToolPathsResponse {
+        environment_id,
+        tenant_id,
+        overrides,
+        tenant_overrides,
+        effective,
+    })
+}
+
+/// Přepsání cest k nástrojům na tenantovi
+async fn get_tenant_tool_paths(
+    State(state): State<ToolPathsApiState>,
+    Path(tenant_id): Path<Uuid>,
+) -> Result<Json<TenantToolPathsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    Ok(Json(tenant_response(&state, tenant_id).await?))
+}
+
+/// Nastaví přepsání na tenantovi; nahrazuje všechny tři cesty (null / prázdná = zdědit)
+async fn update_tenant_tool_paths(
+    State(state): State<ToolPathsApiState>,
+    Path(tenant_id): Path<Uuid>,
+    Json(payload): Json<ToolPathOverrides>,
+) -> Result<Json<TenantToolPathsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let overrides = payload
+        .validated()
+        .map_err(|error| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })))?;
+
+    let result = sqlx::query(
+        "UPDATE tenants SET skopeo_path = $2, encjson_path = $3, kube_build_app_path = $4 WHERE id = $1",
+    )
+    .bind(tenant_id)
+    .bind(&overrides.skopeo_path)
+    .bind(&overrides.encjson_path)
+    .bind(&overrides.kube_build_app_path)
+    .execute(&state.pool)
+    .await
+    .map_err(db_error)?;
+    if result.rows_affected() == 0 {
+        return Err(not_found("Tenant", tenant_id));
+    }
+    info!("Tool path overrides updated for tenant {}: {:?}", tenant_id, overrides);
+
+    Ok(Json(tenant_response(&state, tenant_id).await?))
+}
+
+/// Přepsání cest k nástrojům na environmentu
+async fn get_environment_tool_paths(
+    State(state): State<ToolPathsApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<EnvironmentToolPathsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    Ok(Json(environment_response(&state, id).await?))
+}
+
+/// Nastaví přepsání na environmentu; nahrazuje všechny tři cesty (null / prázdná = zdědit z tenanta)
+async fn update_environment_tool_paths(
+    State(state): State<ToolPathsApiState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ToolPathOverrides>,
+) -> Result<Json<EnvironmentToolPathsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let overrides = payload
+        .validated()
+        .map_err(|error| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })))?;
+
+    let result = sqlx::query(
+        "UPDATE environments SET skopeo_path = $2, encjson_path = $3, kube_build_app_path = $4 WHERE id = $1",
+    )
+    .bind(id)
+    .bind(&overrides.skopeo_path)
+    .bind(&overrides.encjson_path)
+    .bind(&overrides.kube_build_app_path)
+    .execute(&state.pool)
+    .await
+    .map_err(db_error)?;
+    if result.rows_affected() == 0 {
+        return Err(not_found("Environment", id));
+    }
+    info!("Tool path overrides updated for environment {}: {:?}", id, overrides);
+
+    Ok(Json(environment_response(&state, id).await?))
+}
@@ -0,0 +1,626 @@
+use axum::{
+    extract::{rejection::JsonRejection, rejection::PathRejection, rejection::QueryRejection, Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Extension, Json, Router,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::api::error::{ApiError, FieldError};
+use crate::auth::AuthContext;
+use crate::db::models::{Bundle, CopyJob, DeployJob, Environment, Registry, Tenant};
+use crate::services::change_history::{self, FieldChange};
+
+/// Jednotný tvar list response ve v2
+#[derive(Debug, Serialize)]
+pub struct ListResponse<T> {
+    pub items: Vec<T>,
+}
+
+impl<T> From<Vec<T>> for ListResponse<T> {
+    fn from(items: Vec<T>) -> Self {
+        ListResponse { items }
+    }
+}
+
+/// Request pro vytvoření tenanta
+#[derive(Debug, Deserialize)]
+pub struct CreateTenantRequest {
+    /// Volitelné ID od klienta (stabilní ID pro IaC nástroje)
+    pub id: Option<Uuid>,
+    pub name: String,
+    pub slug: String,
+    pub description: Option<String>,
+}
+
+/// Request pro update tenanta
+#[derive(Debug, Deserialize)]
+pub struct UpdateTenantRequest {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// `?preview=true` – vrátí plán změn bez uložení
+#[derive(Debug, Default, Deserialize)]
+pub struct PreviewQuery {
+    #[serde(default)]
+    pub preview: bool,
+}
+
+/// Plán změny pro `?preview=true` (create / update / delete / noop)
+#[derive(Debug, Serialize)]
+pub struct PlanResponse<T> {
+    pub action: &'static str,
+    pub current: Option<T>,
+    pub changes: Vec<FieldChange>,
+}
+
+/// Popis jednoho typu resource pro klienty typu Terraform provider
+#[derive(Debug, Serialize)]
+pub struct ResourceDescriptor {
+    pub kind: &'static str,
+    /// Pole, podle kterého jde resource importovat
+    pub import_key: &'static str,
+    /// Šablona cesty pro dohledání podle `import_key`
+    pub lookup: &'static str,
+    pub read: &'static str,
+    pub list: &'static str,
+    pub create: &'static str,
+    pub update: &'static str,
+    pub delete: &'static str,
+    /// Pole, která nejde změnit bez nového vytvoření (ForceNew)
+    pub immutable_fields: &'static [&'static str],
+    /// Update podporuje `?preview=true`
+    pub update_preview: bool,
+    /// Create přijímá ID od klienta
+    pub client_ids: bool,
+}
+
+/// Stabilní popis API pro správu konfigurace jako kódu
+const RESOURCES: &[ResourceDescriptor] = &[
+    ResourceDescriptor {
+        kind: "tenant",
+        import_key: "slug",
+        lookup: "GET /api/v2/tenants/by-slug/{slug}",
+        read: "GET /api/v2/tenants/{tenant_id}",
+        list: "GET /api/v2/tenants",
+        create: "POST /api/v2/tenants",
+        update: "PUT /api/v2/tenants/{tenant_id}",
+        delete: "DELETE /api/v2/tenants/{tenant_id}",
+        immutable_fields: &["id", "slug"],
+        update_preview: true,
+        client_ids: true,
+    },
+    ResourceDescriptor {
+        kind: "registry",
+        import_key: "name",
+        lookup: "GET /api/v2/tenants/{tenant_id}/registries/by-name/{name}",
+        read: "GET /api/v2/registries/{registry_id}",
+        list: "GET /api/v2/tenants/{tenant_id}/registries",
+        create: "POST /api/v1/tenants/{tenant_id}/registries",
+        update: "PUT /api/v1/registries/{registry_id}",
+        delete: "DELETE /api/v1/registries/{registry_id}",
+        immutable_fields: &["id", "tenant_id"],
+        update_preview: false,
+        client_ids: false,
+    },
+    ResourceDescriptor {
+        kind: "environment",
+        import_key: "slug",
+        lookup: "GET /api/v2/tenants/{tenant_id}/environments/by-slug/{slug}",
+        read: "GET /api/v2/environments/{environment_id}",
+        list: "GET /api/v2/tenants/{tenant_id}/environments",
+        create: "POST /api/v1/tenants/{tenant_id}/environments",
+        update: "PUT /api/v1/environments/{environment_id}",
+        delete: "DELETE /api/v1/environments/{environment_id}",
+        immutable_fields: &["id", "tenant_id"],
+        update_preview: true,
+        client_ids: false,
+    },
+    ResourceDescriptor {
+        kind: "bundle",
+        import_key: "name",
+        lookup: "GET /api/v2/tenants/{tenant_id}/bundles/by-name/{name}",
+        read: "GET /api/v2/bundles/{bundle_id}",
+        list: "GET /api/v2/tenants/{tenant_id}/bundles",
+        create: "POST /api/v1/tenants/{tenant_id}/bundles",
+        update: "PUT /api/v1/bundles/{bundle_id}",
+        delete: "DELETE /api/v1/bundles/{bundle_id}",
+        immutable_fields: &["id", "tenant_id"],
+        update_preview: false,
+        client_ids: false,
+    },
+];
+
+type ApiResult<T> = Result<T, ApiError>;
+
+/// Vytvoří router pro /api/v2
+pub fn router(pool: PgPool) -> Router {
+    Router::new()
+        .route("/resources", get(list_resources))
+        .route("/tenants", get(list_tenants).post(create_tenant))
+        .route("/tenants/by-slug/{slug}", get(get_tenant_by_slug))
+        .route(
+            "/tenants/{tenant_id}",
+            get(get_tenant).put(update_tenant).delete(delete_tenant),
+        )
+        .route("/tenants/{tenant_id}/registries", get(list_registries))
+        .route("/tenants/{tenant_id}/registries/by-name/{name}", get(get_registry_by_name))
+        .route("/tenants/{tenant_id}/environments", get(list_environments))
+        .route("/tenants/{tenant_id}/environments/by-slug/{slug}", get(get_environment_by_slug))
+        .route("/tenants/{tenant_id}/bundles", get(list_bundles))
+        .route("/tenants/{tenant_id}/bundles/by-name/{name}", get(get_bundle_by_name))
+        .route("/registries/{registry_id}", get(get_registry))
+        .route("/environments/{environment_id}", get(get_environment))
+        .route("/bundles/{bundle_id}", get(get_bundle))
+        .route("/copy-jobs/{job_id}", get(get_copy_job))
+        .route("/deploy-jobs/{job_id}", get(get_deploy_job))
+        .with_state(pool)
+}
+
+fn validate_name(fields: &mut Vec<FieldError>, name: &str) {
+    if name.trim().is_empty() {
+        fields.push(FieldError::new("name", "must not be empty"));
+    } else if name.len() > 255 {
+        fields.push(FieldError::new("name", "must be at most 255 characters"));
+    }
+}
+
+fn validate_slug(fields: &mut Vec<FieldError>, slug: &str) {
+    if slug.is_empty() {
+        fields.push(FieldError::new("slug", "must not be empty"));
+    } else if !slug
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        || slug.starts_with('-')
+        || slug.ends_with('-')
+    {
+        fields.push(FieldError::new(
+            "slug",
+            "must contain only lowercase letters, digits and inner dashes",
+        ));
+    }
+}
+
+/// GET /api/v2/tenants - Seznam tenantů
+async fn list_tenants(
+    Extension(auth): Extension<AuthContext>,
+    State(pool): State<PgPool>,
+) -> ApiResult<Json<ListResponse<Tenant>>> {
+    let tenants = if auth.is_admin() {
+        sqlx::query_as::<_, Tenant>("SELECT * FROM tenants ORDER BY created_at DESC")
+            .fetch_all(&pool)
+            .await?
+    } else {
+        sqlx::query_as::<_, Tenant>(
+            "SELECT * FROM tenants WHERE id = ANY($1) ORDER BY created_at DESC",
+        )
+        .bind(&auth.tenant_ids)
+        .fetch_all(&pool)
+        .await?
+    };
+
+    Ok(Json(tenants.into()))
+}
+
+/// GET /api/v2/tenants/{tenant_id} - Detail tenanta
+async fn get_tenant(
+    State(pool): State<PgPool>,
+    path: Result<Path<Uuid>, PathRejection>,
+) -> ApiResult<Json<Tenant>> {
+    let Path(id) = path?;
+    sqlx::query_as::<_, Tenant>("SELECT * FROM tenants WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&pool)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(format!("Tenant {} not found", id)))
+}
+
+/// Stejné atributy = opakovaný create je idempotentní
+fn tenant_matches(tenant: &Tenant, name: &str, slug: &str, description: Option<&str>) -> bool {
+    tenant.name == name && tenant.slug == slug && tenant.description.as_deref() == description
+}
+
+async fn find_tenant(pool: &PgPool, id: Uuid) -> ApiResult<Option<Tenant>> {
+    Ok(sqlx::query_as::<_, Tenant>("SELECT * FROM tenants WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?)
+}
+
+fn plan_response<T: Serialize>(action: &'static str, current: Option<T>, changes: Vec<FieldChange>) -> Response {
+    Json(PlanResponse {
+        action,
+        current,
+        changes,
+    })
+    .into_response()
+}
+
+/// POST /api/v2/tenants - Vytvoření tenanta (field-level validace)
+///
+/// Opakovaný create se stejnými atributy vrátí existujícího tenanta (200),
+/// rozdílné atributy u stejného slugu nebo ID vrací 409.
+async fn create_tenant(
+    State(pool): State<PgPool>,
+    query: Result<Query<PreviewQuery>, QueryRejection>,
+    payload: Result<Json<CreateTenantRequest>, JsonRejection>,
+) -> ApiResult<Response> {
+    let Query(query) = query?;
+    let Json(payload) = payload?;
+
+    let mut fields = Vec::new();
+    validate_name(&mut fields, &payload.name);
+    validate_slug(&mut fields, &payload.slug);
+    if payload.id.is_some_and(|id| id.is_nil()) {
+        fields.push(FieldError::new("id", "must not be nil UUID"));
+    }
+    if !fields.is_empty() {
+        return Err(ApiError::validation(fields));
+    }
+    let name = payload.name.trim();
+
+    let by_id = match payload.id {
+        Some(id) => find_tenant(&pool, id).await?,
+        None => None,
+    };
+    let by_slug = sqlx::query_as::<_, Tenant>("SELECT * FROM tenants WHERE slug = $1")
+        .bind(&payload.slug)
+        .fetch_optional(&pool)
+        .await?;
+
+    if let Some(existing) = by_id.or(by_slug) {
+        let same_id = payload.id.is_none_or(|id| id == existing.id);
+        if !same_id || !tenant_matches(&existing, name, &payload.slug, payload.description.as_deref()) {
+            return Err(ApiError::conflict(format!(
+                "Tenant {} (slug '{}') already exists with different attributes",
+                existing.id, existing.slug
+            )));
+        }
+        if query.preview {
+            return Ok(plan_response("noop", Some(existing), Vec::new()));
+        }
+        return Ok((StatusCode::OK, Json(existing)).into_response());
+    }
+
+    if query.preview {
+        let planned = Tenant {
+            id: payload.id.unwrap_or_else(Uuid::nil),
+            name: name.to_string(),
+            slug: payload.slug.clone(),
+            description: payload.description.clone(),
+            timezone: None,
+            created_at: Utc::now(),
+        };
+        let changes = change_history::diff_snapshots(&serde_json::Value::Null, &serde_json::to_value(&planned).unwrap_or_default());
+        return Ok(plan_response::<Tenant>("create", None, changes));
+    }
+
+    let tenant = sqlx::query_as::<_, Tenant>(
+        "INSERT INTO tenants (id, name, slug, description) VALUES (COALESCE($1, gen_random_uuid()), $2, $3, $4) RETURNING *",
+    )
+    .bind(payload.id)
+    .bind(name)
+    .bind(&payload.slug)
+    .bind(&payload.description)
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| match ApiError::from(e) {
+        err if err.status == StatusCode::CONFLICT => {
+            ApiError::conflict(format!("Tenant with slug '{}' already exists", payload.slug))
+        }
+        err => err,
+    })?;
+
+    Ok((StatusCode::CREATED, Json(tenant)).into_response())
+}
+
+/// PUT /api/v2/tenants/{tenant_id} - Update tenanta
+async fn update_tenant(
+    State(pool): State<PgPool>,
+    path: Result<Path<Uuid>, PathRejection>,
+    query: Result<Query<PreviewQuery>, QueryRejection>,
+    payload: Result<Json<UpdateTenantRequest>, JsonRejection>,
+) -> ApiResult<Response> {
+    let Path(id) = path?;
+    let Query(query) = query?;
+    let Json(payload) = payload?;
+
+    let mut fields = Vec::new();
+    validate_name(&mut fields, &payload.name);
+    if !fields.is_empty() {
+        return Err(ApiError::validation(fields));
+    }
+
+    if query.preview {
+        let current = find_tenant(&pool, id)
+            .await?
+            .ok_or_else(|| ApiError::not_found(format!("Tenant {} not found", id)))?;
+        let planned = Tenant {
+            name: payload.name.trim().to_string(),
+            description: payload.description.clone(),
+            ..current.clone()
+        };
+        let changes = change_history::diff_snapshots(&current, &planned);
+        let action = if changes.is_empty() { "noop" } else { "update" };
+        return Ok(plan_response(action, Some(current), changes));
+    }
+
+    sqlx::query_as::<_, Tenant>(
+        "UPDATE tenants SET name = $1, description = $2 WHERE id = $3 RETURNING *",
+    )
+    .bind(payload.name.trim())
+    .bind(&payload.description)
+    .bind(id)
+    .fetch_optional(&pool)
+    .await?
+    .map(|tenant| Json(tenant).into_response())
+    .ok_or_else(|| ApiError::not_found(format!("Tenant {} not found", id)))
+}
+
+/// DELETE /api/v2/tenants/{tenant_id} - Smazání tenanta
+async fn delete_tenant(
+    State(pool): State<PgPool>,
+    path: Result<Path<Uuid>, PathRejection>,
+    query: Result<Query<PreviewQuery>, QueryRejection>,
+) -> ApiResult<Response> {
+    let Path(id) = path?;
+    let Query(query) = query?;
+
+    if query.preview {
+        let current = find_tenant(&pool, id)
+            .await?
+            .ok_or_else(|| ApiError::not_found(format!("Tenant {} not found", id)))?;
+        let changes = change_history::diff_snapshots(&serde_json::to_value(&current).unwrap_or_default(), &serde_json::Value::Null);
+        return Ok(plan_response("delete", Some(current), changes));
+    }
+
+    let result = sqlx::query("DELETE FROM tenants WHERE id = $1")
+        .bind(id)
+        .execute(&pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::not_found(format!("Tenant {} not found", id)));
+    }
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// GET /api/v2/resources - Popis resource typů pro IaC klienty
+async fn list_resources() -> Json<ListResponse<&'static ResourceDescriptor>> {
+    Json(RESOURCES.iter().collect::<Vec<_>>().into())
+}
+
+/// GET /api/v2/tenants/by-slug/{slug} - Tenant podle slugu (import)
+async fn get_tenant_by_slug(
+    Extension(auth): Extension<AuthContext>,
+    State(pool): State<PgPool>,
+    path: Result<Path<String>, PathRejection>,
+) -> ApiResult<Json<Tenant>> {
+    let Path(slug) = path?;
+    sqlx::query_as::<_, Tenant>("SELECT * FROM tenants WHERE slug = $1")
+        .bind(&slug)
+        .fetch_optional(&pool)
+        .await?
+        // Tenant mimo scope uživatele se tváří jako neexistující
+        .filter(|tenant| auth.is_admin() || auth.tenant_ids.contains(&tenant.id))
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(format!("Tenant with slug '{}' not found", slug)))
+}
+
+/// GET /api/v2/tenants/{tenant_id}/registries/by-name/{name} - Registry podle jména (import)
+async fn get_registry_by_name(
+    State(pool): State<PgPool>,
+    path: Result<Path<(Uuid, String)>, PathRejection>,
+) -> ApiResult<Json<Registry>> {
+    let Path((tenant_id, name)) = path?;
+    sqlx::query_as::<_, Registry>("SELECT * FROM registries WHERE tenant_id = $1 AND name = $2")
+        .bind(tenant_id)
+        .bind(&name)
+        .fetch_optional(&pool)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(format!("Registry '{}' not found in tenant {}", name, tenant_id)))
+}
+
+/// GET /api/v2/tenants/{tenant_id}/environments/by-slug/{slug} - Prostředí podle slugu (import)
+async fn get_environment_by_slug(
+    State(pool): State<PgPool>,
+    path: Result<Path<(Uuid, String)>, PathRejection>,
+) -> ApiResult<Json<Environment>> {
+    let Path((tenant_id, slug)) = path?;
+    sqlx::query_as::<_, Environment>("SELECT * FROM environments WHERE tenant_id = $1 AND slug = $2")
+        .bind(tenant_id)
+        .bind(&slug)
+        .fetch_optional(&pool)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(format!("Environment '{}' not found in tenant {}", slug, tenant_id)))
+}
+
+/// GET /api/v2/tenants/{tenant_id}/bundles/by-name/{name} - Bundle podle jména (import)
+async fn get_bundle_by_name(
+    State(pool): State<PgPool>,
+    path: Result<Path<(Uuid, String)>, PathRejection>,
+) -> ApiResult<Json<Bundle>> {
+    let Path((tenant_id, name)) = path?;
+    sqlx::query_as::<_, Bundle>("SELECT * FROM bundles WHERE tenant_id = $1 AND name = $2")
+        .bind(tenant_id)
+        .bind(&name)
+        .fetch_optional(&pool)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(format!("Bundle '{}' not found in tenant {}", name, tenant_id)))
+}
+
+/// GET /api/v2/tenants/{tenant_id}/registries - Registry tenanta
+async fn list_registries(
+    State(pool): State<PgPool>,
+    path: Result<Path<Uuid>, PathRejection>,
+) -> ApiResult<Json<ListResponse<Registry>>> {
+    let Path(tenant_id) = path?;
+    let registries = sqlx::query_as::<_, Registry>(
+        "SELECT * FROM registries WHERE tenant_id = $1 ORDER BY created_at DESC",
+    )
+    .bind(tenant_id)
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(registries.into()))
+}
+
+/// GET /api/v2/tenants/{tenant_id}/environments - Prostředí tenanta
+async fn list_environments(
+    State(pool): State<PgPool>,
+    path: Result<Path<Uuid>, PathRejection>,
+) -> ApiResult<Json<ListResponse<Environment>>> {
+    let Path(tenant_id) = path?;
+    let environments = sqlx::query_as::<_, Environment>(
+        "SELECT * FROM environments WHERE tenant_id = $1 ORDER BY name",
+    )
+    .bind(tenant_id)
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(environments.into()))
+}
+
+/// GET /api/v2/tenants/{tenant_id}/bundles - Bundles tenanta
+async fn list_bundles(
+    State(pool): State<PgPool>,
+    path: Result<Path<Uuid>, PathRejection>,
+) -> ApiResult<Json<ListResponse<Bundle>>> {
+    let Path(tenant_id) = path?;
+    let bundles = sqlx::query_as::<_, Bundle>(
+        "SELECT * FROM bundles WHERE tenant_id = $1 ORDER BY created_at DESC",
+    )
+    .bind(tenant_id)
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(bundles.into()))
+}
+
+/// GET /api/v2/registries/{registry_id} - Detail registry
+async fn get_registry(
+    State(pool): State<PgPool>,
+    path: Result<Path<Uuid>, PathRejection>,
+) -> ApiResult<Json<Registry>> {
+    let Path(id) = path?;
+    sqlx::query_as::<_, Registry>("SELECT * FROM registries WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&pool)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(format!("Registry {} not found", id)))
+}
+
+/// GET /api/v2/environments/{environment_id} - Detail prostředí
+async fn get_environment(
+    State(pool): State<PgPool>,
+    path: Result<Path<Uuid>, PathRejection>,
+) -> ApiResult<Json<Environment>> {
+    let Path(id) = path?;
+    sqlx::query_as::<_, Environment>("SELECT * FROM environments WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&pool)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(format!("Environment {} not found", id)))
+}
+
+/// GET /api/v2/bundles/{bundle_id} - Detail bundle
+async fn get_bundle(
+    State(pool): State<PgPool>,
+    path: Result<Path<Uuid>, PathRejection>,
+) -> ApiResult<Json<Bundle>> {
+    let Path(id) = path?;
+    sqlx::query_as::<_, Bundle>("SELECT * FROM bundles WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&pool)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(format!("Bundle {} not found", id)))
+}
+
+/// GET /api/v2/copy-jobs/{job_id} - Detail copy jobu
+async fn get_copy_job(
+    State(pool): State<PgPool>,
+    path: Result<Path<Uuid>, PathRejection>,
+) -> ApiResult<Json<CopyJob>> {
+    let Path(id) = path?;
+    sqlx::query_as::<_, CopyJob>("SELECT * FROM copy_jobs WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&pool)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(format!("Copy job {} not found", id)))
+}
+
+/// GET /api/v2/deploy-jobs/{job_id} - Detail deploy jobu
+async fn get_deploy_job(
+    State(pool): State<PgPool>,
+    path: Result<Path<Uuid>, PathRejection>,
+) -> ApiResult<Json<DeployJob>> {
+    let Path(id) = path?;
+    sqlx::query_as::<_, DeployJob>("SELECT * FROM deploy_jobs WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&pool)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(format!("Deploy job {} not found", id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slug_validation() {
+        let mut fields = Vec::new();
+        validate_slug(&mut fields, "team-a1");
+        assert!(fields.is_empty());
+
+        for slug in ["", "Team", "-a", "a-", "a_b"] {
+            let mut fields = Vec::new();
+            validate_slug(&mut fields, slug);
+            assert_eq!(fields.len(), 1, "slug {:?}", slug);
+            assert_eq!(fields[0].field, "slug");
+        }
+    }
+
+    #[test]
+    fn resource_catalog_is_consistent() {
+        let kinds: Vec<&str> = RESOURCES.iter().map(|r| r.kind).collect();
+        assert_eq!(kinds, vec!["tenant", "registry", "environment", "bundle"]);
+        for resource in RESOURCES {
+            assert!(resource.lookup.ends_with(&format!("{{{}}}", resource.import_key)), "{}", resource.kind);
+            assert!(resource.immutable_fields.contains(&"id"));
+            for endpoint in [resource.lookup, resource.read, resource.list, resource.create, resource.update, resource.delete] {
+                let (method, path) = endpoint.split_once(' ').unwrap();
+                assert!(["GET", "POST", "PUT", "DELETE"].contains(&method), "{}", endpoint);
+                assert!(path.starts_with("/api/v1/") || path.starts_with("/api/v2/"), "{}", endpoint);
+            }
+        }
+    }
+
+    #[test]
+    fn tenant_create_replay_matches_only_same_attributes() {
+        let tenant = Tenant {
+            id: Uuid::new_v4(),
+            name: "Team A".to_string(),
+            slug: "team-a".to_string(),
+            description: None,
+            timezone: Some("Europe/Prague".to_string()),
+            created_at: Utc::now(),
+        };
+        assert!(tenant_matches(&tenant, "Team A", "team-a", None));
+        assert!(!tenant_matches(&tenant, "Team A", "team-a", Some("x")));
+        assert!(!tenant_matches(&tenant, "Team B", "team-a", None));
+    }
+}
@@ -10,6 +10,8 @@ use sqlx::PgPool;
 use tracing::warn;
 use uuid::Uuid;
 
+use crate::api::error::ApiError;
+
 const HEADER_USER: &str = "x-auth-user";
 const HEADER_EMAIL: &str = "x-auth-email";
 const HEADER_GROUPS: &str = "x-auth-groups";
@@ -39,13 +41,14 @@ pub async fn auth_middleware(mut req: Request<Body>, next: Next) -> Response {
     if is_public_path(path) {
         return next.run(req).await;
     }
+    let policy_path = canonical_api_path(path);
 
     let headers = req.headers();
     let username = match get_header(headers, HEADER_USER) {
         Some(value) => value,
         None => {
             warn!(method = %req.method(), path, "Missing X-Auth-User");
-            return (StatusCode::UNAUTHORIZED, "Missing X-Auth-User").into_response();
+            return deny(path, StatusCode::UNAUTHORIZED, "Missing X-Auth-User");
         }
     };
 
@@ -53,7 +56,7 @@ pub async fn auth_middleware(mut req: Request<Body>, next: Next) -> Response {
         Some(value) => value,
         None => {
             warn!(user = %username, method = %req.method(), path, "Missing X-Auth-Groups");
-            return (StatusCode::FORBIDDEN, "Missing X-Auth-Groups").into_response();
+            return deny(path, StatusCode::FORBIDDEN, "Missing X-Auth-Groups");
         }
     };
 
@@ -61,14 +64,14 @@ pub async fn auth_middleware(mut req: Request<Body>, next: Next) -> Response {
     let roles = roles_from_groups(&groups);
     if roles.is_empty() {
         warn!(user = %username, method = %req.method(), path, "No role assigned");
-        return (StatusCode::FORBIDDEN, "No role assigned").into_response();
+        return deny(path, StatusCode::FORBIDDEN, "No role assigned");
     }
 
     let tenant_slugs = tenant_slugs_from_groups(&groups);
     let is_admin = roles.contains(&Role::Admin);
     if !is_admin && tenant_slugs.is_empty() {
         warn!(user = %username, method = %req.method(), path, "No tenant scope assigned");
-        return (StatusCode::FORBIDDEN, "No tenant scope assigned").into_response();
+        return deny(path, StatusCode::FORBIDDEN, "No tenant scope assigned");
     }
 
     let tenant_ids = if is_admin {
@@ -78,24 +81,22 @@ pub async fn auth_middleware(mut req: Request<Body>, next: Next) -> Response {
             Ok(ids) if !ids.is_empty() => ids,
             Ok(_) => {
                 warn!(user = %username, method = %req.method(), path, "Tenant scope does not match any tenant");
-                return (StatusCode::FORBIDDEN, "Tenant scope invalid").into_response();
+                return deny(path, StatusCode::FORBIDDEN, "Tenant scope invalid");
             }
             Err(err) => {
                 warn!(user = %username, method = %req.method(), path, error = %err, "Tenant scope lookup failed");
-                return (StatusCode::INTERNAL_SERVER_ERROR, "Tenant scope lookup failed").into_response();
+                return deny(path, StatusCode::INTERNAL_SERVER_ERROR, "Tenant scope lookup failed");
             }
         }
     };
 
-    if !is_admin {
-        if let Some(pool) = req.extensions().get::<PgPool>() {
-            if let Ok(Some(request_tenant_id)) = resolve_request_tenant(pool, path).await {
-                if !tenant_ids.contains(&request_tenant_id) {
-                    warn!(user = %username, method = %req.method(), path, "Tenant access denied");
-                    return (StatusCode::FORBIDDEN, "Tenant access denied").into_response();
-                }
-            }
-        }
+    if !is_admin
+        && let Some(pool) = req.extensions().get::<PgPool>()
+        && let Ok(Some(request_tenant_id)) = resolve_request_tenant(pool, &policy_path).await
+        && !tenant_ids.contains(&request_tenant_id)
+    {
+        warn!(user = %username, method = %req.method(), path, "Tenant access denied");
+        return deny(path, StatusCode::FORBIDDEN, "Tenant access denied");
     }
 
     let ctx = AuthContext {
@@ -107,14 +108,14 @@ pub async fn auth_middleware(mut req: Request<Body>, next: Next) -> Response {
         tenant_ids,
     };
 
-    if !is_authorized(req.method().as_str(), path, &roles) {
+    if !is_authorized(req.method().as_str(), &policy_path, &roles) {
         warn!(
             user = %ctx.username,
             method = %req.method(),
             path,
             "Insufficient role"
         );
-        return (StatusCode::FORBIDDEN, "Insufficient role").into_response();
+        return deny(path, StatusCode::FORBIDDEN, "Insufficient role");
     }
 
     req.extensions_mut().insert(ctx);
@@ -211,10 +212,44 @@ fn tenant_slugs_from_groups(groups: &[String]) -> Vec<String> {
     slugs
 }
 
+/// Chybová odpověď auth vrstvy; /api/v2 dostává jednotný error envelope
+fn deny(path: &str, status: StatusCode, message: &'static str) -> Response {
+    if !path.starts_with("/api/v2/") {
+        return (status, message).into_response();
+    }
+    match status {
+        StatusCode::UNAUTHORIZED => ApiError::unauthorized(message),
+        StatusCode::FORBIDDEN => ApiError::forbidden(message),
+        _ => ApiError::internal(message),
+    }
+    .into_response()
+}
+
+/// Mapuje /api/v2 cesty na ekvivalentní /api/v1 cesty, aby obě verze sdílely
+/// stejná pravidla rolí a tenant scope.
+fn canonical_api_path(path: &str) -> String {
+    let Some(rest) = path.strip_prefix("/api/v2") else {
+        return path.to_string();
+    };
+    let rest = if let Some(tail) = rest.strip_prefix("/copy-jobs") {
+        format!("/copy/jobs{}", tail)
+    } else if let Some(tail) = rest.strip_prefix("/deploy-jobs") {
+        format!("/deploy/jobs{}", tail)
+    } else {
+        rest.to_string()
+    };
+    format!("/api/v1{}", rest)
+}
+
 fn is_public_path(path: &str) -> bool {
     path == "/health"
         || path == "/healthz"
+        || path == "/metrics"
         || path.starts_with("/public/contract/")
+        // Agenti se autentizují vlastním bearer tokenem (ověřuje api::agents)
+        || path.starts_with("/api/v1/agent/")
+        // Sdílené odkazy nesou podepsaný token (ověřuje api::share_links)
+        || path.starts_with("/api/v1/shared/")
 }
 
 fn is_authorized(method: &str, path: &str, roles: &[Role]) -> bool {
@@ -222,7 +257,7 @@ fn is_authorized(method: &str, path: &str, roles: &[Role]) -> bool {
         return true;
     }
 
-    let is_read = matches!(method, "GET" | "HEAD" | "OPTIONS");
+    let is_read = matches!(method, "GET" | "HEAD" | "OPTIONS") || is_read_only_post(path);
     if is_read {
         return roles.contains(&Role::Viewer)
             || roles.contains(&Role::Developer)
@@ -236,8 +271,15 @@ fn is_authorized(method: &str, path: &str, roles: &[Role]) -> bool {
     }
 }
 
+/// POST endpointy, které jen čtou (dotaz v body)
+fn is_read_only_post(path: &str) -> bool {
+    path == "/api/v1/jobs/status"
+}
+
 fn required_write_role(path: &str) -> Option<Role> {
-    if is_deploy_action(path) {
+    if is_admin_only_path(path) {
+        None
+    } else if is_deploy_action(path) {
         Some(Role::DeployManager)
     } else if is_developer_write_path(path) {
         Some(Role::Developer)
@@ -246,6 +288,12 @@ fn required_write_role(path: &str) -> Option<Role> {
     }
 }
 
+/// Cesty pod developer prefixy, které přesto smí měnit jen admin
+fn is_admin_only_path(path: &str) -> bool {
+    // Přepsání binárek nástrojů = spouštění libovolného souboru na serveru
+    path.ends_with("/tool-paths")
+}
+
 fn is_deploy_action(path: &str) -> bool {
     if path == "/api/v1/deploy/jobs" || path == "/api/v1/deploy/jobs/from-copy" {
         return true;
@@ -279,6 +327,7 @@ fn is_developer_write_path(path: &str) -> bool {
         || path.starts_with("/api/v1/releases")
         || path.starts_with("/api/v1/copy")
         || path.starts_with("/api/v1/environments")
+        || path.starts_with("/api/v1/pipelines")
 }
 
 async fn resolve_tenant_ids(pool: Option<&PgPool>, slugs: &[String]) -> Result<Vec<Uuid>, sqlx::Error> {
@@ -313,6 +362,26 @@ async fn resolve_request_tenant(pool: &PgPool, path: &str) -> Result<Option<Uuid
         return tenant_id_for_table(pool, "environments", id).await;
     }
 
+    if let Some(id) = extract_uuid_after(path, "/api/v1/image-policies/") {
+        return tenant_id_for_table(pool, "image_policies", id).await;
+    }
+
+    if let Some(id) = extract_uuid_after(path, "/api/v1/agents/") {
+        return tenant_id_for_table(pool, "agents", id).await;
+    }
+
+    if let Some(id) = extract_uuid_after(path, "/api/v1/share-links/") {
+        return tenant_id_for_table(pool, "share_links", id).await;
+    }
+
+    if let Some(id) = extract_uuid_after(path, "/api/v1/deploy-targets/") {
+        return tenant_id_for_table(pool, "deploy_targets", id).await;
+    }
+
+    if let Some(id) = extract_uuid_after(path, "/api/v1/manifest-destinations/") {
+        return tenant_id_for_table(pool, "manifest_destinations", id).await;
+    }
+
     if let Some(id) = extract_uuid_after(path, "/api/v1/argocd/") {
         return tenant_id_for_table(pool, "argocd_instances", id).await;
     }
@@ -377,6 +446,10 @@ async fn resolve_request_tenant(pool: &PgPool, path: &str) -> Result<Option<Uuid
         return Ok(tenant_id);
     }
 
+    if let Some(id) = extract_uuid_after(path, "/api/v1/pipelines/") {
+        return tenant_id_for_table(pool, "pipeline_runs", id).await;
+    }
+
     Ok(None)
 }
 
@@ -515,5 +588,41 @@ mod tests {
         assert!(!is_authorized("POST", "/api/v1/registries", &deploy_manager));
 
         assert!(!is_authorized("POST", "/api/v1/unknown", &developer));
+        assert!(is_authorized("POST", "/api/v1/jobs/status", &viewer));
+        assert!(is_authorized("POST", "/api/v1/pipelines/trigger", &developer));
+        assert!(!is_authorized("POST", "/api/v1/pipelines/trigger", &viewer));
+
+        // Image policies spravuje jen admin
+        assert!(!is_authorized("POST", "/api/v1/image-policies", &developer));
+        assert!(is_authorized("GET", "/api/v1/image-policies/123", &viewer));
+
+        // Přepsání cest k nástrojům jen admin, i pod developer prefixy
+        assert!(!is_authorized("PUT", "/api/v1/environments/123/tool-paths", &developer));
+        assert!(!is_authorized("PUT", "/api/v1/tenants/123/tool-paths", &deploy_manager));
+        assert!(is_authorized("GET", "/api/v1/environments/123/tool-paths", &viewer));
+        assert!(is_authorized("PUT", "/api/v1/environments/123", &developer));
+
+        // Sdílené odkazy vytváří a ruší jen admin
+        assert!(!is_authorized("POST", "/api/v1/share-links", &deploy_manager));
+        assert!(!is_authorized("DELETE", "/api/v1/share-links/123", &developer));
+        assert!(is_authorized("GET", "/api/v1/share-links/123/access-log", &viewer));
+        // Šablony souborů deploy targetu mohou nést secrety
+        assert!(!is_authorized("PUT", "/api/v1/deploy-targets/123/file-templates", &developer));
+        assert!(is_authorized("GET", "/api/v1/deploy-targets/123/file-templates", &viewer));
+        assert!(!is_authorized("POST", "/api/v1/deploy-targets/env-templates/apply", &developer));
+        assert!(is_public_path("/api/v1/shared/token/logs"));
+    }
+
+    #[test]
+    fn v2_paths_share_v1_policy() {
+        assert_eq!(canonical_api_path("/api/v2/tenants"), "/api/v1/tenants");
+        assert_eq!(canonical_api_path("/api/v2/copy-jobs/abc"), "/api/v1/copy/jobs/abc");
+        assert_eq!(canonical_api_path("/api/v2/deploy-jobs/abc"), "/api/v1/deploy/jobs/abc");
+        assert_eq!(canonical_api_path("/api/v1/bundles"), "/api/v1/bundles");
+
+        let viewer = vec![Role::Viewer];
+        let developer = vec![Role::Developer];
+        assert!(!is_authorized("POST", &canonical_api_path("/api/v2/tenants"), &viewer));
+        assert!(is_authorized("POST", &canonical_api_path("/api/v2/tenants"), &developer));
     }
 }
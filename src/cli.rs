@@ -0,0 +1,240 @@
+use anyhow::{anyhow, Result};
+use clap::{Args, Subcommand};
+use srm_client::{
+    types::{copy::CopyBundleRequest, deploy::CreateDeployJobRequest, releases::CreateReleaseRequest},
+    Client,
+};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// `srm` - CLI klient pro HTTP API
+#[derive(Debug, Args)]
+pub struct SrmArgs {
+    /// Base URL of the SRM server (e.g. https://srm.example.com)
+    #[arg(long, env = "SRM_URL", default_value = "http://127.0.0.1:3000")]
+    pub url: String,
+
+    /// API token sent as `Authorization: Bearer <token>`
+    #[arg(long, env = "SRM_TOKEN", hide_env_values = true)]
+    pub token: Option<String>,
+
+    /// Poll interval in seconds for --wait / --follow
+    #[arg(long, default_value_t = 2)]
+    pub poll_seconds: u64,
+
+    #[command(subcommand)]
+    pub command: SrmCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SrmCommand {
+    /// Copy jobs
+    #[command(subcommand)]
+    Copy(CopyCommand),
+    /// Releases
+    #[command(subcommand)]
+    Release(ReleaseCommand),
+    /// Deploy jobs
+    #[command(subcommand)]
+    Deploy(DeployCommand),
+    /// Show jobs waiting to start and running jobs per worker
+    Queue,
+    /// Check the local server configuration (database, migrations, tools, credentials, temp dir) without starting it
+    Doctor,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CopyCommand {
+    /// Create and start a copy job for a bundle version
+    Start {
+        #[arg(long)]
+        bundle: Uuid,
+        #[arg(long)]
+        version: i32,
+        #[arg(long)]
+        target_tag: Option<String>,
+        #[arg(long)]
+        environment: Option<Uuid>,
+        /// Replace the target tag even if another job already owns it
+        #[arg(long)]
+        overwrite: bool,
+        /// Wait until the job finishes (exit code 1 on failure)
+        #[arg(long)]
+        wait: bool,
+    },
+    /// Show copy job status
+    Status { job_id: Uuid },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ReleaseCommand {
+    /// Create a release from a successful copy job
+    Create {
+        #[arg(long)]
+        copy_job: Uuid,
+        #[arg(long)]
+        release_id: String,
+        #[arg(long)]
+        notes: Option<String>,
+        /// Release channel: rc, stable (default) or hotfix
+        #[arg(long)]
+        channel: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DeployCommand {
+    /// Create and start a deploy job
+    Start {
+        #[arg(long)]
+        release: Uuid,
+        #[arg(long)]
+        environment: Uuid,
+        #[arg(long)]
+        dry_run: bool,
+        /// Render and validate only, never write to git (writes a validation report)
+        #[arg(long)]
+        validate_only: bool,
+        /// Override an extra env var for this job (KEY=VALUE, repeatable; must be allow-listed by the environment)
+        #[arg(long = "env", value_parser = parse_env_override)]
+        env: Vec<(String, String)>,
+        /// Wait until the job finishes (exit code 1 on failure)
+        #[arg(long)]
+        wait: bool,
+    },
+    /// Show deploy job status; with --follow stream logs until the job finishes
+    Watch {
+        job_id: Uuid,
+        #[arg(long)]
+        follow: bool,
+    },
+}
+
+fn parse_env_override(value: &str) -> Result<(String, String), String> {
+    value
+        .split_once('=')
+        .map(|(key, value)| (key.trim().to_string(), value.to_string()))
+        .filter(|(key, _)| !key.is_empty())
+        .ok_or_else(|| format!("expected KEY=VALUE, got '{}'", value))
+}
+
+/// Spustí CLI příkaz, vrací exit code
+pub async fn run(args: SrmArgs) -> Result<i32> {
+    let mut client = Client::new(args.url);
+    if let Some(token) = args.token {
+        client = client.with_token(token);
+    }
+    let poll = Duration::from_secs(args.poll_seconds.max(1));
+
+    match args.command {
+        SrmCommand::Copy(CopyCommand::Start { bundle, version, target_tag, environment, overwrite, wait }) => {
+            let request = CopyBundleRequest {
+                target_tag,
+                environment_id: environment,
+                overwrite: overwrite.then_some(true),
+                ..Default::default()
+            };
+            let created = client.copy_bundle_version(bundle, version, &request).await?;
+            client.start_copy_job(created.job_id).await?;
+            println!("{}", created.job_id);
+            if wait {
+                return wait_for_job(&client, created.job_id, false, poll).await;
+            }
+        }
+        SrmCommand::Copy(CopyCommand::Status { job_id }) => {
+            let status = client.get_copy_job(job_id).await?;
+            println!("{}", serde_json::to_string_pretty(&status)?);
+        }
+        SrmCommand::Release(ReleaseCommand::Create { copy_job, release_id, notes, channel }) => {
+            let release = client
+                .create_release(&CreateReleaseRequest {
+                    copy_job_id: copy_job,
+                    release_id,
+                    notes,
+                    created_by: None,
+                    source_ref_mode: None,
+                    channel,
+                })
+                .await?;
+            println!("{}", serde_json::to_string_pretty(&release)?);
+        }
+        SrmCommand::Deploy(DeployCommand::Start { release, environment, dry_run, validate_only, env, wait }) => {
+            let created = client
+                .create_deploy_job(&CreateDeployJobRequest {
+                    release_id: release,
+                    environment_id: environment,
+                    dry_run: Some(dry_run),
+                    release_image_url_mode: None,
+                    validate_only: validate_only.then_some(true),
+                    env_overrides: (!env.is_empty()).then(|| env.into_iter().collect()),
+                })
+                .await?;
+            client.start_deploy_job(created.job_id).await?;
+            println!("{}", created.job_id);
+            if wait {
+                return wait_for_job(&client, created.job_id, false, poll).await;
+            }
+        }
+        SrmCommand::Deploy(DeployCommand::Watch { job_id, follow }) => {
+            if follow {
+                return wait_for_job(&client, job_id, true, poll).await;
+            }
+            let job = client.get_deploy_job(job_id).await?;
+            println!("{}", serde_json::to_string_pretty(&job)?);
+        }
+        SrmCommand::Doctor => unreachable!("srm doctor runs locally and is dispatched in main"),
+        SrmCommand::Queue => {
+            let queue = client.job_queue().await?;
+            for summary in &queue.summary {
+                println!(
+                    "{}: {} running, {} starting, {} queued, {} pending",
+                    summary.kind, summary.running, summary.starting, summary.queued, summary.pending
+                );
+            }
+            for job in &queue.jobs {
+                println!(
+                    "{}\t{}\t{}\t{}\t{}s\t{}",
+                    job.job_id,
+                    job.kind,
+                    job.queue_state,
+                    job.position.map(|p| format!("#{}", p)).unwrap_or_else(|| "-".to_string()),
+                    job.waiting_seconds,
+                    job.description
+                );
+            }
+        }
+    }
+
+    Ok(0)
+}
+
+/// Čeká na dokončení jobu (přes batch status), volitelně průběžně vypisuje deploy logy
+async fn wait_for_job(client: &Client, job_id: Uuid, follow_deploy_logs: bool, poll: Duration) -> Result<i32> {
+    let mut printed_lines = 0usize;
+    loop {
+        let job = client
+            .job_status(&[job_id])
+            .await?
+            .jobs
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("Job {} not found", job_id))?;
+
+        if follow_deploy_logs {
+            let lines = client.deploy_job_log_history(job_id).await?;
+            for line in lines.iter().skip(printed_lines) {
+                println!("{}", line);
+            }
+            printed_lines = printed_lines.max(lines.len());
+        }
+
+        if job.is_finished {
+            eprintln!("Job {} finished: {}", job_id, job.status);
+            if let Some(error) = job.error_message {
+                eprintln!("{}", error);
+            }
+            return Ok(if job.status == "success" { 0 } else { 1 });
+        }
+        tokio::time::sleep(poll).await;
+    }
+}
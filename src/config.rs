@@ -1,8 +1,16 @@
 use anyhow::{Context, Result};
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::env;
 
+use crate::services::image_tool::COPY_FORMATS;
+use crate::services::notifications::parse_owner_webhooks;
+use crate::services::object_storage::ObjectStorageConfig;
+use crate::services::reaper::ReaperConfig;
+use crate::services::release_tag_cleanup::{self, ReleaseTagCleanup};
+use crate::services::sandbox::{SandboxConfig, SandboxMode, SandboxPolicy, SandboxTool};
+
 /// CLI arguments
 #[derive(Debug, Parser)]
 #[command(name = "simple-release-management")]
@@ -19,6 +27,45 @@ pub struct CliArgs {
     /// Disable authorization middleware (development/testing only)
     #[arg(long, default_value_t = false)]
     pub disable_auth: bool,
+
+    /// Process role: `all` (API + job execution), `web` (API only, jobs go to the DB queue),
+    /// `worker` (claims and runs queued jobs) or `agent` (runs copy and deploy jobs of assigned environments,
+    /// talks to the central server over HTTPS, no database)
+    #[arg(long, env = "SRM_ROLE", value_enum, default_value_t = ProcessRole::All)]
+    pub role: ProcessRole,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessRole {
+    All,
+    Web,
+    Worker,
+    Agent,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// CLI client for the HTTP API (also available as `srm` via symlink)
+    Srm(crate::cli::SrmArgs),
+}
+
+impl CliArgs {
+    /// Parse argv; při spuštění jako `srm` (symlink) se doplní subcommand `srm`
+    pub fn parse_with_alias() -> Self {
+        let mut args: Vec<std::ffi::OsString> = env::args_os().collect();
+        let invoked_as_srm = args
+            .first()
+            .and_then(|arg0| std::path::Path::new(arg0).file_stem())
+            .is_some_and(|stem| stem == "srm");
+        if invoked_as_srm {
+            args.insert(1, "srm".into());
+        }
+        Self::parse_from(args)
+    }
 }
 
 #[allow(dead_code)]
@@ -34,19 +81,50 @@ pub struct Config {
     pub image_tool_dst_insecure: bool,
     pub image_tool_extra_inspect_args: Vec<String>,
     pub image_tool_extra_copy_args: Vec<String>,
+    pub image_tool_inspect_cache_seconds: u64,
+    pub image_tool_copy_all: bool,
+    pub image_tool_copy_preserve_digests: bool,
+    pub image_tool_copy_format: Option<String>,
+    pub release_tag_cleanup: ReleaseTagCleanup,
+    pub copy_blob_reuse: bool,
     pub kube_build_app_path: String,
     pub apply_env_path: String,
     pub encjson_path: String,
     pub encjson_legacy_path: String,
     pub encjson_key_dir: Option<String>,
     pub kubeconform_path: String,
+    pub deploy_diff_max_bytes: usize,
+    pub deploy_diff_inline_max_bytes: usize,
     pub encryption_secret: String,
     pub max_concurrent_copy_jobs: usize,
+    pub precheck_concurrency: usize,
     pub copy_timeout_seconds: u64,
     pub copy_max_retries: u32,
     pub copy_retry_delay_seconds: u64,
     pub static_dir: Option<String>,
     pub auth_enabled: bool,
+    pub dashboard_refresh_seconds: u64,
+    pub base_image_check_seconds: u64,
+    pub credential_expiry_warn_days: i64,
+    pub credential_expiry_check_seconds: u64,
+    pub manifest_delivery_interval_seconds: u64,
+    pub manifest_delivery_max_attempts: u32,
+    pub notification_webhook_url: Option<String>,
+    pub notification_owner_webhooks: HashMap<String, String>,
+    pub role: ProcessRole,
+    pub worker_id: String,
+    pub worker_max_copy_jobs: usize,
+    pub worker_max_deploy_jobs: usize,
+    pub worker_poll_seconds: u64,
+    pub worker_lease_seconds: u64,
+    pub pipeline_step_timeout_seconds: u64,
+    pub agent_server_url: Option<String>,
+    pub agent_token: Option<String>,
+    pub agent_poll_seconds: u64,
+    pub log_fanout_enabled: bool,
+    pub sandbox: SandboxConfig,
+    pub object_storage: Option<ObjectStorageConfig>,
+    pub reaper: ReaperConfig,
 }
 
 impl Config {
@@ -74,9 +152,11 @@ impl Config {
             })
         });
 
+        // Agent nemá DB ani nešifruje credentials - dostává je hotové od serveru
+        let is_agent = cli.role == ProcessRole::Agent;
+
         let config = Config {
-            database_url: env::var("DATABASE_URL")
-                .context("DATABASE_URL must be set")?,
+            database_url: required_env("DATABASE_URL", is_agent)?,
 
             // CLI argumenty mají prioritu před ENV
             host: cli.host,
@@ -93,6 +173,27 @@ impl Config {
             image_tool_dst_insecure: parse_bool_env("IMAGE_TOOL_DST_INSECURE").unwrap_or(false),
             image_tool_extra_inspect_args: parse_command_args_env("IMAGE_TOOL_EXTRA_INSPECT_ARGS")?,
             image_tool_extra_copy_args: parse_command_args_env("IMAGE_TOOL_EXTRA_COPY_ARGS")?,
+            image_tool_inspect_cache_seconds: env::var("IMAGE_TOOL_INSPECT_CACHE_SECONDS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(60),
+            image_tool_copy_all: parse_bool_env("IMAGE_TOOL_COPY_ALL").unwrap_or(false),
+            image_tool_copy_preserve_digests: parse_bool_env("IMAGE_TOOL_COPY_PRESERVE_DIGESTS").unwrap_or(false),
+            image_tool_copy_format: env::var("IMAGE_TOOL_COPY_FORMAT")
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty()),
+            release_tag_cleanup: match env::var("RELEASE_TAG_CLEANUP") {
+                Ok(value) => ReleaseTagCleanup::parse(&value).with_context(|| {
+                    format!(
+                        "RELEASE_TAG_CLEANUP must be one of: {} (got '{}')",
+                        release_tag_cleanup::MODES.join(", "),
+                        value
+                    )
+                })?,
+                Err(_) => ReleaseTagCleanup::Off,
+            },
+            copy_blob_reuse: parse_bool_env("COPY_BLOB_REUSE").unwrap_or(true),
 
             kube_build_app_path: env::var("KUBE_BUILD_APP_PATH")
                 .unwrap_or_else(|_| "kube_build_app".to_string()),
@@ -114,14 +215,28 @@ impl Config {
             kubeconform_path: env::var("KUBECONFORM_PATH")
                 .unwrap_or_else(|_| "kubeconform".to_string()),
 
-            encryption_secret: env::var("ENCRYPTION_SECRET")
-                .context("ENCRYPTION_SECRET must be set")?,
+            encryption_secret: required_env("ENCRYPTION_SECRET", is_agent)?,
+
+            deploy_diff_max_bytes: env::var("DEPLOY_DIFF_MAX_BYTES")
+                .unwrap_or_else(|_| "52428800".to_string())
+                .parse()
+                .unwrap_or(52_428_800),
+
+            deploy_diff_inline_max_bytes: env::var("DEPLOY_DIFF_INLINE_MAX_BYTES")
+                .unwrap_or_else(|_| "2097152".to_string())
+                .parse()
+                .unwrap_or(2_097_152),
 
             max_concurrent_copy_jobs: env::var("MAX_CONCURRENT_COPY_JOBS")
                 .unwrap_or_else(|_| "3".to_string())
                 .parse()
                 .unwrap_or(3),
 
+            precheck_concurrency: env::var("PRECHECK_CONCURRENCY")
+                .unwrap_or_else(|_| "8".to_string())
+                .parse()
+                .unwrap_or(8),
+
             copy_timeout_seconds: env::var("COPY_TIMEOUT_SECONDS")
                 .unwrap_or_else(|_| "3600".to_string())
                 .parse()
@@ -143,8 +258,124 @@ impl Config {
                 .filter(|v| !v.is_empty()),
 
             auth_enabled,
+
+            dashboard_refresh_seconds: env::var("DASHBOARD_REFRESH_SECONDS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+
+            base_image_check_seconds: env::var("BASE_IMAGE_CHECK_SECONDS")
+                .unwrap_or_else(|_| "21600".to_string())
+                .parse()
+                .unwrap_or(21600),
+
+            credential_expiry_warn_days: env::var("CREDENTIAL_EXPIRY_WARN_DAYS")
+                .unwrap_or_else(|_| "14".to_string())
+                .parse::<i64>()
+                .unwrap_or(14)
+                .max(0),
+
+            credential_expiry_check_seconds: env::var("CREDENTIAL_EXPIRY_CHECK_SECONDS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .unwrap_or(3600),
+
+            manifest_delivery_interval_seconds: env::var("MANIFEST_DELIVERY_INTERVAL_SECONDS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+
+            manifest_delivery_max_attempts: env::var("MANIFEST_DELIVERY_MAX_ATTEMPTS")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .unwrap_or(5),
+
+            notification_webhook_url: env::var("NOTIFICATION_WEBHOOK_URL")
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty()),
+
+            notification_owner_webhooks: env::var("NOTIFICATION_OWNER_WEBHOOKS")
+                .map(|v| parse_owner_webhooks(&v))
+                .unwrap_or_default(),
+
+            role: cli.role,
+
+            worker_id: env::var("WORKER_ID")
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .unwrap_or_else(|| {
+                    let host = env::var("HOSTNAME").unwrap_or_else(|_| "worker".to_string());
+                    format!("{}-{}", host, std::process::id())
+                }),
+
+            worker_max_copy_jobs: env::var("WORKER_MAX_COPY_JOBS")
+                .unwrap_or_else(|_| "2".to_string())
+                .parse()
+                .unwrap_or(2),
+
+            worker_max_deploy_jobs: env::var("WORKER_MAX_DEPLOY_JOBS")
+                .unwrap_or_else(|_| "2".to_string())
+                .parse()
+                .unwrap_or(2),
+
+            worker_poll_seconds: env::var("WORKER_POLL_SECONDS")
+                .unwrap_or_else(|_| "2".to_string())
+                .parse()
+                .unwrap_or(2),
+
+            worker_lease_seconds: env::var("WORKER_LEASE_SECONDS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .unwrap_or(60),
+
+            pipeline_step_timeout_seconds: env::var("PIPELINE_STEP_TIMEOUT_SECONDS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .unwrap_or(3600),
+
+            agent_server_url: env::var("AGENT_SERVER_URL")
+                .ok()
+                .map(|v| v.trim().trim_end_matches('/').to_string())
+                .filter(|v| !v.is_empty()),
+
+            agent_token: env::var("AGENT_TOKEN")
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty()),
+
+            agent_poll_seconds: env::var("AGENT_POLL_SECONDS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .unwrap_or(10),
+
+            log_fanout_enabled: parse_bool_env("LOG_FANOUT_ENABLED").unwrap_or(true),
+
+            sandbox: parse_sandbox_config()?,
+
+            object_storage: parse_object_storage_config()?,
+
+            reaper: ReaperConfig {
+                interval_seconds: env::var("REAPER_INTERVAL_SECONDS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()
+                    .unwrap_or(300),
+                temp_max_age_hours: env::var("REAPER_TEMP_MAX_AGE_HOURS")
+                    .unwrap_or_else(|_| "6".to_string())
+                    .parse()
+                    .unwrap_or(6),
+            },
         };
 
+        if let Some(format) = config.image_tool_copy_format.as_deref().filter(|f| !COPY_FORMATS.contains(f)) {
+            anyhow::bail!(
+                "IMAGE_TOOL_COPY_FORMAT must be one of: {} (got '{}')",
+                COPY_FORMATS.join(", "),
+                format
+            );
+        }
+
         Ok(config)
     }
 
@@ -153,6 +384,14 @@ impl Config {
     }
 }
 
+/// Povinná proměnná prostředí; `optional` = role, která ji nepotřebuje (agent)
+fn required_env(name: &str, optional: bool) -> Result<String> {
+    match env::var(name) {
+        Err(_) if optional => Ok(String::new()),
+        value => value.with_context(|| format!("{} must be set", name)),
+    }
+}
+
 fn parse_bool_env(name: &str) -> Option<bool> {
     let raw = env::var(name).ok()?;
     let normalized = raw.trim().to_ascii_lowercase();
@@ -177,3 +416,100 @@ fn parse_command_args_env(name: &str) -> Result<Vec<String>> {
     shell_words::split(trimmed)
         .with_context(|| format!("Failed to parse {name} as shell-style arguments"))
 }
+
+fn parse_list_env(name: &str) -> Option<Vec<String>> {
+    let raw = env::var(name).ok()?;
+    Some(
+        raw.split(',')
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .collect(),
+    )
+}
+
+fn parse_optional_env<T: std::str::FromStr>(name: &str) -> Result<Option<T>> {
+    match env::var(name) {
+        Ok(raw) if !raw.trim().is_empty() => raw
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|_| anyhow::anyhow!("Invalid value for {name}: {raw}")),
+        _ => Ok(None),
+    }
+}
+
+fn parse_sandbox_mode_env(name: &str) -> Result<Option<SandboxMode>> {
+    match env::var(name) {
+        Ok(raw) => SandboxMode::parse(&raw)
+            .map(Some)
+            .with_context(|| format!("Invalid {name}: {raw} (expected none, bwrap or nsjail)")),
+        Err(_) => Ok(None),
+    }
+}
+
+/// SANDBOX_* = výchozí hodnoty, SANDBOX_<TOOL>_* je přepisují pro jeden nástroj
+fn parse_sandbox_config() -> Result<SandboxConfig> {
+    let default_mode = parse_sandbox_mode_env("SANDBOX_MODE")?.unwrap_or_default();
+    let default_cpu = parse_optional_env::<u64>("SANDBOX_CPU_SECONDS")?;
+    let default_memory = parse_optional_env::<u64>("SANDBOX_MEMORY_MB")?;
+    let default_restrict_env = parse_bool_env("SANDBOX_RESTRICT_ENV");
+
+    let mut policies = HashMap::new();
+    for tool in SandboxTool::ALL {
+        let prefix = format!("SANDBOX_{}", tool.env_name());
+        let mode = parse_sandbox_mode_env(&format!("{prefix}_MODE"))?.unwrap_or(default_mode);
+        let cpu_seconds = parse_optional_env::<u64>(&format!("{prefix}_CPU_SECONDS"))?.or(default_cpu);
+        let memory_mb = parse_optional_env::<u64>(&format!("{prefix}_MEMORY_MB"))?.or(default_memory);
+        // Omezené env je výchozí, jakmile je nástroj v bwrap/nsjail
+        let restrict_env = parse_bool_env(&format!("{prefix}_RESTRICT_ENV"))
+            .or(default_restrict_env)
+            .unwrap_or(mode != SandboxMode::None);
+        policies.insert(
+            tool,
+            SandboxPolicy {
+                mode,
+                cpu_seconds,
+                memory_mb,
+                restrict_env,
+            },
+        );
+    }
+
+    Ok(SandboxConfig {
+        uid: parse_optional_env("SANDBOX_UID")?,
+        gid: parse_optional_env("SANDBOX_GID")?,
+        env_allow: parse_list_env("SANDBOX_ENV_ALLOW").unwrap_or_default(),
+        writable_paths: parse_list_env("SANDBOX_WRITABLE_PATHS")
+            .unwrap_or_else(|| vec![env::temp_dir().to_string_lossy().into_owned()]),
+        bwrap_path: env::var("SANDBOX_BWRAP_PATH").unwrap_or_else(|_| "bwrap".to_string()),
+        nsjail_path: env::var("SANDBOX_NSJAIL_PATH").unwrap_or_else(|_| "nsjail".to_string()),
+        prlimit_path: env::var("SANDBOX_PRLIMIT_PATH").unwrap_or_else(|_| "prlimit".to_string()),
+        policies,
+    })
+}
+
+/// Object storage je zapnuté, jakmile je nastaven endpoint i bucket
+fn parse_object_storage_config() -> Result<Option<ObjectStorageConfig>> {
+    let endpoint = env::var("OBJECT_STORAGE_ENDPOINT").unwrap_or_default();
+    let bucket = env::var("OBJECT_STORAGE_BUCKET").unwrap_or_default();
+    if endpoint.trim().is_empty() || bucket.trim().is_empty() {
+        return Ok(None);
+    }
+
+    let mut prefix = env::var("OBJECT_STORAGE_PREFIX").unwrap_or_default().trim().trim_matches('/').to_string();
+    if !prefix.is_empty() {
+        prefix.push('/');
+    }
+
+    Ok(Some(ObjectStorageConfig {
+        endpoint: endpoint.trim().to_string(),
+        bucket: bucket.trim().to_string(),
+        region: env::var("OBJECT_STORAGE_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+        access_key: env::var("OBJECT_STORAGE_ACCESS_KEY").context("OBJECT_STORAGE_ACCESS_KEY must be set")?,
+        secret_key: env::var("OBJECT_STORAGE_SECRET_KEY").context("OBJECT_STORAGE_SECRET_KEY must be set")?,
+        prefix,
+        path_style: parse_bool_env("OBJECT_STORAGE_PATH_STYLE").unwrap_or(true),
+        archive_after_hours: parse_optional_env("OBJECT_STORAGE_ARCHIVE_AFTER_HOURS")?.unwrap_or(24),
+        archive_interval_seconds: parse_optional_env("OBJECT_STORAGE_ARCHIVE_INTERVAL_SECONDS")?.unwrap_or(600),
+    }))
+}
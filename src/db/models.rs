@@ -5,6 +5,8 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use crate::services::credential_expiry::ExpiryState;
+
 /// Tenant - základní organizační jednotka
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Tenant {
@@ -12,6 +14,92 @@ pub struct Tenant {
     pub name: String,
     pub slug: String,
     pub description: Option<String>,
+    /// IANA timezone pro datum auto tagů (např. `Europe/Prague`)
+    pub timezone: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Allow/deny pravidlo tenanta pro zdrojové images
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ImagePolicy {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub name: String,
+    /// `allow` nebo `deny`
+    pub effect: String,
+    /// Regex na host source registry (např. `docker\.io`), NULL = libovolná
+    pub registry_pattern: Option<String>,
+    /// Regex na cestu image v registry (např. `library/.*`), NULL = libovolná
+    pub repository_pattern: Option<String>,
+    /// Regex na slug environmentu (např. `prod.*`), NULL = všechny environmenty
+    pub environment_pattern: Option<String>,
+    pub description: Option<String>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Cíl doručení release manifestu (git / S3 / HTTP)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ManifestDestination {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub name: String,
+    /// `git`, `s3` nebo `http`
+    pub kind: String,
+    /// Jen releases tohoto bundle, NULL = všechny bundles tenanta
+    pub bundle_id: Option<Uuid>,
+    /// `yaml` nebo `json`
+    pub format: String,
+    /// Cesta v repozitáři / klíč objektu (např. `{bundle}/{release_id}.{ext}`)
+    pub path_template: String,
+    pub git_repository_id: Option<Uuid>,
+    /// NULL = výchozí větev repozitáře
+    pub git_branch: Option<String>,
+    /// HTTP: cílová URL; S3: endpoint (NULL = globální object storage)
+    pub url: Option<String>,
+    pub s3_bucket: Option<String>,
+    pub s3_region: Option<String>,
+    pub s3_access_key: Option<String>,
+    pub s3_path_style: bool,
+    /// HTTP bearer token / S3 secret key
+    #[serde(skip_serializing)]
+    pub secret_encrypted: Option<String>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Agent v izolované síti, který spouští copy joby přiřazených prostředí
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Agent {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub name: String,
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    pub is_active: bool,
+    /// Verze a hostname hlášené posledním heartbeatem
+    pub version: Option<String>,
+    pub hostname: Option<String>,
+    pub last_seen_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Dočasný podepsaný odkaz na logy / diff jobu
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ShareLink {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    /// `copy` nebo `deploy`
+    pub job_kind: String,
+    pub job_id: Uuid,
+    /// `logs`, `diff`
+    pub scopes: Vec<String>,
+    pub note: Option<String>,
+    pub created_by: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -71,9 +159,17 @@ pub struct Registry {
     pub password_encrypted: Option<String>,
     #[serde(skip_serializing)]
     pub token_encrypted: Option<String>,
+    /// Kdy přestanou platit uložené credentials (robot token, heslo)
+    pub credentials_expire_at: Option<DateTime<Utc>>,
+    /// Dopočítává API z `credentials_expire_at`
+    #[sqlx(skip)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credentials_expiry: Option<ExpiryState>,
     pub role: String,
     pub description: Option<String>,
     pub is_active: bool,
+    /// Přepsání retry politiky a copy flagů (`CopyOptionsOverride`)
+    pub copy_options: Option<serde_json::Value>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -138,6 +234,8 @@ pub struct ImageMapping {
     pub target_image: String,
     pub app_name: String,
     pub container_name: Option<String>,
+    /// Vlastník (uživatel / tým) - notifikace o selhání a skupina v release notes
+    pub owner: Option<String>,
 
     pub created_at: DateTime<Utc>,
 }
@@ -182,6 +280,11 @@ pub struct CopyJobImage {
     pub error_message: Option<String>,
     pub copied_at: Option<DateTime<Utc>>,
     pub bytes_copied: Option<i64>,
+    /// Odhad přenosu při znovupoužití vrstev (velikost vrstev, které cíl neměl)
+    pub estimated_transfer_bytes: Option<i64>,
+    /// Bajty, které cílová registry už měla nebo připojila z jiného repozitáře
+    pub bytes_reused: Option<i64>,
+    pub blobs_mounted: Option<i32>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -191,6 +294,7 @@ pub struct CopyJobLog {
     pub id: Uuid,
     pub copy_job_id: Uuid,
     pub line: String,
+    pub seq: i64,
     pub created_at: DateTime<Utc>,
 }
 
@@ -214,20 +318,7 @@ impl std::fmt::Display for ReleaseStatus {
 }
 
 /// Release - zamašličkovaný snapshot pro produkci
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
-pub struct Release {
-    pub id: Uuid,
-    pub copy_job_id: Uuid,
-    pub release_id: String,
-    pub status: String,
-    pub source_ref_mode: String,
-    pub notes: Option<String>,
-    pub created_by: Option<String>,
-    pub is_auto: bool,
-    pub auto_reason: Option<String>,
-    pub extra_tags: Option<Vec<String>>,
-    pub created_at: DateTime<Utc>,
-}
+pub use srm_api_types::releases::Release;
 
 /// Deploy target - definice build pipeline pro release
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -288,6 +379,12 @@ pub struct Environment {
     pub extra_env_vars: serde_json::Value,
     pub argocd_poll_interval_seconds: i32,
     pub kubernetes_poll_interval_seconds: i32,
+    /// Extra env proměnné, které smí přepsat deploy job (`KEY` nebo `PREFIX_*`)
+    pub job_env_override_allowlist: Vec<String>,
+    /// Povolené jsou jen validate-only deploy joby
+    pub validate_only_required: bool,
+    /// Kanály release, které environment odebírá (jiné do něj nejde nasadit)
+    pub release_channels: Vec<String>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -418,6 +515,36 @@ pub struct DeployTargetExtraEnvVar {
     pub value: String,
 }
 
+/// Šablona souboru generovaného do deploy výstupu (obsah je šifrovaný)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DeployTargetFileTemplate {
+    pub id: Uuid,
+    pub deploy_target_id: Uuid,
+    pub path: String,
+    #[serde(skip_serializing)]
+    pub content_encrypted: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Šablona napojení environmentu na deploy target (cesty/větve s proměnnými `{env_slug}`, ...)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DeployTargetEnvTemplate {
+    pub deploy_target_id: Uuid,
+    pub env_repo_id: Option<Uuid>,
+    pub env_repo_path: Option<String>,
+    pub env_repo_branch: Option<String>,
+    pub deploy_repo_id: Option<Uuid>,
+    pub deploy_repo_path: Option<String>,
+    pub deploy_repo_branch: Option<String>,
+    pub allow_auto_release: bool,
+    pub append_env_suffix: bool,
+    pub release_manifest_mode: String,
+    pub encjson_key_dir: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
 /// Git repository configuration per tenant
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct GitRepository {
@@ -433,6 +560,12 @@ pub struct GitRepository {
     pub git_token_encrypted: Option<String>,
     #[serde(skip_serializing)]
     pub git_ssh_key_encrypted: Option<String>,
+    /// Kdy přestane platit token / SSH klíč
+    pub credentials_expire_at: Option<DateTime<Utc>>,
+    /// Dopočítává API z `credentials_expire_at`
+    #[sqlx(skip)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credentials_expiry: Option<ExpiryState>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -463,6 +596,17 @@ pub struct DeployJob {
     pub tag_name: Option<String>,
     pub dry_run: bool,
     pub release_image_url_mode: String,
+    /// Extra env proměnné přepsané pro tento job (key -> value)
+    pub env_overrides: serde_json::Value,
+    /// Uživatel, který job s přepsáním vytvořil
+    pub env_overrides_by: Option<String>,
+    /// Snapshot výsledných proměnných při spuštění (`GET /deploy/jobs/{id}/env`)
+    #[serde(skip)]
+    pub resolved_env: Option<serde_json::Value>,
+    /// Jen render + validace, bez git zápisů (`GET /deploy/jobs/{id}/report`)
+    pub validate_only: bool,
+    #[serde(skip)]
+    pub validation_report: Option<serde_json::Value>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -471,6 +615,7 @@ pub struct DeployJobLog {
     pub id: Uuid,
     pub deploy_job_id: Uuid,
     pub log_line: String,
+    pub seq: i64,
     pub created_at: DateTime<Utc>,
 }
 
@@ -480,5 +625,46 @@ pub struct DeployJobDiff {
     pub deploy_job_id: Uuid,
     pub files_changed: String,
     pub diff_patch: String,
+    /// Klíč v object storage, pokud byl patch archivován (diff_patch je pak prázdný)
+    #[serde(skip)]
+    pub diff_patch_key: Option<String>,
+    /// Gzip patch (nové diffy); diff_patch je pak prázdný
+    #[serde(skip)]
+    pub diff_patch_gz: Option<Vec<u8>>,
+    /// Velikost patche před zkrácením
+    pub diff_size_bytes: Option<i64>,
+    pub diff_truncated: bool,
+    /// Index souborů (`DiffFileEntry`) pro `?file=`
+    #[serde(skip)]
+    pub file_index: Option<serde_json::Value>,
     pub created_at: DateTime<Utc>,
 }
+
+/// Krok deploy jobu (`services::deploy_steps::DEPLOY_STEPS`)
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct DeployJobStep {
+    pub step: String,
+    pub position: i32,
+    /// pending | in_progress | success | warning | failed | skipped
+    pub status: String,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub duration_ms: Option<i64>,
+    /// První řádek chyby (u `warning` ignorovaná chyba, u `skipped` důvod)
+    pub error_line: Option<String>,
+}
+
+/// Field-level změna entity (environment, registry, bundle)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EntityChange {
+    pub id: Uuid,
+    pub entity_type: String,
+    pub entity_id: Uuid,
+    pub tenant_id: Option<Uuid>,
+    pub changed_by: Option<String>,
+    pub changes: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Pipeline run - copy -> release -> deploy spuštěné jedním voláním
+pub use srm_api_types::pipelines::PipelineRun;
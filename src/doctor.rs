@@ -0,0 +1,412 @@
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::io::Write;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::config::{CliArgs, Config, ProcessRole};
+use crate::crypto;
+use crate::services::sandbox::{SandboxTool, ToolSandbox};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const TOOL_TIMEOUT: Duration = Duration::from_secs(10);
+/// Kolik neplatných credentials vypsat jménem
+const MAX_LISTED: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CheckStatus {
+    Ok,
+    Warn,
+    Fail,
+    Skip,
+}
+
+impl CheckStatus {
+    fn label(self) -> &'static str {
+        match self {
+            CheckStatus::Ok => " OK ",
+            CheckStatus::Warn => "WARN",
+            CheckStatus::Fail => "FAIL",
+            CheckStatus::Skip => "SKIP",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Check {
+    name: String,
+    status: CheckStatus,
+    detail: String,
+}
+
+#[derive(Debug, Default)]
+struct Report {
+    checks: Vec<Check>,
+}
+
+impl Report {
+    fn add(&mut self, name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) {
+        self.checks.push(Check {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+        });
+    }
+
+    fn count(&self, status: CheckStatus) -> usize {
+        self.checks.iter().filter(|check| check.status == status).count()
+    }
+
+    fn render(&self) -> String {
+        let width = self.checks.iter().map(|check| check.name.len()).max().unwrap_or(0);
+        let mut out = String::new();
+        for check in &self.checks {
+            out.push_str(&format!(
+                "[{}] {:<width$}  {}\n",
+                check.status.label(),
+                check.name,
+                check.detail,
+                width = width
+            ));
+        }
+        out.push_str(&format!(
+            "\n{} ok, {} warnings, {} failed\n",
+            self.count(CheckStatus::Ok),
+            self.count(CheckStatus::Warn),
+            self.count(CheckStatus::Fail)
+        ));
+        out
+    }
+
+    /// 1 = server v tomto prostředí nenastartuje nebo nebude fungovat
+    fn exit_code(&self) -> i32 {
+        if self.count(CheckStatus::Fail) > 0 { 1 } else { 0 }
+    }
+}
+
+/// `srm doctor` - kontrola konfigurace serveru před prvním startem; vrací exit code
+pub async fn run(cli: CliArgs) -> i32 {
+    let mut report = Report::default();
+    let config = match Config::from_env_and_cli(cli) {
+        Ok(config) => config,
+        Err(e) => {
+            report.add("configuration", CheckStatus::Fail, format!("{:#}", e));
+            print!("{}", report.render());
+            return report.exit_code();
+        }
+    };
+    report.add(
+        "configuration",
+        CheckStatus::Ok,
+        format!("role {}, listen {}", format!("{:?}", config.role).to_lowercase(), config.server_address()),
+    );
+
+    // Agent nemá DB ani šifrované credentials, nástroje copy i deploy jobů ale potřebuje
+    if config.role == ProcessRole::Agent {
+        report.add("database", CheckStatus::Skip, "agent role has no database");
+    } else {
+        check_database(&config, &mut report).await;
+    }
+
+    let sandbox = ToolSandbox::new(config.sandbox.clone());
+    let tools = [
+        (config.image_tool.as_str(), SandboxTool::ImageTool, config.image_tool_path.as_str(), "--version"),
+        ("git", SandboxTool::Git, "git", "--version"),
+        ("encjson", SandboxTool::Encjson, config.encjson_path.as_str(), "--version"),
+        ("kubeconform", SandboxTool::Kubeconform, config.kubeconform_path.as_str(), "-v"),
+    ];
+    for (name, tool, path, flag) in tools {
+        check_tool(&sandbox, &mut report, name, tool, path, flag).await;
+    }
+
+    check_temp_dir(&mut report);
+
+    print!("{}", report.render());
+    report.exit_code()
+}
+
+async fn check_database(config: &Config, report: &mut Report) {
+    let pool = match PgPoolOptions::new()
+        .max_connections(1)
+        .acquire_timeout(CONNECT_TIMEOUT)
+        .connect(&config.database_url)
+        .await
+    {
+        Ok(pool) => pool,
+        Err(e) => {
+            report.add("database", CheckStatus::Fail, format!("cannot connect: {}", e));
+            report.add("migrations", CheckStatus::Skip, "database unavailable");
+            report.add("registry credentials", CheckStatus::Skip, "database unavailable");
+            return;
+        }
+    };
+    let version = sqlx::query_scalar::<_, String>("SELECT version()")
+        .fetch_one(&pool)
+        .await
+        .map(|v| v.split(" on ").next().unwrap_or(&v).to_string())
+        .unwrap_or_else(|_| "unknown version".to_string());
+    report.add("database", CheckStatus::Ok, format!("connected ({})", version));
+
+    let migrations_ok = check_migrations(&pool, report).await;
+    if migrations_ok {
+        check_credentials(&pool, &config.encryption_secret, report).await;
+    } else {
+        report.add("registry credentials", CheckStatus::Skip, "schema is not migrated");
+    }
+}
+
+/// Porovná migrace zabudované v binárce s `_sqlx_migrations`; vrací, zda schéma odpovídá (případně po doběhnutí čekajících)
+async fn check_migrations(pool: &PgPool, report: &mut Report) -> bool {
+    let migrator = sqlx::migrate!("./migrations");
+    let table_exists = sqlx::query_scalar::<_, bool>("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+        .fetch_one(pool)
+        .await
+        .unwrap_or(false);
+    let applied = if table_exists {
+        match sqlx::query_as::<_, (i64, bool, Vec<u8>)>("SELECT version, success, checksum FROM _sqlx_migrations")
+            .fetch_all(pool)
+            .await
+        {
+            Ok(rows) => rows,
+            Err(e) => {
+                report.add("migrations", CheckStatus::Fail, format!("cannot read _sqlx_migrations: {}", e));
+                return false;
+            }
+        }
+    } else {
+        Vec::new()
+    };
+    let embedded: Vec<(i64, Vec<u8>)> = migrator
+        .iter()
+        .filter(|m| m.migration_type.is_up_migration())
+        .map(|m| (m.version, m.checksum.to_vec()))
+        .collect();
+    let state = MigrationState::compare(&embedded, &applied);
+    let (status, detail) = state.describe();
+    report.add("migrations", status, detail);
+    // Čekající migrace server při startu doběhne, credentials ale potřebují současné schéma
+    state.pending.is_empty() && status != CheckStatus::Fail
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct MigrationState {
+    applied: usize,
+    pending: Vec<i64>,
+    failed: Vec<i64>,
+    modified: Vec<i64>,
+    /// V DB, ale ne v binárce (DB migrovala novější verze SRM)
+    unknown: Vec<i64>,
+}
+
+impl MigrationState {
+    fn compare(embedded: &[(i64, Vec<u8>)], applied: &[(i64, bool, Vec<u8>)]) -> Self {
+        let applied_by_version: HashMap<i64, (bool, &Vec<u8>)> =
+            applied.iter().map(|(version, success, checksum)| (*version, (*success, checksum))).collect();
+        let mut state = MigrationState::default();
+        for (version, checksum) in embedded {
+            match applied_by_version.get(version) {
+                None => state.pending.push(*version),
+                Some((false, _)) => state.failed.push(*version),
+                Some((true, applied_checksum)) if *applied_checksum != checksum => state.modified.push(*version),
+                Some(_) => state.applied += 1,
+            }
+        }
+        state.unknown = applied
+            .iter()
+            .map(|(version, _, _)| *version)
+            .filter(|version| !embedded.iter().any(|(v, _)| v == version))
+            .collect();
+        state
+    }
+
+    fn describe(&self) -> (CheckStatus, String) {
+        let list = |versions: &[i64]| versions.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", ");
+        if !self.failed.is_empty() {
+            return (CheckStatus::Fail, format!("failed migrations: {}", list(&self.failed)));
+        }
+        if !self.modified.is_empty() {
+            return (
+                CheckStatus::Fail,
+                format!("applied migrations changed since they ran (checksum mismatch): {}", list(&self.modified)),
+            );
+        }
+        if !self.unknown.is_empty() {
+            return (
+                CheckStatus::Fail,
+                format!(
+                    "database has migrations this build does not know ({}), it was migrated by a newer version",
+                    list(&self.unknown)
+                ),
+            );
+        }
+        if !self.pending.is_empty() {
+            return (
+                CheckStatus::Warn,
+                format!(
+                    "{} applied, {} pending (applied on server start): {}",
+                    self.applied,
+                    self.pending.len(),
+                    list(&self.pending)
+                ),
+            );
+        }
+        (CheckStatus::Ok, format!("{} applied, none pending", self.applied))
+    }
+}
+
+/// Zkusí dešifrovat všechna uložená hesla / tokeny registry aktuálním `ENCRYPTION_SECRET`
+async fn check_credentials(pool: &PgPool, secret: &str, report: &mut Report) {
+    let rows = sqlx::query_as::<_, (String, Option<String>, Option<String>)>(
+        r#"
+        SELECT 'registry ' || name, password_encrypted, token_encrypted FROM registries
+        UNION ALL
+        SELECT 'environment ' || e.slug || ' / ' || r.name, erc.password_encrypted, erc.token_encrypted
+        FROM environment_registry_credentials erc
+        JOIN environments e ON e.id = erc.environment_id
+        JOIN registries r ON r.id = erc.registry_id
+        UNION ALL
+        SELECT 'environment ' || slug || ' source', source_password_encrypted, source_token_encrypted FROM environments
+        UNION ALL
+        SELECT 'environment ' || slug || ' target', target_password_encrypted, target_token_encrypted FROM environments
+        "#,
+    )
+    .fetch_all(pool)
+    .await;
+    let rows = match rows {
+        Ok(rows) => rows,
+        Err(e) => {
+            report.add("registry credentials", CheckStatus::Fail, format!("query failed: {}", e));
+            return;
+        }
+    };
+
+    let mut checked = 0;
+    let mut broken = Vec::new();
+    for (owner, password, token) in rows {
+        for value in [password, token].into_iter().flatten().filter(|v| !v.is_empty()) {
+            checked += 1;
+            if crypto::decrypt(&value, secret).is_err() && !broken.contains(&owner) {
+                broken.push(owner.clone());
+            }
+        }
+    }
+    if broken.is_empty() {
+        report.add(
+            "registry credentials",
+            CheckStatus::Ok,
+            format!("{} stored secret(s) decrypt with the current ENCRYPTION_SECRET", checked),
+        );
+        return;
+    }
+    let mut listed = broken.iter().take(MAX_LISTED).cloned().collect::<Vec<_>>().join(", ");
+    if broken.len() > MAX_LISTED {
+        listed.push_str(&format!(" and {} more", broken.len() - MAX_LISTED));
+    }
+    report.add(
+        "registry credentials",
+        CheckStatus::Fail,
+        format!("cannot decrypt with the current ENCRYPTION_SECRET: {}", listed),
+    );
+}
+
+async fn check_tool(
+    sandbox: &ToolSandbox,
+    report: &mut Report,
+    name: &str,
+    tool: SandboxTool,
+    path: &str,
+    version_flag: &str,
+) {
+    let program = sandbox.program(tool, path);
+    let mut command = program.command(None);
+    command.arg(version_flag).kill_on_drop(true);
+    let output = match tokio::time::timeout(TOOL_TIMEOUT, command.output()).await {
+        Err(_) => {
+            report.add(name, CheckStatus::Fail, format!("{} {} timed out", path, version_flag));
+            return;
+        }
+        Ok(Err(e)) => {
+            report.add(name, CheckStatus::Fail, program.spawn_error(&e));
+            return;
+        }
+        Ok(Ok(output)) => output,
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let version = first_line(&stdout).or_else(|| first_line(&stderr)).unwrap_or("no version output");
+    if output.status.success() {
+        let sandboxed = if sandbox.is_active(tool) {
+            format!(" [sandbox: {}]", sandbox.summary(tool))
+        } else {
+            String::new()
+        };
+        report.add(name, CheckStatus::Ok, format!("{} ({}){}", version, path, sandboxed));
+    } else {
+        report.add(
+            name,
+            CheckStatus::Fail,
+            format!("{} {} exited with {}: {}", path, version_flag, output.status, version),
+        );
+    }
+}
+
+fn first_line(text: &str) -> Option<&str> {
+    text.lines().map(str::trim).find(|line| !line.is_empty())
+}
+
+/// Checkouty, renderované manifesty a copy logy jdou do temp adresáře
+fn check_temp_dir(report: &mut Report) {
+    let dir = std::env::temp_dir();
+    let result = tempfile::Builder::new()
+        .prefix("srm-doctor-")
+        .tempfile_in(&dir)
+        .and_then(|mut file| file.write_all(Uuid::new_v4().as_bytes()));
+    match result {
+        Ok(()) => report.add("temp dir", CheckStatus::Ok, format!("{} is writable", dir.display())),
+        Err(e) => report.add(
+            "temp dir",
+            CheckStatus::Fail,
+            format!("{} is not writable: {}", dir.display(), e),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrations_are_compared_by_version_and_checksum() {
+        let embedded = vec![(1, vec![1]), (2, vec![2]), (3, vec![3])];
+        let state = MigrationState::compare(&embedded, &[(1, true, vec![1])]);
+        assert_eq!(state.pending, vec![2, 3]);
+        assert_eq!(state.describe().0, CheckStatus::Warn);
+
+        let state = MigrationState::compare(
+            &embedded,
+            &[(1, true, vec![1]), (2, true, vec![9]), (3, true, vec![3]), (4, true, vec![4])],
+        );
+        assert_eq!(state.modified, vec![2]);
+        assert_eq!(state.unknown, vec![4]);
+        assert_eq!(state.describe().0, CheckStatus::Fail);
+
+        let state = MigrationState::compare(&embedded, &[(1, true, vec![1]), (2, true, vec![2]), (3, true, vec![3])]);
+        assert_eq!(state.describe(), (CheckStatus::Ok, "3 applied, none pending".to_string()));
+    }
+
+    #[test]
+    fn report_fails_only_on_failed_checks() {
+        let mut report = Report::default();
+        report.add("database", CheckStatus::Ok, "connected");
+        report.add("migrations", CheckStatus::Warn, "1 pending");
+        report.add("database", CheckStatus::Skip, "agent");
+        assert_eq!(report.exit_code(), 0);
+        report.add("git", CheckStatus::Fail, "not found");
+        assert_eq!(report.exit_code(), 1);
+        let rendered = report.render();
+        assert!(rendered.contains("[FAIL] git         not found"), "{}", rendered);
+        assert!(rendered.ends_with("1 ok, 1 warnings, 1 failed\n"));
+        assert_eq!(first_line("\n  git version 2.43.0\n"), Some("git version 2.43.0"));
+    }
+}
@@ -1,10 +1,14 @@
+mod agent;
 mod api;
 mod auth;
+mod cli;
 mod config;
 mod crypto;
 mod db;
+mod doctor;
 mod registry;
 mod services;
+mod worker;
 
 use anyhow::{Context, Result};
 use axum::{
@@ -15,8 +19,7 @@ use axum::{
     routing::get,
     Extension, Router,
 };
-use clap::Parser;
-use config::{CliArgs, Config};
+use config::{CliArgs, Command, Config, ProcessRole};
 use rust_embed::RustEmbed;
 use sqlx::postgres::PgPoolOptions;
 use std::sync::Arc;
@@ -65,6 +68,22 @@ fn embedded_asset_response(path: &str) -> Option<Response> {
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    // Parse CLI argumentů
+    let mut cli = CliArgs::parse_with_alias();
+
+    // CLI klient režim - bez serveru a bez server logování
+    if let Some(Command::Srm(args)) = cli.command.take() {
+        // doctor nevolá API, kontroluje konfiguraci tohoto procesu (env + CLI argumenty)
+        if matches!(args.command, cli::SrmCommand::Doctor) {
+            std::process::exit(doctor::run(cli).await);
+        }
+        let code = cli::run(args).await.unwrap_or_else(|e| {
+            eprintln!("error: {:#}", e);
+            2
+        });
+        std::process::exit(code);
+    }
+
     // Inicializace loggingu
     tracing_subscriber::registry()
         .with(
@@ -76,20 +95,60 @@ async fn main() -> Result<()> {
 
     info!("Starting Simple Release Management");
 
-    // Parse CLI argumentů
-    let cli = CliArgs::parse();
-
     // Načtení konfigurace (CLI argumenty mají prioritu)
     let config = Config::from_env_and_cli(cli).context("Failed to load configuration")?;
     info!("Configuration loaded");
     info!("Server will listen on: {}", config.server_address());
     info!("Base path: {}", if config.base_path.is_empty() { "/" } else { &config.base_path });
     info!("Image tool: {} ({})", config.image_tool, config.image_tool_path);
+    info!("Role: {:?}", config.role);
     info!(
         "Authorization: {}",
         if config.auth_enabled { "enabled" } else { "DISABLED (development mode)" }
     );
 
+    // Agent - bez DB a bez API, jen /health + smyčka nad HTTPS API centrálního serveru
+    if config.role == ProcessRole::Agent {
+        let (Some(server_url), Some(token)) = (config.agent_server_url.clone(), config.agent_token.clone()) else {
+            anyhow::bail!("AGENT_SERVER_URL and AGENT_TOKEN must be set for --role agent");
+        };
+        let sandbox = services::sandbox::ToolSandbox::new(config.sandbox.clone());
+        let skopeo_service = build_image_tool(&config, sandbox.clone());
+        if let Err(e) = skopeo_service.check_available().await {
+            tracing::warn!("Image tool is not available: {}. Copy operations will fail.", e);
+        }
+        let deploy_tools = api::deploy::DeployTools {
+            kube_build_app_path: config.kube_build_app_path.clone(),
+            apply_env_path: config.apply_env_path.clone(),
+            encjson_path: config.encjson_path.clone(),
+            encjson_legacy_path: config.encjson_legacy_path.clone(),
+            encjson_key_dir: config.encjson_key_dir.clone(),
+            kubeconform_path: config.kubeconform_path.clone(),
+            sandbox,
+        };
+        tokio::spawn(agent::run(
+            skopeo_service,
+            deploy_tools,
+            agent::AgentConfig {
+                server_url,
+                token,
+                poll_interval: std::time::Duration::from_secs(config.agent_poll_seconds.max(1)),
+            },
+        ));
+
+        let app = Router::new().route("/health", get(health_handler));
+        let listener = tokio::net::TcpListener::bind(&config.server_address())
+            .await
+            .context("Failed to bind server address")?;
+        info!("Agent health endpoint on {}", config.server_address());
+        axum::serve(listener, app)
+            .with_graceful_shutdown(shutdown_signal())
+            .await
+            .context("Server error")?;
+        info!("Agent shutdown complete");
+        return Ok(());
+    }
+
     // Připojení k databázi
     info!("Connecting to database...");
     let pool = PgPoolOptions::new()
@@ -110,15 +169,46 @@ async fn main() -> Result<()> {
 
     info!("Database migrations completed successfully");
 
+    // Background refresh dashboard summary views (jen procesy s API)
+    if config.role != ProcessRole::Worker {
+        services::dashboard_views::spawn_refresh_task(
+            pool.clone(),
+            std::time::Duration::from_secs(config.dashboard_refresh_seconds),
+        );
+    }
+
+    // Log fan-out mezi instancemi (LISTEN/NOTIFY); worker jen publikuje, neposlouchá
+    let log_fanout = services::log_fanout::LogFanout::new(config.log_fanout_enabled);
+    if config.role != ProcessRole::Worker {
+        services::log_fanout::spawn_listener(pool.clone(), log_fanout.clone());
+    }
+
+    // Volitelné S3/MinIO úložiště pro archivy logů a deploy diffy
+    let object_storage = services::object_storage::ObjectStorage::new(config.object_storage.clone());
+    if let Some(storage_config) = config.object_storage.as_ref() {
+        info!("Object storage: {} (bucket {})", storage_config.endpoint, storage_config.bucket);
+        if config.role != ProcessRole::Worker {
+            services::object_storage::spawn_archiver(pool.clone(), object_storage.clone(), storage_config);
+        }
+    }
+
+    // V roli `web` joby jen zařazujeme do fronty, spouští je worker
+    let dispatch = if config.role == ProcessRole::Web {
+        services::job_queue::JobDispatch::Queue
+    } else {
+        services::job_queue::JobDispatch::InProcess
+    };
+
+    // Sandbox pro externí nástroje (git, skopeo, encjson, kube_build_app, apply-env, kubeconform)
+    let sandbox = services::sandbox::ToolSandbox::new(config.sandbox.clone());
+    for tool in services::sandbox::SandboxTool::ALL {
+        if sandbox.is_active(tool) {
+            info!("Sandbox {:?}: {}", tool, sandbox.summary(tool));
+        }
+    }
+
     // Inicializace image tool service
-    let skopeo_service = services::ImageToolService::new(
-        config.image_tool.clone(),
-        config.image_tool_path.clone(),
-        config.image_tool_src_insecure,
-        config.image_tool_dst_insecure,
-        config.image_tool_extra_inspect_args.clone(),
-        config.image_tool_extra_copy_args.clone(),
-    );
+    let skopeo_service = build_image_tool(&config, sandbox.clone());
 
     // Zkontrolovat že image tool je dostupný
     match skopeo_service.check_available().await {
@@ -132,11 +222,17 @@ async fn main() -> Result<()> {
     }
 
     // Vytvoření API routeru
+    let notifier = services::notifications::Notifier::new(config.notification_webhook_url.clone())
+        .with_owner_webhooks(config.notification_owner_webhooks.clone());
+
     let api_router = api::create_api_router(
         pool.clone(),
         config.encryption_secret.clone(),
         config.image_tool.clone(),
         config.image_tool_path.clone(),
+        config.credential_expiry_warn_days,
+        notifier.clone(),
+        object_storage.clone(),
     );
 
     // Vytvoření copy API state
@@ -146,10 +242,15 @@ async fn main() -> Result<()> {
         encryption_secret: config.encryption_secret.clone(),
         job_logs: Arc::new(RwLock::new(std::collections::HashMap::new())),
         cancel_flags: Arc::new(RwLock::new(std::collections::HashSet::new())),
+        dispatch,
+        log_fanout: log_fanout.clone(),
+        precheck_concurrency: config.precheck_concurrency,
+        object_storage: object_storage.clone(),
+        notifier: notifier.clone(),
     };
 
     // Vytvoření copy API routeru
-    let copy_router = api::copy::router(copy_state);
+    let copy_router = api::copy::router(copy_state.clone());
 
     // Vytvoření deploy API state
     let deploy_state = api::deploy::DeployApiState {
@@ -162,16 +263,136 @@ async fn main() -> Result<()> {
         encjson_key_dir: config.encjson_key_dir.clone(),
         kubeconform_path: config.kubeconform_path.clone(),
         job_logs: Arc::new(RwLock::new(std::collections::HashMap::new())),
+        dispatch,
+        log_fanout,
+        sandbox,
+        object_storage,
+        diff_limits: services::deploy_diff::DiffLimits {
+            max_bytes: config.deploy_diff_max_bytes,
+            inline_max_bytes: config.deploy_diff_inline_max_bytes,
+        },
+        notifier: notifier.clone(),
     };
 
-    let deploy_router = api::deploy::router(deploy_state);
+    // Úklid po spadlých jobech (temp adresáře, log kanály, cancel flagy) - jen kde joby běží
+    let reaper_metrics = services::reaper::ReaperMetrics::default();
+    if config.role != ProcessRole::Web {
+        services::reaper::spawn_reaper(
+            pool.clone(),
+            services::reaper::ReaperTargets {
+                copy_logs: copy_state.job_logs.clone(),
+                copy_cancel_flags: copy_state.cancel_flags.clone(),
+                deploy_logs: deploy_state.job_logs.clone(),
+            },
+            &config.reaper,
+            reaper_metrics.clone(),
+        );
+    }
+    // Kontrola upstream digestů u mappings na plovoucích tazích - jen kde joby běží
+    if config.role != ProcessRole::Web {
+        api::base_images::spawn_checker(
+            copy_state.clone(),
+            std::time::Duration::from_secs(config.base_image_check_seconds),
+        );
+    }
+    // Připomínky expirujících registry/git credentials - jen kde joby běží
+    if config.role != ProcessRole::Web {
+        services::credential_expiry::spawn_reminder_task(
+            pool.clone(),
+            notifier.clone(),
+            config.credential_expiry_warn_days,
+            std::time::Duration::from_secs(config.credential_expiry_check_seconds),
+        );
+    }
+    // Doručování release manifestů do git / S3 / HTTP cílů - jen kde joby běží
+    if config.role != ProcessRole::Web {
+        api::manifest_destinations::spawn_delivery_task(
+            deploy_state.clone(),
+            std::time::Duration::from_secs(config.manifest_delivery_interval_seconds),
+            config.manifest_delivery_max_attempts.max(1),
+        );
+    }
+    let metrics_route = get(move || {
+        let metrics = reaper_metrics.clone();
+        async move { metrics.render() }
+    });
+
+    // Worker - bez API, jen /health + /metrics + smyčka nad DB frontou
+    if config.role == ProcessRole::Worker {
+        tokio::spawn(worker::run(
+            copy_state,
+            deploy_state,
+            worker::WorkerConfig {
+                worker_id: config.worker_id.clone(),
+                max_copy_jobs: config.worker_max_copy_jobs.max(1),
+                max_deploy_jobs: config.worker_max_deploy_jobs.max(1),
+                poll_interval: std::time::Duration::from_secs(config.worker_poll_seconds.max(1)),
+                // Lease musí přežít několik ticků, jinak by se běžící joby převzaly
+                lease_seconds: config.worker_lease_seconds.max(config.worker_poll_seconds.max(1) * 3),
+            },
+        ));
+
+        let app = Router::new()
+            .route("/health", get(health_handler))
+            .route("/metrics", metrics_route);
+        let listener = tokio::net::TcpListener::bind(&config.server_address())
+            .await
+            .context("Failed to bind server address")?;
+        info!("Worker {} health endpoint on {}", config.worker_id, config.server_address());
+        axum::serve(listener, app)
+            .with_graceful_shutdown(shutdown_signal())
+            .await
+            .context("Server error")?;
+        info!("Worker shutdown complete");
+        return Ok(());
+    }
+
+    let deploy_router = api::deploy::router(deploy_state.clone());
+    let base_images_router = api::base_images::router(copy_state.clone());
+    let agents_router = api::agents::router(api::agents::AgentApiState {
+        copy: copy_state.clone(),
+        deploy: deploy_state.clone(),
+    });
+    let pull_secrets_router = api::pull_secrets::router(copy_state.clone());
+    let manifest_destinations_router = api::manifest_destinations::router(deploy_state.clone());
+    let share_links_router = api::share_links::router(deploy_state.clone());
+    let file_templates_router = api::file_templates::router(deploy_state.clone());
+    let env_templates_router = api::env_templates::router(deploy_state.clone());
+    let tool_paths_router = api::tool_paths::router(api::tool_paths::ToolPathsApiState {
+        pool: pool.clone(),
+        defaults: services::tool_paths::ToolPathDefaults {
+            skopeo_path: config.image_tool_path.clone(),
+            encjson_path: config.encjson_path.clone(),
+            kube_build_app_path: config.kube_build_app_path.clone(),
+        },
+    });
+
+    // Pipeline API (copy -> release -> deploy jedním voláním); drivery běží v API procesu,
+    // proto se tu uklízí i runy po spadlých instancích
+    let pipeline_state = api::pipelines::PipelineApiState {
+        copy: copy_state,
+        deploy: deploy_state,
+        step_timeout: std::time::Duration::from_secs(config.pipeline_step_timeout_seconds.max(1)),
+    };
+    api::pipelines::spawn_orphan_sweeper(pipeline_state.clone());
+    let pipeline_router = api::pipelines::router(pipeline_state);
 
     // Vytvoření kompletního routeru
     let mut app = Router::new()
         .route("/health", get(health_handler))
+        .route("/metrics", metrics_route)
         .merge(api_router)
         .nest("/api/v1", copy_router)
         .nest("/api/v1", deploy_router)
+        .nest("/api/v1", pipeline_router)
+        .nest("/api/v1", base_images_router)
+        .nest("/api/v1", manifest_destinations_router)
+        .nest("/api/v1", agents_router)
+        .nest("/api/v1", pull_secrets_router)
+        .nest("/api/v1", tool_paths_router)
+        .nest("/api/v1", share_links_router)
+        .nest("/api/v1", file_templates_router)
+        .nest("/api/v1", env_templates_router)
         .layer(Extension(pool.clone()));
 
     if let Some(static_dir) = config.static_dir.clone() {
@@ -190,6 +411,7 @@ async fn main() -> Result<()> {
     } else {
         app.layer(middleware::from_fn(auth::auth_disabled_middleware))
     };
+    let app = app.layer(middleware::from_fn(api::error::request_id_middleware));
 
     info!("Application initialized successfully");
     info!("Starting HTTP server on {}", config.server_address());
@@ -211,6 +433,29 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Image tool service podle konfigurace (server, worker i agent)
+fn build_image_tool(config: &Config, sandbox: services::sandbox::ToolSandbox) -> services::ImageToolService {
+    services::ImageToolService::new(
+        config.image_tool.clone(),
+        config.image_tool_path.clone(),
+        config.image_tool_src_insecure,
+        config.image_tool_dst_insecure,
+        config.image_tool_extra_inspect_args.clone(),
+        config.image_tool_extra_copy_args.clone(),
+    )
+    .with_sandbox(sandbox)
+    .with_inspect_cache_ttl(std::time::Duration::from_secs(config.image_tool_inspect_cache_seconds))
+    .with_copy_defaults(services::image_tool::CopyOptions {
+        max_retries: config.copy_max_retries.max(1),
+        retry_delay_seconds: config.copy_retry_delay_seconds,
+        all: config.image_tool_copy_all,
+        preserve_digests: config.image_tool_copy_preserve_digests,
+        format: config.image_tool_copy_format.clone(),
+        release_tag_cleanup: config.release_tag_cleanup,
+        blob_reuse: config.copy_blob_reuse,
+    })
+}
+
 /// Health check handler
 async fn health_handler() -> &'static str {
     "OK"
@@ -0,0 +1,251 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::services::image_tool::CopyOptions;
+use crate::services::release_manifest::ReleaseManifest;
+use crate::services::validation_report::ValidationReport;
+
+/// Prefix tokenů agentů (rozpoznatelné v logu / secret scannerech)
+pub const TOKEN_PREFIX: &str = "srm_agent_";
+
+/// Stav obrazu hlášený agentem
+pub const IMAGE_IN_PROGRESS: &str = "in_progress";
+pub const IMAGE_SUCCESS: &str = "success";
+pub const IMAGE_FAILED: &str = "failed";
+
+/// Stav kroku deploy jobu hlášený agentem (`failed` určí server při dokončení jobu)
+pub const STEP_IN_PROGRESS: &str = "in_progress";
+pub const STEP_SUCCESS: &str = "success";
+pub const STEP_SKIPPED: &str = "skipped";
+pub const STEP_WARNING: &str = "warning";
+
+/// Nový token agenta; ukládá se jen jeho hash
+pub fn generate_token() -> String {
+    let bytes: [u8; 32] = rand::random();
+    format!("{}{}", TOKEN_PREFIX, hex(&bytes))
+}
+
+pub fn hash_token(token: &str) -> String {
+    hex(&Sha256::digest(token.trim().as_bytes()))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Token z hlavičky `Authorization: Bearer <token>`
+pub fn bearer_token(header: Option<&str>) -> Option<&str> {
+    let value = header?.trim();
+    let (scheme, token) = value.split_once(' ')?;
+    let token = token.trim();
+    (scheme.eq_ignore_ascii_case("bearer") && !token.is_empty()).then_some(token)
+}
+
+/// `claimed_by` copy / deploy jobu převzatého agentem
+pub fn claimed_by(agent_id: Uuid) -> String {
+    format!("agent:{}", agent_id)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentHeartbeat {
+    pub version: String,
+    pub hostname: Option<String>,
+}
+
+/// Copy job předaný agentovi - URL a credentials jsou už vyřešené na serveru
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentCopyWork {
+    pub job_id: Uuid,
+    pub target_tag: String,
+    pub extra_tags: Vec<String>,
+    pub validate_only: bool,
+    /// Přepsat cílový tag s jiným digestem (overwrite nebo release job)
+    pub allow_overwrite: bool,
+    pub copy_options: CopyOptions,
+    pub images: Vec<AgentCopyImage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentCopyImage {
+    pub id: Uuid,
+    /// Zdroj včetně tagu / digestu (`registry/repo:tag` nebo `registry/repo@sha256:...`)
+    pub source_url: String,
+    /// Cílový repozitář bez tagu (`registry/repo`)
+    pub target_repository: String,
+    pub source_username: Option<String>,
+    pub source_password: Option<String>,
+    pub target_username: Option<String>,
+    pub target_password: Option<String>,
+    /// Cílový release tag je v karanténě - shodě digestu se nevěří a image se zkopíruje znovu
+    #[serde(default)]
+    pub quarantined: bool,
+    /// Repozitáře cílové registry (`registry/repo`), ze kterých lze připojit chybějící vrstvy
+    #[serde(default)]
+    pub mount_from: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentLogBatch {
+    pub lines: Vec<String>,
+}
+
+/// Odpověď na log batch - agent podle ní přeruší zrušený job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentJobState {
+    pub cancelled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentImageReport {
+    /// `in_progress`, `success` nebo `failed`
+    pub status: String,
+    pub source_sha256: Option<String>,
+    pub target_sha256: Option<String>,
+    pub error_message: Option<String>,
+    pub bytes_copied: Option<i64>,
+    /// Odhad přenosu po znovupoužití vrstev
+    #[serde(default)]
+    pub estimated_transfer_bytes: Option<i64>,
+    #[serde(default)]
+    pub bytes_reused: Option<i64>,
+    #[serde(default)]
+    pub blobs_mounted: Option<i32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AgentCompleteRequest {
+    pub cancelled: bool,
+}
+
+/// Deploy job vyřešený na serveru (DB, dešifrované credentials) - podle něj běží deploy
+/// na workeru i na agentovi
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeployWork {
+    pub job_id: Uuid,
+    pub environment_slug: String,
+    /// `releases.release_id` (`SIMPLE_RELEASE_ID`)
+    pub release_id: String,
+    /// Git tag deploy commitu
+    pub tag_name: String,
+    pub dry_run: bool,
+    pub validate_only: bool,
+    pub env_repo: DeployWorkRepo,
+    pub deploy_repo: DeployWorkRepo,
+    /// Adresář environmentu v env repu
+    pub env_repo_path: String,
+    /// Výstupní adresář v deploy repu (prázdný = kořen repa)
+    pub deploy_repo_path: String,
+    pub release_manifest_mode: String,
+    /// Snapshot release manifestu, v režimu `environment_registry` už s URL registry environmentu
+    pub release_manifest: ReleaseManifest,
+    /// Proměnné z mapování release (`target_key` -> hodnota)
+    pub release_env_vars: HashMap<String, String>,
+    pub extra_env_vars: Vec<DeployEnvVar>,
+    pub encjson_key_dir: Option<String>,
+    pub file_templates: Vec<DeployWorkFile>,
+    /// Binárky přepsané na tenantovi nebo environmentu
+    pub encjson_path: Option<String>,
+    pub kube_build_app_path: Option<String>,
+    /// Větší diff se vrací zkrácený (`DEPLOY_DIFF_MAX_BYTES`)
+    pub diff_max_bytes: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeployWorkRepo {
+    pub id: Uuid,
+    pub repo_url: String,
+    pub branch: String,
+    pub ssh_private_key: Option<String>,
+    pub username: Option<String>,
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeployEnvVar {
+    pub key: String,
+    pub value: String,
+}
+
+/// Šablona souboru deploy targetu s dešifrovaným obsahem
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeployWorkFile {
+    pub path: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct DeployJobImageRow {
+    pub file_path: String,
+    pub container_name: String,
+    pub image: String,
+}
+
+/// Diff deploy výstupu, už zkrácený na `diff_max_bytes`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeployDiff {
+    pub files_changed: String,
+    pub diff_patch: String,
+    /// Velikost před zkrácením
+    pub size_bytes: i64,
+    pub truncated: bool,
+}
+
+/// Výsledky deploy pipeline; plní se průběžně, server je uloží i po chybě jobu
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeployOutcome {
+    pub inventory: Option<serde_json::Value>,
+    pub generated_profiles: Option<serde_json::Value>,
+    /// `None` = obrazy se nesebraly
+    pub images: Option<Vec<DeployJobImageRow>>,
+    pub diff: Option<DeployDiff>,
+    pub validation_report: Option<ValidationReport>,
+    pub commit_sha: Option<String>,
+    /// Vyplní se až po úspěšném průchodu pipeline
+    pub tag_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentDeployStep {
+    pub step: String,
+    /// `in_progress`, `success`, `skipped` nebo `warning`
+    pub status: String,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AgentDeployCompleteRequest {
+    pub outcome: DeployOutcome,
+    /// Chyba pipeline; `None` = deploy proběhl
+    pub error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_are_prefixed_and_hashed() {
+        let token = generate_token();
+        assert!(token.starts_with(TOKEN_PREFIX));
+        assert_eq!(token.len(), TOKEN_PREFIX.len() + 64);
+        assert_ne!(token, generate_token());
+        let hash = hash_token(&token);
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, hash_token(&format!(" {}\n", token)));
+        assert_eq!(
+            hash_token("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn bearer_token_parsing() {
+        assert_eq!(bearer_token(Some("Bearer srm_agent_x")), Some("srm_agent_x"));
+        assert_eq!(bearer_token(Some("bearer  srm_agent_x ")), Some("srm_agent_x"));
+        assert_eq!(bearer_token(Some("Basic dXNlcjpwYXNz")), None);
+        assert_eq!(bearer_token(Some("Bearer ")), None);
+        assert_eq!(bearer_token(None), None);
+    }
+}
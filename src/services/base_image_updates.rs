@@ -0,0 +1,42 @@
+/// Tag, pod kterým upstream může vydat jiný digest (`latest`, `1.27`, `stable`, `3-alpine`).
+/// Plná verze `X.Y.Z` a digest se berou jako pevné a nekontrolují se.
+pub fn is_moving_tag(tag: &str) -> bool {
+    let tag = tag.trim();
+    if tag.is_empty() || tag.contains(':') {
+        return tag.is_empty();
+    }
+    // Suffix varianty (`1.27-alpine`, `3.12.1-slim`) verzi nemění
+    let version = tag.trim_start_matches('v').split(['-', '_', '+']).next().unwrap_or("");
+    let parts: Vec<&str> = version.split('.').collect();
+    if parts.iter().all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit())) {
+        return parts.len() < 3;
+    }
+    // Bez číselné verze (`latest`, `stable`, `lts`, `alpine`)
+    !tag.chars().any(|c| c.is_ascii_digit())
+}
+
+/// Liší se aktuální upstream digest od digestu naposledy zkopírované image?
+pub fn digest_changed(copied: &str, upstream: &str) -> bool {
+    !copied.trim().eq_ignore_ascii_case(upstream.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moving_tags_are_detected() {
+        for tag in ["latest", "1.27", "1", "v2", "stable", "1.27-alpine", "alpine", ""] {
+            assert!(is_moving_tag(tag), "{} should be moving", tag);
+        }
+        for tag in ["1.27.3", "v1.2.3", "1.27.3-alpine", "2026.10.15.1", "sha256:abc", "build-1234"] {
+            assert!(!is_moving_tag(tag), "{} should be pinned", tag);
+        }
+    }
+
+    #[test]
+    fn digest_comparison_ignores_case_and_whitespace() {
+        assert!(!digest_changed("sha256:ABC", " sha256:abc"));
+        assert!(digest_changed("sha256:abc", "sha256:def"));
+    }
+}
@@ -0,0 +1,544 @@
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose, Engine};
+use reqwest::{header, Client, Method, Response, StatusCode, Url};
+use serde_json::Value;
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::services::image_tool::SkopeoCredentials;
+
+/// Max. počet repozitářů cílové registry, ze kterých se zkouší mount chybějícího blobu
+pub const MAX_MOUNT_SOURCES: i64 = 5;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const MANIFEST_ACCEPT: &str = "application/vnd.oci.image.index.v1+json, \
+    application/vnd.docker.distribution.manifest.list.v2+json, \
+    application/vnd.oci.image.manifest.v1+json, \
+    application/vnd.docker.distribution.manifest.v2+json";
+const DOCKER_HUB_API_HOST: &str = "registry-1.docker.io";
+
+/// Image reference ve tvaru, jaký dostává image tool (`host/repo:tag` nebo `host/repo@digest`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageRef {
+    /// Host registry pro API (Docker Hub -> `registry-1.docker.io`)
+    pub host: String,
+    pub repository: String,
+    pub reference: String,
+}
+
+impl ImageRef {
+    pub fn parse(url: &str) -> Result<Self, String> {
+        let url = url
+            .trim()
+            .trim_start_matches("docker://")
+            .trim_start_matches("https://")
+            .trim_start_matches("http://");
+        let (name, reference) = match url.split_once('@') {
+            Some((name, digest)) => (name, digest.to_string()),
+            None => match url.rsplit_once(':') {
+                // `host:5000/repo` nemá tag, dvojtečka patří k portu
+                Some((name, tag)) if !tag.contains('/') => (name, tag.to_string()),
+                _ => (url, "latest".to_string()),
+            },
+        };
+        let (host, repository) = name
+            .split_once('/')
+            .filter(|(host, repository)| !host.is_empty() && !repository.is_empty())
+            .ok_or_else(|| format!("Image reference '{}' has no registry host", url))?;
+        let host = host.to_ascii_lowercase();
+        if matches!(host.as_str(), "docker.io" | "index.docker.io" | DOCKER_HUB_API_HOST) {
+            let repository = if repository.contains('/') {
+                repository.to_string()
+            } else {
+                format!("library/{}", repository)
+            };
+            return Ok(Self {
+                host: DOCKER_HUB_API_HOST.to_string(),
+                repository,
+                reference,
+            });
+        }
+        Ok(Self {
+            host,
+            repository: repository.to_string(),
+            reference,
+        })
+    }
+}
+
+/// Blob image (config nebo vrstva)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobRef {
+    pub digest: String,
+    pub size: u64,
+}
+
+/// Výsledek přípravy cílového repozitáře před kopií
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlobReuseReport {
+    pub blobs: usize,
+    /// Bloby, které cílový repozitář už má (image tool je přeskočí)
+    pub existing: usize,
+    /// Bloby připojené (cross-repo mount) z jiného repozitáře cílové registry
+    pub mounted: usize,
+    pub total_bytes: u64,
+    pub existing_bytes: u64,
+    pub mounted_bytes: u64,
+}
+
+impl BlobReuseReport {
+    pub fn reused_bytes(&self) -> u64 {
+        self.existing_bytes + self.mounted_bytes
+    }
+
+    /// Odhad bajtů, které image tool přenese ze zdrojové registry (vrstvy, které cíl nemá ani po mountu);
+    /// skutečný přenos image tool nehlásí
+    pub fn estimated_transfer_bytes(&self) -> u64 {
+        self.total_bytes.saturating_sub(self.reused_bytes())
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} of {} blobs already in target, {} mounted from other repositories; estimated transfer {} of {}",
+            self.existing,
+            self.blobs,
+            self.mounted,
+            format_bytes(self.estimated_transfer_bytes()),
+            format_bytes(self.total_bytes)
+        )
+    }
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// Parametry přípravy (stejné jako u image toolu)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReuseOptions {
+    /// Všechny platformy z manifest listu (`--all`)
+    pub all: bool,
+    pub src_insecure: bool,
+    pub dst_insecure: bool,
+}
+
+/// `WWW-Authenticate` výzva registry
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Challenge {
+    scheme: String,
+    params: HashMap<String, String>,
+}
+
+impl Challenge {
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let (scheme, rest) = value.split_once(' ').unwrap_or((value, ""));
+        let mut params = HashMap::new();
+        let mut rest = rest.trim();
+        while !rest.is_empty() {
+            let (key, after_key) = rest.split_once('=')?;
+            let after_key = after_key.trim_start();
+            // Hodnoty v uvozovkách mohou obsahovat čárky (`scope="repository:a:pull,push"`)
+            let (param, remainder) = match after_key.strip_prefix('"') {
+                Some(quoted) => {
+                    let end = quoted.find('"')?;
+                    (&quoted[..end], &quoted[end + 1..])
+                }
+                None => after_key.split_once(',').map_or((after_key, ""), |(v, r)| (v, r)),
+            };
+            params.insert(key.trim().to_ascii_lowercase(), param.to_string());
+            rest = remainder.trim_start_matches([',', ' ']);
+        }
+        Some(Self {
+            scheme: scheme.to_ascii_lowercase(),
+            params,
+        })
+    }
+}
+
+/// Spojení na jednu registry s cache tokenů podle scope
+struct RegistrySession {
+    client: Client,
+    base: String,
+    insecure: bool,
+    username: Option<String>,
+    password: Option<String>,
+    authorizations: HashMap<String, String>,
+}
+
+impl RegistrySession {
+    fn new(host: &str, username: Option<&str>, password: Option<&str>, insecure: bool) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .danger_accept_invalid_certs(insecure)
+            .build()
+            .context("Failed to build registry HTTP client")?;
+        Ok(Self {
+            client,
+            base: format!("https://{}", host),
+            insecure,
+            username: username.map(str::to_string).filter(|v| !v.is_empty()),
+            password: password.map(str::to_string).filter(|v| !v.is_empty()),
+            authorizations: HashMap::new(),
+        })
+    }
+
+    async fn send(&mut self, method: &Method, path: &str, authorization: Option<&str>) -> Result<Response> {
+        loop {
+            let mut request = self
+                .client
+                .request(method.clone(), format!("{}{}", self.base, path))
+                .header(header::ACCEPT, MANIFEST_ACCEPT);
+            if let Some(authorization) = authorization {
+                request = request.header(header::AUTHORIZATION, authorization);
+            }
+            match request.send().await {
+                Ok(response) => return Ok(response),
+                // Insecure registry bez TLS (skopeo s --tls-verify=false zkouší i http)
+                Err(err) if self.insecure && err.is_connect() && self.base.starts_with("https://") => {
+                    self.base = self.base.replacen("https://", "http://", 1);
+                }
+                Err(err) => return Err(err).with_context(|| format!("{} {} failed", method, path)),
+            }
+        }
+    }
+
+    async fn request(&mut self, method: Method, path: &str, scopes: &[String]) -> Result<Response> {
+        let key = scopes.join(" ");
+        let cached = self.authorizations.get(&key).cloned();
+        let response = self.send(&method, path, cached.as_deref()).await?;
+        if response.status() != StatusCode::UNAUTHORIZED {
+            return Ok(response);
+        }
+        let challenge = response
+            .headers()
+            .get(header::WWW_AUTHENTICATE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(Challenge::parse)
+            .ok_or_else(|| anyhow!("Registry returned 401 without a usable WWW-Authenticate header"))?;
+        let authorization = self.authorize(&challenge, scopes).await?;
+        self.authorizations.insert(key, authorization.clone());
+        self.send(&method, path, Some(&authorization)).await
+    }
+
+    fn basic(&self) -> Option<String> {
+        let username = self.username.as_deref()?;
+        let credentials = format!("{}:{}", username, self.password.as_deref().unwrap_or(""));
+        Some(format!("Basic {}", general_purpose::STANDARD.encode(credentials)))
+    }
+
+    async fn authorize(&self, challenge: &Challenge, scopes: &[String]) -> Result<String> {
+        if challenge.scheme == "basic" {
+            return self.basic().ok_or_else(|| anyhow!("Registry requires credentials"));
+        }
+        if challenge.scheme != "bearer" {
+            bail!("Unsupported registry auth scheme '{}'", challenge.scheme);
+        }
+        let realm = challenge
+            .params
+            .get("realm")
+            .ok_or_else(|| anyhow!("Bearer challenge without realm"))?;
+        let mut url = Url::parse(realm).with_context(|| format!("Invalid token realm '{}'", realm))?;
+        {
+            let mut query = url.query_pairs_mut();
+            if let Some(service) = challenge.params.get("service") {
+                query.append_pair("service", service);
+            }
+            for scope in scopes {
+                query.append_pair("scope", scope);
+            }
+        }
+        let mut request = self.client.get(url);
+        if let Some(basic) = self.basic() {
+            request = request.header(header::AUTHORIZATION, basic);
+        }
+        let response = request.send().await.context("Token request failed")?;
+        if !response.status().is_success() {
+            bail!("Token request returned {}", response.status());
+        }
+        let body: Value = response.json().await.context("Invalid token response")?;
+        let token = body
+            .get("token")
+            .or_else(|| body.get("access_token"))
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow!("Token response without token"))?;
+        Ok(format!("Bearer {}", token))
+    }
+}
+
+fn pull_scope(repository: &str) -> String {
+    format!("repository:{}:pull", repository)
+}
+
+fn push_scope(repository: &str) -> String {
+    format!("repository:{}:pull,push", repository)
+}
+
+/// Architektura pro výběr z manifest listu (image tool bez `--all` bere platformu hostitele)
+fn host_architecture() -> &'static str {
+    match std::env::consts::ARCH {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        other => other,
+    }
+}
+
+/// Digesty manifestů z indexu / manifest listu, které se budou kopírovat
+fn index_children(index: &Value, all: bool, architecture: &str) -> Vec<String> {
+    let entries = index.get("manifests").and_then(Value::as_array).cloned().unwrap_or_default();
+    let digest = |entry: &Value| entry.get("digest").and_then(Value::as_str).map(str::to_string);
+    if all {
+        return entries.iter().filter_map(digest).collect();
+    }
+    let matching = entries.iter().find(|entry| {
+        let platform = entry.get("platform");
+        platform.and_then(|p| p.get("os")).and_then(Value::as_str) == Some("linux")
+            && platform.and_then(|p| p.get("architecture")).and_then(Value::as_str) == Some(architecture)
+    });
+    matching.or(entries.first()).and_then(digest).into_iter().collect()
+}
+
+/// Config a vrstvy image manifestu
+fn manifest_blobs(manifest: &Value) -> Result<Vec<BlobRef>> {
+    if manifest.get("fsLayers").is_some() {
+        bail!("Schema 1 manifests are not supported");
+    }
+    let blob = |entry: &Value| -> Result<BlobRef> {
+        Ok(BlobRef {
+            digest: entry
+                .get("digest")
+                .and_then(Value::as_str)
+                .ok_or_else(|| anyhow!("Manifest entry without digest"))?
+                .to_string(),
+            size: entry.get("size").and_then(Value::as_u64).unwrap_or(0),
+        })
+    };
+    let mut blobs = Vec::new();
+    if let Some(config) = manifest.get("config") {
+        blobs.push(blob(config)?);
+    }
+    for layer in manifest.get("layers").and_then(Value::as_array).into_iter().flatten() {
+        blobs.push(blob(layer)?);
+    }
+    Ok(blobs)
+}
+
+async fn fetch_manifest(session: &mut RegistrySession, image: &ImageRef, reference: &str) -> Result<Value> {
+    let path = format!("/v2/{}/manifests/{}", image.repository, reference);
+    let response = session
+        .request(Method::GET, &path, &[pull_scope(&image.repository)])
+        .await?;
+    if !response.status().is_success() {
+        bail!("Manifest {}:{} returned {}", image.repository, reference, response.status());
+    }
+    response.json().await.context("Invalid manifest JSON")
+}
+
+/// Bloby zdrojového image (u manifest listu podle `all` / platformy), bez duplicit
+async fn source_blobs(session: &mut RegistrySession, image: &ImageRef, all: bool) -> Result<Vec<BlobRef>> {
+    let manifest = fetch_manifest(session, image, &image.reference).await?;
+    let manifests = if manifest.get("manifests").is_some() {
+        let mut children = Vec::new();
+        for digest in index_children(&manifest, all, host_architecture()) {
+            children.push(fetch_manifest(session, image, &digest).await?);
+        }
+        children
+    } else {
+        vec![manifest]
+    };
+    let mut seen = HashSet::new();
+    let mut blobs = Vec::new();
+    for manifest in &manifests {
+        for blob in manifest_blobs(manifest)? {
+            if seen.insert(blob.digest.clone()) {
+                blobs.push(blob);
+            }
+        }
+    }
+    Ok(blobs)
+}
+
+/// Zkusí připojit blob z jiného repozitáře stejné registry; neúspěšný pokus zahodí otevřený upload
+async fn mount_blob(session: &mut RegistrySession, repository: &str, from: &str, digest: &str) -> Result<bool> {
+    let path = format!("/v2/{}/blobs/uploads/?mount={}&from={}", repository, digest, from);
+    let response = session
+        .request(Method::POST, &path, &[push_scope(repository), pull_scope(from)])
+        .await?;
+    if response.status() == StatusCode::CREATED {
+        return Ok(true);
+    }
+    // 202 = registry mount neudělala a založila běžný upload
+    if response.status() == StatusCode::ACCEPTED
+        && let Some(location) = response.headers().get(header::LOCATION).and_then(|v| v.to_str().ok())
+    {
+        let location = location.strip_prefix(&session.base).unwrap_or(location).to_string();
+        if location.starts_with('/') {
+            let _ = session.request(Method::DELETE, &location, &[push_scope(repository)]).await;
+        }
+    }
+    Ok(false)
+}
+
+/// Porovná bloby zdrojového image s cílovým repozitářem a chybějící zkusí připojit z `mount_from`
+/// (`host/repo` ve stejné registry). Image tool pak přenese jen bloby, které cíl ani po mountu nemá.
+pub async fn prepare_target(
+    source_url: &str,
+    target_url: &str,
+    credentials: &SkopeoCredentials,
+    mount_from: &[String],
+    options: ReuseOptions,
+) -> Result<BlobReuseReport> {
+    let source = ImageRef::parse(source_url).map_err(|e| anyhow!(e))?;
+    let target = ImageRef::parse(target_url).map_err(|e| anyhow!(e))?;
+    let mount_from: Vec<String> = mount_from
+        .iter()
+        .filter_map(|url| ImageRef::parse(url).ok())
+        .filter(|candidate| candidate.host == target.host && candidate.repository != target.repository)
+        .map(|candidate| candidate.repository)
+        .collect();
+
+    let mut source_session = RegistrySession::new(
+        &source.host,
+        credentials.source_username.as_deref(),
+        credentials.source_password.as_deref(),
+        options.src_insecure,
+    )?;
+    let blobs = source_blobs(&mut source_session, &source, options.all).await?;
+
+    let mut target_session = RegistrySession::new(
+        &target.host,
+        credentials.target_username.as_deref(),
+        credentials.target_password.as_deref(),
+        options.dst_insecure,
+    )?;
+    let mut report = BlobReuseReport {
+        blobs: blobs.len(),
+        ..Default::default()
+    };
+    for blob in &blobs {
+        report.total_bytes += blob.size;
+        let path = format!("/v2/{}/blobs/{}", target.repository, blob.digest);
+        let exists = target_session
+            .request(Method::HEAD, &path, &[push_scope(&target.repository)])
+            .await?
+            .status()
+            .is_success();
+        if exists {
+            report.existing += 1;
+            report.existing_bytes += blob.size;
+            continue;
+        }
+        for from in &mount_from {
+            if mount_blob(&mut target_session, &target.repository, from, &blob.digest).await? {
+                report.mounted += 1;
+                report.mounted_bytes += blob.size;
+                break;
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Repozitáře cílové registry, kam už SRM kopírovalo (zdroje pro mount) - nejdřív stejný název image
+/// v jiné cestě, pak nejčerstvější
+pub async fn mount_sources(pool: &PgPool, target_registry_id: Uuid, target_image: &str) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT cji.target_image
+        FROM copy_job_images cji
+        JOIN copy_jobs cj ON cj.id = cji.copy_job_id
+        WHERE cj.target_registry_id = $1
+          AND cji.copy_status = 'success'
+          AND cji.target_image <> $2
+        GROUP BY cji.target_image
+        ORDER BY bool_or(split_part(reverse(cji.target_image), '/', 1) = split_part(reverse($2), '/', 1)) DESC,
+                 MAX(cji.copied_at) DESC NULLS LAST
+        LIMIT $3
+        "#,
+    )
+    .bind(target_registry_id)
+    .bind(target_image)
+    .bind(MAX_MOUNT_SOURCES)
+    .fetch_all(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn image_references_are_split() {
+        let image = ImageRef::parse("registry.example.com:5000/team/app:1.2").unwrap();
+        assert_eq!(image.host, "registry.example.com:5000");
+        assert_eq!(image.repository, "team/app");
+        assert_eq!(image.reference, "1.2");
+
+        let image = ImageRef::parse("Registry.example.com/app@sha256:abc").unwrap();
+        assert_eq!((image.host.as_str(), image.reference.as_str()), ("registry.example.com", "sha256:abc"));
+
+        let image = ImageRef::parse("localhost:5000/app").unwrap();
+        assert_eq!((image.repository.as_str(), image.reference.as_str()), ("app", "latest"));
+
+        let hub = ImageRef::parse("docker.io/nginx:1.27").unwrap();
+        assert_eq!(hub.host, DOCKER_HUB_API_HOST);
+        assert_eq!(hub.repository, "library/nginx");
+        assert!(ImageRef::parse("nginx:1.27").is_err());
+    }
+
+    #[test]
+    fn challenges_keep_quoted_commas() {
+        let challenge = Challenge::parse(
+            r#"Bearer realm="https://auth.example.com/token",service="registry.example.com",scope="repository:team/app:pull,push""#,
+        )
+        .unwrap();
+        assert_eq!(challenge.scheme, "bearer");
+        assert_eq!(challenge.params["realm"], "https://auth.example.com/token");
+        assert_eq!(challenge.params["scope"], "repository:team/app:pull,push");
+        assert_eq!(Challenge::parse(r#"Basic realm="Registry""#).unwrap().scheme, "basic");
+    }
+
+    #[test]
+    fn manifests_list_blobs_and_platforms() {
+        let manifest = json!({
+            "schemaVersion": 2,
+            "config": { "digest": "sha256:c", "size": 100 },
+            "layers": [{ "digest": "sha256:l1", "size": 1000 }, { "digest": "sha256:l2", "size": 5000 }],
+        });
+        let blobs = manifest_blobs(&manifest).unwrap();
+        assert_eq!(blobs.len(), 3);
+        assert_eq!(blobs.iter().map(|b| b.size).sum::<u64>(), 6100);
+        assert!(manifest_blobs(&json!({ "fsLayers": [] })).is_err());
+
+        let index = json!({ "manifests": [
+            { "digest": "sha256:arm", "platform": { "os": "linux", "architecture": "arm64" } },
+            { "digest": "sha256:amd", "platform": { "os": "linux", "architecture": "amd64" } },
+        ]});
+        assert_eq!(index_children(&index, false, "amd64"), vec!["sha256:amd"]);
+        assert_eq!(index_children(&index, false, "s390x"), vec!["sha256:arm"]);
+        assert_eq!(index_children(&index, true, "amd64").len(), 2);
+
+        let report = BlobReuseReport {
+            blobs: 3,
+            existing: 1,
+            mounted: 1,
+            total_bytes: 6100,
+            existing_bytes: 100,
+            mounted_bytes: 5000,
+        };
+        assert_eq!(report.estimated_transfer_bytes(), 1000);
+        assert!(report.summary().contains("estimated transfer 1000 B of 6.0 KiB"), "{}", report.summary());
+    }
+}
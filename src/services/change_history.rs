@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Náhrada tajných hodnot v historii změn
+const REDACTED: &str = "***";
+
+/// Pole, která se do historie nezapisují
+const IGNORED_FIELDS: &[&str] = &["id", "created_at"];
+
+/// Změna jednoho pole (před / po)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+/// Změněná top-level pole mezi dvěma snapshoty entity
+pub fn diff_snapshots<T: Serialize>(before: &T, after: &T) -> Vec<FieldChange> {
+    let before = serde_json::to_value(before).unwrap_or(Value::Null);
+    let after = serde_json::to_value(after).unwrap_or(Value::Null);
+    diff_values(&before, &after)
+}
+
+fn diff_values(before: &Value, after: &Value) -> Vec<FieldChange> {
+    let empty = serde_json::Map::new();
+    let before_map = before.as_object().unwrap_or(&empty);
+    let after_map = after.as_object().unwrap_or(&empty);
+
+    let mut fields: Vec<&String> = before_map.keys().chain(after_map.keys()).collect();
+    fields.sort();
+    fields.dedup();
+
+    fields
+        .into_iter()
+        .filter(|field| !IGNORED_FIELDS.contains(&field.as_str()))
+        .filter_map(|field| {
+            let old = before_map.get(field).cloned().unwrap_or(Value::Null);
+            let new = after_map.get(field).cloned().unwrap_or(Value::Null);
+            (old != new).then(|| FieldChange {
+                field: field.clone(),
+                before: old,
+                after: new,
+            })
+        })
+        .collect()
+}
+
+/// Tajné pole - porovnává se zašifrovaná hodnota, do historie jde jen `***`
+pub fn secret_change(field: &str, before: Option<&str>, after: Option<&str>) -> Option<FieldChange> {
+    if before == after {
+        return None;
+    }
+    let redact = |value: Option<&str>| match value {
+        Some(_) => Value::String(REDACTED.to_string()),
+        None => Value::Null,
+    };
+    Some(FieldChange {
+        field: field.to_string(),
+        before: redact(before),
+        after: redact(after),
+    })
+}
+
+/// Uloží změny do entity_change_history (prázdný seznam se neukládá)
+pub async fn record_changes(
+    pool: &PgPool,
+    entity_type: &str,
+    entity_id: Uuid,
+    tenant_id: Option<Uuid>,
+    changed_by: Option<&str>,
+    changes: &[FieldChange],
+) -> Result<(), sqlx::Error> {
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    sqlx::query(
+        "INSERT INTO entity_change_history (entity_type, entity_id, tenant_id, changed_by, changes)
+         VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(entity_type)
+    .bind(entity_id)
+    .bind(tenant_id)
+    .bind(changed_by)
+    .bind(serde_json::to_value(changes).unwrap_or_else(|_| Value::Array(Vec::new())))
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn diff_reports_only_changed_fields() {
+        let before = json!({"id": 1, "name": "prod", "branch": "main", "created_at": "a"});
+        let after = json!({"id": 2, "name": "prod", "branch": "release", "created_at": "b"});
+        let changes = diff_values(&before, &after);
+        assert_eq!(
+            changes,
+            vec![FieldChange {
+                field: "branch".to_string(),
+                before: json!("main"),
+                after: json!("release"),
+            }]
+        );
+    }
+
+    #[test]
+    fn secret_change_is_redacted() {
+        assert!(secret_change("password", Some("x"), Some("x")).is_none());
+        let change = secret_change("password", None, Some("enc")).unwrap();
+        assert_eq!(change.before, Value::Null);
+        assert_eq!(change.after, json!(REDACTED));
+    }
+}
@@ -0,0 +1,128 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgConnection};
+use uuid::Uuid;
+
+use crate::services::change_history::FieldChange;
+
+/// Odpověď `?preview=true` – změny se neuloží (transakce se vrátí)
+#[derive(Debug, Serialize)]
+pub struct ConfigPreview<T: Serialize> {
+    pub preview: bool,
+    pub changes: Vec<FieldChange>,
+    pub affected: AffectedDeploys,
+    /// Entita tak, jak by vypadala po uložení
+    pub result: T,
+}
+
+impl<T: Serialize> ConfigPreview<T> {
+    pub fn new(changes: Vec<FieldChange>, affected: AffectedDeploys, result: T) -> Self {
+        ConfigPreview {
+            preview: true,
+            changes,
+            affected,
+            result,
+        }
+    }
+}
+
+/// Deploye, které by novou konfiguraci použily
+#[derive(Debug, Default, Serialize)]
+pub struct AffectedDeploys {
+    /// Čekající joby – konfiguraci načítají až při spuštění
+    pub pending_jobs: Vec<PendingDeployJob>,
+    /// Aktivní napojení deploy targetů; další deploy (i auto-release) poběží s novou konfigurací
+    pub target_envs: Vec<AffectedTargetEnv>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct PendingDeployJob {
+    pub id: Uuid,
+    pub release_id: Uuid,
+    pub release_name: String,
+    pub environment_id: Uuid,
+    pub environment_slug: String,
+    pub deploy_target_id: Option<Uuid>,
+    pub deploy_target_name: Option<String>,
+    pub dry_run: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct AffectedTargetEnv {
+    pub deploy_target_id: Uuid,
+    pub deploy_target_name: String,
+    pub environment_id: Uuid,
+    pub environment_slug: String,
+    pub allow_auto_release: bool,
+    /// Poslední úspěšně nasazený release (kandidát na redeploy)
+    pub last_deployed_release: Option<String>,
+    pub last_deployed_at: Option<DateTime<Utc>>,
+}
+
+/// Deploye navázané na environment. Volá se uvnitř preview transakce, takže vidí i neuložené změny.
+pub async fn affected_deploys(conn: &mut PgConnection, environment_id: Uuid) -> Result<AffectedDeploys, sqlx::Error> {
+    load_affected(conn, "dj.environment_id", "dte.environment_id", environment_id).await
+}
+
+/// Deploye navázané na deploy target (všechny jeho environmenty)
+pub async fn affected_target_deploys(conn: &mut PgConnection, deploy_target_id: Uuid) -> Result<AffectedDeploys, sqlx::Error> {
+    load_affected(conn, "dj.deploy_target_id", "dte.deploy_target_id", deploy_target_id).await
+}
+
+async fn load_affected(
+    conn: &mut PgConnection,
+    job_column: &str,
+    target_env_column: &str,
+    id: Uuid,
+) -> Result<AffectedDeploys, sqlx::Error> {
+    let pending_jobs = sqlx::query_as::<_, PendingDeployJob>(&format!(
+        r#"
+        SELECT dj.id, dj.release_id, r.release_id AS release_name,
+               dj.environment_id, e.slug AS environment_slug,
+               dj.deploy_target_id, dt.name AS deploy_target_name,
+               dj.dry_run, dj.created_at
+        FROM deploy_jobs dj
+        JOIN releases r ON r.id = dj.release_id
+        JOIN environments e ON e.id = dj.environment_id
+        LEFT JOIN deploy_targets dt ON dt.id = dj.deploy_target_id
+        WHERE dj.status = 'pending' AND {job_column} = $1
+        ORDER BY dj.created_at
+        "#
+    ))
+    .bind(id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let target_envs = sqlx::query_as::<_, AffectedTargetEnv>(&format!(
+        r#"
+        SELECT dte.deploy_target_id, dt.name AS deploy_target_name,
+               dte.environment_id, e.slug AS environment_slug,
+               dte.allow_auto_release,
+               last.release_name AS last_deployed_release,
+               last.completed_at AS last_deployed_at
+        FROM deploy_target_envs dte
+        JOIN deploy_targets dt ON dt.id = dte.deploy_target_id
+        JOIN environments e ON e.id = dte.environment_id
+        LEFT JOIN LATERAL (
+            SELECT r.release_id AS release_name, dj.completed_at
+            FROM deploy_jobs dj
+            JOIN releases r ON r.id = dj.release_id
+            WHERE dj.deploy_target_env_id = dte.id AND dj.status = 'success' AND NOT dj.dry_run
+            ORDER BY dj.completed_at DESC NULLS LAST
+            LIMIT 1
+        ) last ON TRUE
+        WHERE dte.is_active AND dt.is_active AND NOT dt.is_archived
+          AND {target_env_column} = $1
+        ORDER BY dt.name, e.slug
+        "#
+    ))
+    .bind(id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(AffectedDeploys {
+        pending_jobs,
+        target_envs,
+    })
+}
@@ -0,0 +1,119 @@
+/// Zbývající image copy jobu s historií předchozích kopií stejné source image
+#[derive(Debug, Clone, Default)]
+pub struct RemainingImage {
+    /// Průměrná doba kopie (s) z předchozích jobů
+    pub history_seconds: Option<f64>,
+    /// Průměrná velikost (bajty) z předchozích jobů
+    pub history_bytes: Option<f64>,
+    /// Vyplněno pro právě kopírovanou image
+    pub in_progress: Option<InProgressImage>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct InProgressImage {
+    pub elapsed_seconds: f64,
+    pub bytes_copied: Option<u64>,
+    pub total_bytes: Option<u64>,
+}
+
+/// Odhad pro image bez vlastní historie
+#[derive(Debug, Clone, Default)]
+pub struct EtaBaseline {
+    /// Propustnost z nedávných kopií (bajty za sekundu)
+    pub bytes_per_second: Option<f64>,
+    /// Průměrná doba jedné image z nedávných kopií (s)
+    pub seconds_per_image: Option<f64>,
+}
+
+/// Odhad jedné image: vlastní historie doby -> historická velikost / propustnost -> průměr image
+fn estimate_image(image: &RemainingImage, baseline: &EtaBaseline) -> Option<f64> {
+    image
+        .history_seconds
+        .or_else(|| {
+            let bytes = image.history_bytes.filter(|b| *b > 0.0)?;
+            let rate = baseline.bytes_per_second.filter(|r| *r > 0.0)?;
+            Some(bytes / rate)
+        })
+        .or(baseline.seconds_per_image)
+}
+
+/// Zbývající čas (s), `None` když není z čeho odhadovat
+pub fn estimate_remaining_seconds(images: &[RemainingImage], baseline: &EtaBaseline) -> Option<f64> {
+    let mut remaining = 0.0;
+    for image in images {
+        let Some(progress) = &image.in_progress else {
+            remaining += estimate_image(image, baseline)?;
+            continue;
+        };
+        // Běžící transfer: extrapolace z přenesených bajtů, jinak odhad mínus uplynulý čas
+        let by_bytes = match (progress.bytes_copied, progress.total_bytes) {
+            (Some(copied), Some(total)) if copied > 0 && total >= copied => {
+                Some(progress.elapsed_seconds * (total - copied) as f64 / copied as f64)
+            }
+            _ => None,
+        };
+        remaining += match by_bytes {
+            Some(seconds) => seconds,
+            None => (estimate_image(image, baseline)? - progress.elapsed_seconds).max(0.0),
+        };
+    }
+    Some(remaining)
+}
+
+/// Procento hotového času jobu (uplynulý / (uplynulý + zbývající))
+pub fn percent_complete(elapsed_seconds: f64, remaining_seconds: f64) -> f64 {
+    let total = elapsed_seconds + remaining_seconds;
+    if total <= 0.0 {
+        return 0.0;
+    }
+    ((elapsed_seconds / total) * 1000.0).round() / 10.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn falls_back_from_history_to_baseline() {
+        let baseline = EtaBaseline {
+            bytes_per_second: Some(10_000_000.0),
+            seconds_per_image: Some(30.0),
+        };
+        let images = vec![
+            RemainingImage {
+                history_seconds: Some(12.0),
+                ..Default::default()
+            },
+            RemainingImage {
+                history_bytes: Some(50_000_000.0),
+                ..Default::default()
+            },
+            RemainingImage::default(),
+        ];
+        assert_eq!(estimate_remaining_seconds(&images, &baseline), Some(12.0 + 5.0 + 30.0));
+        assert_eq!(estimate_remaining_seconds(&images[2..], &EtaBaseline::default()), None);
+    }
+
+    #[test]
+    fn in_progress_image_uses_transferred_bytes() {
+        let image = |bytes_copied, total_bytes| RemainingImage {
+            history_seconds: Some(100.0),
+            history_bytes: None,
+            in_progress: Some(InProgressImage {
+                elapsed_seconds: 20.0,
+                bytes_copied,
+                total_bytes,
+            }),
+        };
+        let baseline = EtaBaseline::default();
+        assert_eq!(estimate_remaining_seconds(&[image(Some(25), Some(100))], &baseline), Some(60.0));
+        assert_eq!(estimate_remaining_seconds(&[image(None, None)], &baseline), Some(80.0));
+    }
+
+    #[test]
+    fn percent_is_time_based() {
+        assert_eq!(percent_complete(30.0, 90.0), 25.0);
+        assert_eq!(percent_complete(0.0, 0.0), 0.0);
+        assert_eq!(percent_complete(10.0, 0.0), 100.0);
+    }
+}
@@ -0,0 +1,282 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use std::time::Duration;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::services::events::{CredentialExpiringEventData, EventEnvelope, CREDENTIAL_EXPIRING};
+use crate::services::notifications::Notifier;
+
+/// Stav expirace credentials v list endpointech
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpiryState {
+    Valid,
+    ExpiringSoon,
+    Expired,
+}
+
+/// Credentials s expirací v okně `warn_days` (včetně již expirovaných)
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ExpiringCredential {
+    /// `registry`, `environment_registry` nebo `git_repository`
+    pub credential_kind: String,
+    pub credential_id: Uuid,
+    pub tenant_id: Uuid,
+    pub name: String,
+    pub registry_id: Option<Uuid>,
+    pub environment_id: Option<Uuid>,
+    pub environment_slug: Option<String>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl ExpiringCredential {
+    pub fn days_left(&self, now: DateTime<Utc>) -> i64 {
+        days_left(self.expires_at, now)
+    }
+}
+
+/// Celé dny do expirace (zaokrouhleno nahoru; po expiraci <= 0)
+pub fn days_left(expires_at: DateTime<Utc>, now: DateTime<Utc>) -> i64 {
+    let seconds = (expires_at - now).num_seconds();
+    if seconds <= 0 {
+        seconds / 86_400
+    } else {
+        (seconds + 86_399) / 86_400
+    }
+}
+
+pub fn expiry_state_at(expires_at: Option<DateTime<Utc>>, now: DateTime<Utc>, warn_days: i64) -> Option<ExpiryState> {
+    let expires_at = expires_at?;
+    Some(if expires_at <= now {
+        ExpiryState::Expired
+    } else if days_left(expires_at, now) <= warn_days {
+        ExpiryState::ExpiringSoon
+    } else {
+        ExpiryState::Valid
+    })
+}
+
+/// `None` = credentials bez expirace
+pub fn expiry_state(expires_at: Option<DateTime<Utc>>, warn_days: i64) -> Option<ExpiryState> {
+    expiry_state_at(expires_at, Utc::now(), warn_days)
+}
+
+/// Prahy připomínek: `warn_days`, 7, 3, 1 a 0 (= expirováno)
+pub fn reminder_thresholds(warn_days: i64) -> Vec<i64> {
+    let mut thresholds: Vec<i64> = [warn_days, 7, 3, 1, 0]
+        .into_iter()
+        .filter(|days| *days >= 0 && *days <= warn_days)
+        .collect();
+    thresholds.sort_unstable_by(|a, b| b.cmp(a));
+    thresholds.dedup();
+    thresholds
+}
+
+/// Nejnižší dosažený práh; `None` = ještě mimo okno připomínek
+pub fn due_threshold(expires_at: DateTime<Utc>, now: DateTime<Utc>, thresholds: &[i64]) -> Option<i64> {
+    if expires_at <= now {
+        return thresholds.contains(&0).then_some(0);
+    }
+    let days = days_left(expires_at, now);
+    thresholds.iter().copied().filter(|t| *t > 0 && days <= *t).min()
+}
+
+/// Datum expirace z CSV / formuláře: RFC 3339 nebo `YYYY-MM-DD` (půlnoc UTC)
+pub fn parse_expiry(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+                .map(|dt| dt.and_utc())
+        })
+}
+
+/// Aktivní credentials registries, environment overrides a git repozitářů expirující do `within_days`
+pub async fn list_expiring(
+    pool: &PgPool,
+    within_days: i64,
+    tenant_id: Option<Uuid>,
+) -> Result<Vec<ExpiringCredential>, sqlx::Error> {
+    sqlx::query_as::<_, ExpiringCredential>(
+        r#"
+        SELECT * FROM (
+            SELECT 'registry' AS credential_kind, r.id AS credential_id, r.tenant_id, r.name,
+                   r.id AS registry_id, NULL::uuid AS environment_id, NULL::text AS environment_slug,
+                   r.credentials_expire_at AS expires_at
+            FROM registries r
+            WHERE r.credentials_expire_at IS NOT NULL AND r.is_active AND r.auth_type <> 'none'
+            UNION ALL
+            SELECT 'environment_registry', erc.id, r.tenant_id, r.name,
+                   r.id, e.id, e.slug, erc.expires_at
+            FROM environment_registry_credentials erc
+            JOIN registries r ON r.id = erc.registry_id
+            JOIN environments e ON e.id = erc.environment_id
+            WHERE erc.expires_at IS NOT NULL AND r.is_active AND erc.auth_type <> 'none'
+            UNION ALL
+            SELECT 'git_repository', g.id, g.tenant_id, g.name,
+                   NULL, NULL, NULL, g.credentials_expire_at
+            FROM git_repositories g
+            WHERE g.credentials_expire_at IS NOT NULL AND g.git_auth_type <> 'none'
+        ) c
+        WHERE c.expires_at < NOW() + make_interval(days => $1)
+          AND ($2::uuid IS NULL OR c.tenant_id = $2)
+        ORDER BY c.expires_at, c.name
+        "#,
+    )
+    .bind(within_days as i32)
+    .bind(tenant_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Spustí periodické připomínky expirujících credentials (interval 0 = vypnuto)
+pub fn spawn_reminder_task(pool: PgPool, notifier: Notifier, warn_days: i64, interval: Duration) {
+    if interval.is_zero() {
+        info!("Credential expiry reminders disabled");
+        return;
+    }
+
+    info!(
+        "Credential expiry reminders every {}s ({} days ahead, webhook {})",
+        interval.as_secs(),
+        warn_days,
+        if notifier.is_enabled() { "enabled" } else { "disabled" }
+    );
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = send_reminders(&pool, &notifier, warn_days).await {
+                warn!(error = %e, "Credential expiry reminder run failed");
+            }
+        }
+    });
+}
+
+/// Pošle připomínku za každý nově dosažený práh. Záznam v `credential_expiry_reminders`
+/// zajistí, že ji při více procesech pošle jen jeden; nedoručená se smaže a zkusí znovu.
+pub async fn send_reminders(pool: &PgPool, notifier: &Notifier, warn_days: i64) -> Result<usize, sqlx::Error> {
+    let thresholds = reminder_thresholds(warn_days);
+    let now = Utc::now();
+    let mut sent = 0;
+    for credential in list_expiring(pool, warn_days, None).await? {
+        let Some(threshold) = due_threshold(credential.expires_at, now, &thresholds) else {
+            continue;
+        };
+        let claimed = sqlx::query(
+            "INSERT INTO credential_expiry_reminders (credential_kind, credential_id, expires_at, threshold_days)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(&credential.credential_kind)
+        .bind(credential.credential_id)
+        .bind(credential.expires_at)
+        .bind(threshold as i32)
+        .execute(pool)
+        .await?
+        .rows_affected()
+            > 0;
+        if !claimed {
+            continue;
+        }
+
+        let days_left = credential.days_left(now);
+        let expired = credential.expires_at <= now;
+        warn!(
+            kind = %credential.credential_kind,
+            name = %credential.name,
+            environment = credential.environment_slug.as_deref().unwrap_or("-"),
+            expires_at = %credential.expires_at,
+            days_left,
+            "{}",
+            if expired { "Stored credentials expired" } else { "Stored credentials expire soon" }
+        );
+
+        let envelope = EventEnvelope::new(
+            &CREDENTIAL_EXPIRING,
+            Some(credential.tenant_id),
+            CredentialExpiringEventData {
+                credential_kind: credential.credential_kind.clone(),
+                credential_id: credential.credential_id,
+                name: credential.name.clone(),
+                registry_id: credential.registry_id,
+                environment_id: credential.environment_id,
+                expires_at: credential.expires_at,
+                days_left,
+                expired,
+            },
+        );
+        if notifier.send(&envelope).await.is_err() {
+            sqlx::query(
+                "DELETE FROM credential_expiry_reminders
+                 WHERE credential_kind = $1 AND credential_id = $2 AND expires_at = $3 AND threshold_days = $4",
+            )
+            .bind(&credential.credential_kind)
+            .bind(credential.credential_id)
+            .bind(credential.expires_at)
+            .bind(threshold as i32)
+            .execute(pool)
+            .await?;
+            continue;
+        }
+        sent += 1;
+    }
+    Ok(sent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration as ChronoDuration;
+
+    #[test]
+    fn state_follows_warning_window() {
+        let now = Utc::now();
+        assert_eq!(expiry_state_at(None, now, 14), None);
+        assert_eq!(
+            expiry_state_at(Some(now + ChronoDuration::days(30)), now, 14),
+            Some(ExpiryState::Valid)
+        );
+        assert_eq!(
+            expiry_state_at(Some(now + ChronoDuration::days(14)), now, 14),
+            Some(ExpiryState::ExpiringSoon)
+        );
+        assert_eq!(expiry_state_at(Some(now), now, 14), Some(ExpiryState::Expired));
+    }
+
+    #[test]
+    fn reminders_fire_once_per_reached_threshold() {
+        let now = Utc::now();
+        let thresholds = reminder_thresholds(14);
+        assert_eq!(thresholds, vec![14, 7, 3, 1, 0]);
+        assert_eq!(reminder_thresholds(5), vec![5, 3, 1, 0]);
+
+        let at = |hours: i64| due_threshold(now + ChronoDuration::hours(hours), now, &thresholds);
+        assert_eq!(at(20 * 24), None);
+        assert_eq!(at(10 * 24), Some(14));
+        assert_eq!(at(7 * 24), Some(7));
+        assert_eq!(at(2 * 24 + 1), Some(3));
+        assert_eq!(at(5), Some(1));
+        assert_eq!(at(-1), Some(0));
+    }
+
+    #[test]
+    fn parses_dates_and_timestamps() {
+        assert_eq!(
+            parse_expiry("2026-12-31").map(|dt| dt.to_rfc3339()),
+            Some("2026-12-31T00:00:00+00:00".to_string())
+        );
+        assert_eq!(
+            parse_expiry("2026-12-31T12:00:00+02:00").map(|dt| dt.to_rfc3339()),
+            Some("2026-12-31T10:00:00+00:00".to_string())
+        );
+        assert_eq!(parse_expiry("next week"), None);
+    }
+}
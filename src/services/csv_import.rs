@@ -0,0 +1,170 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Row-level chyba importu; `row` je pořadí záznamu v souboru (hlavička = 1)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CsvRowError {
+    pub row: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<String>,
+    pub error: String,
+}
+
+impl CsvRowError {
+    pub fn new(row: usize, column: Option<&str>, error: impl Into<String>) -> Self {
+        CsvRowError {
+            row,
+            column: column.map(str::to_string),
+            error: error.into(),
+        }
+    }
+}
+
+/// Výsledek CSV importu
+#[derive(Debug, Serialize)]
+pub struct CsvImportResult {
+    pub validate_only: bool,
+    pub total_rows: usize,
+    pub imported: usize,
+    pub errors: Vec<CsvRowError>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CsvImportQuery {
+    pub validate_only: Option<bool>,
+}
+
+/// One data row keyed by normalized (lowercase) header names
+#[derive(Debug, Clone)]
+pub struct CsvRecord {
+    pub row: usize,
+    values: HashMap<String, String>,
+}
+
+impl CsvRecord {
+    /// Trimmed value, `None` when the column is missing or empty
+    pub fn get(&self, column: &str) -> Option<&str> {
+        self.values
+            .get(column)
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+    }
+}
+
+/// Parses CSV with a header row. Delimiter is `,` or `;` (detected from the header line,
+/// spreadsheet exports with a Czech locale use semicolons).
+pub fn parse_records(input: &str, required: &[&str]) -> Result<Vec<CsvRecord>, Vec<CsvRowError>> {
+    let input = input.trim_start_matches('\u{feff}');
+    let header_line = input.lines().next().unwrap_or("");
+    let delimiter = if header_line.contains(';') && !header_line.contains(',') {
+        ';'
+    } else {
+        ','
+    };
+
+    let rows = parse_rows(input, delimiter).map_err(|e| vec![e])?;
+    let mut rows = rows.into_iter();
+    let Some(header) = rows.next() else {
+        return Err(vec![CsvRowError::new(1, None, "CSV is empty")]);
+    };
+    let header: Vec<String> = header.iter().map(|h| h.trim().to_ascii_lowercase()).collect();
+
+    let missing: Vec<CsvRowError> = required
+        .iter()
+        .filter(|col| !header.iter().any(|h| h == *col))
+        .map(|col| CsvRowError::new(1, Some(col), "Missing required column"))
+        .collect();
+    if !missing.is_empty() {
+        return Err(missing);
+    }
+
+    let mut records = Vec::new();
+    let mut errors = Vec::new();
+    for (idx, fields) in rows.enumerate() {
+        let row = idx + 2;
+        if fields.iter().all(|f| f.trim().is_empty()) {
+            continue;
+        }
+        if fields.len() > header.len() {
+            errors.push(CsvRowError::new(
+                row,
+                None,
+                format!("Expected {} columns, found {}", header.len(), fields.len()),
+            ));
+            continue;
+        }
+        let values = header.iter().cloned().zip(fields).collect();
+        records.push(CsvRecord { row, values });
+    }
+
+    if errors.is_empty() {
+        Ok(records)
+    } else {
+        Err(errors)
+    }
+}
+
+fn parse_rows(input: &str, delimiter: char) -> Result<Vec<Vec<String>>, CsvRowError> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = input.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if in_quotes {
+            match ch {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => field.push(ch),
+            }
+            continue;
+        }
+        match ch {
+            '"' if field.is_empty() => in_quotes = true,
+            '\r' => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            c if c == delimiter => row.push(std::mem::take(&mut field)),
+            _ => field.push(ch),
+        }
+    }
+
+    if in_quotes {
+        return Err(CsvRowError::new(rows.len() + 1, None, "Unterminated quoted field"));
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_quoted_fields_and_semicolons() {
+        let input = "app_name;source_image\n\"api\";\"repo/a;b \"\"x\"\"\"\r\n\nweb;repo/web\n";
+        let records = parse_records(input, &["app_name", "source_image"]).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].get("source_image"), Some("repo/a;b \"x\""));
+        assert_eq!(records[1].row, 4);
+        assert_eq!(records[1].get("container_name"), None);
+    }
+
+    #[test]
+    fn reports_missing_columns_and_bad_rows() {
+        let errors = parse_records("app_name\nx", &["app_name", "source_image"]).unwrap_err();
+        assert_eq!(errors, vec![CsvRowError::new(1, Some("source_image"), "Missing required column")]);
+
+        let errors = parse_records("a,b\n1,2,3\n", &["a"]).unwrap_err();
+        assert_eq!(errors[0].row, 2);
+    }
+}
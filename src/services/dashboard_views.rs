@@ -0,0 +1,128 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Materialized views, které refreshuje background task
+pub const SUMMARY_VIEWS: &[&str] = &[
+    "deploy_job_list_mv",
+    "copy_job_list_mv",
+    "dashboard_tenant_stats_mv",
+];
+
+/// Starší summary view se nepoužije a endpoint čte živě (vypnutý nebo zaseknutý refresh)
+pub const SUMMARY_MAX_AGE_SECONDS: i64 = 120;
+
+/// Hlavička se zdrojem dat (`summary` / `live`)
+pub const DATA_SOURCE_HEADER: &str = "x-data-source";
+/// Hlavička s časem refreshe summary view (RFC 3339)
+pub const REFRESHED_AT_HEADER: &str = "x-data-refreshed-at";
+
+/// Spustí background task, který periodicky refreshuje summary views
+pub fn spawn_refresh_task(pool: PgPool, interval: Duration) {
+    if interval.is_zero() {
+        info!("Dashboard summary refresh disabled");
+        return;
+    }
+
+    info!("Dashboard summary refresh every {}s", interval.as_secs());
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            for view in SUMMARY_VIEWS {
+                if let Err(e) = refresh_view(&pool, view).await {
+                    warn!(view, error = %e, "Failed to refresh dashboard summary view");
+                }
+            }
+        }
+    });
+}
+
+pub async fn refresh_view(pool: &PgPool, view: &str) -> Result<(), sqlx::Error> {
+    // Čas před refreshem - job listy podle něj dočítají živě joby, které view ještě nezachytil
+    let snapshot_at = Utc::now();
+    let started = Instant::now();
+    // Názvy view jsou konstanty ze SUMMARY_VIEWS, ne uživatelský vstup
+    sqlx::query(&format!("REFRESH MATERIALIZED VIEW CONCURRENTLY {}", view))
+        .execute(pool)
+        .await?;
+    let duration_ms = started.elapsed().as_millis() as i64;
+
+    sqlx::query(
+        "INSERT INTO dashboard_view_refreshes (view_name, refreshed_at, duration_ms)
+         VALUES ($1, $2, $3)
+         ON CONFLICT (view_name) DO UPDATE SET refreshed_at = EXCLUDED.refreshed_at, duration_ms = EXCLUDED.duration_ms",
+    )
+    .bind(view)
+    .bind(snapshot_at)
+    .bind(duration_ms)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Čas posledního refreshe view; `None` = ještě nebyl
+pub async fn view_refreshed_at(pool: &PgPool, view: &str) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    sqlx::query_scalar::<_, DateTime<Utc>>(
+        "SELECT refreshed_at FROM dashboard_view_refreshes WHERE view_name = $1",
+    )
+    .bind(view)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Čas refreshe, pokud je view dost čerstvé pro odpověď
+pub async fn usable_summary(pool: &PgPool, view: &str) -> Option<DateTime<Utc>> {
+    let refreshed_at = view_refreshed_at(pool, view).await.ok().flatten()?;
+    is_fresh(refreshed_at, Utc::now()).then_some(refreshed_at)
+}
+
+fn is_fresh(refreshed_at: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+    (now - refreshed_at).num_seconds() <= SUMMARY_MAX_AGE_SECONDS
+}
+
+/// Zdroj job listu nad summary view: hotové joby z view, běžící a nové (od refreshe) živě.
+/// Hotový stav se už nemění, takže list nezaostává za právě spuštěným nebo doběhlým jobem.
+pub fn job_list_source(view: &str, id_column: &str, live_query: &str) -> String {
+    format!(
+        "(SELECT * FROM {view} WHERE status IN ('success', 'failed', 'cancelled')
+          UNION ALL
+          SELECT * FROM ({live_query}) live
+          WHERE live.{id_column} NOT IN (SELECT {id_column} FROM {view} WHERE status IN ('success', 'failed', 'cancelled'))
+            AND (live.{id_column} IN (SELECT {id_column} FROM {view} WHERE status NOT IN ('success', 'failed', 'cancelled'))
+                 OR live.started_at > (SELECT refreshed_at FROM dashboard_view_refreshes WHERE view_name = '{view}') - INTERVAL '1 minute')
+        ) jobs"
+    )
+}
+
+/// Freshness headers pro list endpointy
+pub fn freshness_headers(refreshed_at: Option<DateTime<Utc>>) -> axum::http::HeaderMap {
+    let mut headers = axum::http::HeaderMap::new();
+    match refreshed_at {
+        Some(ts) => {
+            headers.insert(DATA_SOURCE_HEADER, axum::http::HeaderValue::from_static("summary"));
+            if let Ok(value) = axum::http::HeaderValue::from_str(&ts.to_rfc3339()) {
+                headers.insert(REFRESHED_AT_HEADER, value);
+            }
+        }
+        None => {
+            headers.insert(DATA_SOURCE_HEADER, axum::http::HeaderValue::from_static("live"));
+        }
+    }
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn freshness_respects_max_age() {
+        let now = Utc::now();
+        assert!(is_fresh(now - chrono::Duration::seconds(SUMMARY_MAX_AGE_SECONDS), now));
+        assert!(!is_fresh(now - chrono::Duration::seconds(SUMMARY_MAX_AGE_SECONDS + 1), now));
+    }
+}
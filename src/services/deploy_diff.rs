@@ -0,0 +1,181 @@
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+use crate::db::models::DeployJobDiff;
+use crate::services::object_storage::ObjectStorage;
+
+/// Limity pro ukládání a vracení deploy diffů
+#[derive(Debug, Clone, Copy)]
+pub struct DiffLimits {
+    /// Větší patch se uloží zkrácený (na hranici souboru)
+    pub max_bytes: usize,
+    /// Větší patch se v `GET /diff` nevrací celý, jen index souborů pro `?file=`
+    pub inline_max_bytes: usize,
+}
+
+impl Default for DiffLimits {
+    fn default() -> Self {
+        DiffLimits {
+            max_bytes: 50 * 1024 * 1024,
+            inline_max_bytes: 2 * 1024 * 1024,
+        }
+    }
+}
+
+/// Jeden soubor v patchi - byte rozsah v celém (rozbaleném) patchi
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffFileEntry {
+    pub path: String,
+    pub offset: usize,
+    pub length: usize,
+    pub additions: u32,
+    pub deletions: u32,
+}
+
+/// Rozdělí `git diff` výstup podle hlaviček `diff --git a/... b/...`
+pub fn index_patch(patch: &str) -> Vec<DiffFileEntry> {
+    let mut entries: Vec<DiffFileEntry> = Vec::new();
+    let mut offset = 0;
+    for line in patch.split_inclusive('\n') {
+        if let Some(header) = line.strip_prefix("diff --git ") {
+            if let Some(last) = entries.last_mut() {
+                last.length = offset - last.offset;
+            }
+            let path = header
+                .trim_end()
+                .rsplit_once(" b/")
+                .map(|(_, path)| path.to_string())
+                .unwrap_or_else(|| header.trim_end().to_string());
+            entries.push(DiffFileEntry {
+                path,
+                offset,
+                length: 0,
+                additions: 0,
+                deletions: 0,
+            });
+        } else if let Some(entry) = entries.last_mut() {
+            if line.starts_with('+') && !line.starts_with("+++ ") {
+                entry.additions += 1;
+            } else if line.starts_with('-') && !line.starts_with("--- ") {
+                entry.deletions += 1;
+            }
+        }
+        offset += line.len();
+    }
+    if let Some(last) = entries.last_mut() {
+        last.length = offset - last.offset;
+    }
+    entries
+}
+
+/// Zkrátí patch na `max_bytes` - na hranici souboru, jinak na hranici řádku
+pub fn cap_patch(patch: String, max_bytes: usize) -> (String, bool) {
+    if patch.len() <= max_bytes {
+        return (patch, false);
+    }
+    let cut = index_patch(&patch)
+        .iter()
+        .map(|entry| entry.offset + entry.length)
+        .take_while(|end| *end <= max_bytes)
+        .last()
+        .unwrap_or_else(|| {
+            let mut end = max_bytes;
+            while !patch.is_char_boundary(end) {
+                end -= 1;
+            }
+            patch[..end].rfind('\n').map(|pos| pos + 1).unwrap_or(0)
+        });
+    (patch[..cut].to_string(), true)
+}
+
+pub fn compress(patch: &str) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(patch.as_bytes())?;
+    Ok(encoder.finish()?)
+}
+
+pub fn decompress(bytes: &[u8]) -> Result<String> {
+    let mut patch = String::new();
+    GzDecoder::new(bytes)
+        .read_to_string(&mut patch)
+        .context("Failed to decompress deploy diff")?;
+    Ok(patch)
+}
+
+/// Celý patch bez ohledu na to, kde je uložený (text, gzip v DB, object storage)
+pub async fn load_patch(storage: &ObjectStorage, diff: &DeployJobDiff) -> Result<String> {
+    if let Some(key) = diff.diff_patch_key.as_deref() {
+        let body = storage.get(key).await?;
+        return if key.ends_with(".gz") {
+            decompress(&body)
+        } else {
+            Ok(String::from_utf8_lossy(&body).into_owned())
+        };
+    }
+    if let Some(gz) = diff.diff_patch_gz.as_deref() {
+        return decompress(gz);
+    }
+    Ok(diff.diff_patch.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATCH: &str = "diff --git a/app/deploy.yml b/app/deploy.yml\n\
+index 1..2 100644\n\
+--- a/app/deploy.yml\n\
++++ b/app/deploy.yml\n\
+@@ -1 +1 @@\n\
+-image: app:1\n\
++image: app:2\n\
+diff --git a/app/svc.yml b/app/svc.yml\n\
+new file mode 100644\n\
+--- /dev/null\n\
++++ b/app/svc.yml\n\
+@@ -0,0 +1,2 @@\n\
++kind: Service\n\
++name: app\n";
+
+    #[test]
+    fn index_splits_files_with_counts() {
+        let index = index_patch(PATCH);
+        assert_eq!(index.len(), 2);
+        assert_eq!(index[0].path, "app/deploy.yml");
+        assert_eq!((index[0].additions, index[0].deletions), (1, 1));
+        assert_eq!(index[1].path, "app/svc.yml");
+        assert_eq!((index[1].additions, index[1].deletions), (2, 0));
+        assert_eq!(index[1].offset + index[1].length, PATCH.len());
+        assert!(PATCH[index[1].offset..].starts_with("diff --git a/app/svc.yml"));
+    }
+
+    #[test]
+    fn cap_cuts_at_file_boundary() {
+        let first_len = index_patch(PATCH)[0].length;
+        let (capped, truncated) = cap_patch(PATCH.to_string(), first_len + 10);
+        assert!(truncated);
+        assert_eq!(capped.len(), first_len);
+
+        let (whole, truncated) = cap_patch(PATCH.to_string(), PATCH.len());
+        assert!(!truncated);
+        assert_eq!(whole, PATCH);
+    }
+
+    #[test]
+    fn cap_falls_back_to_line_boundary() {
+        let (capped, truncated) = cap_patch(PATCH.to_string(), 50);
+        assert!(truncated);
+        assert!(capped.len() <= 50);
+        assert!(capped.ends_with('\n'));
+    }
+
+    #[test]
+    fn gzip_roundtrip() {
+        let packed = compress(PATCH).unwrap();
+        assert_eq!(decompress(&packed).unwrap(), PATCH);
+    }
+}
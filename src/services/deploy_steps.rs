@@ -0,0 +1,115 @@
+use sqlx::PgPool;
+use tracing::warn;
+use uuid::Uuid;
+
+/// Kroky deploy jobu v pořadí, v jakém je `run_deploy_job` prochází
+pub const DEPLOY_STEPS: [&str; 7] = ["prepare", "clone", "render", "encjson", "kubeconform", "diff", "push"];
+
+/// Založí všechny kroky jako `pending`
+pub async fn init(pool: &PgPool, job_id: Uuid) {
+    let positions: Vec<i32> = (0..DEPLOY_STEPS.len() as i32).collect();
+    let result = sqlx::query(
+        "INSERT INTO deploy_job_steps (deploy_job_id, step, position)
+         SELECT $1, step, position FROM UNNEST($2::text[], $3::int[]) AS s(step, position)
+         ON CONFLICT (deploy_job_id, step) DO NOTHING",
+    )
+    .bind(job_id)
+    .bind(&DEPLOY_STEPS[..])
+    .bind(&positions)
+    .execute(pool)
+    .await;
+    if let Err(e) = result {
+        warn!(job_id = %job_id, error = %e, "Failed to init deploy job steps");
+    }
+}
+
+pub async fn start(pool: &PgPool, job_id: Uuid, step: &str) {
+    let result = sqlx::query(
+        "UPDATE deploy_job_steps SET status = 'in_progress', started_at = NOW()
+         WHERE deploy_job_id = $1 AND step = $2",
+    )
+    .bind(job_id)
+    .bind(step)
+    .execute(pool)
+    .await;
+    log_failure(result, job_id, step);
+}
+
+pub async fn success(pool: &PgPool, job_id: Uuid, step: &str) {
+    finish(pool, job_id, step, "success", None).await;
+}
+
+/// Krok doběhl, ale jeho chyba se ignoruje (např. kubeconform)
+pub async fn warning(pool: &PgPool, job_id: Uuid, step: &str, message: &str) {
+    finish(pool, job_id, step, "warning", first_error_line(message)).await;
+}
+
+pub async fn skip(pool: &PgPool, job_id: Uuid, step: &str, reason: &str) {
+    finish(pool, job_id, step, "skipped", Some(reason.to_string())).await;
+}
+
+/// Po chybě jobu: běžící krok je `failed` s prvním řádkem chyby, zbylé `skipped`
+pub async fn fail(pool: &PgPool, job_id: Uuid, error: &anyhow::Error) {
+    let error_line = first_error_line(&format!("{:#}", error));
+    let result = sqlx::query(
+        "UPDATE deploy_job_steps SET
+            status = CASE WHEN status = 'in_progress' THEN 'failed' ELSE 'skipped' END,
+            error_line = CASE WHEN status = 'in_progress' THEN $2 ELSE error_line END,
+            completed_at = NOW(),
+            duration_ms = (EXTRACT(EPOCH FROM (NOW() - started_at)) * 1000)::BIGINT
+         WHERE deploy_job_id = $1 AND status IN ('pending', 'in_progress')",
+    )
+    .bind(job_id)
+    .bind(error_line)
+    .execute(pool)
+    .await;
+    if let Err(e) = result {
+        warn!(job_id = %job_id, error = %e, "Failed to mark deploy job steps as failed");
+    }
+}
+
+async fn finish(pool: &PgPool, job_id: Uuid, step: &str, status: &str, error_line: Option<String>) {
+    let result = sqlx::query(
+        "UPDATE deploy_job_steps SET status = $3, error_line = $4, completed_at = NOW(),
+            duration_ms = (EXTRACT(EPOCH FROM (NOW() - started_at)) * 1000)::BIGINT
+         WHERE deploy_job_id = $1 AND step = $2",
+    )
+    .bind(job_id)
+    .bind(step)
+    .bind(status)
+    .bind(error_line)
+    .execute(pool)
+    .await;
+    log_failure(result, job_id, step);
+}
+
+// Evidence kroků je jen informativní - chyba DB kvůli ní deploy neshodí
+fn log_failure<T>(result: Result<T, sqlx::Error>, job_id: Uuid, step: &str) {
+    if let Err(e) = result {
+        warn!(job_id = %job_id, step, error = %e, "Failed to update deploy job step");
+    }
+}
+
+/// První neprázdný řádek chybové zprávy
+pub fn first_error_line(message: &str) -> Option<String> {
+    message
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(|line| line.chars().take(500).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_error_line_skips_blank_lines() {
+        assert_eq!(
+            first_error_line("\n  kube_build_app failed: missing values.yaml\nstack trace").as_deref(),
+            Some("kube_build_app failed: missing values.yaml")
+        );
+        assert_eq!(first_error_line(" \n"), None);
+        assert_eq!(first_error_line(&"x".repeat(600)).map(|line| line.len()), Some(500));
+    }
+}
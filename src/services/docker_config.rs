@@ -0,0 +1,215 @@
+use base64::{engine::general_purpose, Engine};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Docker Hub se v config.json objevuje pod několika jmény
+const DOCKER_HUB_ALIASES: &[&str] = &["index.docker.io", "registry-1.docker.io", "registry.hub.docker.com"];
+const DOCKER_HUB: &str = "docker.io";
+
+#[derive(Debug, Default, Deserialize)]
+struct DockerConfigFile {
+    #[serde(default)]
+    auths: BTreeMap<String, DockerAuthEntry>,
+    #[serde(rename = "credHelpers", default)]
+    cred_helpers: BTreeMap<String, String>,
+    #[serde(rename = "credsStore")]
+    creds_store: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct DockerAuthEntry {
+    auth: Option<String>,
+    username: Option<String>,
+    password: Option<String>,
+    identitytoken: Option<String>,
+    registrytoken: Option<String>,
+}
+
+/// Credentials jedné registry z config.json
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DockerCredential {
+    /// Normalizovaný host (bez schématu a cesty, lowercase)
+    pub host: String,
+    /// `basic` (username + password) nebo `bearer` (registry token)
+    pub auth_type: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub token: Option<String>,
+}
+
+/// Záznam, který nejde převést na uložené credentials
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SkippedDockerAuth {
+    pub host: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Default)]
+pub struct ParsedDockerConfig {
+    pub credentials: Vec<DockerCredential>,
+    pub skipped: Vec<SkippedDockerAuth>,
+    /// Chyby, kvůli kterým se neimportuje nic (neplatný `auth` apod.)
+    pub errors: Vec<String>,
+}
+
+/// Host registry z klíče `auths` nebo z `base_url` registry
+pub fn normalize_host(value: &str) -> String {
+    let value = value.trim();
+    let value = value
+        .strip_prefix("https://")
+        .or_else(|| value.strip_prefix("http://"))
+        .unwrap_or(value);
+    let host = value.split('/').next().unwrap_or_default().to_ascii_lowercase();
+    if DOCKER_HUB_ALIASES.contains(&host.as_str()) {
+        DOCKER_HUB.to_string()
+    } else {
+        host
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// Rozparsuje docker config.json (`auths`, `credHelpers`, `credsStore`)
+pub fn parse(body: &str) -> Result<ParsedDockerConfig, String> {
+    let config: DockerConfigFile =
+        serde_json::from_str(body).map_err(|e| format!("Invalid docker config.json: {}", e))?;
+
+    let mut parsed = ParsedDockerConfig::default();
+    let mut seen: HashMap<String, String> = HashMap::new();
+    for (key, entry) in config.auths {
+        let host = normalize_host(&key);
+        if host.is_empty() {
+            parsed.errors.push(format!("Invalid registry key '{}'", key));
+            continue;
+        }
+        if let Some(previous) = seen.get(&host) {
+            parsed
+                .errors
+                .push(format!("Registry '{}' is listed twice ('{}' and '{}')", host, previous, key));
+            continue;
+        }
+        seen.insert(host.clone(), key.clone());
+
+        let mut username = non_empty(entry.username);
+        let mut password = non_empty(entry.password);
+        if let Some(auth) = non_empty(entry.auth) {
+            let decoded = general_purpose::STANDARD
+                .decode(auth.as_bytes())
+                .ok()
+                .and_then(|bytes| String::from_utf8(bytes).ok());
+            let Some((user, pass)) = decoded.as_deref().and_then(|value| value.split_once(':')) else {
+                parsed
+                    .errors
+                    .push(format!("Registry '{}': 'auth' is not base64 encoded 'username:password'", key));
+                continue;
+            };
+            username = non_empty(Some(user.to_string()));
+            password = non_empty(Some(pass.to_string()));
+        }
+
+        if let (Some(username), Some(password)) = (&username, &password) {
+            parsed.credentials.push(DockerCredential {
+                host,
+                auth_type: "basic".to_string(),
+                username: Some(username.clone()),
+                password: Some(password.clone()),
+                token: None,
+            });
+        } else if let Some(token) = non_empty(entry.registrytoken) {
+            parsed.credentials.push(DockerCredential {
+                host,
+                auth_type: "bearer".to_string(),
+                username: None,
+                password: None,
+                token: Some(token),
+            });
+        } else if entry.identitytoken.is_some() {
+            parsed.skipped.push(SkippedDockerAuth {
+                host,
+                reason: "identitytoken (OAuth refresh token) is not supported".to_string(),
+            });
+        } else {
+            // Prázdný záznam = credentials drží credsStore
+            let reason = match &config.creds_store {
+                Some(store) => format!("credentials are stored in credential store '{}'", store),
+                None => "entry has no credentials".to_string(),
+            };
+            parsed.skipped.push(SkippedDockerAuth { host, reason });
+        }
+    }
+
+    for (key, helper) in config.cred_helpers {
+        let host = normalize_host(&key);
+        if seen.contains_key(&host) {
+            continue;
+        }
+        parsed.skipped.push(SkippedDockerAuth {
+            host,
+            reason: format!("credential helper '{}' cannot be resolved on the server", helper),
+        });
+    }
+
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_auth_username_password_and_registry_token() {
+        let auth = general_purpose::STANDARD.encode("ci-bot:s3cr:et");
+        let body = format!(
+            r#"{{
+                "auths": {{
+                    "https://Harbor.example.com/v2/": {{"auth": "{}"}},
+                    "quay.io": {{"username": "robot", "password": "pw"}},
+                    "ghcr.io": {{"registrytoken": "tok"}},
+                    "https://index.docker.io/v1/": {{}},
+                    "gcr.io": {{"identitytoken": "refresh"}}
+                }},
+                "credsStore": "desktop",
+                "credHelpers": {{"123.dkr.ecr.eu-west-1.amazonaws.com": "ecr-login", "quay.io": "x"}}
+            }}"#,
+            auth
+        );
+        let parsed = parse(&body).unwrap();
+        assert!(parsed.errors.is_empty());
+
+        let harbor = parsed.credentials.iter().find(|c| c.host == "harbor.example.com").unwrap();
+        assert_eq!(harbor.auth_type, "basic");
+        assert_eq!(harbor.username.as_deref(), Some("ci-bot"));
+        assert_eq!(harbor.password.as_deref(), Some("s3cr:et"));
+        assert!(parsed.credentials.iter().any(|c| c.host == "quay.io" && c.password.as_deref() == Some("pw")));
+        let ghcr = parsed.credentials.iter().find(|c| c.host == "ghcr.io").unwrap();
+        assert_eq!(ghcr.auth_type, "bearer");
+        assert_eq!(ghcr.token.as_deref(), Some("tok"));
+
+        let skipped: Vec<&str> = parsed.skipped.iter().map(|s| s.host.as_str()).collect();
+        assert_eq!(skipped, vec!["gcr.io", "docker.io", "123.dkr.ecr.eu-west-1.amazonaws.com"]);
+        assert!(parsed.skipped[1].reason.contains("desktop"));
+    }
+
+    #[test]
+    fn invalid_auth_and_duplicates_are_errors() {
+        assert!(parse("not json").is_err());
+        let body = r#"{"auths": {
+            "registry.local": {"auth": "bm90LWJhc2U2NA-"},
+            "docker.io": {"username": "a", "password": "b"},
+            "https://index.docker.io/v1/": {"username": "a", "password": "b"}
+        }}"#;
+        let parsed = parse(body).unwrap();
+        assert_eq!(parsed.errors.len(), 2);
+        assert!(parsed.errors.iter().any(|e| e.contains("registry.local")));
+        assert!(parsed.errors.iter().any(|e| e.contains("listed twice")));
+    }
+
+    #[test]
+    fn hosts_are_normalized() {
+        assert_eq!(normalize_host("https://registry.example.com:5000/v2/"), "registry.example.com:5000");
+        assert_eq!(normalize_host("Registry.Example.com/team/project"), "registry.example.com");
+        assert_eq!(normalize_host("registry-1.docker.io"), "docker.io");
+    }
+}
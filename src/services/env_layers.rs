@@ -0,0 +1,268 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Vrstva, ze které pochází proměnná - pozdější vrstva přepisuje dřívější
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnvLayer {
+    /// Výchozí hodnoty deploy targetu (`deploy_target_env_vars`, `deploy_target_extra_env_vars`)
+    Target,
+    /// `environments.release_env_var_mappings` / `environments.extra_env_vars`
+    Environment,
+    /// Přepsání při vytvoření jobu (`deploy_jobs.env_overrides`)
+    Job,
+}
+
+impl EnvLayer {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EnvLayer::Target => "target",
+            EnvLayer::Environment => "environment",
+            EnvLayer::Job => "job",
+        }
+    }
+}
+
+/// Hodnoty jedné vrstvy
+#[derive(Debug, Clone, Default)]
+pub struct EnvLayerValues {
+    /// source_key -> target_key
+    pub mappings: Vec<(String, String)>,
+    /// key -> value
+    pub extra_env_vars: Vec<(String, String)>,
+}
+
+impl EnvLayerValues {
+    /// JSON objekty ve tvaru, v jakém je ukládá environment (`{"KEY": "value"}`)
+    pub fn from_json(mappings: &serde_json::Value, extra_env_vars: &serde_json::Value) -> Self {
+        EnvLayerValues {
+            mappings: json_pairs(mappings),
+            extra_env_vars: json_pairs(extra_env_vars),
+        }
+    }
+}
+
+fn json_pairs(value: &serde_json::Value) -> Vec<(String, String)> {
+    value
+        .as_object()
+        .map(|map| {
+            map.iter()
+                .map(|(key, value)| (key.clone(), value.as_str().unwrap_or("").to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResolvedMapping {
+    pub source_key: String,
+    pub target_key: String,
+    pub layer: EnvLayer,
+    /// Nižší vrstvy, jejichž hodnotu tato přepsala
+    pub overrides: Vec<EnvLayer>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResolvedEnvVar {
+    pub key: String,
+    pub value: String,
+    pub layer: EnvLayer,
+    /// Nižší vrstvy, jejichž hodnotu tato přepsala
+    pub overrides: Vec<EnvLayer>,
+}
+
+/// Výsledná sada proměnných pro deploy job
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResolvedEnv {
+    pub release_env_var_mappings: Vec<ResolvedMapping>,
+    pub extra_env_vars: Vec<ResolvedEnvVar>,
+}
+
+/// Složí vrstvy v pořadí target ← environment ← job.
+/// Mapování se slučují podle `target_key` (výsledná proměnná), extra proměnné podle `key`.
+pub fn resolve(layers: &[(EnvLayer, EnvLayerValues)]) -> ResolvedEnv {
+    let mut mappings: BTreeMap<String, ResolvedMapping> = BTreeMap::new();
+    let mut extra: BTreeMap<String, ResolvedEnvVar> = BTreeMap::new();
+
+    for (layer, values) in layers {
+        for (source_key, target_key) in &values.mappings {
+            let (source_key, target_key) = (source_key.trim(), target_key.trim());
+            if source_key.is_empty() || target_key.is_empty() {
+                continue;
+            }
+            let overrides = overridden_layers(mappings.get(target_key).map(|m| (m.layer, &m.overrides)));
+            mappings.insert(
+                target_key.to_string(),
+                ResolvedMapping {
+                    source_key: source_key.to_string(),
+                    target_key: target_key.to_string(),
+                    layer: *layer,
+                    overrides,
+                },
+            );
+        }
+        for (key, value) in &values.extra_env_vars {
+            let key = key.trim();
+            if key.is_empty() {
+                continue;
+            }
+            let overrides = overridden_layers(extra.get(key).map(|v| (v.layer, &v.overrides)));
+            extra.insert(
+                key.to_string(),
+                ResolvedEnvVar {
+                    key: key.to_string(),
+                    value: value.trim().to_string(),
+                    layer: *layer,
+                    overrides,
+                },
+            );
+        }
+    }
+
+    ResolvedEnv {
+        release_env_var_mappings: mappings.into_values().collect(),
+        extra_env_vars: extra.into_values().collect(),
+    }
+}
+
+fn overridden_layers(previous: Option<(EnvLayer, &Vec<EnvLayer>)>) -> Vec<EnvLayer> {
+    let Some((layer, earlier)) = previous else {
+        return Vec::new();
+    };
+    let mut layers = earlier.clone();
+    if !layers.contains(&layer) {
+        layers.push(layer);
+    }
+    layers
+}
+
+/// Název proměnné použitelný v release.env i v prostředí procesu
+pub fn is_valid_env_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Položka allow-listu je přesný název, nebo prefix zakončený `*`
+pub fn is_valid_allowlist_entry(entry: &str) -> bool {
+    match entry.strip_suffix('*') {
+        Some("") => false,
+        Some(prefix) => is_valid_env_key(prefix),
+        None => is_valid_env_key(entry),
+    }
+}
+
+pub fn allowlist_matches(allowlist: &[String], key: &str) -> bool {
+    allowlist.iter().any(|entry| match entry.strip_suffix('*') {
+        Some(prefix) => key.starts_with(prefix),
+        None => entry == key,
+    })
+}
+
+/// Ověří přepsání z `CreateDeployJobRequest.env_overrides` proti allow-listu environmentu
+pub fn validate_job_overrides(overrides: &BTreeMap<String, String>, allowlist: &[String]) -> Result<(), String> {
+    let invalid: Vec<&str> = overrides
+        .keys()
+        .map(String::as_str)
+        .filter(|key| !is_valid_env_key(key))
+        .collect();
+    if !invalid.is_empty() {
+        return Err(format!("Invalid env var names: {}", invalid.join(", ")));
+    }
+    let multiline: Vec<&str> = overrides
+        .iter()
+        .filter(|(_, value)| value.contains(['\n', '\r']))
+        .map(|(key, _)| key.as_str())
+        .collect();
+    if !multiline.is_empty() {
+        return Err(format!("Env var values must be single-line: {}", multiline.join(", ")));
+    }
+    let denied: Vec<&str> = overrides
+        .keys()
+        .map(String::as_str)
+        .filter(|key| !allowlist_matches(allowlist, key))
+        .collect();
+    if !denied.is_empty() {
+        return Err(format!(
+            "Env vars not allowed to be overridden in this environment: {}",
+            denied.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+        items.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn later_layers_win_and_record_overrides() {
+        let resolved = resolve(&[
+            (
+                EnvLayer::Target,
+                EnvLayerValues {
+                    mappings: pairs(&[("SIMPLE_RELEASE_ID", "APP_VERSION")]),
+                    extra_env_vars: pairs(&[("FEATURE_X", "off"), ("REGION", "eu")]),
+                },
+            ),
+            (
+                EnvLayer::Environment,
+                EnvLayerValues {
+                    mappings: pairs(&[("SIMPLE_RELEASE_ID", "RELEASE")]),
+                    extra_env_vars: pairs(&[("FEATURE_X", "on")]),
+                },
+            ),
+            (
+                EnvLayer::Job,
+                EnvLayerValues {
+                    mappings: Vec::new(),
+                    extra_env_vars: pairs(&[("FEATURE_X", "beta"), ("  ", "ignored")]),
+                },
+            ),
+        ]);
+
+        let keys: Vec<&str> = resolved.release_env_var_mappings.iter().map(|m| m.target_key.as_str()).collect();
+        assert_eq!(keys, vec!["APP_VERSION", "RELEASE"]);
+
+        assert_eq!(resolved.extra_env_vars.len(), 2);
+        let feature = &resolved.extra_env_vars[0];
+        assert_eq!((feature.key.as_str(), feature.value.as_str()), ("FEATURE_X", "beta"));
+        assert_eq!(feature.layer, EnvLayer::Job);
+        assert_eq!(feature.overrides, vec![EnvLayer::Target, EnvLayer::Environment]);
+        let region = &resolved.extra_env_vars[1];
+        assert_eq!((region.layer, region.overrides.is_empty()), (EnvLayer::Target, true));
+    }
+
+    #[test]
+    fn job_overrides_respect_allowlist() {
+        let allowlist = vec!["FEATURE_*".to_string(), "LOG_LEVEL".to_string()];
+        let overrides = |items: &[(&str, &str)]| -> BTreeMap<String, String> { pairs(items).into_iter().collect() };
+
+        assert!(validate_job_overrides(&overrides(&[("FEATURE_X", "on"), ("LOG_LEVEL", "debug")]), &allowlist).is_ok());
+        let err = validate_job_overrides(&overrides(&[("FEATURE_X", "on"), ("DB_URL", "x")]), &allowlist).unwrap_err();
+        assert!(err.ends_with(": DB_URL"));
+        assert!(validate_job_overrides(&overrides(&[("1BAD", "x")]), &allowlist).is_err());
+        assert!(validate_job_overrides(&overrides(&[("FEATURE_X", "a\nB=c")]), &allowlist).is_err());
+        assert!(validate_job_overrides(&overrides(&[("LOG_LEVEL", "x")]), &[]).is_err());
+
+        assert!(is_valid_allowlist_entry("FEATURE_*"));
+        assert!(!is_valid_allowlist_entry("*"));
+        assert!(!is_valid_allowlist_entry("BAD-KEY"));
+    }
+
+    #[test]
+    fn from_json_reads_environment_maps() {
+        let values = EnvLayerValues::from_json(
+            &serde_json::json!({"SIMPLE_RELEASE_ID": "VERSION"}),
+            &serde_json::json!({"A": "1", "B": null}),
+        );
+        assert_eq!(values.mappings, pairs(&[("SIMPLE_RELEASE_ID", "VERSION")]));
+        assert_eq!(values.extra_env_vars, pairs(&[("A", "1"), ("B", "")]));
+    }
+}
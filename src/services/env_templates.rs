@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+/// Proměnné dostupné v šabloně napojení environmentu na deploy target
+pub const VARIABLES: [&str; 3] = ["env_slug", "env_name", "target_name"];
+
+/// Hodnoty proměnných pro jedno napojení
+#[derive(Debug, Clone)]
+pub struct TemplateVars {
+    pub env_slug: String,
+    pub env_name: String,
+    pub target_name: String,
+}
+
+impl TemplateVars {
+    fn values(&self) -> HashMap<&'static str, &str> {
+        HashMap::from([
+            ("env_slug", self.env_slug.as_str()),
+            ("env_name", self.env_name.as_str()),
+            ("target_name", self.target_name.as_str()),
+        ])
+    }
+}
+
+/// Části šablony: text a názvy proměnných `{name}`
+fn tokens(pattern: &str) -> Result<Vec<(bool, &str)>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = pattern;
+    while let Some(start) = rest.find('{') {
+        if start > 0 {
+            tokens.push((false, &rest[..start]));
+        }
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("Unclosed '{{' in template '{}'", pattern))?;
+        let name = rest[start + 1..start + end].trim();
+        if !VARIABLES.contains(&name) {
+            return Err(format!(
+                "Unknown variable '{{{}}}' in template '{}' (available: {})",
+                name,
+                pattern,
+                VARIABLES.map(|v| format!("{{{}}}", v)).join(", ")
+            ));
+        }
+        tokens.push((true, name));
+        rest = &rest[start + end + 1..];
+    }
+    if rest.contains('}') {
+        return Err(format!("Unmatched '}}' in template '{}'", pattern));
+    }
+    if !rest.is_empty() {
+        tokens.push((false, rest));
+    }
+    Ok(tokens)
+}
+
+/// Ověří šablonu při uložení (jen známé proměnné, uzavřené závorky)
+pub fn validate(pattern: &str) -> Result<(), String> {
+    tokens(pattern).map(|_| ())
+}
+
+/// Dosadí proměnné do šablony
+pub fn render(pattern: &str, vars: &TemplateVars) -> Result<String, String> {
+    let values = vars.values();
+    Ok(tokens(pattern)?
+        .into_iter()
+        .map(|(is_var, text)| if is_var { values[text] } else { text })
+        .collect())
+}
+
+/// Dosadí proměnné do volitelné šablony; prázdná šablona = nenastaveno
+pub fn render_optional(pattern: Option<&str>, vars: &TemplateVars) -> Result<Option<String>, String> {
+    match pattern.map(str::trim).filter(|p| !p.is_empty()) {
+        Some(pattern) => render(pattern, vars).map(Some),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> TemplateVars {
+        TemplateVars {
+            env_slug: "prod-eu".to_string(),
+            env_name: "Production EU".to_string(),
+            target_name: "billing".to_string(),
+        }
+    }
+
+    #[test]
+    fn variables_are_substituted() {
+        assert_eq!(render("deploy/{env_slug}", &vars()).unwrap(), "deploy/prod-eu");
+        assert_eq!(render("{target_name}-{ env_slug }", &vars()).unwrap(), "billing-prod-eu");
+        assert_eq!(render("static/path", &vars()).unwrap(), "static/path");
+        assert_eq!(render_optional(Some("  "), &vars()).unwrap(), None);
+    }
+
+    #[test]
+    fn invalid_templates_are_rejected() {
+        assert!(validate("deploy/{env}").unwrap_err().contains("Unknown variable"));
+        assert!(validate("deploy/{env_slug").unwrap_err().contains("Unclosed"));
+        assert!(validate("deploy/env_slug}").unwrap_err().contains("Unmatched"));
+        assert!(validate("envs/{env_slug}/{target_name}").is_ok());
+    }
+}
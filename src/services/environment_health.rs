@@ -0,0 +1,350 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::services::credential_expiry::{self, ExpiringCredential, ExpiryState};
+
+/// Semafor přehledu prostředí; pořadí = závažnost (pro výběr nejhoršího)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthLight {
+    Green,
+    /// Stav nejde určit (ArgoCD app ještě nezkontrolovaná, `Unknown` z ArgoCD)
+    Unknown,
+    Yellow,
+    Red,
+}
+
+/// Nejhorší ze stavů; `None` = žádná složka se na prostředí nevztahuje
+pub fn worst(lights: impl IntoIterator<Item = Option<HealthLight>>) -> Option<HealthLight> {
+    lights.into_iter().flatten().max()
+}
+
+/// Poslední (ne dry-run) deploy job prostředí
+pub fn deploy_light(status: &str) -> HealthLight {
+    match status {
+        "success" => HealthLight::Green,
+        "failed" => HealthLight::Red,
+        // pending (čeká na schválení), in_progress, cancelled
+        _ => HealthLight::Yellow,
+    }
+}
+
+/// ArgoCD health status aplikace
+pub fn argocd_health_light(health: Option<&str>) -> HealthLight {
+    match health {
+        Some("Healthy") => HealthLight::Green,
+        Some("Progressing") | Some("Suspended") => HealthLight::Yellow,
+        Some("Degraded") | Some("Missing") => HealthLight::Red,
+        _ => HealthLight::Unknown,
+    }
+}
+
+/// Drift = rozdíl mezi gitem a clusterem (ArgoCD sync status); selhaná sync operace je červená
+pub fn drift_light(sync: Option<&str>, operation_phase: Option<&str>) -> HealthLight {
+    if matches!(operation_phase, Some("Failed") | Some("Error")) {
+        return HealthLight::Red;
+    }
+    match sync {
+        Some("Synced") => HealthLight::Green,
+        Some("OutOfSync") => HealthLight::Yellow,
+        _ => HealthLight::Unknown,
+    }
+}
+
+pub fn expiry_light(expiry: ExpiryState) -> HealthLight {
+    match expiry {
+        ExpiryState::Valid => HealthLight::Green,
+        ExpiryState::ExpiringSoon => HealthLight::Yellow,
+        ExpiryState::Expired => HealthLight::Red,
+    }
+}
+
+/// Registry a git repozitáře, jejichž credentials prostředí používá
+#[derive(Debug, Clone, FromRow)]
+pub struct EnvironmentScope {
+    pub id: Uuid,
+    pub name: String,
+    pub slug: String,
+    pub color: Option<String>,
+    pub registry_ids: Vec<Uuid>,
+    /// Registry s vlastními credentials prostředí - credentials registry se pro ně nepoužijí
+    pub override_registry_ids: Vec<Uuid>,
+    pub git_repository_ids: Vec<Uuid>,
+}
+
+/// Patří expirující credentials k prostředí?
+pub fn credential_applies(scope: &EnvironmentScope, credential: &ExpiringCredential) -> bool {
+    match credential.credential_kind.as_str() {
+        "environment_registry" => credential.environment_id == Some(scope.id),
+        "registry" => credential.registry_id.is_some_and(|registry_id| {
+            scope.registry_ids.contains(&registry_id) && !scope.override_registry_ids.contains(&registry_id)
+        }),
+        "git_repository" => scope.git_repository_ids.contains(&credential.credential_id),
+        _ => false,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct LatestDeploy {
+    #[serde(skip)]
+    pub environment_id: Uuid,
+    pub job_id: Uuid,
+    pub status: String,
+    pub release_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub error_message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ArgocdAppHealth {
+    #[serde(skip)]
+    pub environment_id: Uuid,
+    pub application_name: String,
+    pub sync_status: Option<String>,
+    pub health_status: Option<String>,
+    pub operation_phase: Option<String>,
+    pub last_checked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeployHealth {
+    pub status: Option<HealthLight>,
+    pub latest: Option<LatestDeploy>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ArgocdHealth {
+    pub status: Option<HealthLight>,
+    pub apps: Vec<ArgocdAppHealth>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DriftHealth {
+    pub status: Option<HealthLight>,
+    /// Aplikace, které nejsou `Synced` (nebo jejich sync selhal)
+    pub out_of_sync: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CredentialHealthItem {
+    #[serde(flatten)]
+    pub credential: ExpiringCredential,
+    pub days_left: i64,
+    pub expiry: ExpiryState,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CredentialHealth {
+    pub status: Option<HealthLight>,
+    pub expiring: Vec<CredentialHealthItem>,
+}
+
+/// Souhrn jednoho prostředí pro semaforový přehled
+#[derive(Debug, Serialize)]
+pub struct EnvironmentHealth {
+    pub environment_id: Uuid,
+    pub name: String,
+    pub slug: String,
+    pub color: Option<String>,
+    /// Nejhorší ze složek; `unknown`, když se žádná neuplatní
+    pub status: HealthLight,
+    pub deploy: DeployHealth,
+    pub argocd: ArgocdHealth,
+    pub drift: DriftHealth,
+    pub credentials: CredentialHealth,
+}
+
+async fn load_scopes(pool: &PgPool, tenant_id: Uuid) -> Result<Vec<EnvironmentScope>, sqlx::Error> {
+    sqlx::query_as::<_, EnvironmentScope>(
+        r#"
+        SELECT e.id, e.name, e.slug, e.color,
+               ARRAY_REMOVE(ARRAY[e.source_registry_id, e.target_registry_id], NULL)
+                   || COALESCE((SELECT array_agg(p.registry_id) FROM environment_registry_paths p WHERE p.environment_id = e.id), '{}')
+                   AS registry_ids,
+               COALESCE((SELECT array_agg(c.registry_id) FROM environment_registry_credentials c WHERE c.environment_id = e.id), '{}')
+                   AS override_registry_ids,
+               ARRAY_REMOVE(ARRAY[e.env_repo_id, e.deploy_repo_id], NULL) AS git_repository_ids
+        FROM environments e
+        WHERE e.tenant_id = $1
+        ORDER BY e.name
+        "#,
+    )
+    .bind(tenant_id)
+    .fetch_all(pool)
+    .await
+}
+
+async fn load_latest_deploys(pool: &PgPool, environment_ids: &[Uuid]) -> Result<Vec<LatestDeploy>, sqlx::Error> {
+    sqlx::query_as::<_, LatestDeploy>(
+        r#"
+        SELECT DISTINCT ON (dj.environment_id)
+               dj.environment_id, dj.id AS job_id, dj.status, r.release_id,
+               dj.created_at, dj.completed_at, dj.error_message
+        FROM deploy_jobs dj
+        LEFT JOIN releases r ON r.id = dj.release_id
+        WHERE dj.environment_id = ANY($1) AND NOT dj.dry_run AND NOT dj.validate_only
+        ORDER BY dj.environment_id, dj.created_at DESC
+        "#,
+    )
+    .bind(environment_ids)
+    .fetch_all(pool)
+    .await
+}
+
+async fn load_argocd_apps(pool: &PgPool, environment_ids: &[Uuid]) -> Result<Vec<ArgocdAppHealth>, sqlx::Error> {
+    sqlx::query_as::<_, ArgocdAppHealth>(
+        r#"
+        SELECT environment_id, application_name,
+               last_sync_status AS sync_status, last_health_status AS health_status,
+               last_operation_phase AS operation_phase, last_checked_at
+        FROM environment_argocd_apps
+        WHERE environment_id = ANY($1) AND is_active
+        ORDER BY application_name
+        "#,
+    )
+    .bind(environment_ids)
+    .fetch_all(pool)
+    .await
+}
+
+/// Přehled všech prostředí tenanta (poslední deploy, ArgoCD health, drift, expirace credentials)
+pub async fn tenant_overview(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    warn_days: i64,
+) -> Result<Vec<EnvironmentHealth>, sqlx::Error> {
+    let scopes = load_scopes(pool, tenant_id).await?;
+    let environment_ids: Vec<Uuid> = scopes.iter().map(|scope| scope.id).collect();
+
+    let mut deploys: HashMap<Uuid, LatestDeploy> = load_latest_deploys(pool, &environment_ids)
+        .await?
+        .into_iter()
+        .map(|deploy| (deploy.environment_id, deploy))
+        .collect();
+    let mut apps: HashMap<Uuid, Vec<ArgocdAppHealth>> = HashMap::new();
+    for app in load_argocd_apps(pool, &environment_ids).await? {
+        apps.entry(app.environment_id).or_default().push(app);
+    }
+    let credentials = credential_expiry::list_expiring(pool, warn_days, Some(tenant_id)).await?;
+
+    let now = Utc::now();
+    Ok(scopes
+        .into_iter()
+        .map(|scope| {
+            let latest = deploys.remove(&scope.id);
+            let deploy = DeployHealth {
+                status: latest.as_ref().map(|deploy| deploy_light(&deploy.status)),
+                latest,
+            };
+
+            let apps = apps.remove(&scope.id).unwrap_or_default();
+            let drift = DriftHealth {
+                status: worst(apps.iter().map(|app| {
+                    Some(drift_light(app.sync_status.as_deref(), app.operation_phase.as_deref()))
+                })),
+                out_of_sync: apps
+                    .iter()
+                    .filter(|app| {
+                        drift_light(app.sync_status.as_deref(), app.operation_phase.as_deref()) != HealthLight::Green
+                    })
+                    .map(|app| app.application_name.clone())
+                    .collect(),
+            };
+            let argocd = ArgocdHealth {
+                status: worst(apps.iter().map(|app| Some(argocd_health_light(app.health_status.as_deref())))),
+                apps,
+            };
+
+            let expiring: Vec<CredentialHealthItem> = credentials
+                .iter()
+                .filter(|credential| credential_applies(&scope, credential))
+                .map(|credential| {
+                    let expiry = credential_expiry::expiry_state_at(Some(credential.expires_at), now, warn_days)
+                        .unwrap_or(ExpiryState::Expired);
+                    CredentialHealthItem {
+                        credential: credential.clone(),
+                        days_left: credential.days_left(now),
+                        expiry,
+                    }
+                })
+                .collect();
+            let credentials = CredentialHealth {
+                // Bez expirujících credentials je složka zelená
+                status: Some(worst(expiring.iter().map(|item| Some(expiry_light(item.expiry)))).unwrap_or(HealthLight::Green)),
+                expiring,
+            };
+
+            EnvironmentHealth {
+                environment_id: scope.id,
+                name: scope.name,
+                slug: scope.slug,
+                color: scope.color,
+                status: worst([deploy.status, argocd.status, drift.status, credentials.status])
+                    .unwrap_or(HealthLight::Unknown),
+                deploy,
+                argocd,
+                drift,
+                credentials,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scope() -> EnvironmentScope {
+        EnvironmentScope {
+            id: Uuid::from_u128(1),
+            name: "Production".to_string(),
+            slug: "prod".to_string(),
+            color: None,
+            registry_ids: vec![Uuid::from_u128(10), Uuid::from_u128(11)],
+            override_registry_ids: vec![Uuid::from_u128(11)],
+            git_repository_ids: vec![Uuid::from_u128(20)],
+        }
+    }
+
+    fn credential(kind: &str, credential_id: u128, registry_id: Option<u128>, environment_id: Option<u128>) -> ExpiringCredential {
+        ExpiringCredential {
+            credential_kind: kind.to_string(),
+            credential_id: Uuid::from_u128(credential_id),
+            tenant_id: Uuid::nil(),
+            name: "c".to_string(),
+            registry_id: registry_id.map(Uuid::from_u128),
+            environment_id: environment_id.map(Uuid::from_u128),
+            environment_slug: None,
+            expires_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn worst_light_wins_and_missing_components_are_ignored() {
+        assert_eq!(worst([None, None]), None);
+        assert_eq!(worst([Some(HealthLight::Green), None, Some(HealthLight::Unknown)]), Some(HealthLight::Unknown));
+        assert_eq!(worst([Some(HealthLight::Yellow), Some(HealthLight::Red), Some(HealthLight::Green)]), Some(HealthLight::Red));
+        assert_eq!(deploy_light("pending"), HealthLight::Yellow);
+        assert_eq!(argocd_health_light(Some("Degraded")), HealthLight::Red);
+        assert_eq!(argocd_health_light(None), HealthLight::Unknown);
+        assert_eq!(drift_light(Some("Synced"), Some("Failed")), HealthLight::Red);
+        assert_eq!(drift_light(Some("OutOfSync"), Some("Succeeded")), HealthLight::Yellow);
+    }
+
+    #[test]
+    fn credentials_are_matched_to_environment() {
+        let scope = scope();
+        assert!(credential_applies(&scope, &credential("environment_registry", 1, Some(11), Some(1))));
+        assert!(!credential_applies(&scope, &credential("environment_registry", 1, Some(11), Some(2))));
+        assert!(credential_applies(&scope, &credential("registry", 10, Some(10), None)));
+        // Prostředí má pro registry vlastní credentials
+        assert!(!credential_applies(&scope, &credential("registry", 11, Some(11), None)));
+        assert!(!credential_applies(&scope, &credential("registry", 12, Some(12), None)));
+        assert!(credential_applies(&scope, &credential("git_repository", 20, None, None)));
+        assert!(!credential_applies(&scope, &credential("git_repository", 21, None, None)));
+    }
+}
@@ -0,0 +1,480 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+/// Compatibility policy publikovaná v katalogu
+pub const COMPATIBILITY_POLICY: &str = "Within a version, changes are additive only: new optional fields may appear \
+and consumers must ignore unknown fields. Removing or renaming a field, changing its type or meaning, \
+or adding a required field creates a new version. The previous version stays published and emitted \
+alongside the new one for at least two minor releases.";
+
+/// Obálka všech odchozích eventů (webhooky, notifikace)
+#[derive(Debug, Clone, Serialize)]
+pub struct EventEnvelope<T: Serialize> {
+    pub id: Uuid,
+    #[serde(rename = "type")]
+    pub event_type: &'static str,
+    pub version: u32,
+    pub occurred_at: DateTime<Utc>,
+    pub tenant_id: Option<Uuid>,
+    pub data: T,
+}
+
+impl<T: Serialize> EventEnvelope<T> {
+    pub fn new(spec: &EventSpec, tenant_id: Option<Uuid>, data: T) -> Self {
+        EventEnvelope {
+            id: Uuid::new_v4(),
+            event_type: spec.event_type,
+            version: spec.version,
+            occurred_at: Utc::now(),
+            tenant_id,
+            data,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CopyJobEventData {
+    pub job_id: Uuid,
+    pub bundle_id: Uuid,
+    pub bundle_version: i32,
+    pub target_tag: String,
+    pub environment_id: Option<Uuid>,
+    pub status: String,
+    pub total_images: i64,
+    pub failed_images: i64,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReleaseEventData {
+    pub release_uuid: Uuid,
+    pub release_id: String,
+    pub copy_job_id: Uuid,
+    pub is_auto: bool,
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeployJobEventData {
+    pub job_id: Uuid,
+    pub release_uuid: Uuid,
+    pub environment_id: Uuid,
+    pub status: String,
+    pub dry_run: bool,
+    pub commit_sha: Option<String>,
+    pub tag_name: Option<String>,
+    pub error_message: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PipelineEventData {
+    pub pipeline_id: Uuid,
+    pub status: String,
+    pub stage: String,
+    pub copy_job_id: Option<Uuid>,
+    pub release_uuid: Option<Uuid>,
+    pub deploy_job_ids: Vec<Uuid>,
+    pub error_message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CredentialExpiringEventData {
+    pub credential_kind: String,
+    pub credential_id: Uuid,
+    pub name: String,
+    pub registry_id: Option<Uuid>,
+    pub environment_id: Option<Uuid>,
+    pub expires_at: DateTime<Utc>,
+    pub days_left: i64,
+    pub expired: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FailedImageEventData {
+    pub app_name: String,
+    pub container_name: Option<String>,
+    pub source_image: String,
+    pub target_image: String,
+    pub error_message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CopyImagesFailedEventData {
+    pub job_id: Uuid,
+    pub bundle_id: Uuid,
+    pub bundle_version: i32,
+    pub target_tag: String,
+    pub environment_id: Option<Uuid>,
+    /// Vlastník image mappingů; `null` = mappingy bez vlastníka
+    pub owner: Option<String>,
+    pub images: Vec<FailedImageEventData>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum FieldKind {
+    Uuid,
+    DateTime,
+    String,
+    Integer,
+    Boolean,
+    UuidArray,
+    ObjectArray(&'static [FieldSpec]),
+    Enum(&'static [&'static str]),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FieldSpec {
+    pub name: &'static str,
+    pub kind: FieldKind,
+    /// Nullable pole jsou vždy přítomná, hodnota může být `null`
+    pub nullable: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct EventSpec {
+    pub event_type: &'static str,
+    pub version: u32,
+    pub description: &'static str,
+    pub fields: &'static [FieldSpec],
+}
+
+const fn field(name: &'static str, kind: FieldKind) -> FieldSpec {
+    FieldSpec { name, kind, nullable: false }
+}
+
+const fn nullable(name: &'static str, kind: FieldKind) -> FieldSpec {
+    FieldSpec { name, kind, nullable: true }
+}
+
+const JOB_STATUSES: &[&str] = &["pending", "in_progress", "success", "failed", "cancelled"];
+const FINISHED_STATUSES: &[&str] = &["success", "failed", "cancelled"];
+
+const COPY_JOB_FIELDS: &[FieldSpec] = &[
+    field("job_id", FieldKind::Uuid),
+    field("bundle_id", FieldKind::Uuid),
+    field("bundle_version", FieldKind::Integer),
+    field("target_tag", FieldKind::String),
+    nullable("environment_id", FieldKind::Uuid),
+    field("status", FieldKind::Enum(JOB_STATUSES)),
+    field("total_images", FieldKind::Integer),
+    field("failed_images", FieldKind::Integer),
+    field("started_at", FieldKind::DateTime),
+    nullable("completed_at", FieldKind::DateTime),
+];
+
+const RELEASE_FIELDS: &[FieldSpec] = &[
+    field("release_uuid", FieldKind::Uuid),
+    field("release_id", FieldKind::String),
+    field("copy_job_id", FieldKind::Uuid),
+    field("is_auto", FieldKind::Boolean),
+    nullable("created_by", FieldKind::String),
+    field("created_at", FieldKind::DateTime),
+];
+
+const DEPLOY_JOB_FIELDS: &[FieldSpec] = &[
+    field("job_id", FieldKind::Uuid),
+    field("release_uuid", FieldKind::Uuid),
+    field("environment_id", FieldKind::Uuid),
+    field("status", FieldKind::Enum(JOB_STATUSES)),
+    field("dry_run", FieldKind::Boolean),
+    nullable("commit_sha", FieldKind::String),
+    nullable("tag_name", FieldKind::String),
+    nullable("error_message", FieldKind::String),
+    field("started_at", FieldKind::DateTime),
+    nullable("completed_at", FieldKind::DateTime),
+];
+
+const PIPELINE_FIELDS: &[FieldSpec] = &[
+    field("pipeline_id", FieldKind::Uuid),
+    field("status", FieldKind::Enum(FINISHED_STATUSES)),
+    field("stage", FieldKind::Enum(&["copy", "release", "deploy", "done"])),
+    nullable("copy_job_id", FieldKind::Uuid),
+    nullable("release_uuid", FieldKind::Uuid),
+    field("deploy_job_ids", FieldKind::UuidArray),
+    nullable("error_message", FieldKind::String),
+];
+
+pub const COPY_JOB_STARTED: EventSpec = EventSpec {
+    event_type: "copy_job.started",
+    version: 1,
+    description: "A copy job started transferring images.",
+    fields: COPY_JOB_FIELDS,
+};
+pub const COPY_JOB_FINISHED: EventSpec = EventSpec {
+    event_type: "copy_job.finished",
+    version: 1,
+    description: "A copy job reached a terminal status (success, failed, cancelled).",
+    fields: COPY_JOB_FIELDS,
+};
+pub const RELEASE_CREATED: EventSpec = EventSpec {
+    event_type: "release.created",
+    version: 1,
+    description: "A release was created from a successful copy job.",
+    fields: RELEASE_FIELDS,
+};
+pub const DEPLOY_JOB_STARTED: EventSpec = EventSpec {
+    event_type: "deploy_job.started",
+    version: 1,
+    description: "A deploy job started.",
+    fields: DEPLOY_JOB_FIELDS,
+};
+pub const DEPLOY_JOB_FINISHED: EventSpec = EventSpec {
+    event_type: "deploy_job.finished",
+    version: 1,
+    description: "A deploy job reached a terminal status (success, failed, cancelled).",
+    fields: DEPLOY_JOB_FIELDS,
+};
+pub const PIPELINE_FINISHED: EventSpec = EventSpec {
+    event_type: "pipeline.finished",
+    version: 1,
+    description: "A triggered pipeline (copy, release, deploy) finished.",
+    fields: PIPELINE_FIELDS,
+};
+
+const CREDENTIAL_EXPIRING_FIELDS: &[FieldSpec] = &[
+    field(
+        "credential_kind",
+        FieldKind::Enum(&["registry", "environment_registry", "git_repository"]),
+    ),
+    field("credential_id", FieldKind::Uuid),
+    field("name", FieldKind::String),
+    nullable("registry_id", FieldKind::Uuid),
+    nullable("environment_id", FieldKind::Uuid),
+    field("expires_at", FieldKind::DateTime),
+    field("days_left", FieldKind::Integer),
+    field("expired", FieldKind::Boolean),
+];
+
+pub const CREDENTIAL_EXPIRING: EventSpec = EventSpec {
+    event_type: "credential.expiring",
+    version: 1,
+    description: "Stored registry or git credentials are about to expire (or already expired).",
+    fields: CREDENTIAL_EXPIRING_FIELDS,
+};
+
+const FAILED_IMAGE_FIELDS: &[FieldSpec] = &[
+    field("app_name", FieldKind::String),
+    nullable("container_name", FieldKind::String),
+    field("source_image", FieldKind::String),
+    field("target_image", FieldKind::String),
+    nullable("error_message", FieldKind::String),
+];
+
+const COPY_IMAGES_FAILED_FIELDS: &[FieldSpec] = &[
+    field("job_id", FieldKind::Uuid),
+    field("bundle_id", FieldKind::Uuid),
+    field("bundle_version", FieldKind::Integer),
+    field("target_tag", FieldKind::String),
+    nullable("environment_id", FieldKind::Uuid),
+    nullable("owner", FieldKind::String),
+    field("images", FieldKind::ObjectArray(FAILED_IMAGE_FIELDS)),
+];
+
+pub const COPY_JOB_IMAGES_FAILED: EventSpec = EventSpec {
+    event_type: "copy_job.images_failed",
+    version: 1,
+    description: "Images of one owner failed in a finished copy job (one event per owner).",
+    fields: COPY_IMAGES_FAILED_FIELDS,
+};
+
+/// Katalog všech publikovaných eventů (typ + verze)
+pub const EVENT_CATALOG: &[EventSpec] = &[
+    COPY_JOB_STARTED,
+    COPY_JOB_FINISHED,
+    RELEASE_CREATED,
+    DEPLOY_JOB_STARTED,
+    DEPLOY_JOB_FINISHED,
+    PIPELINE_FINISHED,
+    CREDENTIAL_EXPIRING,
+    COPY_JOB_IMAGES_FAILED,
+];
+
+pub fn find_spec(event_type: &str, version: u32) -> Option<&'static EventSpec> {
+    EVENT_CATALOG
+        .iter()
+        .find(|spec| spec.event_type == event_type && spec.version == version)
+}
+
+fn field_schema(spec: &FieldSpec) -> Value {
+    let mut schema = match spec.kind {
+        FieldKind::Uuid => json!({ "type": "string", "format": "uuid" }),
+        FieldKind::DateTime => json!({ "type": "string", "format": "date-time" }),
+        FieldKind::String => json!({ "type": "string" }),
+        FieldKind::Integer => json!({ "type": "integer" }),
+        FieldKind::Boolean => json!({ "type": "boolean" }),
+        FieldKind::UuidArray => json!({ "type": "array", "items": { "type": "string", "format": "uuid" } }),
+        FieldKind::ObjectArray(fields) => json!({
+            "type": "array",
+            "items": object_schema(fields),
+        }),
+        FieldKind::Enum(values) => json!({ "type": "string", "enum": values }),
+    };
+    if spec.nullable {
+        let base_type = schema["type"].clone();
+        schema["type"] = json!([base_type, "null"]);
+        if let Some(values) = schema.get_mut("enum").and_then(Value::as_array_mut) {
+            values.push(Value::Null);
+        }
+    }
+    schema
+}
+
+fn object_schema(fields: &[FieldSpec]) -> Value {
+    let properties: Map<String, Value> = fields
+        .iter()
+        .map(|f| (f.name.to_string(), field_schema(f)))
+        .collect();
+    let required: Vec<&str> = fields.iter().map(|f| f.name).collect();
+    json!({
+        "type": "object",
+        "required": required,
+        "properties": properties,
+        "additionalProperties": true
+    })
+}
+
+/// JSON Schema (draft 2020-12) pro celý event včetně obálky
+pub fn json_schema(spec: &EventSpec) -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": format!("urn:srm:event:{}:v{}", spec.event_type, spec.version),
+        "title": format!("{} v{}", spec.event_type, spec.version),
+        "description": spec.description,
+        "type": "object",
+        "required": ["id", "type", "version", "occurred_at", "tenant_id", "data"],
+        "properties": {
+            "id": { "type": "string", "format": "uuid" },
+            "type": { "const": spec.event_type },
+            "version": { "const": spec.version },
+            "occurred_at": { "type": "string", "format": "date-time" },
+            "tenant_id": { "type": ["string", "null"], "format": "uuid" },
+            "data": object_schema(spec.fields)
+        },
+        "additionalProperties": true
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    fn keys(value: &Value) -> BTreeSet<String> {
+        value.as_object().unwrap().keys().cloned().collect()
+    }
+
+    fn spec_keys(spec: &EventSpec) -> BTreeSet<String> {
+        spec.fields.iter().map(|f| f.name.to_string()).collect()
+    }
+
+    /// Payload structs and published schemas must not drift apart.
+    #[test]
+    fn payload_structs_match_schemas() {
+        let now = Utc::now();
+        let id = Uuid::new_v4();
+        let copy = serde_json::to_value(CopyJobEventData {
+            job_id: id,
+            bundle_id: id,
+            bundle_version: 1,
+            target_tag: "t".into(),
+            environment_id: None,
+            status: "success".into(),
+            total_images: 1,
+            failed_images: 0,
+            started_at: now,
+            completed_at: None,
+        })
+        .unwrap();
+        let release = serde_json::to_value(ReleaseEventData {
+            release_uuid: id,
+            release_id: "r".into(),
+            copy_job_id: id,
+            is_auto: false,
+            created_by: None,
+            created_at: now,
+        })
+        .unwrap();
+        let deploy = serde_json::to_value(DeployJobEventData {
+            job_id: id,
+            release_uuid: id,
+            environment_id: id,
+            status: "failed".into(),
+            dry_run: false,
+            commit_sha: None,
+            tag_name: None,
+            error_message: None,
+            started_at: now,
+            completed_at: None,
+        })
+        .unwrap();
+        let pipeline = serde_json::to_value(PipelineEventData {
+            pipeline_id: id,
+            status: "success".into(),
+            stage: "done".into(),
+            copy_job_id: None,
+            release_uuid: None,
+            deploy_job_ids: vec![],
+            error_message: None,
+        })
+        .unwrap();
+        let credential = serde_json::to_value(CredentialExpiringEventData {
+            credential_kind: "registry".into(),
+            credential_id: id,
+            name: "harbor".into(),
+            registry_id: Some(id),
+            environment_id: None,
+            expires_at: now,
+            days_left: 3,
+            expired: false,
+        })
+        .unwrap();
+
+        assert_eq!(keys(&copy), spec_keys(&COPY_JOB_FINISHED));
+        assert_eq!(keys(&release), spec_keys(&RELEASE_CREATED));
+        assert_eq!(keys(&deploy), spec_keys(&DEPLOY_JOB_FINISHED));
+        assert_eq!(keys(&pipeline), spec_keys(&PIPELINE_FINISHED));
+        assert_eq!(keys(&credential), spec_keys(&CREDENTIAL_EXPIRING));
+
+        let failed = serde_json::to_value(CopyImagesFailedEventData {
+            job_id: id,
+            bundle_id: id,
+            bundle_version: 2,
+            target_tag: "t".into(),
+            environment_id: None,
+            owner: Some("team-a".into()),
+            images: vec![FailedImageEventData {
+                app_name: "api".into(),
+                container_name: None,
+                source_image: "src/api".into(),
+                target_image: "dst/api".into(),
+                error_message: Some("denied".into()),
+            }],
+        })
+        .unwrap();
+        assert_eq!(keys(&failed), spec_keys(&COPY_JOB_IMAGES_FAILED));
+        let FieldKind::ObjectArray(image_fields) = COPY_JOB_IMAGES_FAILED.fields.last().unwrap().kind else {
+            panic!("images must be an object array");
+        };
+        let image_keys: BTreeSet<String> = image_fields.iter().map(|f| f.name.to_string()).collect();
+        assert_eq!(keys(&failed["images"][0]), image_keys);
+    }
+
+    #[test]
+    fn catalog_entries_are_unique_and_resolvable() {
+        let mut seen = BTreeSet::new();
+        for spec in EVENT_CATALOG {
+            assert!(seen.insert((spec.event_type, spec.version)));
+            let schema = json_schema(spec);
+            assert_eq!(schema["properties"]["type"]["const"], spec.event_type);
+            assert!(find_spec(spec.event_type, spec.version).is_some());
+        }
+        let nullable = field_schema(&nullable("x", FieldKind::Uuid));
+        assert_eq!(nullable["type"], json!(["string", "null"]));
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Maximální velikost jedné šablony (bajty)
+pub const MAX_TEMPLATE_BYTES: usize = 256 * 1024;
+
+/// Relativní cesta v deploy výstupu bez `..` a prázdných segmentů
+pub fn validate_path(path: &str) -> Result<String, String> {
+    let path = path.trim();
+    if path.is_empty() {
+        return Err("File template path cannot be empty".to_string());
+    }
+    if path.starts_with('/') || path.contains('\\') {
+        return Err("File template path must be relative to the deploy output".to_string());
+    }
+    if path.split('/').any(|segment| segment.is_empty() || segment == "." || segment == "..") {
+        return Err("File template path cannot contain empty, '.' or '..' segments".to_string());
+    }
+    if path.split('/').next() == Some(".git") {
+        return Err("File template path cannot point into .git".to_string());
+    }
+    Ok(path.to_string())
+}
+
+/// Proměnné z env souboru (`release.env`); pozdější řádek vyhrává stejně jako při `apply-env`
+pub fn parse_env_file(contents: &str) -> BTreeMap<String, String> {
+    let mut vars = BTreeMap::new();
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim();
+        if key.is_empty() {
+            continue;
+        }
+        vars.insert(key.to_string(), unquote(value.trim()));
+    }
+    vars
+}
+
+fn unquote(value: &str) -> String {
+    if value.len() >= 2 && value.starts_with('\'') && value.ends_with('\'') {
+        return value[1..value.len() - 1].to_string();
+    }
+    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        let mut out = String::new();
+        let mut chars = value[1..value.len() - 1].chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                out.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                Some(other) => out.push(other),
+                None => out.push('\\'),
+            }
+        }
+        return out;
+    }
+    value.to_string()
+}
+
+/// Dosadí `${NAME}` z proměnných; `$$` je doslovný `$`.
+/// Chybějící proměnné jsou chyba - vrací se jejich seznam (bez hodnot).
+pub fn render(template: &str, vars: &BTreeMap<String, String>) -> Result<String, Vec<String>> {
+    let mut out = String::with_capacity(template.len());
+    let mut missing = BTreeSet::new();
+    let mut rest = template;
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        let tail = &rest[pos..];
+        if let Some(after) = tail.strip_prefix("$$") {
+            out.push('$');
+            rest = after;
+        } else if let Some(after) = tail.strip_prefix("${")
+            && let Some(end) = after.find('}')
+            && is_var_name(&after[..end])
+        {
+            let name = &after[..end];
+            match vars.get(name) {
+                Some(value) => out.push_str(value),
+                None => {
+                    missing.insert(name.to_string());
+                }
+            }
+            rest = &after[end + 1..];
+        } else {
+            out.push('$');
+            rest = &tail[1..];
+        }
+    }
+    out.push_str(rest);
+
+    if missing.is_empty() {
+        Ok(out)
+    } else {
+        Err(missing.into_iter().collect())
+    }
+}
+
+fn is_var_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Názvy proměnných, které šablona používá (pro výpis bez obsahu)
+pub fn placeholders(template: &str) -> Vec<String> {
+    match render(template, &BTreeMap::new()) {
+        Ok(_) => Vec::new(),
+        Err(names) => names,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> BTreeMap<String, String> {
+        parse_env_file(
+            "# comment\nDB_URL=jdbc:postgresql://db/app\nexport DB_PASSWORD=\"p\\\"w\"\nNAME='x y'\nDB_URL=jdbc:postgresql://db2/app\n",
+        )
+    }
+
+    #[test]
+    fn env_file_parsing_matches_dotenv_output() {
+        let vars = vars();
+        assert_eq!(vars["DB_URL"], "jdbc:postgresql://db2/app");
+        assert_eq!(vars["DB_PASSWORD"], "p\"w");
+        assert_eq!(vars["NAME"], "x y");
+    }
+
+    #[test]
+    fn render_substitutes_and_reports_missing() {
+        let template = "spring.datasource.url=${DB_URL}\nspring.datasource.password=${DB_PASSWORD}\ncost=$$5 ${not-a-var} $HOME\n";
+        assert_eq!(
+            render(template, &vars()).unwrap(),
+            "spring.datasource.url=jdbc:postgresql://db2/app\nspring.datasource.password=p\"w\ncost=$5 ${not-a-var} $HOME\n"
+        );
+        assert_eq!(render("${A} ${B} ${A}", &vars()), Err(vec!["A".to_string(), "B".to_string()]));
+        assert_eq!(placeholders("${B}${A}$${C}"), vec!["A".to_string(), "B".to_string()]);
+    }
+
+    #[test]
+    fn paths_stay_inside_output() {
+        assert_eq!(validate_path(" config/application.properties ").unwrap(), "config/application.properties");
+        for path in ["", "/etc/passwd", "a/../b", "a//b", "./a", ".git/config", "a\\b"] {
+            assert!(validate_path(path).is_err(), "{:?}", path);
+        }
+    }
+}
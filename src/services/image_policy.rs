@@ -0,0 +1,224 @@
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::db::models::ImagePolicy;
+
+pub const EFFECT_ALLOW: &str = "allow";
+pub const EFFECT_DENY: &str = "deny";
+
+/// Zdrojová image, jak ji vidí pravidla: host source registry + cesta v registry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyImage {
+    pub registry: String,
+    pub repository: String,
+}
+
+impl PolicyImage {
+    pub fn new(registry_base_url: &str, repository: &str) -> Self {
+        PolicyImage {
+            registry: registry_host(registry_base_url),
+            repository: repository.trim().trim_matches('/').to_string(),
+        }
+    }
+
+    pub fn reference(&self) -> String {
+        format!("{}/{}", self.registry, self.repository)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PolicyViolation {
+    /// Pozice image ve vstupu `check_images` / `evaluate`
+    #[serde(skip)]
+    pub index: usize,
+    pub image: String,
+    /// Deny pravidlo, které image zakázalo (None = nevyhovuje žádnému allow pravidlu)
+    pub policy: Option<String>,
+    pub reason: String,
+}
+
+/// `https://registry.example.com/` -> `registry.example.com`
+pub fn registry_host(base_url: &str) -> String {
+    base_url
+        .trim()
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// Vyhodnotí pravidla platná pro environment. `matches` jsou dvojice (index image, id pravidla).
+/// Deny vždy vyhrává; pokud existuje aspoň jedno allow pravidlo, image mu musí odpovídat.
+pub fn evaluate(images: &[PolicyImage], policies: &[ImagePolicy], matches: &[(usize, Uuid)]) -> Vec<PolicyViolation> {
+    let has_allow = policies.iter().any(|p| p.effect == EFFECT_ALLOW);
+    images
+        .iter()
+        .enumerate()
+        .filter_map(|(index, image)| {
+            let matched: Vec<&ImagePolicy> = policies
+                .iter()
+                .filter(|p| matches.contains(&(index, p.id)))
+                .collect();
+            if let Some(deny) = matched.iter().find(|p| p.effect == EFFECT_DENY) {
+                return Some(PolicyViolation {
+                    index,
+                    image: image.reference(),
+                    policy: Some(deny.name.clone()),
+                    reason: format!("denied by image policy '{}'", deny.name),
+                });
+            }
+            (has_allow && !matched.iter().any(|p| p.effect == EFFECT_ALLOW)).then(|| PolicyViolation {
+                index,
+                image: image.reference(),
+                policy: None,
+                reason: "not matched by any allow image policy".to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Chybová zpráva pro API (prvních pár porušení)
+pub fn violations_message(violations: &[PolicyViolation]) -> String {
+    let mut items: Vec<String> = violations
+        .iter()
+        .take(5)
+        .map(|v| format!("{} ({})", v.image, v.reason))
+        .collect();
+    if violations.len() > 5 {
+        items.push(format!("and {} more", violations.len() - 5));
+    }
+    format!("Blocked by image policy: {}", items.join(", "))
+}
+
+/// Porušení pravidel tenanta pro dané images.
+/// `environment_slug = None` bere jen pravidla bez `environment_pattern` (validace bundle).
+/// Regexy vyhodnocuje Postgres a musí odpovídat celé hodnotě.
+pub async fn check_images(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    environment_slug: Option<&str>,
+    images: &[PolicyImage],
+) -> Result<Vec<PolicyViolation>, sqlx::Error> {
+    if images.is_empty() {
+        return Ok(Vec::new());
+    }
+    let policies = sqlx::query_as::<_, ImagePolicy>(
+        "SELECT * FROM image_policies
+         WHERE tenant_id = $1 AND is_active
+           AND (environment_pattern IS NULL
+                OR ($2::text IS NOT NULL AND $2 ~ ('^(?:' || environment_pattern || ')$')))
+         ORDER BY created_at",
+    )
+    .bind(tenant_id)
+    .bind(environment_slug)
+    .fetch_all(pool)
+    .await?;
+    if policies.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let registries: Vec<&str> = images.iter().map(|i| i.registry.as_str()).collect();
+    let repositories: Vec<&str> = images.iter().map(|i| i.repository.as_str()).collect();
+    let policy_ids: Vec<Uuid> = policies.iter().map(|p| p.id).collect();
+    let rows = sqlx::query_as::<_, (i64, Uuid)>(
+        "SELECT i.idx, p.id
+         FROM UNNEST($1::text[], $2::text[]) WITH ORDINALITY AS i(registry, repository, idx)
+         JOIN image_policies p ON p.id = ANY($3)
+         WHERE (p.registry_pattern IS NULL OR i.registry ~ ('^(?:' || p.registry_pattern || ')$'))
+           AND (p.repository_pattern IS NULL OR i.repository ~ ('^(?:' || p.repository_pattern || ')$'))",
+    )
+    .bind(&registries)
+    .bind(&repositories)
+    .bind(&policy_ids)
+    .fetch_all(pool)
+    .await?;
+    let matches: Vec<(usize, Uuid)> = rows
+        .into_iter()
+        .map(|(idx, policy_id)| ((idx - 1) as usize, policy_id))
+        .collect();
+
+    Ok(evaluate(images, &policies, &matches))
+}
+
+/// Ověří, že Postgres regex jde zkompilovat
+pub async fn validate_pattern(pool: &PgPool, pattern: &str) -> Result<(), String> {
+    sqlx::query_scalar::<_, bool>("SELECT '' ~ ('^(?:' || $1 || ')$')")
+        .bind(pattern)
+        .fetch_one(pool)
+        .await
+        .map(|_| ())
+        .map_err(|e| match e {
+            sqlx::Error::Database(db) => db.message().to_string(),
+            other => other.to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn policy(name: &str, effect: &str) -> ImagePolicy {
+        ImagePolicy {
+            id: Uuid::new_v4(),
+            tenant_id: Uuid::nil(),
+            name: name.to_string(),
+            effect: effect.to_string(),
+            registry_pattern: None,
+            repository_pattern: None,
+            environment_pattern: None,
+            description: None,
+            is_active: true,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn deny_wins_over_allow() {
+        let images = vec![
+            PolicyImage::new("https://docker.io/", "library/nginx"),
+            PolicyImage::new("harbor.example.com", "app/api"),
+        ];
+        let allow_all = policy("allow-all", EFFECT_ALLOW);
+        let deny_hub = policy("no-docker-hub", EFFECT_DENY);
+        let matches = vec![(0, allow_all.id), (0, deny_hub.id), (1, allow_all.id)];
+        let violations = evaluate(&images, &[allow_all, deny_hub], &matches);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].image, "docker.io/library/nginx");
+        assert_eq!(violations[0].policy.as_deref(), Some("no-docker-hub"));
+    }
+
+    #[test]
+    fn allow_rules_act_as_allowlist() {
+        let images = vec![
+            PolicyImage::new("harbor.example.com", "app/api"),
+            PolicyImage::new("quay.io", "org/tool"),
+        ];
+        let allow_harbor = policy("harbor-only", EFFECT_ALLOW);
+        let matches = vec![(0, allow_harbor.id)];
+        let violations = evaluate(&images, std::slice::from_ref(&allow_harbor), &matches);
+        assert_eq!(violations.len(), 1);
+        assert_eq!((violations[0].index, violations[0].image.as_str()), (1, "quay.io/org/tool"));
+        assert_eq!(violations[0].policy, None);
+
+        // Jen deny pravidla - co nezakazují, je povolené
+        let deny = policy("deny", EFFECT_DENY);
+        assert!(evaluate(&images, &[deny], &[]).is_empty());
+    }
+
+    #[test]
+    fn message_lists_first_violations() {
+        let violations: Vec<PolicyViolation> = (0..7)
+            .map(|i| PolicyViolation {
+                index: i,
+                image: format!("docker.io/img{}", i),
+                policy: Some("deny".to_string()),
+                reason: "denied by image policy 'deny'".to_string(),
+            })
+            .collect();
+        let message = violations_message(&violations);
+        assert!(message.starts_with("Blocked by image policy: docker.io/img0 (denied"));
+        assert!(message.ends_with("and 2 more"));
+    }
+}
@@ -2,12 +2,20 @@
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
 use tokio::sync::broadcast;
 use tracing::{info, warn};
 
+use crate::services::release_tag_cleanup::{self, ReleaseTagCleanup};
+use crate::services::sandbox::{SandboxTool, ToolProgram, ToolSandbox};
+
+pub use srm_api_types::copy::CopyOptionsOverride;
+
 const PROGRESS_MARKER_PREFIX: &str = "__PROGRESS__";
 
 /// Skopeo credentials pro autentizaci
@@ -28,6 +36,181 @@ pub struct ImageToolService {
     pub dst_insecure: bool,
     pub extra_inspect_args: Vec<String>,
     pub extra_copy_args: Vec<String>,
+    pub sandbox: ToolSandbox,
+    /// Globální retry politika a copy flagy (COPY_* / IMAGE_TOOL_COPY_*)
+    pub copy_defaults: CopyOptions,
+    inspect_cache: InspectCache,
+}
+
+/// Efektivní retry politika a copy flagy jednoho copy jobu
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CopyOptions {
+    /// Celkový počet pokusů
+    pub max_retries: u32,
+    pub retry_delay_seconds: u64,
+    pub all: bool,
+    pub preserve_digests: bool,
+    pub format: Option<String>,
+    /// Úklid release tagu po selhaném / zrušeném release jobu
+    #[serde(default)]
+    pub release_tag_cleanup: ReleaseTagCleanup,
+    /// Před kopií připojit chybějící vrstvy z jiných repozitářů cílové registry (cross-repo mount)
+    #[serde(default = "default_blob_reuse")]
+    pub blob_reuse: bool,
+}
+
+fn default_blob_reuse() -> bool {
+    true
+}
+
+impl Default for CopyOptions {
+    fn default() -> Self {
+        CopyOptions {
+            max_retries: 3,
+            retry_delay_seconds: 30,
+            all: false,
+            preserve_digests: false,
+            format: None,
+            release_tag_cleanup: ReleaseTagCleanup::Off,
+            blob_reuse: true,
+        }
+    }
+}
+
+/// Hodnoty pro skopeo `--format`
+pub const COPY_FORMATS: [&str; 3] = ["oci", "v2s1", "v2s2"];
+
+impl CopyOptions {
+    /// Přepíše nastavené hodnoty (pořadí: globální -> registry -> job)
+    pub fn with_override(mut self, overrides: Option<&CopyOptionsOverride>) -> Self {
+        let Some(overrides) = overrides else {
+            return self;
+        };
+        if let Some(max_retries) = overrides.max_retries {
+            self.max_retries = max_retries.max(1);
+        }
+        if let Some(delay) = overrides.retry_delay_seconds {
+            self.retry_delay_seconds = delay;
+        }
+        if let Some(all) = overrides.all {
+            self.all = all;
+        }
+        if let Some(preserve_digests) = overrides.preserve_digests {
+            self.preserve_digests = preserve_digests;
+        }
+        if let Some(format) = overrides.format.as_deref() {
+            self.format = Some(format.trim().to_string()).filter(|f| !f.is_empty());
+        }
+        if let Some(cleanup) = overrides.release_tag_cleanup.as_deref().and_then(ReleaseTagCleanup::parse) {
+            self.release_tag_cleanup = cleanup;
+        }
+        if let Some(blob_reuse) = overrides.blob_reuse {
+            self.blob_reuse = blob_reuse;
+        }
+        self
+    }
+
+    /// Flagy pro `copy` (jen skopeo; oci-patch je nezná)
+    fn copy_flags(&self) -> Vec<String> {
+        let mut flags = Vec::new();
+        if self.all {
+            flags.push("--all".to_string());
+        }
+        if self.preserve_digests {
+            flags.push("--preserve-digests".to_string());
+        }
+        if let Some(format) = &self.format {
+            flags.push("--format".to_string());
+            flags.push(format.clone());
+        }
+        flags
+    }
+
+    pub fn summary(&self) -> String {
+        let flags = self.copy_flags();
+        format!(
+            "attempts={} delay={}s flags=[{}] release_tag_cleanup={} blob_reuse={}",
+            self.max_retries,
+            self.retry_delay_seconds,
+            flags.join(" "),
+            self.release_tag_cleanup.as_str(),
+            self.blob_reuse
+        )
+    }
+}
+
+/// Kontrola přepsání z API (registry / job)
+pub fn validate_copy_options(overrides: &CopyOptionsOverride) -> Result<(), String> {
+    let format = overrides.format.as_deref().map(str::trim).filter(|f| !f.is_empty());
+    if let Some(format) = format.filter(|f| !COPY_FORMATS.contains(f)) {
+        return Err(format!(
+            "Unsupported copy format '{}' (expected one of: {})",
+            format,
+            COPY_FORMATS.join(", ")
+        ));
+    }
+    if overrides.max_retries == Some(0) {
+        return Err("max_retries must be at least 1".to_string());
+    }
+    if let Some(cleanup) = overrides.release_tag_cleanup.as_deref()
+        && ReleaseTagCleanup::parse(cleanup).is_none()
+    {
+        return Err(format!(
+            "Unsupported release_tag_cleanup '{}' (expected one of: {})",
+            cleanup.trim(),
+            release_tag_cleanup::MODES.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Krátkodobá cache výsledků inspect, klíč = registry/repo:ref (+ uživatel)
+#[derive(Clone, Default)]
+struct InspectCache {
+    ttl: Duration,
+    entries: Arc<Mutex<HashMap<String, (Instant, ImageInfo)>>>,
+}
+
+impl InspectCache {
+    fn key(image_url: &str, username: Option<&str>) -> String {
+        format!("{}|{}", image_url, username.unwrap_or(""))
+    }
+
+    fn get(&self, image_url: &str, username: Option<&str>) -> Option<ImageInfo> {
+        if self.ttl.is_zero() {
+            return None;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        let key = Self::key(image_url, username);
+        match entries.get(&key) {
+            Some((stored_at, info)) if stored_at.elapsed() < self.ttl => Some(info.clone()),
+            Some(_) => {
+                entries.remove(&key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&self, image_url: &str, username: Option<&str>, info: &ImageInfo) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        // Vyhodit prošlé záznamy, ať cache neroste s počtem jobů
+        let ttl = self.ttl;
+        entries.retain(|_, (stored_at, _)| stored_at.elapsed() < ttl);
+        entries.insert(Self::key(image_url, username), (Instant::now(), info.clone()));
+    }
+
+    /// Zahodí všechny záznamy pro image (po copy/tagu se cílový tag změnil)
+    fn invalidate(&self, image_url: &str) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        let prefix = format!("{}|", image_url);
+        self.entries.lock().unwrap().retain(|key, _| !key.starts_with(&prefix));
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -99,12 +282,48 @@ impl ImageToolService {
             dst_insecure,
             extra_inspect_args,
             extra_copy_args,
+            sandbox: ToolSandbox::default(),
+            copy_defaults: CopyOptions::default(),
+            inspect_cache: InspectCache::default(),
         }
     }
 
+    /// Globální retry politika a copy flagy
+    pub fn with_copy_defaults(mut self, copy_defaults: CopyOptions) -> Self {
+        self.copy_defaults = copy_defaults;
+        self
+    }
+
+    /// Zapnout cache výsledků inspect s daným TTL (0 = vypnuto)
+    pub fn with_inspect_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.inspect_cache = InspectCache {
+            ttl,
+            entries: Arc::default(),
+        };
+        self
+    }
+
+    /// Spouštět image tool přes sandbox wrapper
+    pub fn with_sandbox(mut self, sandbox: ToolSandbox) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
+    /// Jiná binárka image toolu (přepsání na tenantovi / environmentu)
+    pub fn with_tool_path(mut self, image_tool_path: String) -> Self {
+        self.image_tool_path = image_tool_path;
+        self
+    }
+
+    fn program(&self) -> ToolProgram<'_> {
+        self.sandbox.program(SandboxTool::ImageTool, &self.image_tool_path)
+    }
+
     /// Zkontroluje že image tool je dostupný
     pub async fn check_available(&self) -> Result<bool> {
-        let output = Command::new(&self.image_tool_path)
+        let output = self
+            .program()
+            .command(None)
             .arg("--version")
             .output()
             .await
@@ -120,9 +339,14 @@ impl ImageToolService {
         username: Option<&str>,
         password: Option<&str>,
     ) -> Result<ImageInfo> {
+        if let Some(cached) = self.inspect_cache.get(image_url, username) {
+            info!("Inspecting image: {} (cached)", image_url);
+            return Ok(cached);
+        }
+
         info!("Inspecting image: {}", image_url);
 
-        let mut cmd = Command::new(&self.image_tool_path);
+        let mut cmd = self.program().command(None);
         cmd.arg("inspect");
 
         // Add credentials if provided
@@ -161,7 +385,9 @@ impl ImageToolService {
         // Pokusit se získat tag z Name
         let tag = name.split(':').last().unwrap_or("latest").to_string();
 
-        Ok(ImageInfo { digest, name, tag })
+        let info = ImageInfo { digest, name, tag };
+        self.inspect_cache.insert(image_url, username, &info);
+        Ok(info)
     }
 
     /// Zkopíruje image ze source do target
@@ -173,7 +399,7 @@ impl ImageToolService {
     ) -> Result<CopyProgress> {
         info!("Copying image from {} to {}", source_url, target_url);
 
-        let mut cmd = Command::new(&self.image_tool_path);
+        let mut cmd = self.program().command(None);
         cmd.arg("copy");
 
         if self.tool == ImageTool::Skopeo {
@@ -195,6 +421,9 @@ impl ImageToolService {
         }
 
         self.append_copy_insecure_args(&mut cmd);
+        if self.tool == ImageTool::Skopeo {
+            cmd.args(self.copy_defaults.copy_flags());
+        }
         cmd.args(&self.extra_copy_args);
         cmd.arg(format!("docker://{}", source_url))
             .arg(format!("docker://{}", target_url))
@@ -205,6 +434,7 @@ impl ImageToolService {
             .output()
             .await
             .with_context(|| format!("Failed to execute {} copy", self.tool.display_name()))?;
+        self.inspect_cache.invalidate(target_url);
 
         if output.status.success() {
             info!(
@@ -242,11 +472,12 @@ impl ImageToolService {
         target_url: &str,
         creds: &SkopeoCredentials,
         dest_no_reuse: bool,
+        options: &CopyOptions,
         log_tx: Option<&broadcast::Sender<String>>,
     ) -> Result<CopyProgress> {
         info!("Copying image from {} to {}", source_url, target_url);
 
-        let mut cmd = Command::new(&self.image_tool_path);
+        let mut cmd = self.program().command(None);
         cmd.arg("copy");
 
         if self.tool == ImageTool::Skopeo {
@@ -270,15 +501,25 @@ impl ImageToolService {
         }
 
         self.append_copy_insecure_args(&mut cmd);
+        if self.tool == ImageTool::Skopeo {
+            cmd.args(options.copy_flags());
+        }
         cmd.args(&self.extra_copy_args);
         cmd.arg(format!("docker://{}", source_url))
             .arg(format!("docker://{}", target_url))
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
-        let mut child = cmd
-            .spawn()
-            .with_context(|| format!("Failed to execute {} copy", self.tool.display_name()))?;
+        let mut child = match cmd.spawn() {
+            Ok(child) => child,
+            Err(err) => {
+                let message = self.program().spawn_error(&err);
+                if let Some(tx) = log_tx {
+                    let _ = tx.send(message.clone());
+                }
+                return Err(anyhow::Error::new(err).context(message));
+            }
+        };
         let stdout = child.stdout.take().context("Failed to capture stdout")?;
         let stderr = child.stderr.take().context("Failed to capture stderr")?;
 
@@ -371,6 +612,7 @@ impl ImageToolService {
                 return Err(anyhow::anyhow!("Skopeo copy finished without exit status"));
             }
         };
+        self.inspect_cache.invalidate(target_url);
 
         if status.success() {
             Ok(CopyProgress {
@@ -380,7 +622,12 @@ impl ImageToolService {
                 total_bytes,
             })
         } else {
-            let message = if last_err.is_empty() {
+            let message = if let Some(note) = self.program().exit_note(&status) {
+                if let Some(tx) = log_tx {
+                    let _ = tx.send(note.clone());
+                }
+                format!("Copy failed: {}", note)
+            } else if last_err.is_empty() {
                 format!("Copy failed: exit status {}", status)
             } else {
                 format!("Copy failed: {}", last_err)
@@ -401,17 +648,18 @@ impl ImageToolService {
         source_url: &str,
         target_url: &str,
         creds: &SkopeoCredentials,
-        max_retries: u32,
-        retry_delay_secs: u64,
+        options: &CopyOptions,
         log_tx: Option<&broadcast::Sender<String>>,
     ) -> Result<CopyProgress> {
         let mut attempts = 0;
+        let max_retries = options.max_retries.max(1);
+        let retry_delay_secs = options.retry_delay_seconds;
 
         loop {
             attempts += 1;
 
             let mut progress = self
-                .copy_image_streaming(source_url, target_url, creds, false, log_tx)
+                .copy_image_streaming(source_url, target_url, creds, false, options, log_tx)
                 .await?;
 
             if progress.status == CopyStatus::Failed
@@ -421,7 +669,7 @@ impl ImageToolService {
                     let _ = tx.send("Detected reuse-blob error, retrying with --dest-no-reuse...".to_string());
                 }
                 progress = self
-                    .copy_image_streaming(source_url, target_url, creds, true, log_tx)
+                    .copy_image_streaming(source_url, target_url, creds, true, options, log_tx)
                     .await?;
                 if let Some(tx) = log_tx {
                     let _ = tx.send("FALLBACK: --dest-no-reuse applied".to_string());
@@ -469,7 +717,7 @@ impl ImageToolService {
             source_digest_url, target_tag_url
         );
 
-        let mut cmd = Command::new(&self.image_tool_path);
+        let mut cmd = self.program().command(None);
         cmd.arg("tag-existing");
 
         if let (Some(user), Some(pass)) = (&creds.target_username, &creds.target_password) {
@@ -489,6 +737,7 @@ impl ImageToolService {
             .output()
             .await
             .with_context(|| format!("Failed to execute {} tag-existing", self.tool.display_name()))?;
+        self.inspect_cache.invalidate(target_tag_url);
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -497,6 +746,48 @@ impl ImageToolService {
 
         Ok(())
     }
+
+    pub fn supports_delete(&self) -> bool {
+        self.tool == ImageTool::Skopeo
+    }
+
+    /// Smaže image v cílové registry (`skopeo delete`).
+    /// Registry maže manifest, takže zmizí i ostatní tagy se stejným digestem.
+    pub async fn delete_image(&self, target_url: &str, creds: &SkopeoCredentials) -> Result<()> {
+        if !self.supports_delete() {
+            anyhow::bail!("{} does not support deleting images", self.tool.display_name());
+        }
+
+        info!("Deleting image {}", target_url);
+
+        let mut cmd = self.program().command(None);
+        cmd.arg("delete");
+
+        if let (Some(user), Some(pass)) = (&creds.target_username, &creds.target_password) {
+            cmd.arg("--creds").arg(format!("{}:{}", user, pass));
+        }
+
+        if self.dst_insecure {
+            cmd.arg("--tls-verify=false");
+        }
+
+        cmd.arg(format!("docker://{}", target_url))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let output = cmd
+            .output()
+            .await
+            .with_context(|| format!("Failed to execute {} delete", self.tool.display_name()))?;
+        self.inspect_cache.invalidate(target_url);
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("{} delete failed: {}", self.tool.display_name(), stderr.trim());
+        }
+
+        Ok(())
+    }
 }
 
 impl ImageToolService {
@@ -576,4 +867,83 @@ mod tests {
         let available = service.check_available().await.unwrap();
         assert!(available);
     }
+
+    fn image_info(digest: &str) -> ImageInfo {
+        ImageInfo {
+            digest: digest.to_string(),
+            name: "registry.local/app".to_string(),
+            tag: "1.0".to_string(),
+        }
+    }
+
+    #[test]
+    fn inspect_cache_hits_until_invalidated() {
+        let cache = InspectCache {
+            ttl: Duration::from_secs(60),
+            entries: Arc::default(),
+        };
+        cache.insert("registry.local/app:1.0", Some("robot"), &image_info("sha256:aaa"));
+
+        let hit = cache.get("registry.local/app:1.0", Some("robot")).unwrap();
+        assert_eq!(hit.digest, "sha256:aaa");
+        assert!(cache.get("registry.local/app:1.0", None).is_none());
+        assert!(cache.get("registry.local/app:1.0.1", Some("robot")).is_none());
+
+        cache.invalidate("registry.local/app:1.0");
+        assert!(cache.get("registry.local/app:1.0", Some("robot")).is_none());
+    }
+
+    #[test]
+    fn inspect_cache_disabled_with_zero_ttl() {
+        let cache = InspectCache::default();
+        cache.insert("registry.local/app:1.0", None, &image_info("sha256:aaa"));
+        assert!(cache.get("registry.local/app:1.0", None).is_none());
+    }
+
+    #[test]
+    fn copy_options_layer_registry_then_job() {
+        let registry = CopyOptionsOverride {
+            max_retries: Some(5),
+            all: Some(true),
+            format: Some("oci".to_string()),
+            release_tag_cleanup: Some("quarantine".to_string()),
+            ..Default::default()
+        };
+        let job = CopyOptionsOverride {
+            all: Some(false),
+            preserve_digests: Some(true),
+            blob_reuse: Some(false),
+            ..Default::default()
+        };
+        let options = CopyOptions::default()
+            .with_override(Some(&registry))
+            .with_override(Some(&job))
+            .with_override(None);
+
+        assert_eq!(options.max_retries, 5);
+        assert_eq!(options.retry_delay_seconds, 30);
+        assert_eq!(options.copy_flags(), vec!["--preserve-digests", "--format", "oci"]);
+        assert_eq!(options.release_tag_cleanup, ReleaseTagCleanup::Quarantine);
+        assert!(!options.blob_reuse);
+    }
+
+    #[test]
+    fn copy_options_validation() {
+        assert!(validate_copy_options(&CopyOptionsOverride::default()).is_ok());
+        let bad_format = CopyOptionsOverride {
+            format: Some("docker".to_string()),
+            ..Default::default()
+        };
+        assert!(validate_copy_options(&bad_format).is_err());
+        let zero = CopyOptionsOverride {
+            max_retries: Some(0),
+            ..Default::default()
+        };
+        assert!(validate_copy_options(&zero).is_err());
+        let bad_cleanup = CopyOptionsOverride {
+            release_tag_cleanup: Some("purge".to_string()),
+            ..Default::default()
+        };
+        assert!(validate_copy_options(&bad_cleanup).is_err());
+    }
 }
@@ -0,0 +1,170 @@
+use chrono::{DateTime, Utc};
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::PgPool;
+use std::time::Duration;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::services::job_logs::{is_finished_status, JobKind};
+
+/// Ruční spuštění pending deploy jobu uživatelem (ostatní typy zapisují DB triggery)
+pub const APPROVAL_GRANTED: &str = "approval.granted";
+
+const DEFAULT_LIMIT: i64 = 500;
+const MAX_LIMIT: i64 = 5000;
+/// Interval, ve kterém stream kontroluje nové události
+const STREAM_TICK: Duration = Duration::from_secs(1);
+
+/// Jedna strukturovaná událost jobu
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct JobEvent {
+    pub seq: i64,
+    pub event_type: String,
+    pub step: Option<String>,
+    pub data: Value,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct JobEventsQuery {
+    /// Vrátí jen události se `seq > after_seq`; u streamu má přednost `Last-Event-ID`
+    pub after_seq: Option<i64>,
+    pub limit: Option<i64>,
+    /// Filtr podle typu (`step.finished`, `image.failed`, ...)
+    pub event_type: Option<String>,
+}
+
+/// Položka streamu událostí
+#[derive(Debug)]
+pub enum JobEventStreamItem {
+    Event(JobEvent),
+    /// Job skončil a všechny jeho události byly odeslány
+    End(String),
+    /// Job neexistuje
+    NotFound,
+}
+
+fn job_column(kind: JobKind) -> &'static str {
+    match kind {
+        JobKind::Copy => "copy_job_id",
+        JobKind::Deploy => "deploy_job_id",
+    }
+}
+
+fn status_sql(kind: JobKind) -> &'static str {
+    match kind {
+        JobKind::Copy => "SELECT status FROM copy_jobs WHERE id = $1",
+        JobKind::Deploy => "SELECT status FROM deploy_jobs WHERE id = $1",
+    }
+}
+
+/// Offset a velikost stránky (`after_seq`, `limit`) po ořezání do povolených mezí
+fn page(query: &JobEventsQuery) -> (i64, i64) {
+    (
+        query.after_seq.unwrap_or(0).max(0),
+        query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT),
+    )
+}
+
+/// Zapíše `approval.granted`, pokud deploy job ještě čeká na spuštění.
+/// Evidence je jen informativní - chyba DB spuštění jobu neshodí.
+pub async fn record_approval(pool: &PgPool, job_id: Uuid, approved_by: &str) {
+    let result = sqlx::query(
+        "INSERT INTO job_events (deploy_job_id, event_type, data)
+         SELECT id, $2, jsonb_build_object('approved_by', $3::text)
+         FROM deploy_jobs WHERE id = $1 AND status = 'pending'",
+    )
+    .bind(job_id)
+    .bind(APPROVAL_GRANTED)
+    .bind(approved_by)
+    .execute(pool)
+    .await;
+    if let Err(e) = result {
+        warn!(job_id = %job_id, error = %e, "Failed to record deploy job approval");
+    }
+}
+
+/// Stav jobu; `None` = job neexistuje
+pub async fn job_status(pool: &PgPool, kind: JobKind, job_id: Uuid) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>(status_sql(kind))
+        .bind(job_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Události jobu v pořadí vzniku
+pub async fn list(
+    pool: &PgPool,
+    kind: JobKind,
+    job_id: Uuid,
+    query: &JobEventsQuery,
+) -> Result<Vec<JobEvent>, sqlx::Error> {
+    let sql = format!(
+        "SELECT seq, event_type, step, data, created_at FROM job_events
+         WHERE {} = $1 AND seq > $2 AND ($3::text IS NULL OR event_type = $3)
+         ORDER BY seq LIMIT $4",
+        job_column(kind)
+    );
+    let (after_seq, limit) = page(query);
+    sqlx::query_as::<_, JobEvent>(&sql)
+        .bind(job_id)
+        .bind(after_seq)
+        .bind(&query.event_type)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+}
+
+/// Přehraje události od `after_seq` a pokračuje novými, dokud job neskončí.
+/// Kurzorem je `seq`, takže reconnect přes `Last-Event-ID` nic nevynechá ani nezduplikuje.
+pub fn stream_events(
+    pool: PgPool,
+    kind: JobKind,
+    job_id: Uuid,
+    query: JobEventsQuery,
+) -> impl Stream<Item = anyhow::Result<JobEventStreamItem>> {
+    async_stream::try_stream! {
+        let mut query = JobEventsQuery { limit: Some(DEFAULT_LIMIT), ..query };
+        loop {
+            // Status čtený před událostmi: trigger zapisuje koncovou událost ve stejné transakci jako stav
+            let Some(status) = job_status(&pool, kind, job_id).await? else {
+                yield JobEventStreamItem::NotFound;
+                break;
+            };
+
+            let events = list(&pool, kind, job_id, &query).await?;
+            let page_full = events.len() as i64 >= DEFAULT_LIMIT;
+            for event in events {
+                query.after_seq = Some(event.seq);
+                yield JobEventStreamItem::Event(event);
+            }
+            if page_full {
+                continue;
+            }
+            if is_finished_status(&status) {
+                yield JobEventStreamItem::End(status);
+                break;
+            }
+            tokio::time::sleep(STREAM_TICK).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limit_and_offset_are_clamped() {
+        let query = |after_seq, limit| JobEventsQuery {
+            after_seq,
+            limit,
+            event_type: None,
+        };
+        assert_eq!(page(&query(None, None)), (0, DEFAULT_LIMIT));
+        assert_eq!(page(&query(Some(-5), Some(0))), (0, 1));
+        assert_eq!(page(&query(Some(42), Some(100_000))), (42, MAX_LIMIT));
+    }
+}
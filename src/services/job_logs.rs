@@ -0,0 +1,299 @@
+use futures::Stream;
+use serde::Deserialize;
+use sqlx::PgPool;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::services::object_storage::{self, ObjectStorage};
+
+/// Default long-poll wait; stays below typical proxy idle timeouts (30s)
+pub const DEFAULT_POLL_TIMEOUT_SECONDS: u64 = 25;
+pub const MAX_POLL_TIMEOUT_SECONDS: u64 = 60;
+const DEFAULT_POLL_LIMIT: i64 = 500;
+const MAX_POLL_LIMIT: i64 = 5000;
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Max. prodleva streamu, pokud nepřijde živý signál (řádek z jiné instance, ztracený wakeup)
+const STREAM_TICK: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JobKind {
+    Copy,
+    Deploy,
+}
+
+impl JobKind {
+    fn lines_sql(self) -> &'static str {
+        match self {
+            JobKind::Copy => {
+                "SELECT seq, line, created_at FROM copy_job_logs
+                 WHERE copy_job_id = $1 AND seq > $2 ORDER BY seq LIMIT $3"
+            }
+            JobKind::Deploy => {
+                "SELECT seq, log_line AS line, created_at FROM deploy_job_logs
+                 WHERE deploy_job_id = $1 AND seq > $2 ORDER BY seq LIMIT $3"
+            }
+        }
+    }
+
+    fn status_sql(self) -> &'static str {
+        match self {
+            JobKind::Copy => {
+                "SELECT
+                    cj.status,
+                    cj.current_transfer_stage AS stage,
+                    cj.current_transfer_message AS message,
+                    cj.current_bytes_copied AS bytes_copied,
+                    cj.current_total_bytes AS total_bytes,
+                    (SELECT COUNT(*) FROM copy_job_images i WHERE i.copy_job_id = cj.id) AS total_images,
+                    (SELECT COUNT(*) FROM copy_job_images i
+                     WHERE i.copy_job_id = cj.id AND i.copy_status = 'success') AS copied_images,
+                    (SELECT COUNT(*) FROM copy_job_images i
+                     WHERE i.copy_job_id = cj.id AND i.copy_status = 'failed') AS failed_images,
+                    cj.log_archive_key,
+                    (cj.logs_ended_at IS NOT NULL OR cj.completed_at < NOW() - INTERVAL '1 minute') AS logs_ended
+                 FROM copy_jobs cj WHERE cj.id = $1"
+            }
+            JobKind::Deploy => {
+                "SELECT status,
+                    NULL::text AS stage, NULL::text AS message,
+                    NULL::bigint AS bytes_copied, NULL::bigint AS total_bytes,
+                    NULL::bigint AS total_images, NULL::bigint AS copied_images, NULL::bigint AS failed_images,
+                    log_archive_key,
+                    (logs_ended_at IS NOT NULL OR completed_at < NOW() - INTERVAL '1 minute') AS logs_ended
+                 FROM deploy_jobs WHERE id = $1"
+            }
+        }
+    }
+}
+
+pub use srm_api_types::logs::{JobProgress, LogPollQuery, LogPollResponse, PolledLogLine};
+
+#[derive(Debug, sqlx::FromRow)]
+struct JobStatusRow {
+    status: String,
+    #[sqlx(flatten)]
+    progress: JobProgress,
+    /// Logy hotového jobu přesunuté do object storage (řádky v DB už nejsou)
+    log_archive_key: Option<String>,
+    /// Poslední řádek je uložený (`logs_ended_at`); hotový job bez značky se bere jako uzavřený
+    /// až po minutě - pojistka pro cesty, kde log nikdo nezavřel
+    logs_ended: bool,
+}
+
+pub fn is_finished_status(status: &str) -> bool {
+    matches!(status, "success" | "failed" | "cancelled")
+}
+
+fn poll_timeout(query: &LogPollQuery) -> Duration {
+    Duration::from_secs(
+        query
+            .timeout_seconds
+            .unwrap_or(DEFAULT_POLL_TIMEOUT_SECONDS)
+            .min(MAX_POLL_TIMEOUT_SECONDS),
+    )
+}
+
+/// Řádky za `after_seq` - z DB, u archivovaného jobu z object storage
+async fn fetch_lines(
+    pool: &PgPool,
+    storage: &ObjectStorage,
+    kind: JobKind,
+    job_id: Uuid,
+    archive_key: Option<&str>,
+    after_seq: i64,
+    limit: i64,
+) -> anyhow::Result<Vec<PolledLogLine>> {
+    if let Some(key) = archive_key {
+        let lines = object_storage::load_log_archive(storage, key).await?;
+        return Ok(lines
+            .into_iter()
+            .filter(|line| line.seq > after_seq)
+            .take(limit as usize)
+            .collect());
+    }
+
+    Ok(sqlx::query_as::<_, PolledLogLine>(kind.lines_sql())
+        .bind(job_id)
+        .bind(after_seq)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?)
+}
+
+/// Všechny řádky jobu (endpoint `/logs/history`)
+pub async fn load_all_lines(
+    pool: &PgPool,
+    storage: &ObjectStorage,
+    kind: JobKind,
+    job_id: Uuid,
+) -> anyhow::Result<Vec<PolledLogLine>> {
+    let archive_sql = match kind {
+        JobKind::Copy => "SELECT log_archive_key FROM copy_jobs WHERE id = $1",
+        JobKind::Deploy => "SELECT log_archive_key FROM deploy_jobs WHERE id = $1",
+    };
+    let archive_key = sqlx::query_scalar::<_, Option<String>>(archive_sql)
+        .bind(job_id)
+        .fetch_optional(pool)
+        .await?
+        .flatten();
+    fetch_lines(pool, storage, kind, job_id, archive_key.as_deref(), 0, i64::MAX).await
+}
+
+/// Long-poll: čeká, dokud nepřibudou řádky za `after_seq`, job neskončí nebo nevyprší timeout.
+/// Vrací `None`, pokud job neexistuje.
+pub async fn poll_logs(
+    pool: &PgPool,
+    storage: &ObjectStorage,
+    kind: JobKind,
+    job_id: Uuid,
+    query: &LogPollQuery,
+) -> anyhow::Result<Option<LogPollResponse>> {
+    let after_seq = query.after_seq.unwrap_or(0).max(0);
+    let limit = query.limit.unwrap_or(DEFAULT_POLL_LIMIT).clamp(1, MAX_POLL_LIMIT);
+    let deadline = Instant::now() + poll_timeout(query);
+
+    loop {
+        let Some(job) = sqlx::query_as::<_, JobStatusRow>(kind.status_sql())
+            .bind(job_id)
+            .fetch_optional(pool)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let lines = fetch_lines(pool, storage, kind, job_id, job.log_archive_key.as_deref(), after_seq, limit).await?;
+
+        // `finished` až po doručení všech řádků uzavřeného logu
+        let finished = job.logs_ended && (lines.len() as i64) < limit;
+        if !lines.is_empty() || finished || Instant::now() >= deadline {
+            let next_seq = lines.last().map(|l| l.seq).unwrap_or(after_seq);
+            return Ok(Some(LogPollResponse {
+                lines,
+                next_seq,
+                status: job.status,
+                finished,
+                progress: (kind == JobKind::Copy).then_some(job.progress),
+            }));
+        }
+
+        tokio::time::sleep(POLL_INTERVAL.min(deadline.saturating_duration_since(Instant::now()))).await;
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct LogStreamQuery {
+    /// Přehraje řádky se `seq > after_seq`; při reconnectu má přednost `Last-Event-ID`
+    pub after_seq: Option<i64>,
+}
+
+/// Položka kombinovaného streamu (historie + živé řádky)
+#[derive(Debug)]
+pub enum LogStreamItem {
+    Line(PolledLogLine),
+    /// Job skončil a všechny jeho řádky byly odeslány
+    End(String),
+    /// Job neexistuje
+    NotFound,
+}
+
+/// Počáteční offset: `Last-Event-ID` (reconnect EventSource) má přednost před `?after_seq=`
+pub fn stream_start_seq(last_event_id: Option<&str>, query: &LogStreamQuery) -> i64 {
+    last_event_id
+        .and_then(|raw| raw.trim().parse::<i64>().ok())
+        .or(query.after_seq)
+        .unwrap_or(0)
+        .max(0)
+}
+
+/// Přehraje uložené řádky od `after_seq` a plynule pokračuje živými řádky.
+/// Zdrojem pravdy je vždy DB (kurzor `seq` = žádné duplicity ani mezery);
+/// `wakeup` (lokální broadcast nebo fan-out) jen zkracuje čekání na nové řádky.
+pub fn stream_logs(
+    pool: PgPool,
+    storage: ObjectStorage,
+    kind: JobKind,
+    job_id: Uuid,
+    after_seq: i64,
+    mut wakeup: Option<broadcast::Receiver<String>>,
+) -> impl Stream<Item = anyhow::Result<LogStreamItem>> {
+    async_stream::try_stream! {
+        let mut cursor = after_seq;
+        loop {
+            let Some(job) = sqlx::query_as::<_, JobStatusRow>(kind.status_sql())
+                .bind(job_id)
+                .fetch_optional(&pool)
+                .await?
+            else {
+                yield LogStreamItem::NotFound;
+                break;
+            };
+
+            // Archivovaný job je hotový - celý archiv najednou a konec
+            if let Some(key) = job.log_archive_key.as_deref() {
+                for line in fetch_lines(&pool, &storage, kind, job_id, Some(key), cursor, i64::MAX).await? {
+                    yield LogStreamItem::Line(line);
+                }
+                yield LogStreamItem::End(job.status);
+                break;
+            }
+
+            let lines = fetch_lines(&pool, &storage, kind, job_id, None, cursor, DEFAULT_POLL_LIMIT).await?;
+            let page_full = lines.len() as i64 >= DEFAULT_POLL_LIMIT;
+            for line in lines {
+                cursor = line.seq;
+                yield LogStreamItem::Line(line);
+            }
+            if page_full {
+                continue;
+            }
+            // Značka konce čtená před řádky: po jejím zápisu už žádný řádek nepřibude.
+            // Samotný hotový status nestačí - persist task může ještě dopisovat.
+            if job.logs_ended {
+                yield LogStreamItem::End(job.status);
+                break;
+            }
+
+            match wakeup.as_mut() {
+                Some(rx) => {
+                    tokio::select! {
+                        received = rx.recv() => {
+                            if matches!(received, Err(broadcast::error::RecvError::Closed)) {
+                                wakeup = None;
+                            }
+                        }
+                        _ = tokio::time::sleep(STREAM_TICK) => {}
+                    }
+                }
+                None => tokio::time::sleep(STREAM_TICK).await,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn poll_timeout_is_clamped() {
+        let query = |timeout_seconds| LogPollQuery {
+            after_seq: None,
+            timeout_seconds,
+            limit: None,
+        };
+        assert_eq!(poll_timeout(&query(None)), Duration::from_secs(DEFAULT_POLL_TIMEOUT_SECONDS));
+        assert_eq!(poll_timeout(&query(Some(0))), Duration::ZERO);
+        assert_eq!(poll_timeout(&query(Some(600))), Duration::from_secs(MAX_POLL_TIMEOUT_SECONDS));
+    }
+
+    #[test]
+    fn last_event_id_wins_over_after_seq() {
+        let query = LogStreamQuery { after_seq: Some(5) };
+        assert_eq!(stream_start_seq(None, &query), 5);
+        assert_eq!(stream_start_seq(Some("42"), &query), 42);
+        assert_eq!(stream_start_seq(Some("garbage"), &query), 5);
+        assert_eq!(stream_start_seq(None, &LogStreamQuery::default()), 0);
+        assert_eq!(stream_start_seq(Some("-3"), &query), 0);
+    }
+}
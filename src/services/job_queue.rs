@@ -0,0 +1,223 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::services::agent_protocol;
+use crate::services::job_logs::JobKind;
+
+/// Kde se spouští copy/deploy joby po `/start`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobDispatch {
+    /// Job běží v aktuálním procesu (role `all` a `worker`)
+    InProcess,
+    /// Job se jen označí jako queued, spustí ho worker (role `web`)
+    Queue,
+}
+
+fn table(kind: JobKind) -> &'static str {
+    match kind {
+        JobKind::Copy => "copy_jobs",
+        JobKind::Deploy => "deploy_jobs",
+    }
+}
+
+/// Stav jobu před zařazením do fronty; `None` = job neexistuje
+pub async fn queue_state(pool: &PgPool, kind: JobKind, job_id: Uuid) -> Result<Option<(String, bool)>, sqlx::Error> {
+    let sql = format!("SELECT status, queued_at IS NOT NULL FROM {} WHERE id = $1", table(kind));
+    sqlx::query_as::<_, (String, bool)>(&sql)
+        .bind(job_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Zařadí pending job do fronty; `false` = job už není pending nebo je už ve frontě
+pub async fn enqueue(pool: &PgPool, kind: JobKind, job_id: Uuid) -> Result<bool, sqlx::Error> {
+    let sql = format!(
+        "UPDATE {} SET queued_at = NOW() WHERE id = $1 AND status = 'pending' AND queued_at IS NULL",
+        table(kind)
+    );
+    let result = sqlx::query(&sql).bind(job_id).execute(pool).await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Převezme nejstarší nepřevzatý job z fronty (SKIP LOCKED - bezpečné pro více workerů).
+/// Joby prostředí s přiřazeným agentem převezme jen agent, ne worker.
+pub async fn claim_next(pool: &PgPool, kind: JobKind, worker_id: &str) -> Result<Option<Uuid>, sqlx::Error> {
+    let sql = format!(
+        "UPDATE {table} SET claimed_by = $1, claimed_at = NOW(), heartbeat_at = NOW()
+         WHERE id = (
+             SELECT id FROM {table}
+             WHERE status = 'pending' AND queued_at IS NOT NULL AND claimed_by IS NULL
+               AND NOT EXISTS (SELECT 1 FROM environments e WHERE e.id = {table}.environment_id AND e.agent_id IS NOT NULL)
+             ORDER BY queued_at
+             FOR UPDATE SKIP LOCKED
+             LIMIT 1
+         )
+         RETURNING id",
+        table = table(kind)
+    );
+    sqlx::query_scalar::<_, Uuid>(&sql)
+        .bind(worker_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Převezme nejstarší job z fronty pro prostředí přiřazená agentovi
+pub async fn claim_next_for_agent(pool: &PgPool, kind: JobKind, agent_id: Uuid) -> Result<Option<Uuid>, sqlx::Error> {
+    let sql = format!(
+        "UPDATE {table} SET claimed_by = $2, claimed_at = NOW()
+         WHERE id = (
+             SELECT j.id FROM {table} j
+             JOIN environments e ON e.id = j.environment_id
+             WHERE e.agent_id = $1 AND j.status = 'pending' AND j.queued_at IS NOT NULL AND j.claimed_by IS NULL
+             ORDER BY j.queued_at
+             FOR UPDATE OF j SKIP LOCKED
+             LIMIT 1
+         )
+         RETURNING id",
+        table = table(kind)
+    );
+    sqlx::query_scalar::<_, Uuid>(&sql)
+        .bind(agent_id)
+        .bind(agent_protocol::claimed_by(agent_id))
+        .fetch_optional(pool)
+        .await
+}
+
+/// Agent, kterému patří prostředí jobu (`None` = job spouští worker / API)
+pub async fn job_agent(pool: &PgPool, kind: JobKind, job_id: Uuid) -> Result<Option<Uuid>, sqlx::Error> {
+    let sql = format!(
+        "SELECT e.agent_id FROM {} j JOIN environments e ON e.id = j.environment_id WHERE j.id = $1",
+        table(kind)
+    );
+    sqlx::query_scalar::<_, Option<Uuid>>(&sql)
+        .bind(job_id)
+        .fetch_optional(pool)
+        .await
+        .map(Option::flatten)
+}
+
+/// Agent přiřazený prostředí (`None` = joby prostředí spouští worker / API)
+pub async fn environment_agent(pool: &PgPool, environment_id: Uuid) -> Result<Option<Uuid>, sqlx::Error> {
+    sqlx::query_scalar::<_, Option<Uuid>>("SELECT agent_id FROM environments WHERE id = $1")
+        .bind(environment_id)
+        .fetch_optional(pool)
+        .await
+        .map(Option::flatten)
+}
+
+/// Počet rozběhnutých jobů převzatých tímto workerem (jen s platným leasem)
+pub async fn active_count(pool: &PgPool, kind: JobKind, worker_id: &str, lease_seconds: u64) -> Result<i64, sqlx::Error> {
+    let sql = format!(
+        "SELECT COUNT(*) FROM {} WHERE claimed_by = $1 AND status IN ('pending', 'in_progress')
+           AND heartbeat_at >= NOW() - make_interval(secs => $2)",
+        table(kind)
+    );
+    sqlx::query_scalar::<_, i64>(&sql)
+        .bind(worker_id)
+        .bind(lease_seconds as f64)
+        .fetch_one(pool)
+        .await
+}
+
+/// Obnoví lease jobů, které v tomto procesu opravdu běží
+pub async fn heartbeat(pool: &PgPool, kind: JobKind, worker_id: &str, job_ids: &[Uuid]) -> Result<(), sqlx::Error> {
+    if job_ids.is_empty() {
+        return Ok(());
+    }
+    let sql = format!(
+        "UPDATE {} SET heartbeat_at = NOW() WHERE claimed_by = $1 AND id = ANY($2)",
+        table(kind)
+    );
+    sqlx::query(&sql).bind(worker_id).bind(job_ids).execute(pool).await?;
+    Ok(())
+}
+
+/// Joby workerů s propadlým leasem (`$1` = lease v sekundách). Joby restartovaného workeru (`$2`)
+/// platí za propadlé hned. Joby agentů hlídá agent sám (`fail_interrupted_jobs`).
+const STALE_CLAIM: &str = "claimed_by IS NOT NULL AND claimed_by NOT LIKE 'agent:%'
+    AND (heartbeat_at IS NULL OR heartbeat_at < NOW() - make_interval(secs => $1) OR claimed_by = $2)";
+
+/// Vrátí do fronty převzaté, ale nespuštěné joby spadlých workerů
+pub async fn requeue_stale(
+    pool: &PgPool,
+    kind: JobKind,
+    lease_seconds: u64,
+    restarted_worker: Option<&str>,
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    let sql = format!(
+        "UPDATE {} SET claimed_by = NULL, claimed_at = NULL, heartbeat_at = NULL
+         WHERE status = 'pending' AND {STALE_CLAIM}
+         RETURNING id",
+        table(kind)
+    );
+    sqlx::query_scalar::<_, Uuid>(&sql)
+        .bind(lease_seconds as f64)
+        .bind(restarted_worker)
+        .fetch_all(pool)
+        .await
+}
+
+/// Převezme běžící joby spadlých workerů; volající je označí jako failed
+pub async fn take_stale_running(
+    pool: &PgPool,
+    kind: JobKind,
+    lease_seconds: u64,
+    restarted_worker: Option<&str>,
+    worker_id: &str,
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    let sql = format!(
+        "UPDATE {table} SET claimed_by = $3, heartbeat_at = NOW()
+         WHERE id IN (
+             SELECT id FROM {table}
+             WHERE status = 'in_progress' AND {STALE_CLAIM}
+             FOR UPDATE SKIP LOCKED
+         )
+         RETURNING id",
+        table = table(kind)
+    );
+    sqlx::query_scalar::<_, Uuid>(&sql)
+        .bind(lease_seconds as f64)
+        .bind(restarted_worker)
+        .bind(worker_id)
+        .fetch_all(pool)
+        .await
+}
+
+/// Které z běžících copy jobů byly mezitím zrušené přes API (cancel flag žije v paměti workeru)
+pub async fn cancelled_copy_jobs(pool: &PgPool, job_ids: &[Uuid]) -> Result<Vec<Uuid>, sqlx::Error> {
+    sqlx::query_scalar::<_, Uuid>("SELECT id FROM copy_jobs WHERE id = ANY($1) AND status = 'cancelled'")
+        .bind(job_ids)
+        .fetch_all(pool)
+        .await
+}
+
+/// Označí převzatý job, který se nepodařilo spustit, jako failed
+pub async fn fail_unstarted(pool: &PgPool, kind: JobKind, job_id: Uuid, message: &str) -> Result<(), sqlx::Error> {
+    match kind {
+        JobKind::Copy => {
+            sqlx::query("INSERT INTO copy_job_logs (copy_job_id, line) VALUES ($1, $2)")
+                .bind(job_id)
+                .bind(format!("Copy job failed to start: {}", message))
+                .execute(pool)
+                .await?;
+            sqlx::query(
+                "UPDATE copy_jobs SET status = 'failed', completed_at = NOW(), logs_ended_at = NOW()
+                 WHERE id = $1 AND status = 'pending'",
+            )
+                .bind(job_id)
+                .execute(pool)
+                .await?;
+        }
+        JobKind::Deploy => {
+            sqlx::query(
+                "UPDATE deploy_jobs SET status = 'failed', completed_at = NOW(), logs_ended_at = NOW(), error_message = $2
+                 WHERE id = $1 AND status = 'pending'",
+            )
+            .bind(job_id)
+            .bind(message)
+            .execute(pool)
+            .await?;
+        }
+    }
+    Ok(())
+}
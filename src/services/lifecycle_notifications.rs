@@ -0,0 +1,222 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::services::events::{
+    CopyJobEventData, DeployJobEventData, EventEnvelope, EventSpec, PipelineEventData, ReleaseEventData,
+    PIPELINE_FINISHED, RELEASE_CREATED,
+};
+use crate::services::notifications::Notifier;
+
+#[derive(Debug, FromRow)]
+struct CopyJobEventRow {
+    tenant_id: Uuid,
+    bundle_id: Uuid,
+    bundle_version: i32,
+    target_tag: String,
+    environment_id: Option<Uuid>,
+    status: String,
+    total_images: i64,
+    failed_images: i64,
+    started_at: DateTime<Utc>,
+    completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, FromRow)]
+struct DeployJobEventRow {
+    tenant_id: Uuid,
+    release_uuid: Uuid,
+    environment_id: Uuid,
+    status: String,
+    dry_run: bool,
+    commit_sha: Option<String>,
+    tag_name: Option<String>,
+    error_message: Option<String>,
+    started_at: DateTime<Utc>,
+    completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, FromRow)]
+struct ReleaseEventRow {
+    tenant_id: Uuid,
+    release_id: String,
+    copy_job_id: Uuid,
+    is_auto: bool,
+    created_by: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, FromRow)]
+struct PipelineEventRow {
+    tenant_id: Uuid,
+    status: String,
+    stage: String,
+    copy_job_id: Option<Uuid>,
+    release_uuid: Option<Uuid>,
+    deploy_job_ids: Vec<Uuid>,
+    error_message: Option<String>,
+}
+
+/// Payload se načte při přechodu stavu, doručení běží na pozadí - pomalý webhook nezdrží job
+fn dispatch<T: Serialize + Send + Sync + 'static>(notifier: &Notifier, spec: &'static EventSpec, tenant_id: Uuid, data: T) {
+    let notifier = notifier.clone();
+    let envelope = EventEnvelope::new(spec, Some(tenant_id), data);
+    tokio::spawn(async move {
+        // Chybu doručení loguje Notifier
+        let _ = notifier.send(&envelope).await;
+    });
+}
+
+/// `copy_job.started` / `copy_job.finished` se stavem jobu v okamžiku přechodu
+pub async fn copy_job(pool: &PgPool, notifier: &Notifier, spec: &'static EventSpec, job_id: Uuid) {
+    if !notifier.has_webhook() {
+        return;
+    }
+    let row = sqlx::query_as::<_, CopyJobEventRow>(
+        r#"
+        SELECT b.tenant_id, bv.bundle_id, bv.version AS bundle_version, cj.target_tag, cj.environment_id,
+               cj.status, cj.started_at, cj.completed_at,
+               (SELECT COUNT(*) FROM copy_job_images WHERE copy_job_id = cj.id) AS total_images,
+               (SELECT COUNT(*) FROM copy_job_images WHERE copy_job_id = cj.id AND copy_status = 'failed') AS failed_images
+        FROM copy_jobs cj
+        JOIN bundle_versions bv ON bv.id = cj.bundle_version_id
+        JOIN bundles b ON b.id = bv.bundle_id
+        WHERE cj.id = $1
+        "#,
+    )
+    .bind(job_id)
+    .fetch_optional(pool)
+    .await;
+    match row {
+        Ok(Some(row)) => dispatch(
+            notifier,
+            spec,
+            row.tenant_id,
+            CopyJobEventData {
+                job_id,
+                bundle_id: row.bundle_id,
+                bundle_version: row.bundle_version,
+                target_tag: row.target_tag,
+                environment_id: row.environment_id,
+                status: row.status,
+                total_images: row.total_images,
+                failed_images: row.failed_images,
+                started_at: row.started_at,
+                completed_at: row.completed_at,
+            },
+        ),
+        Ok(None) => {}
+        Err(e) => warn!(%job_id, event = spec.event_type, error = %e, "Failed to load copy job event"),
+    }
+}
+
+/// `deploy_job.started` / `deploy_job.finished` se stavem jobu v okamžiku přechodu
+pub async fn deploy_job(pool: &PgPool, notifier: &Notifier, spec: &'static EventSpec, job_id: Uuid) {
+    if !notifier.has_webhook() {
+        return;
+    }
+    let row = sqlx::query_as::<_, DeployJobEventRow>(
+        r#"
+        SELECT e.tenant_id, dj.release_id AS release_uuid, dj.environment_id, dj.status, dj.dry_run,
+               dj.commit_sha, dj.tag_name, dj.error_message, dj.started_at, dj.completed_at
+        FROM deploy_jobs dj
+        JOIN environments e ON e.id = dj.environment_id
+        WHERE dj.id = $1
+        "#,
+    )
+    .bind(job_id)
+    .fetch_optional(pool)
+    .await;
+    match row {
+        Ok(Some(row)) => dispatch(
+            notifier,
+            spec,
+            row.tenant_id,
+            DeployJobEventData {
+                job_id,
+                release_uuid: row.release_uuid,
+                environment_id: row.environment_id,
+                status: row.status,
+                dry_run: row.dry_run,
+                commit_sha: row.commit_sha,
+                tag_name: row.tag_name,
+                error_message: row.error_message,
+                started_at: row.started_at,
+                completed_at: row.completed_at,
+            },
+        ),
+        Ok(None) => {}
+        Err(e) => warn!(%job_id, event = spec.event_type, error = %e, "Failed to load deploy job event"),
+    }
+}
+
+/// `release.created` po založení release (ruční i automatické)
+pub async fn release_created(pool: &PgPool, notifier: &Notifier, release_uuid: Uuid) {
+    if !notifier.has_webhook() {
+        return;
+    }
+    let row = sqlx::query_as::<_, ReleaseEventRow>(
+        r#"
+        SELECT b.tenant_id, r.release_id, r.copy_job_id, r.is_auto, r.created_by, r.created_at
+        FROM releases r
+        JOIN copy_jobs cj ON cj.id = r.copy_job_id
+        JOIN bundle_versions bv ON bv.id = cj.bundle_version_id
+        JOIN bundles b ON b.id = bv.bundle_id
+        WHERE r.id = $1
+        "#,
+    )
+    .bind(release_uuid)
+    .fetch_optional(pool)
+    .await;
+    match row {
+        Ok(Some(row)) => dispatch(
+            notifier,
+            &RELEASE_CREATED,
+            row.tenant_id,
+            ReleaseEventData {
+                release_uuid,
+                release_id: row.release_id,
+                copy_job_id: row.copy_job_id,
+                is_auto: row.is_auto,
+                created_by: row.created_by,
+                created_at: row.created_at,
+            },
+        ),
+        Ok(None) => {}
+        Err(e) => warn!(%release_uuid, error = %e, "Failed to load release event"),
+    }
+}
+
+/// `pipeline.finished` po terminálním stavu pipeline run
+pub async fn pipeline_finished(pool: &PgPool, notifier: &Notifier, pipeline_id: Uuid) {
+    if !notifier.has_webhook() {
+        return;
+    }
+    let row = sqlx::query_as::<_, PipelineEventRow>(
+        "SELECT tenant_id, status, stage, copy_job_id, release_uuid, deploy_job_ids, error_message
+         FROM pipeline_runs WHERE id = $1",
+    )
+    .bind(pipeline_id)
+    .fetch_optional(pool)
+    .await;
+    match row {
+        Ok(Some(row)) => dispatch(
+            notifier,
+            &PIPELINE_FINISHED,
+            row.tenant_id,
+            PipelineEventData {
+                pipeline_id,
+                status: row.status,
+                stage: row.stage,
+                copy_job_id: row.copy_job_id,
+                release_uuid: row.release_uuid,
+                deploy_job_ids: row.deploy_job_ids,
+                error_message: row.error_message,
+            },
+        ),
+        Ok(None) => {}
+        Err(e) => warn!(%pipeline_id, error = %e, "Failed to load pipeline event"),
+    }
+}
@@ -0,0 +1,279 @@
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgListener;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, RwLock};
+use uuid::Uuid;
+
+use crate::services::job_logs::{is_finished_status, JobKind};
+
+/// Postgres kanál pro log řádky jobů (LISTEN/NOTIFY)
+pub const NOTIFY_CHANNEL: &str = "srm_job_logs";
+
+/// NOTIFY payload má limit 8000 bajtů (včetně JSON escapování); delší řádky se zkrátí (v DB zůstávají celé)
+const MAX_NOTIFY_PAYLOAD_BYTES: usize = 7900;
+
+#[derive(Debug, Deserialize)]
+struct LogNotification {
+    kind: String,
+    job_id: Uuid,
+    #[serde(default)]
+    line: Option<String>,
+    #[serde(default)]
+    end: bool,
+}
+
+#[derive(Serialize)]
+struct LogNotificationPayload<'a> {
+    kind: &'static str,
+    job_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seq: Option<i64>,
+    line: &'a str,
+}
+
+/// JSON payload pro NOTIFY; řádek se zkracuje na hranici UTF-8 znaku, dokud se celý payload nevejde do limitu
+fn notify_payload(kind: JobKind, job_id: Uuid, seq: Option<i64>, line: &str) -> String {
+    let mut line = line;
+    loop {
+        let payload = serde_json::to_string(&LogNotificationPayload {
+            kind: kind_name(kind),
+            job_id,
+            seq,
+            line,
+        })
+        .unwrap_or_default();
+        if payload.len() <= MAX_NOTIFY_PAYLOAD_BYTES {
+            return payload;
+        }
+        let excess = payload.len() - MAX_NOTIFY_PAYLOAD_BYTES;
+        let mut cut = line.len().saturating_sub(excess);
+        while !line.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        line = &line[..cut];
+    }
+}
+
+type FanoutChannels = HashMap<(JobKind, Uuid), broadcast::Sender<String>>;
+
+/// Most mezi instancemi - log řádky jobu běžícího na jiné replice/workeru
+#[derive(Clone)]
+pub struct LogFanout {
+    enabled: bool,
+    channels: Arc<RwLock<FanoutChannels>>,
+}
+
+impl LogFanout {
+    pub fn new(enabled: bool) -> Self {
+        LogFanout {
+            enabled,
+            channels: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Uloží log řádek do DB a (je-li fan-out zapnutý) ho rozešle ostatním instancím.
+    /// NOTIFY běží až po INSERTu zvlášť - jeho chyba uložený řádek nevrátí.
+    pub async fn persist_line(&self, pool: &PgPool, kind: JobKind, job_id: Uuid, line: &str) -> Result<(), sqlx::Error> {
+        let insert = match kind {
+            JobKind::Copy => "INSERT INTO copy_job_logs (copy_job_id, line) VALUES ($1, $2) RETURNING seq",
+            JobKind::Deploy => "INSERT INTO deploy_job_logs (deploy_job_id, log_line) VALUES ($1, $2) RETURNING seq",
+        };
+        let seq = sqlx::query_scalar::<_, i64>(insert)
+            .bind(job_id)
+            .bind(line)
+            .fetch_one(pool)
+            .await?;
+        if self.enabled
+            && let Err(e) = notify(pool, &notify_payload(kind, job_id, Some(seq), line)).await
+        {
+            tracing::warn!(%job_id, "Job log notify failed: {}", e);
+        }
+        Ok(())
+    }
+
+    /// Rozešle řádek bez uložení (progress markery copy jobů)
+    pub async fn publish_line(&self, pool: &PgPool, kind: JobKind, job_id: Uuid, line: &str) -> Result<(), sqlx::Error> {
+        if !self.enabled {
+            return Ok(());
+        }
+        notify(pool, &notify_payload(kind, job_id, None, line)).await
+    }
+
+    /// Uzavře log jobu až po uložení jeho posledního řádku: zapíše značku `logs_ended_at`,
+    /// na kterou čeká stream/poll, a oznámí konec ostatním instancím (odběratelé dostanou `Closed`)
+    pub async fn end_log(&self, pool: &PgPool, kind: JobKind, job_id: Uuid) -> Result<(), sqlx::Error> {
+        let mark = match kind {
+            JobKind::Copy => "UPDATE copy_jobs SET logs_ended_at = NOW() WHERE id = $1 AND logs_ended_at IS NULL",
+            JobKind::Deploy => "UPDATE deploy_jobs SET logs_ended_at = NOW() WHERE id = $1 AND logs_ended_at IS NULL",
+        };
+        sqlx::query(mark).bind(job_id).execute(pool).await?;
+        if !self.enabled {
+            return Ok(());
+        }
+        sqlx::query("SELECT pg_notify($1, json_build_object('kind', $2::text, 'job_id', $3::uuid, 'end', true)::text)")
+            .bind(NOTIFY_CHANNEL)
+            .bind(kind_name(kind))
+            .bind(job_id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Odběr logů jobu běžícího jinde; `None` = fan-out vypnutý, job neexistuje nebo už skončil
+    pub async fn subscribe(&self, pool: &PgPool, kind: JobKind, job_id: Uuid) -> Option<broadcast::Receiver<String>> {
+        if !self.enabled {
+            return None;
+        }
+        let rx = {
+            let mut channels = self.channels.write().await;
+            channels
+                .entry((kind, job_id))
+                .or_insert_with(|| broadcast::channel(512).0)
+                .subscribe()
+        };
+
+        // Kontrola až po subscribe - konec jobu mezi kontrolou a subscribe by se jinak ztratil
+        let sql = match kind {
+            JobKind::Copy => "SELECT status FROM copy_jobs WHERE id = $1",
+            JobKind::Deploy => "SELECT status FROM deploy_jobs WHERE id = $1",
+        };
+        match sqlx::query_scalar::<_, String>(sql).bind(job_id).fetch_optional(pool).await {
+            Ok(Some(status)) if !is_finished_status(&status) => Some(rx),
+            _ => None,
+        }
+    }
+
+    async fn dispatch(&self, payload: &str) {
+        let Ok(notification) = serde_json::from_str::<LogNotification>(payload) else {
+            tracing::warn!("Invalid job log notification payload");
+            return;
+        };
+        let kind = match notification.kind.as_str() {
+            "copy" => JobKind::Copy,
+            "deploy" => JobKind::Deploy,
+            _ => return,
+        };
+        let key = (kind, notification.job_id);
+
+        let mut channels = self.channels.write().await;
+        if notification.end {
+            channels.remove(&key);
+            return;
+        }
+        let Some(line) = notification.line else {
+            return;
+        };
+        // Nikdo už neposlouchá
+        if let Some(sender) = channels.get(&key)
+            && sender.send(line).is_err()
+        {
+            channels.remove(&key);
+        }
+    }
+}
+
+async fn notify(pool: &PgPool, payload: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("SELECT pg_notify($1, $2)")
+        .bind(NOTIFY_CHANNEL)
+        .bind(payload)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+fn kind_name(kind: JobKind) -> &'static str {
+    match kind {
+        JobKind::Copy => "copy",
+        JobKind::Deploy => "deploy",
+    }
+}
+
+/// Spustí LISTEN na `srm_job_logs` a předává řádky lokálním odběratelům (s reconnectem)
+pub fn spawn_listener(pool: PgPool, fanout: LogFanout) {
+    if !fanout.enabled {
+        return;
+    }
+    tokio::spawn(async move {
+        loop {
+            let mut listener = match PgListener::connect_with(&pool).await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    tracing::warn!("Job log listener connect failed: {}", e);
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    continue;
+                }
+            };
+            if let Err(e) = listener.listen(NOTIFY_CHANNEL).await {
+                tracing::warn!("Job log LISTEN failed: {}", e);
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                continue;
+            }
+            tracing::info!("Job log fan-out listening on {}", NOTIFY_CHANNEL);
+
+            loop {
+                match listener.recv().await {
+                    Ok(notification) => fanout.dispatch(notification.payload()).await,
+                    Err(e) => {
+                        tracing::warn!("Job log listener error: {}", e);
+                        break;
+                    }
+                }
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn dispatch_routes_lines_and_closes_on_end() {
+        let fanout = LogFanout::new(true);
+        let job_id = Uuid::new_v4();
+        let mut rx = {
+            let mut channels = fanout.channels.write().await;
+            channels
+                .entry((JobKind::Copy, job_id))
+                .or_insert_with(|| broadcast::channel(16).0)
+                .subscribe()
+        };
+
+        let line = format!(r#"{{"kind":"copy","job_id":"{}","seq":1,"line":"hello"}}"#, job_id);
+        fanout.dispatch(&line).await;
+        let other = format!(r#"{{"kind":"deploy","job_id":"{}","line":"ignored"}}"#, job_id);
+        fanout.dispatch(&other).await;
+        assert_eq!(rx.recv().await.unwrap(), "hello");
+
+        fanout.dispatch(&format!(r#"{{"kind":"copy","job_id":"{}","end":true}}"#, job_id)).await;
+        assert!(matches!(rx.recv().await, Err(broadcast::error::RecvError::Closed)));
+    }
+
+    #[tokio::test]
+    async fn notify_payload_fits_limit_for_long_multibyte_lines() {
+        let job_id = Uuid::new_v4();
+        for line in ["žluťoučký kůň ".repeat(1000), r#"{"msg":"\"quoted\""}"#.repeat(800), "🚀".repeat(3000)] {
+            let payload = notify_payload(JobKind::Deploy, job_id, Some(42), &line);
+            assert!(payload.len() <= MAX_NOTIFY_PAYLOAD_BYTES, "payload {} bytes", payload.len());
+
+            let fanout = LogFanout::new(true);
+            let mut rx = {
+                let mut channels = fanout.channels.write().await;
+                channels
+                    .entry((JobKind::Deploy, job_id))
+                    .or_insert_with(|| broadcast::channel(16).0)
+                    .subscribe()
+            };
+            fanout.dispatch(&payload).await;
+            let received = rx.recv().await.unwrap();
+            assert!(!received.is_empty() && line.starts_with(&received));
+        }
+
+        let short = notify_payload(JobKind::Copy, job_id, None, "hello");
+        assert_eq!(short, format!(r#"{{"kind":"copy","job_id":"{}","line":"hello"}}"#, job_id));
+    }
+}
@@ -0,0 +1,121 @@
+use anyhow::Result;
+
+use crate::services::release_manifest::ReleaseManifest;
+
+pub const KIND_GIT: &str = "git";
+pub const KIND_S3: &str = "s3";
+pub const KIND_HTTP: &str = "http";
+
+pub const FORMAT_YAML: &str = "yaml";
+pub const FORMAT_JSON: &str = "json";
+
+pub const DEFAULT_PATH_TEMPLATE: &str = "{bundle}/{release_id}.{ext}";
+
+const PLACEHOLDERS: &[&str] = &["tenant", "bundle", "release_id", "release_uuid", "ext"];
+
+/// Hodnoty placeholderů v `path_template`
+pub struct PathVars<'a> {
+    pub tenant: &'a str,
+    pub bundle: &'a str,
+    pub release_id: &'a str,
+    pub release_uuid: &'a str,
+    pub format: &'a str,
+}
+
+/// Hodnota placeholderu jako jeden segment cesty (žádné `/`, mezery ani `..`)
+fn path_segment(value: &str) -> String {
+    let segment: String = value
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') { c } else { '-' })
+        .collect();
+    if segment.is_empty() || segment.chars().all(|c| c == '.') {
+        "_".repeat(segment.len().max(1))
+    } else {
+        segment
+    }
+}
+
+/// Relativní cesta bez `..`, s jen známými placeholdery
+pub fn validate_path_template(template: &str) -> Result<(), String> {
+    let template = template.trim();
+    if template.is_empty() {
+        return Err("Path template cannot be empty".to_string());
+    }
+    if template.starts_with('/') || template.contains('\\') {
+        return Err("Path template must be a relative path".to_string());
+    }
+    if template.split('/').any(|segment| segment.is_empty() || segment == "." || segment == "..") {
+        return Err("Path template cannot contain empty, '.' or '..' segments".to_string());
+    }
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            return Err("Unclosed '{' in path template".to_string());
+        };
+        let name = &rest[start + 1..start + len];
+        if !PLACEHOLDERS.contains(&name) {
+            return Err(format!(
+                "Unknown placeholder '{{{}}}', expected one of: {}",
+                name,
+                PLACEHOLDERS.iter().map(|p| format!("{{{}}}", p)).collect::<Vec<_>>().join(", ")
+            ));
+        }
+        rest = &rest[start + len + 1..];
+    }
+    Ok(())
+}
+
+pub fn render_path(template: &str, vars: &PathVars) -> String {
+    template
+        .trim()
+        .replace("{tenant}", &path_segment(vars.tenant))
+        .replace("{bundle}", &path_segment(vars.bundle))
+        .replace("{release_id}", &path_segment(vars.release_id))
+        .replace("{release_uuid}", &path_segment(vars.release_uuid))
+        .replace("{ext}", extension(vars.format))
+}
+
+fn extension(format: &str) -> &'static str {
+    if format == FORMAT_JSON { "json" } else { "yaml" }
+}
+
+/// Tělo manifestu a jeho content type
+pub fn serialize_manifest(manifest: &ReleaseManifest, format: &str) -> Result<(Vec<u8>, &'static str)> {
+    if format == FORMAT_JSON {
+        Ok((serde_json::to_vec_pretty(manifest)?, "application/json"))
+    } else {
+        Ok((serde_yaml_ng::to_string(manifest)?.into_bytes(), "text/yaml; charset=utf-8"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn template_validation_rejects_escapes_and_unknown_placeholders() {
+        assert!(validate_path_template(DEFAULT_PATH_TEMPLATE).is_ok());
+        assert!(validate_path_template("releases/{tenant}/{release_uuid}.json").is_ok());
+        assert!(validate_path_template("").is_err());
+        assert!(validate_path_template("/abs/{release_id}").is_err());
+        assert!(validate_path_template("../{release_id}").is_err());
+        assert!(validate_path_template("a//b").is_err());
+        assert!(validate_path_template("{version}.yaml").is_err());
+        assert!(validate_path_template("{release_id.yaml").is_err());
+    }
+
+    #[test]
+    fn placeholders_render_as_single_segments() {
+        let vars = PathVars {
+            tenant: "acme",
+            bundle: "Shop Backend/api",
+            release_id: "..",
+            release_uuid: "5b3c",
+            format: FORMAT_JSON,
+        };
+        assert_eq!(render_path(DEFAULT_PATH_TEMPLATE, &vars), "Shop-Backend-api/__.json");
+        assert_eq!(render_path("{tenant}/{release_uuid}.{ext}", &vars), "acme/5b3c.json");
+        let vars = PathVars { format: FORMAT_YAML, release_id: "2026.10.15-1", ..vars };
+        assert_eq!(render_path("{release_id}.{ext}", &vars), "2026.10.15-1.yaml");
+    }
+}
@@ -1,4 +1,42 @@
+pub mod agent_protocol;
+pub mod base_image_updates;
+pub mod blob_reuse;
+pub mod change_history;
+pub mod config_preview;
+pub mod copy_eta;
+pub mod credential_expiry;
+pub mod csv_import;
+pub mod dashboard_views;
+pub mod deploy_diff;
+pub mod deploy_steps;
+pub mod docker_config;
+pub mod env_layers;
+pub mod env_templates;
+pub mod environment_health;
+pub mod events;
+pub mod file_templates;
+pub mod image_policy;
 pub mod image_tool;
+pub mod job_events;
+pub mod key_material;
+pub mod lifecycle_notifications;
+pub mod job_logs;
+pub mod job_queue;
+pub mod log_fanout;
+pub mod manifest_delivery;
+pub mod notifications;
+pub mod object_storage;
+pub mod owner_notifications;
+pub mod pull_secret;
+pub mod reaper;
+pub mod release_channels;
+pub mod release_exports;
 pub mod release_manifest;
+pub mod release_notes;
+pub mod release_tag_cleanup;
+pub mod sandbox;
+pub mod share_links;
+pub mod tool_paths;
+pub mod validation_report;
 
 pub use image_tool::ImageToolService;
@@ -0,0 +1,108 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::warn;
+
+use crate::services::events::EventEnvelope;
+
+/// Odesílání eventů na notifikační webhook (`NOTIFICATION_WEBHOOK_URL`).
+/// Bez URL se eventy jen logují.
+#[derive(Clone)]
+pub struct Notifier {
+    client: reqwest::Client,
+    webhook_url: Option<String>,
+    /// Webhooky vlastníků aplikací (`NOTIFICATION_OWNER_WEBHOOKS`)
+    owner_webhooks: HashMap<String, String>,
+}
+
+impl Notifier {
+    pub fn new(webhook_url: Option<String>) -> Self {
+        Notifier {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .expect("Failed to build notification HTTP client"),
+            webhook_url,
+            owner_webhooks: HashMap::new(),
+        }
+    }
+
+    pub fn with_owner_webhooks(mut self, owner_webhooks: HashMap<String, String>) -> Self {
+        self.owner_webhooks = owner_webhooks;
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.webhook_url.is_some() || !self.owner_webhooks.is_empty()
+    }
+
+    /// Eventy životního cyklu jdou jen na výchozí webhook
+    pub fn has_webhook(&self) -> bool {
+        self.webhook_url.is_some()
+    }
+
+    /// POST obálky eventu jako JSON; chyba = event nebyl doručen
+    pub async fn send<T: Serialize>(&self, envelope: &EventEnvelope<T>) -> Result<(), reqwest::Error> {
+        self.post(self.webhook_url.as_deref(), envelope).await
+    }
+
+    /// Event pro vlastníka aplikace - jeho webhook, jinak výchozí `NOTIFICATION_WEBHOOK_URL`
+    pub async fn send_to_owner<T: Serialize>(
+        &self,
+        owner: Option<&str>,
+        envelope: &EventEnvelope<T>,
+    ) -> Result<(), reqwest::Error> {
+        self.post(self.owner_url(owner), envelope).await
+    }
+
+    fn owner_url(&self, owner: Option<&str>) -> Option<&str> {
+        owner
+            .and_then(|owner| self.owner_webhooks.get(owner))
+            .map(String::as_str)
+            .or(self.webhook_url.as_deref())
+    }
+
+    async fn post<T: Serialize>(&self, url: Option<&str>, envelope: &EventEnvelope<T>) -> Result<(), reqwest::Error> {
+        let Some(url) = url else {
+            return Ok(());
+        };
+        let result = self
+            .client
+            .post(url)
+            .json(envelope)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        if let Err(e) = &result {
+            warn!(event = envelope.event_type, error = %e, "Failed to deliver notification");
+        }
+        result.map(|_| ())
+    }
+}
+
+/// `owner=url` páry oddělené čárkou (`team-payments=https://...,alice=https://...`)
+pub fn parse_owner_webhooks(raw: &str) -> HashMap<String, String> {
+    raw.split(',')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(owner, url)| (owner.trim().to_string(), url.trim().to_string()))
+        .filter(|(owner, url)| !owner.is_empty() && !url.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn owner_webhooks_fall_back_to_default() {
+        let owners = parse_owner_webhooks(" team-a = https://a.example/hook ,broken, =https://x, bob=https://b?x=1");
+        assert_eq!(owners.len(), 2);
+        assert_eq!(owners["bob"], "https://b?x=1");
+
+        let notifier = Notifier::new(Some("https://default".to_string())).with_owner_webhooks(owners);
+        assert_eq!(notifier.owner_url(Some("team-a")), Some("https://a.example/hook"));
+        assert_eq!(notifier.owner_url(Some("unknown")), Some("https://default"));
+        assert_eq!(notifier.owner_url(None), Some("https://default"));
+        assert_eq!(Notifier::new(None).owner_url(Some("team-a")), None);
+    }
+}
@@ -0,0 +1,431 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::services::job_logs::{JobKind, PolledLogLine};
+
+/// Počet jobů (a diffů) archivovaných v jednom průchodu archiveru
+const ARCHIVE_BATCH_SIZE: i64 = 50;
+
+/// S3/MinIO úložiště pro archivy logů a deploy artefakty
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ObjectStorageConfig {
+    /// Např. `https://s3.eu-central-1.amazonaws.com` nebo `http://minio:9000`
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key: String,
+    pub secret_key: String,
+    /// Prefix klíčů v bucketu (např. `srm/`)
+    pub prefix: String,
+    /// `http://endpoint/bucket/key` (MinIO) místo `http://bucket.endpoint/key`
+    pub path_style: bool,
+    /// Stáří hotových jobů, po kterém se logy a diffy přesunou z DB do úložiště
+    pub archive_after_hours: u64,
+    pub archive_interval_seconds: u64,
+}
+
+struct S3Client {
+    config: ObjectStorageConfig,
+    http: reqwest::Client,
+}
+
+/// Volitelné object storage; bez konfigurace zůstává vše v Postgresu
+#[derive(Clone, Default)]
+pub struct ObjectStorage {
+    client: Option<Arc<S3Client>>,
+}
+
+impl ObjectStorage {
+    pub fn new(config: Option<ObjectStorageConfig>) -> Self {
+        ObjectStorage {
+            client: config.map(|config| {
+                Arc::new(S3Client {
+                    config,
+                    http: reqwest::Client::new(),
+                })
+            }),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.client.is_some()
+    }
+
+    fn client(&self) -> Result<&S3Client> {
+        self.client
+            .as_deref()
+            .context("Object storage is not configured (OBJECT_STORAGE_ENDPOINT / OBJECT_STORAGE_BUCKET)")
+    }
+
+    pub async fn put(&self, key: &str, body: Vec<u8>, content_type: &str) -> Result<()> {
+        let client = self.client()?;
+        let response = client
+            .request(reqwest::Method::PUT, key, &body, Utc::now())
+            .header(reqwest::header::CONTENT_TYPE, content_type)
+            .body(body)
+            .send()
+            .await
+            .with_context(|| format!("Failed to upload {}", key))?;
+        if !response.status().is_success() {
+            anyhow::bail!("Upload of {} failed: {} {}", key, response.status(), response.text().await.unwrap_or_default());
+        }
+        Ok(())
+    }
+
+    /// Otevře objekt ke streamování (tělo se čte po částech přes `Response::chunk`)
+    pub async fn open(&self, key: &str) -> Result<reqwest::Response> {
+        let client = self.client()?;
+        let response = client
+            .request(reqwest::Method::GET, key, &[], Utc::now())
+            .send()
+            .await
+            .with_context(|| format!("Failed to download {}", key))?;
+        if !response.status().is_success() {
+            anyhow::bail!("Download of {} failed: {}", key, response.status());
+        }
+        Ok(response)
+    }
+
+    pub async fn get(&self, key: &str) -> Result<Vec<u8>> {
+        let client = self.client()?;
+        let response = client
+            .request(reqwest::Method::GET, key, &[], Utc::now())
+            .send()
+            .await
+            .with_context(|| format!("Failed to download {}", key))?;
+        if !response.status().is_success() {
+            anyhow::bail!("Download of {} failed: {}", key, response.status());
+        }
+        Ok(response.bytes().await?.to_vec())
+    }
+}
+
+impl S3Client {
+    fn object_url(&self, key: &str) -> (String, String, String) {
+        let endpoint = self.config.endpoint.trim_end_matches('/');
+        let (scheme, host) = endpoint.split_once("://").unwrap_or(("https", endpoint));
+        let key_path = encode_path(&format!("{}{}", self.config.prefix, key));
+        let (host, path) = if self.config.path_style {
+            (host.to_string(), format!("/{}/{}", encode_path(&self.config.bucket), key_path))
+        } else {
+            (format!("{}.{}", self.config.bucket, host), format!("/{}", key_path))
+        };
+        (format!("{}://{}{}", scheme, host, path), host, path)
+    }
+
+    /// Podepsaný request (AWS Signature V4, payload hash v hlavičce)
+    fn request(&self, method: reqwest::Method, key: &str, body: &[u8], now: DateTime<Utc>) -> reqwest::RequestBuilder {
+        let (url, host, path) = self.object_url(key);
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let payload_hash = hex(&Sha256::digest(body));
+        let authorization = sign_v4(
+            &SigningParams {
+                method: method.as_str(),
+                host: &host,
+                path: &path,
+                payload_hash: &payload_hash,
+                amz_date: &amz_date,
+                region: &self.config.region,
+                access_key: &self.config.access_key,
+                secret_key: &self.config.secret_key,
+            },
+        );
+        self.http
+            .request(method, url)
+            .header("x-amz-date", amz_date)
+            .header("x-amz-content-sha256", payload_hash)
+            .header(reqwest::header::AUTHORIZATION, authorization)
+    }
+}
+
+struct SigningParams<'a> {
+    method: &'a str,
+    host: &'a str,
+    path: &'a str,
+    payload_hash: &'a str,
+    amz_date: &'a str,
+    region: &'a str,
+    access_key: &'a str,
+    secret_key: &'a str,
+}
+
+fn sign_v4(params: &SigningParams<'_>) -> String {
+    let date = &params.amz_date[..8];
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+    let canonical_request = format!(
+        "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+        params.method, params.path, params.host, params.payload_hash, params.amz_date, signed_headers, params.payload_hash
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date, params.region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        params.amz_date,
+        scope,
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+    let key = signing_key(params.secret_key, date, params.region, "s3");
+    let signature = hex(&hmac_sha256(&key, string_to_sign.as_bytes()));
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        params.access_key, scope, signed_headers, signature
+    )
+}
+
+fn signing_key(secret_key: &str, date: &str, region: &str, service: &str) -> [u8; 32] {
+    let k_date = hmac_sha256(format!("AWS4{}", secret_key).as_bytes(), date.as_bytes());
+    let k_region = hmac_sha256(&k_date, region.as_bytes());
+    let k_service = hmac_sha256(&k_region, service.as_bytes());
+    hmac_sha256(&k_service, b"aws4_request")
+}
+
+pub(crate) fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut block = [0u8; 64];
+    if key.len() > block.len() {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let inner_pad: Vec<u8> = block.iter().map(|b| b ^ 0x36).collect();
+    let outer_pad: Vec<u8> = block.iter().map(|b| b ^ 0x5c).collect();
+    let inner = Sha256::new().chain_update(&inner_pad).chain_update(data).finalize();
+    let outer = Sha256::new().chain_update(&outer_pad).chain_update(inner).finalize();
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&outer);
+    out
+}
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// URI encoding cesty podle SigV4 (`/` zůstává)
+fn encode_path(path: &str) -> String {
+    path.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn log_archive_key(kind: JobKind, job_id: Uuid) -> String {
+    match kind {
+        JobKind::Copy => format!("logs/copy/{}.ndjson", job_id),
+        JobKind::Deploy => format!("logs/deploy/{}.ndjson", job_id),
+    }
+}
+
+pub(crate) fn release_export_key(release_id: Uuid, export_id: Uuid) -> String {
+    format!("exports/releases/{}/{}.json", release_id, export_id)
+}
+
+/// Načte archivované log řádky (NDJSON `PolledLogLine`)
+pub async fn load_log_archive(storage: &ObjectStorage, key: &str) -> Result<Vec<PolledLogLine>> {
+    let body = storage.get(key).await?;
+    String::from_utf8_lossy(&body)
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).with_context(|| format!("Invalid log archive {}", key)))
+        .collect()
+}
+
+/// Spustí background archiver: logy a diffy hotových jobů a exporty release starší než limit přesune do úložiště
+pub fn spawn_archiver(pool: PgPool, storage: ObjectStorage, config: &ObjectStorageConfig) {
+    if !storage.is_enabled() || config.archive_interval_seconds == 0 {
+        return;
+    }
+
+    let archive_after_hours = config.archive_after_hours;
+    let interval = Duration::from_secs(config.archive_interval_seconds);
+    info!(
+        "Object storage archiver every {}s (jobs older than {}h)",
+        interval.as_secs(),
+        archive_after_hours
+    );
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            for kind in [JobKind::Copy, JobKind::Deploy] {
+                if let Err(e) = archive_job_logs(&pool, &storage, kind, archive_after_hours).await {
+                    warn!(error = %e, "Failed to archive job logs to object storage");
+                }
+            }
+            if let Err(e) = archive_deploy_diffs(&pool, &storage, archive_after_hours).await {
+                warn!(error = %e, "Failed to archive deploy diffs to object storage");
+            }
+            if let Err(e) = archive_release_exports(&pool, &storage, archive_after_hours).await {
+                warn!(error = %e, "Failed to archive release exports to object storage");
+            }
+        }
+    });
+}
+
+async fn archive_job_logs(pool: &PgPool, storage: &ObjectStorage, kind: JobKind, archive_after_hours: u64) -> Result<()> {
+    let (jobs_table, logs_table, job_column, line_column) = match kind {
+        JobKind::Copy => ("copy_jobs", "copy_job_logs", "copy_job_id", "line"),
+        JobKind::Deploy => ("deploy_jobs", "deploy_job_logs", "deploy_job_id", "log_line"),
+    };
+
+    let candidates = sqlx::query_scalar::<_, Uuid>(&format!(
+        "SELECT j.id FROM {jobs_table} j
+         WHERE j.log_archive_key IS NULL
+           AND j.status IN ('success', 'failed', 'cancelled')
+           AND j.completed_at < NOW() - make_interval(hours => $1)
+           AND EXISTS (SELECT 1 FROM {logs_table} l WHERE l.{job_column} = j.id)
+         ORDER BY j.completed_at
+         LIMIT $2"
+    ))
+    .bind(archive_after_hours as i32)
+    .bind(ARCHIVE_BATCH_SIZE)
+    .fetch_all(pool)
+    .await?;
+
+    for job_id in candidates {
+        let lines = sqlx::query_as::<_, PolledLogLine>(&format!(
+            "SELECT seq, {line_column} AS line, created_at FROM {logs_table} WHERE {job_column} = $1 ORDER BY seq"
+        ))
+        .bind(job_id)
+        .fetch_all(pool)
+        .await?;
+
+        let mut body = String::new();
+        for line in &lines {
+            body.push_str(&serde_json::to_string(line)?);
+            body.push('\n');
+        }
+        let key = log_archive_key(kind, job_id);
+        storage.put(&key, body.into_bytes(), "application/x-ndjson").await?;
+
+        // Pointer a smazání řádků v jedné transakci; souběžný archiver jen přepíše stejný objekt
+        let mut tx = pool.begin().await?;
+        let updated = sqlx::query(&format!(
+            "UPDATE {jobs_table} SET log_archive_key = $1 WHERE id = $2 AND log_archive_key IS NULL"
+        ))
+        .bind(&key)
+        .bind(job_id)
+        .execute(&mut *tx)
+        .await?;
+        if updated.rows_affected() == 1 {
+            sqlx::query(&format!("DELETE FROM {logs_table} WHERE {job_column} = $1"))
+                .bind(job_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+    }
+    Ok(())
+}
+
+async fn archive_deploy_diffs(pool: &PgPool, storage: &ObjectStorage, archive_after_hours: u64) -> Result<()> {
+    let candidates = sqlx::query_as::<_, (Uuid, Uuid, String, Option<Vec<u8>>)>(
+        "SELECT d.id, d.deploy_job_id, d.diff_patch, d.diff_patch_gz FROM deploy_job_diffs d
+         JOIN deploy_jobs j ON j.id = d.deploy_job_id
+         WHERE d.diff_patch_key IS NULL
+           AND (d.diff_patch <> '' OR d.diff_patch_gz IS NOT NULL)
+           AND j.completed_at < NOW() - make_interval(hours => $1)
+         ORDER BY d.created_at
+         LIMIT $2",
+    )
+    .bind(archive_after_hours as i32)
+    .bind(ARCHIVE_BATCH_SIZE)
+    .fetch_all(pool)
+    .await?;
+
+    for (diff_id, deploy_job_id, diff_patch, diff_patch_gz) in candidates {
+        // Gzip diffy se nahrávají tak, jak jsou (.patch.gz)
+        let key = match diff_patch_gz {
+            Some(gz) => {
+                let key = format!("artifacts/deploy/{}/{}.patch.gz", deploy_job_id, diff_id);
+                storage.put(&key, gz, "application/gzip").await?;
+                key
+            }
+            None => {
+                let key = format!("artifacts/deploy/{}/{}.patch", deploy_job_id, diff_id);
+                storage.put(&key, diff_patch.into_bytes(), "text/x-diff").await?;
+                key
+            }
+        };
+        sqlx::query(
+            "UPDATE deploy_job_diffs SET diff_patch_key = $1, diff_patch = '', diff_patch_gz = NULL WHERE id = $2",
+        )
+        .bind(&key)
+        .bind(diff_id)
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Exporty release uložené v DB (vzniklé před zapnutím object storage) přesune do úložiště
+async fn archive_release_exports(pool: &PgPool, storage: &ObjectStorage, archive_after_hours: u64) -> Result<()> {
+    let candidates = sqlx::query_as::<_, (Uuid, Uuid, Vec<u8>)>(
+        "SELECT id, release_id, content FROM release_exports
+         WHERE object_key IS NULL AND content IS NOT NULL
+           AND created_at < NOW() - make_interval(hours => $1)
+         ORDER BY created_at
+         LIMIT $2",
+    )
+    .bind(archive_after_hours as i32)
+    .bind(ARCHIVE_BATCH_SIZE)
+    .fetch_all(pool)
+    .await?;
+
+    for (export_id, release_id, content) in candidates {
+        let key = release_export_key(release_id, export_id);
+        storage.put(&key, content, "application/json").await?;
+        sqlx::query("UPDATE release_exports SET object_key = $1, content = NULL WHERE id = $2")
+            .bind(&key)
+            .bind(export_id)
+            .execute(pool)
+            .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hmac_matches_rfc4231() {
+        let mac = hmac_sha256(b"Jefe", b"what do ya want for nothing?");
+        assert_eq!(hex(&mac), "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843");
+    }
+
+    #[test]
+    fn signing_key_matches_aws_example() {
+        let key = signing_key("wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY", "20150830", "us-east-1", "iam");
+        assert_eq!(hex(&key), "c4afb1cc5771d871763a393e44b703571b55cc28424d1a5e86da6ed3c154a4b9");
+    }
+
+    #[test]
+    fn object_url_respects_path_style_and_prefix() {
+        let client = |path_style| S3Client {
+            config: ObjectStorageConfig {
+                endpoint: "http://minio:9000/".to_string(),
+                bucket: "srm".to_string(),
+                prefix: "prod/".to_string(),
+                path_style,
+                ..Default::default()
+            },
+            http: reqwest::Client::new(),
+        };
+        let (url, host, path) = client(true).object_url("logs/copy/a b.ndjson");
+        assert_eq!(url, "http://minio:9000/srm/prod/logs/copy/a%20b.ndjson");
+        assert_eq!(host, "minio:9000");
+        assert_eq!(path, "/srm/prod/logs/copy/a%20b.ndjson");
+
+        let (url, host, _) = client(false).object_url("x.patch");
+        assert_eq!(url, "http://srm.minio:9000/prod/x.patch");
+        assert_eq!(host, "srm.minio:9000");
+    }
+}
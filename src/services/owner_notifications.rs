@@ -0,0 +1,142 @@
+use sqlx::{FromRow, PgPool};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::services::events::{CopyImagesFailedEventData, EventEnvelope, FailedImageEventData, COPY_JOB_IMAGES_FAILED};
+use crate::services::notifications::Notifier;
+
+#[derive(Debug, FromRow)]
+struct FailedImageRow {
+    owner: Option<String>,
+    app_name: String,
+    container_name: Option<String>,
+    source_image: String,
+    target_image: String,
+    error_message: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+struct CopyJobRow {
+    tenant_id: Uuid,
+    bundle_id: Uuid,
+    bundle_version: i32,
+    target_tag: String,
+    environment_id: Option<Uuid>,
+}
+
+/// Seskupí selhané image podle vlastníka (pořadí vlastníků zachová, bez vlastníka na konci)
+fn group_by_owner(rows: Vec<FailedImageRow>) -> Vec<(Option<String>, Vec<FailedImageEventData>)> {
+    let mut groups: Vec<(Option<String>, Vec<FailedImageEventData>)> = Vec::new();
+    for row in rows {
+        let image = FailedImageEventData {
+            app_name: row.app_name,
+            container_name: row.container_name,
+            source_image: row.source_image,
+            target_image: row.target_image,
+            error_message: row.error_message,
+        };
+        match groups.iter_mut().find(|(owner, _)| *owner == row.owner) {
+            Some((_, images)) => images.push(image),
+            None => groups.push((row.owner, vec![image])),
+        }
+    }
+    groups.sort_by_key(|(owner, _)| owner.is_none());
+    groups
+}
+
+/// Po dokončení copy jobu pošle každému vlastníkovi jeden event se selhanými image jeho aplikací
+pub async fn notify_failed_images(pool: &PgPool, notifier: &Notifier, job_id: Uuid) -> Result<usize, sqlx::Error> {
+    let job = sqlx::query_as::<_, CopyJobRow>(
+        r#"
+        SELECT b.tenant_id, bv.bundle_id, bv.version AS bundle_version, cj.target_tag, cj.environment_id
+        FROM copy_jobs cj
+        JOIN bundle_versions bv ON bv.id = cj.bundle_version_id
+        JOIN bundles b ON b.id = bv.bundle_id
+        WHERE cj.id = $1
+        "#,
+    )
+    .bind(job_id)
+    .fetch_one(pool)
+    .await?;
+
+    let rows = sqlx::query_as::<_, FailedImageRow>(
+        r#"
+        SELECT im.owner, im.app_name, im.container_name,
+               cji.source_image || ':' || cji.source_tag AS source_image,
+               cji.target_image || ':' || cji.target_tag AS target_image,
+               cji.error_message
+        FROM copy_job_images cji
+        JOIN image_mappings im ON im.id = cji.image_mapping_id
+        WHERE cji.copy_job_id = $1 AND cji.copy_status = 'failed'
+        ORDER BY im.owner, im.app_name, im.container_name
+        "#,
+    )
+    .bind(job_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut sent = 0;
+    for (owner, images) in group_by_owner(rows) {
+        info!(
+            job_id = %job_id,
+            owner = owner.as_deref().unwrap_or("-"),
+            failed_images = images.len(),
+            "Notifying owner about failed images"
+        );
+        let envelope = EventEnvelope::new(
+            &COPY_JOB_IMAGES_FAILED,
+            Some(job.tenant_id),
+            CopyImagesFailedEventData {
+                job_id,
+                bundle_id: job.bundle_id,
+                bundle_version: job.bundle_version,
+                target_tag: job.target_tag.clone(),
+                environment_id: job.environment_id,
+                owner: owner.clone(),
+                images,
+            },
+        );
+        if notifier.send_to_owner(owner.as_deref(), &envelope).await.is_ok() {
+            sent += 1;
+        }
+    }
+    Ok(sent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(owner: Option<&str>, app: &str) -> FailedImageRow {
+        FailedImageRow {
+            owner: owner.map(str::to_string),
+            app_name: app.to_string(),
+            container_name: None,
+            source_image: format!("src/{}:1", app),
+            target_image: format!("dst/{}:1", app),
+            error_message: Some("manifest unknown".to_string()),
+        }
+    }
+
+    #[test]
+    fn failed_images_are_grouped_per_owner() {
+        let groups = group_by_owner(vec![
+            row(None, "legacy"),
+            row(Some("team-a"), "api"),
+            row(Some("team-b"), "web"),
+            row(Some("team-a"), "worker"),
+        ]);
+        let summary: Vec<(Option<&str>, Vec<&str>)> = groups
+            .iter()
+            .map(|(owner, images)| (owner.as_deref(), images.iter().map(|i| i.app_name.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Some("team-a"), vec!["api", "worker"]),
+                (Some("team-b"), vec!["web"]),
+                (None, vec!["legacy"]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use base64::{engine::general_purpose, Engine};
+use serde_json::{json, Value};
+
+use crate::services::docker_config;
+
+pub const FORMATS: [&str; 2] = ["secret", "external-secret"];
+pub const STORE_KINDS: [&str; 2] = ["SecretStore", "ClusterSecretStore"];
+const EXTERNAL_SECRETS_API_VERSION: &str = "external-secrets.io/v1";
+/// Docker Hub má v config.json historický klíč
+const DOCKER_HUB_AUTH_KEY: &str = "https://index.docker.io/v1/";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PullSecretFormat {
+    /// `kubernetes.io/dockerconfigjson` Secret s credentials
+    Secret,
+    /// ExternalSecret, který Secret stejného typu vytvoří z externího secret store (bez credentials)
+    ExternalSecret,
+}
+
+impl PullSecretFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "secret" => Some(Self::Secret),
+            "external-secret" | "externalsecret" => Some(Self::ExternalSecret),
+            _ => None,
+        }
+    }
+}
+
+/// Odkaz do externího secret store (External Secrets Operator)
+#[derive(Debug, Clone)]
+pub struct ExternalSecretRef {
+    pub store_name: String,
+    pub store_kind: String,
+    pub remote_key: String,
+    pub remote_property: Option<String>,
+    pub refresh_interval: String,
+}
+
+/// Název / namespace objektu podle DNS-1123 (malá písmena, číslice, `-`, `.`)
+pub fn validate_name(kind: &str, value: &str) -> Result<(), String> {
+    let valid = !value.is_empty()
+        && value.len() <= 253
+        && value
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '.')
+        && value.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && value.ends_with(|c: char| c.is_ascii_alphanumeric());
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid {} '{}': use lowercase letters, digits, '-' and '.', starting and ending with a letter or digit",
+            kind, value
+        ))
+    }
+}
+
+/// Klíč v `auths` pro `base_url` registry
+pub fn auth_key(base_url: &str) -> String {
+    let host = docker_config::normalize_host(base_url);
+    if host == "docker.io" {
+        DOCKER_HUB_AUTH_KEY.to_string()
+    } else {
+        host
+    }
+}
+
+/// Obsah `.dockerconfigjson` pro jednu registry
+pub fn docker_config_json(base_url: &str, username: &str, password: &str) -> String {
+    let auth = general_purpose::STANDARD.encode(format!("{}:{}", username, password));
+    json!({
+        "auths": {
+            auth_key(base_url): {
+                "username": username,
+                "password": password,
+                "auth": auth,
+            }
+        }
+    })
+    .to_string()
+}
+
+fn metadata(name: &str, namespace: Option<&str>) -> Value {
+    let mut metadata = json!({
+        "name": name,
+        "labels": { "app.kubernetes.io/managed-by": "simple-release-management" },
+    });
+    if let Some(namespace) = namespace {
+        metadata["namespace"] = json!(namespace);
+    }
+    metadata
+}
+
+/// Secret typu `kubernetes.io/dockerconfigjson`
+pub fn secret_manifest(name: &str, namespace: Option<&str>, docker_config_json: &str) -> Value {
+    json!({
+        "apiVersion": "v1",
+        "kind": "Secret",
+        "metadata": metadata(name, namespace),
+        "type": "kubernetes.io/dockerconfigjson",
+        "data": {
+            ".dockerconfigjson": general_purpose::STANDARD.encode(docker_config_json),
+        },
+    })
+}
+
+/// ExternalSecret, jehož cílový Secret má typ `kubernetes.io/dockerconfigjson`
+pub fn external_secret_manifest(name: &str, namespace: Option<&str>, reference: &ExternalSecretRef) -> Value {
+    let mut remote_ref = json!({ "key": reference.remote_key });
+    if let Some(property) = &reference.remote_property {
+        remote_ref["property"] = json!(property);
+    }
+    json!({
+        "apiVersion": EXTERNAL_SECRETS_API_VERSION,
+        "kind": "ExternalSecret",
+        "metadata": metadata(name, namespace),
+        "spec": {
+            "refreshInterval": reference.refresh_interval,
+            "secretStoreRef": {
+                "name": reference.store_name,
+                "kind": reference.store_kind,
+            },
+            "target": {
+                "name": name,
+                "template": { "type": "kubernetes.io/dockerconfigjson" },
+            },
+            "data": [{
+                "secretKey": ".dockerconfigjson",
+                "remoteRef": remote_ref,
+            }],
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secret_contains_docker_config_for_registry_host() {
+        let config = docker_config_json("https://Registry.example.com/team", "robot", "s3cret");
+        let parsed: Value = serde_json::from_str(&config).unwrap();
+        let entry = &parsed["auths"]["registry.example.com"];
+        assert_eq!(entry["auth"], general_purpose::STANDARD.encode("robot:s3cret"));
+        assert_eq!(auth_key("https://registry-1.docker.io"), DOCKER_HUB_AUTH_KEY);
+
+        let manifest = secret_manifest("pull", Some("apps"), &config);
+        assert_eq!(manifest["type"], "kubernetes.io/dockerconfigjson");
+        assert_eq!(manifest["metadata"]["namespace"], "apps");
+        let data = manifest["data"][".dockerconfigjson"].as_str().unwrap();
+        assert_eq!(general_purpose::STANDARD.decode(data).unwrap(), config.as_bytes());
+    }
+
+    #[test]
+    fn external_secret_references_store_without_credentials() {
+        let reference = ExternalSecretRef {
+            store_name: "vault".to_string(),
+            store_kind: "ClusterSecretStore".to_string(),
+            remote_key: "registry/prod".to_string(),
+            remote_property: Some("dockerconfigjson".to_string()),
+            refresh_interval: "1h".to_string(),
+        };
+        let manifest = external_secret_manifest("pull", None, &reference);
+        assert!(manifest["metadata"].get("namespace").is_none());
+        assert_eq!(manifest["spec"]["secretStoreRef"]["kind"], "ClusterSecretStore");
+        assert_eq!(manifest["spec"]["data"][0]["remoteRef"]["property"], "dockerconfigjson");
+        assert_eq!(manifest["spec"]["target"]["template"]["type"], "kubernetes.io/dockerconfigjson");
+    }
+
+    #[test]
+    fn names_follow_dns_rules() {
+        assert!(validate_name("name", "prod-pull-secret").is_ok());
+        assert!(validate_name("name", "Prod").is_err());
+        assert!(validate_name("name", "-prod").is_err());
+        assert!(validate_name("namespace", "").is_err());
+        assert_eq!(PullSecretFormat::parse("External-Secret"), Some(PullSecretFormat::ExternalSecret));
+        assert_eq!(PullSecretFormat::parse("sealed"), None);
+    }
+}
@@ -0,0 +1,284 @@
+use anyhow::Result;
+use serde::Deserialize;
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+use tokio::sync::{broadcast, RwLock};
+use tracing::{info, warn};
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+use crate::services::job_logs::{is_finished_status, JobKind};
+
+/// Prefix pracovních adresářů deploy jobů (`srm-deploy-{job_id}-XXXX` v temp dir)
+pub const DEPLOY_TEMP_PREFIX: &str = "srm-deploy-";
+
+pub type JobLogChannels = Arc<RwLock<HashMap<Uuid, broadcast::Sender<String>>>>;
+
+/// Nastavení reaperu (`REAPER_*`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReaperConfig {
+    /// 0 = reaper vypnutý
+    pub interval_seconds: u64,
+    /// Stáří temp adresáře, po kterém se smaže (pokud jeho job neběží)
+    pub temp_max_age_hours: u64,
+}
+
+impl Default for ReaperConfig {
+    fn default() -> Self {
+        ReaperConfig {
+            interval_seconds: 300,
+            temp_max_age_hours: 6,
+        }
+    }
+}
+
+/// Lokální stav jobů, který po pádu jobu nikdo neuklidí
+#[derive(Clone)]
+pub struct ReaperTargets {
+    pub copy_logs: JobLogChannels,
+    pub copy_cancel_flags: Arc<RwLock<HashSet<Uuid>>>,
+    pub deploy_logs: JobLogChannels,
+}
+
+/// Čítače reaperu pro `GET /metrics`
+#[derive(Debug, Clone, Default)]
+pub struct ReaperMetrics {
+    runs: Arc<AtomicU64>,
+    temp_dirs_removed: Arc<AtomicU64>,
+    bytes_reclaimed: Arc<AtomicU64>,
+    channels_dropped: Arc<AtomicU64>,
+    cancel_flags_dropped: Arc<AtomicU64>,
+}
+
+impl ReaperMetrics {
+    /// Prometheus text format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for (name, help, value) in [
+            ("srm_reaper_runs_total", "Completed reaper runs", &self.runs),
+            (
+                "srm_reaper_temp_dirs_removed_total",
+                "Orphaned job temp directories removed",
+                &self.temp_dirs_removed,
+            ),
+            (
+                "srm_reaper_reclaimed_bytes_total",
+                "Disk space reclaimed from orphaned temp directories",
+                &self.bytes_reclaimed,
+            ),
+            (
+                "srm_reaper_log_channels_dropped_total",
+                "Log channels dropped for finished or orphaned jobs",
+                &self.channels_dropped,
+            ),
+            (
+                "srm_reaper_cancel_flags_dropped_total",
+                "Stale copy job cancel flags dropped",
+                &self.cancel_flags_dropped,
+            ),
+        ] {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} counter");
+            let _ = writeln!(out, "{name} {}", value.load(Ordering::Relaxed));
+        }
+        out
+    }
+}
+
+/// Spustí periodický úklid (temp adresáře, log kanály, cancel flagy)
+pub fn spawn_reaper(pool: PgPool, targets: ReaperTargets, config: &ReaperConfig, metrics: ReaperMetrics) {
+    if config.interval_seconds == 0 {
+        return;
+    }
+
+    let interval = Duration::from_secs(config.interval_seconds);
+    let temp_max_age = Duration::from_secs(config.temp_max_age_hours * 3600);
+    info!(
+        "Resource reaper every {}s (temp dirs older than {}h)",
+        interval.as_secs(),
+        config.temp_max_age_hours
+    );
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            reap_temp_dirs(&targets, temp_max_age, &metrics).await;
+            if let Err(e) = reap_job_state(&pool, &targets, &metrics).await {
+                warn!(error = %e, "Failed to reap job log channels");
+            }
+            metrics.runs.fetch_add(1, Ordering::Relaxed);
+        }
+    });
+}
+
+async fn reap_temp_dirs(targets: &ReaperTargets, max_age: Duration, metrics: &ReaperMetrics) {
+    let running: HashSet<Uuid> = targets.deploy_logs.read().await.keys().copied().collect();
+    let temp_root = std::env::temp_dir();
+    let removed = tokio::task::spawn_blocking(move || remove_stale_dirs(&temp_root, max_age, &running, SystemTime::now()))
+        .await
+        .unwrap_or_default();
+
+    for (path, bytes) in &removed {
+        info!("Reaper removed orphaned temp dir {} ({} bytes)", path.display(), bytes);
+    }
+    metrics.temp_dirs_removed.fetch_add(removed.len() as u64, Ordering::Relaxed);
+    metrics
+        .bytes_reclaimed
+        .fetch_add(removed.iter().map(|(_, bytes)| bytes).sum(), Ordering::Relaxed);
+}
+
+/// Smaže `srm-deploy-*` adresáře starší než `max_age`, kromě běžících jobů
+fn remove_stale_dirs(root: &Path, max_age: Duration, running: &HashSet<Uuid>, now: SystemTime) -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return Vec::new();
+    };
+
+    let mut removed = Vec::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let Some(rest) = name.strip_prefix(DEPLOY_TEMP_PREFIX) else {
+            continue;
+        };
+        if rest.get(..36).and_then(|id| Uuid::parse_str(id).ok()).is_some_and(|id| running.contains(&id)) {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let age = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .unwrap_or_default();
+        if !metadata.is_dir() || age < max_age {
+            continue;
+        }
+
+        let path = entry.path();
+        let bytes = dir_size(&path);
+        match std::fs::remove_dir_all(&path) {
+            Ok(()) => removed.push((path, bytes)),
+            Err(e) => warn!("Reaper failed to remove {}: {}", path.display(), e),
+        }
+    }
+    removed
+}
+
+fn dir_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+async fn reap_job_state(pool: &PgPool, targets: &ReaperTargets, metrics: &ReaperMetrics) -> Result<()> {
+    let dropped_copy = reap_channels(pool, JobKind::Copy, &targets.copy_logs).await?;
+    let dropped_deploy = reap_channels(pool, JobKind::Deploy, &targets.deploy_logs).await?;
+    metrics
+        .channels_dropped
+        .fetch_add((dropped_copy.len() + dropped_deploy.len()) as u64, Ordering::Relaxed);
+
+    // Cancel flag má smysl jen pro job, který ještě může běžet
+    let flags: Vec<Uuid> = targets.copy_cancel_flags.read().await.iter().copied().collect();
+    if !flags.is_empty() {
+        let statuses = job_statuses(pool, JobKind::Copy, &flags).await?;
+        let stale: Vec<Uuid> = flags
+            .into_iter()
+            .filter(|id| dropped_copy.contains(id) || statuses.get(id).is_none_or(|status| is_finished_status(status)))
+            .collect();
+        if !stale.is_empty() {
+            let mut cancel_flags = targets.copy_cancel_flags.write().await;
+            for id in &stale {
+                cancel_flags.remove(id);
+            }
+            metrics.cancel_flags_dropped.fetch_add(stale.len() as u64, Ordering::Relaxed);
+        }
+    }
+    Ok(())
+}
+
+/// Zahodí kanály dokončených jobů a jobů, jejichž task zanikl (panic) - v mapě zůstal jediný sender
+async fn reap_channels(pool: &PgPool, kind: JobKind, channels: &JobLogChannels) -> Result<Vec<Uuid>> {
+    let ids: Vec<Uuid> = channels.read().await.keys().copied().collect();
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let statuses = job_statuses(pool, kind, &ids).await?;
+
+    let mut channels = channels.write().await;
+    let stale: Vec<Uuid> = channels
+        .iter()
+        .filter(|(id, sender)| match statuses.get(id) {
+            None => true,
+            Some(status) if is_finished_status(status) => true,
+            Some(status) => status != "pending" && sender.strong_count() == 1,
+        })
+        .map(|(id, _)| *id)
+        .collect();
+    for id in &stale {
+        channels.remove(id);
+        info!(job_id = %id, "Reaper dropped log channel of {:?} job", kind);
+    }
+    Ok(stale)
+}
+
+async fn job_statuses(pool: &PgPool, kind: JobKind, ids: &[Uuid]) -> Result<HashMap<Uuid, String>, sqlx::Error> {
+    let table = match kind {
+        JobKind::Copy => "copy_jobs",
+        JobKind::Deploy => "deploy_jobs",
+    };
+    let rows = sqlx::query_as::<_, (Uuid, String)>(&format!("SELECT id, status FROM {table} WHERE id = ANY($1)"))
+        .bind(ids)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removes_only_stale_unowned_deploy_dirs() {
+        let root = tempfile::tempdir().unwrap();
+        let running_id = Uuid::new_v4();
+        let stale = root.path().join(format!("{}{}-abc", DEPLOY_TEMP_PREFIX, Uuid::new_v4()));
+        let running = root.path().join(format!("{}{}-def", DEPLOY_TEMP_PREFIX, running_id));
+        let foreign = root.path().join("other-dir");
+        for dir in [&stale, &running, &foreign] {
+            std::fs::create_dir_all(dir.join("deploy")).unwrap();
+            std::fs::write(dir.join("deploy/file.yaml"), b"0123456789").unwrap();
+        }
+
+        let later = SystemTime::now() + Duration::from_secs(7200);
+        let running_ids = HashSet::from([running_id]);
+
+        // Příliš čerstvé - nic se nemaže
+        assert!(remove_stale_dirs(root.path(), Duration::from_secs(3600), &running_ids, SystemTime::now()).is_empty());
+
+        let removed = remove_stale_dirs(root.path(), Duration::from_secs(3600), &running_ids, later);
+        assert_eq!(removed, vec![(stale.clone(), 10)]);
+        assert!(!stale.exists());
+        assert!(running.exists());
+        assert!(foreign.exists());
+    }
+
+    #[test]
+    fn metrics_render_prometheus_counters() {
+        let metrics = ReaperMetrics::default();
+        metrics.bytes_reclaimed.fetch_add(2048, Ordering::Relaxed);
+        let text = metrics.render();
+        assert!(text.contains("# TYPE srm_reaper_reclaimed_bytes_total counter"));
+        assert!(text.contains("srm_reaper_reclaimed_bytes_total 2048"));
+        assert!(text.contains("srm_reaper_runs_total 0"));
+    }
+}
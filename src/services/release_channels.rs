@@ -0,0 +1,72 @@
+/// Kanály release; environment odebírá jejich podmnožinu
+pub const CHANNELS: [&str; 3] = ["rc", "stable", "hotfix"];
+/// Ručně vytvořený release bez kanálu
+pub const DEFAULT_CHANNEL: &str = "stable";
+/// Auto release z copy jobu je kandidát, ne hotová verze
+pub const AUTO_RELEASE_CHANNEL: &str = "rc";
+
+/// Normalizuje název kanálu (`RC` -> `rc`); neznámý kanál = chyba
+pub fn parse(value: &str) -> Result<&'static str, String> {
+    let value = value.trim().to_ascii_lowercase();
+    CHANNELS
+        .iter()
+        .find(|channel| **channel == value)
+        .copied()
+        .ok_or_else(|| format!("Unknown release channel '{}' (allowed: {})", value, CHANNELS.join(", ")))
+}
+
+/// Volitelný kanál z requestu; prázdná hodnota = nenastaveno
+pub fn parse_optional(value: Option<&str>) -> Result<Option<&'static str>, String> {
+    match value.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(value) => parse(value).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Odběr kanálů environmentu: normalizovaný, bez duplicit, v pořadí `CHANNELS`
+pub fn normalize_subscriptions(values: &[String]) -> Result<Vec<String>, String> {
+    let mut parsed = Vec::new();
+    for value in values.iter().filter(|v| !v.trim().is_empty()) {
+        parsed.push(parse(value)?);
+    }
+    if parsed.is_empty() {
+        return Err(format!(
+            "Environment must subscribe to at least one release channel ({})",
+            CHANNELS.join(", ")
+        ));
+    }
+    Ok(CHANNELS
+        .iter()
+        .filter(|channel| parsed.contains(channel))
+        .map(|channel| channel.to_string())
+        .collect())
+}
+
+/// Smí environment s danými odběry nasadit release z kanálu `channel`
+pub fn is_subscribed(subscriptions: &[String], channel: &str) -> bool {
+    subscriptions.iter().any(|s| s == channel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channels_are_parsed_case_insensitively() {
+        assert_eq!(parse(" RC ").unwrap(), "rc");
+        assert!(parse("beta").unwrap_err().contains("Unknown release channel"));
+        assert_eq!(parse_optional(Some("  ")).unwrap(), None);
+        assert_eq!(parse_optional(Some("Hotfix")).unwrap(), Some("hotfix"));
+    }
+
+    #[test]
+    fn subscriptions_are_normalized() {
+        let values = vec!["hotfix".to_string(), "Stable".to_string(), "stable".to_string()];
+        let subscriptions = normalize_subscriptions(&values).unwrap();
+        assert_eq!(subscriptions, vec!["stable", "hotfix"]);
+        assert!(is_subscribed(&subscriptions, "hotfix"));
+        assert!(!is_subscribed(&subscriptions, "rc"));
+        assert!(normalize_subscriptions(&[" ".to_string()]).is_err());
+        assert!(normalize_subscriptions(&["nightly".to_string()]).is_err());
+    }
+}
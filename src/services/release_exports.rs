@@ -0,0 +1,137 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::db::models::Release;
+use crate::services::object_storage::{self, ObjectStorage};
+use crate::services::release_manifest::{load_release_manifest, ReleaseManifest};
+use crate::services::release_notes::{self, ReleaseNotes};
+
+/// Verze formátu exportu (pole `export_version`)
+pub const RELEASE_EXPORT_VERSION: u32 = 1;
+
+/// Záznam exportu bez obsahu; `object_key` = obsah leží v object storage
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ReleaseExport {
+    pub id: Uuid,
+    pub release_id: Uuid,
+    pub size_bytes: i64,
+    pub sha256: String,
+    pub object_key: Option<String>,
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Obsah exportu - z DB, nebo pointer do object storage
+pub enum ExportContent {
+    Inline(Vec<u8>),
+    Stored(String),
+}
+
+#[derive(Serialize)]
+struct ReleaseExportDocument<'a> {
+    export_version: u32,
+    exported_at: DateTime<Utc>,
+    release: &'a Release,
+    manifest: &'a ReleaseManifest,
+    notes: Option<&'a ReleaseNotes>,
+}
+
+const EXPORT_COLUMNS: &str = "id, release_id, size_bytes, sha256, object_key, created_by, created_at";
+
+/// Sestaví export release (release + manifest + release notes) a uloží ho -
+/// s object storage jen jako pointer, jinak do DB. `Ok(None)` = release neexistuje.
+pub async fn create_export(
+    pool: &PgPool,
+    storage: &ObjectStorage,
+    release_db_id: Uuid,
+    created_by: Option<&str>,
+) -> Result<Option<ReleaseExport>> {
+    let Some(release) = sqlx::query_as::<_, Release>("SELECT * FROM releases WHERE id = $1")
+        .bind(release_db_id)
+        .fetch_optional(pool)
+        .await?
+    else {
+        return Ok(None);
+    };
+    let manifest = load_release_manifest(pool, release_db_id).await?;
+    let notes = release_notes::build_release_notes(pool, release_db_id, None).await?;
+    let body = serde_json::to_vec_pretty(&ReleaseExportDocument {
+        export_version: RELEASE_EXPORT_VERSION,
+        exported_at: Utc::now(),
+        release: &release,
+        manifest: &manifest,
+        notes: notes.as_ref(),
+    })?;
+
+    let export_id = Uuid::new_v4();
+    let sha256 = object_storage::hex(&Sha256::digest(&body));
+    let size_bytes = body.len() as i64;
+    let (content, object_key) = if storage.is_enabled() {
+        let key = object_storage::release_export_key(release_db_id, export_id);
+        storage.put(&key, body, "application/json").await?;
+        (None, Some(key))
+    } else {
+        (Some(body), None)
+    };
+
+    let export = sqlx::query_as::<_, ReleaseExport>(&format!(
+        "INSERT INTO release_exports (id, release_id, size_bytes, sha256, content, object_key, created_by)
+         VALUES ($1, $2, $3, $4, $5, $6, $7)
+         RETURNING {EXPORT_COLUMNS}"
+    ))
+    .bind(export_id)
+    .bind(release_db_id)
+    .bind(size_bytes)
+    .bind(&sha256)
+    .bind(content)
+    .bind(object_key)
+    .bind(created_by)
+    .fetch_one(pool)
+    .await?;
+    Ok(Some(export))
+}
+
+/// Exporty release od nejnovějšího
+pub async fn list_exports(pool: &PgPool, release_db_id: Uuid) -> Result<Vec<ReleaseExport>, sqlx::Error> {
+    sqlx::query_as::<_, ReleaseExport>(&format!(
+        "SELECT {EXPORT_COLUMNS} FROM release_exports WHERE release_id = $1 ORDER BY created_at DESC"
+    ))
+    .bind(release_db_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Export ke stažení; `None` = export k release neexistuje
+pub async fn load_export(
+    pool: &PgPool,
+    release_db_id: Uuid,
+    export_id: Uuid,
+) -> Result<Option<(ReleaseExport, ExportContent)>, sqlx::Error> {
+    #[derive(FromRow)]
+    struct Row {
+        #[sqlx(flatten)]
+        export: ReleaseExport,
+        content: Option<Vec<u8>>,
+    }
+
+    let row = sqlx::query_as::<_, Row>(&format!(
+        "SELECT {EXPORT_COLUMNS}, content FROM release_exports WHERE id = $1 AND release_id = $2"
+    ))
+    .bind(export_id)
+    .bind(release_db_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| {
+        // Pointer má přednost - archiver po nahrání obsah v DB maže
+        let content = match row.export.object_key.clone() {
+            Some(key) => ExportContent::Stored(key),
+            None => ExportContent::Inline(row.content.unwrap_or_default()),
+        };
+        (row.export, content)
+    }))
+}
@@ -1,19 +1,28 @@
 use anyhow::Result;
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use std::collections::HashSet;
 use uuid::Uuid;
 
-#[derive(Debug, Serialize)]
+/// Aktuální verze schématu release manifestu. Zvýšit při každé nekompatibilní změně
+/// a doplnit krok do `migrate_manifest_value`.
+pub const RELEASE_MANIFEST_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ReleaseManifest {
+    /// Manifesty bez verze (před zavedením schématu) jsou verze 0
+    #[serde(default)]
+    pub schema_version: u32,
     pub release_id: String,
     pub created_at: DateTime<Utc>,
     pub registry_base: Option<String>,
     pub images: Vec<ReleaseManifestImage>,
+    #[serde(default)]
     pub extra_tags: Vec<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ReleaseManifestImage {
     pub app_name: String,
     pub container_name: Option<String>,
@@ -40,6 +49,63 @@ struct ManifestImageRow {
     container_name: Option<String>,
 }
 
+/// Manifest ze snapshotu uloženého na release; starší releases bez snapshotu se dopočítají a uloží
+pub async fn load_release_manifest(pool: &PgPool, release_db_id: Uuid) -> Result<ReleaseManifest> {
+    let snapshot = sqlx::query_scalar::<_, Option<serde_json::Value>>(
+        "SELECT manifest_snapshot FROM releases WHERE id = $1",
+    )
+    .bind(release_db_id)
+    .fetch_one(pool)
+    .await?;
+
+    if let Some(value) = snapshot {
+        let stored_version = schema_version_of(&value);
+        match migrate_manifest(value) {
+            Ok(manifest) => {
+                if stored_version != Some(RELEASE_MANIFEST_SCHEMA_VERSION) {
+                    store_migrated_snapshot(pool, release_db_id, &manifest).await?;
+                }
+                return Ok(manifest);
+            }
+            Err(err) => {
+                tracing::warn!("Release {} manifest snapshot is unreadable, rebuilding: {}", release_db_id, err);
+            }
+        }
+    }
+
+    refresh_release_manifest(pool, release_db_id).await
+}
+
+/// Snapshot převedený na aktuální schéma přepíše původní, `manifest_snapshot_at` zůstává
+async fn store_migrated_snapshot(pool: &PgPool, release_db_id: Uuid, manifest: &ReleaseManifest) -> Result<()> {
+    sqlx::query("UPDATE releases SET manifest_snapshot = $1 WHERE id = $2")
+        .bind(serde_json::to_value(manifest)?)
+        .bind(release_db_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Znovu sestaví manifest z copy jobu a uloží ho jako snapshot release
+pub async fn refresh_release_manifest(pool: &PgPool, release_db_id: Uuid) -> Result<ReleaseManifest> {
+    let manifest = build_release_manifest(pool, release_db_id).await?;
+    sqlx::query(
+        "UPDATE releases SET manifest_snapshot = $1, manifest_snapshot_at = NOW() WHERE id = $2",
+    )
+    .bind(serde_json::to_value(&manifest)?)
+    .bind(release_db_id)
+    .execute(pool)
+    .await?;
+    Ok(manifest)
+}
+
+/// Uloží snapshot manifestu nového release; při chybě se dopočítá později při čtení
+pub async fn store_manifest_snapshot(pool: &PgPool, release_db_id: Uuid) {
+    if let Err(err) = refresh_release_manifest(pool, release_db_id).await {
+        tracing::warn!("Failed to store manifest snapshot for release {}: {}", release_db_id, err);
+    }
+}
+
 pub async fn build_release_manifest(pool: &PgPool, release_db_id: Uuid) -> Result<ReleaseManifest> {
     let base = sqlx::query_as::<_, ReleaseBaseRow>(
         r#"
@@ -96,6 +162,7 @@ pub async fn build_release_manifest(pool: &PgPool, release_db_id: Uuid) -> Resul
         .collect();
 
     Ok(ReleaseManifest {
+        schema_version: RELEASE_MANIFEST_SCHEMA_VERSION,
         release_id: base.release_id,
         created_at: base.created_at,
         registry_base,
@@ -111,3 +178,192 @@ fn normalize_registry_base(base_url: &str) -> String {
         .trim_start_matches("http://");
     without_scheme.trim_end_matches('/').to_string()
 }
+
+/// Verze schématu uložená v manifestu (`None` = bez verze, tj. verze 0)
+pub fn schema_version_of(value: &serde_json::Value) -> Option<u32> {
+    value
+        .get("schema_version")
+        .and_then(serde_json::Value::as_u64)
+        .and_then(|v| u32::try_from(v).ok())
+}
+
+/// Převede manifest libovolné podporované verze na aktuální schéma
+pub fn migrate_manifest(value: serde_json::Value) -> Result<ReleaseManifest> {
+    let value = migrate_manifest_value(value)?;
+    Ok(serde_json::from_value(value)?)
+}
+
+fn migrate_manifest_value(mut value: serde_json::Value) -> Result<serde_json::Value> {
+    let object = value
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("Release manifest must be a mapping"))?;
+    let version = match object.get("schema_version") {
+        None | Some(serde_json::Value::Null) => 0,
+        Some(raw) => raw
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| anyhow::anyhow!("Invalid schema_version: {}", raw))?,
+    };
+    if version > RELEASE_MANIFEST_SCHEMA_VERSION {
+        anyhow::bail!(
+            "Release manifest schema_version {} is newer than supported {}",
+            version,
+            RELEASE_MANIFEST_SCHEMA_VERSION
+        );
+    }
+
+    // 0 -> 1: verze se začala uvádět, `extra_tags` a `registry_base` jsou vždy přítomné
+    if version < 1 {
+        object
+            .entry("extra_tags")
+            .or_insert_with(|| serde_json::Value::Array(Vec::new()));
+        object.entry("registry_base").or_insert(serde_json::Value::Null);
+    }
+
+    object.insert("schema_version".to_string(), RELEASE_MANIFEST_SCHEMA_VERSION.into());
+    Ok(value)
+}
+
+/// Kontroly kontraktu, na který se spoléhají externí konzumenti (kube_build_app, Ansible)
+pub fn validate_manifest(manifest: &ReleaseManifest) -> Vec<String> {
+    let mut errors = Vec::new();
+    if manifest.schema_version != RELEASE_MANIFEST_SCHEMA_VERSION {
+        errors.push(format!(
+            "schema_version must be {}, got {}",
+            RELEASE_MANIFEST_SCHEMA_VERSION, manifest.schema_version
+        ));
+    }
+    if manifest.release_id.trim().is_empty() {
+        errors.push("release_id must not be empty".to_string());
+    }
+
+    let mut seen = HashSet::new();
+    for (index, image) in manifest.images.iter().enumerate() {
+        let at = format!("images[{}]", index);
+        if image.app_name.trim().is_empty() {
+            errors.push(format!("{}: app_name must not be empty", at));
+        }
+        if image.image.trim().is_empty() {
+            errors.push(format!("{}: image must not be empty", at));
+        } else if image.image.contains('@') || image_has_tag(&image.image) {
+            errors.push(format!("{}: image must not contain a tag or digest ({})", at, image.image));
+        }
+        if image.tag.trim().is_empty() {
+            errors.push(format!("{}: tag must not be empty", at));
+        }
+        if let Some(digest) = image.digest.as_deref()
+            && !is_valid_digest(digest)
+        {
+            errors.push(format!("{}: digest must be sha256:<64 hex chars> ({})", at, digest));
+        }
+        let key = (image.app_name.as_str(), image.container_name.as_deref().unwrap_or(""));
+        if !seen.insert(key) {
+            errors.push(format!("{}: duplicate app/container {}:{}", at, key.0, key.1));
+        }
+    }
+
+    if manifest.extra_tags.iter().any(|tag| tag.trim().is_empty()) {
+        errors.push("extra_tags must not contain empty tags".to_string());
+    }
+    errors
+}
+
+// Dvojtečka za posledním `/` je tag, dřívější může být port registry
+fn image_has_tag(image: &str) -> bool {
+    image.rsplit('/').next().is_some_and(|name| name.contains(':'))
+}
+
+fn is_valid_digest(digest: &str) -> bool {
+    digest
+        .strip_prefix("sha256:")
+        .is_some_and(|hex| hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// JSON Schema aktuální verze manifestu (`GET /release-manifest/schema`)
+pub fn manifest_json_schema() -> serde_json::Value {
+    serde_json::json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": format!("srm:release-manifest:v{}", RELEASE_MANIFEST_SCHEMA_VERSION),
+        "title": "SRM release manifest",
+        "type": "object",
+        "required": ["schema_version", "release_id", "created_at", "registry_base", "images", "extra_tags"],
+        "properties": {
+            "schema_version": { "const": RELEASE_MANIFEST_SCHEMA_VERSION },
+            "release_id": { "type": "string", "minLength": 1 },
+            "created_at": { "type": "string", "format": "date-time" },
+            "registry_base": { "type": ["string", "null"] },
+            "images": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["app_name", "container_name", "image", "tag", "digest"],
+                    "properties": {
+                        "app_name": { "type": "string", "minLength": 1 },
+                        "container_name": { "type": ["string", "null"] },
+                        "image": { "type": "string", "minLength": 1 },
+                        "tag": { "type": "string", "minLength": 1 },
+                        "digest": { "type": ["string", "null"], "pattern": "^sha256:[0-9a-fA-F]{64}$" }
+                    }
+                }
+            },
+            "extra_tags": { "type": "array", "items": { "type": "string", "minLength": 1 } }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn legacy_manifest() -> serde_json::Value {
+        serde_json::json!({
+            "release_id": "2026.10.1",
+            "created_at": "2026-10-01T10:00:00Z",
+            "images": [{
+                "app_name": "api",
+                "container_name": null,
+                "image": "registry.example.com:5000/team/api",
+                "tag": "2026.10.1",
+                "digest": format!("sha256:{}", "a".repeat(64))
+            }]
+        })
+    }
+
+    #[test]
+    fn legacy_manifest_is_migrated_to_current_schema() {
+        let value = legacy_manifest();
+        assert_eq!(schema_version_of(&value), None);
+        let manifest = migrate_manifest(value).unwrap();
+        assert_eq!(manifest.schema_version, RELEASE_MANIFEST_SCHEMA_VERSION);
+        assert!(manifest.extra_tags.is_empty());
+        assert!(validate_manifest(&manifest).is_empty());
+    }
+
+    #[test]
+    fn newer_schema_is_rejected() {
+        let mut value = legacy_manifest();
+        value["schema_version"] = (RELEASE_MANIFEST_SCHEMA_VERSION + 1).into();
+        assert!(migrate_manifest(value).is_err());
+    }
+
+    #[test]
+    fn validation_reports_contract_violations() {
+        let mut value = legacy_manifest();
+        value["images"][0]["image"] = "registry.example.com:5000/team/api:latest".into();
+        value["images"][0]["digest"] = "sha256:abc".into();
+        let mut manifest = migrate_manifest(value).unwrap();
+        manifest.images.push(ReleaseManifestImage {
+            app_name: "api".to_string(),
+            container_name: None,
+            image: "registry.example.com:5000/team/api".to_string(),
+            tag: String::new(),
+            digest: None,
+        });
+        let errors = validate_manifest(&manifest);
+        assert_eq!(errors.len(), 4, "{:?}", errors);
+        assert!(errors[0].contains("tag or digest"));
+        assert!(errors[1].starts_with("images[0]: digest"));
+        assert_eq!(errors[2], "images[1]: tag must not be empty");
+        assert!(errors[3].starts_with("images[1]: duplicate"));
+    }
+}
@@ -0,0 +1,331 @@
+use anyhow::Result;
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use std::collections::HashMap;
+use std::fmt::Write;
+use uuid::Uuid;
+
+use crate::services::release_manifest::{load_release_manifest, ReleaseManifestImage};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoteChangeKind {
+    Added,
+    Updated,
+    Removed,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NoteChange {
+    pub app_name: String,
+    pub container_name: Option<String>,
+    pub change: NoteChangeKind,
+    pub previous_image: Option<String>,
+    pub previous_digest: Option<String>,
+    pub image: Option<String>,
+    pub digest: Option<String>,
+}
+
+/// Změny jednoho vlastníka; `owner: null` = aplikace bez vlastníka
+#[derive(Debug, Serialize)]
+pub struct OwnerChanges {
+    pub owner: Option<String>,
+    pub changes: Vec<NoteChange>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReleaseNotes {
+    pub release_uuid: Uuid,
+    pub release_id: String,
+    /// Release, proti kterému se změny počítají (`None` = první release bundle)
+    pub against_release_uuid: Option<Uuid>,
+    pub against_release_id: Option<String>,
+    /// Volný text z release
+    pub notes: Option<String>,
+    pub groups: Vec<OwnerChanges>,
+    pub unchanged: usize,
+}
+
+#[derive(Debug, FromRow)]
+struct NotesReleaseRow {
+    id: Uuid,
+    release_id: String,
+    notes: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+struct MappingOwnerRow {
+    app_name: String,
+    container_name: String,
+    owner: Option<String>,
+}
+
+type AppKey = (String, String);
+
+fn image_key(image: &ReleaseManifestImage) -> AppKey {
+    (image.app_name.clone(), image.container_name.clone().unwrap_or_default())
+}
+
+fn image_ref(image: &ReleaseManifestImage) -> String {
+    format!("{}:{}", image.image, image.tag)
+}
+
+/// Image se změnil, pokud se liší digest; bez digestů rozhoduje repozitář (tag je per release)
+fn is_changed(previous: &ReleaseManifestImage, current: &ReleaseManifestImage) -> bool {
+    match (&previous.digest, &current.digest) {
+        (Some(a), Some(b)) => a != b,
+        (None, None) => previous.image != current.image,
+        _ => true,
+    }
+}
+
+/// Změny mezi dvěma manifesty seskupené podle vlastníka (vlastníci abecedně, bez vlastníka na konci).
+/// Vlastník se bere z aktuálního release, u odebraných aplikací z předchozího.
+pub fn group_changes(
+    current: &[ReleaseManifestImage],
+    previous: &[ReleaseManifestImage],
+    owners: &HashMap<AppKey, String>,
+) -> (Vec<OwnerChanges>, usize) {
+    let previous_by_key: HashMap<AppKey, &ReleaseManifestImage> =
+        previous.iter().map(|image| (image_key(image), image)).collect();
+    let current_by_key: HashMap<AppKey, &ReleaseManifestImage> =
+        current.iter().map(|image| (image_key(image), image)).collect();
+
+    let mut keys: Vec<&AppKey> = current_by_key.keys().chain(previous_by_key.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    let mut unchanged = 0;
+    let mut grouped: Vec<OwnerChanges> = Vec::new();
+    for key in keys {
+        let before = previous_by_key.get(key).copied();
+        let after = current_by_key.get(key).copied();
+        let change = match (before, after) {
+            (None, Some(_)) => NoteChangeKind::Added,
+            (Some(_), None) => NoteChangeKind::Removed,
+            (Some(b), Some(a)) if is_changed(b, a) => NoteChangeKind::Updated,
+            _ => {
+                unchanged += 1;
+                continue;
+            }
+        };
+        let owner = owners.get(key).cloned();
+        let note = NoteChange {
+            app_name: key.0.clone(),
+            container_name: Some(key.1.clone()).filter(|c| !c.is_empty()),
+            change,
+            previous_image: before.map(image_ref),
+            previous_digest: before.and_then(|b| b.digest.clone()),
+            image: after.map(image_ref),
+            digest: after.and_then(|a| a.digest.clone()),
+        };
+        match grouped.iter_mut().find(|group| group.owner == owner) {
+            Some(group) => group.changes.push(note),
+            None => grouped.push(OwnerChanges {
+                owner,
+                changes: vec![note],
+            }),
+        }
+    }
+    grouped.sort_by(|a, b| (a.owner.is_none(), &a.owner).cmp(&(b.owner.is_none(), &b.owner)));
+    (grouped, unchanged)
+}
+
+fn short_digest(digest: Option<&str>) -> &str {
+    match digest {
+        Some(digest) => digest.get(..19).unwrap_or(digest),
+        None => "-",
+    }
+}
+
+pub fn render_markdown(notes: &ReleaseNotes) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# Release {}", notes.release_id);
+    if let Some(against) = &notes.against_release_id {
+        let _ = writeln!(out, "\nChanges since {}.", against);
+    }
+    if let Some(text) = notes.notes.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+        let _ = writeln!(out, "\n{}", text);
+    }
+    if notes.groups.is_empty() {
+        let _ = writeln!(out, "\nNo image changes.");
+    }
+    for group in &notes.groups {
+        let _ = writeln!(out, "\n## {}\n", group.owner.as_deref().unwrap_or("Unassigned"));
+        for change in &group.changes {
+            let app = match &change.container_name {
+                Some(container) => format!("{}/{}", change.app_name, container),
+                None => change.app_name.clone(),
+            };
+            let line = match change.change {
+                NoteChangeKind::Added => format!("added `{}`", change.image.as_deref().unwrap_or("-")),
+                NoteChangeKind::Removed => format!("removed (was `{}`)", change.previous_image.as_deref().unwrap_or("-")),
+                NoteChangeKind::Updated => format!(
+                    "`{}` ({} → {})",
+                    change.image.as_deref().unwrap_or("-"),
+                    short_digest(change.previous_digest.as_deref()),
+                    short_digest(change.digest.as_deref())
+                ),
+            };
+            let _ = writeln!(out, "- **{}**: {}", app, line);
+        }
+    }
+    if notes.unchanged > 0 {
+        let _ = writeln!(out, "\n{} unchanged image(s).", notes.unchanged);
+    }
+    out
+}
+
+async fn load_owners(pool: &PgPool, release_db_id: Uuid) -> Result<HashMap<AppKey, String>> {
+    let rows = sqlx::query_as::<_, MappingOwnerRow>(
+        r#"
+        SELECT DISTINCT im.app_name, COALESCE(im.container_name, '') AS container_name, im.owner
+        FROM releases r
+        JOIN copy_job_images cji ON cji.copy_job_id = r.copy_job_id
+        JOIN image_mappings im ON im.id = cji.image_mapping_id
+        WHERE r.id = $1 AND im.owner IS NOT NULL
+        "#,
+    )
+    .bind(release_db_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| Some(((row.app_name, row.container_name), row.owner?)))
+        .collect())
+}
+
+/// Release notes proti `against`; bez něj proti base release (patch) nebo předchozímu release téhož bundle.
+/// `Ok(None)` = release neexistuje.
+pub async fn build_release_notes(pool: &PgPool, release_db_id: Uuid, against: Option<Uuid>) -> Result<Option<ReleaseNotes>> {
+    let Some(release) = sqlx::query_as::<_, NotesReleaseRow>("SELECT id, release_id, notes FROM releases WHERE id = $1")
+        .bind(release_db_id)
+        .fetch_optional(pool)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    let previous = match against {
+        Some(id) => Some(
+            sqlx::query_as::<_, NotesReleaseRow>("SELECT id, release_id, notes FROM releases WHERE id = $1")
+                .bind(id)
+                .fetch_optional(pool)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("Release with id {} not found", id))?,
+        ),
+        None => {
+            sqlx::query_as::<_, NotesReleaseRow>(
+                r#"
+                SELECT p.id, p.release_id, p.notes
+                FROM releases r
+                JOIN copy_jobs cj ON cj.id = r.copy_job_id
+                JOIN bundle_versions bv ON bv.id = cj.bundle_version_id
+                JOIN releases p ON p.id <> r.id
+                JOIN copy_jobs pcj ON pcj.id = p.copy_job_id
+                JOIN bundle_versions pbv ON pbv.id = pcj.bundle_version_id
+                WHERE r.id = $1
+                  AND (p.id = r.base_release_id
+                       OR (r.base_release_id IS NULL AND pbv.bundle_id = bv.bundle_id AND p.created_at < r.created_at))
+                ORDER BY p.created_at DESC
+                LIMIT 1
+                "#,
+            )
+            .bind(release_db_id)
+            .fetch_optional(pool)
+            .await?
+        }
+    };
+
+    let current_manifest = load_release_manifest(pool, release.id).await?;
+    let mut owners = load_owners(pool, release.id).await?;
+    let previous_images = match &previous {
+        Some(previous) => {
+            for (key, owner) in load_owners(pool, previous.id).await? {
+                owners.entry(key).or_insert(owner);
+            }
+            load_release_manifest(pool, previous.id).await?.images
+        }
+        None => Vec::new(),
+    };
+
+    let (groups, unchanged) = group_changes(&current_manifest.images, &previous_images, &owners);
+    Ok(Some(ReleaseNotes {
+        release_uuid: release.id,
+        release_id: release.release_id,
+        against_release_uuid: previous.as_ref().map(|p| p.id),
+        against_release_id: previous.map(|p| p.release_id),
+        notes: release.notes,
+        groups,
+        unchanged,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(app: &str, digest: &str) -> ReleaseManifestImage {
+        ReleaseManifestImage {
+            app_name: app.to_string(),
+            container_name: None,
+            image: format!("registry.example.com/shop/{}", app),
+            tag: "r1".to_string(),
+            digest: Some(digest.to_string()),
+        }
+    }
+
+    fn owners(pairs: &[(&str, &str)]) -> HashMap<AppKey, String> {
+        pairs
+            .iter()
+            .map(|(app, owner)| ((app.to_string(), String::new()), owner.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn changes_are_grouped_by_owner() {
+        let previous = vec![image("api", "sha256:a"), image("web", "sha256:w"), image("old", "sha256:o")];
+        let current = vec![
+            image("api", "sha256:b"),
+            image("web", "sha256:w"),
+            image("worker", "sha256:k"),
+            image("cron", "sha256:c"),
+        ];
+        let owners = owners(&[("api", "team-b"), ("worker", "team-a"), ("old", "team-b")]);
+
+        let (groups, unchanged) = group_changes(&current, &previous, &owners);
+        assert_eq!(unchanged, 1);
+        let owners: Vec<Option<&str>> = groups.iter().map(|g| g.owner.as_deref()).collect();
+        assert_eq!(owners, vec![Some("team-a"), Some("team-b"), None]);
+        let changes = |index: usize| -> Vec<(&str, NoteChangeKind)> {
+            groups[index].changes.iter().map(|c| (c.app_name.as_str(), c.change)).collect()
+        };
+        assert_eq!(changes(0), vec![("worker", NoteChangeKind::Added)]);
+        assert_eq!(
+            changes(1),
+            vec![("api", NoteChangeKind::Updated), ("old", NoteChangeKind::Removed)]
+        );
+        assert_eq!(changes(2), vec![("cron", NoteChangeKind::Added)]);
+    }
+
+    #[test]
+    fn markdown_lists_owner_sections() {
+        let (groups, unchanged) = group_changes(
+            &[image("api", "sha256:b")],
+            &[image("api", "sha256:a")],
+            &owners(&[("api", "team-b")]),
+        );
+        let markdown = render_markdown(&ReleaseNotes {
+            release_uuid: Uuid::nil(),
+            release_id: "2026.10.15".to_string(),
+            against_release_uuid: None,
+            against_release_id: Some("2026.10.01".to_string()),
+            notes: None,
+            groups,
+            unchanged,
+        });
+        assert!(markdown.contains("## team-b\n"));
+        assert!(markdown.contains("- **api**: `registry.example.com/shop/api:r1` (sha256:a → sha256:b)"));
+        assert!(markdown.contains("Changes since 2026.10.01."));
+    }
+}
@@ -0,0 +1,201 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::PgPool;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// Úklid release tagu, který zůstal v cílové registry po selhaném / zrušeném release jobu
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseTagCleanup {
+    /// Tagy zůstanou beze změny
+    #[default]
+    Off,
+    /// Tagy se jen zaevidují; další copy je nepovažuje za hotové a zkopíruje je znovu
+    Quarantine,
+    /// Tagy se smažou z registry; když to nejde bezpečně, spadnou do karantény
+    Delete,
+}
+
+pub const MODES: [&str; 3] = ["off", "quarantine", "delete"];
+
+/// Event v timeline jobu (`job_events`)
+pub const CLEANUP_EVENT: &str = "release_tags.cleanup";
+
+impl ReleaseTagCleanup {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "off" | "none" | "" => Some(Self::Off),
+            "quarantine" => Some(Self::Quarantine),
+            "delete" => Some(Self::Delete),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Quarantine => "quarantine",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+/// Release tag, který job stihl do cílové registry zapsat
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct PushedTag {
+    pub target_image: String,
+    pub target_tag: String,
+    pub digest: String,
+    /// Stejný digest je v repozitáři i pod tagem jiného úspěšného jobu (base build, předchozí release)
+    pub digest_shared: bool,
+}
+
+/// Co se s tagem udělá
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CleanupAction {
+    Delete,
+    Quarantine,
+}
+
+/// Mazání v registry odstraní celý manifest (i ostatní tagy se stejným digestem),
+/// proto se sdílený digest nebo image tool bez mazání řeší karanténou.
+pub fn action_for(mode: ReleaseTagCleanup, tag: &PushedTag, can_delete: bool) -> Option<CleanupAction> {
+    match mode {
+        ReleaseTagCleanup::Off => None,
+        ReleaseTagCleanup::Quarantine => Some(CleanupAction::Quarantine),
+        ReleaseTagCleanup::Delete if can_delete && !tag.digest_shared => Some(CleanupAction::Delete),
+        ReleaseTagCleanup::Delete => Some(CleanupAction::Quarantine),
+    }
+}
+
+/// Release tagy zapsané release jobem (image s cílovým digestem); pro ostatní joby nic
+pub async fn pushed_tags(pool: &PgPool, job_id: Uuid) -> Result<Vec<PushedTag>, sqlx::Error> {
+    sqlx::query_as::<_, PushedTag>(
+        "SELECT DISTINCT cji.target_image, cj.target_tag, cji.target_sha256 AS digest,
+                EXISTS(
+                    SELECT 1 FROM copy_job_images other
+                    JOIN copy_jobs oj ON oj.id = other.copy_job_id
+                    WHERE oj.id <> cj.id
+                      AND oj.status = 'success'
+                      AND oj.target_registry_id = cj.target_registry_id
+                      AND other.target_image = cji.target_image
+                      AND other.target_sha256 = cji.target_sha256
+                ) AS digest_shared
+         FROM copy_job_images cji
+         JOIN copy_jobs cj ON cj.id = cji.copy_job_id
+         WHERE cj.id = $1
+           AND cj.is_release_job
+           AND NOT cj.validate_only
+           AND cj.target_registry_id IS NOT NULL
+           AND cji.target_sha256 IS NOT NULL
+         ORDER BY cji.target_image",
+    )
+    .bind(job_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Zaeviduje tag v karanténě (novější selhaný job přebírá záznam)
+pub async fn quarantine(pool: &PgPool, job_id: Uuid, tag: &PushedTag) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO quarantined_release_tags (target_registry_id, target_image, target_tag, digest, copy_job_id)
+         SELECT target_registry_id, $2, $3, $4, id FROM copy_jobs WHERE id = $1 AND target_registry_id IS NOT NULL
+         ON CONFLICT (target_registry_id, target_image, target_tag)
+         DO UPDATE SET digest = EXCLUDED.digest, copy_job_id = EXCLUDED.copy_job_id, created_at = NOW()",
+    )
+    .bind(job_id)
+    .bind(&tag.target_image)
+    .bind(&tag.target_tag)
+    .bind(&tag.digest)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Cílové image, jejichž tag je v karanténě - copy u nich nevěří shodě digestu
+pub async fn quarantined_images(
+    pool: &PgPool,
+    target_registry_id: Uuid,
+    target_tag: &str,
+) -> Result<HashSet<String>, sqlx::Error> {
+    let images = sqlx::query_scalar::<_, String>(
+        "SELECT target_image FROM quarantined_release_tags WHERE target_registry_id = $1 AND target_tag = $2",
+    )
+    .bind(target_registry_id)
+    .bind(target_tag)
+    .fetch_all(pool)
+    .await?;
+    Ok(images.into_iter().collect())
+}
+
+/// Úspěšný job tagy znovu zapsal - karanténa pro ně končí
+pub async fn release_quarantine(pool: &PgPool, job_id: Uuid) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        "DELETE FROM quarantined_release_tags q
+         USING copy_jobs cj, copy_job_images cji
+         WHERE cj.id = $1
+           AND cji.copy_job_id = cj.id
+           AND q.target_registry_id = cj.target_registry_id
+           AND q.target_image = cji.target_image
+           AND q.target_tag = cj.target_tag",
+    )
+    .bind(job_id)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Výsledek úklidu do timeline jobu
+pub async fn record_event(
+    pool: &PgPool,
+    job_id: Uuid,
+    mode: ReleaseTagCleanup,
+    deleted: &[String],
+    quarantined: &[String],
+) -> Result<(), sqlx::Error> {
+    sqlx::query("INSERT INTO job_events (copy_job_id, event_type, data) VALUES ($1, $2, $3)")
+        .bind(job_id)
+        .bind(CLEANUP_EVENT)
+        .bind(json!({
+            "mode": mode.as_str(),
+            "deleted": deleted,
+            "quarantined": quarantined,
+        }))
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(digest_shared: bool) -> PushedTag {
+        PushedTag {
+            target_image: "team/app".to_string(),
+            target_tag: "2026.10.15-1".to_string(),
+            digest: "sha256:abc".to_string(),
+            digest_shared,
+        }
+    }
+
+    #[test]
+    fn modes_parse_case_insensitive() {
+        assert_eq!(ReleaseTagCleanup::parse(" Delete "), Some(ReleaseTagCleanup::Delete));
+        assert_eq!(ReleaseTagCleanup::parse("none"), Some(ReleaseTagCleanup::Off));
+        assert_eq!(ReleaseTagCleanup::parse("purge"), None);
+        for mode in MODES {
+            assert_eq!(ReleaseTagCleanup::parse(mode).unwrap().as_str(), mode);
+        }
+    }
+
+    #[test]
+    fn shared_digest_is_never_deleted() {
+        assert_eq!(action_for(ReleaseTagCleanup::Off, &tag(false), true), None);
+        assert_eq!(action_for(ReleaseTagCleanup::Quarantine, &tag(false), true), Some(CleanupAction::Quarantine));
+        assert_eq!(action_for(ReleaseTagCleanup::Delete, &tag(false), true), Some(CleanupAction::Delete));
+        assert_eq!(action_for(ReleaseTagCleanup::Delete, &tag(true), true), Some(CleanupAction::Quarantine));
+        assert_eq!(action_for(ReleaseTagCleanup::Delete, &tag(false), false), Some(CleanupAction::Quarantine));
+    }
+}
@@ -0,0 +1,437 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+use std::sync::Arc;
+use tokio::process::Command;
+
+/// Proměnné prostředí, které projdou do nástroje i při omezeném env
+pub const DEFAULT_ENV_ALLOW: &[&str] = &[
+    "PATH",
+    "HOME",
+    "LANG",
+    "LC_ALL",
+    "TZ",
+    "TMPDIR",
+    "SSL_CERT_FILE",
+    "SSL_CERT_DIR",
+    "HTTP_PROXY",
+    "HTTPS_PROXY",
+    "NO_PROXY",
+    "http_proxy",
+    "https_proxy",
+    "no_proxy",
+];
+
+/// Způsob izolace externího nástroje
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SandboxMode {
+    /// Bez namespace izolace (UID, limity a env se uplatní i tak)
+    #[default]
+    None,
+    Bwrap,
+    Nsjail,
+}
+
+impl SandboxMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "none" | "off" => Some(SandboxMode::None),
+            "bwrap" | "bubblewrap" => Some(SandboxMode::Bwrap),
+            "nsjail" => Some(SandboxMode::Nsjail),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            SandboxMode::None => "none",
+            SandboxMode::Bwrap => "bwrap",
+            SandboxMode::Nsjail => "nsjail",
+        }
+    }
+}
+
+/// Externí nástroje spouštěné serverem
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SandboxTool {
+    Git,
+    ImageTool,
+    Encjson,
+    KubeBuildApp,
+    ApplyEnv,
+    Kubeconform,
+}
+
+impl SandboxTool {
+    pub const ALL: [SandboxTool; 6] = [
+        SandboxTool::Git,
+        SandboxTool::ImageTool,
+        SandboxTool::Encjson,
+        SandboxTool::KubeBuildApp,
+        SandboxTool::ApplyEnv,
+        SandboxTool::Kubeconform,
+    ];
+
+    /// Část názvu env proměnné pro per-tool nastavení (`SANDBOX_<TOOL>_MODE`)
+    pub fn env_name(self) -> &'static str {
+        match self {
+            SandboxTool::Git => "GIT",
+            SandboxTool::ImageTool => "IMAGE_TOOL",
+            SandboxTool::Encjson => "ENCJSON",
+            SandboxTool::KubeBuildApp => "KUBE_BUILD_APP",
+            SandboxTool::ApplyEnv => "APPLY_ENV",
+            SandboxTool::Kubeconform => "KUBECONFORM",
+        }
+    }
+}
+
+/// Nastavení sandboxu pro jeden nástroj
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct SandboxPolicy {
+    pub mode: SandboxMode,
+    pub cpu_seconds: Option<u64>,
+    pub memory_mb: Option<u64>,
+    /// Nástroj dostane jen proměnné z allow-listu + ty, které mu předá server
+    pub restrict_env: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SandboxConfig {
+    /// Samostatné UID/GID pro nástroje (server musí běžet jako root)
+    pub uid: Option<u32>,
+    pub gid: Option<u32>,
+    pub env_allow: Vec<String>,
+    /// Cesty zapisovatelné uvnitř bwrap/nsjail (pracovní adresář se přidá vždy)
+    pub writable_paths: Vec<String>,
+    pub bwrap_path: String,
+    pub nsjail_path: String,
+    pub prlimit_path: String,
+    pub policies: HashMap<SandboxTool, SandboxPolicy>,
+}
+
+/// Wrapper pro spouštění externích nástrojů podle sandbox konfigurace
+#[derive(Clone, Default)]
+pub struct ToolSandbox {
+    config: Arc<SandboxConfig>,
+}
+
+impl ToolSandbox {
+    pub fn new(config: SandboxConfig) -> Self {
+        ToolSandbox {
+            config: Arc::new(config),
+        }
+    }
+
+    pub fn policy(&self, tool: SandboxTool) -> SandboxPolicy {
+        self.config.policies.get(&tool).cloned().unwrap_or_default()
+    }
+
+    pub fn is_active(&self, tool: SandboxTool) -> bool {
+        let policy = self.policy(tool);
+        policy.mode != SandboxMode::None
+            || policy.cpu_seconds.is_some()
+            || policy.memory_mb.is_some()
+            || policy.restrict_env
+            || self.config.uid.is_some()
+    }
+
+    pub fn program<'a>(&'a self, tool: SandboxTool, path: &'a str) -> ToolProgram<'a> {
+        ToolProgram {
+            sandbox: self,
+            tool,
+            path,
+        }
+    }
+
+    /// Připraví příkaz; argumenty a env přidává volající jako u `Command::new`
+    pub fn command(&self, tool: SandboxTool, program: &str, cwd: Option<&Path>) -> Command {
+        let policy = self.policy(tool);
+        let argv = self.wrapper_argv(&policy, program, cwd);
+
+        let mut cmd = Command::new(&argv[0]);
+        cmd.args(&argv[1..]);
+        if let Some(dir) = cwd {
+            cmd.current_dir(dir);
+        }
+        if policy.restrict_env {
+            cmd.env_clear();
+            let allow = DEFAULT_ENV_ALLOW
+                .iter()
+                .copied()
+                .chain(self.config.env_allow.iter().map(String::as_str));
+            for name in allow {
+                if let Some(value) = std::env::var_os(name) {
+                    cmd.env(name, value);
+                }
+            }
+        }
+        // nsjail přepíná UID sám (--user/--group)
+        if policy.mode != SandboxMode::Nsjail {
+            #[cfg(unix)]
+            {
+                if let Some(uid) = self.config.uid {
+                    cmd.uid(uid);
+                }
+                if let Some(gid) = self.config.gid {
+                    cmd.gid(gid);
+                }
+            }
+        }
+        cmd
+    }
+
+    fn wrapper_argv(&self, policy: &SandboxPolicy, program: &str, cwd: Option<&Path>) -> Vec<OsString> {
+        let mut writable: Vec<PathBuf> = self.config.writable_paths.iter().map(PathBuf::from).collect();
+        if let Some(dir) = cwd {
+            writable.push(dir.to_path_buf());
+        }
+
+        let mut argv: Vec<OsString> = Vec::new();
+        match policy.mode {
+            SandboxMode::None => {}
+            SandboxMode::Bwrap => {
+                argv.push(self.config.bwrap_path.clone().into());
+                for arg in ["--ro-bind", "/", "/", "--dev", "/dev", "--proc", "/proc"] {
+                    argv.push(arg.into());
+                }
+                for path in &writable {
+                    argv.push("--bind-try".into());
+                    argv.push(path.clone().into());
+                    argv.push(path.clone().into());
+                }
+                if let Some(dir) = cwd {
+                    argv.push("--chdir".into());
+                    argv.push(dir.into());
+                }
+                for arg in ["--unshare-pid", "--unshare-ipc", "--unshare-uts", "--die-with-parent", "--new-session", "--"] {
+                    argv.push(arg.into());
+                }
+            }
+            SandboxMode::Nsjail => {
+                argv.push(self.config.nsjail_path.clone().into());
+                // Síť zůstává (git push, registry), root FS je read-only
+                for arg in ["--mode", "o", "--quiet", "--disable_clone_newnet", "--chroot", "/", "--keep_env", "--time_limit", "0"] {
+                    argv.push(arg.into());
+                }
+                for path in &writable {
+                    argv.push("--bindmount".into());
+                    argv.push(path.clone().into());
+                }
+                if let Some(dir) = cwd {
+                    argv.push("--cwd".into());
+                    argv.push(dir.into());
+                }
+                let memory = policy.memory_mb.map(|mb| mb.to_string()).unwrap_or_else(|| "max".to_string());
+                let cpu = policy.cpu_seconds.map(|s| s.to_string()).unwrap_or_else(|| "max".to_string());
+                // nsjail má jinak nízké výchozí limity (např. fsize 1 MB)
+                for (flag, value) in [
+                    ("--rlimit_as", memory),
+                    ("--rlimit_cpu", cpu),
+                    ("--rlimit_fsize", "max".to_string()),
+                    ("--rlimit_nofile", "max".to_string()),
+                ] {
+                    argv.push(flag.into());
+                    argv.push(value.into());
+                }
+                if let Some(uid) = self.config.uid {
+                    argv.push("--user".into());
+                    argv.push(uid.to_string().into());
+                }
+                if let Some(gid) = self.config.gid {
+                    argv.push("--group".into());
+                    argv.push(gid.to_string().into());
+                }
+                argv.push("--".into());
+            }
+        }
+
+        // nsjail vyžaduje absolutní cestu k programu
+        if policy.mode == SandboxMode::Nsjail {
+            argv.push(resolve_program(program).into());
+        } else {
+            argv.push(program.into());
+        }
+
+        if policy.mode != SandboxMode::Nsjail && (policy.cpu_seconds.is_some() || policy.memory_mb.is_some()) {
+            let mut limited: Vec<OsString> = vec![self.config.prlimit_path.clone().into()];
+            if let Some(cpu) = policy.cpu_seconds {
+                limited.push(format!("--cpu={}", cpu).into());
+            }
+            if let Some(mb) = policy.memory_mb {
+                limited.push(format!("--as={}", mb.saturating_mul(1024 * 1024)).into());
+            }
+            limited.push("--".into());
+            limited.extend(argv);
+            argv = limited;
+        }
+        argv
+    }
+
+    /// Popis sandboxu pro logy jobů, např. `bwrap, uid 1500, cpu 600s, memory 2048MB, restricted env`
+    pub fn summary(&self, tool: SandboxTool) -> String {
+        let policy = self.policy(tool);
+        let mut parts = vec![policy.mode.name().to_string()];
+        if let Some(uid) = self.config.uid {
+            parts.push(format!("uid {}", uid));
+        }
+        if let Some(cpu) = policy.cpu_seconds {
+            parts.push(format!("cpu {}s", cpu));
+        }
+        if let Some(mb) = policy.memory_mb {
+            parts.push(format!("memory {}MB", mb));
+        }
+        if policy.restrict_env {
+            parts.push("restricted env".to_string());
+        }
+        parts.join(", ")
+    }
+
+    /// Předá soubor/adresář (rekurzivně) sandbox UID, aby k němu nástroj měl přístup
+    pub fn hand_over(&self, path: &Path) -> std::io::Result<()> {
+        #[cfg(unix)]
+        {
+            if self.config.uid.is_none() && self.config.gid.is_none() {
+                return Ok(());
+            }
+            std::os::unix::fs::lchown(path, self.config.uid, self.config.gid)?;
+            if path.is_dir() && !path.is_symlink() {
+                for entry in std::fs::read_dir(path)? {
+                    self.hand_over(&entry?.path())?;
+                }
+            }
+        }
+        #[cfg(not(unix))]
+        let _ = path;
+        Ok(())
+    }
+}
+
+/// Nástroj + cesta k binárce, jak ji dostávají helpery pro spouštění příkazů
+#[derive(Clone, Copy)]
+pub struct ToolProgram<'a> {
+    pub sandbox: &'a ToolSandbox,
+    pub tool: SandboxTool,
+    pub path: &'a str,
+}
+
+impl ToolProgram<'_> {
+    pub fn command(&self, cwd: Option<&Path>) -> Command {
+        self.sandbox.command(self.tool, self.path, cwd)
+    }
+
+    /// Chyba spuštění (chybějící bwrap/nsjail, EPERM při přepnutí UID, ...)
+    pub fn spawn_error(&self, error: &std::io::Error) -> String {
+        if self.sandbox.is_active(self.tool) {
+            format!(
+                "Failed to start {} in sandbox ({}): {}",
+                self.path,
+                self.sandbox.summary(self.tool),
+                error
+            )
+        } else {
+            format!("Failed to start {}: {}", self.path, error)
+        }
+    }
+
+    /// Vysvětlení pro proces ukončený signálem (typicky překročený CPU/memory limit)
+    pub fn exit_note(&self, status: &ExitStatus) -> Option<String> {
+        if !self.sandbox.is_active(self.tool) || status.code().is_some() {
+            return None;
+        }
+        #[cfg(unix)]
+        let signal = std::os::unix::process::ExitStatusExt::signal(status)
+            .map(|sig| sig.to_string())
+            .unwrap_or_else(|| "?".to_string());
+        #[cfg(not(unix))]
+        let signal = "?".to_string();
+        Some(format!(
+            "{} was terminated by signal {} (sandbox: {})",
+            self.path,
+            signal,
+            self.sandbox.summary(self.tool)
+        ))
+    }
+}
+
+fn resolve_program(program: &str) -> PathBuf {
+    if program.contains('/') {
+        return PathBuf::from(program);
+    }
+    std::env::var_os("PATH")
+        .and_then(|paths| {
+            std::env::split_paths(&paths)
+                .map(|dir| dir.join(program))
+                .find(|candidate| candidate.is_file())
+        })
+        .unwrap_or_else(|| PathBuf::from(program))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sandbox(mode: SandboxMode, cpu_seconds: Option<u64>) -> ToolSandbox {
+        let mut policies = HashMap::new();
+        policies.insert(
+            SandboxTool::Git,
+            SandboxPolicy {
+                mode,
+                cpu_seconds,
+                memory_mb: Some(512),
+                restrict_env: true,
+            },
+        );
+        ToolSandbox::new(SandboxConfig {
+            uid: Some(1500),
+            gid: Some(1500),
+            env_allow: Vec::new(),
+            writable_paths: vec!["/tmp".to_string()],
+            bwrap_path: "bwrap".to_string(),
+            nsjail_path: "nsjail".to_string(),
+            prlimit_path: "prlimit".to_string(),
+            policies,
+        })
+    }
+
+    fn argv(sandbox: &ToolSandbox, program: &str, cwd: Option<&Path>) -> Vec<String> {
+        sandbox
+            .wrapper_argv(&sandbox.policy(SandboxTool::Git), program, cwd)
+            .into_iter()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn bwrap_is_wrapped_in_prlimit_and_binds_workdir() {
+        let sandbox = sandbox(SandboxMode::Bwrap, Some(60));
+        let args = argv(&sandbox, "git", Some(Path::new("/work/repo")));
+        assert_eq!(&args[..4], ["prlimit", "--cpu=60", "--as=536870912", "--"]);
+        assert_eq!(args[4], "bwrap");
+        assert!(args.windows(3).any(|w| w == ["--bind-try", "/work/repo", "/work/repo"]));
+        assert!(args.windows(2).any(|w| w == ["--chdir", "/work/repo"]));
+        assert_eq!(args.last().map(String::as_str), Some("git"));
+    }
+
+    #[test]
+    fn nsjail_sets_limits_and_user_itself() {
+        let sandbox = sandbox(SandboxMode::Nsjail, None);
+        let args = argv(&sandbox, "/usr/bin/git", None);
+        assert_eq!(args[0], "nsjail");
+        assert!(args.windows(2).any(|w| w == ["--rlimit_as", "512"]));
+        assert!(args.windows(2).any(|w| w == ["--rlimit_cpu", "max"]));
+        assert!(args.windows(2).any(|w| w == ["--user", "1500"]));
+        assert_eq!(&args[args.len() - 2..], ["--", "/usr/bin/git"]);
+    }
+
+    #[test]
+    fn unconfigured_tool_runs_directly() {
+        let sandbox = ToolSandbox::default();
+        assert!(!sandbox.is_active(SandboxTool::Encjson));
+        assert_eq!(argv(&sandbox, "encjson", None), ["encjson"]);
+        assert_eq!(sandbox.summary(SandboxTool::Encjson), "none");
+    }
+}
@@ -0,0 +1,111 @@
+use chrono::{DateTime, TimeZone, Utc};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::services::object_storage::{hex, hmac_sha256};
+
+pub const SCOPE_LOGS: &str = "logs";
+pub const SCOPE_DIFF: &str = "diff";
+
+pub const OUTCOME_GRANTED: &str = "granted";
+pub const OUTCOME_EXPIRED: &str = "expired";
+pub const OUTCOME_REVOKED: &str = "revoked";
+pub const OUTCOME_OUT_OF_SCOPE: &str = "out_of_scope";
+
+/// Výchozí a maximální platnost odkazu
+pub const DEFAULT_EXPIRES_IN_HOURS: i64 = 24;
+pub const MAX_EXPIRES_IN_HOURS: i64 = 24 * 30;
+
+/// Klíč pro podpis odkazů odvozený z ENCRYPTION_SECRET (změna secretu zneplatní všechny odkazy)
+fn signing_key(secret: &str) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&Sha256::new().chain_update(b"srm-share-link:").chain_update(secret).finalize());
+    key
+}
+
+fn signature(secret: &str, id: Uuid, expires_at: i64) -> String {
+    let payload = format!("share-link:{}:{}", id.simple(), expires_at);
+    hex(&hmac_sha256(&signing_key(secret), payload.as_bytes()))
+}
+
+/// Token do URL: `{id}.{expirace v unix sekundách}.{HMAC-SHA256}`
+pub fn sign(secret: &str, id: Uuid, expires_at: DateTime<Utc>) -> String {
+    let expires_at = expires_at.timestamp();
+    format!("{}.{}.{}", id.simple(), expires_at, signature(secret, id, expires_at))
+}
+
+/// Ověří podpis tokenu; vrací id odkazu a podepsanou expiraci. Expiraci kontroluje volající,
+/// aby se přístup přes expirovaný odkaz dal zapsat do auditu.
+pub fn verify(secret: &str, token: &str) -> Option<(Uuid, DateTime<Utc>)> {
+    let mut parts = token.split('.');
+    let (Some(id), Some(expires_at), Some(sig), None) = (parts.next(), parts.next(), parts.next(), parts.next()) else {
+        return None;
+    };
+    let id = Uuid::parse_str(id).ok()?;
+    let expires_at: i64 = expires_at.parse().ok()?;
+    let expected = signature(secret, id, expires_at);
+    // Porovnání v konstantním čase
+    if expected.len() != sig.len() || expected.bytes().zip(sig.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) != 0 {
+        return None;
+    }
+    Some((id, Utc.timestamp_opt(expires_at, 0).single()?))
+}
+
+/// Normalizované scopes; `diff` má jen deploy job
+pub fn validate_scopes(job_kind: &str, scopes: Option<&[String]>) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = match scopes {
+        Some(scopes) => scopes.iter().map(|s| s.trim().to_lowercase()).filter(|s| !s.is_empty()).collect(),
+        None => vec![SCOPE_LOGS.to_string()],
+    };
+    normalized.sort();
+    normalized.dedup();
+    if normalized.is_empty() {
+        return Err("At least one scope is required".to_string());
+    }
+    for scope in &normalized {
+        match scope.as_str() {
+            SCOPE_LOGS => {}
+            SCOPE_DIFF if job_kind == "deploy" => {}
+            SCOPE_DIFF => return Err("Scope 'diff' is only available for deploy jobs".to_string()),
+            other => {
+                return Err(format!(
+                    "Invalid scope '{}', expected '{}' or '{}'",
+                    other, SCOPE_LOGS, SCOPE_DIFF
+                ))
+            }
+        }
+    }
+    Ok(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_roundtrip_and_tampering() {
+        let id = Uuid::new_v4();
+        let expires_at = Utc.timestamp_opt(1_800_000_000, 0).single().unwrap();
+        let token = sign("secret", id, expires_at);
+
+        assert_eq!(verify("secret", &token), Some((id, expires_at)));
+        assert_eq!(verify("other-secret", &token), None);
+
+        // Prodloužení expirace zneplatní podpis
+        let (head, sig) = token.rsplit_once('.').unwrap();
+        let (id_part, _) = head.split_once('.').unwrap();
+        assert_eq!(verify("secret", &format!("{}.{}.{}", id_part, 1_900_000_000, sig)), None);
+        assert_eq!(verify("secret", &format!("{}.x", token)), None);
+        assert_eq!(verify("secret", "garbage"), None);
+    }
+
+    #[test]
+    fn scopes_are_normalized_and_validated() {
+        assert_eq!(validate_scopes("copy", None).unwrap(), vec!["logs"]);
+        let scopes = vec!["Diff".to_string(), "logs".to_string(), "logs".to_string()];
+        assert_eq!(validate_scopes("deploy", Some(&scopes)).unwrap(), vec!["diff", "logs"]);
+        assert!(validate_scopes("copy", Some(&scopes)).is_err());
+        assert!(validate_scopes("deploy", Some(&["env".to_string()])).is_err());
+        assert!(validate_scopes("deploy", Some(&[])).is_err());
+    }
+}
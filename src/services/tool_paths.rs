@@ -0,0 +1,244 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use std::path::Path;
+use uuid::Uuid;
+
+pub const SOURCE_ENVIRONMENT: &str = "environment";
+pub const SOURCE_TENANT: &str = "tenant";
+pub const SOURCE_GLOBAL: &str = "global";
+
+/// Cesty k binárkám nástrojů přepsané na tenantovi nebo environmentu; `None` = zdědit
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct ToolPathOverrides {
+    /// Binárka image toolu (`IMAGE_TOOL_PATH`), typicky skopeo
+    pub skopeo_path: Option<String>,
+    pub encjson_path: Option<String>,
+    pub kube_build_app_path: Option<String>,
+}
+
+impl ToolPathOverrides {
+    /// Hodnoty z `self` mají přednost před `fallback`
+    pub fn or(self, fallback: ToolPathOverrides) -> Self {
+        Self {
+            skopeo_path: self.skopeo_path.or(fallback.skopeo_path),
+            encjson_path: self.encjson_path.or(fallback.encjson_path),
+            kube_build_app_path: self.kube_build_app_path.or(fallback.kube_build_app_path),
+        }
+    }
+
+    /// Normalizace a validace při uložení (prázdná hodnota = zdědit)
+    pub fn validated(&self) -> Result<Self, String> {
+        Ok(Self {
+            skopeo_path: validate_tool_path("skopeo_path", self.skopeo_path.as_deref())?,
+            encjson_path: validate_tool_path("encjson_path", self.encjson_path.as_deref())?,
+            kube_build_app_path: validate_tool_path("kube_build_app_path", self.kube_build_app_path.as_deref())?,
+        })
+    }
+}
+
+/// Globální cesty z konfigurace
+#[derive(Debug, Clone)]
+pub struct ToolPathDefaults {
+    pub skopeo_path: String,
+    pub encjson_path: String,
+    pub kube_build_app_path: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EffectiveToolPath {
+    pub path: String,
+    /// `environment`, `tenant` nebo `global`
+    pub source: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EffectiveToolPaths {
+    pub skopeo_path: EffectiveToolPath,
+    pub encjson_path: EffectiveToolPath,
+    pub kube_build_app_path: EffectiveToolPath,
+}
+
+fn pick(environment: Option<&String>, tenant: Option<&String>, global: &str) -> EffectiveToolPath {
+    match (environment, tenant) {
+        (Some(path), _) => EffectiveToolPath { path: path.clone(), source: SOURCE_ENVIRONMENT },
+        (None, Some(path)) => EffectiveToolPath { path: path.clone(), source: SOURCE_TENANT },
+        (None, None) => EffectiveToolPath { path: global.to_string(), source: SOURCE_GLOBAL },
+    }
+}
+
+/// Efektivní cesty včetně toho, odkud pochází (environment -> tenant -> globální konfigurace)
+pub fn effective(
+    environment: Option<&ToolPathOverrides>,
+    tenant: &ToolPathOverrides,
+    defaults: &ToolPathDefaults,
+) -> EffectiveToolPaths {
+    EffectiveToolPaths {
+        skopeo_path: pick(
+            environment.and_then(|e| e.skopeo_path.as_ref()),
+            tenant.skopeo_path.as_ref(),
+            &defaults.skopeo_path,
+        ),
+        encjson_path: pick(
+            environment.and_then(|e| e.encjson_path.as_ref()),
+            tenant.encjson_path.as_ref(),
+            &defaults.encjson_path,
+        ),
+        kube_build_app_path: pick(
+            environment.and_then(|e| e.kube_build_app_path.as_ref()),
+            tenant.kube_build_app_path.as_ref(),
+            &defaults.kube_build_app_path,
+        ),
+    }
+}
+
+/// Cesta musí být absolutní a ukazovat na spustitelný soubor na tomto stroji
+pub fn validate_tool_path(field: &str, value: Option<&str>) -> Result<Option<String>, String> {
+    let Some(value) = value.map(str::trim).filter(|v| !v.is_empty()) else {
+        return Ok(None);
+    };
+    let path = Path::new(value);
+    if !path.is_absolute() {
+        return Err(format!("{} must be an absolute path", field));
+    }
+    let metadata = std::fs::metadata(path).map_err(|e| format!("{} '{}' is not accessible: {}", field, value, e))?;
+    if !metadata.is_file() {
+        return Err(format!("{} '{}' is not a file", field, value));
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if metadata.permissions().mode() & 0o111 == 0 {
+            return Err(format!("{} '{}' is not executable", field, value));
+        }
+    }
+    Ok(Some(value.to_string()))
+}
+
+pub async fn tenant_overrides(pool: &PgPool, tenant_id: Uuid) -> Result<Option<ToolPathOverrides>, sqlx::Error> {
+    sqlx::query_as::<_, ToolPathOverrides>(
+        "SELECT skopeo_path, encjson_path, kube_build_app_path FROM tenants WHERE id = $1",
+    )
+    .bind(tenant_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Přepsání na environmentu a jeho tenant
+pub async fn environment_overrides(
+    pool: &PgPool,
+    environment_id: Uuid,
+) -> Result<Option<(Uuid, ToolPathOverrides)>, sqlx::Error> {
+    let row = sqlx::query_as::<_, (Uuid, Option<String>, Option<String>, Option<String>)>(
+        "SELECT tenant_id, skopeo_path, encjson_path, kube_build_app_path FROM environments WHERE id = $1",
+    )
+    .bind(environment_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row.map(|(tenant_id, skopeo_path, encjson_path, kube_build_app_path)| {
+        (
+            tenant_id,
+            ToolPathOverrides {
+                skopeo_path,
+                encjson_path,
+                kube_build_app_path,
+            },
+        )
+    }))
+}
+
+/// Přepsání platná pro environment (environment před tenantem); globální konfiguraci doplní volající
+pub async fn for_environment(pool: &PgPool, environment_id: Uuid) -> Result<ToolPathOverrides, sqlx::Error> {
+    let Some((tenant_id, environment)) = environment_overrides(pool, environment_id).await? else {
+        return Ok(ToolPathOverrides::default());
+    };
+    let tenant = tenant_overrides(pool, tenant_id).await?.unwrap_or_default();
+    Ok(environment.or(tenant))
+}
+
+/// Přepsání pro job bez environmentu - jen tenant
+pub async fn for_tenant(pool: &PgPool, tenant_id: Uuid) -> Result<ToolPathOverrides, sqlx::Error> {
+    Ok(tenant_overrides(pool, tenant_id).await?.unwrap_or_default())
+}
+
+/// Přepsání pro copy job: environment jobu, jinak tenant cílové registry
+pub async fn for_copy_job(
+    pool: &PgPool,
+    environment_id: Option<Uuid>,
+    target_registry_id: Uuid,
+) -> Result<ToolPathOverrides, sqlx::Error> {
+    if let Some(environment_id) = environment_id {
+        return for_environment(pool, environment_id).await;
+    }
+    let tenant_id: Option<Uuid> = sqlx::query_scalar("SELECT tenant_id FROM registries WHERE id = $1")
+        .bind(target_registry_id)
+        .fetch_optional(pool)
+        .await?;
+    match tenant_id {
+        Some(tenant_id) => for_tenant(pool, tenant_id).await,
+        None => Ok(ToolPathOverrides::default()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn defaults() -> ToolPathDefaults {
+        ToolPathDefaults {
+            skopeo_path: "skopeo".to_string(),
+            encjson_path: "encjson".to_string(),
+            kube_build_app_path: "kube_build_app".to_string(),
+        }
+    }
+
+    #[test]
+    fn environment_overrides_tenant_overrides_global() {
+        let tenant = ToolPathOverrides {
+            skopeo_path: Some("/opt/skopeo-1.9/skopeo".to_string()),
+            encjson_path: Some("/opt/tenant/encjson".to_string()),
+            kube_build_app_path: None,
+        };
+        let environment = ToolPathOverrides {
+            skopeo_path: Some("/opt/skopeo-1.4/skopeo".to_string()),
+            ..Default::default()
+        };
+
+        let paths = effective(Some(&environment), &tenant, &defaults());
+        assert_eq!(paths.skopeo_path.path, "/opt/skopeo-1.4/skopeo");
+        assert_eq!(paths.skopeo_path.source, SOURCE_ENVIRONMENT);
+        assert_eq!(paths.encjson_path.source, SOURCE_TENANT);
+        assert_eq!(paths.kube_build_app_path.path, "kube_build_app");
+        assert_eq!(paths.kube_build_app_path.source, SOURCE_GLOBAL);
+
+        let merged = environment.or(tenant);
+        assert_eq!(merged.skopeo_path.as_deref(), Some("/opt/skopeo-1.4/skopeo"));
+        assert_eq!(merged.encjson_path.as_deref(), Some("/opt/tenant/encjson"));
+        assert_eq!(merged.kube_build_app_path, None);
+    }
+
+    #[test]
+    fn tool_path_must_be_an_absolute_executable() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join("skopeo");
+        std::fs::write(&binary, "#!/bin/sh\n").unwrap();
+        let binary = binary.to_string_lossy().to_string();
+
+        assert_eq!(validate_tool_path("skopeo_path", None), Ok(None));
+        assert_eq!(validate_tool_path("skopeo_path", Some("  ")), Ok(None));
+        assert!(validate_tool_path("skopeo_path", Some("bin/skopeo")).unwrap_err().contains("absolute"));
+        assert!(validate_tool_path("skopeo_path", Some("/nonexistent/skopeo")).is_err());
+        let dir_path = dir.path().to_string_lossy().to_string();
+        assert!(validate_tool_path("skopeo_path", Some(&dir_path)).unwrap_err().contains("not a file"));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert!(validate_tool_path("skopeo_path", Some(&binary)).unwrap_err().contains("not executable"));
+            std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        assert_eq!(
+            validate_tool_path("skopeo_path", Some(&format!(" {} ", binary))),
+            Ok(Some(binary.clone()))
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Passed,
+    Warning,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationCheck {
+    pub check: String,
+    pub status: CheckStatus,
+    pub detail: Option<String>,
+}
+
+/// Výsledek validate-only deploy jobu (`deploy_jobs.validation_report`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ValidationReport {
+    /// false, pokud některá kontrola skončila `failed`
+    pub passed: bool,
+    pub checks: Vec<ValidationCheck>,
+    pub files_changed: usize,
+    pub images: usize,
+    pub diff_truncated: bool,
+}
+
+impl ValidationReport {
+    pub fn record(&mut self, check: &str, status: CheckStatus, detail: Option<String>) {
+        self.checks.push(ValidationCheck {
+            check: check.to_string(),
+            status,
+            detail,
+        });
+        self.passed = self.checks.iter().all(|c| c.status != CheckStatus::Failed);
+    }
+
+    pub fn failed_checks(&self) -> Vec<&str> {
+        self.checks
+            .iter()
+            .filter(|c| c.status == CheckStatus::Failed)
+            .map(|c| c.check.as_str())
+            .collect()
+    }
+}
+
+/// Validace vždy běží ve strict režimu release manifestu (`match_digest` -> `strict_digest`)
+pub fn strict_manifest_mode(mode: &str) -> String {
+    let normalized = mode.trim().to_lowercase();
+    if normalized.starts_with("strict") {
+        return normalized;
+    }
+    if normalized.ends_with("tag") {
+        "strict_tag".to_string()
+    } else {
+        "strict_digest".to_string()
+    }
+}
+
+/// Vyrenderované image, které nepochází z release manifestu (repo bez tagu/digestu)
+pub fn foreign_images<'a>(rendered: &'a [String], release_repos: &[String]) -> Vec<&'a str> {
+    let mut foreign: Vec<&str> = rendered
+        .iter()
+        .map(String::as_str)
+        .filter(|image| {
+            !release_repos.iter().any(|repo| {
+                image
+                    .strip_prefix(repo.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with(':') || rest.starts_with('@'))
+            })
+        })
+        .collect();
+    foreign.sort_unstable();
+    foreign.dedup();
+    foreign
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_mode_is_forced_strict() {
+        assert_eq!(strict_manifest_mode("match_digest"), "strict_digest");
+        assert_eq!(strict_manifest_mode("match_tag"), "strict_tag");
+        assert_eq!(strict_manifest_mode("strict_tag"), "strict_tag");
+        assert_eq!(strict_manifest_mode(""), "strict_digest");
+    }
+
+    #[test]
+    fn foreign_images_ignore_tags_and_digests() {
+        let rendered = vec![
+            "reg.example.com/app/api:1.2".to_string(),
+            "reg.example.com/app/web@sha256:abc".to_string(),
+            "reg.example.com/app/api-sidecar:1".to_string(),
+            "docker.io/library/busybox:1".to_string(),
+        ];
+        let repos = vec!["reg.example.com/app/api".to_string(), "reg.example.com/app/web".to_string()];
+        assert_eq!(
+            foreign_images(&rendered, &repos),
+            vec!["docker.io/library/busybox:1", "reg.example.com/app/api-sidecar:1"]
+        );
+    }
+
+    #[test]
+    fn report_fails_on_any_failed_check() {
+        let mut report = ValidationReport::default();
+        report.record("kubeconform", CheckStatus::Passed, None);
+        report.record("images", CheckStatus::Warning, Some("x".to_string()));
+        assert!(report.passed);
+        report.record("release_manifest", CheckStatus::Failed, Some("missing app".to_string()));
+        assert!(!report.passed);
+        assert_eq!(report.failed_checks(), vec!["release_manifest"]);
+    }
+}
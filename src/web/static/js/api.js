@@ -5,10 +5,15 @@
 // Získat BASE_PATH z window nebo použít default
 const BASE_PATH = window.BASE_PATH || '';
 const API_BASE = `${BASE_PATH}/api/v1`;
+// Stáří summary view, po které se job listy po vlastní změně čtou živě (SUMMARY_MAX_AGE_SECONDS)
+const JOB_LIST_FRESH_WINDOW_MS = 120 * 1000;
+// Requesty, které zakládají nebo mění copy / deploy joby
+const JOB_MUTATION_PATTERN = /\/copy(\/|$)|\/deploy\/jobs|^\/releases/;
 
 class ApiClient {
     constructor() {
         this.baseUrl = API_BASE;
+        this.jobsChangedAt = 0;
     }
 
     /**
@@ -26,6 +31,12 @@ class ApiClient {
 
         try {
             const response = await fetch(url, config);
+            if (options.onHeaders) {
+                options.onHeaders(response.headers);
+            }
+            if (response.ok && config.method && config.method !== 'GET' && JOB_MUTATION_PATTERN.test(endpoint)) {
+                this.jobsChangedAt = Date.now();
+            }
 
             // Handle non-JSON responses (např. prázdné 204)
             if (response.status === 204) {
@@ -138,6 +149,26 @@ class ApiClient {
         return this.get(`/tenants/${id}`);
     }
 
+    async getBaseImageUpdates({ tenantId = null, bundleId = null } = {}) {
+        const params = new URLSearchParams();
+        if (tenantId) params.set('tenant_id', tenantId);
+        if (bundleId) params.set('bundle_id', bundleId);
+        const query = params.toString();
+        return this.get(`/base-image-updates${query ? `?${query}` : ''}`);
+    }
+
+    async getExpiringCredentials({ tenantId = null, days = null } = {}) {
+        const params = new URLSearchParams();
+        if (tenantId) params.set('tenant_id', tenantId);
+        if (days !== null) params.set('days', days);
+        const query = params.toString();
+        return this.get(`/credentials/expiring${query ? `?${query}` : ''}`);
+    }
+
+    async getImagePolicies(tenantId) {
+        return this.get(`/tenants/${tenantId}/image-policies`);
+    }
+
     async createTenant(data) {
         return this.post('/tenants', data);
     }
@@ -290,13 +321,14 @@ class ApiClient {
 
     // ==================== COPY OPERATIONS ====================
 
-    async startCopyJob(bundleId, version, targetTag, timezoneOffsetMinutes = null, environmentId = null, sourceRegistryId = null, targetRegistryId = null) {
+    async startCopyJob(bundleId, version, targetTag, timezoneOffsetMinutes = null, environmentId = null, sourceRegistryId = null, targetRegistryId = null, overwrite = false) {
         return this.post(`/bundles/${bundleId}/versions/${version}/copy`, {
             target_tag: targetTag,
             timezone_offset_minutes: timezoneOffsetMinutes,
             environment_id: environmentId,
             source_registry_id: sourceRegistryId,
             target_registry_id: targetRegistryId,
+            overwrite: overwrite || null,
         });
     }
 
@@ -368,12 +400,28 @@ class ApiClient {
         return JSON.parse(text);
     }
 
-    async getCopyJobs() {
-        return this.get('/copy/jobs');
+    /**
+     * GET job listu; k poli přidá `dataSource` (`summary` / `live`) a `refreshedAt`.
+     * Po vlastní změně jobů (nebo s `fresh`) čte živá data místo summary view.
+     */
+    async getJobList(endpoint, fresh = false) {
+        const live = fresh || Date.now() - this.jobsChangedAt < JOB_LIST_FRESH_WINDOW_MS;
+        let headers = null;
+        const rows = await this.request(live ? `${endpoint}?fresh=true` : endpoint, {
+            method: 'GET',
+            onHeaders: (h) => { headers = h; },
+        }) || [];
+        rows.dataSource = (headers && headers.get('x-data-source')) || 'live';
+        rows.refreshedAt = headers ? headers.get('x-data-refreshed-at') : null;
+        return rows;
+    }
+
+    async getCopyJobs(fresh = false) {
+        return this.getJobList('/copy/jobs', fresh);
     }
 
-    async getDeployments() {
-        return this.get('/deploy/jobs');
+    async getDeployments(fresh = false) {
+        return this.getJobList('/deploy/jobs', fresh);
     }
 
     async getBundleDeployments(bundleId) {
@@ -499,6 +547,18 @@ class ApiClient {
         return this.getText(`/releases/${id}/manifest`);
     }
 
+    async getReleaseNotesMarkdown(id) {
+        return this.getText(`/releases/${id}/notes?format=markdown`);
+    }
+
+    async getReleaseDeliveries(id) {
+        return this.get(`/releases/${id}/deliveries`);
+    }
+
+    async redeliverReleaseManifest(id, destinationId) {
+        return this.post(`/releases/${id}/deliveries/${destinationId}`, {});
+    }
+
     // ==================== DEPLOY TARGETS ====================
 
     async getDeployTargets(tenantId) {
@@ -666,6 +726,10 @@ class ApiClient {
         return this.put(`/environments/${id}`, data);
     }
 
+    async previewEnvironmentUpdate(id, data) {
+        return this.put(`/environments/${id}?preview=true`, data);
+    }
+
     async deleteEnvironment(id) {
         return this.delete(`/environments/${id}`);
     }
@@ -756,6 +820,16 @@ class ApiClient {
         }
     }
 
+    async getDeployJobDiffFile(jobId, path) {
+        const response = await fetch(`${this.baseUrl}/deploy/jobs/${jobId}/diff?file=${encodeURIComponent(path)}`);
+        if (!response.ok) {
+            const error = await response.json().catch(() => ({ error: response.statusText }));
+            throw new Error(error.error || `HTTP ${response.status}`);
+        }
+        const data = await response.json();
+        return data ? data.diff_patch || '' : '';
+    }
+
     async getDeployJobDiff(jobId) {
         try {
             const response = await fetch(`${this.baseUrl}/deploy/jobs/${jobId}/diff`);
@@ -768,6 +842,36 @@ class ApiClient {
         }
     }
 
+    async getDeployJobEnv(jobId) {
+        try {
+            const response = await fetch(`${this.baseUrl}/deploy/jobs/${jobId}/env`);
+            if (!response.ok) return null;
+            return await response.json();
+        } catch (e) {
+            return null;
+        }
+    }
+
+    async getDeployJobReport(jobId) {
+        try {
+            const response = await fetch(`${this.baseUrl}/deploy/jobs/${jobId}/report`);
+            if (!response.ok) return null;
+            return await response.json();
+        } catch (e) {
+            return null;
+        }
+    }
+
+    async getDeployJobSteps(jobId) {
+        try {
+            const response = await fetch(`${this.baseUrl}/deploy/jobs/${jobId}/steps`);
+            if (!response.ok) return [];
+            return await response.json();
+        } catch (e) {
+            return [];
+        }
+    }
+
     async getDeployJobImages(jobId) {
         try {
             const response = await fetch(`${this.baseUrl}/deploy/jobs/${jobId}/images`);
@@ -25,6 +25,32 @@ function renderScopeNotice() {
     `;
 }
 
+// Původ dat job listu - hotové joby ze summary view, běžící a nové vždy živě
+function renderDataFreshness(rows, route) {
+    if (rows.dataSource !== 'summary' || !rows.refreshedAt) {
+        return '<span class="text-secondary small me-2"><i class="ti ti-bolt me-1"></i>Live data</span>';
+    }
+    const time = new Date(rows.refreshedAt).toLocaleTimeString('cs-CZ');
+    return `
+        <span class="text-secondary small me-2" title="Finished jobs come from a summary refreshed in the background; running and new jobs are always live">
+            <i class="ti ti-clock me-1"></i>Summary as of ${escapeHtml(time)}
+        </span>
+        <a class="btn btn-outline-secondary me-2" href="#${route}?fresh=1" data-fresh-reload>
+            <i class="ti ti-refresh"></i>
+            Load live
+        </a>
+    `;
+}
+
+// Opakované "Load live" na stejném hashi nevyvolá hashchange
+document.addEventListener('click', (event) => {
+    const link = event.target.closest('[data-fresh-reload]');
+    if (link && window.location.hash === link.getAttribute('href')) {
+        event.preventDefault();
+        router.handleRoute();
+    }
+});
+
 function requireWriteAccess(actionLabel = 'This action') {
     const app = getApp();
     if (!app?.canWrite()) {
@@ -54,6 +80,54 @@ function escapeHtml(value) {
         .replace(/'/g, '&#39;');
 }
 
+// Badge pro `credentials_expiry` (expiring_soon / expired) z list endpointů
+function credentialExpiryBadge(expiry) {
+    if (expiry === 'expired') return '<span class="badge bg-danger text-danger-fg">Credentials expired</span>';
+    if (expiry === 'expiring_soon') return '<span class="badge bg-warning text-warning-fg">Credentials expire soon</span>';
+    return '';
+}
+
+// Výsledek `?preview=true` – změny polí a deploye, které by novou konfiguraci použily
+function renderConfigPreview(preview) {
+    const format = (value) => value === null || value === undefined
+        ? '<span class="text-secondary">-</span>'
+        : `<code>${escapeHtml(typeof value === 'string' ? value : JSON.stringify(value))}</code>`;
+    const changes = preview.changes || [];
+    const pendingJobs = preview.affected?.pending_jobs || [];
+    const targetEnvs = preview.affected?.target_envs || [];
+    return `
+        <div class="alert alert-info mb-0">
+            <h4 class="alert-title">Preview (not saved)</h4>
+            ${changes.length === 0 ? '<div>No changes.</div>' : `
+                <table class="table table-sm mb-2">
+                    <thead><tr><th>Field</th><th>Before</th><th>After</th></tr></thead>
+                    <tbody>
+                        ${changes.map(change => `
+                            <tr>
+                                <td>${escapeHtml(change.field)}</td>
+                                <td>${format(change.before)}</td>
+                                <td>${format(change.after)}</td>
+                            </tr>
+                        `).join('')}
+                    </tbody>
+                </table>
+            `}
+            <div class="fw-semibold mt-2">Affected deploys</div>
+            ${pendingJobs.length === 0 && targetEnvs.length === 0 ? '<div class="text-secondary">None</div>' : ''}
+            ${pendingJobs.map(job => `
+                <div>Pending job: ${escapeHtml(job.release_name)} → ${escapeHtml(job.environment_slug)}${job.deploy_target_name ? ` (${escapeHtml(job.deploy_target_name)})` : ''}${job.dry_run ? ' · dry run' : ''}</div>
+            `).join('')}
+            ${targetEnvs.map(entry => `
+                <div>
+                    ${escapeHtml(entry.deploy_target_name)} / ${escapeHtml(entry.environment_slug)}
+                    ${entry.allow_auto_release ? '<span class="badge bg-blue-lt text-blue-fg ms-1">auto-release</span>' : ''}
+                    <span class="text-secondary small">${entry.last_deployed_release ? `last deployed: ${escapeHtml(entry.last_deployed_release)}` : 'never deployed'}</span>
+                </div>
+            `).join('')}
+        </div>
+    `;
+}
+
 function formatBytes(value) {
     const num = Number(value || 0);
     if (!Number.isFinite(num) || num < 0) return '-';
@@ -376,6 +450,37 @@ function collectEnvironmentVarMappings() {
     return mappings;
 }
 
+function parseListInput(value) {
+    return String(value || '')
+        .split(/[,\n]/)
+        .map(item => item.trim())
+        .filter(Boolean);
+}
+
+function parseEnvOverrides(value) {
+    const overrides = {};
+    String(value || '').split('\n').forEach(line => {
+        const trimmed = line.trim();
+        if (!trimmed || trimmed.startsWith('#')) return;
+        const eq = trimmed.indexOf('=');
+        if (eq <= 0) {
+            throw new Error(`Invalid env override (expected KEY=VALUE): ${trimmed}`);
+        }
+        overrides[trimmed.slice(0, eq).trim()] = trimmed.slice(eq + 1);
+    });
+    return overrides;
+}
+
+function collectReleaseChannels() {
+    return Array.from(document.querySelectorAll('.release-channel-option:checked')).map(input => input.value);
+}
+
+function renderReleaseChannelBadge(channel) {
+    const colors = { rc: 'bg-yellow-lt text-yellow-fg', stable: 'bg-green-lt text-green-fg', hotfix: 'bg-red-lt text-red-fg' };
+    if (!channel) return '';
+    return `<span class="badge ${colors[channel] || 'bg-secondary-lt text-secondary-fg'} ms-2">${channel}</span>`;
+}
+
 function collectEnvironmentExtraVars() {
     const extra = [];
     const rows = document.querySelectorAll('#extra-env-vars [data-extra-var-index]');
@@ -626,7 +731,7 @@ router.on('/', async () => {
 
     try {
         // Načíst všechna data paralelně
-        const [tenants, bundles, releases, registries, copyJobs, deployJobs, gitRepos] = await Promise.all([
+        const [tenants, bundles, releases, registries, copyJobs, deployJobs, gitRepos, expiringCredentials] = await Promise.all([
             api.getTenants(),
             api.getBundles(),
             api.getReleases(),
@@ -634,6 +739,7 @@ router.on('/', async () => {
             api.getCopyJobs(),
             api.getDeployments(),
             api.getGitRepos(),
+            api.getExpiringCredentials().catch(() => []),
         ]);
 
         // Spočítat registry podle rolí
@@ -667,8 +773,33 @@ router.on('/', async () => {
             .sort((a, b) => new Date(b.started_at) - new Date(a.started_at))
             .slice(0, 5);
 
+        const credentialLink = (credential) => credential.credential_kind === 'git_repository'
+            ? `#/git-repos/${credential.credential_id}/edit`
+            : `#/registries/${credential.registry_id}`;
+        const credentialLabel = (credential) => {
+            const kind = credential.credential_kind === 'git_repository' ? 'Git repo' : 'Registry';
+            const env = credential.environment_slug ? ` (${credential.environment_slug})` : '';
+            const when = credential.expiry === 'expired'
+                ? 'expired'
+                : `expires in ${credential.days_left} day${credential.days_left === 1 ? '' : 's'}`;
+            return `${kind} <a href="${credentialLink(credential)}">${escapeHtml(credential.name)}</a>${escapeHtml(env)} ${when}`;
+        };
+
         content.innerHTML = `
             ${renderScopeNotice()}
+            ${expiringCredentials.length > 0 ? `
+                <div class="alert ${expiringCredentials.some(c => c.expiry === 'expired') ? 'alert-danger' : 'alert-warning'} mb-4">
+                    <div class="d-flex">
+                        <i class="ti ti-key me-2"></i>
+                        <div>
+                            <h4 class="alert-title">Stored credentials expire soon</h4>
+                            <ul class="mb-0">
+                                ${expiringCredentials.map(c => `<li>${credentialLabel(c)}</li>`).join('')}
+                            </ul>
+                        </div>
+                    </div>
+                </div>
+            ` : ''}
             <!-- Stats Row -->
             <div class="row row-deck row-cards mb-4">
                 <div class="col-sm-6 col-lg-3">
@@ -1267,7 +1398,7 @@ router.on('/tenants/:id', async (params) => {
 
     try {
         const canWrite = getApp()?.canWrite?.() || false;
-        const [tenant, registries, bundles, gitRepos, environments, argocdInstances, kubernetesInstances] = await Promise.all([
+        const [tenant, registries, bundles, gitRepos, environments, argocdInstances, kubernetesInstances, imagePolicies] = await Promise.all([
             api.getTenant(params.id),
             api.getRegistries(params.id),
             api.getBundles(params.id),
@@ -1275,6 +1406,7 @@ router.on('/tenants/:id', async (params) => {
             api.getEnvironments(params.id),
             api.getArgocdInstances(params.id),
             api.getKubernetesInstances(params.id),
+            api.getImagePolicies(params.id).catch(() => []),
         ]);
         const argocdAppsByEnv = await Promise.all(
             environments.map(async env => ({
@@ -1421,6 +1553,41 @@ router.on('/tenants/:id', async (params) => {
                         </div>
                     </div>
 
+                    ${imagePolicies.length > 0 ? `
+                        <div class="card mb-3">
+                            <div class="card-header">
+                                <h3 class="card-title">Image Policies</h3>
+                                <div class="card-actions">
+                                    <span class="text-secondary small">Managed by admins via API</span>
+                                </div>
+                            </div>
+                            <div class="table-responsive">
+                                <table class="table table-vcenter card-table">
+                                    <thead>
+                                        <tr>
+                                            <th>Name</th>
+                                            <th>Effect</th>
+                                            <th>Registry</th>
+                                            <th>Repository</th>
+                                            <th>Environments</th>
+                                        </tr>
+                                    </thead>
+                                    <tbody>
+                                        ${imagePolicies.map(policy => `
+                                            <tr class="${policy.is_active ? '' : 'text-secondary'}">
+                                                <td>${escapeHtml(policy.name)}${policy.is_active ? '' : ' <span class="badge">inactive</span>'}</td>
+                                                <td><span class="badge ${policy.effect === 'deny' ? 'bg-red-lt' : 'bg-green-lt'}">${policy.effect}</span></td>
+                                                <td><code class="small">${escapeHtml(policy.registry_pattern || '*')}</code></td>
+                                                <td><code class="small">${escapeHtml(policy.repository_pattern || '*')}</code></td>
+                                                <td><code class="small">${escapeHtml(policy.environment_pattern || '*')}</code></td>
+                                            </tr>
+                                        `).join('')}
+                                    </tbody>
+                                </table>
+                            </div>
+                        </div>
+                    ` : ''}
+
                     <div class="row">
                         <div class="col-md-6">
                             <div class="card mb-3">
@@ -1712,7 +1879,8 @@ router.on('/tenants/:id', async (params) => {
                                                     <div>${repo.name}</div>
                                                     <div class="text-secondary small"><code class="small">${repo.repo_url}</code></div>
                                                 </div>
-                                                <span class="badge bg-secondary-lt text-secondary-fg">${repo.default_branch || 'main'}</span>
+                                                ${credentialExpiryBadge(repo.credentials_expiry)}
+                                                <span class="badge bg-secondary-lt text-secondary-fg ms-1">${repo.default_branch || 'main'}</span>
                                             </div>
                                         </a>
                                     `).join('')}
@@ -2113,6 +2281,8 @@ router.on('/registries', async () => {
                                     </td>
                                     <td>
                                         <span class="badge" :class="reg.is_active ? 'bg-success text-success-fg' : 'bg-secondary text-secondary-fg'" x-text="reg.is_active ? 'Active' : 'Inactive'"></span>
+                                        <span class="badge bg-warning text-warning-fg ms-1" x-show="reg.credentials_expiry === 'expiring_soon'">Credentials expire soon</span>
+                                        <span class="badge bg-danger text-danger-fg ms-1" x-show="reg.credentials_expiry === 'expired'">Credentials expired</span>
                                     </td>
                                 </tr>
                             </template>
@@ -2208,7 +2378,10 @@ router.on('/git-repos', async () => {
                                     <td><a href="#/git-repos/${repo.id}/edit"><strong>${repo.name}</strong></a></td>
                                     <td>${tenantMap[repo.tenant_id]?.name || 'Unknown'}</td>
                                     <td><code class="small">${repo.repo_url}</code></td>
-                                    <td><span class="badge bg-secondary-lt text-secondary-fg">${repo.default_branch || 'main'}</span></td>
+                                    <td>
+                                        <span class="badge bg-secondary-lt text-secondary-fg">${repo.default_branch || 'main'}</span>
+                                        ${credentialExpiryBadge(repo.credentials_expiry)}
+                                    </td>
                                 </tr>
                             `).join('')}
                         </tbody>
@@ -3969,6 +4142,12 @@ router.on('/registries/:id', async (params) => {
                                     '<span class="badge bg-success text-success-fg">Active</span>' :
                                     '<span class="badge bg-secondary text-secondary-fg">Inactive</span>'}
                             </div>
+                            <div class="mb-3">
+                                <div class="text-secondary mb-1">Credentials Expire</div>
+                                ${registry.credentials_expire_at
+                                    ? `${new Date(registry.credentials_expire_at).toLocaleDateString('cs-CZ')} ${credentialExpiryBadge(registry.credentials_expiry)}`
+                                    : '-'}
+                            </div>
                             <div class="mb-3">
                                 <div class="text-secondary mb-1">Description</div>
                                 <div>${registry.description || '-'}</div>
@@ -4120,6 +4299,8 @@ router.on('/environments/new', async (params, query) => {
                 }
                 data.release_env_var_mappings = collectEnvironmentVarMappings();
                 data.extra_env_vars = collectEnvironmentExtraVars();
+                data.job_env_override_allowlist = parseListInput(data.job_env_override_allowlist);
+                data.release_channels = collectReleaseChannels();
                 await api.createEnvironment(tenantId, data);
                 getApp().showSuccess('Environment created successfully');
                 router.navigate(`/tenants/${tenantId}`);
@@ -4255,6 +4436,13 @@ router.on('/environments/:id/edit', async (params) => {
                 }
                 data.release_env_var_mappings = collectEnvironmentVarMappings();
                 data.extra_env_vars = collectEnvironmentExtraVars();
+                data.job_env_override_allowlist = parseListInput(data.job_env_override_allowlist);
+                data.release_channels = collectReleaseChannels();
+                if (e.submitter?.dataset.preview) {
+                    const preview = await api.previewEnvironmentUpdate(params.id, data);
+                    document.getElementById('environment-preview').innerHTML = renderConfigPreview(preview);
+                    return;
+                }
                 await api.updateEnvironment(params.id, data);
                 getApp().showSuccess('Environment updated successfully');
                 router.navigate(`/tenants/${environment.tenant_id}`);
@@ -4599,7 +4787,7 @@ router.on('/bundles/:id', async (params) => {
         const canWrite = getApp()?.canWrite?.() || false;
         const canDeploy = getApp()?.canDeploy?.() || false;
         const bundle = await api.getBundle(params.id);
-        const [versions, copyJobs, releases, deployments, tenant, sourceRegistry, registries, environments] = await Promise.all([
+        const [versions, copyJobs, releases, deployments, tenant, sourceRegistry, registries, environments, baseImageUpdates] = await Promise.all([
             api.getBundleVersions(params.id),
             api.getBundleCopyJobs(params.id),
             api.getReleases(),
@@ -4608,7 +4796,9 @@ router.on('/bundles/:id', async (params) => {
             bundle.source_registry_id ? api.getRegistry(bundle.source_registry_id).catch(() => null) : null,
             api.getRegistries().catch(() => []),
             bundle.tenant_id ? api.getEnvironments(bundle.tenant_id).catch(() => []) : Promise.resolve([]),
+            api.getBaseImageUpdates({ bundleId: params.id }).catch(() => null),
         ]);
+        const upstreamUpdates = baseImageUpdates?.updates || [];
 
         const registryMap = {};
         (registries || []).forEach(r => {
@@ -4652,6 +4842,26 @@ router.on('/bundles/:id', async (params) => {
                 </div>
             `}
 
+            ${upstreamUpdates.length > 0 ? `
+                <div class="alert alert-warning">
+                    <div class="d-flex gap-2">
+                        <i class="ti ti-refresh-alert"></i>
+                        <div>
+                            <strong>Upstream images changed since the last copy - a rebuild/re-copy is due.</strong>
+                            <ul class="mb-0 mt-1">
+                                ${upstreamUpdates.map(update => `
+                                    <li>
+                                        ${escapeHtml(update.app_name)}${update.container_name ? ` / ${escapeHtml(update.container_name)}` : ''}:
+                                        <code class="small">${escapeHtml(update.source_ref)}</code>
+                                        <span class="text-secondary small">checked ${new Date(update.checked_at).toLocaleString('cs-CZ')}</span>
+                                    </li>
+                                `).join('')}
+                            </ul>
+                        </div>
+                    </div>
+                </div>
+            ` : ''}
+
             <div class="row">
                 <div class="col-12">
                     <div class="card mb-3">
@@ -4904,7 +5114,15 @@ router.on('/bundles/:id', async (params) => {
                                             })() : '-'}</td>
                                             <td>
                                                 ${job.is_release_job ? `
-                                                    <span class="badge bg-purple-lt text-purple-fg">image release</span>
+                                                    <div class="d-flex flex-column gap-1">
+                                                        <span class="badge bg-purple-lt text-purple-fg">image release</span>
+                                                        ${job.status === 'success' && releaseByCopyJobId.get(job.job_id) ? `
+                                                            <button type="button" class="btn btn-sm btn-outline-secondary patch-release-btn" data-job-id="${job.job_id}" ${canWrite ? '' : 'disabled title="Write role required"'}>
+                                                                <i class="ti ti-adjustments"></i>
+                                                                Patch Release
+                                                            </button>
+                                                        ` : ''}
+                                                    </div>
                                                 ` : job.status === 'success' ? `
                                                     <div class="d-flex flex-column gap-1">
                                                         ${canWrite ? `
@@ -5046,6 +5264,18 @@ router.on('/bundles/:id', async (params) => {
             });
         });
 
+        document.querySelectorAll('.patch-release-btn').forEach(btn => {
+            btn.addEventListener('click', async () => {
+                if (!getApp()?.canWrite?.()) {
+                    getApp().showError('Write role required.');
+                    return;
+                }
+                const jobId = btn.getAttribute('data-job-id');
+                const sourceJobs = copyJobs.filter(job => !job.is_release_job && job.status === 'success');
+                await runPatchReleaseFromJob(jobId, releaseByCopyJobId.get(jobId), sourceJobs);
+            });
+        });
+
         document.getElementById('archive-bundle-btn')?.addEventListener('click', async (event) => {
             if (!getApp()?.canWrite?.()) {
                 getApp().showError('Write role required.');
@@ -5284,6 +5514,7 @@ router.on('/bundles/:id/copy', async (params) => {
             target_image: m.target_image,
             app_name: m.app_name,
             container_name: m.container_name,
+            owner: m.owner,
         }));
 
         const renderWizard = () => {
@@ -5331,6 +5562,7 @@ router.on('/bundles/:id/copy', async (params) => {
                             target_image: m.target_image,
                             app_name: m.app_name,
                             container_name: m.container_name,
+                            owner: m.owner,
                         }));
                         renderWizard();
                     } catch (error) {
@@ -5526,6 +5758,7 @@ router.on('/bundles/:id/versions/new', async (params) => {
                 target_image: m.target_image,
                 app_name: m.app_name,
                 container_name: m.container_name,
+                owner: m.owner,
             })),
         };
 
@@ -5585,12 +5818,18 @@ router.on('/bundles/:id/versions/new', async (params) => {
                                                        value="${mapping.app_name || ''}"
                                                        placeholder="app name">
                                             </div>
-                                            <div class="col-md-5">
+                                            <div class="col-md-3">
                                                 <label class="form-label">Container Name</label>
                                                 <input type="text" class="form-control form-control-sm mapping-container-name"
                                                        value="${mapping.container_name || ''}"
                                                        placeholder="container name (optional)">
                                             </div>
+                                            <div class="col-md-3">
+                                                <label class="form-label">Owner</label>
+                                                <input type="text" class="form-control form-control-sm mapping-owner"
+                                                       value="${escapeHtml(mapping.owner || '')}"
+                                                       placeholder="user or team (optional)">
+                                            </div>
                                         </div>
                                     </div>
                                 </div>
@@ -5612,7 +5851,7 @@ router.on('/bundles/:id/versions/new', async (params) => {
                             </button>
                         </div>
                         <div class="text-secondary small mt-2">
-                            Import format: <code>source_image;source_tag;target_image;app_name;container_name</code>
+                            Import format: <code>source_image;source_tag;target_image;app_name;container_name;owner</code>
                         </div>
 
                         ${state.mappings.length === 0 ? `
@@ -5652,6 +5891,7 @@ router.on('/bundles/:id/versions/new', async (params) => {
                     const targetImage = card.querySelector('.mapping-target-image')?.value || '';
                     let appName = card.querySelector('.mapping-app-name')?.value || '';
                     const containerName = card.querySelector('.mapping-container-name')?.value || '';
+                    const owner = card.querySelector('.mapping-owner')?.value.trim() || '';
                     if (!appName && targetImage) {
                         const parts = targetImage.split('/');
                         appName = parts[parts.length - 1] || '';
@@ -5662,6 +5902,7 @@ router.on('/bundles/:id/versions/new', async (params) => {
                         target_image: targetImage,
                         app_name: appName,
                         container_name: containerName,
+                        owner,
                     });
                 });
                 state.mappings = mappings;
@@ -5677,6 +5918,7 @@ router.on('/bundles/:id/versions/new', async (params) => {
                         target_image: '',
                         app_name: '',
                         container_name: '',
+                        owner: '',
                     });
                     render();
                 });
@@ -5840,6 +6082,7 @@ router.on('/bundles/:id/versions/:version', async (params) => {
                                 <th>Target Image</th>
                                 <th>App</th>
                                 <th>Container</th>
+                                <th>Owner</th>
                             </tr>
                         </thead>
                         <tbody>
@@ -5857,6 +6100,7 @@ router.on('/bundles/:id/versions/:version', async (params) => {
                                     </td>
                                     <td>${mapping.app_name || '-'}</td>
                                     <td>${mapping.container_name || '-'}</td>
+                                    <td>${mapping.owner ? escapeHtml(mapping.owner) : '-'}</td>
                                 </tr>
                             `}).join('')}
                         </tbody>
@@ -6022,6 +6266,7 @@ router.on('/releases', async () => {
                                                 <a href="#/releases/${release.id}"><strong>${release.release_id}</strong></a>
                                                 ${isAuto ? '<span class="badge bg-azure-lt text-azure-fg ms-2">auto</span>' : ''}
                                                 <span class="badge bg-azure-lt text-azure-fg ms-2">${release.source_ref_mode || 'tag'}</span>
+                                                ${renderReleaseChannelBadge(release.channel)}
                                                 <div class="text-secondary small mt-1">
                                                     ${
                                                         release.environment_id && environmentMap.get(release.environment_id)
@@ -6350,10 +6595,12 @@ router.on('/releases/:id', async (params) => {
     content.innerHTML = '<div class="text-center py-5"><div class="spinner-border"></div></div>';
 
     try {
-        const [release, manifest, deployJobs] = await Promise.all([
+        const [release, manifest, deployJobs, releaseNotes, deliveries] = await Promise.all([
             api.getRelease(params.id),
             api.getReleaseManifest(params.id),
             api.getReleaseDeployJobs(params.id),
+            api.getReleaseNotesMarkdown(params.id).catch(() => ''),
+            api.getReleaseDeliveries(params.id).catch(() => []),
         ]);
         const copyJob = release.copy_job_id ? await api.getCopyJobStatus(release.copy_job_id).catch(() => null) : null;
         const bundle = copyJob?.bundle_id ? await api.getBundle(copyJob.bundle_id).catch(() => null) : null;
@@ -6386,6 +6633,7 @@ router.on('/releases/:id', async (params) => {
                         <i class="ti ti-rocket me-2"></i>
                         ${release.release_id}
                         ${release.is_auto ? '<span class="badge bg-azure-lt text-azure-fg ms-2">auto</span>' : ''}
+                        ${renderReleaseChannelBadge(release.channel)}
                     </h3>
                     <div class="card-actions">
                         <button class="btn btn-sm btn-outline-primary" id="copy-release-images-btn" ${canWrite ? '' : 'disabled'} title="${canWrite ? '' : 'Developer or admin role required'}">
@@ -6402,6 +6650,7 @@ router.on('/releases/:id', async (params) => {
                         <div>${targetRegistry?.base_url ? `Target: <code>${targetRegistry.base_url}${targetRegistry.default_project_path ? ` (path: ${targetRegistry.default_project_path})` : ''}</code>` : 'Target: -'}</div>
                         <div>Environment: ${environment ? `<span class="badge" style="${environment.color ? `background:${environment.color};color:#fff;` : ''}">${environment.name}</span>` : '-'}</div>
                         <div>${release.copy_job_id ? `Copy Job: <a href="#/copy-jobs/${release.copy_job_id}"><code>${release.copy_job_id}</code></a>` : 'Copy Job: -'}</div>
+                        ${release.base_release_id ? `<div>Patched from: <a href="#/releases/${release.base_release_id}"><code>${release.base_release_id}</code></a></div>` : ''}
                     </div>
                     <dl class="row mb-0">
                         <dt class="col-4">Notes:</dt>
@@ -6434,6 +6683,66 @@ router.on('/releases/:id', async (params) => {
                 </div>
             </div>
 
+            ${deliveries.length > 0 ? `
+                <div class="card mb-3">
+                    <div class="card-header">
+                        <h3 class="card-title">Manifest Deliveries</h3>
+                    </div>
+                    <div class="table-responsive">
+                        <table class="table table-vcenter card-table">
+                            <thead>
+                                <tr>
+                                    <th>Destination</th>
+                                    <th>Status</th>
+                                    <th>Attempts</th>
+                                    <th>Location</th>
+                                    <th>Updated</th>
+                                    <th class="w-1"></th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                ${deliveries.map(delivery => `
+                                    <tr>
+                                        <td>
+                                            ${delivery.destination_name}
+                                            <span class="badge bg-azure-lt text-azure-fg ms-2">${delivery.kind}</span>
+                                        </td>
+                                        <td>
+                                            <span class="badge ${
+                                                delivery.status === 'success' ? 'bg-success text-success-fg' :
+                                                delivery.status === 'failed' ? 'bg-danger text-danger-fg' :
+                                                delivery.status === 'in_progress' ? 'bg-info text-info-fg' :
+                                                'bg-warning text-warning-fg'
+                                            }">${delivery.status}</span>
+                                            ${delivery.error_message ? `<div class="text-danger small mt-1">${escapeHtml(delivery.error_message)}</div>` : ''}
+                                        </td>
+                                        <td>${delivery.attempts}</td>
+                                        <td>${delivery.location ? `<code class="small">${escapeHtml(delivery.kind === 'git' ? delivery.location.slice(0, 8) : delivery.location)}</code>` : '-'}</td>
+                                        <td>${new Date(delivery.updated_at).toLocaleString('cs-CZ')}</td>
+                                        <td>
+                                            <button class="btn btn-sm btn-outline-primary redeliver-manifest-btn" data-destination-id="${delivery.destination_id}" ${canWrite ? '' : 'disabled'}>
+                                                Redeliver
+                                            </button>
+                                        </td>
+                                    </tr>
+                                `).join('')}
+                            </tbody>
+                        </table>
+                    </div>
+                </div>
+            ` : ''}
+
+            ${releaseNotes ? `
+                <div class="card mb-3">
+                    <div class="card-header">
+                        <h3 class="card-title">Release Notes by Owner</h3>
+                    </div>
+                    <div class="card-body">
+                        <pre class="manifest-code" id="release-notes-content"></pre>
+                    </div>
+                </div>
+            ` : ''}
+
             <div class="alert alert-info">
                 <i class="ti ti-info-circle"></i>
                 Build Manifests builds deployment manifests in <code>tsm-deploy/deploy/&lt;env&gt;</code> for this release.
@@ -6521,6 +6830,8 @@ router.on('/releases/:id', async (params) => {
         `;
 
         document.getElementById('manifest-content').textContent = manifest;
+        const releaseNotesEl = document.getElementById('release-notes-content');
+        if (releaseNotesEl) releaseNotesEl.textContent = releaseNotes;
 
         // Copy manifest handler
         document.getElementById('copy-manifest-btn').addEventListener('click', async () => {
@@ -6562,6 +6873,19 @@ router.on('/releases/:id', async (params) => {
             }
         });
 
+        document.querySelectorAll('.redeliver-manifest-btn').forEach(btn => {
+            btn.addEventListener('click', async () => {
+                if (!requireWriteAccess('Redeliver manifest')) return;
+                try {
+                    await api.redeliverReleaseManifest(release.id, btn.dataset.destinationId);
+                    getApp().showSuccess('Manifest delivery queued');
+                    router.handleRoute();
+                } catch (error) {
+                    getApp().showError(error.message);
+                }
+            });
+        });
+
         const buildDeployBtn = document.getElementById('build-deploy-btn');
         if (buildDeployBtn) {
             buildDeployBtn.addEventListener('click', async () => {
@@ -6597,13 +6921,18 @@ router.on('/deploy-jobs/:id', async (params) => {
 
     try {
         const canDeploy = getApp()?.canDeploy?.() || false;
-        const [job, logHistory, diffInfo, imageRows, inventory] = await Promise.all([
+        const [job, logHistory, diffInfo, imageRows, inventory, steps, jobEnv] = await Promise.all([
             api.getDeployJob(params.id),
             api.getDeployJobLogHistory(params.id),
             api.getDeployJobDiff(params.id),
             api.getDeployJobImages(params.id),
             api.getDeployJobInventory(params.id).catch(() => null),
+            api.getDeployJobSteps(params.id),
+            api.getDeployJobEnv(params.id),
         ]);
+        const report = job.validate_only
+            ? await api.getDeployJobReport(params.id).catch(() => null)
+            : null;
         const environment = job.environment_id
             ? await api.getEnvironment(job.environment_id).catch(() => null)
             : null;
@@ -6637,6 +6966,7 @@ router.on('/deploy-jobs/:id', async (params) => {
                         <i class="ti ti-cloud-upload me-2"></i>
                         Build Job Monitor
                         ${job.is_auto ? '<span class="badge bg-azure-lt text-azure-fg ms-2">auto</span>' : ''}
+                        ${job.validate_only ? '<span class="badge bg-purple-lt text-purple-fg ms-2">validate-only</span>' : ''}
                     </h3>
                     ${job.status === 'pending' ? `
                         <div class="card-actions">
@@ -6679,10 +7009,131 @@ router.on('/deploy-jobs/:id', async (params) => {
 
                         <dt class="col-4">Commit:</dt>
                         <dd class="col-8">${job.commit_sha ? `<code>${job.commit_sha}</code>` : '-'}</dd>
+
+                        ${job.env_overrides_by ? `
+                        <dt class="col-4">Env overrides:</dt>
+                        <dd class="col-8">
+                            ${Object.entries(job.env_overrides || {}).map(([key, value]) => `<code>${escapeHtml(key)}=${escapeHtml(value)}</code>`).join(' ')}
+                            <span class="text-secondary small ms-1">by ${escapeHtml(job.env_overrides_by)}</span>
+                        </dd>
+                        ` : ''}
                     </dl>
                 </div>
             </div>
 
+            ${report ? `
+            <div class="card mb-3">
+                <div class="card-header">
+                    <h3 class="card-title">Validation Report</h3>
+                    <div class="card-actions">
+                        <span class="badge ${report.passed ? 'bg-success text-success-fg' : 'bg-danger text-danger-fg'}">${report.passed ? 'passed' : 'failed'}</span>
+                    </div>
+                </div>
+                <div class="card-body border-bottom py-2 text-secondary small">
+                    Files changed: ${report.files_changed}${report.diff_truncated ? ' (diff truncated)' : ''} &middot; Images: ${report.images}
+                </div>
+                <div class="table-responsive">
+                    <table class="table table-vcenter card-table">
+                        <thead>
+                            <tr>
+                                <th>Check</th>
+                                <th>Status</th>
+                                <th>Detail</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            ${report.checks.map(check => `
+                            <tr>
+                                <td><code>${escapeHtml(check.check)}</code></td>
+                                <td><span class="badge ${
+                                    check.status === 'passed' ? 'bg-success text-success-fg' :
+                                    check.status === 'failed' ? 'bg-danger text-danger-fg' :
+                                    check.status === 'warning' ? 'bg-warning text-warning-fg' :
+                                    'bg-secondary text-secondary-fg'
+                                }">${escapeHtml(check.status)}</span></td>
+                                <td class="text-secondary small">${check.detail ? escapeHtml(check.detail) : ''}</td>
+                            </tr>
+                            `).join('')}
+                        </tbody>
+                    </table>
+                </div>
+            </div>
+            ` : ''}
+
+            ${steps.length ? `
+            <div class="card mb-3">
+                <div class="card-header">
+                    <h3 class="card-title">Steps</h3>
+                </div>
+                <div class="table-responsive">
+                    <table class="table table-vcenter card-table">
+                        <thead>
+                            <tr>
+                                <th>Step</th>
+                                <th>Status</th>
+                                <th>Duration</th>
+                                <th>Error</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            ${steps.map(step => `
+                            <tr>
+                                <td><code>${escapeHtml(step.step)}</code></td>
+                                <td><span class="badge ${
+                                    step.status === 'success' ? 'bg-success text-success-fg' :
+                                    step.status === 'failed' ? 'bg-danger text-danger-fg' :
+                                    step.status === 'warning' ? 'bg-warning text-warning-fg' :
+                                    step.status === 'in_progress' ? 'bg-info text-info-fg' :
+                                    'bg-secondary text-secondary-fg'
+                                }">${escapeHtml(step.status)}</span></td>
+                                <td>${step.duration_ms != null ? `${(step.duration_ms / 1000).toFixed(1)} s` : '-'}</td>
+                                <td class="text-secondary small">${step.error_line ? escapeHtml(step.error_line) : ''}</td>
+                            </tr>
+                            `).join('')}
+                        </tbody>
+                    </table>
+                </div>
+            </div>
+            ` : ''}
+
+            ${jobEnv && (jobEnv.release_env_var_mappings.length || jobEnv.extra_env_vars.length) ? `
+            <div class="card mb-3">
+                <div class="card-header">
+                    <h3 class="card-title">Environment Variables</h3>
+                    <div class="card-actions text-secondary small">
+                        ${jobEnv.snapshot ? 'resolved at job start' : 'resolved from current configuration'}
+                    </div>
+                </div>
+                <div class="table-responsive">
+                    <table class="table table-vcenter card-table">
+                        <thead>
+                            <tr>
+                                <th>Variable</th>
+                                <th>Value</th>
+                                <th>Layer</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            ${jobEnv.release_env_var_mappings.map(mapping => `
+                            <tr>
+                                <td><code>${escapeHtml(mapping.target_key)}</code></td>
+                                <td><code>${escapeHtml(jobEnv.effective[mapping.target_key] ?? mapping.source_key)}</code> <span class="text-secondary small">from ${escapeHtml(mapping.source_key)}</span></td>
+                                <td>${renderEnvLayer(mapping)}</td>
+                            </tr>
+                            `).join('')}
+                            ${jobEnv.extra_env_vars.map(variable => `
+                            <tr>
+                                <td><code>${escapeHtml(variable.key)}</code></td>
+                                <td><code>${escapeHtml(variable.value)}</code></td>
+                                <td>${renderEnvLayer(variable)}</td>
+                            </tr>
+                            `).join('')}
+                        </tbody>
+                    </table>
+                </div>
+            </div>
+            ` : ''}
+
             <div class="card">
                 <div class="card-header">
                     <h3 class="card-title">${job.status === 'in_progress' ? 'Live Logs' : 'Audit Logs'}</h3>
@@ -6749,8 +7200,28 @@ router.on('/deploy-jobs/:id', async (params) => {
                         <div class="text-secondary small mb-1">Files changed</div>
                         <pre class="terminal-body" style="max-height: 200px;">${escapeHtml(diffInfo.files_changed || '')}</pre>
                     </div>
+                    ${diffInfo.diff_truncated ? `
+                    <div class="alert alert-warning py-2">
+                        Diff was truncated (${formatBytes(diffInfo.diff_size_bytes || 0)} total), only the first files are stored.
+                    </div>
+                    ` : ''}
                     <div>
                         <div class="text-secondary small mb-1">Diff</div>
+                        ${diffInfo.patch_omitted ? `
+                        <div class="text-secondary small mb-2">Diff is too large to show at once, open individual files.</div>
+                        <div class="list-group" id="deploy-diff-files">
+                            ${(diffInfo.files || []).map((file, index) => `
+                            <div class="list-group-item">
+                                <a href="#" class="deploy-diff-file" data-path="${escapeHtml(file.path)}" data-index="${index}">
+                                    <code>${escapeHtml(file.path)}</code>
+                                </a>
+                                <span class="text-success small ms-2">+${file.additions}</span>
+                                <span class="text-danger small ms-1">-${file.deletions}</span>
+                                <div class="deploy-diff-file-content mt-2" id="deploy-diff-file-${index}"></div>
+                            </div>
+                            `).join('')}
+                        </div>
+                        ` : ''}
                         <div id="deploy-diff-content"></div>
                     </div>
                 </div>
@@ -6849,19 +7320,44 @@ router.on('/deploy-jobs/:id', async (params) => {
             });
         }
 
-        if (diffInfo && diffInfo.diff_patch) {
-            const diffEl = document.getElementById('deploy-diff-content');
+        const renderDiff = (diffEl, patch) => {
             if (diffEl && window.Diff2Html) {
-                diffEl.innerHTML = window.Diff2Html.html(diffInfo.diff_patch, {
+                diffEl.innerHTML = window.Diff2Html.html(patch, {
                     drawFileList: false,
                     matching: 'lines',
                     outputFormat: 'line-by-line',
                     colorScheme: 'auto',
                 });
             } else if (diffEl) {
-                diffEl.innerHTML = `<pre class="terminal-body" style="max-height: 320px; white-space: pre;">${escapeHtml(diffInfo.diff_patch || '')}</pre>`;
+                diffEl.innerHTML = `<pre class="terminal-body" style="max-height: 320px; white-space: pre;">${escapeHtml(patch || '')}</pre>`;
             }
+        };
+
+        if (diffInfo && diffInfo.diff_patch) {
+            renderDiff(document.getElementById('deploy-diff-content'), diffInfo.diff_patch);
         }
+
+        // Velký diff - jednotlivé soubory se načítají až po kliknutí
+        document.querySelectorAll('.deploy-diff-file').forEach((link) => {
+            link.addEventListener('click', async (event) => {
+                event.preventDefault();
+                const target = document.getElementById(`deploy-diff-file-${link.dataset.index}`);
+                if (!target) return;
+                if (target.dataset.loaded) {
+                    target.innerHTML = '';
+                    delete target.dataset.loaded;
+                    return;
+                }
+                target.innerHTML = '<div class="text-secondary small">Loading...</div>';
+                try {
+                    const patch = await api.getDeployJobDiffFile(params.id, link.dataset.path);
+                    renderDiff(target, patch);
+                    target.dataset.loaded = '1';
+                } catch (error) {
+                    target.innerHTML = `<div class="text-danger small">Failed to load file diff: ${escapeHtml(error.message)}</div>`;
+                }
+            });
+        });
     } catch (error) {
         content.innerHTML = `
             <div class="alert alert-danger">
@@ -7559,6 +8055,14 @@ router.on('/bundles/:id/versions/:version/copy', async (params) => {
                         <small class="form-hint">Required for environment-specific registry paths.</small>
                     </div>
 
+                    <div class="mb-3">
+                        <label class="form-check">
+                            <input class="form-check-input" type="checkbox" id="copy-overwrite">
+                            <span class="form-check-label">Overwrite existing target tag</span>
+                        </label>
+                        <small class="form-hint">Without this, the job is rejected when the tag is already used by another copy job or points to a different digest.</small>
+                    </div>
+
                     <div class="list-group mb-3" id="copy-preview-list">
                         <div class="list-group-item">
                             <strong>Images to copy:</strong>
@@ -7755,6 +8259,7 @@ router.on('/bundles/:id/versions/:version/copy', async (params) => {
                     envId,
                     null,
                     null,
+                    document.getElementById('copy-overwrite')?.checked || false,
                 );
                 getApp().showSuccess('Copy job created. Click Start to run.');
                 router.navigate(`/copy-jobs/${response.job_id}`);
@@ -7880,6 +8385,17 @@ router.on('/copy-jobs/:jobId', async (params) => {
             }
         };
 
+        const formatEta = (status) => {
+            if (status.eta_seconds === null || status.eta_seconds === undefined) return '';
+            const percent = status.percent_complete !== null && status.percent_complete !== undefined
+                ? ` (${Math.round(status.percent_complete)}%)`
+                : '';
+            const at = status.estimated_completion_at
+                ? `, ~${new Date(status.estimated_completion_at).toLocaleTimeString('cs-CZ')}`
+                : '';
+            return ` • ETA: ${formatDurationHuman(status.eta_seconds)}${percent}${at}`;
+        };
+
         const updateDurationUi = (status = lastRenderedStatus) => {
             const el = document.getElementById('copy-job-duration');
             if (!el || !status) return;
@@ -7887,7 +8403,7 @@ router.on('/copy-jobs/:jobId', async (params) => {
                 ? 'Duration'
                 : 'Elapsed';
             const seconds = Number(status.duration_seconds || 0);
-            el.textContent = `${label}: ${formatDurationHuman(seconds)}`;
+            el.textContent = `${label}: ${formatDurationHuman(seconds)}${formatEta(status)}`;
         };
 
         const updateFollowLogsUi = () => {
@@ -8042,6 +8558,10 @@ router.on('/copy-jobs/:jobId', async (params) => {
             const isComplete = status.status === 'success' || status.status === 'failed' || status.status === 'cancelled';
             const failedImages = images.filter(img => img.copy_status === 'failed');
             const skippedImages = images.filter(img => img.copy_status === 'success' && img.bytes_copied === 0);
+            const reuseImages = images.filter(img => img.copy_status === 'success' && img.bytes_reused != null);
+            const estimatedTransferBytes = reuseImages.reduce((sum, img) => sum + Number(img.estimated_transfer_bytes || 0), 0);
+            const reusedBytes = reuseImages.reduce((sum, img) => sum + Number(img.bytes_reused || 0), 0);
+            const mountedBlobs = reuseImages.reduce((sum, img) => sum + Number(img.blobs_mounted || 0), 0);
             const autoRelease = releaseList.find(r => r.copy_job_id === status.job_id && r.is_auto);
 
             content.innerHTML = `
@@ -8135,6 +8655,11 @@ router.on('/copy-jobs/:jobId', async (params) => {
                                     Skipped: ${skippedImages.length}
                                 </div>
                             ` : ''}
+                            ${reuseImages.length > 0 ? `
+                                <div class="text-secondary small mt-1" title="Estimated from layer sizes: layers already present in the target registry are not transferred again">
+                                    Estimated transfer: ${formatBytes(estimatedTransferBytes)} &middot; reused: ${formatBytes(reusedBytes)}${mountedBlobs > 0 ? ` (${mountedBlobs} layers mounted)` : ''}
+                                </div>
+                            ` : ''}
                         </div>
 
                         <div id="copy-job-current-transfer">
@@ -8170,7 +8695,7 @@ router.on('/copy-jobs/:jobId', async (params) => {
                         <div class="text-secondary small mb-3">
                             Auto-release: ${autoRelease ? `created (<a href="#/releases/${autoRelease.id}">${autoRelease.release_id}</a>)` : 'not created'}
                             ${status.validate_only ? ' • Validate-only run (no copy)' : ''}
-                            <span class="ms-2" id="copy-job-duration">${status.status === 'success' || status.status === 'failed' || status.status === 'cancelled' ? 'Duration' : 'Elapsed'}: ${formatDurationHuman(status.duration_seconds || 0)}</span>
+                            <span class="ms-2" id="copy-job-duration">${status.status === 'success' || status.status === 'failed' || status.status === 'cancelled' ? 'Duration' : 'Elapsed'}: ${formatDurationHuman(status.duration_seconds || 0)}${formatEta(status)}</span>
                         </div>
 
                         ${isComplete ? `
@@ -8408,6 +8933,9 @@ router.on('/copy-jobs/:jobId', async (params) => {
             lastRenderedStatus = {
                 ...lastRenderedStatus,
                 duration_seconds: Number(lastRenderedStatus.duration_seconds || 0) + 1,
+                eta_seconds: lastRenderedStatus.eta_seconds > 0 && lastRenderedStatus.status !== 'pending'
+                    ? lastRenderedStatus.eta_seconds - 1
+                    : lastRenderedStatus.eta_seconds,
             };
             updateDurationUi(lastRenderedStatus);
         }, 1000);
@@ -8550,13 +9078,13 @@ router.on('/copy-jobs/:jobId', async (params) => {
 });
 
 // Copy Jobs List
-router.on('/copy-jobs', async () => {
+router.on('/copy-jobs', async (params, query = {}) => {
     const content = document.getElementById('app-content');
     content.innerHTML = '<div class="text-center py-5"><div class="spinner-border"></div></div>';
 
     try {
         const [jobs, registries, bundles, tenants] = await Promise.all([
-            api.getCopyJobs(),
+            api.getCopyJobs(query.fresh === '1'),
             api.getRegistries(),
             api.getBundles(),
             api.getTenants(),
@@ -8592,6 +9120,7 @@ router.on('/copy-jobs', async () => {
                 <div class="card-header">
                     <h3 class="card-title">Copy Jobs</h3>
                     <div class="card-actions">
+                        ${renderDataFreshness(jobs, '/copy-jobs')}
                         ${tab === 'copy' ? `
                             <button class="btn btn-outline-secondary" id="copy-jobs-compare" ${selectedJobs.size === 2 ? '' : 'disabled'}>
                                 <i class="ti ti-arrows-diff"></i>
@@ -9039,13 +9568,13 @@ router.on('/copy-jobs', async () => {
 });
 
 // Manifest Builds List
-router.on('/deployments', async () => {
+router.on('/deployments', async (params, query = {}) => {
     const content = document.getElementById('app-content');
     content.innerHTML = '<div class="text-center py-5"><div class="spinner-border"></div></div>';
 
     try {
         const [deployments, tenants, bundles] = await Promise.all([
-            api.getDeployments(),
+            api.getDeployments(query.fresh === '1'),
             api.getTenants(),
             api.getBundles(),
         ]);
@@ -9078,6 +9607,9 @@ router.on('/deployments', async () => {
                 <div class="card">
                     <div class="card-header">
                         <h3 class="card-title">Manifest Builds</h3>
+                        <div class="card-actions">
+                            ${renderDataFreshness(deployments, '/deployments')}
+                        </div>
                     </div>
                     <div class="card-body border-bottom py-3">
                         <div class="row g-2">
@@ -9338,9 +9870,10 @@ async function runAutoDeployFromCopyJob(copyJobId, tenantId, targetTag) {
         return;
     }
 
-    const eligible = environments.filter(env => env.allow_auto_release);
+    // Auto release vzniká v kanálu rc
+    const eligible = environments.filter(env => env.allow_auto_release && (env.release_channels || []).includes('rc'));
     if (eligible.length === 0) {
-        getApp().showError('No environments allow auto release');
+        getApp().showError('No environments allow auto release of rc releases');
         return;
     }
 
@@ -9593,6 +10126,132 @@ async function runSelectiveCopyFromJob(copyJobId, bundle) {
     });
 }
 
+async function runPatchReleaseFromJob(releaseJobId, baseRelease, sourceJobs) {
+    let images = [];
+
+    try {
+        images = await api.getCopyJobImages(releaseJobId);
+    } catch (error) {
+        getApp().showError(`Failed to load copy job images: ${error.message}`);
+        return;
+    }
+
+    if (!images.length) {
+        getApp().showError('No images available for patch release');
+        return;
+    }
+    if (!sourceJobs.length) {
+        getApp().showError('No successful copy job to take patched images from');
+        return;
+    }
+
+    const dialogHtml = `
+        <div class="modal modal-blur fade show" style="display: block;" id="patch-release-modal">
+            <div class="modal-dialog modal-lg modal-dialog-centered" role="document">
+                <div class="modal-content">
+                    <div class="modal-body">
+                        <div class="modal-title">Patch Release ${baseRelease?.release_id || ''}</div>
+                        <div class="text-secondary small mt-1">
+                            Selected images will be copied from the source copy job. Unselected images are retagged from the base release.
+                        </div>
+                        <div class="row mt-3">
+                            <div class="col-md-6">
+                                <label class="form-label required">New Release ID</label>
+                                <input type="text" class="form-control" id="patch-release-id" placeholder="${baseRelease?.release_id || ''}-hotfix1">
+                            </div>
+                            <div class="col-md-6">
+                                <label class="form-label required">Source Copy Job</label>
+                                <select class="form-select" id="patch-release-source">
+                                    ${sourceJobs.map(job => `
+                                        <option value="${job.job_id}">v${job.version} - ${job.target_tag}</option>
+                                    `).join('')}
+                                </select>
+                            </div>
+                        </div>
+                        <div class="mt-3">
+                            <label class="form-label">Notes</label>
+                            <input type="text" class="form-control" id="patch-release-notes" placeholder="Patch of release ${baseRelease?.release_id || ''}">
+                        </div>
+                        <div class="table-responsive mt-3" style="max-height: 320px;">
+                            <table class="table table-vcenter card-table">
+                                <thead>
+                                    <tr>
+                                        <th class="w-1"></th>
+                                        <th>Image</th>
+                                        <th>Current Tag</th>
+                                    </tr>
+                                </thead>
+                                <tbody>
+                                    ${images.map(img => `
+                                        <tr>
+                                            <td>
+                                                <input class="form-check-input patch-image-checkbox" type="checkbox" value="${img.id}">
+                                            </td>
+                                            <td><code class="small">${img.target_image}</code></td>
+                                            <td><span class="badge bg-azure-lt">${img.target_tag}</span></td>
+                                        </tr>
+                                    `).join('')}
+                                </tbody>
+                            </table>
+                        </div>
+                    </div>
+                    <div class="modal-footer">
+                        <button type="button" class="btn btn-link link-secondary" id="patch-release-cancel">
+                            Cancel
+                        </button>
+                        <button type="button" class="btn btn-primary" id="patch-release-confirm">
+                            Create Patch Release
+                        </button>
+                    </div>
+                </div>
+            </div>
+        </div>
+        <div class="modal-backdrop fade show"></div>
+    `;
+
+    document.body.insertAdjacentHTML('beforeend', dialogHtml);
+
+    const modal = document.getElementById('patch-release-modal');
+    const backdrop = document.querySelector('.modal-backdrop');
+    const checkboxes = Array.from(document.querySelectorAll('.patch-image-checkbox'));
+
+    const cleanup = () => {
+        modal.remove();
+        backdrop.remove();
+    };
+
+    document.getElementById('patch-release-cancel').addEventListener('click', cleanup);
+
+    document.getElementById('patch-release-confirm').addEventListener('click', async () => {
+        const selectedIds = checkboxes.filter(cb => cb.checked).map(cb => cb.value);
+        const releaseId = document.getElementById('patch-release-id').value.trim();
+        if (!releaseId) {
+            getApp().showError('Release ID is required');
+            return;
+        }
+        if (selectedIds.length === 0) {
+            getApp().showError('Select at least one image');
+            return;
+        }
+        const payload = {
+            base_copy_job_id: releaseJobId,
+            selected_image_ids: selectedIds,
+            release_id: releaseId,
+            source_copy_job_id: document.getElementById('patch-release-source').value,
+            notes: document.getElementById('patch-release-notes').value.trim() || null,
+        };
+
+        cleanup();
+        try {
+            const response = await api.startSelectiveCopyJob(payload);
+            getApp().showSuccess('Patch release copy job created');
+            router.navigate(`/copy-jobs/${response.job_id}`);
+        } catch (error) {
+            getApp().showError(error.message);
+        }
+    });
+}
+
 async function runDeployFromRelease(release, environments) {
     const eligible = environments || [];
     if (eligible.length === 0) {
@@ -9625,6 +10284,11 @@ async function runDeployFromRelease(release, environments) {
                                 Default: use exact image URLs stored in the Image Release manifest.
                             </div>
                         </div>
+                        <div class="mt-3 d-none" id="release-deploy-env-overrides-wrap">
+                            <label class="form-label">Env Overrides</label>
+                            <textarea class="form-control font-monospace" rows="3" id="release-deploy-env-overrides" placeholder="FEATURE_X=on"></textarea>
+                            <div class="form-hint" id="release-deploy-env-overrides-hint"></div>
+                        </div>
                         <div class="form-check mt-3">
                             <input class="form-check-input" type="checkbox" id="release-deploy-dry-run" checked>
                             <label class="form-check-label" for="release-deploy-dry-run">
@@ -9634,6 +10298,15 @@ async function runDeployFromRelease(release, environments) {
                                 Dry run disabled: changes will be committed and pushed to git.
                             </div>
                         </div>
+                        <div class="form-check mt-2">
+                            <input class="form-check-input" type="checkbox" id="release-deploy-validate-only">
+                            <label class="form-check-label" for="release-deploy-validate-only">
+                                Validate only (render + checks + report, never writes to git)
+                            </label>
+                            <div class="form-hint d-none" id="release-deploy-validate-only-hint">
+                                Required by this environment.
+                            </div>
+                        </div>
                     </div>
                     <div class="modal-footer">
                         <button type="button" class="btn btn-link link-secondary" id="release-deploy-cancel">
@@ -9662,12 +10335,35 @@ async function runDeployFromRelease(release, environments) {
     const dryRunWarning = document.getElementById('release-deploy-warning');
     const imageUrlModeSelect = document.getElementById('release-deploy-image-url-mode');
     const imageUrlModeHint = document.getElementById('release-deploy-image-url-mode-hint');
+    const validateOnlyCheckbox = document.getElementById('release-deploy-validate-only');
+    const validateOnlyHint = document.getElementById('release-deploy-validate-only-hint');
+    const envOverridesWrap = document.getElementById('release-deploy-env-overrides-wrap');
+    const envOverridesInput = document.getElementById('release-deploy-env-overrides');
+    const envOverridesHint = document.getElementById('release-deploy-env-overrides-hint');
 
     const cleanup = () => {
         modal.remove();
         backdrop.remove();
     };
 
+    const updateEnvOverrides = () => {
+        const target = eligible.find(t => t.id === select.value);
+        const allowlist = target?.job_env_override_allowlist || [];
+        envOverridesWrap.classList.toggle('d-none', allowlist.length === 0);
+        envOverridesHint.innerHTML = `One <code>KEY=VALUE</code> per line. Allowed: ${allowlist.map(item => `<code>${escapeHtml(item)}</code>`).join(', ')}`;
+    };
+
+    const updateValidateOnly = () => {
+        const target = eligible.find(t => t.id === select.value);
+        const required = Boolean(target?.validate_only_required);
+        if (required) {
+            validateOnlyCheckbox.checked = true;
+        }
+        validateOnlyCheckbox.disabled = required;
+        validateOnlyHint.classList.toggle('d-none', !required);
+        validateOnlyCheckbox.dispatchEvent(new Event('change'));
+    };
+
     const updateTitle = (target) => {
         if (!target) {
             titleEl.textContent = 'Environment';
@@ -9707,23 +10403,28 @@ async function runDeployFromRelease(release, environments) {
             updateTitle(match);
         }
     }
+    updateEnvOverrides();
 
     select.addEventListener('change', () => {
         const target = eligible.find(t => t.id === select.value);
         confirmBtn.disabled = !target;
         updateTitle(target);
         updateImageUrlModeHint();
+        updateEnvOverrides();
+        updateValidateOnly();
     });
     imageUrlModeSelect.addEventListener('change', updateImageUrlModeHint);
     updateImageUrlModeHint();
 
     if (dryRunCheckbox && dryRunWarning) {
         const syncWarning = () => {
-            dryRunWarning.classList.toggle('d-none', dryRunCheckbox.checked);
+            dryRunWarning.classList.toggle('d-none', dryRunCheckbox.checked || validateOnlyCheckbox.checked);
         };
         dryRunCheckbox.addEventListener('change', syncWarning);
+        validateOnlyCheckbox.addEventListener('change', syncWarning);
         syncWarning();
     }
+    updateValidateOnly();
 
     cancelBtn.addEventListener('click', () => {
         cleanup();
@@ -9732,8 +10433,16 @@ async function runDeployFromRelease(release, environments) {
     confirmBtn.addEventListener('click', async () => {
         const targetEnvId = select.value;
         const dryRun = dryRunCheckbox?.checked ?? true;
+        const validateOnly = validateOnlyCheckbox.checked;
         const releaseImageUrlMode = imageUrlModeSelect?.value || 'manifest_urls';
         if (!targetEnvId) return;
+        let envOverrides;
+        try {
+            envOverrides = envOverridesWrap.classList.contains('d-none') ? {} : parseEnvOverrides(envOverridesInput.value);
+        } catch (error) {
+            getApp().showError(error.message);
+            return;
+        }
         cleanup();
         try {
             const response = await api.createDeployJob({
@@ -9741,6 +10450,8 @@ async function runDeployFromRelease(release, environments) {
                 environment_id: targetEnvId,
                 dry_run: dryRun,
                 release_image_url_mode: releaseImageUrlMode,
+                ...(validateOnly ? { validate_only: true } : {}),
+                ...(Object.keys(envOverrides).length ? { env_overrides: envOverrides } : {}),
             });
             getApp().showSuccess('Build job created');
             router.navigate(`/deploy-jobs/${response.job_id}`);
@@ -9864,6 +10575,7 @@ async function copyMappingsToClipboard(mappings) {
         m.target_image || '',
         m.app_name || '',
         m.container_name || '',
+        m.owner || '',
     ].join(';'));
     const payload = rows.join('\n');
     try {
@@ -9909,6 +10621,14 @@ function applyReplaceRules(value, rules) {
     return result;
 }
 
+function renderEnvLayer(item) {
+    const colors = { target: 'bg-secondary-lt text-secondary-fg', environment: 'bg-azure-lt text-azure-fg', job: 'bg-orange-lt text-orange-fg' };
+    const overrides = (item.overrides || []).length
+        ? `<span class="text-secondary small ms-1">overrides ${item.overrides.map(escapeHtml).join(', ')}</span>`
+        : '';
+    return `<span class="badge ${colors[item.layer] || ''}">${escapeHtml(item.layer)}</span>${overrides}`;
+}
+
 function formatTargetWithEnv(name, envName) {
     if (!envName) return name || '';
     const suffix = `(${envName})`;
@@ -9938,6 +10658,7 @@ function parseMappingCsv(input, rules = []) {
         const targetImage = applyReplaceRules(targetRaw, rules);
         let appName = parts[3] || '';
         const containerName = parts[4] || '';
+        const owner = parts[5] || '';
         if (!appName && targetImage) {
             const segs = targetImage.split('/');
             appName = segs[segs.length - 1] || '';
@@ -9950,6 +10671,7 @@ function parseMappingCsv(input, rules = []) {
             target_image: targetImage,
             app_name: appName,
             container_name: containerName,
+            owner,
             valid,
         });
     }
@@ -9972,7 +10694,7 @@ async function showMappingImportModal({ onApply }) {
                         <div class="mb-3">
                             <label class="form-label">Paste CSV (semicolon-separated)</label>
                             <textarea class="form-control" id="import-mappings-input" rows="6"
-                                placeholder="source_image;source_tag;target_image;app_name;container_name"></textarea>
+                                placeholder="source_image;source_tag;target_image;app_name;container_name;owner"></textarea>
                             <div class="form-hint">Empty source_tag defaults to <code>latest</code>.</div>
                         </div>
                         <div class="mb-3">
@@ -9995,11 +10717,12 @@ async function showMappingImportModal({ onApply }) {
                                         <th>Target</th>
                                         <th>App</th>
                                         <th>Container</th>
+                                        <th>Owner</th>
                                     </tr>
                                 </thead>
                                 <tbody id="import-preview-body">
                                     <tr>
-                                        <td colspan="5" class="text-center text-secondary">No data yet</td>
+                                        <td colspan="6" class="text-center text-secondary">No data yet</td>
                                     </tr>
                                 </tbody>
                             </table>
@@ -10084,7 +10807,7 @@ async function showMappingImportModal({ onApply }) {
         applyBtn.disabled = valid.length === 0;
 
         if (rows.length === 0) {
-            previewBody.innerHTML = '<tr><td colspan="5" class="text-center text-secondary">No data yet</td></tr>';
+            previewBody.innerHTML = '<tr><td colspan="6" class="text-center text-secondary">No data yet</td></tr>';
             return;
         }
 
@@ -10095,6 +10818,7 @@ async function showMappingImportModal({ onApply }) {
                 <td><code class="small">${row.target_image || '-'}</code></td>
                 <td>${row.app_name || '-'}</td>
                 <td>${row.container_name || '-'}</td>
+                <td>${row.owner ? escapeHtml(row.owner) : '-'}</td>
             </tr>
         `).join('');
     };
@@ -10120,6 +10844,7 @@ async function showMappingImportModal({ onApply }) {
             target_image: row.target_image,
             app_name: row.app_name,
             container_name: row.container_name,
+            owner: row.owner,
         })));
         cleanup();
     });
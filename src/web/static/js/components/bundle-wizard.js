@@ -236,12 +236,19 @@ class BundleWizard {
                                            placeholder="app name">
                                     <small class="form-hint">Kubernetes app name (used for release manifest)</small>
                                 </div>
-                                <div class="col-md-5">
+                                <div class="col-md-3">
                                     <label class="form-label">Container Name</label>
                                     <input type="text" class="form-control form-control-sm mapping-container-name"
                                            value="${mapping.container_name || ''}"
                                            placeholder="container name (optional)">
                                 </div>
+                                <div class="col-md-3">
+                                    <label class="form-label">Owner</label>
+                                    <input type="text" class="form-control form-control-sm mapping-owner"
+                                           value="${mapping.owner || ''}"
+                                           placeholder="user or team (optional)">
+                                    <small class="form-hint">Notified when this app's images fail</small>
+                                </div>
                             </div>
                         </div>
                     </div>
@@ -267,7 +274,7 @@ class BundleWizard {
                 </button>
             </div>
             <div class="text-secondary small mt-2">
-                Import format: <code>source_image;source_tag;target_image;app_name;container_name</code>
+                Import format: <code>source_image;source_tag;target_image;app_name;container_name;owner</code>
             </div>
 
             ${this.data.imageMappings.length === 0 ? `
@@ -351,6 +358,7 @@ class BundleWizard {
                                 <th>Target Image</th>
                                 <th>App</th>
                                 <th>Container</th>
+                                <th>Owner</th>
                             </tr>
                         </thead>
                         <tbody>
@@ -362,6 +370,7 @@ class BundleWizard {
                                     <td><code>${mapping.target_image}</code></td>
                                     <td>${mapping.app_name || '-'}</td>
                                     <td>${mapping.container_name || '-'}</td>
+                                    <td>${mapping.owner || '-'}</td>
                                 </tr>
                             `).join('')}
                         </tbody>
@@ -407,6 +416,7 @@ class BundleWizard {
             const targetImage = card.querySelector('.mapping-target-image')?.value || '';
             let appName = card.querySelector('.mapping-app-name')?.value || '';
             const containerName = card.querySelector('.mapping-container-name')?.value || '';
+            const owner = card.querySelector('.mapping-owner')?.value.trim() || '';
             if (!appName && targetImage) {
                 const parts = targetImage.split('/');
                 appName = parts[parts.length - 1] || '';
@@ -417,7 +427,8 @@ class BundleWizard {
                 source_tag: sourceTag,
                 target_image: targetImage,
                 app_name: appName,
-                container_name: containerName
+                container_name: containerName,
+                owner
             });
         });
 
@@ -490,7 +501,8 @@ class BundleWizard {
             source_tag: '',
             target_image: '',
             app_name: '',
-            container_name: ''
+            container_name: '',
+            owner: ''
         });
     }
 
@@ -0,0 +1,119 @@
+use axum::Json;
+use std::time::Duration;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::api::{copy, deploy};
+use crate::services::events::{COPY_JOB_FINISHED, DEPLOY_JOB_FINISHED};
+use crate::services::job_logs::JobKind;
+use crate::services::job_queue;
+use crate::services::lifecycle_notifications;
+
+/// Nastavení worker procesu (`--role worker`)
+#[derive(Debug, Clone)]
+pub struct WorkerConfig {
+    pub worker_id: String,
+    pub max_copy_jobs: usize,
+    pub max_deploy_jobs: usize,
+    pub poll_interval: Duration,
+    /// Lease převzatých jobů; obnovuje se každým tickem
+    pub lease_seconds: u64,
+}
+
+/// Smyčka workeru - přebírá joby z DB fronty a spouští je v tomto procesu
+pub async fn run(copy_state: copy::CopyApiState, deploy_state: deploy::DeployApiState, config: WorkerConfig) {
+    info!(
+        worker_id = %config.worker_id,
+        max_copy_jobs = config.max_copy_jobs,
+        max_deploy_jobs = config.max_deploy_jobs,
+        "Job worker started"
+    );
+    // Joby předchozího procesu se stejným WORKER_ID už neběží - nečekáme na propadnutí leasu
+    if let Err(e) = recover_stale_jobs(&copy_state, &deploy_state, &config, Some(&config.worker_id)).await {
+        warn!(worker_id = %config.worker_id, error = %e, "Failed to recover jobs of the previous worker process");
+    }
+    loop {
+        if let Err(e) = tick(&copy_state, &deploy_state, &config).await {
+            warn!(worker_id = %config.worker_id, error = %e, "Job worker iteration failed");
+        }
+        tokio::time::sleep(config.poll_interval).await;
+    }
+}
+
+async fn tick(
+    copy_state: &copy::CopyApiState,
+    deploy_state: &deploy::DeployApiState,
+    config: &WorkerConfig,
+) -> Result<(), sqlx::Error> {
+    let pool = &copy_state.pool;
+
+    // Cancel přes API mění jen DB; běžícím jobům tohoto procesu nastavíme cancel flag
+    let running: Vec<Uuid> = copy_state.job_logs.read().await.keys().copied().collect();
+    if !running.is_empty() {
+        let cancelled = job_queue::cancelled_copy_jobs(pool, &running).await?;
+        if !cancelled.is_empty() {
+            copy_state.cancel_flags.write().await.extend(cancelled);
+        }
+    }
+
+    let running_deploys: Vec<Uuid> = deploy_state.job_logs.read().await.keys().copied().collect();
+    job_queue::heartbeat(pool, JobKind::Copy, &config.worker_id, &running).await?;
+    job_queue::heartbeat(pool, JobKind::Deploy, &config.worker_id, &running_deploys).await?;
+    recover_stale_jobs(copy_state, deploy_state, config, None).await?;
+
+    let mut active = job_queue::active_count(pool, JobKind::Copy, &config.worker_id, config.lease_seconds).await?;
+    while (active as usize) < config.max_copy_jobs {
+        let Some(job_id) = job_queue::claim_next(pool, JobKind::Copy, &config.worker_id).await? else {
+            break;
+        };
+        active += 1;
+        info!(%job_id, "Claimed copy job");
+        if let Err((_, Json(e))) = copy::launch_copy_job(copy_state.clone(), job_id).await {
+            warn!(%job_id, error = %e.error, "Copy job failed to start");
+            job_queue::fail_unstarted(pool, JobKind::Copy, job_id, &e.error).await?;
+            lifecycle_notifications::copy_job(pool, &copy_state.notifier, &COPY_JOB_FINISHED, job_id).await;
+        }
+    }
+
+    let mut active = job_queue::active_count(pool, JobKind::Deploy, &config.worker_id, config.lease_seconds).await?;
+    while (active as usize) < config.max_deploy_jobs {
+        let Some(job_id) = job_queue::claim_next(pool, JobKind::Deploy, &config.worker_id).await? else {
+            break;
+        };
+        active += 1;
+        info!(%job_id, "Claimed deploy job");
+        if let Err((_, Json(e))) = deploy::launch_deploy_job(deploy_state.clone(), job_id).await {
+            warn!(%job_id, error = %e.error, "Deploy job failed to start");
+            job_queue::fail_unstarted(pool, JobKind::Deploy, job_id, &e.error).await?;
+            lifecycle_notifications::deploy_job(pool, &deploy_state.notifier, &DEPLOY_JOB_FINISHED, job_id).await;
+        }
+    }
+
+    Ok(())
+}
+
+/// Joby workerů s propadlým leasem: nespuštěné se vrátí do fronty, běžící skončí jako failed
+async fn recover_stale_jobs(
+    copy_state: &copy::CopyApiState,
+    deploy_state: &deploy::DeployApiState,
+    config: &WorkerConfig,
+    restarted_worker: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    let pool = &copy_state.pool;
+    for kind in [JobKind::Copy, JobKind::Deploy] {
+        for job_id in job_queue::requeue_stale(pool, kind, config.lease_seconds, restarted_worker).await? {
+            warn!(%job_id, ?kind, "Worker lease expired before the job started, returning it to the queue");
+        }
+        let interrupted =
+            job_queue::take_stale_running(pool, kind, config.lease_seconds, restarted_worker, &config.worker_id).await?;
+        for job_id in interrupted {
+            warn!(%job_id, ?kind, "Worker lease expired while the job was running, marking it failed");
+            let reason = "Interrupted (worker stopped while the job was running)";
+            match kind {
+                JobKind::Copy => copy::fail_interrupted_copy_job(copy_state, job_id, reason).await,
+                JobKind::Deploy => deploy::fail_interrupted_deploy_job(deploy_state, job_id, reason).await,
+            }
+        }
+    }
+    Ok(())
+}